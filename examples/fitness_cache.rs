@@ -0,0 +1,95 @@
+//! Time the same seeded evolution with and without the fitness cache of the distance matrix,
+//! once with a single matrix and once with several traffic scenarios, and print the cache
+//! statistics.
+//!
+//! Usage: `cargo run --release --example fitness_cache -- [n_nodes] [n_generations]`
+//!
+//! The defaults are 200 nodes and 200 generations. The stepwise evolution is single-threaded.
+use genetic_algorithm_tsp::objective::ScenarioAggregation;
+use genetic_algorithm_tsp::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::env;
+use std::time::Instant;
+
+/// Create a matrix of the euclidean distances between random points.
+///
+/// # Arguments
+///
+/// * `n_nodes` - How many points the matrix has.
+/// * `seed` - The seed of the points.
+///
+fn random_dist_mat(n_nodes: usize, seed: u64) -> DistanceMat {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let points = (0..n_nodes)
+        .map(|_| (rng.gen_range(0.0..100.0), rng.gen_range(0.0..100.0)))
+        .collect::<Vec<(f64, f64)>>();
+    DistanceMat::from_coordinates(&points)
+}
+
+/// Evolve a seeded population and print how long it took and how the cache was used.
+///
+/// # Arguments
+///
+/// * `name` - How the run is called in the output.
+/// * `distance_mat` - The distance matrix to evolve the routes on.
+/// * `config` - How the routes are evolved.
+///
+fn time_evolution(name: &str, distance_mat: &DistanceMat, config: EvolutionConfig) {
+    let initial_population = Routes::random_with_rng(
+        config.size_generation,
+        distance_mat.n_units(),
+        &mut StdRng::seed_from_u64(0),
+    );
+    let started = Instant::now();
+    let mut evolution = Evolution::new(distance_mat, config).start(initial_population, 0);
+    evolution.evolve(usize::MAX);
+    let elapsed = started.elapsed();
+    let best = evolution.population().get_n_fittest(1, distance_mat);
+    let best_cost = distance_mat.get_cost(&best[0].indexes);
+    match distance_mat.fitness_cache_stats() {
+        Some(stats) => println!(
+            "{:<28} {:>8.1?}  best cost {:>9.2}  hits {:>9}  misses {:>9}",
+            name, elapsed, best_cost, stats.hits, stats.misses
+        ),
+        None => println!(
+            "{:<28} {:>8.1?}  best cost {:>9.2}",
+            name, elapsed, best_cost
+        ),
+    }
+}
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    let parse = |index: usize, default: usize| {
+        args.get(index)
+            .map(|arg| arg.parse().expect("the arguments must be numbers"))
+            .unwrap_or(default)
+    };
+    let (n_nodes, n_generations) = (parse(0, 200), parse(1, 200));
+    let config = EvolutionConfig::new().with_n_generations(n_generations);
+    let scenarios = || {
+        (1..8)
+            .map(|seed| random_dist_mat(n_nodes, seed))
+            .collect::<Vec<DistanceMat>>()
+    };
+
+    time_evolution("one matrix", &random_dist_mat(n_nodes, 0), config);
+    time_evolution(
+        "one matrix, cached",
+        &random_dist_mat(n_nodes, 0).with_fitness_cache(10_000),
+        config,
+    );
+    time_evolution(
+        "8 scenarios",
+        &random_dist_mat(n_nodes, 0).with_scenarios(scenarios(), ScenarioAggregation::Average),
+        config,
+    );
+    time_evolution(
+        "8 scenarios, cached",
+        &random_dist_mat(n_nodes, 0)
+            .with_scenarios(scenarios(), ScenarioAggregation::Average)
+            .with_fitness_cache(10_000),
+        config,
+    );
+}
@@ -0,0 +1,125 @@
+//! Solve a traveling salesman problem given as a CSV of `name,lat,lon` rows and print the
+//! itinerary with the distance of every leg.
+//!
+//! Usage: `cargo run --example solve_csv -- [cities.csv] [itinerary.csv]`
+//!
+//! The input defaults to `tests/test-data/cities.csv`, the itinerary is printed to stdout if
+//! no output path is given.
+use genetic_algorithm_tsp::prelude::*;
+use std::env;
+use std::fmt::Write;
+use std::fs;
+
+/// The mean radius of the earth in kilometers.
+const EARTH_RADIUS: f64 = 6371.0;
+
+/// A named location on the earth.
+struct City {
+    name: String,
+    lat: f64,
+    lon: f64,
+}
+
+/// Read the cities from a CSV with the header `name,lat,lon`.
+///
+/// # Arguments
+///
+/// * `content` - The content of the CSV file.
+///
+fn parse_cities(content: &str) -> Result<Vec<City>, String> {
+    content
+        .lines()
+        .enumerate()
+        .skip(1)
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(line_number, line)| {
+            let fields = line.split(',').map(str::trim).collect::<Vec<&str>>();
+            if fields.len() != 3 {
+                return Err(format!(
+                    "line {}: expected `name,lat,lon`, got `{}`",
+                    line_number + 1,
+                    line
+                ));
+            }
+            let parse_degrees = |field: &str| {
+                field
+                    .parse::<f64>()
+                    .map_err(|error| format!("line {}: {}: `{}`", line_number + 1, error, field))
+            };
+            Ok(City {
+                name: fields[0].to_string(),
+                lat: parse_degrees(fields[1])?,
+                lon: parse_degrees(fields[2])?,
+            })
+        })
+        .collect()
+}
+
+/// The great-circle distance in kilometers between two cities (haversine formula).
+///
+/// # Arguments
+///
+/// * `from` - The city where the leg starts.
+/// * `to` - The city where the leg ends.
+///
+fn haversine(from: &City, to: &City) -> f64 {
+    let delta_lat = (to.lat - from.lat).to_radians();
+    let delta_lon = (to.lon - from.lon).to_radians();
+    let a = (delta_lat / 2.0).sin().powi(2)
+        + from.lat.to_radians().cos() * to.lat.to_radians().cos() * (delta_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS * a.sqrt().asin()
+}
+
+fn main() -> Result<(), String> {
+    let arguments = env::args().collect::<Vec<String>>();
+    let input_path = arguments
+        .get(1)
+        .map(String::as_str)
+        .unwrap_or("tests/test-data/cities.csv");
+    let cities = parse_cities(
+        &fs::read_to_string(input_path)
+            .map_err(|error| format!("could not read {}: {}", input_path, error))?,
+    )?;
+    if cities.len() < 2 {
+        return Err(format!("{} needs at least two cities", input_path));
+    }
+
+    let distance_mat = DistanceMat::new(
+        cities
+            .iter()
+            .map(|from| cities.iter().map(|to| haversine(from, to)).collect())
+            .collect(),
+    );
+    let population = evolve_population(Routes::random(30, cities.len()), 500, 30, &distance_mat, 0);
+    let best_route = &population.get_n_fittest(1, &distance_mat)[0];
+
+    // Write the itinerary including the leg back to the start.
+    let mut itinerary = String::from("leg,from,to,distance_km\n");
+    let mut total_distance = 0.0;
+    let closed_route = best_route
+        .indexes
+        .iter()
+        .chain(best_route.indexes.first())
+        .collect::<Vec<&usize>>();
+    for (leg, stops) in closed_route.windows(2).enumerate() {
+        let (from, to) = (&cities[*stops[0]], &cities[*stops[1]]);
+        let distance = haversine(from, to);
+        total_distance += distance;
+        writeln!(
+            itinerary,
+            "{},{},{},{:.1}",
+            leg + 1,
+            from.name,
+            to.name,
+            distance
+        )
+        .expect("Writing to a string cannot fail.");
+    }
+    match arguments.get(2) {
+        Some(output_path) => fs::write(output_path, itinerary)
+            .map_err(|error| format!("could not write {}: {}", output_path, error))?,
+        None => print!("{}", itinerary),
+    }
+    eprintln!("Total distance: {:.1} km", total_distance);
+    Ok(())
+}
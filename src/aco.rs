@@ -0,0 +1,574 @@
+#[cfg(not(any(feature = "std", test)))]
+use crate::compat::Float;
+use crate::distance_mat::DistanceMat;
+use crate::route::Route;
+use crate::routes::{nearest_neighbor_tour, Routes};
+use crate::utils::get_random_elem_from_range_with_rng;
+use alloc::vec::Vec;
+use rand::Rng;
+
+/// How the ants of `AntColony` lay pheromone on the legs of their tours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PheromoneUpdate {
+    /// Ant System: every ant of an iteration lays pheromone in inverse proportion to the cost
+    /// of its tour.
+    AntSystem,
+    /// MAX-MIN Ant System: only the best ant of an iteration lays pheromone, and the pheromone
+    /// of every leg is kept between a lower and an upper bound, so that the colony neither
+    /// stops exploring nor forgets any leg completely.
+    #[default]
+    MaxMin,
+}
+
+/// Ant colony optimization over a `DistanceMat`, a pheromone-based alternative to the genetic
+/// algorithm: in every iteration each ant builds a tour node by node, choosing the next node
+/// with a probability proportional to `pheromone^alpha * (1 / distance)^beta`. Afterwards the
+/// pheromone evaporates and the ants reinforce the legs of good tours, so that later ants
+/// follow them. The tours are ordinary `Route`s, evaluated with `DistanceMat::get_cost`, and
+/// can seed a population of the genetic algorithm with `Routes::ant_colony`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AntColony {
+    /// How many iterations the colony runs.
+    pub n_iterations: usize,
+    /// How many ants build a tour in every iteration. If it is `None`, there is one ant per
+    /// node.
+    pub n_ants: Option<usize>,
+    /// How much the ants follow the pheromone.
+    pub alpha: f64,
+    /// How much the ants prefer close nodes.
+    pub beta: f64,
+    /// Which fraction of the pheromone evaporates after every iteration, between 0 and 1.
+    pub evaporation: f64,
+    /// Which ants lay pheromone.
+    pub update: PheromoneUpdate,
+}
+impl AntColony {
+    /// Create a MAX-MIN Ant System with one ant per node, `alpha = 1`, `beta = 2` and an
+    /// evaporation of 0.1.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_iterations` - How many iterations the colony runs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::aco::{AntColony, PheromoneUpdate};
+    ///
+    /// let colony = AntColony::new(100);
+    /// assert_eq!(colony.update, PheromoneUpdate::MaxMin);
+    /// ```
+    pub fn new(n_iterations: usize) -> Self {
+        AntColony {
+            n_iterations,
+            n_ants: None,
+            alpha: 1.0,
+            beta: 2.0,
+            evaporation: 0.1,
+            update: PheromoneUpdate::MaxMin,
+        }
+    }
+    /// Set how many ants build a tour in every iteration.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_ants` - The number of ants, at least one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::aco::AntColony;
+    ///
+    /// let colony = AntColony::new(100).with_n_ants(20);
+    /// assert_eq!(colony.n_ants, Some(20));
+    /// ```
+    pub fn with_n_ants(mut self, n_ants: usize) -> Self {
+        self.n_ants = Some(n_ants.max(1));
+        self
+    }
+    /// Set how much the ants follow the pheromone and how much they prefer close nodes.
+    ///
+    /// # Arguments
+    ///
+    /// * `alpha` - The exponent of the pheromone.
+    /// * `beta` - The exponent of the inverse distance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::aco::AntColony;
+    ///
+    /// let colony = AntColony::new(100).with_weights(1.0, 5.0);
+    /// assert_eq!(colony.beta, 5.0);
+    /// ```
+    pub fn with_weights(mut self, alpha: f64, beta: f64) -> Self {
+        self.alpha = alpha;
+        self.beta = beta;
+        self
+    }
+    /// Set which fraction of the pheromone evaporates after every iteration.
+    ///
+    /// # Arguments
+    ///
+    /// * `evaporation` - The evaporation rate, between 0 and 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::aco::AntColony;
+    ///
+    /// let colony = AntColony::new(100).with_evaporation(0.02);
+    /// assert_eq!(colony.evaporation, 0.02);
+    /// ```
+    pub fn with_evaporation(mut self, evaporation: f64) -> Self {
+        self.evaporation = evaporation;
+        self
+    }
+    /// Set which ants lay pheromone.
+    ///
+    /// # Arguments
+    ///
+    /// * `update` - The pheromone update of the colony.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::aco::{AntColony, PheromoneUpdate};
+    ///
+    /// let colony = AntColony::new(100).with_update(PheromoneUpdate::AntSystem);
+    /// ```
+    pub fn with_update(mut self, update: PheromoneUpdate) -> Self {
+        self.update = update;
+        self
+    }
+    /// Run the colony and return the best tour any ant has built. An empty distance matrix
+    /// gives an empty route with cost 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the tours are built on.
+    /// * `rng` - The random number generator the ants draw their tours from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::aco::AntColony;
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use rand::SeedableRng;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 9.0, 1.0],
+    ///     vec![1.0, 0.0, 1.0, 9.0],
+    ///     vec![9.0, 1.0, 0.0, 1.0],
+    ///     vec![1.0, 9.0, 1.0, 0.0],
+    /// ]);
+    /// let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+    /// let result = AntColony::new(20).solve(&distance_matrix, &mut rng);
+    /// assert_eq!(result.cost, 4.0);
+    /// ```
+    pub fn solve<R: Rng + ?Sized>(
+        &self,
+        distance_mat: &DistanceMat,
+        rng: &mut R,
+    ) -> AntColonyResult {
+        if distance_mat.n_units() == 0 {
+            return AntColonyResult {
+                route: Route::new(Vec::new()),
+                cost: 0.0,
+                best_iteration: 0,
+            };
+        }
+        self.run(distance_mat, rng).0
+    }
+    /// Run the colony and return the best tour together with the final pheromone. The distance
+    /// matrix must not be empty, as the colony is calibrated with a nearest-neighbor tour.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the tours are built on.
+    /// * `rng` - The random number generator the ants draw their tours from.
+    ///
+    fn run<R: Rng + ?Sized>(
+        &self,
+        distance_mat: &DistanceMat,
+        rng: &mut R,
+    ) -> (AntColonyResult, Pheromones) {
+        let n_nodes = distance_mat.n_units();
+        let n_ants = self.n_ants.unwrap_or(n_nodes).max(1);
+        let reference = nearest_neighbor_tour(0, distance_mat);
+        let mut best = AntColonyResult {
+            cost: distance_mat.get_cost(&reference),
+            route: Route::new(reference),
+            best_iteration: 0,
+        };
+        let initial = match self.update {
+            PheromoneUpdate::AntSystem => n_ants as f64 / positive(best.cost),
+            PheromoneUpdate::MaxMin => self.max_pheromone(best.cost),
+        };
+        let mut pheromones = Pheromones::new(distance_mat, initial, self.beta);
+        for iteration in 1..=self.n_iterations {
+            pheromones.update_weights(self.alpha);
+            let tours = (0..n_ants)
+                .map(|_| {
+                    let tour = pheromones.construct_tour(rng);
+                    let cost = distance_mat.get_cost(&tour);
+                    (tour, cost)
+                })
+                .collect::<Vec<(Vec<usize>, f64)>>();
+            pheromones.evaporate(self.evaporation);
+            match self.update {
+                PheromoneUpdate::AntSystem => {
+                    for (tour, cost) in &tours {
+                        pheromones.deposit(tour, positive(*cost).recip(), distance_mat);
+                    }
+                }
+                PheromoneUpdate::MaxMin => {
+                    if let Some((tour, cost)) = tours
+                        .iter()
+                        .min_by(|(_, cost_a), (_, cost_b)| cost_a.total_cmp(cost_b))
+                    {
+                        pheromones.deposit(tour, positive(*cost).recip(), distance_mat);
+                    }
+                }
+            }
+            for (tour, cost) in tours {
+                if cost < best.cost {
+                    best = AntColonyResult {
+                        route: Route::new(tour),
+                        cost,
+                        best_iteration: iteration,
+                    };
+                }
+            }
+            if self.update == PheromoneUpdate::MaxMin {
+                // The bounds follow the best tour found so far.
+                let max_pheromone = self.max_pheromone(best.cost);
+                pheromones.clamp(max_pheromone / (2 * n_nodes.max(1)) as f64, max_pheromone);
+            }
+        }
+        pheromones.update_weights(self.alpha);
+        (best, pheromones)
+    }
+    /// The upper bound of the pheromone of the MAX-MIN Ant System, the pheromone a leg of the
+    /// best tour converges to.
+    ///
+    /// # Arguments
+    ///
+    /// * `best_cost` - The cost of the best tour found so far.
+    ///
+    fn max_pheromone(&self, best_cost: f64) -> f64 {
+        (self.evaporation.max(f64::EPSILON) * positive(best_cost)).recip()
+    }
+}
+
+/// Clamp a cost to a positive number, so that its inverse can be laid as pheromone.
+///
+/// # Arguments
+///
+/// * `cost` - The cost of a tour.
+///
+fn positive(cost: f64) -> f64 {
+    cost.max(f64::EPSILON)
+}
+
+/// The pheromone of every leg of an instance together with the attractiveness of its nodes.
+#[derive(Debug, Clone, PartialEq)]
+struct Pheromones {
+    /// The number of nodes.
+    n_nodes: usize,
+    /// The pheromone of every leg, row by row.
+    pheromone: Vec<f64>,
+    /// `(1 / distance)^beta` of every leg, row by row.
+    visibility: Vec<f64>,
+    /// `pheromone^alpha * (1 / distance)^beta` of every leg, from which the ants choose.
+    weights: Vec<f64>,
+}
+impl Pheromones {
+    /// Lay the same pheromone on every leg.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the visibility is computed from.
+    /// * `initial` - The pheromone of every leg.
+    /// * `beta` - The exponent of the inverse distance.
+    ///
+    fn new(distance_mat: &DistanceMat, initial: f64, beta: f64) -> Self {
+        let n_nodes = distance_mat.n_units();
+        let visibility = (0..n_nodes)
+            .flat_map(|from| (0..n_nodes).map(move |to| (from, to)))
+            .map(|(from, to)| {
+                distance_mat
+                    .distance(from, to)
+                    .max(f64::EPSILON)
+                    .recip()
+                    .powf(beta)
+            })
+            .collect::<Vec<f64>>();
+        Pheromones {
+            n_nodes,
+            pheromone: vec![initial; n_nodes * n_nodes],
+            weights: visibility.clone(),
+            visibility,
+        }
+    }
+    /// Recompute the weights from which the ants choose after the pheromone changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `alpha` - The exponent of the pheromone.
+    ///
+    fn update_weights(&mut self, alpha: f64) {
+        for ((weight, pheromone), visibility) in self
+            .weights
+            .iter_mut()
+            .zip(&self.pheromone)
+            .zip(&self.visibility)
+        {
+            *weight = pheromone.powf(alpha) * visibility;
+        }
+    }
+    /// Build a tour from a random start node, every next node is drawn in proportion to its
+    /// weight.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The random number generator the nodes are drawn from.
+    ///
+    fn construct_tour<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<usize> {
+        let mut tour = Vec::with_capacity(self.n_nodes);
+        if self.n_nodes == 0 {
+            return tour;
+        }
+        let mut unvisited = (0..self.n_nodes).collect::<Vec<usize>>();
+        let mut current =
+            unvisited.swap_remove(get_random_elem_from_range_with_rng(0..self.n_nodes, rng));
+        tour.push(current);
+        while !unvisited.is_empty() {
+            let row = &self.weights[current * self.n_nodes..(current + 1) * self.n_nodes];
+            let total = unvisited.iter().map(|node| row[*node]).sum::<f64>();
+            let position = if total.is_finite() && total > 0.0 {
+                let mut target = get_random_elem_from_range_with_rng(0.0..total, rng);
+                unvisited
+                    .iter()
+                    .position(|node| {
+                        target -= row[*node];
+                        target < 0.0
+                    })
+                    // Rounding may leave a tiny rest for the last node.
+                    .unwrap_or(unvisited.len() - 1)
+            } else {
+                get_random_elem_from_range_with_rng(0..unvisited.len(), rng)
+            };
+            current = unvisited.swap_remove(position);
+            tour.push(current);
+        }
+        tour
+    }
+    /// Let a fraction of the pheromone of every leg evaporate.
+    ///
+    /// # Arguments
+    ///
+    /// * `evaporation` - The fraction that evaporates.
+    ///
+    fn evaporate(&mut self, evaporation: f64) {
+        for pheromone in self.pheromone.iter_mut() {
+            *pheromone *= 1.0 - evaporation;
+        }
+    }
+    /// Lay pheromone on the legs of a tour, on symmetric matrices in both directions.
+    ///
+    /// # Arguments
+    ///
+    /// * `tour` - The tour whose legs are reinforced.
+    /// * `amount` - How much pheromone is laid on every leg.
+    /// * `distance_mat` - The distance matrix the tour was built on.
+    ///
+    fn deposit(&mut self, tour: &[usize], amount: f64, distance_mat: &DistanceMat) {
+        let symmetric = distance_mat.is_symmetric();
+        for (position, from) in tour.iter().enumerate() {
+            let to = tour[(position + 1) % tour.len()];
+            self.pheromone[from * self.n_nodes + to] += amount;
+            if symmetric {
+                self.pheromone[to * self.n_nodes + from] += amount;
+            }
+        }
+    }
+    /// Keep the pheromone of every leg between two bounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `min` - The lower bound.
+    /// * `max` - The upper bound.
+    ///
+    fn clamp(&mut self, min: f64, max: f64) {
+        for pheromone in self.pheromone.iter_mut() {
+            *pheromone = pheromone.clamp(min, max);
+        }
+    }
+}
+
+/// The outcome of `AntColony::solve`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AntColonyResult {
+    /// The best tour any ant has built.
+    pub route: Route,
+    /// The cost of the best tour.
+    pub cost: f64,
+    /// In which iteration the best tour was built, 0 if no ant beat the nearest-neighbor tour
+    /// the colony is calibrated with.
+    pub best_iteration: usize,
+}
+
+impl Routes {
+    /// Create a population from an ant colony, e.g. to start the genetic algorithm from the
+    /// good but diverse tours of a short colony run: the best tour of the colony plus tours
+    /// that ants build from its final pheromone. Duplicate tours are merged and replaced by
+    /// random routes, so that like in `random` there are `n_routes` routes, at most `n_nodes!`.
+    /// An empty distance matrix gives an empty population.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_routes` - The number of routes your population of routes should contain.
+    /// * `distance_mat` - The distances the tours are built on.
+    /// * `colony` - The colony that is run.
+    /// * `rng` - The random number generator the ants draw their tours from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::aco::AntColony;
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let routes = Routes::ant_colony(5, &distance_matrix, &AntColony::new(10), &mut rand::thread_rng());
+    /// assert_eq!(routes.len(), 5);
+    /// ```
+    pub fn ant_colony<R: Rng + ?Sized>(
+        n_routes: usize,
+        distance_mat: &DistanceMat,
+        colony: &AntColony,
+        rng: &mut R,
+    ) -> Self {
+        if n_routes == 0 || distance_mat.n_units() == 0 {
+            return Routes::from(Vec::new());
+        }
+        let (best, pheromones) = colony.run(distance_mat, rng);
+        let mut routes = vec![best.route];
+        routes.extend((1..n_routes).map(|_| Route::new(pheromones.construct_tour(rng))));
+        Routes::from(routes).top_up_with_rng(n_routes, rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::valid_permutation;
+    use crate::utils::all_permutations;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::f64::consts::PI;
+
+    fn circle(n_nodes: usize) -> DistanceMat {
+        DistanceMat::from_coordinates(
+            &(0..n_nodes)
+                .map(|node| {
+                    let angle = 2.0 * PI * (node * 7 % n_nodes) as f64 / n_nodes as f64;
+                    (angle.cos(), angle.sin())
+                })
+                .collect::<Vec<(f64, f64)>>(),
+        )
+    }
+
+    #[test]
+    fn colony_finds_the_circle() {
+        let distance_mat = circle(13);
+        let optimum = 26.0 * (PI / 13.0).sin();
+        for update in [PheromoneUpdate::AntSystem, PheromoneUpdate::MaxMin] {
+            let result = AntColony::new(50)
+                .with_update(update)
+                .solve(&distance_mat, &mut StdRng::seed_from_u64(0));
+            valid_permutation(&(0..13).collect::<Vec<usize>>(), &result.route.indexes);
+            assert_eq!(result.cost, distance_mat.get_cost(&result.route.indexes));
+            assert!((result.cost - optimum).abs() < 1e-9, "{:?}", update);
+        }
+    }
+    #[test]
+    fn colony_finds_the_optimum_of_a_small_instance() {
+        let distance_mat = DistanceMat::from_coordinates(
+            &(0..9)
+                .map(|node| ((node * 37 % 11) as f64, (node * 53 % 13) as f64))
+                .collect::<Vec<(f64, f64)>>(),
+        );
+        let optimum = all_permutations(9)
+            .iter()
+            .map(|tour| distance_mat.get_cost(tour))
+            .fold(f64::INFINITY, f64::min);
+        assert!(distance_mat.get_cost(&nearest_neighbor_tour(0, &distance_mat)) > optimum + 1e-9);
+        for update in [PheromoneUpdate::AntSystem, PheromoneUpdate::MaxMin] {
+            let result = AntColony::new(100)
+                .with_update(update)
+                .solve(&distance_mat, &mut StdRng::seed_from_u64(0));
+            assert!(result.best_iteration > 0);
+            assert!((result.cost - optimum).abs() < 1e-9, "{:?}", update);
+        }
+    }
+    #[test]
+    fn colony_is_deterministic_given_the_seed() {
+        let distance_mat = circle(10);
+        let colony = AntColony::new(5).with_n_ants(3);
+        assert_eq!(
+            colony.solve(&distance_mat, &mut StdRng::seed_from_u64(3)),
+            colony.solve(&distance_mat, &mut StdRng::seed_from_u64(3))
+        );
+    }
+    #[test]
+    fn max_min_keeps_the_pheromone_bounded() {
+        let distance_mat = circle(8);
+        let colony = AntColony::new(30);
+        let (best, pheromones) = colony.run(&distance_mat, &mut StdRng::seed_from_u64(0));
+        let max_pheromone = colony.max_pheromone(best.cost);
+        assert!(pheromones
+            .pheromone
+            .iter()
+            .all(|pheromone| *pheromone <= max_pheromone && *pheromone >= max_pheromone / 16.0));
+    }
+    #[test]
+    fn colony_seeds_a_population() {
+        let distance_mat = circle(12);
+        let routes = Routes::ant_colony(
+            6,
+            &distance_mat,
+            &AntColony::new(20),
+            &mut StdRng::seed_from_u64(0),
+        );
+        assert_eq!(routes.len(), 6);
+        let best = AntColony::new(20).solve(&distance_mat, &mut StdRng::seed_from_u64(0));
+        for route in routes.iter() {
+            valid_permutation(&(0..12).collect::<Vec<usize>>(), &route.indexes);
+        }
+        assert!(routes.iter().any(|route| *route == best.route));
+    }
+    #[test]
+    fn converged_colony_still_seeds_a_full_population() {
+        // With strong pheromone and greedy ants most tours repeat the best one.
+        let colony = AntColony::new(50)
+            .with_weights(5.0, 5.0)
+            .with_update(PheromoneUpdate::AntSystem);
+        let routes = Routes::ant_colony(20, &circle(6), &colony, &mut StdRng::seed_from_u64(1));
+        assert_eq!(routes.len(), 20);
+        let routes = Routes::ant_colony(20, &circle(3), &colony, &mut StdRng::seed_from_u64(1));
+        assert_eq!(routes.len(), 6);
+    }
+    #[test]
+    fn empty_distance_matrix() {
+        let distance_mat = DistanceMat::new(Vec::<Vec<f64>>::new());
+        let mut rng = StdRng::seed_from_u64(0);
+        let result = AntColony::new(5).solve(&distance_mat, &mut rng);
+        assert!(result.route.indexes.is_empty());
+        assert_eq!(result.cost, 0.0);
+        assert!(Routes::ant_colony(3, &distance_mat, &AntColony::new(5), &mut rng).is_empty());
+    }
+}
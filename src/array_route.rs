@@ -0,0 +1,261 @@
+#[cfg(feature = "std")]
+use crate::distance_mat::DistanceMat;
+#[cfg(feature = "std")]
+use crate::route::sample_relocation;
+use crate::route::Route;
+#[cfg(all(feature = "self-check", feature = "std"))]
+use crate::self_check::{check_fitness, check_permutation};
+#[cfg(feature = "std")]
+use crate::subsequence::Subsequence;
+#[cfg(feature = "std")]
+use crate::utils::move_elem;
+use core::fmt;
+#[cfg(feature = "std")]
+use genetic_algorithm_traits::Individual;
+
+/// A route through exactly `N` nodes that is stored inline as `[u16; N]`. It behaves like
+/// `Route`, but none of its operators allocate on the heap, which pays off if the size of the
+/// instance is known at compile time. Supports instances with up to 65536 nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ArrayRoute<const N: usize> {
+    /// The order in which the nodes should be visited.
+    pub indexes: [u16; N],
+}
+impl<const N: usize> fmt::Display for ArrayRoute<N> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "ArrayRoute({:?})", self.indexes)
+    }
+}
+impl<const N: usize> ArrayRoute<N> {
+    /// Create a new route based on an array of indexes.
+    ///
+    /// # Arguments
+    ///
+    /// * `indexes` - The order in which the nodes are visited in the Traveling Salesman Problem.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::array_route::ArrayRoute;
+    ///
+    /// let my_individual = ArrayRoute::new([0, 1, 2]);
+    /// ```
+    pub fn new(indexes: [u16; N]) -> Self {
+        ArrayRoute { indexes }
+    }
+    /// Convert a `Route` with exactly `N` nodes. Returns `None` if the route has a different
+    /// number of nodes or visits a node that doesn't fit into `u16`.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The route that should be converted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::array_route::ArrayRoute;
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// assert_eq!(ArrayRoute::<3>::from_route(&Route::new(vec![2, 0, 1])), Some(ArrayRoute::new([2, 0, 1])));
+    /// assert_eq!(ArrayRoute::<2>::from_route(&Route::new(vec![2, 0, 1])), None);
+    /// ```
+    pub fn from_route(route: &Route) -> Option<Self> {
+        if route.indexes.len() != N {
+            return None;
+        }
+        let mut indexes = [0; N];
+        for (index, node) in indexes.iter_mut().zip(&route.indexes) {
+            *index = u16::try_from(*node).ok()?;
+        }
+        Some(ArrayRoute { indexes })
+    }
+    /// Convert the route into a heap allocated `Route`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::array_route::ArrayRoute;
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// assert_eq!(ArrayRoute::new([2, 0, 1]).to_route(), Route::new(vec![2, 0, 1]));
+    /// ```
+    pub fn to_route(&self) -> Route {
+        Route::new(self.indexes.iter().map(|node| *node as usize).collect())
+    }
+}
+
+/// The `ordered_crossover`-operator of `Route` for arrays.
+///
+/// # Arguments
+///
+/// * `parent_a` - The first parent from which the subsequence is taken.
+/// * `parent_b` - The second parent in which the subsequence is inputed.
+/// * `subsequence` - The actual subsequence that is taken.
+///
+#[cfg(feature = "std")]
+fn ordered_crossover<const N: usize>(
+    parent_a: &[u16; N],
+    parent_b: &[u16; N],
+    subsequence: Subsequence,
+) -> [u16; N] {
+    let start = subsequence.start_index;
+    let end = subsequence.start_index + subsequence.length;
+    let selection = &parent_a[start..end];
+    let mut child = [0; N];
+    // Same order as `utils::ordered_crossover`: the elements of the receiver's subsequence that
+    // are not in the donor's selection, the selection, then the elements after and before.
+    let receiver_elements = parent_b[start..end]
+        .iter()
+        .filter(|elem| !selection.contains(elem));
+    let remaining_elements = parent_b[end..]
+        .iter()
+        .chain(&parent_b[..start])
+        .filter(|elem| !selection.contains(elem));
+    for (child_elem, elem) in child
+        .iter_mut()
+        .zip(receiver_elements.chain(selection).chain(remaining_elements))
+    {
+        *child_elem = *elem;
+    }
+    child
+}
+
+#[cfg(feature = "std")]
+impl<'a, const N: usize> Individual<'a> for ArrayRoute<N> {
+    // The Distance matrix is needed by the individuals to compute their fitness on.
+    type IndividualCost = DistanceMat;
+    /// Randomly moves a node in front of another node, like `Route::mutate`.
+    ///
+    /// # Arguments
+    ///
+    /// * `prob` - The probability with which the indexes will be changed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::array_route::ArrayRoute;
+    /// use genetic_algorithm_traits::Individual;
+    ///
+    /// let my_mutated_indiviual = ArrayRoute::new([0, 1, 2]).mutate(1.0);
+    /// ```
+    fn mutate(mut self, prob: f32) -> Self {
+        if let Some((put_before_idx, move_idx)) = sample_relocation(N, prob) {
+            #[cfg(feature = "self-check")]
+            let parent = self.indexes;
+            move_elem(&mut self.indexes, put_before_idx, move_idx);
+            #[cfg(feature = "self-check")]
+            check_permutation("ArrayRoute::mutate", &parent, &self.indexes);
+        }
+        self
+    }
+    /// Crossover this invidual with another individual with the `ordered_crossover` algorithm,
+    /// like `Route::crossover`.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The other individual you would like to crossover with this individual.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::array_route::ArrayRoute;
+    /// use genetic_algorithm_traits::Individual;
+    ///
+    /// let child = ArrayRoute::new([0, 1, 2]).crossover(&ArrayRoute::new([1, 0, 2]));
+    /// ```
+    fn crossover(&self, other: &ArrayRoute<N>) -> Self {
+        let child = ArrayRoute {
+            indexes: ordered_crossover(
+                &self.indexes,
+                &other.indexes,
+                Subsequence::random_subsequence_with_rng(N, &mut rand::thread_rng()),
+            ),
+        };
+        #[cfg(feature = "self-check")]
+        check_permutation("ArrayRoute::crossover", &self.indexes, &child.indexes);
+        child
+    }
+    /// Compute the fitness of the route under the objective of the distance matrix, like
+    /// `Route::fitness`.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_matrix` - Distance Matrix that determines the length of the proposed
+    ///   route
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::array_route::ArrayRoute;
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_traits::Individual;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert_eq!(ArrayRoute::new([0, 1, 2]).fitness(&distance_matrix), -6.0);
+    /// ```
+    fn fitness(&self, distance_mat: &DistanceMat) -> f64 {
+        let indexes: [usize; N] = core::array::from_fn(|index| self.indexes[index] as usize);
+        let fitness = -distance_mat.get_cost(&indexes);
+        #[cfg(feature = "self-check")]
+        check_fitness("ArrayRoute::fitness", &self.indexes, fitness);
+        fitness
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::valid_permutation;
+    use crate::utils::ordered_crossover as route_ordered_crossover;
+    #[test]
+    fn from_route_with_too_large_node() {
+        assert_eq!(
+            ArrayRoute::<2>::from_route(&Route::new(vec![0, 70000])),
+            None
+        );
+    }
+    #[test]
+    fn mutate_keeps_permutation() {
+        for _ in 0..10 {
+            let route = ArrayRoute::new([0, 1, 2, 3, 4, 5]);
+            valid_permutation(&route.indexes, &route.mutate(1.0).indexes);
+        }
+    }
+    #[test]
+    fn crossover_like_route() {
+        let parent_a = ArrayRoute::new([0, 4, 1, 3, 2, 5]);
+        let parent_b = ArrayRoute::new([5, 2, 3, 0, 1, 4]);
+        for start_index in 0..4 {
+            for length in 1..(6 - start_index) {
+                assert_eq!(
+                    ordered_crossover(
+                        &parent_a.indexes,
+                        &parent_b.indexes,
+                        Subsequence::new(start_index, length)
+                    ),
+                    ArrayRoute::from_route(&route_ordered_crossover(
+                        &parent_a.to_route(),
+                        &parent_b.to_route(),
+                        Subsequence::new(start_index, length)
+                    ))
+                    .unwrap()
+                    .indexes
+                );
+            }
+        }
+    }
+    #[test]
+    fn fitness_like_route() {
+        let distance_mat = DistanceMat::new(vec![
+            vec![0.0, 1.0, 2.0, 3.0],
+            vec![1.0, 0.0, 3.0, 1.0],
+            vec![2.0, 3.0, 0.0, 2.0],
+            vec![3.0, 1.0, 2.0, 0.0],
+        ]);
+        let route = ArrayRoute::new([3, 1, 0, 2]);
+        assert_eq!(
+            route.fitness(&distance_mat),
+            route.to_route().fitness(&distance_mat)
+        );
+    }
+}
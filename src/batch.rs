@@ -0,0 +1,291 @@
+use crate::distance_mat::DistanceMat;
+use crate::route::Route;
+use crate::routes::Routes;
+use alloc::vec::Vec;
+
+/// A population stored as structure of arrays: the nodes of all routes are stored back to back
+/// in one contiguous buffer and `offsets` marks where every route starts. Evaluating the whole
+/// population then walks a single allocation, and consumers like SIMD or GPU kernels can take
+/// the buffers as they are instead of copying every route.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RouteBatch {
+    /// The nodes of all routes, one route after another.
+    nodes: Vec<usize>,
+    /// Route `i` consists of `nodes[offsets[i]..offsets[i + 1]]`. Always starts with 0.
+    offsets: Vec<usize>,
+}
+impl RouteBatch {
+    /// Create an empty batch.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::batch::RouteBatch;
+    ///
+    /// assert!(RouteBatch::new().is_empty());
+    /// ```
+    pub fn new() -> Self {
+        RouteBatch {
+            nodes: Vec::new(),
+            offsets: vec![0],
+        }
+    }
+    /// Append a route to the end of the batch.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The nodes of the route in the order in which they are visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::batch::RouteBatch;
+    ///
+    /// let mut batch = RouteBatch::new();
+    /// batch.push(&[0, 2, 1]);
+    /// assert_eq!(batch.get(0), Some(&[0, 2, 1][..]));
+    /// ```
+    pub fn push(&mut self, route: &[usize]) {
+        if self.offsets.is_empty() {
+            self.offsets.push(0);
+        }
+        self.nodes.extend_from_slice(route);
+        self.offsets.push(self.nodes.len());
+    }
+    /// The number of routes in the batch.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::batch::RouteBatch;
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let batch = RouteBatch::from(&Routes::from(vec![Route::new(vec![0, 1, 2])]));
+    /// assert_eq!(batch.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.offsets.len().saturating_sub(1)
+    }
+    /// Does the batch contain no routes?
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::batch::RouteBatch;
+    ///
+    /// assert!(RouteBatch::default().is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Get the nodes of the route at position `index`, `None` if there is no such route.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The position of the route in the batch.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::batch::RouteBatch;
+    ///
+    /// let mut batch = RouteBatch::new();
+    /// batch.push(&[1, 0]);
+    /// assert_eq!(batch.get(0), Some(&[1, 0][..]));
+    /// assert_eq!(batch.get(1), None);
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&[usize]> {
+        let start = *self.offsets.get(index)?;
+        let end = *self.offsets.get(index + 1)?;
+        Some(&self.nodes[start..end])
+    }
+    /// Iterate over the routes of the batch as slices.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::batch::RouteBatch;
+    ///
+    /// let mut batch = RouteBatch::new();
+    /// batch.push(&[0, 1]);
+    /// batch.push(&[1, 0]);
+    /// assert_eq!(batch.iter().collect::<Vec<&[usize]>>(), vec![&[0, 1][..], &[1, 0][..]]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = &[usize]> {
+        self.offsets
+            .windows(2)
+            .map(move |bounds| &self.nodes[bounds[0]..bounds[1]])
+    }
+    /// The contiguous buffer holding the nodes of all routes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::batch::RouteBatch;
+    ///
+    /// let mut batch = RouteBatch::new();
+    /// batch.push(&[0, 1]);
+    /// batch.push(&[1, 0]);
+    /// assert_eq!(batch.nodes(), &[0, 1, 1, 0]);
+    /// ```
+    pub fn nodes(&self) -> &[usize] {
+        &self.nodes
+    }
+    /// Where the routes start in `nodes`, followed by the length of `nodes`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::batch::RouteBatch;
+    ///
+    /// let mut batch = RouteBatch::new();
+    /// batch.push(&[0, 1]);
+    /// batch.push(&[1, 0]);
+    /// assert_eq!(batch.offsets(), &[0, 2, 4]);
+    /// ```
+    pub fn offsets(&self) -> &[usize] {
+        &self.offsets
+    }
+    /// Compute the fitness of every route in the batch, in the order of the batch.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the fitness is computed on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::batch::RouteBatch;
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let mut batch = RouteBatch::new();
+    /// batch.push(&[0, 1, 2]);
+    /// assert_eq!(batch.fitnesses(&distance_matrix), vec![-6.0]);
+    /// ```
+    pub fn fitnesses(&self, distance_mat: &DistanceMat) -> Vec<f64> {
+        let mut fitnesses = Vec::with_capacity(self.len());
+        self.fitnesses_into(distance_mat, &mut fitnesses);
+        fitnesses
+    }
+    /// Compute the fitness of every route like `fitnesses`, but write them into `fitnesses`
+    /// so that the buffer can be reused between generations.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the fitness is computed on.
+    /// * `fitnesses` - The buffer the fitnesses are written to. It is cleared first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::batch::RouteBatch;
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let mut batch = RouteBatch::new();
+    /// batch.push(&[0, 1, 2]);
+    /// let mut fitnesses = vec![1.0, 2.0];
+    /// batch.fitnesses_into(&distance_matrix, &mut fitnesses);
+    /// assert_eq!(fitnesses, vec![-6.0]);
+    /// ```
+    pub fn fitnesses_into(&self, distance_mat: &DistanceMat, fitnesses: &mut Vec<f64>) {
+        fitnesses.clear();
+        fitnesses.extend(self.iter().map(|route| -distance_mat.get_cost(route)));
+    }
+}
+
+impl From<&Routes> for RouteBatch {
+    /// Copy all routes of a population into one contiguous buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `routes` - The population that should be stored as batch.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::batch::RouteBatch;
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let batch = RouteBatch::from(&Routes::from(vec![Route::new(vec![0, 1, 2])]));
+    /// assert_eq!(batch.nodes(), &[0, 1, 2]);
+    /// ```
+    fn from(routes: &Routes) -> Self {
+        let mut batch = RouteBatch {
+            nodes: Vec::with_capacity(routes.len() * routes.get_n_nodes()),
+            offsets: Vec::with_capacity(routes.len() + 1),
+        };
+        batch.offsets.push(0);
+        for route in routes.iter() {
+            batch.push(&route.indexes);
+        }
+        batch
+    }
+}
+
+impl From<&RouteBatch> for Routes {
+    /// Create a population from the routes of a batch.
+    ///
+    /// # Arguments
+    ///
+    /// * `batch` - The batch the routes are taken from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::batch::RouteBatch;
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let mut batch = RouteBatch::new();
+    /// batch.push(&[0, 1, 2]);
+    /// assert_eq!(Routes::from(&batch).len(), 1);
+    /// ```
+    fn from(batch: &RouteBatch) -> Self {
+        Routes::from(
+            batch
+                .iter()
+                .map(|route| Route::new(route.to_vec()))
+                .collect::<Vec<Route>>(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::test_utils::test_dist_mat;
+    #[test]
+    fn default_is_empty() {
+        let mut batch = RouteBatch::default();
+        assert_eq!(batch.len(), 0);
+        assert_eq!(batch.get(0), None);
+        assert_eq!(batch.iter().count(), 0);
+        batch.push(&[0, 1]);
+        assert_eq!(batch.offsets(), &[0, 2]);
+    }
+    #[test]
+    fn routes_of_different_lengths() {
+        let mut batch = RouteBatch::new();
+        batch.push(&[0, 1, 2]);
+        batch.push(&[]);
+        batch.push(&[1]);
+        assert_eq!(batch.len(), 3);
+        assert_eq!(batch.get(1), Some(&[][..]));
+        assert_eq!(batch.get(2), Some(&[1][..]));
+    }
+    #[cfg(feature = "std")]
+    #[test]
+    fn fitnesses_match_routes() {
+        let distance_mat = test_dist_mat();
+        let routes = Routes::random(4, 3);
+        let batch = RouteBatch::from(&routes);
+        for (route, fitness) in batch.iter().zip(batch.fitnesses(&distance_mat)) {
+            assert_eq!(Route::new(route.to_vec()).fitness(&distance_mat), fitness);
+        }
+        assert_eq!(Routes::from(&batch), routes);
+    }
+}
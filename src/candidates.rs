@@ -0,0 +1,169 @@
+use crate::distance_mat::DistanceMat;
+use alloc::vec::Vec;
+
+/// The `k` nearest neighbors of every node, the candidate edges of an instance as used by
+/// Lin-Kernighan style solvers. Good tours of large instances almost only use edges between
+/// close nodes, so operators that only introduce candidate edges, e.g.
+/// `CrossoverOperator::Candidate` and `MutationOperator::Candidate`, waste far fewer offspring
+/// on long edges than uniformly random ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CandidateList {
+    /// How many neighbors every node has, at most the number of nodes minus one.
+    k: usize,
+    /// The neighbors of every node, `k` per node and sorted by their distance from it.
+    neighbors: Vec<usize>,
+}
+impl CandidateList {
+    /// Compute the `k` nearest neighbors of every node of a distance matrix. The neighbors of
+    /// a node are the nodes with the shortest distance from it, ties are broken by the index.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the neighbors are taken from.
+    /// * `k` - How many neighbors every node gets, it is capped at the number of nodes minus one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::candidates::CandidateList;
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let candidates = CandidateList::new(&distance_matrix, 1);
+    /// assert_eq!(candidates.neighbors(2), &[0]);
+    /// ```
+    pub fn new(distance_mat: &DistanceMat, k: usize) -> Self {
+        let n_units = distance_mat.n_units();
+        let k = k.min(n_units.saturating_sub(1));
+        let mut neighbors = Vec::with_capacity(n_units * k);
+        let mut others = Vec::with_capacity(n_units);
+        for from in 0..n_units {
+            others.clear();
+            others.extend((0..n_units).filter(|to| *to != from));
+            let by_distance = |to_a: &usize, to_b: &usize| {
+                distance_mat
+                    .distance(from, *to_a)
+                    .total_cmp(&distance_mat.distance(from, *to_b))
+                    .then(to_a.cmp(to_b))
+            };
+            if k < others.len() {
+                others.select_nth_unstable_by(k, by_distance);
+                others.truncate(k);
+            }
+            others.sort_unstable_by(by_distance);
+            neighbors.extend_from_slice(&others);
+        }
+        CandidateList { k, neighbors }
+    }
+    /// Get how many neighbors every node has.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::candidates::CandidateList;
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// // A node has only two other nodes.
+    /// assert_eq!(CandidateList::new(&distance_matrix, 5).k(), 2);
+    /// ```
+    pub fn k(&self) -> usize {
+        self.k
+    }
+    /// Get the neighbors of a node, sorted by their distance from it.
+    ///
+    /// # Arguments
+    ///
+    /// * `node` - The node whose neighbors are returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::candidates::CandidateList;
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert_eq!(CandidateList::new(&distance_matrix, 2).neighbors(1), &[0, 2]);
+    /// ```
+    pub fn neighbors(&self, node: usize) -> &[usize] {
+        &self.neighbors[node * self.k..(node + 1) * self.k]
+    }
+    /// Check whether the edge between two nodes is a candidate edge, e.g. whether one of them
+    /// is a neighbor of the other.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - One end of the edge.
+    /// * `to` - The other end of the edge.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::candidates::CandidateList;
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let candidates = CandidateList::new(&distance_matrix, 1);
+    /// assert!(candidates.is_candidate(0, 1));
+    /// assert!(!candidates.is_candidate(1, 2));
+    /// ```
+    pub fn is_candidate(&self, from: usize, to: usize) -> bool {
+        self.neighbors(from).contains(&to) || self.neighbors(to).contains(&from)
+    }
+    /// Restrict the candidate list to a subset of the nodes, whose node `label` is
+    /// `nodes[label]`. Every node keeps its nearest neighbors in the subset under their new
+    /// labels, and as it loses at most one neighbor per left out node, `k` shrinks by their
+    /// number.
+    ///
+    /// # Arguments
+    ///
+    /// * `nodes` - The nodes that are kept, in the order of their new labels.
+    /// * `labels` - The new label of every node, `usize::MAX` for the nodes that are left out.
+    ///
+    pub(crate) fn restricted_to(&self, nodes: &[usize], labels: &[usize]) -> CandidateList {
+        let n_left_out = labels.iter().filter(|label| **label == usize::MAX).count();
+        let k = self
+            .k
+            .saturating_sub(n_left_out)
+            .min(nodes.len().saturating_sub(1));
+        let neighbors = nodes
+            .iter()
+            .flat_map(|node| {
+                self.neighbors(*node)
+                    .iter()
+                    .filter_map(|neighbor| labels.get(*neighbor).copied())
+                    .filter(|label| *label != usize::MAX)
+                    .take(k)
+            })
+            .collect::<Vec<usize>>();
+        CandidateList { k, neighbors }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neighbors_are_the_nearest_nodes() {
+        let distance_mat =
+            DistanceMat::from_coordinates(&(0..10).map(|x| (x as f64, 0.0)).collect::<Vec<_>>());
+        let candidates = CandidateList::new(&distance_mat, 3);
+        assert_eq!(candidates.neighbors(0), &[1, 2, 3]);
+        assert_eq!(candidates.neighbors(5), &[4, 6, 3]);
+        // Without nodes 0 and 4, node 5 (label 3) keeps its nearest neighbor 6 (label 4).
+        let nodes = [1, 2, 3, 5, 6, 7, 8, 9];
+        let mut labels = vec![usize::MAX; 10];
+        for (label, node) in nodes.iter().enumerate() {
+            labels[*node] = label;
+        }
+        let restricted = candidates.restricted_to(&nodes, &labels);
+        assert_eq!(restricted.k(), 1);
+        assert_eq!(restricted.neighbors(3), &[4]);
+        assert_eq!(restricted.neighbors(0), &[1]);
+        assert_eq!(candidates.neighbors(9), &[8, 7, 6]);
+        assert!(candidates.is_candidate(3, 5));
+        assert!(!candidates.is_candidate(2, 6));
+    }
+}
@@ -0,0 +1,163 @@
+use crate::distance_mat::DistanceMat;
+use crate::route::Route;
+use core::fmt;
+
+/// A certificate that states in which neighborhoods a route is a local optimum, e.g. that no
+/// single 2-opt or Or-opt move can lower its cost. It turns "the best route that was found"
+/// into a verifiable statement about the route.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LocalOptimality {
+    /// The cost of the route.
+    pub cost: f64,
+    /// Can no reversal of a stretch of the route lower its cost?
+    pub two_opt_optimal: bool,
+    /// Can no move of a chain of one to three nodes lower its cost?
+    pub or_opt_optimal: bool,
+}
+impl LocalOptimality {
+    /// Check whether a route is a local optimum for 2-opt and Or-opt.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The route that should be checked.
+    /// * `distance_mat` - The distance matrix the cost is computed on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::certificate::LocalOptimality;
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 9.0, 1.0],
+    ///     vec![1.0, 0.0, 1.0, 9.0],
+    ///     vec![9.0, 1.0, 0.0, 1.0],
+    ///     vec![1.0, 9.0, 1.0, 0.0],
+    /// ]);
+    /// assert!(LocalOptimality::check(&Route::new(vec![0, 1, 2, 3]), &distance_matrix).is_certified());
+    /// assert!(!LocalOptimality::check(&Route::new(vec![0, 2, 1, 3]), &distance_matrix).is_certified());
+    /// ```
+    pub fn check(route: &Route, distance_mat: &DistanceMat) -> Self {
+        // The local searches only change a route if one of their moves improves it.
+        LocalOptimality {
+            cost: distance_mat.get_cost(&route.indexes),
+            two_opt_optimal: route.two_opt(distance_mat) == *route,
+            or_opt_optimal: route.or_opt(distance_mat) == *route,
+        }
+    }
+    /// Is the route a local optimum for 2-opt and Or-opt?
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::certificate::LocalOptimality;
+    ///
+    /// let certificate = LocalOptimality { cost: 4.0, two_opt_optimal: true, or_opt_optimal: false };
+    /// assert!(!certificate.is_certified());
+    /// ```
+    pub fn is_certified(&self) -> bool {
+        self.two_opt_optimal && self.or_opt_optimal
+    }
+}
+impl fmt::Display for LocalOptimality {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let optimal = |is_optimal: bool| if is_optimal { "" } else { "not " };
+        write!(
+            formatter,
+            "cost {:.2}, {}2-opt optimal, {}Or-opt optimal",
+            self.cost,
+            optimal(self.two_opt_optimal),
+            optimal(self.or_opt_optimal)
+        )
+    }
+}
+
+/// Improve a route with 2-opt and Or-opt in turns until neither of them can improve it, and
+/// return the improved route together with its certificate.
+///
+/// # Arguments
+///
+/// * `route` - The route that should be improved, e.g. the best route of an evolution.
+/// * `distance_mat` - The distance matrix the cost is computed on.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::certificate::improve_until_locally_optimal;
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+/// use genetic_algorithm_tsp::route::Route;
+///
+/// let distance_matrix = DistanceMat::new(vec![
+///     vec![0.0, 1.0, 9.0, 1.0],
+///     vec![1.0, 0.0, 1.0, 9.0],
+///     vec![9.0, 1.0, 0.0, 1.0],
+///     vec![1.0, 9.0, 1.0, 0.0],
+/// ]);
+/// let (route, certificate) = improve_until_locally_optimal(&Route::new(vec![0, 2, 1, 3]), &distance_matrix);
+/// assert!(certificate.is_certified());
+/// assert_eq!(certificate.cost, 4.0);
+/// ```
+pub fn improve_until_locally_optimal(
+    route: &Route,
+    distance_mat: &DistanceMat,
+) -> (Route, LocalOptimality) {
+    let mut route = route.two_opt(distance_mat);
+    loop {
+        let improved = route.or_opt(distance_mat).two_opt(distance_mat);
+        if improved == route {
+            break;
+        }
+        route = improved;
+    }
+    let certificate = LocalOptimality {
+        cost: distance_mat.get_cost(&route.indexes),
+        two_opt_optimal: true,
+        or_opt_optimal: true,
+    };
+    (route, certificate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::test_utils::valid_permutation;
+    #[cfg(feature = "std")]
+    use crate::utils::get_random_elem_from_range;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn improved_route_passes_check() {
+        let points = (0..15)
+            .map(|_| {
+                (
+                    get_random_elem_from_range(0.0..100.0),
+                    get_random_elem_from_range(0.0..100.0),
+                )
+            })
+            .collect::<Vec<(f64, f64)>>();
+        let distance_mat = DistanceMat::from_coordinates(&points);
+        let route = Route::new((0..15).collect());
+        let (improved_route, certificate) = improve_until_locally_optimal(&route, &distance_mat);
+        valid_permutation(&route.indexes, &improved_route.indexes);
+        assert_eq!(
+            LocalOptimality::check(&improved_route, &distance_mat),
+            certificate
+        );
+        assert!(certificate.cost <= distance_mat.get_cost(&route.indexes));
+    }
+    #[test]
+    fn format() {
+        let certificate = LocalOptimality {
+            cost: 4.0,
+            two_opt_optimal: true,
+            or_opt_optimal: false,
+        };
+        assert_eq!(
+            format!("{}", certificate),
+            "cost 4.00, 2-opt optimal, not Or-opt optimal"
+        );
+    }
+}
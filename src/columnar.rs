@@ -0,0 +1,299 @@
+use crate::distance_mat::DistanceMat;
+use arrow_array::{Array, Float64Array, RecordBatch};
+use arrow_schema::ArrowError;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The name of the column that contains the distances of a matrix in row-major order.
+pub const DISTANCE_COLUMN: &str = "distance";
+/// The name of the column that contains the x-coordinates of the nodes.
+pub const X_COLUMN: &str = "x";
+/// The name of the column that contains the y-coordinates of the nodes.
+pub const Y_COLUMN: &str = "y";
+
+/// How many rows are read from a Parquet file at once.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// The errors that can occur while reading distance matrices or coordinates from Parquet files
+/// or Arrow record batches.
+#[derive(Debug)]
+pub enum ColumnarError {
+    /// The file could not be opened.
+    Io(io::Error),
+    /// The Parquet file could not be decoded.
+    Parquet(parquet::errors::ParquetError),
+    /// A record batch could not be read.
+    Arrow(ArrowError),
+    /// A column is missing, has another type than `Float64` or contains nulls.
+    InvalidColumn(String),
+    /// The number of distances is not the square of the number of nodes.
+    NotSquare {
+        /// The number of distances that were read.
+        n_entries: usize,
+    },
+}
+impl fmt::Display for ColumnarError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ColumnarError::Io(error) => write!(formatter, "Reading the file failed: {}", error),
+            ColumnarError::Parquet(error) => {
+                write!(formatter, "Decoding the Parquet file failed: {}", error)
+            }
+            ColumnarError::Arrow(error) => {
+                write!(formatter, "Reading the record batches failed: {}", error)
+            }
+            ColumnarError::InvalidColumn(column) => write!(
+                formatter,
+                "The column {} is missing, not of type Float64 or contains nulls",
+                column
+            ),
+            ColumnarError::NotSquare { n_entries } => write!(
+                formatter,
+                "{} distances don't form a square matrix",
+                n_entries
+            ),
+        }
+    }
+}
+impl Error for ColumnarError {}
+impl From<io::Error> for ColumnarError {
+    fn from(error: io::Error) -> Self {
+        ColumnarError::Io(error)
+    }
+}
+impl From<parquet::errors::ParquetError> for ColumnarError {
+    fn from(error: parquet::errors::ParquetError) -> Self {
+        ColumnarError::Parquet(error)
+    }
+}
+impl From<ArrowError> for ColumnarError {
+    fn from(error: ArrowError) -> Self {
+        ColumnarError::Arrow(error)
+    }
+}
+
+/// Append the values of a `Float64`-column of a record batch.
+///
+/// # Arguments
+///
+/// * `batch` - The record batch that contains the column.
+/// * `column` - The name of the column.
+/// * `values` - The values are appended to this buffer.
+///
+fn extend_column(
+    batch: &RecordBatch,
+    column: &str,
+    values: &mut Vec<f64>,
+) -> Result<(), ColumnarError> {
+    let array = batch
+        .column_by_name(column)
+        .and_then(|array| array.as_any().downcast_ref::<Float64Array>())
+        .filter(|array| array.null_count() == 0)
+        .ok_or_else(|| ColumnarError::InvalidColumn(column.to_string()))?;
+    values.extend_from_slice(array.values());
+    Ok(())
+}
+
+/// Create a distance matrix from Arrow record batches, e.g. the chunks of a larger table. The
+/// `distance`-column of all batches together has to contain the `n * n` distances of the
+/// matrix in row-major order, e.g. the distance from node `i` to node `j` is entry `i * n + j`.
+///
+/// # Arguments
+///
+/// * `batches` - The record batches in the order of their rows.
+///
+/// # Examples
+///
+/// ```
+/// use arrow_array::{Float64Array, RecordBatch};
+/// use genetic_algorithm_tsp::columnar::distance_mat_from_record_batches;
+/// use std::sync::Arc;
+///
+/// let batch = |distances: Vec<f64>| {
+///     RecordBatch::try_from_iter([("distance", Arc::new(Float64Array::from(distances)) as _)])
+/// };
+/// let distance_matrix = distance_mat_from_record_batches(vec![
+///     batch(vec![0.0, 1.0, 2.0, 1.0, 0.0]),
+///     batch(vec![3.0, 2.0, 3.0, 0.0]),
+/// ])
+/// .unwrap();
+/// assert_eq!(distance_matrix.get_cost(&[0, 1, 2]), 6.0);
+/// ```
+pub fn distance_mat_from_record_batches<I>(batches: I) -> Result<DistanceMat, ColumnarError>
+where
+    I: IntoIterator<Item = Result<RecordBatch, ArrowError>>,
+{
+    let mut distances = Vec::new();
+    for batch in batches {
+        extend_column(&batch?, DISTANCE_COLUMN, &mut distances)?;
+    }
+    let n_nodes = (distances.len() as f64).sqrt().round() as usize;
+    if n_nodes * n_nodes != distances.len() {
+        return Err(ColumnarError::NotSquare {
+            n_entries: distances.len(),
+        });
+    }
+    Ok(DistanceMat::new(if n_nodes == 0 {
+        Vec::new()
+    } else {
+        distances
+            .chunks_exact(n_nodes)
+            .map(|row| row.to_vec())
+            .collect()
+    }))
+}
+
+/// Read the coordinates of the nodes from Arrow record batches with an `x`- and a `y`-column,
+/// e.g. to create a distance matrix with `DistanceMat::from_coordinates`.
+///
+/// # Arguments
+///
+/// * `batches` - The record batches in the order of their rows.
+///
+/// # Examples
+///
+/// ```
+/// use arrow_array::{Float64Array, RecordBatch};
+/// use genetic_algorithm_tsp::columnar::coordinates_from_record_batches;
+/// use std::sync::Arc;
+///
+/// let batch = RecordBatch::try_from_iter([
+///     ("x", Arc::new(Float64Array::from(vec![0.0, 3.0])) as _),
+///     ("y", Arc::new(Float64Array::from(vec![0.0, 4.0])) as _),
+/// ]);
+/// assert_eq!(coordinates_from_record_batches(vec![batch]).unwrap(), vec![(0.0, 0.0), (3.0, 4.0)]);
+/// ```
+pub fn coordinates_from_record_batches<I>(batches: I) -> Result<Vec<(f64, f64)>, ColumnarError>
+where
+    I: IntoIterator<Item = Result<RecordBatch, ArrowError>>,
+{
+    let mut x_coordinates = Vec::new();
+    let mut y_coordinates = Vec::new();
+    for batch in batches {
+        let batch = batch?;
+        extend_column(&batch, X_COLUMN, &mut x_coordinates)?;
+        extend_column(&batch, Y_COLUMN, &mut y_coordinates)?;
+    }
+    Ok(x_coordinates.into_iter().zip(y_coordinates).collect())
+}
+
+/// Read a distance matrix from a Parquet file with a `distance`-column that contains the
+/// distances in row-major order, see `distance_mat_from_record_batches`. The file is read in
+/// chunks, so only the matrix itself has to fit into memory. The source of the matrix is set
+/// to the path.
+///
+/// # Arguments
+///
+/// * `path` - The path of the Parquet file.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::columnar::read_distance_mat_parquet;
+/// use std::path::Path;
+///
+/// assert!(read_distance_mat_parquet(Path::new("does_not_exist.parquet")).is_err());
+/// ```
+pub fn read_distance_mat_parquet(path: &Path) -> Result<DistanceMat, ColumnarError> {
+    let reader = ParquetRecordBatchReaderBuilder::try_new(fs::File::open(path)?)?
+        .with_batch_size(CHUNK_SIZE)
+        .build()?;
+    Ok(distance_mat_from_record_batches(reader)?.with_source(&path.display().to_string()))
+}
+
+/// Read the coordinates of the nodes from a Parquet file with an `x`- and a `y`-column.
+///
+/// # Arguments
+///
+/// * `path` - The path of the Parquet file.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::columnar::read_coordinates_parquet;
+/// use std::path::Path;
+///
+/// assert!(read_coordinates_parquet(Path::new("does_not_exist.parquet")).is_err());
+/// ```
+pub fn read_coordinates_parquet(path: &Path) -> Result<Vec<(f64, f64)>, ColumnarError> {
+    let reader = ParquetRecordBatchReaderBuilder::try_new(fs::File::open(path)?)?
+        .with_batch_size(CHUNK_SIZE)
+        .build()?;
+    coordinates_from_record_batches(reader)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::ArrayRef;
+    use parquet::arrow::ArrowWriter;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    fn write_parquet(name: &str, columns: Vec<(&str, Vec<f64>)>) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("genetic_algorithm_tsp_{}.parquet", name));
+        let batch = RecordBatch::try_from_iter(
+            columns
+                .into_iter()
+                .map(|(name, values)| (name, Arc::new(Float64Array::from(values)) as ArrayRef)),
+        )
+        .unwrap();
+        let mut writer =
+            ArrowWriter::try_new(fs::File::create(&path).unwrap(), batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        path
+    }
+    #[test]
+    fn read_distance_mat() {
+        let path = write_parquet(
+            "read_distance_mat",
+            vec![(
+                DISTANCE_COLUMN,
+                vec![0.0, 1.0, 2.0, 1.0, 0.0, 3.0, 2.0, 3.0, 0.0],
+            )],
+        );
+        let distance_mat = read_distance_mat_parquet(&path).unwrap();
+        assert_eq!(distance_mat.n_units(), 3);
+        assert_eq!(distance_mat.get_cost(&[0, 1, 2]), 6.0);
+        assert_eq!(
+            distance_mat.metadata().source,
+            Some(path.display().to_string())
+        );
+    }
+    #[test]
+    fn read_coordinates() {
+        let path = write_parquet(
+            "read_coordinates",
+            vec![
+                (X_COLUMN, vec![0.0, 3.0, 3.0]),
+                (Y_COLUMN, vec![0.0, 0.0, 4.0]),
+            ],
+        );
+        let coordinates = read_coordinates_parquet(&path).unwrap();
+        assert_eq!(
+            DistanceMat::from_coordinates(&coordinates).get_cost(&[0, 1, 2]),
+            12.0
+        );
+    }
+    #[test]
+    fn not_square() {
+        let path = write_parquet("not_square", vec![(DISTANCE_COLUMN, vec![0.0, 1.0, 2.0])]);
+        assert!(matches!(
+            read_distance_mat_parquet(&path),
+            Err(ColumnarError::NotSquare { n_entries: 3 })
+        ));
+    }
+    #[test]
+    fn missing_column() {
+        let path = write_parquet("missing_column", vec![("distances", vec![0.0])]);
+        assert!(matches!(
+            read_distance_mat_parquet(&path),
+            Err(ColumnarError::InvalidColumn(_))
+        ));
+    }
+}
@@ -0,0 +1,106 @@
+#[cfg(feature = "std")]
+pub(crate) use std::collections::{hash_set, HashMap, HashSet};
+
+#[cfg(not(feature = "std"))]
+pub(crate) use hashbrown::hash_set;
+
+/// The hasher of the hash sets whose order has to be the same in every run, e.g. the routes of
+/// `Routes`, and of the fingerprint of a `DistanceMat`: the xx hash, or the fixed foldhash
+/// without the `std` feature, as `fasthash` needs `std`. Both don't depend on a random seed, but
+/// they hash differently, so fingerprints and orders differ between builds with and without
+/// the `std` feature.
+#[cfg(feature = "std")]
+pub(crate) type FixedState = fasthash_fork::xx::Hash64;
+
+/// The hasher of the hash sets whose order has to be the same in every run, see the `std`
+/// variant.
+#[cfg(not(feature = "std"))]
+pub(crate) type FixedState = foldhash::fast::FixedState;
+
+/// A hash map of `hashbrown`, which hashes with the `FixedState` unless another hasher is given,
+/// because `core` has no random hasher. Create it with `default`, like the hash maps of `std`.
+#[cfg(not(feature = "std"))]
+pub(crate) type HashMap<K, V, S = FixedState> = hashbrown::HashMap<K, V, S>;
+
+/// A hash set of `hashbrown`, which hashes with the `FixedState` unless another hasher is given,
+/// because `core` has no random hasher. Create it with `default`, like the hash sets of `std`.
+#[cfg(not(feature = "std"))]
+pub(crate) type HashSet<T, S = FixedState> = hashbrown::HashSet<T, S>;
+
+/// The float methods of `std` that `core` lacks, computed with `libm` if the crate is `no_std`. Import it next to the calls, so that they read the same in both builds.
+#[cfg(not(any(feature = "std", test)))]
+pub(crate) trait Float {
+    fn sqrt(self) -> Self;
+    fn hypot(self, other: Self) -> Self;
+    fn powi(self, n: i32) -> Self;
+    fn powf(self, n: Self) -> Self;
+    fn exp(self) -> Self;
+    fn ln(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn tan(self) -> Self;
+    fn asin(self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+    fn floor(self) -> Self;
+    fn ceil(self) -> Self;
+    fn round(self) -> Self;
+    fn fract(self) -> Self;
+    fn rem_euclid(self, rhs: Self) -> Self;
+}
+
+#[cfg(not(any(feature = "std", test)))]
+impl Float for f64 {
+    fn sqrt(self) -> Self {
+        libm::sqrt(self)
+    }
+    fn hypot(self, other: Self) -> Self {
+        libm::hypot(self, other)
+    }
+    fn powi(self, n: i32) -> Self {
+        libm::pow(self, n.into())
+    }
+    fn powf(self, n: Self) -> Self {
+        libm::pow(self, n)
+    }
+    fn exp(self) -> Self {
+        libm::exp(self)
+    }
+    fn ln(self) -> Self {
+        libm::log(self)
+    }
+    fn sin(self) -> Self {
+        libm::sin(self)
+    }
+    fn cos(self) -> Self {
+        libm::cos(self)
+    }
+    fn tan(self) -> Self {
+        libm::tan(self)
+    }
+    fn asin(self) -> Self {
+        libm::asin(self)
+    }
+    fn atan2(self, other: Self) -> Self {
+        libm::atan2(self, other)
+    }
+    fn floor(self) -> Self {
+        libm::floor(self)
+    }
+    fn ceil(self) -> Self {
+        libm::ceil(self)
+    }
+    fn round(self) -> Self {
+        libm::round(self)
+    }
+    fn fract(self) -> Self {
+        self - libm::trunc(self)
+    }
+    fn rem_euclid(self, rhs: Self) -> Self {
+        let remainder = self % rhs;
+        if remainder < 0.0 {
+            remainder + libm::fabs(rhs)
+        } else {
+            remainder
+        }
+    }
+}
@@ -0,0 +1,534 @@
+use crate::distance_mat::DistanceMat;
+use crate::route::Route;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+
+/// A side constraint of a constrained TSP variant, e.g. that some nodes have to be visited before
+/// others. Instead of adding a penalty to the cost, whose weight would have to be tuned, routes
+/// are compared lexicographically: the route with the smaller violation is fitter, and only
+/// routes with the same violation are compared by their fitness, see `compare_lexicographic`.
+pub trait Constraint: fmt::Debug + Sync {
+    /// How much a route violates the constraint, 0.0 if it satisfies the constraint.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The route that should be checked.
+    /// * `distance_mat` - The distance matrix of the instance.
+    ///
+    fn violation(&self, route: &Route, distance_mat: &DistanceMat) -> f64;
+    /// Does a route satisfy the constraint?
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The route that should be checked.
+    /// * `distance_mat` - The distance matrix of the instance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::constraint::{Constraint, Precedence};
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let pickup_before_delivery = Precedence::new(vec![(2, 1)]);
+    /// assert!(pickup_before_delivery.is_feasible(&Route::new(vec![0, 2, 1]), &distance_matrix));
+    /// ```
+    fn is_feasible(&self, route: &Route, distance_mat: &DistanceMat) -> bool {
+        self.violation(route, distance_mat) <= 0.0
+    }
+    /// Try to lower the violation of a route with a cheap local change. The constrained
+    /// evolution repairs every offspring before the selection, by default routes are kept as
+    /// they are.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The route that should be repaired.
+    /// * `distance_mat` - The distance matrix of the instance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::constraint::{Constraint, Precedence};
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let route = Route::new(vec![0, 1, 2]);
+    /// assert_eq!(Precedence::new(vec![(2, 1)]).repair(route.clone(), &distance_matrix), route);
+    /// ```
+    fn repair(&self, route: Route, _distance_mat: &DistanceMat) -> Route {
+        route
+    }
+    /// How many positions at the start and at the end of every route the constraint fixes.
+    /// The crossover and mutation operators of the constrained evolution leave these positions
+    /// in place and only reorder the nodes between them. By default no position is fixed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::constraint::{Constraint, Precedence};
+    ///
+    /// assert_eq!(Precedence::new(vec![(2, 1)]).fixed_ends(), (0, 0));
+    /// ```
+    fn fixed_ends(&self) -> (usize, usize) {
+        (0, 0)
+    }
+}
+
+/// Nodes that have to be visited before other nodes, e.g. a pickup before its delivery. The
+/// violation of a route is the number of pairs that it visits in the wrong order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Precedence {
+    /// The pairs `(before, after)` of nodes, where `before` has to be visited before `after`.
+    pairs: Vec<(usize, usize)>,
+}
+impl Precedence {
+    /// Create the precedence constraint.
+    ///
+    /// # Arguments
+    ///
+    /// * `pairs` - The pairs `(before, after)` of nodes, where `before` has to be visited
+    ///   before `after`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::constraint::Precedence;
+    ///
+    /// let precedence = Precedence::new(vec![(2, 1), (3, 4)]);
+    /// ```
+    pub fn new(pairs: Vec<(usize, usize)>) -> Self {
+        Precedence { pairs }
+    }
+}
+impl Constraint for Precedence {
+    /// The number of pairs that are visited in the wrong order. A pair with a node that is not
+    /// part of the route counts as violated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::constraint::{Constraint, Precedence};
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let precedence = Precedence::new(vec![(2, 1), (0, 1)]);
+    /// assert_eq!(precedence.violation(&Route::new(vec![1, 2, 0]), &distance_matrix), 2.0);
+    /// ```
+    fn violation(&self, route: &Route, _distance_mat: &DistanceMat) -> f64 {
+        let mut positions = vec![usize::MAX; route.get_n_nodes()];
+        for (position, node) in route.indexes.iter().enumerate() {
+            if let Some(node_position) = positions.get_mut(*node) {
+                *node_position = position;
+            }
+        }
+        let position = |node: usize| positions.get(node).copied().unwrap_or(usize::MAX);
+        self.pairs
+            .iter()
+            .filter(|(before, after)| {
+                position(*before) == usize::MAX
+                    || position(*after) == usize::MAX
+                    || position(*before) > position(*after)
+            })
+            .count() as f64
+    }
+}
+
+/// Nodes that have to be visited in the first or in the second half of the tour, e.g. customers
+/// that are only open in the morning or in the afternoon. It is a cheaper approximation of time
+/// windows: a node is in the first half if less than half of the tour lies before it. The
+/// violation of a route is the number of nodes in the wrong half, and misplaced nodes are
+/// repaired by swapping them into their half.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TourHalves {
+    /// The nodes that have to be visited in the first half.
+    first_half: Vec<usize>,
+    /// The nodes that have to be visited in the second half.
+    second_half: Vec<usize>,
+}
+impl TourHalves {
+    /// Create the constraint. A node in both groups can never be placed correctly.
+    ///
+    /// # Arguments
+    ///
+    /// * `first_half` - The nodes that have to be visited in the first half.
+    /// * `second_half` - The nodes that have to be visited in the second half.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::constraint::TourHalves;
+    ///
+    /// let morning_and_afternoon = TourHalves::new(vec![1, 4], vec![2]);
+    /// ```
+    pub fn new(first_half: Vec<usize>, second_half: Vec<usize>) -> Self {
+        TourHalves {
+            first_half,
+            second_half,
+        }
+    }
+    /// For every position of a route, whether its node has to be visited in the first half
+    /// (`Some(true)`), in the second half (`Some(false)`) or anywhere (`None`).
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The route whose nodes should be looked up.
+    ///
+    fn wanted_halves(&self, route: &[usize]) -> Vec<Option<bool>> {
+        route
+            .iter()
+            .map(|node| {
+                if self.first_half.contains(node) {
+                    Some(true)
+                } else if self.second_half.contains(node) {
+                    Some(false)
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+impl Constraint for TourHalves {
+    /// The number of nodes in the wrong half. A node that is not part of the route counts as
+    /// misplaced.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::constraint::{Constraint, TourHalves};
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0; 4]; 4]);
+    /// let constraint = TourHalves::new(vec![3], vec![0]);
+    /// assert_eq!(constraint.violation(&Route::new(vec![0, 1, 2, 3]), &distance_matrix), 2.0);
+    /// assert_eq!(constraint.violation(&Route::new(vec![3, 1, 2, 0]), &distance_matrix), 0.0);
+    /// ```
+    fn violation(&self, route: &Route, _distance_mat: &DistanceMat) -> f64 {
+        let n_nodes = route.get_n_nodes();
+        let n_placed = self
+            .wanted_halves(&route.indexes)
+            .iter()
+            .enumerate()
+            .filter(|(position, wanted)| **wanted == Some(position * 2 < n_nodes))
+            .count();
+        (self.first_half.len() + self.second_half.len() - n_placed) as f64
+    }
+    /// Swap misplaced nodes into their half: first with a misplaced node of the other group,
+    /// then with a node that can be visited anywhere. The order of all other nodes is kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::constraint::{Constraint, TourHalves};
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0; 4]; 4]);
+    /// let constraint = TourHalves::new(vec![3], vec![0]);
+    /// assert_eq!(
+    ///     constraint.repair(Route::new(vec![0, 1, 2, 3]), &distance_matrix),
+    ///     Route::new(vec![3, 1, 2, 0])
+    /// );
+    /// ```
+    fn repair(&self, route: Route, _distance_mat: &DistanceMat) -> Route {
+        let mut indexes = route.indexes;
+        let n_nodes = indexes.len();
+        let wanted = self.wanted_halves(&indexes);
+        let positions = |predicate: &dyn Fn(usize, Option<bool>) -> bool| {
+            (0..n_nodes)
+                .filter(|position| predicate(*position, wanted[*position]))
+                .collect::<Vec<usize>>()
+        };
+        let too_late =
+            positions(&|position, wanted| wanted == Some(true) && position * 2 >= n_nodes);
+        let too_early =
+            positions(&|position, wanted| wanted == Some(false) && position * 2 < n_nodes);
+        let free_early = positions(&|position, wanted| wanted.is_none() && position * 2 < n_nodes);
+        let free_late = positions(&|position, wanted| wanted.is_none() && position * 2 >= n_nodes);
+        let mut free_early = free_early.into_iter();
+        let mut free_late = free_late.into_iter();
+        let mut too_early = too_early.into_iter();
+        for late in too_late {
+            match too_early.next().or_else(|| free_early.next()) {
+                Some(early) => indexes.swap(early, late),
+                None => break,
+            }
+        }
+        for early in too_early {
+            match free_late.next() {
+                Some(late) => indexes.swap(early, late),
+                None => break,
+            }
+        }
+        Route { indexes }
+    }
+}
+
+/// Nodes that every route has to start or end with, e.g. a depot that starts the tour or a
+/// different depot at which an open path ends. The crossover and mutation operators of the
+/// constrained evolution keep the first and last positions of their parents, so offspring of
+/// routes with the right endpoints always have them too. Other routes, e.g. of a random initial
+/// population, are repaired by rotating the start node to the front, which keeps the cost of a
+/// round-trip, and by moving the end node to the back. The violation of a route is the number
+/// of endpoints that are wrong.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FixedEndpoints {
+    /// The node every route has to start with, if any.
+    start: Option<usize>,
+    /// The node every route has to end with, if any.
+    end: Option<usize>,
+}
+impl FixedEndpoints {
+    /// Create the constraint. A route of more than one node can't start and end with the same
+    /// node.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The node every route has to start with, `None` for any node.
+    /// * `end` - The node every route has to end with, `None` for any node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::constraint::FixedEndpoints;
+    ///
+    /// let depot_to_depot = FixedEndpoints::new(Some(0), Some(4));
+    /// ```
+    pub fn new(start: Option<usize>, end: Option<usize>) -> Self {
+        FixedEndpoints { start, end }
+    }
+    /// Create the constraint that every route starts with `node`, e.g. the depot.
+    ///
+    /// # Arguments
+    ///
+    /// * `node` - The node every route has to start with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::constraint::FixedEndpoints;
+    ///
+    /// assert_eq!(FixedEndpoints::start_at(0), FixedEndpoints::new(Some(0), None));
+    /// ```
+    pub fn start_at(node: usize) -> Self {
+        FixedEndpoints::new(Some(node), None)
+    }
+}
+impl Constraint for FixedEndpoints {
+    /// The number of fixed endpoints that the route doesn't have, 0.0 to 2.0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::constraint::{Constraint, FixedEndpoints};
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let constraint = FixedEndpoints::new(Some(0), Some(2));
+    /// assert_eq!(constraint.violation(&Route::new(vec![0, 1, 2]), &distance_matrix), 0.0);
+    /// assert_eq!(constraint.violation(&Route::new(vec![2, 1, 0]), &distance_matrix), 2.0);
+    /// ```
+    fn violation(&self, route: &Route, _distance_mat: &DistanceMat) -> f64 {
+        let is_wrong = |wanted: Option<usize>, actual: Option<&usize>| match wanted {
+            Some(node) => actual != Some(&node),
+            None => false,
+        };
+        (is_wrong(self.start, route.indexes.first()) as usize
+            + is_wrong(self.end, route.indexes.last()) as usize) as f64
+    }
+    /// Rotate the start node to the front and move the end node to the back, e.g. to give the
+    /// routes of the initial population their endpoints. All other nodes keep their order
+    /// around the tour.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::constraint::{Constraint, FixedEndpoints};
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0; 5]; 5]);
+    /// let constraint = FixedEndpoints::new(Some(0), Some(4));
+    /// assert_eq!(
+    ///     constraint.repair(Route::new(vec![2, 4, 0, 3, 1]), &distance_matrix),
+    ///     Route::new(vec![0, 3, 1, 2, 4])
+    /// );
+    /// ```
+    fn repair(&self, route: Route, _distance_mat: &DistanceMat) -> Route {
+        let mut indexes = route.indexes;
+        if let Some(start) = self.start {
+            if let Some(position) = indexes.iter().position(|node| *node == start) {
+                indexes.rotate_left(position);
+            }
+        }
+        if let Some(end) = self.end {
+            // The start node keeps its place if both endpoints are the same node.
+            let first_movable = usize::from(self.start.is_some());
+            if let Some(position) = indexes
+                .iter()
+                .skip(first_movable)
+                .position(|node| *node == end)
+            {
+                let node = indexes.remove(position + first_movable);
+                indexes.push(node);
+            }
+        }
+        Route { indexes }
+    }
+    /// One fixed position at the start if there is a start node, and one at the end if there
+    /// is a different end node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::constraint::{Constraint, FixedEndpoints};
+    ///
+    /// assert_eq!(FixedEndpoints::new(Some(0), Some(4)).fixed_ends(), (1, 1));
+    /// assert_eq!(FixedEndpoints::new(None, Some(4)).fixed_ends(), (0, 1));
+    /// ```
+    fn fixed_ends(&self) -> (usize, usize) {
+        (
+            usize::from(self.start.is_some()),
+            usize::from(self.end.is_some() && self.end != self.start),
+        )
+    }
+}
+
+/// Compare two routes lexicographically by their `(violation, fitness)`: the route with the
+/// smaller violation comes first, and of routes with the same violation the fitter one. Sorting
+/// with this comparison puts the best route first.
+///
+/// # Arguments
+///
+/// * `a` - The violation and the fitness of the first route.
+/// * `b` - The violation and the fitness of the second route.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::constraint::compare_lexicographic;
+/// use std::cmp::Ordering;
+///
+/// // Feasibility first, even if the infeasible route is much shorter.
+/// assert_eq!(compare_lexicographic((0.0, -100.0), (1.0, -5.0)), Ordering::Less);
+/// assert_eq!(compare_lexicographic((0.0, -5.0), (0.0, -100.0)), Ordering::Less);
+/// ```
+pub fn compare_lexicographic(a: (f64, f64), b: (f64, f64)) -> Ordering {
+    a.0.partial_cmp(&b.0)
+        .unwrap_or(Ordering::Equal)
+        .then_with(|| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::routes::{evolve_population_constrained, Routes};
+    use crate::test_utils::{test_dist_mat, valid_permutation};
+
+    #[test]
+    fn precedence_violations() {
+        let precedence = Precedence::new(vec![(0, 1), (1, 2)]);
+        let distance_mat = test_dist_mat();
+        assert_eq!(
+            precedence.violation(&Route::new(vec![0, 1, 2]), &distance_mat),
+            0.0
+        );
+        assert_eq!(
+            precedence.violation(&Route::new(vec![2, 1, 0]), &distance_mat),
+            2.0
+        );
+        // Node 2 is not part of the route.
+        assert_eq!(
+            precedence.violation(&Route::new(vec![0, 1]), &distance_mat),
+            1.0
+        );
+    }
+    #[test]
+    fn tour_halves_repair() {
+        let distance_mat = DistanceMat::new(vec![vec![0.0; 7]; 7]);
+        let constraint = TourHalves::new(vec![5, 6], vec![0, 1]);
+        // Positions 0 to 3 are the first half.
+        let route = Route::new(vec![0, 1, 2, 3, 4, 5, 6]);
+        assert_eq!(constraint.violation(&route, &distance_mat), 4.0);
+        let repaired = constraint.repair(route.clone(), &distance_mat);
+        valid_permutation(&route.indexes, &repaired.indexes);
+        assert!(constraint.is_feasible(&repaired, &distance_mat));
+        // Only misplaced nodes are moved.
+        assert_eq!(repaired, Route::new(vec![5, 6, 2, 3, 4, 0, 1]));
+    }
+    #[test]
+    fn tour_halves_repair_with_free_nodes() {
+        let distance_mat = DistanceMat::new(vec![vec![0.0; 6]; 6]);
+        let constraint = TourHalves::new(vec![4, 5], vec![]);
+        let repaired = constraint.repair(Route::new(vec![0, 1, 2, 3, 4, 5]), &distance_mat);
+        assert!(constraint.is_feasible(&repaired, &distance_mat));
+        // Too many nodes for the first half can not all be placed.
+        let constraint = TourHalves::new(vec![2, 3, 4, 5], vec![]);
+        let repaired = constraint.repair(Route::new(vec![0, 1, 2, 3, 4, 5]), &distance_mat);
+        assert_eq!(constraint.violation(&repaired, &distance_mat), 1.0);
+    }
+    #[test]
+    fn fixed_endpoints_repair() {
+        let distance_mat = DistanceMat::new(vec![vec![0.0; 6]; 6]);
+        let constraint = FixedEndpoints::start_at(3);
+        let route = Route::new(vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(constraint.violation(&route, &distance_mat), 1.0);
+        let repaired = constraint.repair(route.clone(), &distance_mat);
+        // A rotation is the same round-trip.
+        assert_eq!(repaired, Route::new(vec![3, 4, 5, 0, 1, 2]));
+        assert!(constraint.is_feasible(&repaired, &distance_mat));
+        let constraint = FixedEndpoints::new(None, Some(0));
+        let repaired = constraint.repair(route.clone(), &distance_mat);
+        assert_eq!(repaired, Route::new(vec![1, 2, 3, 4, 5, 0]));
+        // Feasible routes are not changed.
+        let constraint = FixedEndpoints::new(Some(1), Some(0));
+        assert_eq!(constraint.repair(repaired.clone(), &distance_mat), repaired);
+        // Both endpoints can't be the same node.
+        let constraint = FixedEndpoints::new(Some(2), Some(2));
+        let repaired = constraint.repair(route.clone(), &distance_mat);
+        valid_permutation(&route.indexes, &repaired.indexes);
+        assert_eq!(constraint.violation(&repaired, &distance_mat), 1.0);
+    }
+    #[cfg(feature = "std")]
+    #[test]
+    fn fixed_endpoints_survive_evolution() {
+        let distance_mat = DistanceMat::from_coordinates(&[
+            (0.0, 0.0),
+            (1.0, 3.0),
+            (2.0, 1.0),
+            (3.0, 4.0),
+            (4.0, 0.0),
+        ]);
+        let constraint = FixedEndpoints::new(Some(2), Some(4));
+        let population = evolve_population_constrained(
+            Routes::random(6, 5),
+            20,
+            6,
+            &distance_mat,
+            0,
+            &constraint,
+        );
+        assert!(population
+            .iter()
+            .all(|route| constraint.is_feasible(route, &distance_mat)));
+    }
+    #[test]
+    fn lexicographic_order() {
+        let mut routes = vec![(1.0, -3.0), (0.0, -9.0), (1.0, -1.0), (0.0, -4.0)];
+        routes.sort_by(|a, b| compare_lexicographic(*a, *b));
+        assert_eq!(
+            routes,
+            vec![(0.0, -4.0), (0.0, -9.0), (1.0, -1.0), (1.0, -3.0)]
+        );
+    }
+}
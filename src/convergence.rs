@@ -0,0 +1,452 @@
+use crate::evolution::{Evolution, StopReason};
+use crate::generation::GenerationStats;
+use crate::route::Route;
+use crate::routes::Routes;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// The undirected edges of the round-trip through `indexes`, including the edge back to the
+/// start. Every edge is stored with the smaller node first.
+///
+/// # Arguments
+///
+/// * `indexes` - The order in which the nodes are visited.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::convergence::tour_edges;
+///
+/// assert_eq!(tour_edges(&[2, 0, 1]), vec![(0, 2), (0, 1), (1, 2)]);
+/// ```
+pub fn tour_edges(indexes: &[usize]) -> Vec<(usize, usize)> {
+    if indexes.len() < 2 {
+        return Vec::new();
+    }
+    indexes
+        .iter()
+        .zip(indexes.iter().cycle().skip(1))
+        .map(|(from, to)| (*from.min(to), *from.max(to)))
+        .collect()
+}
+
+/// Tracks for how many consecutive generations every edge of the best route has been part of
+/// the best route. A population has converged when most edges of its best route are stable,
+/// even if the best fitness still changes slightly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EdgeStability {
+    /// How many consecutive generations an edge has to be in the best route to be stable.
+    window: usize,
+    /// The number of consecutive generations every edge of the last best route was part of
+    /// the best route.
+    edge_ages: HashMap<(usize, usize), usize>,
+}
+impl EdgeStability {
+    /// Create a tracker that considers an edge stable once it was part of the best route for
+    /// `window` consecutive generations.
+    ///
+    /// # Arguments
+    ///
+    /// * `window` - How many consecutive generations an edge has to be in the best route.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::convergence::EdgeStability;
+    ///
+    /// let edge_stability = EdgeStability::new(5);
+    /// ```
+    pub fn new(window: usize) -> Self {
+        EdgeStability {
+            window,
+            edge_ages: HashMap::new(),
+        }
+    }
+    /// Record the best route of the next generation and return the fraction of its edges that
+    /// are stable.
+    ///
+    /// # Arguments
+    ///
+    /// * `best_route` - The best route of the current generation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::convergence::EdgeStability;
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let mut edge_stability = EdgeStability::new(2);
+    /// assert_eq!(edge_stability.record(&Route::new(vec![0, 1, 2, 3])), 0.0);
+    /// // Only the edges (1, 2) and (0, 3) have been in both routes.
+    /// assert_eq!(edge_stability.record(&Route::new(vec![0, 2, 1, 3])), 0.5);
+    /// ```
+    pub fn record(&mut self, best_route: &Route) -> f64 {
+        let edges = tour_edges(&best_route.indexes);
+        self.edge_ages = edges
+            .iter()
+            .map(|edge| (*edge, self.edge_ages.get(edge).map_or(1, |age| age + 1)))
+            .collect();
+        self.stable_fraction()
+    }
+    /// The fraction of the edges of the last recorded best route that are stable. Is 0 if no
+    /// route has been recorded yet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::convergence::EdgeStability;
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let mut edge_stability = EdgeStability::new(1);
+    /// edge_stability.record(&Route::new(vec![0, 1, 2]));
+    /// assert_eq!(edge_stability.stable_fraction(), 1.0);
+    /// ```
+    pub fn stable_fraction(&self) -> f64 {
+        if self.edge_ages.is_empty() {
+            return 0.0;
+        }
+        self.edge_ages
+            .values()
+            .filter(|age| **age >= self.window)
+            .count() as f64
+            / self.edge_ages.len() as f64
+    }
+}
+
+/// The course of an evolution, one entry per generation. It is recorded by
+/// `evolve_population_until_converged` and `generation::evolve_population_with_stats`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct History {
+    /// The statistics of the fitnesses and the diversity of every generation.
+    pub generations: Vec<GenerationStats>,
+    /// The fraction of stable edges in the best route of every generation, empty if the
+    /// stability of the edges wasn't tracked.
+    pub stable_edge_fractions: Vec<f64>,
+}
+impl History {
+    /// The number of recorded generations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::convergence::History;
+    ///
+    /// assert_eq!(History::default().n_generations(), 0);
+    /// ```
+    pub fn n_generations(&self) -> usize {
+        self.generations.len()
+    }
+    /// The fitness of the best route of every generation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::convergence::History;
+    ///
+    /// assert!(History::default().best_fitnesses().is_empty());
+    /// ```
+    pub fn best_fitnesses(&self) -> Vec<f64> {
+        self.generations
+            .iter()
+            .map(|stats| stats.best_fitness)
+            .collect()
+    }
+}
+
+/// Evolve a stepwise evolution generation by generation with its configuration until at least
+/// `min_stable_fraction` of the edges of the best route have been part of the best route for
+/// `window` consecutive generations, or all of its generations are evolved. The best fitness
+/// and the fraction of stable edges of every generation are returned as `History`, together
+/// with the `StopReason`, the final population is the one of the evolution.
+///
+/// # Arguments
+///
+/// * `evolution` - A stepwise evolution, see `Evolution::start`.
+/// * `window` - How many consecutive generations an edge has to be in the best route.
+/// * `min_stable_fraction` - Which fraction of the edges has to be stable to stop.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::convergence::evolve_population_until_converged;
+/// use genetic_algorithm_tsp::evolution::{Evolution, EvolutionConfig, StopReason};
+/// use genetic_algorithm_tsp::routes::Routes;
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+///
+/// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+/// let config = EvolutionConfig::new().with_n_generations(100).with_size_generation(5);
+/// let mut evolution = Evolution::new(&distance_matrix, config).start(Routes::random(2, 3), 42);
+/// let (history, stop_reason) = evolve_population_until_converged(&mut evolution, 3, 1.0);
+/// assert!(history.n_generations() < 100);
+/// assert_eq!(stop_reason, StopReason::Converged);
+/// ```
+pub fn evolve_population_until_converged(
+    evolution: &mut Evolution<'_>,
+    window: usize,
+    min_stable_fraction: f64,
+) -> (History, StopReason) {
+    let distance_matrix = evolution.distance_mat();
+    let mut edge_stability = EdgeStability::new(window);
+    let mut history = History::default();
+    let mut stop_reason = StopReason::GenerationsExhausted;
+    while !evolution.is_finished() {
+        let start = Instant::now();
+        let population = evolution.evolve(1);
+        let elapsed = start.elapsed();
+        let (best_route, mut stats) = match (
+            population.get_n_fittest(1, distance_matrix).pop(),
+            GenerationStats::new(population, distance_matrix),
+        ) {
+            (Some(best_route), Some(stats)) => (best_route, stats),
+            _ => break,
+        };
+        stats.elapsed_ms = elapsed.as_secs_f64() * 1000.0;
+        let stable_fraction = edge_stability.record(&best_route);
+        history.generations.push(stats);
+        history.stable_edge_fractions.push(stable_fraction);
+        if stable_fraction >= min_stable_fraction {
+            stop_reason = StopReason::Converged;
+            break;
+        }
+    }
+    (history, stop_reason)
+}
+
+/// When an evolution should stop before its last generation: when the best fitness has not
+/// improved by more than `epsilon` for `patience` generations, or when the time budget is
+/// exhausted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EarlyStopping {
+    /// By how much the best fitness has to increase to count as improvement.
+    pub epsilon: f64,
+    /// After how many generations without improvement the evolution stops.
+    pub patience: usize,
+    /// The wall-clock time after which no further generation is started, `None` for no limit.
+    pub time_budget: Option<Duration>,
+}
+impl EarlyStopping {
+    /// Stop when the best fitness has not improved by more than `epsilon` for `patience`
+    /// generations.
+    ///
+    /// # Arguments
+    ///
+    /// * `epsilon` - By how much the best fitness has to increase to count as improvement.
+    /// * `patience` - After how many generations without improvement the evolution stops.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::convergence::EarlyStopping;
+    ///
+    /// let early_stopping = EarlyStopping::new(1e-6, 50);
+    /// ```
+    pub fn new(epsilon: f64, patience: usize) -> Self {
+        EarlyStopping {
+            epsilon,
+            patience,
+            time_budget: None,
+        }
+    }
+    /// Additionally stop when `time_budget` has passed since the evolution started.
+    ///
+    /// # Arguments
+    ///
+    /// * `time_budget` - The wall-clock time after which no further generation is started.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::convergence::EarlyStopping;
+    /// use std::time::Duration;
+    ///
+    /// let early_stopping = EarlyStopping::new(1e-6, 50).with_time_budget(Duration::from_secs(2));
+    /// ```
+    pub fn with_time_budget(mut self, time_budget: Duration) -> Self {
+        self.time_budget = Some(time_budget);
+        self
+    }
+}
+
+/// Evolve a stepwise evolution generation by generation with its configuration until all of
+/// its generations are evolved, but stop early as set by `early_stopping`. The number of
+/// generations that were actually evolved is returned together with the `StopReason`, the
+/// final population is the one of the evolution.
+///
+/// # Arguments
+///
+/// * `evolution` - A stepwise evolution, see `Evolution::start`.
+/// * `early_stopping` - When the evolution should stop before its last generation.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::convergence::{EarlyStopping, evolve_population_with_early_stopping};
+/// use genetic_algorithm_tsp::evolution::{Evolution, EvolutionConfig, StopReason};
+/// use genetic_algorithm_tsp::routes::Routes;
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+///
+/// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+/// let config = EvolutionConfig::new().with_n_generations(100).with_size_generation(5);
+/// let mut evolution = Evolution::new(&distance_matrix, config).start(Routes::random(2, 3), 42);
+/// let (n_generations, stop_reason) =
+///     evolve_population_with_early_stopping(&mut evolution, &EarlyStopping::new(0.0, 5));
+/// // All round-trips through three nodes have the same length.
+/// assert_eq!(n_generations, 5);
+/// assert_eq!(stop_reason, StopReason::Converged);
+/// ```
+pub fn evolve_population_with_early_stopping(
+    evolution: &mut Evolution<'_>,
+    early_stopping: &EarlyStopping,
+) -> (usize, StopReason) {
+    let start = Instant::now();
+    let distance_matrix = evolution.distance_mat();
+    let best_fitness = |population: &Routes| {
+        population
+            .get_n_fittest(1, distance_matrix)
+            .first()
+            .map_or(f64::NEG_INFINITY, |route| route.fitness(distance_matrix))
+    };
+    let mut best_so_far = best_fitness(evolution.population());
+    let mut generations_without_improvement = 0;
+    let mut n_generations = 0;
+    let stop_reason = loop {
+        if evolution.is_finished() {
+            break StopReason::GenerationsExhausted;
+        }
+        if generations_without_improvement >= early_stopping.patience {
+            break StopReason::Converged;
+        }
+        if early_stopping
+            .time_budget
+            .is_some_and(|time_budget| start.elapsed() >= time_budget)
+        {
+            break StopReason::TimeBudgetExhausted;
+        }
+        let best = best_fitness(evolution.evolve(1));
+        n_generations += 1;
+        if best > best_so_far + early_stopping.epsilon {
+            best_so_far = best;
+            generations_without_improvement = 0;
+        } else {
+            generations_without_improvement += 1;
+        }
+    };
+    (n_generations, stop_reason)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance_mat::DistanceMat;
+    use crate::evolution::EvolutionConfig;
+    use crate::test_utils::test_dist_mat;
+    #[test]
+    fn edges_of_short_routes() {
+        assert_eq!(tour_edges(&[]), vec![]);
+        assert_eq!(tour_edges(&[0]), vec![]);
+        assert_eq!(tour_edges(&[1, 0]), vec![(0, 1), (0, 1)]);
+    }
+    #[test]
+    fn edges_are_reset() {
+        let mut edge_stability = EdgeStability::new(2);
+        edge_stability.record(&Route::new(vec![0, 1, 2, 3, 4]));
+        edge_stability.record(&Route::new(vec![0, 1, 2, 3, 4]));
+        assert_eq!(edge_stability.stable_fraction(), 1.0);
+        // All edges changed, so none of them is stable anymore.
+        assert_eq!(edge_stability.record(&Route::new(vec![0, 2, 4, 1, 3])), 0.0);
+    }
+    #[test]
+    fn stops_without_improvement() {
+        let distance_mat = test_dist_mat();
+        let config = EvolutionConfig::new()
+            .with_n_generations(20)
+            .with_size_generation(3);
+        let mut evolution = Evolution::new(&distance_mat, config).start(Routes::random(3, 3), 0);
+        let (n_generations, stop_reason) =
+            evolve_population_with_early_stopping(&mut evolution, &EarlyStopping::new(0.0, 3));
+        assert_eq!(evolution.population().len(), 3);
+        assert_eq!(n_generations, 3);
+        assert_eq!(stop_reason, StopReason::Converged);
+    }
+    #[test]
+    fn stops_at_max_generations() {
+        let distance_mat = DistanceMat::new(vec![
+            vec![0.0, 1.0, 9.0, 9.0, 1.0],
+            vec![1.0, 0.0, 1.0, 9.0, 9.0],
+            vec![9.0, 1.0, 0.0, 1.0, 9.0],
+            vec![9.0, 9.0, 1.0, 0.0, 1.0],
+            vec![1.0, 9.0, 9.0, 1.0, 0.0],
+        ]);
+        let config = EvolutionConfig::new()
+            .with_n_generations(7)
+            .with_size_generation(4);
+        let mut evolution = Evolution::new(&distance_mat, config).start(Routes::random(3, 5), 0);
+        let (n_generations, stop_reason) = evolve_population_with_early_stopping(
+            &mut evolution,
+            &EarlyStopping::new(0.0, usize::MAX),
+        );
+        assert_eq!(n_generations, 7);
+        assert_eq!(stop_reason, StopReason::GenerationsExhausted);
+    }
+    #[test]
+    fn stops_like_the_seeded_evolution() {
+        let distance_mat = DistanceMat::from_coordinates(&[
+            (0.0, 0.0),
+            (3.0, 1.0),
+            (1.0, 4.0),
+            (5.0, 2.0),
+            (2.0, 2.0),
+            (4.0, 5.0),
+            (0.0, 3.0),
+        ]);
+        let config = EvolutionConfig::new()
+            .with_n_generations(50)
+            .with_size_generation(6)
+            .with_mutation_rate(0.2)
+            .with_elitism(1);
+        let initial_population = Routes::random(6, 7);
+        let run = || {
+            let mut evolution =
+                Evolution::new(&distance_mat, config).start(initial_population.clone(), 9);
+            let (n_generations, _) =
+                evolve_population_with_early_stopping(&mut evolution, &EarlyStopping::new(0.0, 5));
+            (n_generations, evolution.population().clone())
+        };
+        let (n_generations, population) = run();
+        assert_eq!(run(), (n_generations, population.clone()));
+        // Without early stopping the same seed evolves the same generations.
+        let mut full_run = Evolution::new(&distance_mat, config).start(initial_population, 9);
+        assert_eq!(full_run.evolve(n_generations), &population);
+    }
+    #[test]
+    fn stops_when_time_budget_is_exhausted() {
+        let distance_mat = test_dist_mat();
+        let initial_population = Routes::random(2, 3);
+        let config = EvolutionConfig::new()
+            .with_n_generations(20)
+            .with_size_generation(4);
+        let mut evolution =
+            Evolution::new(&distance_mat, config).start(initial_population.clone(), 0);
+        let (n_generations, stop_reason) = evolve_population_with_early_stopping(
+            &mut evolution,
+            &EarlyStopping::new(0.0, usize::MAX).with_time_budget(Duration::ZERO),
+        );
+        assert_eq!(n_generations, 0);
+        assert_eq!(stop_reason, StopReason::TimeBudgetExhausted);
+        assert_eq!(evolution.population(), &initial_population);
+    }
+    #[test]
+    fn history_covers_generations() {
+        let distance_mat = test_dist_mat();
+        let config = EvolutionConfig::new()
+            .with_n_generations(20)
+            .with_size_generation(4);
+        let mut evolution = Evolution::new(&distance_mat, config).start(Routes::random(2, 3), 0);
+        let (history, stop_reason) = evolve_population_until_converged(&mut evolution, 100, 1.0);
+        assert_eq!(evolution.population().len(), 4);
+        // With a window of 100 generations, the evolution never converges.
+        assert_eq!(history.n_generations(), 20);
+        assert_eq!(history.stable_edge_fractions.len(), 20);
+        assert_eq!(stop_reason, StopReason::GenerationsExhausted);
+    }
+}
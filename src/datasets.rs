@@ -0,0 +1,264 @@
+use crate::distance_mat::DistanceMat;
+use crate::tsplib::{euclidean_distances, geo_distances};
+
+/// The coordinates of the `berlin52` instance from TSPLIB, 52 locations in Berlin.
+const BERLIN52: [(f64, f64); 52] = [
+    (565.0, 575.0),
+    (25.0, 185.0),
+    (345.0, 750.0),
+    (945.0, 685.0),
+    (845.0, 655.0),
+    (880.0, 660.0),
+    (25.0, 230.0),
+    (525.0, 1000.0),
+    (580.0, 1175.0),
+    (650.0, 1130.0),
+    (1605.0, 620.0),
+    (1220.0, 580.0),
+    (1465.0, 200.0),
+    (1530.0, 5.0),
+    (845.0, 680.0),
+    (725.0, 370.0),
+    (145.0, 665.0),
+    (415.0, 635.0),
+    (510.0, 875.0),
+    (560.0, 365.0),
+    (300.0, 465.0),
+    (520.0, 585.0),
+    (480.0, 415.0),
+    (835.0, 625.0),
+    (975.0, 580.0),
+    (1215.0, 245.0),
+    (1320.0, 315.0),
+    (1250.0, 400.0),
+    (660.0, 180.0),
+    (410.0, 250.0),
+    (420.0, 555.0),
+    (575.0, 665.0),
+    (1150.0, 1160.0),
+    (700.0, 580.0),
+    (685.0, 595.0),
+    (685.0, 610.0),
+    (770.0, 610.0),
+    (795.0, 645.0),
+    (720.0, 635.0),
+    (760.0, 650.0),
+    (475.0, 960.0),
+    (95.0, 260.0),
+    (875.0, 920.0),
+    (700.0, 500.0),
+    (555.0, 815.0),
+    (830.0, 485.0),
+    (1170.0, 65.0),
+    (830.0, 610.0),
+    (605.0, 625.0),
+    (595.0, 360.0),
+    (1340.0, 725.0),
+    (1740.0, 245.0),
+];
+/// The geographical coordinates (`DDD.MM`) of the `burma14` instance from TSPLIB.
+const BURMA14: [(f64, f64); 14] = [
+    (16.47, 96.10),
+    (16.47, 94.44),
+    (20.09, 92.54),
+    (22.39, 93.37),
+    (25.23, 97.24),
+    (22.00, 96.05),
+    (20.47, 97.02),
+    (17.20, 96.29),
+    (16.30, 97.38),
+    (14.05, 98.12),
+    (16.53, 97.38),
+    (21.52, 95.59),
+    (19.41, 97.13),
+    (20.09, 94.55),
+];
+/// The geographical coordinates (`DDD.MM`) of the `ulysses16` instance from TSPLIB, the odyssey
+/// of Ulysses.
+const ULYSSES16: [(f64, f64); 16] = [
+    (38.24, 20.42),
+    (39.57, 26.15),
+    (40.56, 25.32),
+    (36.26, 23.12),
+    (33.48, 10.54),
+    (37.56, 12.19),
+    (38.42, 13.11),
+    (37.52, 20.44),
+    (41.23, 9.10),
+    (41.17, 13.05),
+    (36.08, -5.21),
+    (38.47, 15.13),
+    (38.15, 15.35),
+    (37.51, 15.17),
+    (35.49, 14.32),
+    (39.36, 19.56),
+];
+/// A small, hand-made instance with five nodes.
+const FIVE: [[f64; 5]; 5] = [
+    [0.0, 3.0, 4.0, 2.0, 7.0],
+    [3.0, 0.0, 4.0, 6.0, 3.0],
+    [4.0, 4.0, 0.0, 5.0, 8.0],
+    [2.0, 6.0, 5.0, 0.0, 6.0],
+    [7.0, 3.0, 8.0, 6.0, 0.0],
+];
+
+/// A small, classic TSP instance with a known optimal round-trip length.
+#[derive(Debug)]
+pub struct Dataset {
+    /// The name of the instance, e.g. `berlin52`.
+    pub name: &'static str,
+    /// The length of the optimal round-trip.
+    pub optimum: f64,
+    /// The distances between the nodes of the instance.
+    pub distance_mat: DistanceMat,
+}
+
+/// A hand-made instance with five nodes and an optimal round-trip of length 19.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::datasets::five;
+///
+/// let dataset = five();
+/// assert_eq!(dataset.distance_mat.n_units(), 5);
+/// ```
+pub fn five() -> Dataset {
+    Dataset {
+        name: "five",
+        optimum: 19.0,
+        distance_mat: DistanceMat::new(FIVE.iter().map(|row| row.to_vec()).collect()),
+    }
+}
+
+/// The `burma14` instance from TSPLIB with 14 cities in Burma and an optimal round-trip of
+/// length 3323.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::datasets::burma14;
+///
+/// let dataset = burma14();
+/// assert_eq!(dataset.distance_mat.n_units(), 14);
+/// ```
+pub fn burma14() -> Dataset {
+    Dataset {
+        name: "burma14",
+        optimum: 3323.0,
+        distance_mat: DistanceMat::new(geo_distances(&BURMA14)),
+    }
+}
+
+/// The `ulysses16` instance from TSPLIB with 16 locations of the odyssey of Ulysses and an
+/// optimal round-trip of length 6859.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::datasets::ulysses16;
+///
+/// let dataset = ulysses16();
+/// assert_eq!(dataset.distance_mat.n_units(), 16);
+/// ```
+pub fn ulysses16() -> Dataset {
+    Dataset {
+        name: "ulysses16",
+        optimum: 6859.0,
+        distance_mat: DistanceMat::new(geo_distances(&ULYSSES16)),
+    }
+}
+
+/// The `berlin52` instance from TSPLIB with 52 locations in Berlin and an optimal round-trip
+/// of length 7542.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::datasets::berlin52;
+///
+/// let dataset = berlin52();
+/// assert_eq!(dataset.distance_mat.n_units(), 52);
+/// ```
+pub fn berlin52() -> Dataset {
+    Dataset {
+        name: "berlin52",
+        optimum: 7542.0,
+        distance_mat: DistanceMat::new(euclidean_distances(&BERLIN52)),
+    }
+}
+
+/// Get all embedded instances, ordered by their number of nodes.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::datasets::all;
+///
+/// for dataset in all() {
+///     println!("{}: {} nodes, optimum {}", dataset.name, dataset.distance_mat.n_units(), dataset.optimum);
+/// }
+/// ```
+pub fn all() -> Vec<Dataset> {
+    vec![five(), burma14(), ulysses16(), berlin52()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    /// Convert a TSPLIB tour (1-based) to the indexes of a route.
+    fn zero_based(tour: &[usize]) -> Vec<usize> {
+        tour.iter().map(|node| node - 1).collect()
+    }
+    #[test]
+    fn five_optimal_tour() {
+        let dataset = five();
+        assert_eq!(
+            dataset
+                .distance_mat
+                .get_distance(&zero_based(&[1, 3, 2, 5, 4])),
+            Ok(dataset.optimum)
+        );
+    }
+    #[test]
+    fn burma14_optimal_tour() {
+        let dataset = burma14();
+        let tour = zero_based(&[1, 2, 14, 3, 4, 5, 6, 12, 7, 13, 8, 11, 9, 10]);
+        assert_eq!(
+            dataset.distance_mat.get_distance(&tour),
+            Ok(dataset.optimum)
+        );
+    }
+    #[test]
+    fn ulysses16_optimal_tour() {
+        let dataset = ulysses16();
+        let tour = zero_based(&[1, 14, 13, 12, 7, 6, 15, 5, 11, 9, 10, 16, 3, 2, 4, 8]);
+        assert_eq!(
+            dataset.distance_mat.get_distance(&tour),
+            Ok(dataset.optimum)
+        );
+    }
+    #[test]
+    fn berlin52_optimal_tour() {
+        let dataset = berlin52();
+        let tour = zero_based(&[
+            1, 49, 32, 45, 19, 41, 8, 9, 10, 43, 33, 51, 11, 52, 14, 13, 47, 26, 27, 28, 12, 25, 4,
+            6, 15, 5, 24, 48, 38, 37, 40, 39, 36, 35, 34, 44, 46, 16, 29, 50, 20, 23, 30, 2, 7, 42,
+            21, 17, 3, 18, 31, 22,
+        ]);
+        assert_eq!(
+            dataset.distance_mat.get_distance(&tour),
+            Ok(dataset.optimum)
+        );
+    }
+    #[test]
+    fn all_datasets() {
+        assert_eq!(
+            all()
+                .iter()
+                .map(|dataset| dataset.name)
+                .collect::<Vec<&str>>(),
+            vec!["five", "burma14", "ulysses16", "berlin52"]
+        );
+    }
+}
@@ -1,8 +1,862 @@
+use crate::candidates::CandidateList;
+use crate::compat::FixedState;
+#[cfg(not(any(feature = "std", test)))]
+use crate::compat::Float;
+use crate::lower_bound::OneTree;
+use crate::objective::{Objective, ScenarioAggregation, TieBreaking, TimeWindow, TourEquivalence};
+use crate::projection::{great_circle_distance, Projection};
+use crate::route::{Route, ThreeOptMove};
+#[cfg(feature = "std")]
 use crate::routes;
-/// A representation of a f64 based distance matrix.
+use crate::utils::move_elem;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt;
+use core::hash::{BuildHasher, Hasher};
+#[cfg(feature = "std")]
+use core::sync::atomic::AtomicU64;
+use core::sync::atomic::{AtomicU8, Ordering};
+#[cfg(feature = "std")]
+use std::sync::Mutex;
+
+/// The errors that can occur when a `DistanceMat` is created or a route is evaluated on it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DistanceMatError {
+    /// The route does not contain any node.
+    EmptyRoute,
+    /// The route contains a node that is not part of the distance matrix.
+    IndexOutOfBounds {
+        /// The node that is not part of the distance matrix.
+        index: usize,
+        /// The number of nodes in the distance matrix.
+        n_units: usize,
+    },
+    /// The costs of the matrix are given in other units than expected.
+    UnitMismatch {
+        /// The units that were expected, e.g. the units of a threshold.
+        expected: CostUnit,
+        /// The units of the matrix.
+        actual: CostUnit,
+    },
+    /// A row of the matrix does not have one distance for every node.
+    NotSquare {
+        /// The row that has the wrong length.
+        row: usize,
+        /// The number of distances in the row.
+        length: usize,
+        /// The number of nodes in the distance matrix.
+        n_units: usize,
+    },
+    /// A distance is NaN, infinite or negative.
+    InvalidDistance {
+        /// The node the distance starts at.
+        from: usize,
+        /// The node the distance ends at.
+        to: usize,
+    },
+}
+impl fmt::Display for DistanceMatError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DistanceMatError::EmptyRoute => write!(formatter, "The route is empty"),
+            DistanceMatError::IndexOutOfBounds { index, n_units } => write!(
+                formatter,
+                "Node {} is not part of the distance matrix with {} nodes",
+                index, n_units
+            ),
+            DistanceMatError::UnitMismatch { expected, actual } => write!(
+                formatter,
+                "The distance matrix is given in {} but {} were expected",
+                actual, expected
+            ),
+            DistanceMatError::NotSquare {
+                row,
+                length,
+                n_units,
+            } => write!(
+                formatter,
+                "Row {} has {} distances but the distance matrix has {} nodes",
+                row, length, n_units
+            ),
+            DistanceMatError::InvalidDistance { from, to } => write!(
+                formatter,
+                "The distance from node {} to node {} is not a finite, non-negative number",
+                from, to
+            ),
+        }
+    }
+}
+impl Error for DistanceMatError {}
+/// The units in which the costs of a `DistanceMat` are given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CostUnit {
+    /// Distances in meters.
+    Meters,
+    /// Distances in kilometers.
+    Kilometers,
+    /// Distances in miles.
+    Miles,
+    /// Travel times in seconds.
+    Seconds,
+    /// Travel times in minutes.
+    Minutes,
+    /// Travel times in hours.
+    Hours,
+}
+impl fmt::Display for CostUnit {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            CostUnit::Meters => "meters",
+            CostUnit::Kilometers => "kilometers",
+            CostUnit::Miles => "miles",
+            CostUnit::Seconds => "seconds",
+            CostUnit::Minutes => "minutes",
+            CostUnit::Hours => "hours",
+        };
+        write!(formatter, "{}", name)
+    }
+}
+/// Where a `DistanceMat` comes from and what its costs mean, as created by
+/// `DistanceMat::metadata`. It is attached to results, so that they can be traced back to the
+/// exact matrix they were computed on.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DistanceMatMetadata {
+    /// The units of the costs, `None` if they were not specified.
+    pub units: Option<CostUnit>,
+    /// Where the matrix was created from, e.g. a file name or a routing service.
+    pub source: Option<String>,
+    /// The number of nodes in the distance matrix.
+    pub n_units: usize,
+    /// A hash of the number of nodes and all distances. Two matrices with the same
+    /// fingerprint are the same instance with very high probability. It is the xx hash, or
+    /// the foldhash without the `std` feature.
+    pub fingerprint: u64,
+}
+impl fmt::Display for DistanceMatMetadata {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "DistanceMatMetadata(units: {}, source: {}, n_units: {}, fingerprint: {:016x})",
+            self.units
+                .map_or("unknown".to_string(), |units| units.to_string()),
+            self.source.as_deref().unwrap_or("unknown"),
+            self.n_units,
+            self.fingerprint
+        )
+    }
+}
+/// A sanity report of a `DistanceMat` as created by `DistanceMat::summary`. All edge
+/// statistics are computed on the off-diagonal entries.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DistanceMatSummary {
+    /// The number of nodes in the distance matrix.
+    pub n_units: usize,
+    /// The cost of the cheapest edge.
+    pub min_edge: f64,
+    /// The cost of the most expensive edge.
+    pub max_edge: f64,
+    /// The mean cost of all edges.
+    pub mean_edge: f64,
+    /// The number of pairs of nodes `(i, j)` whose distance differs from `(j, i)`.
+    pub n_symmetry_violations: usize,
+    /// The number of edges with cost 0 between two different nodes.
+    pub n_zero_off_diagonal: usize,
+    /// The number of nodes whose distance to themselves is not 0.
+    pub n_nonzero_diagonal: usize,
+    /// The number of triples of nodes `(i, j, k)` for which going from `i` to `k` directly
+    /// is more expensive than going via `j`.
+    pub n_triangle_violations: usize,
+}
+impl DistanceMatSummary {
+    /// Is the distance matrix a metric, e.g. symmetric, 0 on the diagonal and without
+    /// triangle-inequality violations?
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert!(distance_matrix.summary().is_metric());
+    /// ```
+    pub fn is_metric(&self) -> bool {
+        self.n_symmetry_violations == 0
+            && self.n_nonzero_diagonal == 0
+            && self.n_triangle_violations == 0
+    }
+}
+impl fmt::Display for DistanceMatSummary {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "DistanceMatSummary(n_units: {}, min_edge: {}, max_edge: {}, mean_edge: {}, \
+            symmetry violations: {}, zero off-diagonal entries: {}, non-zero diagonal entries: {}, \
+            triangle-inequality violations: {})",
+            self.n_units,
+            self.min_edge,
+            self.max_edge,
+            self.mean_edge,
+            self.n_symmetry_violations,
+            self.n_zero_off_diagonal,
+            self.n_nonzero_diagonal,
+            self.n_triangle_violations
+        )
+    }
+}
+
+/// Compute the distance between every pair of points.
+///
+/// # Arguments
+///
+/// * `points` - The coordinates of the nodes.
+/// * `distance` - The distance between two points.
+///
+fn pairwise_distances(
+    points: &[(f64, f64)],
+    distance: impl Fn((f64, f64), (f64, f64)) -> f64,
+) -> Vec<Vec<f64>> {
+    points
+        .iter()
+        .map(|from| points.iter().map(|to| distance(*from, *to)).collect())
+        .collect()
+}
+
+/// A numeric type the distances of a `DistanceMat` can be stored in, e.g. `f32` to halve the
+/// memory of a large matrix or `u32` for the integer weights of TSPLIB instances. The costs of
+/// routes are always computed in `f64`. It is implemented for the floats `f32` and `f64` and
+/// the integers `u8`, `u16`, `u32`, `u64`, `i16`, `i32` and `i64`.
+pub trait Distance: Copy + PartialEq + fmt::Debug + Send + Sync + 'static + sealed::Store {
+    /// Convert the distance to `f64` to compute costs with it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::Distance;
+    ///
+    /// assert_eq!(3u32.to_f64(), 3.0);
+    /// ```
+    fn to_f64(self) -> f64;
+    /// Convert a distance from `f64`, integers are rounded to the nearest integer and
+    /// saturate at their bounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance` - The distance as `f64`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::Distance;
+    ///
+    /// assert_eq!(u32::from_f64(2.6), 3);
+    /// assert_eq!(f32::from_f64(2.5), 2.5);
+    /// ```
+    fn from_f64(distance: f64) -> Self;
+}
+macro_rules! impl_float_distance {
+    ($($float:ty),*) => {
+        $(impl Distance for $float {
+            #[inline]
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+            #[inline]
+            fn from_f64(distance: f64) -> Self {
+                distance as $float
+            }
+        })*
+    };
+}
+macro_rules! impl_integer_distance {
+    ($($integer:ty),*) => {
+        $(impl Distance for $integer {
+            #[inline]
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+            #[inline]
+            fn from_f64(distance: f64) -> Self {
+                distance.round() as $integer
+            }
+        })*
+    };
+}
+impl_float_distance!(f32, f64);
+impl_integer_distance!(u8, u16, u32, u64, i16, i32, i64);
+
+mod sealed {
+    use alloc::vec::Vec;
+
+    /// Store distances in the variant of `Distances` for their type. This seals `Distance`,
+    /// as every type needs a variant.
+    pub trait Store: Sized {
+        /// Create a distance matrix that stores the distances in their variant.
+        ///
+        /// # Arguments
+        ///
+        /// * `distances` - The distances between all indexes 0..n.
+        ///
+        fn store(distances: Vec<Vec<Self>>) -> super::DistanceMat;
+    }
+}
+
+/// The distances of a `DistanceMat` in one contiguous row-major vector, so that the legs of a
+/// route are looked up without following a pointer per row. It is serialized as rows, like
+/// the `Vec<Vec<T>>` it is created from, with non-finite distances encoded by `non_finite`.
+#[derive(Debug, Clone, PartialEq)]
+struct FlatMatrix<T> {
+    /// The number of rows and columns.
+    n_units: usize,
+    /// The distance from `from` to `to` is at `from * n_units + to`.
+    values: Vec<T>,
+}
+impl<T> FlatMatrix<T> {
+    /// Copy the rows of a square matrix into one vector.
+    ///
+    /// # Arguments
+    ///
+    /// * `rows` - The rows of the matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix is not square.
+    ///
+    fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let n_units = rows.len();
+        let mut values = Vec::with_capacity(n_units * n_units);
+        for (from, row) in rows.into_iter().enumerate() {
+            assert_eq!(
+                row.len(),
+                n_units,
+                "Row {} of the distance matrix has {} distances but the matrix has {} nodes, \
+                 use `DistanceMat::try_new` to check the distances",
+                from,
+                row.len(),
+                n_units
+            );
+            values.extend(row);
+        }
+        FlatMatrix { n_units, values }
+    }
+    /// Get the distances from one node to all nodes.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The node the legs start at.
+    ///
+    #[cfg(feature = "serde")]
+    fn row(&self, from: usize) -> &[T] {
+        &self.values[from * self.n_units..(from + 1) * self.n_units]
+    }
+}
+impl<T: Distance> FlatMatrix<T> {
+    /// Get the distance from one node to another.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The node the leg starts at.
+    /// * `to` - The node the leg ends at.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `from` is not part of the matrix. `to` is only checked in debug builds, as
+    /// the check would otherwise cost a branch per leg; in release builds a `to` that is not
+    /// part of the matrix reads a leg of the next row.
+    ///
+    #[inline]
+    fn get(&self, from: usize, to: usize) -> f64 {
+        debug_assert!(
+            to < self.n_units,
+            "node {to} is not part of the distance matrix"
+        );
+        self.values[from * self.n_units + to].to_f64()
+    }
+}
+#[cfg(feature = "serde")]
+impl<T: Distance + serde::Serialize> serde::Serialize for FlatMatrix<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        /// A row whose distances are written with `non_finite::serialize_distance`.
+        struct Row<'a, T>(&'a [T]);
+        impl<T: Distance + serde::Serialize> serde::Serialize for Row<'_, T> {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_seq(self.0.iter().map(EncodedDistance))
+            }
+        }
+        serializer.collect_seq((0..self.n_units).map(|from| Row(self.row(from))))
+    }
+}
+/// A distance that is serialized with `non_finite`, so that infinite and NaN distances can
+/// be read back from JSON.
+#[cfg(feature = "serde")]
+struct EncodedDistance<T>(T);
+#[cfg(feature = "serde")]
+impl<T: Distance + serde::Serialize> serde::Serialize for EncodedDistance<&T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        non_finite::serialize_distance(self.0, serializer)
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de, T: Distance + serde::Deserialize<'de>> serde::Deserialize<'de> for EncodedDistance<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        non_finite::deserialize_distance(deserializer).map(EncodedDistance)
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de, T: Distance + serde::Deserialize<'de>> serde::Deserialize<'de> for FlatMatrix<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let rows = Vec::<Vec<EncodedDistance<T>>>::deserialize(deserializer)?
+            .into_iter()
+            .map(|row| row.into_iter().map(|distance| distance.0).collect())
+            .collect::<Vec<Vec<T>>>();
+        let n_units = rows.len();
+        if let Some((from, row)) = rows
+            .iter()
+            .enumerate()
+            .find(|(_, row)| row.len() != n_units)
+        {
+            return Err(serde::de::Error::custom(DistanceMatError::NotSquare {
+                row: from,
+                length: row.len(),
+                n_units,
+            }));
+        }
+        Ok(FlatMatrix::from_rows(rows))
+    }
+}
+
+/// Serialize the floats that JSON has no numbers for, infinite and NaN distances or costs, as
+/// the strings `"inf"`, `"-inf"` and `"NaN"`, so that they are read back instead of written as
+/// `null`. Formats that aren't human-readable, e.g. bincode, store them as they are. Use it
+/// with `#[serde(with = "crate::distance_mat::non_finite")]` on `f64` fields.
+#[cfg(feature = "serde")]
+pub(crate) mod non_finite {
+    use super::Distance;
+    use alloc::string::String;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Get the string a non-finite float is written as.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The infinite or NaN float.
+    ///
+    fn encode(value: f64) -> &'static str {
+        if value.is_nan() {
+            "NaN"
+        } else if value > 0.0 {
+            "inf"
+        } else {
+            "-inf"
+        }
+    }
+    /// Serialize a distance, non-finite ones as strings in human-readable formats.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The distance that is serialized.
+    /// * `serializer` - The serializer of the format.
+    ///
+    pub(crate) fn serialize_distance<T: Distance + Serialize, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let float = value.to_f64();
+        if serializer.is_human_readable() && !float.is_finite() {
+            serializer.serialize_str(encode(float))
+        } else {
+            value.serialize(serializer)
+        }
+    }
+    /// Deserialize a distance written by `serialize_distance`. Integers can't be infinite or
+    /// NaN, so they reject the strings.
+    ///
+    /// # Arguments
+    ///
+    /// * `deserializer` - The deserializer of the format.
+    ///
+    pub(crate) fn deserialize_distance<
+        'de,
+        T: Distance + Deserialize<'de>,
+        D: Deserializer<'de>,
+    >(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        if !deserializer.is_human_readable() {
+            return T::deserialize(deserializer);
+        }
+        /// A number or the string of a non-finite float.
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Encoded<T> {
+            Number(T),
+            Text(String),
+        }
+        let text = match Encoded::<T>::deserialize(deserializer)? {
+            Encoded::Number(value) => return Ok(value),
+            Encoded::Text(text) => text,
+        };
+        let float = match text.as_str() {
+            "inf" => f64::INFINITY,
+            "-inf" => f64::NEG_INFINITY,
+            "NaN" => f64::NAN,
+            _ => return Err(D::Error::custom(format!("{:?} is not a number", text))),
+        };
+        let value = T::from_f64(float);
+        if value.to_f64().is_finite() {
+            return Err(D::Error::custom(format!(
+                "{:?} can't be stored in {}",
+                text,
+                core::any::type_name::<T>()
+            )));
+        }
+        Ok(value)
+    }
+    /// Serialize a float like `serialize_distance`, for `#[serde(with)]`.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The float that is serialized.
+    /// * `serializer` - The serializer of the format.
+    ///
+    #[cfg(feature = "regression")]
+    pub(crate) fn serialize<S: Serializer>(value: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_distance(value, serializer)
+    }
+    /// Deserialize a float like `deserialize_distance`, for `#[serde(with)]`.
+    ///
+    /// # Arguments
+    ///
+    /// * `deserializer` - The deserializer of the format.
+    ///
+    #[cfg(feature = "regression")]
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+        deserialize_distance(deserializer)
+    }
+}
+
+macro_rules! distances {
+    ($d:tt; $rows:ident($rows_distance:ty); $($variant:ident($distance:ty, $name:literal)),*) => {
+        /// The distances of a `DistanceMat` in the type they were given in. The type is erased
+        /// here, so that a `Route` evaluates its fitness on the same `DistanceMat` whatever
+        /// the type of its distances. `f64` distances are serialized as rows, the others as
+        /// rows under the name of their type, e.g. `{"u32": [[0, 1], [1, 0]]}`.
+        #[derive(Debug, Clone, PartialEq)]
+        enum Distances {
+            #[doc = concat!("Distances stored as `", stringify!($rows_distance), "`.")]
+            $rows(FlatMatrix<$rows_distance>),
+            $(
+                #[doc = concat!("Distances stored as `", $name, "`.")]
+                $variant(FlatMatrix<$distance>),
+            )*
+        }
+        /// Evaluate an expression on the `FlatMatrix` of `Distances`, so that the type of the
+        /// distances is matched once for all legs the expression looks up, instead of per leg
+        /// like `Distances::get`.
+        macro_rules! with_matrix {
+            ($d distances:expr, |$d matrix:ident| $d body:expr) => {
+                match $d distances {
+                    Distances::$rows($d matrix) => $d body,
+                    $(Distances::$variant($d matrix) => $d body,)*
+                }
+            };
+        }
+        impl Distances {
+            /// Get the number of nodes.
+            fn n_units(&self) -> usize {
+                match self {
+                    Distances::$rows(distances) => distances.n_units,
+                    $(Distances::$variant(distances) => distances.n_units,)*
+                }
+            }
+            /// Get the distance from one node to another, see `FlatMatrix::get`.
+            ///
+            /// # Arguments
+            ///
+            /// * `from` - The node the leg starts at.
+            /// * `to` - The node the leg ends at.
+            ///
+            #[inline]
+            fn get(&self, from: usize, to: usize) -> f64 {
+                with_matrix!(self, |distances| distances.get(from, to))
+            }
+            /// Convert the distances to another type.
+            fn convert<U: Distance>(&self) -> Distances {
+                let n_units = self.n_units();
+                U::store(
+                    (0..n_units)
+                        .map(|from| {
+                            (0..n_units)
+                                .map(|to| U::from_f64(self.get(from, to)))
+                                .collect()
+                        })
+                        .collect(),
+                )
+                .distances
+            }
+        }
+        impl sealed::Store for $rows_distance {
+            fn store(distances: Vec<Vec<Self>>) -> DistanceMat {
+                DistanceMat::from_distances(Distances::$rows(FlatMatrix::from_rows(distances)))
+            }
+        }
+        $(impl sealed::Store for $distance {
+            fn store(distances: Vec<Vec<Self>>) -> DistanceMat {
+                DistanceMat::from_distances(Distances::$variant(FlatMatrix::from_rows(distances)))
+            }
+        })*
+        #[cfg(feature = "serde")]
+        impl serde::Serialize for Distances {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                use serde::ser::SerializeMap;
+                match self {
+                    Distances::$rows(distances) => distances.serialize(serializer),
+                    $(Distances::$variant(distances) => {
+                        let mut map = serializer.serialize_map(Some(1))?;
+                        map.serialize_entry($name, distances)?;
+                        map.end()
+                    })*
+                }
+            }
+        }
+        /// The distances under the name of their type, see `Distances`.
+        #[cfg(feature = "serde")]
+        #[derive(serde::Deserialize)]
+        enum TypedDistances {
+            $(
+                #[serde(rename = $name)]
+                $variant(FlatMatrix<$distance>),
+            )*
+        }
+        #[cfg(feature = "serde")]
+        impl<'de> serde::Deserialize<'de> for Distances {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                /// Rows of distances of the default type or the distances under the name of their type.
+                #[derive(serde::Deserialize)]
+                #[serde(untagged)]
+                enum Serialized {
+                    Rows(FlatMatrix<$rows_distance>),
+                    Typed(TypedDistances),
+                }
+                Ok(match Serialized::deserialize(deserializer)? {
+                    Serialized::Rows(distances) => Distances::$rows(distances),
+                    $(Serialized::Typed(TypedDistances::$variant(distances)) => {
+                        Distances::$variant(distances)
+                    })*
+                })
+            }
+        }
+    };
+}
+distances!(
+    $;
+    F64(f64);
+    F32(f32, "f32"),
+    U8(u8, "u8"),
+    U16(u16, "u16"),
+    U32(u32, "u32"),
+    U64(u64, "u64"),
+    I16(i16, "i16"),
+    I32(i32, "i32"),
+    I64(i64, "i64")
+);
+
+/// How often the fitness cache of a `DistanceMat` was used, see `DistanceMat::with_fitness_cache`.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FitnessCacheStats {
+    /// How many costs were read from the cache.
+    pub hits: u64,
+    /// How many costs had to be computed.
+    pub misses: u64,
+    /// How many costs are currently cached.
+    pub len: usize,
+}
+
+/// How many independently locked shards a `FitnessCache` is split into, so that threads
+/// evaluating different routes rarely wait for each other.
+#[cfg(feature = "std")]
+const FITNESS_CACHE_SHARDS: usize = 16;
+
+/// The cost of a route in a slot of the `FitnessCache`.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+struct CachedCost {
+    hash: u64,
+    route: Vec<usize>,
+    cost: f64,
+}
+
+/// The costs of recently evaluated routes. Every route has one slot, given by the hash of its
+/// nodes, and a route that maps to an occupied slot replaces the cost stored there. The nodes
+/// are kept next to the cost, so a cached cost never belongs to a mutated or crossed route.
+/// The slots are split into shards with a lock of their own.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+struct FitnessCache {
+    /// How many costs are cached at most.
+    capacity: usize,
+    shards: Vec<Mutex<Vec<Option<CachedCost>>>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+#[cfg(feature = "std")]
+impl FitnessCache {
+    /// Create an empty cache.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - How many costs are cached at most.
+    ///
+    fn new(capacity: usize) -> Self {
+        let n_shards = capacity.min(FITNESS_CACHE_SHARDS);
+        FitnessCache {
+            capacity,
+            shards: (0..n_shards)
+                .map(|_| Mutex::new((0..capacity / n_shards).map(|_| None).collect()))
+                .collect(),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+    /// Hash the nodes of a route by rolling a multiplicative hash over them. It is a single
+    /// pass over contiguous memory, and so cheaper than the lookups into the distance matrix
+    /// that evaluating the route takes.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The nodes of the route.
+    ///
+    fn hash(route: &[usize]) -> u64 {
+        route.iter().fold(route.len() as u64, |hash, node| {
+            (hash.rotate_left(5) ^ *node as u64).wrapping_mul(0x517c_c1b7_2722_0a95)
+        })
+    }
+    /// Get the shard and the slot in it that the cost of a route is stored in.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The hash of the nodes of the route.
+    ///
+    fn slot(&self, hash: u64) -> (&Mutex<Vec<Option<CachedCost>>>, usize) {
+        let n_shards = self.shards.len() as u64;
+        let n_slots = (self.capacity / self.shards.len()) as u64;
+        (
+            &self.shards[(hash % n_shards) as usize],
+            (hash / n_shards % n_slots) as usize,
+        )
+    }
+    /// How many costs are currently cached.
+    fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.lock().unwrap().iter().flatten().count())
+            .sum()
+    }
+}
+
+/// A `bool` that is computed at most once, like a `OnceLock<bool>`, but with an atomic of
+/// `core`, so that it is available without `std`.
+#[derive(Debug, Default)]
+struct OnceBool(AtomicU8);
+impl OnceBool {
+    /// The state of a `OnceBool` whose value is not set yet.
+    const UNSET: u8 = 0;
+    /// Create a `OnceBool` whose value is not set yet.
+    fn new() -> Self {
+        OnceBool(AtomicU8::new(OnceBool::UNSET))
+    }
+    /// Get the value, `None` if it is not set yet.
+    fn get(&self) -> Option<&bool> {
+        match self.0.load(Ordering::Acquire) {
+            OnceBool::UNSET => None,
+            1 => Some(&false),
+            _ => Some(&true),
+        }
+    }
+    /// Set the value if it is not set yet, otherwise return the value that was not set.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to set.
+    ///
+    fn set(&self, value: bool) -> Result<(), bool> {
+        self.0
+            .compare_exchange(
+                OnceBool::UNSET,
+                1 + u8::from(value),
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            )
+            .map(|_| ())
+            .map_err(|_| value)
+    }
+    /// Get the value, and compute and set it first if it is not set yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `init` - Computes the value if it is not set yet.
+    ///
+    fn get_or_init(&self, init: impl FnOnce() -> bool) -> &bool {
+        if self.get().is_none() {
+            let _ = self.set(init());
+        }
+        self.get().unwrap()
+    }
+}
+
+/// A representation of a distance matrix. `new` stores its distances as `f64`, `new_typed` in
+/// the `Distance` type they are given in, e.g. `f32` or `u32` to save memory on large instances.
+/// The costs of routes are computed in `f64` whatever the type, so the genetic algorithm
+/// evolves its routes on matrices of every type.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DistanceMat {
-    distances: Vec<Vec<f64>>,
+    distances: Distances,
+    objective: Objective,
+    /// Further scenarios of the same instance, e.g. the distances in heavy traffic.
+    #[cfg_attr(feature = "serde", serde(default))]
+    scenarios: Vec<DistanceMat>,
+    /// How the costs of a route in all scenarios are combined.
+    #[cfg_attr(feature = "serde", serde(default))]
+    aggregation: ScenarioAggregation,
+    /// How routes with the same fitness are ordered in a selection.
+    #[cfg_attr(feature = "serde", serde(default))]
+    tie_breaking: TieBreaking,
+    /// Which routes a selection treats as the same individual.
+    #[cfg_attr(feature = "serde", serde(default))]
+    tour_equivalence: TourEquivalence,
+    /// The units of the costs, if known.
+    #[cfg_attr(feature = "serde", serde(default))]
+    units: Option<CostUnit>,
+    /// Where the matrix was created from, if known.
+    #[cfg_attr(feature = "serde", serde(default))]
+    source: Option<String>,
+    /// The coordinates of the nodes, if the matrix was created from points.
+    #[cfg_attr(feature = "serde", serde(default))]
+    coordinates: Option<Vec<(f64, f64)>>,
+    /// The time window of every node, if the nodes have to be served in time.
+    #[cfg_attr(feature = "serde", serde(default))]
+    time_windows: Option<Vec<TimeWindow>>,
+    /// The cost of every unit of time a node is served after its time window.
+    #[cfg_attr(feature = "serde", serde(default))]
+    lateness_penalty: f64,
+    /// The nearest neighbors of every node, if the operators should be restricted to them.
+    #[cfg_attr(feature = "serde", serde(default))]
+    candidate_list: Option<CandidateList>,
+    /// Is the matrix a metric? Is set by the first call to `summary` or `is_metric`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    metric: OnceBool,
+    /// Is the matrix symmetric? Is set by the first call to `summary` or `is_symmetric`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    symmetric: OnceBool,
+    /// The costs of recently evaluated routes, if the cache is enabled.
+    #[cfg(feature = "std")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    fitness_cache: Option<FitnessCache>,
 }
 
 impl DistanceMat {
@@ -11,68 +865,1605 @@ impl DistanceMat {
     ///
     /// # Arguments
     ///
-    /// * `distances` - The distances between all indexes 0..n. The matrix
-    /// is assumed to be symmetrical and the distance between an object and itself
-    /// (the diagonal) should be only 0.
+    /// * `distances` - The distances between all indexes 0..n. The matrix
+    ///   is assumed to be symmetrical and the distance between an object and itself
+    ///   (the diagonal) should be only 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// ```
+    pub fn new(distances: Vec<Vec<f64>>) -> Self {
+        DistanceMat::new_typed(distances)
+    }
+    /// Create a new distance mat like `new`, but keep the distances in the type they are
+    /// given in, see `Distance`.
+    ///
+    /// # Arguments
+    ///
+    /// * `distances` - The distances between all indexes 0..n.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new_typed(vec![vec![0u32, 1, 2], vec![1, 0, 3], vec![2, 3, 0]]);
+    /// assert_eq!(distance_matrix.get_distance(&vec![1, 0, 2]), Ok(6.0));
+    /// ```
+    pub fn new_typed<T: Distance>(distances: Vec<Vec<T>>) -> Self {
+        T::store(distances)
+    }
+    /// Create a distance matrix with default settings around its distances.
+    ///
+    /// # Arguments
+    ///
+    /// * `distances` - The distances between all indexes 0..n.
+    ///
+    fn from_distances(distances: Distances) -> Self {
+        DistanceMat {
+            distances,
+            objective: Objective::default(),
+            scenarios: Vec::new(),
+            aggregation: ScenarioAggregation::default(),
+            tie_breaking: TieBreaking::default(),
+            tour_equivalence: TourEquivalence::default(),
+            units: None,
+            source: None,
+            coordinates: None,
+            time_windows: None,
+            lateness_penalty: 0.0,
+            candidate_list: None,
+            metric: OnceBool::new(),
+            symmetric: OnceBool::new(),
+            #[cfg(feature = "std")]
+            fitness_cache: None,
+        }
+    }
+    /// Create a new distance mat like `new`, but check the distances first: the matrix has
+    /// to be square and every distance has to be finite and non-negative.
+    ///
+    /// # Arguments
+    ///
+    /// * `distances` - The distances between all indexes 0..n.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::{DistanceMat, DistanceMatError};
+    ///
+    /// assert!(DistanceMat::try_new(vec![vec![0.0, 1.0], vec![1.0, 0.0]]).is_ok());
+    /// assert_eq!(
+    ///     DistanceMat::try_new(vec![vec![0.0, 1.0], vec![1.0]]).unwrap_err(),
+    ///     DistanceMatError::NotSquare { row: 1, length: 1, n_units: 2 }
+    /// );
+    /// assert_eq!(
+    ///     DistanceMat::try_new(vec![vec![0.0, f64::NAN], vec![1.0, 0.0]]).unwrap_err(),
+    ///     DistanceMatError::InvalidDistance { from: 0, to: 1 }
+    /// );
+    /// ```
+    pub fn try_new(distances: Vec<Vec<f64>>) -> Result<Self, DistanceMatError> {
+        DistanceMat::try_new_typed(distances)
+    }
+    /// Create a new distance mat like `new_typed`, but check the distances first like
+    /// `try_new`.
+    ///
+    /// # Arguments
+    ///
+    /// * `distances` - The distances between all indexes 0..n.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::{DistanceMat, DistanceMatError};
+    ///
+    /// assert_eq!(
+    ///     DistanceMat::try_new_typed(vec![vec![0u32, 1], vec![1]]).unwrap_err(),
+    ///     DistanceMatError::NotSquare { row: 1, length: 1, n_units: 2 }
+    /// );
+    /// ```
+    pub fn try_new_typed<T: Distance>(distances: Vec<Vec<T>>) -> Result<Self, DistanceMatError> {
+        let n_units = distances.len();
+        for (from, row) in distances.iter().enumerate() {
+            if row.len() != n_units {
+                return Err(DistanceMatError::NotSquare {
+                    row: from,
+                    length: row.len(),
+                    n_units,
+                });
+            }
+            if let Some(to) = row
+                .iter()
+                .position(|distance| !distance.to_f64().is_finite() || distance.to_f64() < 0.0)
+            {
+                return Err(DistanceMatError::InvalidDistance { from, to });
+            }
+        }
+        Ok(DistanceMat::new_typed(distances))
+    }
+    /// Use another objective than the round-trip length to compute the cost of a route.
+    ///
+    /// # Arguments
+    ///
+    /// * `objective` - The objective that the fitness of a `Route` should be based on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::objective::Objective;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]])
+    ///     .with_objective(Objective::Latency);
+    /// ```
+    pub fn with_objective(mut self, objective: Objective) -> Self {
+        self.objective = objective;
+        self.clear_fitness_cache();
+        self
+    }
+    /// Choose how routes with the same fitness are ordered when the fittest routes are selected,
+    /// by default they are ordered by their canonical tour.
+    ///
+    /// # Arguments
+    ///
+    /// * `tie_breaking` - The order of routes with the same fitness.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::objective::TieBreaking;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]])
+    ///     .with_tie_breaking(TieBreaking::Unordered);
+    /// assert_eq!(distance_matrix.tie_breaking(), TieBreaking::Unordered);
+    /// ```
+    pub fn with_tie_breaking(mut self, tie_breaking: TieBreaking) -> Self {
+        self.tie_breaking = tie_breaking;
+        self
+    }
+    /// Choose which routes a selection treats as the same individual, by default every
+    /// sequence of nodes is a distinct route.
+    ///
+    /// # Arguments
+    ///
+    /// * `tour_equivalence` - Which routes are the same individual.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::objective::TourEquivalence;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]])
+    ///     .with_tour_equivalence(TourEquivalence::RoundTrip);
+    /// assert_eq!(distance_matrix.tour_equivalence(), TourEquivalence::RoundTrip);
+    /// ```
+    pub fn with_tour_equivalence(mut self, tour_equivalence: TourEquivalence) -> Self {
+        self.tour_equivalence = tour_equivalence;
+        self
+    }
+    /// Evaluate routes on several scenarios of the same instance, e.g. the travel times in light
+    /// and heavy traffic. This matrix is the first scenario, `scenarios` are added. The cost of
+    /// a route is computed in every scenario with the objective of this matrix and combined by
+    /// `aggregation`, so that the evolution finds routes that work well in all scenarios.
+    /// Everything else, e.g. `get_distance` or `summary`, only uses this matrix.
+    ///
+    /// # Arguments
+    ///
+    /// * `scenarios` - The distance matrices of the further scenarios. They need to have the
+    ///   same nodes as this matrix, their objectives are ignored.
+    /// * `aggregation` - How the costs of the scenarios are combined into one cost.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a scenario doesn't have as many nodes as this matrix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::objective::ScenarioAggregation;
+    ///
+    /// let light_traffic = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let heavy_traffic = DistanceMat::new(vec![vec![0.0,3.0,2.0], vec![3.0,0.0,5.0], vec![2.0,5.0,0.0]]);
+    /// let distance_matrix = light_traffic.with_scenarios(vec![heavy_traffic], ScenarioAggregation::Average);
+    /// assert_eq!(distance_matrix.get_cost(&[0, 1, 2]), 8.0);
+    /// ```
+    pub fn with_scenarios(
+        mut self,
+        scenarios: Vec<DistanceMat>,
+        aggregation: ScenarioAggregation,
+    ) -> Self {
+        for (index, scenario) in scenarios.iter().enumerate() {
+            assert_eq!(
+                scenario.n_units(),
+                self.n_units(),
+                "The scenario at index {} has {} nodes but the distance matrix has {} nodes",
+                index,
+                scenario.n_units(),
+                self.n_units()
+            );
+        }
+        self.scenarios = scenarios;
+        self.aggregation = aggregation;
+        self.clear_fitness_cache();
+        self
+    }
+    /// Declare the units in which the costs of the matrix are given, so that thresholds in other
+    /// units can be detected by `check_units`.
+    ///
+    /// # Arguments
+    ///
+    /// * `units` - The units of the costs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::{CostUnit, DistanceMat};
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]])
+    ///     .with_units(CostUnit::Seconds);
+    /// assert_eq!(distance_matrix.units(), Some(CostUnit::Seconds));
+    /// ```
+    pub fn with_units(mut self, units: CostUnit) -> Self {
+        self.units = Some(units);
+        self
+    }
+    /// Record where the matrix was created from, e.g. a file name or a routing service.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - A description of the origin of the matrix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]])
+    ///     .with_source("tests/test-data/distances.txt");
+    /// assert_eq!(distance_matrix.metadata().source.as_deref(), Some("tests/test-data/distances.txt"));
+    /// ```
+    pub fn with_source(mut self, source: &str) -> Self {
+        self.source = Some(source.to_string());
+        self
+    }
+    /// Attach the coordinates of the nodes to the matrix, e.g. if the distances are road
+    /// distances between known locations. Spatial operators like
+    /// `Route::spatial_crossover_with_rng` use them, while the costs are still computed from
+    /// the distances.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - The coordinates of the nodes, e.g. `(x, y)` or `(latitude, longitude)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]])
+    ///     .with_coordinates(&[(0.0, 0.0), (1.0, 0.0), (0.0, 2.0)]);
+    /// assert_eq!(distance_matrix.coordinates().map(|points| points.len()), Some(3));
+    /// ```
+    pub fn with_coordinates(mut self, points: &[(f64, f64)]) -> Self {
+        self.coordinates = Some(points.to_vec());
+        self
+    }
+    /// Serve every node within its time window, e.g. to solve the TSP with time windows
+    /// (TSPTW). The distances are the travel times: a route starts at its first node at the
+    /// earliest time of its window, waits at nodes it reaches too early and leaves them after
+    /// their service. Every unit of time a service starts after the latest time of its window,
+    /// including the return to the first node of a round-trip, adds `lateness_penalty` to the
+    /// cost of the route. The costs can then no longer be updated locally, so the delta
+    /// evaluations of moves return `None`.
+    ///
+    /// # Arguments
+    ///
+    /// * `time_windows` - The time window of every node.
+    /// * `lateness_penalty` - The cost of every unit of time of lateness.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is not exactly one time window per node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::objective::TimeWindow;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]])
+    ///     .with_time_windows(
+    ///         vec![TimeWindow::default(), TimeWindow::new(0.0, 1.0, 0.0), TimeWindow::new(0.0, 2.0, 0.0)],
+    ///         10.0,
+    ///     );
+    /// // Node 2 is reached at time 4.0 instead of 2.0.
+    /// assert_eq!(distance_matrix.get_cost(&[0, 1, 2]), 26.0);
+    /// // Node 1 is reached at time 5.0 instead of 1.0.
+    /// assert_eq!(distance_matrix.get_cost(&[0, 2, 1]), 46.0);
+    /// ```
+    pub fn with_time_windows(
+        mut self,
+        time_windows: Vec<TimeWindow>,
+        lateness_penalty: f64,
+    ) -> Self {
+        assert_eq!(
+            time_windows.len(),
+            self.n_units(),
+            "There are {} time windows but the distance matrix has {} nodes",
+            time_windows.len(),
+            self.n_units()
+        );
+        self.time_windows = Some(time_windows);
+        self.lateness_penalty = lateness_penalty;
+        self.clear_fitness_cache();
+        self
+    }
+    /// Get the time windows of the nodes, `None` if the nodes can be served at any time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert_eq!(distance_matrix.time_windows(), None);
+    /// ```
+    pub fn time_windows(&self) -> Option<&[TimeWindow]> {
+        self.time_windows.as_deref()
+    }
+    /// Restrict the candidate operators to the `k` nearest neighbors of every node, see
+    /// `CandidateList`. `CrossoverOperator::Candidate` and `MutationOperator::Candidate` use
+    /// them, while the costs are still computed from all distances. Computing the list takes
+    /// `O(n^2)` time once, which pays off on large instances with 1000 nodes and more.
+    ///
+    /// # Arguments
+    ///
+    /// * `k` - How many neighbors every node gets, 5 to 10 are typical.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]])
+    ///     .with_candidate_list(1);
+    /// assert_eq!(distance_matrix.candidate_list().unwrap().neighbors(0), &[1]);
+    /// ```
+    pub fn with_candidate_list(mut self, k: usize) -> Self {
+        self.candidate_list = Some(CandidateList::new(&self, k));
+        self
+    }
+    /// Get the nearest neighbors of every node, `None` if no candidate list was computed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert_eq!(distance_matrix.candidate_list(), None);
+    /// ```
+    pub fn candidate_list(&self) -> Option<&CandidateList> {
+        self.candidate_list.as_ref()
+    }
+    /// Cache the fitness of up to `capacity` routes, so that routes which survive a selection
+    /// are not evaluated again in every generation. Routes are cached by their nodes, so a route
+    /// that is mutated or crossed is evaluated anew. Every route has a slot given by the hash of
+    /// its nodes, and a new route replaces the one in its slot, so a full cache keeps the most
+    /// recent costs. The slots are split into shards that are locked separately, so that threads
+    /// evolving in parallel rarely wait for each other. Changing the objective or the scenarios
+    /// of the matrix empties the cache. The cache only pays off if the same routes are
+    /// evaluated again and again and evaluating a route is much more expensive than hashing it.
+    /// In a default evolution only the survivors of a generation are evaluated again, about
+    /// one in twenty costs, so the cache is off by default. Measure it for your instance with
+    /// the `fitness_cache` example. The cache needs the `std` feature, as its shards are locked
+    /// with a `Mutex`.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - How many costs are cached at most.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_traits::Individual;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]])
+    ///     .with_fitness_cache(10_000);
+    /// let route = Route::new(vec![0, 1, 2]);
+    /// assert_eq!(route.fitness(&distance_matrix), route.fitness(&distance_matrix));
+    /// assert_eq!(distance_matrix.fitness_cache_stats().unwrap().hits, 1);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn with_fitness_cache(mut self, capacity: usize) -> Self {
+        self.fitness_cache = Some(FitnessCache::new(capacity));
+        self
+    }
+    /// Get how often the fitness cache was used, `None` if it is not enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert_eq!(distance_matrix.fitness_cache_stats(), None);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn fitness_cache_stats(&self) -> Option<FitnessCacheStats> {
+        self.fitness_cache.as_ref().map(|cache| FitnessCacheStats {
+            hits: cache.hits.load(Ordering::Relaxed),
+            misses: cache.misses.load(Ordering::Relaxed),
+            len: cache.len(),
+        })
+    }
+    /// Empty the fitness cache, e.g. because the costs of the routes changed.
+    fn clear_fitness_cache(&mut self) {
+        #[cfg(feature = "std")]
+        if let Some(cache) = self.fitness_cache.as_mut() {
+            *cache = FitnessCache::new(cache.capacity);
+        }
+    }
+    /// Get the cost of a route like `get_cost`, but read it from the fitness cache if it is
+    /// enabled and the route was evaluated before.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The route whose cost should be computed.
+    ///
+    #[cfg(feature = "std")]
+    pub(crate) fn get_cached_cost(&self, route: &[usize]) -> f64 {
+        let cache = match &self.fitness_cache {
+            Some(cache) if cache.capacity > 0 => cache,
+            _ => return self.get_cost(route),
+        };
+        let hash = FitnessCache::hash(route);
+        let (shard, slot) = cache.slot(hash);
+        if let Some(cached) = &shard.lock().unwrap()[slot] {
+            if cached.hash == hash && cached.route == route {
+                cache.hits.fetch_add(1, Ordering::Relaxed);
+                return cached.cost;
+            }
+        }
+        cache.misses.fetch_add(1, Ordering::Relaxed);
+        // The cost is computed without holding the lock, so other threads can use the shard.
+        let cost = self.get_cost(route);
+        match &mut shard.lock().unwrap()[slot] {
+            // Reuse the nodes of the replaced route, so that a warm cache doesn't allocate.
+            Some(cached) => {
+                cached.hash = hash;
+                cached.route.clear();
+                cached.route.extend_from_slice(route);
+                cached.cost = cost;
+            }
+            empty => {
+                *empty = Some(CachedCost {
+                    hash,
+                    route: route.to_vec(),
+                    cost,
+                })
+            }
+        }
+        cost
+    }
+    /// Get the cost of a route like `get_cost`, as there is no fitness cache without `std`.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The route whose cost should be computed.
+    ///
+    #[cfg(not(feature = "std"))]
+    pub(crate) fn get_cached_cost(&self, route: &[usize]) -> f64 {
+        self.get_cost(route)
+    }
+    /// Get the coordinates of the nodes, `None` if the matrix was not created from points.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::from_coordinates(&[(0.0, 0.0), (3.0, 0.0), (3.0, 4.0)]);
+    /// assert_eq!(distance_matrix.coordinates(), Some(&[(0.0, 0.0), (3.0, 0.0), (3.0, 4.0)][..]));
+    /// ```
+    pub fn coordinates(&self) -> Option<&[(f64, f64)]> {
+        self.coordinates.as_deref()
+    }
+    /// Get the units of the costs, `None` if they were not declared.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert_eq!(distance_matrix.units(), None);
+    /// ```
+    pub fn units(&self) -> Option<CostUnit> {
+        self.units
+    }
+    /// Check that the costs of the matrix are given in the expected units, e.g. before comparing
+    /// them with a threshold. A matrix without declared units passes every check.
+    ///
+    /// # Arguments
+    ///
+    /// * `expected` - The units the caller works in.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::{CostUnit, DistanceMat, DistanceMatError};
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]])
+    ///     .with_units(CostUnit::Seconds);
+    /// assert_eq!(distance_matrix.check_units(CostUnit::Seconds), Ok(()));
+    /// assert_eq!(
+    ///     distance_matrix.check_units(CostUnit::Meters),
+    ///     Err(DistanceMatError::UnitMismatch { expected: CostUnit::Meters, actual: CostUnit::Seconds })
+    /// );
+    /// ```
+    pub fn check_units(&self, expected: CostUnit) -> Result<(), DistanceMatError> {
+        match self.units {
+            Some(actual) if actual != expected => {
+                Err(DistanceMatError::UnitMismatch { expected, actual })
+            }
+            _ => Ok(()),
+        }
+    }
+    /// Get the metadata of the matrix: its units, its source, its number of nodes and a
+    /// fingerprint of its distances.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::{CostUnit, DistanceMat};
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]])
+    ///     .with_units(CostUnit::Kilometers);
+    /// let metadata = distance_matrix.metadata();
+    /// assert_eq!(metadata.n_units, 3);
+    /// println!("{}", metadata);
+    /// ```
+    pub fn metadata(&self) -> DistanceMatMetadata {
+        // The distances are hashed row by row, so that only one row is held as bytes at a time.
+        let mut hasher = FixedState::default().build_hasher();
+        hasher.write(&(self.n_units() as u64).to_le_bytes());
+        let mut bytes = Vec::with_capacity(8 * self.n_units());
+        for from in 0..self.n_units() {
+            bytes.clear();
+            for to in 0..self.n_units() {
+                bytes.extend_from_slice(&self.distances.get(from, to).to_bits().to_le_bytes());
+            }
+            hasher.write(&bytes);
+        }
+        DistanceMatMetadata {
+            units: self.units,
+            source: self.source.clone(),
+            n_units: self.n_units(),
+            fingerprint: hasher.finish(),
+        }
+    }
+    /// Get the number of scenarios the cost of a route is computed in, including this matrix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert_eq!(distance_matrix.n_scenarios(), 1);
+    /// ```
+    pub fn n_scenarios(&self) -> usize {
+        self.scenarios.len() + 1
+    }
+    /// Get the objective that is used to compute the cost of a route.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// println!("{:?}", distance_matrix.objective());
+    /// ```
+    pub fn objective(&self) -> Objective {
+        self.objective
+    }
+    /// Get how routes with the same fitness are ordered when the fittest routes are selected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::objective::TieBreaking;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert_eq!(distance_matrix.tie_breaking(), TieBreaking::CanonicalTour);
+    /// ```
+    pub fn tie_breaking(&self) -> TieBreaking {
+        self.tie_breaking
+    }
+    /// Get which routes a selection treats as the same individual.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::objective::TourEquivalence;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert_eq!(distance_matrix.tour_equivalence(), TourEquivalence::Sequence);
+    /// ```
+    pub fn tour_equivalence(&self) -> TourEquivalence {
+        self.tour_equivalence
+    }
+    /// The key of the round-trip a route describes: all routes with the same key have the same
+    /// cost under this matrix. Routes are rotated to start at their smallest node, as the start
+    /// of a round-trip doesn't change its cost. Only if this matrix and all its scenarios are
+    /// symmetric, the smaller of the two directions is taken as well, as in `Route::canonical_tour`.
+    /// Under `Objective::Latency` the first node matters, so the key is the route itself. Under
+    /// `Objective::PathLength` the route is not rotated, but a symmetric matrix takes the
+    /// smaller of its two directions.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The sequence of nodes that is visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let symmetric = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert_eq!(symmetric.tour_key(&[1, 0, 2]), symmetric.tour_key(&[0, 1, 2]));
+    /// let asymmetric = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![5.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert_ne!(asymmetric.tour_key(&[1, 0, 2]), asymmetric.tour_key(&[0, 1, 2]));
+    /// assert_eq!(asymmetric.tour_key(&[1, 2, 0]), asymmetric.tour_key(&[0, 1, 2]));
+    /// ```
+    pub fn tour_key(&self, route: &[usize]) -> Vec<usize> {
+        // The schedule through the time windows depends on where and in which direction the
+        // route starts.
+        if self.objective == Objective::Latency || self.time_windows.is_some() {
+            return route.to_vec();
+        }
+        let is_symmetric =
+            self.is_symmetric() && self.scenarios.iter().all(DistanceMat::is_symmetric);
+        if self.objective == Objective::PathLength {
+            let reversed = route.iter().rev().cloned().collect::<Vec<usize>>();
+            return if is_symmetric && reversed.as_slice() < route {
+                reversed
+            } else {
+                route.to_vec()
+            };
+        }
+        let route = Route::new(route.to_vec());
+        if is_symmetric {
+            return route.canonical_tour();
+        }
+        let mut forward = route.indexes;
+        if let Some(start) = forward
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, node)| **node)
+            .map(|(start, _)| start)
+        {
+            forward.rotate_left(start);
+        }
+        forward
+    }
+    /// Whether moving the node at `idx` of a round-trip elsewhere can't lower its cost. In a
+    /// metric, inserting a node between two others never lowers the cost, so only nodes whose
+    /// removal lowers it are worth moving. This is only used once `summary` or `is_metric`
+    /// recorded that the matrix is a metric.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The sequence of nodes that is visited.
+    /// * `idx` - The position of the node that would be moved.
+    ///
+    pub(crate) fn can_skip_relocation(&self, route: &[usize], idx: usize) -> bool {
+        match self.objective {
+            Objective::TourLength | Objective::Makespan
+                if self.scenarios.is_empty() && self.time_windows.is_none() => {}
+            _ => return false,
+        }
+        if self.metric.get() != Some(&true) || route.len() < 3 {
+            return false;
+        }
+        let n_nodes = route.len();
+        let (before, node, after) = (
+            route[(idx + n_nodes - 1) % n_nodes],
+            route[idx],
+            route[(idx + 1) % n_nodes],
+        );
+        with_matrix!(&self.distances, |distances| {
+            distances.get(before, node) + distances.get(node, after) <= distances.get(before, after)
+        })
+    }
+    /// Whether the cost of a route is the cost of a round-trip, which doesn't change if the
+    /// route is rotated. Under `Objective::Latency` and `Objective::PathLength` and with time
+    /// windows the first node matters.
+    pub(crate) fn is_round_trip(&self) -> bool {
+        !matches!(self.objective, Objective::Latency | Objective::PathLength)
+            && self.time_windows.is_none()
+    }
+    /// Get the number of nodes in the distance matrix, e.g. one of its dimensions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// println!("{}", distance_matrix.n_units());
+    /// ```
+    pub fn n_units(&self) -> usize {
+        self.distances.n_units()
+    }
+    /// Get the distance from one node to another, e.g. a single leg of a route.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The node the leg starts at.
+    /// * `to` - The node the leg ends at.
+    ///
+    /// # Panics
+    ///
+    /// Panics if one of the nodes is not part of the distance matrix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert_eq!(distance_matrix.distance(1, 2), 3.0);
+    /// ```
+    #[inline]
+    pub fn distance(&self, from: usize, to: usize) -> f64 {
+        self.distances.get(from, to)
+    }
+    /// Given a sequence of nodes (in a `Route`-object) compute the distance for the round-
+    /// trip between node 0..0. The route is checked first, so an empty route or a node that
+    /// is not part of the distance matrix is reported as error.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The sequence of nodes that is visited and for which the round-trip-lenght
+    ///   should be computed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert_eq!(distance_matrix.get_distance(&vec![1,0,2]), Ok(6.0));
+    /// assert!(distance_matrix.get_distance(&vec![1,0,3]).is_err());
+    /// ```
+    pub fn get_distance(&self, route: &[usize]) -> Result<f64, DistanceMatError> {
+        if route.is_empty() {
+            return Err(DistanceMatError::EmptyRoute);
+        }
+        if let Some(index) = route.iter().find(|index| **index >= self.n_units()) {
+            return Err(DistanceMatError::IndexOutOfBounds {
+                index: *index,
+                n_units: self.n_units(),
+            });
+        }
+        Ok(self.get_distance_unchecked(route))
+    }
+    /// Compute the round-trip distance like `get_distance`, but without checking the route
+    /// first. This is the fast variant used to compute the fitness of a `Route`.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The sequence of nodes that is visited and for which the round-trip-lenght
+    ///   should be computed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the route is empty or contains a node that is not part of the distance matrix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// println!("{}", distance_matrix.get_distance_unchecked(&vec![1,0,2]));
+    /// ```
+    pub fn get_distance_unchecked(&self, route: &[usize]) -> f64 {
+        with_matrix!(&self.distances, |distances| {
+            route
+                .iter()
+                .fold(
+                    // By folding the indexes we get the distances between 1-2, 2-3, ... , (n-1)-n.
+                    // Then we are missing n-0, therefore that's the initial value we choose in the `fold`-
+                    // operator.
+                    (distances.get(route[route.len() - 1], route[0]), None),
+                    |(mut loss, last_point): (f64, Option<usize>), current_point| {
+                        if let Some(last_point) = last_point {
+                            loss += distances.get(last_point, *current_point);
+                        }
+                        (loss, Some(*current_point))
+                    },
+                )
+                .0
+        })
+    }
+    /// Given a sequence of nodes compute the sum of the arrival times at all nodes, e.g.
+    /// the cost of the route in the traveling repairman problem. The route starts at its
+    /// first node at time 0 and the way back to the first node is not taken into account.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The sequence of nodes that is visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// println!("{}", distance_matrix.get_latency(&vec![1,0,2]));
+    /// ```
+    pub fn get_latency(&self, route: &[usize]) -> f64 {
+        with_matrix!(&self.distances, |distances| {
+            route
+                .windows(2)
+                .fold((0.0, 0.0), |(latency, arrival_time), leg| {
+                    let arrival_time = arrival_time + distances.get(leg[0], leg[1]);
+                    (latency + arrival_time, arrival_time)
+                })
+                .0
+        })
+    }
+    /// Given a sequence of nodes compute the length of the open path from the first to the last
+    /// node, e.g. the cost of the route if it doesn't return to its start.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The sequence of nodes that is visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert_eq!(distance_matrix.get_path_length(&vec![1,0,2]), 3.0);
+    /// ```
+    pub fn get_path_length(&self, route: &[usize]) -> f64 {
+        with_matrix!(&self.distances, |distances| {
+            route
+                .windows(2)
+                .map(|leg| distances.get(leg[0], leg[1]))
+                .sum()
+        })
+    }
+    /// Given a sequence of nodes compute the length of the longest leg of the round-trip
+    /// between node 0..0, e.g. the cost of the route in the bottleneck TSP.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The sequence of nodes that is visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// println!("{}", distance_matrix.get_bottleneck(&vec![1,0,2]));
+    /// ```
+    pub fn get_bottleneck(&self, route: &[usize]) -> f64 {
+        with_matrix!(&self.distances, |distances| {
+            route.windows(2).fold(
+                // As in `get_distance` the leg n-0 is the initial value of the fold.
+                distances.get(route[route.len() - 1], route[0]),
+                |longest_leg, leg| longest_leg.max(distances.get(leg[0], leg[1])),
+            )
+        })
+    }
+    /// Compute the cost of a route under the objective of this distance matrix. For the
+    /// default objective this is the same as `get_distance_unchecked`.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The sequence of nodes that is visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::objective::Objective;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]])
+    ///     .with_objective(Objective::Latency);
+    /// println!("{}", distance_matrix.get_cost(&vec![1,0,2]));
+    /// ```
+    pub fn get_cost(&self, route: &[usize]) -> f64 {
+        if self.scenarios.is_empty() {
+            return self.get_objective_cost(route, self.objective)
+                + self.get_lateness_cost(route, self);
+        }
+        let scenario_costs = core::iter::once(self)
+            .chain(&self.scenarios)
+            .map(|scenario| {
+                scenario.get_objective_cost(route, self.objective)
+                    + scenario.get_lateness_cost(route, self)
+            });
+        match self.aggregation {
+            ScenarioAggregation::WorstCase => scenario_costs.fold(f64::NEG_INFINITY, f64::max),
+            ScenarioAggregation::Average => scenario_costs.sum::<f64>() / self.n_scenarios() as f64,
+        }
+    }
+    /// Compute the cost of a route like `get_cost`, but check the route first, so an empty
+    /// route or a node that is not part of the distance matrix is reported as error instead
+    /// of a panic.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The route whose cost should be computed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::{DistanceMat, DistanceMatError};
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert_eq!(distance_matrix.compute_cost_checked(&Route::new(vec![1,0,2])), Ok(6.0));
+    /// assert_eq!(
+    ///     distance_matrix.compute_cost_checked(&Route::new(vec![1,5])),
+    ///     Err(DistanceMatError::IndexOutOfBounds { index: 5, n_units: 3 })
+    /// );
+    /// ```
+    pub fn compute_cost_checked(&self, route: &Route) -> Result<f64, DistanceMatError> {
+        // `get_distance` checks the route, the scenarios have the same nodes.
+        self.get_distance(&route.indexes)?;
+        Ok(self.get_cost(&route.indexes))
+    }
+    /// Compute the cost of a route in this matrix alone under `objective`.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The sequence of nodes that is visited.
+    /// * `objective` - The objective the cost is computed with.
+    ///
+    fn get_objective_cost(&self, route: &[usize], objective: Objective) -> f64 {
+        match objective {
+            Objective::TourLength | Objective::Makespan => self.get_distance_unchecked(route),
+            Objective::Latency => self.get_latency(route),
+            Objective::Bottleneck => self.get_bottleneck(route),
+            Objective::PathLength => self.get_path_length(route),
+        }
+    }
+    /// Compute how late the nodes of a route are served in total, summed over all nodes whose
+    /// service starts after their time window, see `with_time_windows`. A round-trip also has
+    /// to return to its first node in time, the open routes of `Latency` and `PathLength` end
+    /// at their last node. Without time windows this is 0.0.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The sequence of nodes that is visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::objective::TimeWindow;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]])
+    ///     .with_time_windows(
+    ///         vec![TimeWindow::new(0.0, 5.0, 0.0), TimeWindow::new(2.0, 2.0, 1.0), TimeWindow::default()],
+    ///         1.0,
+    ///     );
+    /// // Wait at node 1 until 2.0, leave at 3.0, reach node 2 at 6.0 and node 0 at 8.0.
+    /// assert_eq!(distance_matrix.get_lateness(&[0, 1, 2]), 3.0);
+    /// ```
+    pub fn get_lateness(&self, route: &[usize]) -> f64 {
+        self.get_schedule_lateness(route, self.time_windows.as_deref(), self.objective)
+    }
+    /// Compute the penalty for the lateness of a route in this matrix under the time windows
+    /// and objective of `windows_of`, e.g. the first scenario.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The sequence of nodes that is visited.
+    /// * `windows_of` - The matrix whose time windows, penalty and objective are used.
+    ///
+    fn get_lateness_cost(&self, route: &[usize], windows_of: &DistanceMat) -> f64 {
+        match windows_of.time_windows.as_deref() {
+            Some(time_windows) => {
+                windows_of.lateness_penalty
+                    * self.get_schedule_lateness(route, Some(time_windows), windows_of.objective)
+            }
+            None => 0.0,
+        }
+    }
+    /// Follow the schedule of a route through the time windows and sum up the lateness.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The sequence of nodes that is visited.
+    /// * `time_windows` - The time window of every node, `None` for no lateness.
+    /// * `objective` - Decides whether the route returns to its first node.
+    ///
+    fn get_schedule_lateness(
+        &self,
+        route: &[usize],
+        time_windows: Option<&[TimeWindow]>,
+        objective: Objective,
+    ) -> f64 {
+        let (Some(time_windows), Some(first)) = (time_windows, route.first()) else {
+            return 0.0;
+        };
+        let returns = !matches!(objective, Objective::Latency | Objective::PathLength);
+        let mut departure = time_windows[*first].earliest + time_windows[*first].service_time;
+        with_matrix!(&self.distances, |distances| {
+            let mut lateness = 0.0;
+            let mut previous = *first;
+            for node in route[1..].iter().chain(returns.then_some(first)) {
+                let window = time_windows[*node];
+                let start = (departure + distances.get(previous, *node)).max(window.earliest);
+                lateness += (start - window.latest).max(0.0);
+                departure = start + window.service_time;
+                previous = *node;
+            }
+            lateness
+        })
+    }
+    /// Compute how the cost of a route changes if the node at `move_idx` is moved in front of
+    /// the node at `put_before_idx`, as done by `Route::mutate`. Only the six legs around the
+    /// two positions are considered, so this is O(1) instead of the O(n) of `get_cost`.
+    /// Returns `None` if the cost of the objective can't be updated locally, e.g. for
+    /// `Latency`, `Bottleneck` or with several scenarios.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The sequence of nodes before the node is moved.
+    /// * `put_before_idx` - The position the node is moved in front of, may be `route.len()`.
+    /// * `move_idx` - The position of the node that is moved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 9.0, 1.0],
+    ///     vec![1.0, 0.0, 1.0, 9.0],
+    ///     vec![9.0, 1.0, 0.0, 1.0],
+    ///     vec![1.0, 9.0, 1.0, 0.0],
+    /// ]);
+    /// // Moving node 1 in front of node 2 turns [0, 2, 1, 3] into [0, 1, 2, 3].
+    /// let delta = distance_matrix.get_relocation_delta(&[0, 2, 1, 3], 1, 2).unwrap();
+    /// assert_eq!(distance_matrix.get_cost(&[0, 2, 1, 3]) + delta, distance_matrix.get_cost(&[0, 1, 2, 3]));
+    /// ```
+    pub fn get_relocation_delta(
+        &self,
+        route: &[usize],
+        put_before_idx: usize,
+        move_idx: usize,
+    ) -> Option<f64> {
+        match self.objective {
+            Objective::TourLength | Objective::Makespan
+                if self.scenarios.is_empty() && self.time_windows.is_none() => {}
+            _ => return None,
+        }
+        let n_nodes = route.len();
+        // Moving the first node to the end or the last node to the front only rotates the
+        // round-trip.
+        let rotates = (move_idx == 0 && put_before_idx == n_nodes)
+            || (move_idx + 1 == n_nodes && put_before_idx == 0);
+        if n_nodes < 3 || put_before_idx == move_idx || put_before_idx == move_idx + 1 || rotates {
+            return Some(0.0);
+        }
+        let moved = route[move_idx];
+        let before_moved = route[(move_idx + n_nodes - 1) % n_nodes];
+        let after_moved = route[(move_idx + 1) % n_nodes];
+        // The moved node ends up between these two nodes.
+        let before_insertion = route[(put_before_idx + n_nodes - 1) % n_nodes];
+        let after_insertion = route[put_before_idx % n_nodes];
+        Some(with_matrix!(&self.distances, |distances| {
+            distances.get(before_moved, after_moved)
+                - distances.get(before_moved, moved)
+                - distances.get(moved, after_moved)
+                + distances.get(before_insertion, moved)
+                + distances.get(moved, after_insertion)
+                - distances.get(before_insertion, after_insertion)
+        }))
+    }
+    /// Compute how the cost of a route changes if the nodes at the positions `first_idx + 1` up
+    /// to `last_idx` are reversed, e.g. the 2-opt move that replaces the legs after `first_idx`
+    /// and after `last_idx` by two new legs. Only these four legs are considered, so this is
+    /// O(1). Returns `None` if the cost can't be updated locally, e.g. for asymmetric matrices,
+    /// in which the reversed legs change their costs, for `Latency`, `Bottleneck` or with
+    /// several scenarios.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The sequence of nodes before the reversal.
+    /// * `first_idx` - The position of the last node before the reversed nodes.
+    /// * `last_idx` - The position of the last reversed node, larger than `first_idx`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 9.0, 1.0],
+    ///     vec![1.0, 0.0, 1.0, 9.0],
+    ///     vec![9.0, 1.0, 0.0, 1.0],
+    ///     vec![1.0, 9.0, 1.0, 0.0],
+    /// ]);
+    /// // Reversing [2, 1] turns [0, 2, 1, 3] into [0, 1, 2, 3].
+    /// let delta = distance_matrix.get_two_opt_delta(&[0, 2, 1, 3], 0, 2).unwrap();
+    /// assert_eq!(distance_matrix.get_cost(&[0, 2, 1, 3]) + delta, distance_matrix.get_cost(&[0, 1, 2, 3]));
+    /// ```
+    pub fn get_two_opt_delta(
+        &self,
+        route: &[usize],
+        first_idx: usize,
+        last_idx: usize,
+    ) -> Option<f64> {
+        match self.objective {
+            Objective::TourLength | Objective::Makespan
+                if self.scenarios.is_empty() && self.time_windows.is_none() => {}
+            _ => return None,
+        }
+        if !self.is_symmetric() {
+            return None;
+        }
+        let n_nodes = route.len();
+        let (before_first, first) = (route[first_idx], route[first_idx + 1]);
+        let (last, after_last) = (route[last_idx], route[(last_idx + 1) % n_nodes]);
+        Some(with_matrix!(&self.distances, |distances| {
+            distances.get(before_first, last) + distances.get(first, after_last)
+                - distances.get(before_first, first)
+                - distances.get(last, after_last)
+        }))
+    }
+    /// Compute how the cost of a round-trip changes if a chain of nodes from `first` to `last`
+    /// is appended to it, without the legs within the chain. Only the leg that closes the
+    /// round-trip and the two legs to and from the chain are considered, so this is O(1).
+    /// Returns `None` if the cost can't be updated locally, like `get_relocation_delta`.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The sequence of nodes the chain is appended to, it must not be empty.
+    /// * `first` - The first node of the chain.
+    /// * `last` - The last node of the chain.
+    ///
+    pub(crate) fn get_append_delta(
+        &self,
+        route: &[usize],
+        first: usize,
+        last: usize,
+    ) -> Option<f64> {
+        match self.objective {
+            Objective::TourLength | Objective::Makespan
+                if self.scenarios.is_empty() && self.time_windows.is_none() => {}
+            _ => return None,
+        }
+        let (route_first, route_last) = (route[0], route[route.len() - 1]);
+        Some(with_matrix!(&self.distances, |distances| {
+            distances.get(route_last, first) + distances.get(last, route_first)
+                - distances.get(route_last, route_first)
+        }))
+    }
+    /// Compute how the cost of a route changes if the chain of `chain_length` nodes starting at
+    /// `chain_start` is moved to another position without reversing it, the Or-opt move of
+    /// `Route::or_opt`. Only the six legs around the chain and its new position are considered,
+    /// so this is O(1). Returns `None` if the cost can't be updated locally, e.g. for `Latency`,
+    /// `Bottleneck` or with several scenarios.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The sequence of nodes before the chain is moved.
+    /// * `chain_start` - The position of the first node of the chain.
+    /// * `chain_length` - How many nodes the chain has, at most the number of nodes minus two.
+    /// * `insert_idx` - The position in the route without the chain that the chain is inserted
+    ///   at.
     ///
     /// # Examples
     ///
     /// ```
     /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
     ///
-    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 9.0, 1.0],
+    ///     vec![1.0, 0.0, 1.0, 9.0],
+    ///     vec![9.0, 1.0, 0.0, 1.0],
+    ///     vec![1.0, 9.0, 1.0, 0.0],
+    /// ]);
+    /// // Moving the chain [2] behind node 1 turns [0, 2, 1, 3] into [0, 1, 2, 3].
+    /// let delta = distance_matrix.get_or_opt_delta(&[0, 2, 1, 3], 1, 1, 2).unwrap();
+    /// assert_eq!(distance_matrix.get_cost(&[0, 2, 1, 3]) + delta, distance_matrix.get_cost(&[0, 1, 2, 3]));
     /// ```
-    pub fn new(distances: Vec<Vec<f64>>) -> Self {
-        DistanceMat { distances }
+    pub fn get_or_opt_delta(
+        &self,
+        route: &[usize],
+        chain_start: usize,
+        chain_length: usize,
+        insert_idx: usize,
+    ) -> Option<f64> {
+        match self.objective {
+            Objective::TourLength | Objective::Makespan
+                if self.scenarios.is_empty() && self.time_windows.is_none() => {}
+            _ => return None,
+        }
+        let n_nodes = route.len();
+        if chain_length == 0 || n_nodes < chain_length + 2 {
+            return Some(0.0);
+        }
+        let chain_end = chain_start + chain_length;
+        let n_rest = n_nodes - chain_length;
+        // The position in the route of a position in the route without the chain.
+        let rest = |rest_idx: usize| {
+            let rest_idx = rest_idx % n_rest;
+            if rest_idx < chain_start {
+                route[rest_idx]
+            } else {
+                route[rest_idx + chain_length]
+            }
+        };
+        let (first, last) = (route[chain_start], route[chain_end - 1]);
+        let before_chain = route[(chain_start + n_nodes - 1) % n_nodes];
+        let after_chain = route[chain_end % n_nodes];
+        // The chain ends up between these two nodes.
+        let before_insertion = rest(insert_idx + n_rest - 1);
+        let after_insertion = rest(insert_idx);
+        if before_insertion == before_chain && after_insertion == after_chain {
+            return Some(0.0);
+        }
+        Some(with_matrix!(&self.distances, |distances| {
+            distances.get(before_chain, after_chain)
+                - distances.get(before_chain, first)
+                - distances.get(last, after_chain)
+                + distances.get(before_insertion, first)
+                + distances.get(last, after_insertion)
+                - distances.get(before_insertion, after_insertion)
+        }))
     }
-    /// Get the number of nodes in the distance matrix, e.g. one of its dimensions.
+    /// Compute how the cost of a route changes by a 3-opt move, which removes the legs after
+    /// `first_idx`, `second_idx` and `third_idx` and reconnects the two segments between them
+    /// as described by `reconnection`, see `Route::three_opt`. Only these six legs are
+    /// considered, so this is O(1). Returns `None` if the cost can't be updated locally, e.g. for
+    /// a reconnection that reverses a segment of an asymmetric matrix, for `Latency`,
+    /// `Bottleneck` or with several scenarios.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The sequence of nodes before the move.
+    /// * `first_idx` - The position of the last node before the first segment.
+    /// * `second_idx` - The position of the last node of the first segment, larger than
+    ///   `first_idx`.
+    /// * `third_idx` - The position of the last node of the second segment, larger than
+    ///   `second_idx`.
+    /// * `reconnection` - How the segments are reconnected.
     ///
     /// # Examples
     ///
     /// ```
     /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::route::ThreeOptMove;
     ///
-    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
-    /// println!("{}", distance_matrix.n_units());
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 9.0, 1.0],
+    ///     vec![1.0, 0.0, 1.0, 9.0],
+    ///     vec![9.0, 1.0, 0.0, 1.0],
+    ///     vec![1.0, 9.0, 1.0, 0.0],
+    /// ]);
+    /// // Exchanging the segments [2] and [1] turns [0, 2, 1, 3] into [0, 1, 2, 3].
+    /// let delta = distance_matrix
+    ///     .get_three_opt_delta(&[0, 2, 1, 3], 0, 1, 2, ThreeOptMove::SegmentExchange)
+    ///     .unwrap();
+    /// assert_eq!(distance_matrix.get_cost(&[0, 2, 1, 3]) + delta, distance_matrix.get_cost(&[0, 1, 2, 3]));
     /// ```
-    pub fn n_units(&self) -> usize {
-        self.distances.len()
+    pub fn get_three_opt_delta(
+        &self,
+        route: &[usize],
+        first_idx: usize,
+        second_idx: usize,
+        third_idx: usize,
+        reconnection: ThreeOptMove,
+    ) -> Option<f64> {
+        match self.objective {
+            Objective::TourLength | Objective::Makespan
+                if self.scenarios.is_empty() && self.time_windows.is_none() => {}
+            _ => return None,
+        }
+        if reconnection != ThreeOptMove::SegmentExchange && !self.is_symmetric() {
+            return None;
+        }
+        let n_nodes = route.len();
+        let before_first = route[first_idx];
+        let (first_start, first_end) = (route[first_idx + 1], route[second_idx]);
+        let (second_start, second_end) = (route[second_idx + 1], route[third_idx]);
+        let after_second = route[(third_idx + 1) % n_nodes];
+        with_matrix!(&self.distances, |distances| {
+            let removed = distances.get(before_first, first_start)
+                + distances.get(first_end, second_start)
+                + distances.get(second_end, after_second);
+            let added = match reconnection {
+                ThreeOptMove::SegmentExchange => {
+                    distances.get(before_first, second_start)
+                        + distances.get(second_end, first_start)
+                        + distances.get(first_end, after_second)
+                }
+                ThreeOptMove::DoubleReversal => {
+                    distances.get(before_first, first_end)
+                        + distances.get(first_start, second_end)
+                        + distances.get(second_start, after_second)
+                }
+                ThreeOptMove::ExchangeReversedFirst => {
+                    distances.get(before_first, second_start)
+                        + distances.get(second_end, first_end)
+                        + distances.get(first_start, after_second)
+                }
+                ThreeOptMove::ExchangeReversedSecond => {
+                    distances.get(before_first, second_end)
+                        + distances.get(second_start, first_start)
+                        + distances.get(first_end, after_second)
+                }
+            };
+            Some(added - removed)
+        })
     }
-    /// Given a sequence of nodes (in a `Route`-object) compute the distance for the round-
-    /// trip between node 0..0
+    /// Compute how the cost of a route changes if the nodes at the positions `first_idx + 1` up
+    /// to `last_idx` are reversed, the 2-opt move of `Route::two_opt`. This is O(1) with
+    /// `get_two_opt_delta` whenever the objective allows it, otherwise the route is evaluated
+    /// before and after the move, so that every objective gets an answer.
     ///
     /// # Arguments
     ///
-    /// * `route` - The sequence of nodes that is visited and for which the round-trip-lenght
-    /// should be computed.
+    /// * `route` - The sequence of nodes before the reversal.
+    /// * `first_idx` - The position of the last node before the reversed nodes.
+    /// * `last_idx` - The position of the last reversed node, larger than `first_idx`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::objective::Objective;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 9.0, 1.0],
+    ///     vec![1.0, 0.0, 1.0, 9.0],
+    ///     vec![9.0, 1.0, 0.0, 1.0],
+    ///     vec![1.0, 9.0, 1.0, 0.0],
+    /// ]);
+    /// // Reversing [2, 1] turns [0, 2, 1, 3] into [0, 1, 2, 3].
+    /// assert_eq!(distance_matrix.delta_two_opt(&[0, 2, 1, 3], 0, 2), -16.0);
+    /// // The latency can't be updated locally, but the change is computed all the same.
+    /// let distance_matrix = distance_matrix.with_objective(Objective::Latency);
+    /// let delta = distance_matrix.delta_two_opt(&[0, 2, 1, 3], 0, 2);
+    /// assert_eq!(distance_matrix.get_cost(&[0, 2, 1, 3]) + delta, distance_matrix.get_cost(&[0, 1, 2, 3]));
+    /// ```
+    pub fn delta_two_opt(&self, route: &[usize], first_idx: usize, last_idx: usize) -> f64 {
+        self.get_two_opt_delta(route, first_idx, last_idx)
+            .unwrap_or_else(|| {
+                let mut moved = route.to_vec();
+                moved[(first_idx + 1)..=last_idx].reverse();
+                self.get_cost(&moved) - self.get_cost(route)
+            })
+    }
+    /// Compute how the cost of a route changes if the node at `move_idx` is moved in front of
+    /// the node at `put_before_idx`, the relocation of `Route::mutate`. This is O(1) with
+    /// `get_relocation_delta` whenever the objective allows it, otherwise the route is
+    /// evaluated before and after the move, so that every objective gets an answer.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The sequence of nodes before the node is moved.
+    /// * `put_before_idx` - The position the node is moved in front of, may be `route.len()`.
+    /// * `move_idx` - The position of the node that is moved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::objective::Objective;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 9.0, 1.0],
+    ///     vec![1.0, 0.0, 1.0, 9.0],
+    ///     vec![9.0, 1.0, 0.0, 1.0],
+    ///     vec![1.0, 9.0, 1.0, 0.0],
+    /// ]);
+    /// // Moving node 1 in front of node 2 turns [0, 2, 1, 3] into [0, 1, 2, 3].
+    /// assert_eq!(distance_matrix.delta_relocate(&[0, 2, 1, 3], 1, 2), -16.0);
+    /// let distance_matrix = distance_matrix.with_objective(Objective::Latency);
+    /// let delta = distance_matrix.delta_relocate(&[0, 2, 1, 3], 1, 2);
+    /// assert_eq!(distance_matrix.get_cost(&[0, 2, 1, 3]) + delta, distance_matrix.get_cost(&[0, 1, 2, 3]));
+    /// ```
+    pub fn delta_relocate(&self, route: &[usize], put_before_idx: usize, move_idx: usize) -> f64 {
+        self.get_relocation_delta(route, put_before_idx, move_idx)
+            .unwrap_or_else(|| {
+                let mut moved = route.to_vec();
+                move_elem(&mut moved, put_before_idx, move_idx);
+                self.get_cost(&moved) - self.get_cost(route)
+            })
+    }
+    /// Compute a lower bound of the cost of every route of the instance, e.g. to report how far
+    /// a route is from the optimum at most. This is the Held-Karp bound of `OneTree::new`, and
+    /// 0 for the objectives it doesn't cover. It takes up to 200 spanning trees, O(200·n²).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 9.0, 1.0],
+    ///     vec![1.0, 0.0, 1.0, 9.0],
+    ///     vec![9.0, 1.0, 0.0, 1.0],
+    ///     vec![1.0, 9.0, 1.0, 0.0],
+    /// ]);
+    /// let cost = distance_matrix.get_cost(&[0, 2, 1, 3]);
+    /// let gap = (cost - distance_matrix.lower_bound()) / distance_matrix.lower_bound();
+    /// assert_eq!(gap, 4.0);
+    /// ```
+    pub fn lower_bound(&self) -> f64 {
+        self.one_tree().map_or(0.0, |one_tree| one_tree.bound)
+    }
+    /// Compute the lower bound of `lower_bound` together with the 1-tree it was found with, see
+    /// `OneTree::new`. Returns `None` for the objectives the bound doesn't cover.
     ///
     /// # Examples
     ///
     /// ```
     /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
-    /// use genetic_algorithm_tsp::route::Route;
     ///
     /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
-    /// println!("{}", distance_matrix.get_distance(&vec![1,0,2]));
+    /// let one_tree = distance_matrix.one_tree().unwrap();
+    /// assert_eq!(one_tree.bound, 6.0);
+    /// assert!(one_tree.is_route());
+    /// ```
+    pub fn one_tree(&self) -> Option<OneTree> {
+        OneTree::new(self)
+    }
+    /// Compute the cost of several sub-tours, one per salesman, under the objective
+    /// of this distance matrix. The costs of the sub-tours are summed up, except for
+    /// `Makespan` and `Bottleneck` for which the maximum is taken. Empty sub-tours
+    /// don't contribute to the cost.
+    ///
+    /// # Arguments
+    ///
+    /// * `tours` - The sequences of nodes that are visited by the individual salesmen.
+    ///
+    /// # Examples
+    ///
     /// ```
-    pub fn get_distance(&self, route: &[usize]) -> f64 {
-        route
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::objective::Objective;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]])
+    ///     .with_objective(Objective::Makespan);
+    /// println!("{}", distance_matrix.get_tours_cost(&[vec![0,1], vec![2]]));
+    /// ```
+    pub fn get_tours_cost(&self, tours: &[Vec<usize>]) -> f64 {
+        let tour_costs = tours
             .iter()
-            .fold(
-                // By folding the indexes we get the distances between 1-2, 2-3, ... , (n-1)-n.
-                // Then we are missing n-0, therefore that's the initial value we choose in the `fold`-
-                // operator.
-                (self.distances[route[route.len() - 1]][route[0]], None),
-                |(mut loss, last_point): (f64, Option<usize>), current_point| {
-                    if let Some(last_point) = last_point {
-                        loss += &self.distances[last_point][*current_point];
+            .filter(|tour| !tour.is_empty())
+            .map(|tour| self.get_cost(tour));
+        match self.objective {
+            Objective::Makespan | Objective::Bottleneck => tour_costs.fold(0.0, f64::max),
+            Objective::TourLength | Objective::Latency | Objective::PathLength => tour_costs.sum(),
+        }
+    }
+    /// Create a sanity report of the distance matrix with statistics of the edge costs and the
+    /// number of entries that violate the assumptions of a symmetric, metric TSP. This helps
+    /// to catch data problems, like mismatching units, before running a long evolution. As all
+    /// triples of nodes are checked, this takes cubic time in the number of nodes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// println!("{}", distance_matrix.summary());
+    /// ```
+    pub fn summary(&self) -> DistanceMatSummary {
+        let n_units = self.n_units();
+        let mut summary = DistanceMatSummary {
+            n_units,
+            min_edge: f64::INFINITY,
+            max_edge: f64::NEG_INFINITY,
+            mean_edge: 0.0,
+            n_symmetry_violations: 0,
+            n_zero_off_diagonal: 0,
+            n_nonzero_diagonal: 0,
+            n_triangle_violations: 0,
+        };
+        let mut total_edge_cost = 0.0;
+        for i in 0..n_units {
+            if self.distances.get(i, i) != 0.0 {
+                summary.n_nonzero_diagonal += 1;
+            }
+            for j in (0..n_units).filter(|j| *j != i) {
+                let edge = self.distances.get(i, j);
+                summary.min_edge = summary.min_edge.min(edge);
+                summary.max_edge = summary.max_edge.max(edge);
+                total_edge_cost += edge;
+                if edge == 0.0 {
+                    summary.n_zero_off_diagonal += 1;
+                }
+                // Count every asymmetric pair only once.
+                if i < j && edge != self.distances.get(j, i) {
+                    summary.n_symmetry_violations += 1;
+                }
+                for k in (0..n_units).filter(|k| *k != i && *k != j) {
+                    if self.distances.get(i, k) > edge + self.distances.get(j, k) {
+                        summary.n_triangle_violations += 1;
                     }
-                    (loss, Some(*current_point))
-                },
-            )
-            .0
+                }
+            }
+        }
+        let _ = self.metric.set(summary.is_metric());
+        let _ = self.symmetric.set(summary.n_symmetry_violations == 0);
+        if n_units > 1 {
+            summary.mean_edge = total_edge_cost / (n_units * (n_units - 1)) as f64;
+        } else {
+            summary.min_edge = 0.0;
+            summary.max_edge = 0.0;
+        }
+        summary
+    }
+    /// Is the distance matrix a metric, e.g. symmetric, 0 on the diagonal and without
+    /// triangle-inequality violations? The result is verified with `summary` on the first call
+    /// and recorded in the distance matrix. Once it is recorded, local search prunes moves that
+    /// can only improve routes of non-metric instances, see `Route::or_opt`. Verifying it takes
+    /// O(n^3), so it is never done by the local search itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert!(distance_matrix.is_metric());
+    /// ```
+    pub fn is_metric(&self) -> bool {
+        // `summary` records the metricity itself.
+        match self.metric.get() {
+            Some(metric) => *metric,
+            None => self.summary().is_metric(),
+        }
     }
 
+    /// Is the distance from every node to every other node the same in both directions? Like
+    /// `is_metric`, the result is recorded in the distance matrix on the first call.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert!(distance_matrix.is_symmetric());
+    /// ```
+    pub fn is_symmetric(&self) -> bool {
+        *self.symmetric.get_or_init(|| {
+            (0..self.n_units()).all(|i| {
+                ((i + 1)..self.n_units())
+                    .all(|j| self.distances.get(i, j) == self.distances.get(j, i))
+            })
+        })
+    }
     /// Generate a random population suiting your distance mat.  
     ///
     /// # Arguments
@@ -87,40 +2478,719 @@ impl DistanceMat {
     /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
     /// println!("{}", distance_matrix.get_random_population(5));
     /// ```
+    #[cfg(feature = "std")]
     pub fn get_random_population(&self, n_routes: usize) -> routes::Routes {
         routes::Routes::random(n_routes, self.n_units())
     }
+    /// Convert the distances to another numeric type, e.g. to store a large matrix as `f32`.
+    /// The scenarios are converted as well, everything else is copied. A fitness cache keeps its
+    /// capacity but starts empty, because the costs of the routes change with their type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.4,2.0], vec![1.4,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let integer_matrix = distance_matrix.convert::<u32>();
+    /// assert_eq!(integer_matrix.get_distance(&[0, 1, 2]), Ok(6.0));
+    /// ```
+    pub fn convert<U: Distance>(&self) -> DistanceMat {
+        let mut converted = DistanceMat::from_distances(self.distances.convert::<U>())
+            .with_objective(self.objective)
+            .with_tie_breaking(self.tie_breaking)
+            .with_tour_equivalence(self.tour_equivalence)
+            .with_scenarios(
+                self.scenarios
+                    .iter()
+                    .map(DistanceMat::convert::<U>)
+                    .collect(),
+                self.aggregation,
+            );
+        converted.units = self.units;
+        converted.source = self.source.clone();
+        converted.coordinates = self.coordinates.clone();
+        converted.time_windows = self.time_windows.clone();
+        converted.lateness_penalty = self.lateness_penalty;
+        converted.candidate_list = self.candidate_list.clone();
+        #[cfg(feature = "std")]
+        {
+            converted.fitness_cache = self
+                .fitness_cache
+                .as_ref()
+                .map(|cache| FitnessCache::new(cache.capacity));
+        }
+        converted
+    }
+}
+
+impl DistanceMat {
+    /// Create a distance matrix from points in the plane, the distance between two points is
+    /// their euclidean distance. The points are kept as `coordinates` of the matrix.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - The `(x, y)`-coordinates of the nodes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::from_coordinates(&[(0.0, 0.0), (3.0, 0.0), (3.0, 4.0)]);
+    /// assert_eq!(distance_matrix.get_distance(&[0, 1, 2]), Ok(12.0));
+    /// ```
+    pub fn from_coordinates(points: &[(f64, f64)]) -> Self {
+        DistanceMat::new(pairwise_distances(
+            points,
+            |(x_from, y_from), (x_to, y_to)| (x_from - x_to).hypot(y_from - y_to),
+        ))
+        .with_coordinates(points)
+    }
+    /// Create a distance matrix from locations on earth, the distance between two locations is
+    /// their great-circle distance in kilometers (haversine formula on a sphere with the mean
+    /// radius of the earth). The units of the matrix are set to kilometers.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - The `(latitude, longitude)`-coordinates of the nodes in decimal degrees.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::{CostUnit, DistanceMat};
+    ///
+    /// // Berlin, Hamburg and Munich.
+    /// let distance_matrix = DistanceMat::from_lat_lon(&[(52.52, 13.405), (53.551, 9.994), (48.137, 11.575)]);
+    /// assert_eq!(distance_matrix.units(), Some(CostUnit::Kilometers));
+    /// let round_trip = distance_matrix.get_distance(&[0, 1, 2]).unwrap();
+    /// assert!(round_trip > 1300.0 && round_trip < 1400.0);
+    /// ```
+    pub fn from_lat_lon(points: &[(f64, f64)]) -> Self {
+        DistanceMat::new(pairwise_distances(points, great_circle_distance))
+            .with_units(CostUnit::Kilometers)
+            .with_coordinates(points)
+    }
+    /// Create a distance matrix from locations on earth like `from_lat_lon`, but attach the
+    /// coordinates projected with `projection` instead of the degrees, so that spatial
+    /// operators like `Route::spatial_crossover_with_rng` and plots work in kilometers. The
+    /// distances are still the great-circle distances.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - The `(latitude, longitude)`-coordinates of the nodes in decimal degrees.
+    /// * `projection` - How the coordinates are projected onto a plane.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::projection::Projection;
+    ///
+    /// let points = [(52.52, 13.405), (53.551, 9.994), (48.137, 11.575)];
+    /// let distance_matrix = DistanceMat::from_lat_lon_projected(&points, Projection::Utm);
+    /// assert_eq!(distance_matrix.coordinates(), Some(&Projection::Utm.project(&points)[..]));
+    /// assert_eq!(distance_matrix.get_distance(&[0, 1, 2]), DistanceMat::from_lat_lon(&points).get_distance(&[0, 1, 2]));
+    /// ```
+    pub fn from_lat_lon_projected(points: &[(f64, f64)], projection: Projection) -> Self {
+        DistanceMat::new(pairwise_distances(points, great_circle_distance))
+            .with_units(CostUnit::Kilometers)
+            .with_coordinates(&projection.project(points))
+    }
+    /// Read a TSPLIB `.tsp` file with `EUC_2D`, `GEO`, `ATT` or `EXPLICIT` edge weights, see
+    /// `tsplib::read_tsplib`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the `.tsp` file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use std::fs;
+    ///
+    /// let path = std::env::temp_dir().join("genetic_algorithm_tsp_doc_from_tsplib.tsp");
+    /// fs::write(&path, "DIMENSION: 2\nEDGE_WEIGHT_TYPE: EUC_2D\nNODE_COORD_SECTION\n1 0 0\n2 3 4\nEOF\n").unwrap();
+    /// assert_eq!(DistanceMat::from_tsplib(&path).unwrap().get_distance(&[0, 1]), Ok(10.0));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn from_tsplib(path: &std::path::Path) -> Result<Self, crate::tsplib::TsplibError> {
+        crate::tsplib::read_tsplib(path)
+    }
 }
 
 #[cfg(test)]
 mod test_distance_mat {
     use super::*;
     use crate::test_utils::test_dist_mat;
+    use crate::utils::change_order;
     #[test]
     fn test_constructor() {
         let dist_mat = DistanceMat::new(vec![vec![0.0, 1.0], vec![1.0, 0.0]]);
-        assert_eq!(dist_mat.distances, vec![vec![0.0, 1.0], vec![1.0, 0.0]]);
+        assert_eq!(
+            dist_mat.distances,
+            Distances::F64(FlatMatrix {
+                n_units: 2,
+                values: vec![0.0, 1.0, 1.0, 0.0]
+            })
+        );
+        assert_eq!(dist_mat.distance(1, 0), 1.0);
+        assert_eq!(dist_mat.metric.get(), None);
     }
     #[test]
     fn test_dist_same_node() {
-        assert_eq!(test_dist_mat().get_distance(&vec![0, 0]), 0.0);
+        assert_eq!(test_dist_mat().get_distance(&[0, 0]), Ok(0.0));
     }
     #[test]
     fn test_dist_two_nodes() {
-        assert_eq!(test_dist_mat().get_distance(&vec![0, 1]), 2.0);
-        assert_eq!(test_dist_mat().get_distance(&vec![0, 2]), 4.0);
-        assert_eq!(test_dist_mat().get_distance(&vec![1, 2]), 6.0);
+        assert_eq!(test_dist_mat().get_distance(&[0, 1]), Ok(2.0));
+        assert_eq!(test_dist_mat().get_distance(&[0, 2]), Ok(4.0));
+        assert_eq!(test_dist_mat().get_distance(&[1, 2]), Ok(6.0));
     }
     #[test]
     fn test_dist_three_nodes() {
-        assert_eq!(test_dist_mat().get_distance(&vec![0, 1, 2]), 6.0);
-        assert_eq!(test_dist_mat().get_distance(&vec![0, 2, 1]), 6.0);
+        assert_eq!(test_dist_mat().get_distance(&[0, 1, 2]), Ok(6.0));
+        assert_eq!(test_dist_mat().get_distance(&[0, 2, 1]), Ok(6.0));
     }
     #[test]
     fn test_dist_repeat_visit() {
-        assert_eq!(test_dist_mat().get_distance(&[0, 2, 1, 2]), 10.0);
+        assert_eq!(test_dist_mat().get_distance(&[0, 2, 1, 2]), Ok(10.0));
+    }
+    #[test]
+    fn test_dist_empty_route() {
+        assert_eq!(
+            test_dist_mat().get_distance(&[]),
+            Err(DistanceMatError::EmptyRoute)
+        );
+    }
+    #[test]
+    fn test_dist_out_of_bounds() {
+        assert_eq!(
+            test_dist_mat().get_distance(&[0, 3, 1]),
+            Err(DistanceMatError::IndexOutOfBounds {
+                index: 3,
+                n_units: 3
+            })
+        );
+    }
+    #[test]
+    fn test_dist_unchecked() {
+        assert_eq!(test_dist_mat().get_distance_unchecked(&[0, 1, 2]), 6.0);
+    }
+    #[test]
+    #[should_panic]
+    fn test_dist_unchecked_out_of_bounds() {
+        test_dist_mat().get_distance_unchecked(&[0, 3]);
+    }
+    #[test]
+    #[should_panic]
+    fn test_distance_to_unknown_node() {
+        // Node 3 would address the first leg of the next row in the flat storage.
+        test_dist_mat().distance(0, 3);
+    }
+    #[test]
+    fn test_try_new_rejects_invalid_matrices() {
+        assert_eq!(
+            DistanceMat::try_new(vec![vec![0.0, 1.0, 2.0], vec![1.0, 0.0, 3.0]]).unwrap_err(),
+            DistanceMatError::NotSquare {
+                row: 0,
+                length: 3,
+                n_units: 2
+            }
+        );
+        assert_eq!(
+            DistanceMat::try_new(vec![vec![0.0, 1.0], vec![-1.0, 0.0]]).unwrap_err(),
+            DistanceMatError::InvalidDistance { from: 1, to: 0 }
+        );
+        assert_eq!(
+            DistanceMat::try_new(vec![vec![0.0, f64::INFINITY], vec![1.0, 0.0]]).unwrap_err(),
+            DistanceMatError::InvalidDistance { from: 0, to: 1 }
+        );
+        assert_eq!(
+            DistanceMat::try_new(vec![vec![0.0, 1.0], vec![1.0, 0.0]])
+                .unwrap()
+                .n_units(),
+            2
+        );
+    }
+    #[test]
+    fn test_compute_cost_checked() {
+        let distance_mat = test_dist_mat().with_objective(Objective::Bottleneck);
+        assert_eq!(
+            distance_mat.compute_cost_checked(&Route::new(vec![0, 1, 2])),
+            Ok(3.0)
+        );
+        assert_eq!(
+            distance_mat.compute_cost_checked(&Route::new(vec![])),
+            Err(DistanceMatError::EmptyRoute)
+        );
+    }
+    #[test]
+    fn test_default_objective() {
+        assert_eq!(test_dist_mat().objective(), Objective::TourLength);
+        assert_eq!(test_dist_mat().get_cost(&[0, 1, 2]), 6.0);
+    }
+    #[test]
+    fn test_latency() {
+        // Arrive at 1 after 1.0, then at 2 after 1.0 + 3.0.
+        assert_eq!(test_dist_mat().get_latency(&[0, 1, 2]), 5.0);
+        // Arrive at 2 after 2.0, then at 1 after 2.0 + 3.0.
+        assert_eq!(test_dist_mat().get_latency(&[0, 2, 1]), 7.0);
+        assert_eq!(test_dist_mat().get_latency(&[1]), 0.0);
+    }
+    #[test]
+    fn test_cost_with_latency_objective() {
+        let dist_mat = test_dist_mat().with_objective(Objective::Latency);
+        assert_eq!(dist_mat.objective(), Objective::Latency);
+        assert_eq!(dist_mat.get_cost(&[0, 2, 1]), 7.0);
+    }
+    #[test]
+    fn test_bottleneck() {
+        assert_eq!(test_dist_mat().get_bottleneck(&[0, 1]), 1.0);
+        assert_eq!(test_dist_mat().get_bottleneck(&[0, 1, 2]), 3.0);
+        assert_eq!(test_dist_mat().get_bottleneck(&[0, 0]), 0.0);
+    }
+    #[test]
+    fn test_cost_with_bottleneck_objective() {
+        let dist_mat = test_dist_mat().with_objective(Objective::Bottleneck);
+        assert_eq!(dist_mat.get_cost(&[0, 2]), 2.0);
+    }
+    #[test]
+    fn test_path_length() {
+        assert_eq!(test_dist_mat().get_path_length(&[0, 1, 2]), 4.0);
+        assert_eq!(test_dist_mat().get_path_length(&[2]), 0.0);
+        let dist_mat = test_dist_mat().with_objective(Objective::PathLength);
+        assert_eq!(dist_mat.get_cost(&[1, 0, 2]), 3.0);
+        assert_eq!(dist_mat.get_relocation_delta(&[0, 1, 2], 0, 2), None);
+        // Rotations of an open path are different paths, its reverse is the same path.
+        assert_ne!(dist_mat.tour_key(&[1, 2, 0]), dist_mat.tour_key(&[0, 1, 2]));
+        assert_eq!(dist_mat.tour_key(&[2, 1, 0]), vec![0, 1, 2]);
+    }
+    #[test]
+    fn test_time_windows() {
+        let time_windows = vec![
+            TimeWindow::default(),
+            TimeWindow::new(0.0, 1.0, 0.0),
+            TimeWindow::new(0.0, 2.0, 0.0),
+        ];
+        let dist_mat = test_dist_mat().with_time_windows(time_windows.clone(), 1.0);
+        assert_eq!(dist_mat.get_lateness(&[0, 1, 2]), 2.0);
+        // Starting at node 1 serves node 2 at 3.0, node 0 at 5.0 and node 1 again at 6.0.
+        assert_eq!(dist_mat.get_lateness(&[1, 2, 0]), 6.0);
+        assert_eq!(dist_mat.get_two_opt_delta(&[0, 1, 2], 0, 2), None);
+        assert_eq!(dist_mat.tour_key(&[1, 2, 0]), vec![1, 2, 0]);
+        // Open paths don't return to their first node.
+        let open_path = test_dist_mat()
+            .with_objective(Objective::PathLength)
+            .with_time_windows(time_windows.clone(), 1.0);
+        assert_eq!(open_path.get_lateness(&[1, 2, 0]), 1.0);
+        // Every scenario follows its own travel times.
+        let slow = DistanceMat::new(vec![
+            vec![0.0, 2.0, 4.0],
+            vec![2.0, 0.0, 6.0],
+            vec![4.0, 6.0, 0.0],
+        ]);
+        let dist_mat = dist_mat.with_scenarios(vec![slow], ScenarioAggregation::WorstCase);
+        assert_eq!(dist_mat.get_cost(&[0, 1, 2]), 12.0 + 7.0);
+    }
+    #[test]
+    fn test_tours_cost_total() {
+        let tours = vec![vec![0, 1], vec![2], vec![]];
+        assert_eq!(test_dist_mat().get_tours_cost(&tours), 2.0);
+    }
+    #[test]
+    fn test_tours_cost_makespan() {
+        let dist_mat = test_dist_mat().with_objective(Objective::Makespan);
+        assert_eq!(dist_mat.get_cost(&[0, 1, 2]), 6.0);
+        assert_eq!(dist_mat.get_tours_cost(&[vec![0, 1], vec![0, 2]]), 4.0);
+        assert_eq!(dist_mat.get_tours_cost(&[]), 0.0);
+    }
+    #[test]
+    fn test_summary_metric() {
+        let summary = test_dist_mat().summary();
+        assert_eq!(
+            summary,
+            DistanceMatSummary {
+                n_units: 3,
+                min_edge: 1.0,
+                max_edge: 3.0,
+                mean_edge: 2.0,
+                n_symmetry_violations: 0,
+                n_zero_off_diagonal: 0,
+                n_nonzero_diagonal: 0,
+                n_triangle_violations: 0,
+            }
+        );
+        assert!(summary.is_metric());
+    }
+    #[test]
+    fn test_summary_violations() {
+        let summary = DistanceMat::new(vec![
+            vec![1.0, 1.0, 10.0],
+            vec![1.0, 0.0, 0.0],
+            vec![10.0, 2.0, 0.0],
+        ])
+        .summary();
+        assert_eq!(summary.n_symmetry_violations, 1);
+        assert_eq!(summary.n_zero_off_diagonal, 1);
+        assert_eq!(summary.n_nonzero_diagonal, 1);
+        // 0 -> 2 is more expensive than 0 -> 1 -> 2 and 2 -> 0 than 2 -> 1 -> 0.
+        assert_eq!(summary.n_triangle_violations, 2);
+        assert!(!summary.is_metric());
+    }
+    #[test]
+    fn test_summary_single_node() {
+        let summary = DistanceMat::new(vec![vec![0.0]]).summary();
+        assert_eq!(summary.min_edge, 0.0);
+        assert_eq!(summary.max_edge, 0.0);
+        assert!(summary.is_metric());
+    }
+    #[test]
+    fn test_is_metric() {
+        let dist_mat = test_dist_mat();
+        assert_eq!(dist_mat.metric.get(), None);
+        assert!(dist_mat.is_metric());
+        assert_eq!(dist_mat.metric.get(), Some(&true));
+    }
+    #[test]
+    fn relocations_are_only_skipped_on_verified_metrics() {
+        // Node 1 lies on the way from node 0 to node 2, so removing it saves nothing.
+        let dist_mat =
+            DistanceMat::from_coordinates(&[(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (1.0, 1.0)]);
+        assert!(!dist_mat.can_skip_relocation(&[0, 1, 2, 3], 1));
+        assert!(dist_mat.is_metric());
+        assert!(dist_mat.can_skip_relocation(&[0, 1, 2, 3], 1));
+        assert!(!dist_mat.can_skip_relocation(&[0, 1, 2, 3], 3));
+        let dist_mat = dist_mat.with_objective(Objective::Latency);
+        assert!(!dist_mat.can_skip_relocation(&[0, 1, 2, 3], 1));
+    }
+    #[test]
+    fn test_is_not_metric() {
+        let dist_mat = DistanceMat::new(vec![
+            vec![0.0, 1.0, 10.0],
+            vec![1.0, 0.0, 1.0],
+            vec![10.0, 1.0, 0.0],
+        ]);
+        assert!(!dist_mat.is_metric());
+    }
+    #[test]
+    fn test_summary_records_metricity() {
+        let dist_mat = test_dist_mat();
+        dist_mat.summary();
+        assert_eq!(dist_mat.metric.get(), Some(&true));
+    }
+    #[test]
+    fn test_relocation_delta() {
+        // An asymmetric matrix, so that the direction of every leg matters.
+        let dist_mat = DistanceMat::new(
+            (0..5)
+                .map(|from| (0..5).map(|to| ((3 * from + 7 * to) % 11) as f64).collect())
+                .collect(),
+        );
+        let route = vec![3, 0, 4, 1, 2];
+        for put_before_idx in 0..=route.len() {
+            for move_idx in 0..route.len() {
+                assert_eq!(
+                    dist_mat.get_cost(&route)
+                        + dist_mat
+                            .get_relocation_delta(&route, put_before_idx, move_idx)
+                            .unwrap(),
+                    dist_mat.get_cost(&change_order(&route, put_before_idx, move_idx))
+                );
+            }
+        }
+    }
+    #[test]
+    fn test_or_opt_delta() {
+        let dist_mat = DistanceMat::new(
+            (0..7)
+                .map(|from| (0..7).map(|to| ((3 * from + 7 * to) % 11) as f64).collect())
+                .collect(),
+        );
+        let route = vec![3, 0, 6, 4, 1, 5, 2];
+        for chain_length in 1..=3 {
+            for chain_start in 0..=(route.len() - chain_length) {
+                let chain = &route[chain_start..(chain_start + chain_length)];
+                let rest = route
+                    .iter()
+                    .filter(|node| !chain.contains(node))
+                    .copied()
+                    .collect::<Vec<usize>>();
+                for insert_idx in 0..=rest.len() {
+                    let mut moved = rest.clone();
+                    moved.splice(insert_idx..insert_idx, chain.iter().copied());
+                    assert_eq!(
+                        dist_mat.get_cost(&route)
+                            + dist_mat
+                                .get_or_opt_delta(&route, chain_start, chain_length, insert_idx)
+                                .unwrap(),
+                        dist_mat.get_cost(&moved)
+                    );
+                }
+            }
+        }
+    }
+    #[test]
+    fn test_three_opt_delta() {
+        let symmetric = DistanceMat::new(
+            (0..7)
+                .map(|from| {
+                    (0..7)
+                        .map(|to| ((7 * (from + to) + from * to) % 13) as f64)
+                        .collect()
+                })
+                .collect(),
+        );
+        let asymmetric = DistanceMat::new(
+            (0..7)
+                .map(|from| (0..7).map(|to| ((3 * from + 7 * to) % 11) as f64).collect())
+                .collect(),
+        );
+        let route = vec![3, 0, 6, 4, 1, 5, 2];
+        for first_idx in 0..5 {
+            for second_idx in (first_idx + 1)..6 {
+                for third_idx in (second_idx + 1)..7 {
+                    for reconnection in ThreeOptMove::ALL {
+                        let mut moved = route.clone();
+                        reconnection.apply(&mut moved, first_idx, second_idx, third_idx);
+                        let delta = symmetric
+                            .get_three_opt_delta(
+                                &route,
+                                first_idx,
+                                second_idx,
+                                third_idx,
+                                reconnection,
+                            )
+                            .unwrap();
+                        assert_eq!(
+                            symmetric.get_cost(&route) + delta,
+                            symmetric.get_cost(&moved)
+                        );
+                        // Only the exchange keeps the direction of the legs.
+                        let delta = asymmetric.get_three_opt_delta(
+                            &route,
+                            first_idx,
+                            second_idx,
+                            third_idx,
+                            reconnection,
+                        );
+                        if reconnection == ThreeOptMove::SegmentExchange {
+                            assert_eq!(
+                                asymmetric.get_cost(&route) + delta.unwrap(),
+                                asymmetric.get_cost(&moved)
+                            );
+                        } else {
+                            assert_eq!(delta, None);
+                        }
+                    }
+                }
+            }
+        }
     }
     #[test]
+    fn test_worst_case_scenario() {
+        let dist_mat = test_dist_mat().with_scenarios(
+            vec![
+                DistanceMat::new(vec![
+                    vec![0.0, 1.0, 2.0],
+                    vec![1.0, 0.0, 9.0],
+                    vec![2.0, 9.0, 0.0],
+                ]),
+                DistanceMat::new(vec![
+                    vec![0.0, 1.0, 1.0],
+                    vec![1.0, 0.0, 1.0],
+                    vec![1.0, 1.0, 0.0],
+                ]),
+            ],
+            ScenarioAggregation::WorstCase,
+        );
+        assert_eq!(dist_mat.n_scenarios(), 3);
+        assert_eq!(dist_mat.get_cost(&[0, 1, 2]), 12.0);
+        assert_eq!(dist_mat.get_relocation_delta(&[0, 1, 2], 0, 2), None);
+    }
+    #[test]
+    fn test_scenarios_use_objective_of_first_matrix() {
+        let dist_mat = test_dist_mat()
+            .with_scenarios(
+                vec![DistanceMat::new(vec![
+                    vec![0.0, 5.0, 2.0],
+                    vec![5.0, 0.0, 3.0],
+                    vec![2.0, 3.0, 0.0],
+                ])],
+                ScenarioAggregation::Average,
+            )
+            .with_objective(Objective::Bottleneck);
+        assert_eq!(dist_mat.get_cost(&[0, 1, 2]), 4.0);
+    }
+    #[test]
+    #[should_panic(expected = "The scenario at index 0 has 2 nodes")]
+    fn test_scenarios_need_the_same_nodes() {
+        test_dist_mat().with_scenarios(
+            vec![DistanceMat::new(vec![vec![0.0, 1.0], vec![1.0, 0.0]])],
+            ScenarioAggregation::Average,
+        );
+    }
+    #[test]
+    fn test_relocation_delta_of_rotations() {
+        // Moving the first node behind the last one, or the last one in front of the first,
+        // keeps the round-trip and must not change its cost.
+        let dist_mat = DistanceMat::new(
+            (0..5)
+                .map(|from| (0..5).map(|to| ((3 * from + 7 * to) % 11) as f64).collect())
+                .collect(),
+        );
+        let route = vec![3, 0, 4, 1, 2];
+        assert_eq!(dist_mat.get_relocation_delta(&route, 5, 0), Some(0.0));
+        assert_eq!(dist_mat.get_relocation_delta(&route, 0, 4), Some(0.0));
+        assert_eq!(
+            dist_mat.get_cost(&change_order(&route, 5, 0)),
+            dist_mat.get_cost(&route)
+        );
+    }
+    #[test]
+    fn test_relocation_delta_not_local() {
+        let dist_mat = test_dist_mat().with_objective(Objective::Latency);
+        assert_eq!(dist_mat.get_relocation_delta(&[0, 1, 2], 0, 2), None);
+    }
+    #[test]
+    fn test_from_coordinates_is_symmetric() {
+        let dist_mat = DistanceMat::from_coordinates(&[(0.0, 0.0), (1.0, 2.0), (-3.0, 1.5)]);
+        assert!(dist_mat.is_metric());
+        assert_eq!(dist_mat.units(), None);
+    }
+    #[test]
+    fn test_from_lat_lon() {
+        // One degree of latitude is about 111 km, the same location has distance 0.
+        let dist_mat = DistanceMat::from_lat_lon(&[(10.0, 20.0), (11.0, 20.0), (10.0, 20.0)]);
+        assert!((dist_mat.get_latency(&[0, 1]) - 111.19).abs() < 0.01);
+        assert_eq!(dist_mat.get_latency(&[0, 2]), 0.0);
+    }
+    #[test]
+    fn test_two_opt_delta_exhaustive() {
+        let dist_mat = DistanceMat::from_coordinates(&[
+            (0.0, 0.0),
+            (4.0, 1.0),
+            (2.0, 5.0),
+            (7.0, 3.0),
+            (1.0, 8.0),
+            (6.0, 6.0),
+        ]);
+        let route = [3, 0, 5, 1, 4, 2];
+        for first_idx in 0..(route.len() - 1) {
+            for last_idx in (first_idx + 1)..route.len() {
+                let mut reversed = route;
+                reversed[(first_idx + 1)..=last_idx].reverse();
+                let delta = dist_mat
+                    .get_two_opt_delta(&route, first_idx, last_idx)
+                    .unwrap();
+                assert!(
+                    (dist_mat.get_cost(&route) + delta - dist_mat.get_cost(&reversed)).abs() < 1e-9
+                );
+            }
+        }
+    }
+    #[test]
+    fn test_two_opt_delta_asymmetric() {
+        let dist_mat = DistanceMat::new(vec![
+            vec![0.0, 1.0, 2.0],
+            vec![1.0, 0.0, 3.0],
+            vec![2.0, 4.0, 0.0],
+        ]);
+        assert!(!dist_mat.is_symmetric());
+        assert_eq!(dist_mat.get_two_opt_delta(&[0, 1, 2], 0, 2), None);
+    }
+    #[test]
+    fn test_delta_moves() {
+        let symmetric = DistanceMat::from_coordinates(&[
+            (0.0, 0.0),
+            (4.0, 1.0),
+            (2.0, 5.0),
+            (7.0, 3.0),
+            (1.0, 8.0),
+            (6.0, 6.0),
+        ]);
+        let asymmetric = DistanceMat::new(
+            (0..6)
+                .map(|from| (0..6).map(|to| ((3 * from + 7 * to) % 11) as f64).collect())
+                .collect(),
+        );
+        let latency = DistanceMat::new(
+            (0..6)
+                .map(|from| (0..6).map(|to| ((3 * from + 7 * to) % 11) as f64).collect())
+                .collect(),
+        )
+        .with_objective(Objective::Latency);
+        let route = [3, 0, 5, 1, 4, 2];
+        for dist_mat in [symmetric, asymmetric, latency] {
+            let cost = dist_mat.get_cost(&route);
+            for first_idx in 0..(route.len() - 1) {
+                for last_idx in (first_idx + 1)..route.len() {
+                    let mut reversed = route;
+                    reversed[(first_idx + 1)..=last_idx].reverse();
+                    let delta = dist_mat.delta_two_opt(&route, first_idx, last_idx);
+                    assert!((cost + delta - dist_mat.get_cost(&reversed)).abs() < 1e-9);
+                }
+            }
+            for move_idx in 0..route.len() {
+                for put_before_idx in 0..=route.len() {
+                    let mut moved = route;
+                    move_elem(&mut moved, put_before_idx, move_idx);
+                    let delta = dist_mat.delta_relocate(&route, put_before_idx, move_idx);
+                    assert!((cost + delta - dist_mat.get_cost(&moved)).abs() < 1e-9);
+                }
+            }
+        }
+    }
+    #[test]
+    fn test_fingerprint() {
+        let fingerprint = test_dist_mat().metadata().fingerprint;
+        assert_eq!(
+            test_dist_mat()
+                .with_units(CostUnit::Meters)
+                .with_source("test")
+                .metadata()
+                .fingerprint,
+            fingerprint
+        );
+        let other = DistanceMat::new(vec![
+            vec![0.0, 1.0, 2.0],
+            vec![1.0, 0.0, 3.5],
+            vec![2.0, 3.5, 0.0],
+        ]);
+        assert_ne!(other.metadata().fingerprint, fingerprint);
+    }
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_fingerprint_hashes_all_distances_at_once() {
+        // Streaming the rows gives the hash of the whole byte sequence, so fingerprints stay
+        // comparable with the ones computed from a single buffer.
+        let mut bytes = 3u64.to_le_bytes().to_vec();
+        for distance in [0.0f64, 1.0, 2.0, 1.0, 0.0, 3.0, 2.0, 3.0, 0.0] {
+            bytes.extend_from_slice(&distance.to_bits().to_le_bytes());
+        }
+        assert_eq!(
+            test_dist_mat().metadata().fingerprint,
+            fasthash_fork::xx::hash64(bytes)
+        );
+    }
+    #[test]
+    fn test_check_units_without_units() {
+        assert_eq!(test_dist_mat().check_units(CostUnit::Hours), Ok(()));
+    }
+    #[test]
+    fn test_unit_mismatch_message() {
+        let error = test_dist_mat()
+            .with_units(CostUnit::Seconds)
+            .check_units(CostUnit::Meters)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "The distance matrix is given in seconds but meters were expected"
+        );
+    }
+    #[cfg(feature = "std")]
+    #[test]
     fn test_get_random_population() {
         let distance_matrix = DistanceMat::new(vec![
             vec![0.0, 1.0, 2.0],
@@ -129,4 +3199,196 @@ mod test_distance_mat {
         ]);
         distance_matrix.get_random_population(5);
     }
+    #[cfg(feature = "std")]
+    #[test]
+    fn fitness_cache_reuses_costs() {
+        let distance_mat = test_dist_mat().with_fitness_cache(2);
+        let route = Route::new(vec![0, 1, 2]);
+        let fitness = route.fitness(&distance_mat);
+        assert_eq!(route.fitness(&distance_mat), fitness);
+        assert_eq!(
+            distance_mat.fitness_cache_stats(),
+            Some(FitnessCacheStats {
+                hits: 1,
+                misses: 1,
+                len: 1
+            })
+        );
+    }
+    #[cfg(feature = "std")]
+    #[test]
+    fn fitness_cache_replaces_the_cost_in_a_full_slot() {
+        let distance_mat = test_dist_mat().with_fitness_cache(1);
+        let route = Route::new(vec![0, 1, 2]);
+        let other_route = Route::new(vec![1, 0, 2]);
+        route.fitness(&distance_mat);
+        other_route.fitness(&distance_mat);
+        // The other route took the only slot, so the route is evaluated again.
+        assert_eq!(
+            route.fitness(&distance_mat),
+            -distance_mat.get_cost(&route.indexes)
+        );
+        assert_eq!(
+            distance_mat.fitness_cache_stats(),
+            Some(FitnessCacheStats {
+                hits: 0,
+                misses: 3,
+                len: 1
+            })
+        );
+    }
+    #[cfg(feature = "std")]
+    #[test]
+    fn fitness_cache_is_shared_between_threads() {
+        let distance_mat = test_dist_mat().with_fitness_cache(100);
+        let routes = [vec![0, 1, 2], vec![1, 0, 2], vec![2, 0, 1]];
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for nodes in routes.iter().cycle().take(30) {
+                        let route = Route::new(nodes.clone());
+                        assert_eq!(route.fitness(&distance_mat), -distance_mat.get_cost(nodes));
+                    }
+                });
+            }
+        });
+        let stats = distance_mat.fitness_cache_stats().unwrap();
+        assert_eq!(stats.hits + stats.misses, 120);
+        assert!(stats.hits > 0 && stats.len <= routes.len());
+    }
+    #[cfg(feature = "std")]
+    #[test]
+    fn fitness_cache_is_kept_empty_by_convert() {
+        let distance_mat = test_dist_mat().with_fitness_cache(10);
+        Route::new(vec![0, 1, 2]).fitness(&distance_mat);
+        let converted = distance_mat.convert::<u32>();
+        assert_eq!(
+            converted.fitness_cache_stats(),
+            Some(FitnessCacheStats {
+                hits: 0,
+                misses: 0,
+                len: 0
+            })
+        );
+    }
+    #[cfg(feature = "std")]
+    #[test]
+    fn fitness_cache_is_emptied_by_new_objective() {
+        let route = Route::new(vec![0, 1, 2]);
+        let distance_mat = test_dist_mat().with_fitness_cache(10);
+        let round_trip_fitness = route.fitness(&distance_mat);
+        let distance_mat = distance_mat.with_objective(Objective::Latency);
+        assert_eq!(distance_mat.fitness_cache_stats().unwrap().len, 0);
+        assert_eq!(
+            route.fitness(&distance_mat),
+            -distance_mat.get_cost(&route.indexes)
+        );
+        assert_ne!(route.fitness(&distance_mat), round_trip_fitness);
+    }
+    #[cfg(feature = "regression")]
+    #[test]
+    fn serialized_as_rows() {
+        let json = serde_json::to_value(test_dist_mat()).unwrap();
+        assert_eq!(
+            json["distances"],
+            serde_json::json!([[0.0, 1.0, 2.0], [1.0, 0.0, 3.0], [2.0, 3.0, 0.0]])
+        );
+        let distance_mat: DistanceMat = serde_json::from_value(json).unwrap();
+        assert_eq!(distance_mat.distance(2, 1), 3.0);
+        let mut ragged = serde_json::to_value(test_dist_mat()).unwrap();
+        ragged["distances"] = serde_json::json!([[0.0, 1.0], [1.0]]);
+        assert!(serde_json::from_value::<DistanceMat>(ragged).is_err());
+    }
+    #[cfg(feature = "regression")]
+    #[test]
+    fn non_finite_distances_are_serialized_as_strings() {
+        let distance_mat = DistanceMat::new(vec![
+            vec![0.0, f64::INFINITY, 2.0],
+            vec![f64::NEG_INFINITY, 0.0, f64::NAN],
+            vec![2.0, 3.0, 0.0],
+        ]);
+        let json = serde_json::to_string(&distance_mat).unwrap();
+        assert!(
+            json.contains(r#"[0.0,"inf",2.0],["-inf",0.0,"NaN"]"#),
+            "{}",
+            json
+        );
+        let read: DistanceMat = serde_json::from_str(&json).unwrap();
+        assert_eq!(read.distance(0, 1), f64::INFINITY);
+        assert_eq!(read.distance(1, 0), f64::NEG_INFINITY);
+        assert!(read.distance(1, 2).is_nan());
+        assert_eq!(read.distance(2, 1), 3.0);
+        let json = serde_json::to_string(&distance_mat.convert::<f32>()).unwrap();
+        let read: DistanceMat = serde_json::from_str(&json).unwrap();
+        assert_eq!(read.distance(0, 1), f64::INFINITY);
+        // Integers can't be infinite.
+        let json = r#"{"distances": {"u32": [[0, "inf"], [1, 0]]}}"#;
+        assert!(serde_json::from_str::<DistanceMat>(json).is_err());
+        let json = r#"{"distances": [[0.0, "far"], [1.0, 0.0]]}"#;
+        assert!(serde_json::from_str::<DistanceMat>(json).is_err());
+    }
+    #[cfg(feature = "regression")]
+    #[test]
+    fn serialized_with_the_type_of_the_distances() {
+        let json = serde_json::to_value(test_dist_mat().convert::<u32>()).unwrap();
+        assert_eq!(
+            json["distances"],
+            serde_json::json!({"u32": [[0, 1, 2], [1, 0, 3], [2, 3, 0]]})
+        );
+        let distance_mat: DistanceMat = serde_json::from_value(json).unwrap();
+        assert_eq!(
+            distance_mat.distances,
+            test_dist_mat().convert::<u32>().distances
+        );
+    }
+    #[test]
+    fn generic_distances_give_the_same_costs() {
+        let route = [0, 2, 1];
+        let integer_mat =
+            DistanceMat::new_typed(vec![vec![0u32, 1, 2], vec![1, 0, 3], vec![2, 3, 0]]);
+        let float_mat = DistanceMat::new_typed(vec![
+            vec![0.0f32, 1.0, 2.0],
+            vec![1.0, 0.0, 3.0],
+            vec![2.0, 3.0, 0.0],
+        ]);
+        assert_eq!(
+            integer_mat.get_cost(&route),
+            test_dist_mat().get_cost(&route)
+        );
+        assert_eq!(float_mat.get_cost(&route), test_dist_mat().get_cost(&route));
+        assert_eq!(
+            Route::new(route.to_vec()).fitness(&integer_mat),
+            Route::new(route.to_vec()).fitness(&test_dist_mat())
+        );
+        assert_eq!(
+            integer_mat.metadata().fingerprint,
+            test_dist_mat().convert::<u32>().metadata().fingerprint
+        );
+        assert!(DistanceMat::try_new_typed(vec![vec![0.0f32, f32::NAN], vec![1.0, 0.0]]).is_err());
+    }
+    #[test]
+    fn new_stores_f64_distances_without_annotations() {
+        assert_eq!(DistanceMat::new(Vec::new()).n_units(), 0);
+        assert_eq!(DistanceMat::try_new(Vec::new()).unwrap().n_units(), 0);
+        assert_eq!(
+            DistanceMat::new(vec![vec![0.0, 1.0], vec![1.0, 0.0]]).distances,
+            DistanceMat::new_typed(vec![vec![0.0f64, 1.0], vec![1.0, 0.0]]).distances
+        );
+    }
+    #[test]
+    fn convert_rounds_integer_distances() {
+        let distance_mat = DistanceMat::new(vec![vec![0.0, 1.4], vec![1.6, 0.0]])
+            .with_objective(Objective::Latency);
+        let integer_mat = distance_mat.convert::<u16>();
+        assert_eq!(
+            integer_mat.distances,
+            Distances::U16(FlatMatrix {
+                n_units: 2,
+                values: vec![0, 1, 2, 0]
+            })
+        );
+        assert_eq!(integer_mat.objective, Objective::Latency);
+        let round_trip = distance_mat.convert::<f32>().convert::<f64>();
+        assert!((round_trip.distance(0, 1) - 1.4).abs() < 1e-6);
+    }
 }
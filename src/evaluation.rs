@@ -0,0 +1,927 @@
+use crate::distance_mat::DistanceMat;
+use crate::evolution::{Evolution, EvolutionConfig};
+use crate::routes::{benchmark, benchmark_population, BenchmarkResult, Routes};
+use genetic_algorithm_traits::Population;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// The settings of the genetic algorithm that should be evaluated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperatorConfig {
+    /// The name of the configuration as shown in the summary table.
+    pub name: String,
+    /// How many generations should the algorithm evolve?
+    pub n_generations: usize,
+    /// How many individuals should be selected at the end of each evolution step.
+    pub size_generation: usize,
+    /// How many threads should be used, 0 means single-threaded.
+    pub n_jobs: usize,
+}
+impl OperatorConfig {
+    /// Create a new configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the configuration as shown in the summary table.
+    /// * `n_generations` - How many generations should the algorithm evolve?
+    /// * `size_generation` - How many individuals should be selected at the end of each
+    ///   evolution step.
+    /// * `n_jobs` - How many threads should be used, 0 means single-threaded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evaluation::OperatorConfig;
+    ///
+    /// let config = OperatorConfig::new("small", 10, 10, 0);
+    /// ```
+    pub fn new(name: &str, n_generations: usize, size_generation: usize, n_jobs: usize) -> Self {
+        OperatorConfig {
+            name: name.to_string(),
+            n_generations,
+            size_generation,
+            n_jobs,
+        }
+    }
+    /// The default configuration for an instance with `n_nodes` nodes, as used by `solve_tsp`
+    /// when no configuration is given. The defaults were chosen by benchmarking random euclidean
+    /// instances: small instances profit from larger generations, while for larger instances
+    /// more generations of a small population give better routes in the same time. The
+    /// configurations are single-threaded, as every thread only runs a share of the generations.
+    ///
+    /// | nodes      | generations | size of a generation |
+    /// |------------|-------------|----------------------|
+    /// | < 100      | 1000        | 20                   |
+    /// | 100 - 1000 | 2000        | 10                   |
+    /// | > 1000     | 4000        | 10                   |
+    ///
+    /// # Arguments
+    ///
+    /// * `n_nodes` - The number of nodes of the instance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evaluation::OperatorConfig;
+    ///
+    /// let config = OperatorConfig::for_instance_size(52);
+    /// assert_eq!(config.size_generation, 20);
+    /// ```
+    pub fn for_instance_size(n_nodes: usize) -> Self {
+        if n_nodes < 100 {
+            OperatorConfig::new("small", 1000, 20, 0)
+        } else if n_nodes <= 1000 {
+            OperatorConfig::new("medium", 2000, 10, 0)
+        } else {
+            OperatorConfig::new("large", 4000, 10, 0)
+        }
+    }
+    /// Create a configuration for every combination of the number of generations and the size of
+    /// a generation, e.g. as grid for `benchmark_sweep`. The configurations are named
+    /// `<n_generations>x<size_generation>` and ordered by the number of generations first.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_generations` - The numbers of generations that should be combined.
+    /// * `size_generations` - The sizes of a generation that should be combined.
+    /// * `n_jobs` - How many threads every configuration should use, 0 means single-threaded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evaluation::OperatorConfig;
+    ///
+    /// let grid = OperatorConfig::grid(&[10, 20], &[5, 10, 15], 0);
+    /// assert_eq!(grid.len(), 6);
+    /// assert_eq!(grid[1], OperatorConfig::new("10x10", 10, 10, 0));
+    /// ```
+    pub fn grid(n_generations: &[usize], size_generations: &[usize], n_jobs: usize) -> Vec<Self> {
+        n_generations
+            .iter()
+            .flat_map(|n_generations| {
+                size_generations.iter().map(move |size_generation| {
+                    OperatorConfig::new(
+                        &format!("{}x{}", n_generations, size_generation),
+                        *n_generations,
+                        *size_generation,
+                        n_jobs,
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+/// The result of benchmarking one configuration of a sweep.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepResult {
+    /// The configuration that was benchmarked.
+    pub config: OperatorConfig,
+    /// The run time, the best fitness and, with `memory-stats`, the allocations of the run.
+    pub result: BenchmarkResult,
+}
+impl fmt::Display for SweepResult {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "n_generations: {}, size_generation: {}, time: {} ms, minimal loss: {}",
+            self.config.n_generations,
+            self.config.size_generation,
+            self.result.run_time,
+            self.result.minimal_loss
+        )?;
+        if self.config.n_jobs > 0 {
+            write!(formatter, ", n_jobs: {}", self.config.n_jobs)?;
+        }
+        #[cfg(feature = "memory-stats")]
+        write!(formatter, ", allocations: {:?}", self.result.allocations)?;
+        Ok(())
+    }
+}
+
+/// Benchmark every configuration of a grid once on a random initial population, like the
+/// `benchmark` command of the `genetic-tsp` binary does.
+///
+/// # Arguments
+///
+/// * `config_grid` - The configurations that should be benchmarked, e.g. from
+///   `OperatorConfig::grid`.
+/// * `distance_mat` - The instance the configurations are benchmarked on.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::evaluation::{benchmark_sweep, OperatorConfig};
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+///
+/// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+/// let results = benchmark_sweep(&OperatorConfig::grid(&[5, 10], &[3, 6], 0), &distance_matrix);
+/// for result in &results {
+///     println!("{}", result);
+/// }
+/// assert_eq!(results.len(), 4);
+/// ```
+pub fn benchmark_sweep(
+    config_grid: &[OperatorConfig],
+    distance_mat: &DistanceMat,
+) -> Vec<SweepResult> {
+    config_grid
+        .iter()
+        .map(|config| SweepResult {
+            config: config.clone(),
+            result: benchmark(
+                config.n_generations,
+                config.size_generation,
+                distance_mat,
+                config.n_jobs,
+            ),
+        })
+        .collect()
+}
+
+/// How close a configuration gets to the known optimum of an instance over several runs, as
+/// returned by `benchmark_against_optimum`. This is how results on instances with a known
+/// optimum, e.g. the TSPLIB instances, are usually reported: the gap of the best route of every
+/// run to the optimum, how often a run reaches the target gap and how long it takes to get
+/// there.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptimumComparison {
+    /// The configuration that was benchmarked.
+    pub config: EvolutionConfig,
+    /// The cost of an optimal route of the instance.
+    pub known_optimum: f64,
+    /// The gap to the optimum at which a run counts as a success.
+    pub target_gap: f64,
+    /// The gap of the best route of every run to the optimum, relative to the optimum.
+    pub gaps: Vec<f64>,
+    /// How long every run took to reach the target gap, `None` if it didn't reach it.
+    pub times_to_target: Vec<Option<Duration>>,
+}
+impl OptimumComparison {
+    /// The smallest gap of all runs, `None` without any run.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evaluation::benchmark_against_optimum;
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::evolution::EvolutionConfig;
+    ///
+    /// let config = EvolutionConfig::new().with_n_generations(5).with_size_generation(3);
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let comparison = benchmark_against_optimum(&distance_matrix, 6.0, &config, 2, 0.0);
+    /// assert_eq!(comparison.best_gap(), Some(0.0));
+    /// ```
+    pub fn best_gap(&self) -> Option<f64> {
+        self.gaps.iter().copied().reduce(f64::min)
+    }
+    /// The mean gap of all runs, `None` without any run.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evaluation::benchmark_against_optimum;
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::evolution::EvolutionConfig;
+    ///
+    /// let config = EvolutionConfig::new().with_n_generations(5).with_size_generation(3);
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let comparison = benchmark_against_optimum(&distance_matrix, 6.0, &config, 0, 0.0);
+    /// assert_eq!(comparison.mean_gap(), None);
+    /// ```
+    pub fn mean_gap(&self) -> Option<f64> {
+        if self.gaps.is_empty() {
+            None
+        } else {
+            Some(self.gaps.iter().sum::<f64>() / self.gaps.len() as f64)
+        }
+    }
+    /// The fraction of the runs that reached the target gap, 0 without any run.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evaluation::benchmark_against_optimum;
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::evolution::EvolutionConfig;
+    ///
+    /// let config = EvolutionConfig::new().with_n_generations(5).with_size_generation(3);
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let comparison = benchmark_against_optimum(&distance_matrix, 6.0, &config, 4, 0.0);
+    /// assert_eq!(comparison.success_rate(), 1.0);
+    /// ```
+    pub fn success_rate(&self) -> f64 {
+        if self.times_to_target.is_empty() {
+            return 0.0;
+        }
+        self.times_to_target.iter().flatten().count() as f64 / self.times_to_target.len() as f64
+    }
+    /// The mean time to the target gap of the runs that reached it, `None` if none did.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evaluation::benchmark_against_optimum;
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::evolution::EvolutionConfig;
+    ///
+    /// let config = EvolutionConfig::new().with_n_generations(5).with_size_generation(3);
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// // No route is cheaper than 6, so no run reaches a made-up optimum of 5.
+    /// let comparison = benchmark_against_optimum(&distance_matrix, 5.0, &config, 2, 0.0);
+    /// assert_eq!(comparison.mean_time_to_target(), None);
+    /// ```
+    pub fn mean_time_to_target(&self) -> Option<Duration> {
+        let times = self
+            .times_to_target
+            .iter()
+            .flatten()
+            .collect::<Vec<&Duration>>();
+        if times.is_empty() {
+            None
+        } else {
+            Some(times.iter().copied().sum::<Duration>() / times.len() as u32)
+        }
+    }
+}
+impl fmt::Display for OptimumComparison {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "{}x{}: n_runs: {}, best gap: {:.2}%, mean gap: {:.2}%, success rate: {:.0}% at {:.2}%",
+            self.config.n_generations,
+            self.config.size_generation,
+            self.gaps.len(),
+            100.0 * self.best_gap().unwrap_or(f64::NAN),
+            100.0 * self.mean_gap().unwrap_or(f64::NAN),
+            100.0 * self.success_rate(),
+            100.0 * self.target_gap
+        )?;
+        if let Some(time) = self.mean_time_to_target() {
+            write!(formatter, ", mean time to target: {:?}", time)?;
+        }
+        Ok(())
+    }
+}
+
+/// Run a configuration several times on an instance with a known optimum and compare the best
+/// routes to it, see `OptimumComparison`. Every run starts from a random population and is
+/// evolved generation by generation as a stepwise `Evolution`, so that the time at which it
+/// reaches the target gap can be measured, including the time to find the best route of every
+/// generation. The `i`-th run is seeded with `i`, so that the gaps are reproducible, and like
+/// every stepwise evolution it is single-threaded, `n_jobs` of the configuration is ignored.
+///
+/// # Arguments
+///
+/// * `distance_mat` - The instance the configuration is benchmarked on.
+/// * `known_optimum` - The cost of an optimal route of the instance, e.g. the best known value
+///   of a TSPLIB instance.
+/// * `config` - The settings of the genetic algorithm.
+/// * `n_runs` - How often the configuration is run.
+/// * `target_gap` - The gap to the optimum, relative to the optimum, at which a run counts as a
+///   success, e.g. 0.0 for the optimum or 0.01 for one percent above it.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::evaluation::benchmark_against_optimum;
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+/// use genetic_algorithm_tsp::evolution::EvolutionConfig;
+/// use genetic_algorithm_tsp::route::MutationOperator;
+///
+/// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+/// let config = EvolutionConfig::new()
+///     .with_n_generations(5)
+///     .with_size_generation(3)
+///     .with_mutation_operator(MutationOperator::Swap);
+/// let comparison = benchmark_against_optimum(&distance_matrix, 6.0, &config, 3, 0.0);
+/// println!("{}", comparison);
+/// assert_eq!(comparison.gaps, vec![0.0, 0.0, 0.0]);
+/// ```
+pub fn benchmark_against_optimum(
+    distance_mat: &DistanceMat,
+    known_optimum: f64,
+    config: &EvolutionConfig,
+    n_runs: usize,
+    target_gap: f64,
+) -> OptimumComparison {
+    let gap = |cost: f64| (cost - known_optimum) / known_optimum.abs().max(f64::EPSILON);
+    // Rounding errors of the costs shouldn't decide whether the optimum was found.
+    let reaches_target = |cost: f64| gap(cost) <= target_gap + 1e-9;
+    let (gaps, times_to_target) = (0..n_runs)
+        .map(|run| {
+            let seed = run as u64;
+            let initial_population = Routes::random_with_rng(
+                config.size_generation,
+                distance_mat.n_units(),
+                &mut StdRng::seed_from_u64(seed),
+            );
+            let mut evolution =
+                Evolution::new(distance_mat, *config).start(initial_population, seed);
+            let mut best_cost = population_best_cost(evolution.population(), distance_mat);
+            let mut elapsed = Duration::ZERO;
+            let mut time_to_target = Some(elapsed).filter(|_| reaches_target(best_cost));
+            while !evolution.is_finished() {
+                let start = Instant::now();
+                let population = evolution.evolve(1);
+                best_cost = best_cost.min(population_best_cost(population, distance_mat));
+                elapsed += start.elapsed();
+                if time_to_target.is_none() && reaches_target(best_cost) {
+                    time_to_target = Some(elapsed);
+                }
+            }
+            (gap(best_cost), time_to_target)
+        })
+        .unzip();
+    OptimumComparison {
+        config: *config,
+        known_optimum,
+        target_gap,
+        gaps,
+        times_to_target,
+    }
+}
+
+/// The cost of the fittest route of a population, infinite if it is empty.
+fn population_best_cost(population: &Routes, distance_mat: &DistanceMat) -> f64 {
+    population
+        .get_n_fittest(1, distance_mat)
+        .first()
+        .map_or(f64::INFINITY, |route| -route.fitness(distance_mat))
+}
+
+/// The aggregated result of running one configuration several times on one instance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvaluationSummary {
+    /// The name of the instance, e.g. its file name without extension.
+    pub instance: String,
+    /// The name of the configuration.
+    pub config: String,
+    /// How often the configuration was run on the instance.
+    pub n_runs: usize,
+    /// The best final fitness over all runs.
+    pub best_fitness: f64,
+    /// The mean final fitness over all runs.
+    pub mean_fitness: f64,
+    /// The worst final fitness over all runs.
+    pub worst_fitness: f64,
+    /// The mean run time in milliseconds.
+    pub mean_run_time: f64,
+}
+
+/// The summary table of an evaluation with one row per instance and configuration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvaluationTable {
+    /// The rows of the table, ordered by instance and then by configuration.
+    pub rows: Vec<EvaluationSummary>,
+}
+impl fmt::Display for EvaluationTable {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            formatter,
+            "instance\tconfig\tn_runs\tbest_fitness\tmean_fitness\tworst_fitness\tmean_time_ms"
+        )?;
+        for row in &self.rows {
+            writeln!(
+                formatter,
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                row.instance,
+                row.config,
+                row.n_runs,
+                row.best_fitness,
+                row.mean_fitness,
+                row.worst_fitness,
+                row.mean_run_time
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The projected run time of a configuration, extrapolated from a few sample generations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuntimeEstimate {
+    /// How many generations were run to measure the time of a single generation.
+    pub n_sample_generations: usize,
+    /// The time it took to run the sample generations.
+    pub sample_time: Duration,
+    /// The projected time of the full run.
+    pub projected_time: Duration,
+    /// The time the full run is allowed to take.
+    pub budget: Duration,
+}
+impl RuntimeEstimate {
+    /// Is the projected time of the full run larger than the budget?
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evaluation::{estimate_runtime, OperatorConfig};
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use std::time::Duration;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let estimate = estimate_runtime(
+    ///     &distance_matrix, &OperatorConfig::new("small", 100, 5, 0), 2, Duration::from_secs(60), false
+    /// ).unwrap();
+    /// assert!(!estimate.exceeds_budget());
+    /// ```
+    pub fn exceeds_budget(&self) -> bool {
+        self.projected_time > self.budget
+    }
+}
+impl fmt::Display for RuntimeEstimate {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "Projected run time of {:?} (budget {:?}), extrapolated from {} generations in {:?}",
+            self.projected_time, self.budget, self.n_sample_generations, self.sample_time
+        )
+    }
+}
+
+/// The error returned by `estimate_runtime` in strict mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EstimateError {
+    /// The projected time of the full run is larger than the budget.
+    BudgetExceeded(RuntimeEstimate),
+}
+impl fmt::Display for EstimateError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EstimateError::BudgetExceeded(estimate) => {
+                write!(formatter, "Budget exceeded: {}", estimate)
+            }
+        }
+    }
+}
+impl Error for EstimateError {}
+
+/// Estimate the run time of a configuration before starting it. A random population is
+/// evolved for `n_sample_generations` generations and the time per generation is extrapolated
+/// to the full run. With `n_jobs` threads every thread evolves `n_generations / n_jobs + 1`
+/// generations in parallel. If the projected time exceeds the budget, the estimate is returned
+/// for the caller to check with `RuntimeEstimate::exceeds_budget`, or in `strict` mode an error
+/// is returned.
+///
+/// # Arguments
+///
+/// * `distance_mat` - The distance matrix of the instance that should be solved.
+/// * `config` - The configuration whose run time should be estimated.
+/// * `n_sample_generations` - How many generations should be run to measure the time?
+/// * `budget` - The time the full run is allowed to take.
+/// * `strict` - Should exceeding the budget be an error?
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::evaluation::{estimate_runtime, OperatorConfig};
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+/// use std::time::Duration;
+///
+/// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+/// let estimate = estimate_runtime(
+///     &distance_matrix, &OperatorConfig::new("small", 100, 5, 0), 2, Duration::from_secs(60), true
+/// );
+/// let estimate = estimate.unwrap();
+/// if estimate.exceeds_budget() {
+///     println!("Warning: {}", estimate);
+/// }
+/// ```
+pub fn estimate_runtime(
+    distance_mat: &DistanceMat,
+    config: &OperatorConfig,
+    n_sample_generations: usize,
+    budget: Duration,
+    strict: bool,
+) -> Result<RuntimeEstimate, EstimateError> {
+    let n_sample_generations = n_sample_generations.max(1);
+    let population = Routes::random(config.size_generation, distance_mat.n_units());
+    let before = Instant::now();
+    (0..n_sample_generations).fold(population, |pop, _| {
+        pop.evolve(0.5)
+            .get_fittest_population(config.size_generation, distance_mat)
+    });
+    let sample_time = before.elapsed();
+    // With threads, every thread evolves its generations in parallel.
+    let n_sequential_generations = config
+        .n_generations
+        .checked_div(config.n_jobs)
+        .map_or(config.n_generations, |n_generations| n_generations + 1);
+    let estimate = RuntimeEstimate {
+        n_sample_generations,
+        sample_time,
+        projected_time: Duration::from_nanos(
+            (sample_time.as_nanos() * n_sequential_generations as u128
+                / n_sample_generations as u128) as u64,
+        ),
+        budget,
+    };
+    if strict && estimate.exceeds_budget() {
+        return Err(EstimateError::BudgetExceeded(estimate));
+    }
+    Ok(estimate)
+}
+
+/// Read a distance matrix in the format of `tests/test-data/distances.txt`, e.g. one row per
+/// line with `;`-separated distances. The matrix has to be square and all distances have to be
+/// finite and non-negative, see `DistanceMat::try_new`.
+///
+/// # Arguments
+///
+/// * `path` - The path to the file containing the matrix.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::evaluation::read_instance;
+/// use std::fs;
+///
+/// let path = std::env::temp_dir().join("genetic_algorithm_tsp_doc_read_instance.txt");
+/// fs::write(&path, "0;1;2\n1;0;3\n2;3;0").unwrap();
+/// assert_eq!(read_instance(&path).unwrap().n_units(), 3);
+/// ```
+pub fn read_instance(path: &Path) -> io::Result<DistanceMat> {
+    fs::read_to_string(path)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            line.split(';')
+                .map(|float_string| {
+                    float_string.trim().parse::<f64>().map_err(|error| {
+                        io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("{}: {}", path.display(), error),
+                        )
+                    })
+                })
+                .collect::<io::Result<Vec<f64>>>()
+        })
+        .collect::<io::Result<Vec<Vec<f64>>>>()
+        .and_then(|distances| {
+            DistanceMat::try_new(distances).map_err(|error| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{}: {}", path.display(), error),
+                )
+            })
+        })
+}
+
+/// Run every configuration `n_runs` times on every instance in a directory and summarize
+/// the results. Every file in the directory has to be a distance matrix in the format of
+/// `tests/test-data/distances.txt`.
+///
+/// # Arguments
+///
+/// * `directory` - The directory containing the instances.
+/// * `configs` - The configurations that should be evaluated.
+/// * `n_runs` - How often every configuration should be run on every instance, at least 1.
+///
+/// # Errors
+///
+/// Fails if `n_runs` is 0, as nothing could be summarized, if the directory can't be read or
+/// if one of its files is not a valid distance matrix.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::evaluation::{evaluate_instances, OperatorConfig};
+/// use std::fs;
+///
+/// let directory = std::env::temp_dir().join("genetic_algorithm_tsp_doc_evaluate_instances");
+/// fs::create_dir_all(&directory).unwrap();
+/// fs::write(directory.join("three.txt"), "0;1;2\n1;0;3\n2;3;0").unwrap();
+/// let table = evaluate_instances(
+///     &directory,
+///     &[OperatorConfig::new("small", 5, 3, 0), OperatorConfig::new("large", 10, 6, 0)],
+///     3,
+/// ).unwrap();
+/// println!("{}", table);
+/// ```
+pub fn evaluate_instances(
+    directory: &Path,
+    configs: &[OperatorConfig],
+    n_runs: usize,
+) -> io::Result<EvaluationTable> {
+    if n_runs == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "every configuration has to be run at least once",
+        ));
+    }
+    let mut instance_paths = fs::read_dir(directory)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    instance_paths.retain(|path| path.is_file());
+    instance_paths.sort();
+
+    let mut rows = Vec::with_capacity(instance_paths.len() * configs.len());
+    for path in instance_paths {
+        let distance_mat = read_instance(&path)?;
+        let instance = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        for config in configs {
+            let runs = (0..n_runs)
+                .map(|_| {
+                    benchmark_population(
+                        config.n_generations,
+                        config.size_generation,
+                        &distance_mat,
+                        config.n_jobs,
+                    )
+                })
+                .collect::<Vec<(u64, f64)>>();
+            rows.push(EvaluationSummary {
+                instance: instance.clone(),
+                config: config.name.clone(),
+                n_runs,
+                best_fitness: runs
+                    .iter()
+                    .map(|(_, fitness)| *fitness)
+                    .fold(f64::NEG_INFINITY, f64::max),
+                mean_fitness: runs.iter().map(|(_, fitness)| fitness).sum::<f64>() / n_runs as f64,
+                worst_fitness: runs
+                    .iter()
+                    .map(|(_, fitness)| *fitness)
+                    .fold(f64::INFINITY, f64::min),
+                mean_run_time: runs.iter().map(|(time, _)| *time as f64).sum::<f64>()
+                    / n_runs as f64,
+            });
+        }
+    }
+    Ok(EvaluationTable { rows })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exact::held_karp;
+    use crate::route::{CrossoverOperator, MutationOperator};
+    use crate::selection::Selection;
+    use std::path::PathBuf;
+
+    fn test_directory(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("genetic_algorithm_tsp_{}", name));
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+    #[test]
+    fn read_valid_instance() {
+        let directory = test_directory("read_valid_instance");
+        fs::write(directory.join("two.txt"), "0;1\n1;0\n").unwrap();
+        assert_eq!(
+            read_instance(&directory.join("two.txt")).unwrap().n_units(),
+            2
+        );
+    }
+    #[test]
+    fn read_invalid_instance() {
+        let directory = test_directory("read_invalid_instance");
+        fs::write(directory.join("names.txt"), "Berlin\nParis\n").unwrap();
+        assert_eq!(
+            read_instance(&directory.join("names.txt"))
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+    #[test]
+    fn read_ragged_instance() {
+        let directory = test_directory("read_ragged_instance");
+        fs::write(directory.join("ragged.txt"), "0;1\n1\n").unwrap();
+        assert_eq!(
+            read_instance(&directory.join("ragged.txt"))
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+    #[test]
+    fn estimate_within_budget() {
+        let distance_mat = DistanceMat::new(vec![
+            vec![0.0, 1.0, 2.0],
+            vec![1.0, 0.0, 3.0],
+            vec![2.0, 3.0, 0.0],
+        ]);
+        let estimate = estimate_runtime(
+            &distance_mat,
+            &OperatorConfig::new("small", 10, 3, 0),
+            2,
+            Duration::from_secs(3600),
+            true,
+        )
+        .unwrap();
+        assert_eq!(estimate.n_sample_generations, 2);
+        assert_eq!(estimate.projected_time, estimate.sample_time * 5);
+        assert!(!estimate.exceeds_budget());
+    }
+    #[test]
+    fn estimate_multi_threaded() {
+        let distance_mat = DistanceMat::new(vec![vec![0.0, 1.0], vec![1.0, 0.0]]);
+        let estimate = estimate_runtime(
+            &distance_mat,
+            &OperatorConfig::new("threads", 8, 2, 4),
+            1,
+            Duration::from_secs(3600),
+            true,
+        )
+        .unwrap();
+        // Every thread runs 8 / 4 + 1 generations.
+        assert_eq!(estimate.projected_time, estimate.sample_time * 3);
+    }
+    #[test]
+    fn estimate_exceeds_budget() {
+        let distance_mat = DistanceMat::new(vec![
+            vec![0.0, 1.0, 2.0],
+            vec![1.0, 0.0, 3.0],
+            vec![2.0, 3.0, 0.0],
+        ]);
+        let config = OperatorConfig::new("long", 1_000_000, 6, 0);
+        let result = estimate_runtime(&distance_mat, &config, 2, Duration::ZERO, true);
+        assert!(matches!(result, Err(EstimateError::BudgetExceeded(_))));
+        let estimate = estimate_runtime(&distance_mat, &config, 2, Duration::ZERO, false).unwrap();
+        assert!(estimate.exceeds_budget());
+    }
+    #[test]
+    fn sweep_keeps_order_of_grid() {
+        let distance_mat = DistanceMat::new(vec![vec![0.0, 1.0], vec![1.0, 0.0]]);
+        let grid = OperatorConfig::grid(&[2, 4], &[1, 3], 2);
+        let results = benchmark_sweep(&grid, &distance_mat);
+        assert_eq!(
+            results
+                .iter()
+                .map(|result| result.config.clone())
+                .collect::<Vec<OperatorConfig>>(),
+            grid
+        );
+        for result in &results {
+            assert_eq!(result.result.minimal_loss, -2.0);
+            assert!(result.to_string().contains(", n_jobs: 2"));
+        }
+    }
+    #[test]
+    fn evaluate_all_combinations() {
+        let directory = test_directory("evaluate_all_combinations");
+        fs::write(directory.join("a.txt"), "0;1;2\n1;0;3\n2;3;0").unwrap();
+        fs::write(
+            directory.join("b.txt"),
+            "0;1;2;1\n1;0;3;1\n2;3;0;1\n1;1;1;0",
+        )
+        .unwrap();
+        let table = evaluate_instances(
+            &directory,
+            &[
+                OperatorConfig::new("small", 2, 3, 0),
+                OperatorConfig::new("large", 4, 5, 0),
+            ],
+            2,
+        )
+        .unwrap();
+        assert_eq!(
+            table
+                .rows
+                .iter()
+                .map(|row| (row.instance.as_str(), row.config.as_str()))
+                .collect::<Vec<(&str, &str)>>(),
+            vec![
+                ("a", "small"),
+                ("a", "large"),
+                ("b", "small"),
+                ("b", "large")
+            ]
+        );
+        for row in &table.rows {
+            assert_eq!(row.n_runs, 2);
+            assert!(row.best_fitness >= row.mean_fitness);
+            assert!(row.mean_fitness >= row.worst_fitness);
+        }
+        // Only the three-node instance has a round-trip of length 6.
+        assert_eq!(table.rows[0].best_fitness, -6.0);
+        assert_eq!(format!("{}", table).lines().count(), 5);
+    }
+    #[test]
+    fn evaluate_without_runs() {
+        let directory = test_directory("evaluate_without_runs");
+        fs::write(directory.join("a.txt"), "0;1;2\n1;0;3\n2;3;0").unwrap();
+        let error = evaluate_instances(&directory, &[OperatorConfig::new("small", 2, 3, 0)], 0)
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+    #[test]
+    fn comparison_with_the_optimum() {
+        let distance_mat = DistanceMat::from_coordinates(&[
+            (0.0, 0.0),
+            (3.0, 1.0),
+            (6.0, 0.0),
+            (7.0, 4.0),
+            (5.0, 7.0),
+            (1.0, 6.0),
+            (2.0, 3.0),
+        ]);
+        let optimum = distance_mat.get_cost(&held_karp(&distance_mat).unwrap().indexes);
+        let config = EvolutionConfig::new()
+            .with_n_generations(100)
+            .with_size_generation(10);
+        let comparison = benchmark_against_optimum(&distance_mat, optimum, &config, 5, 0.0);
+        assert_eq!(comparison.gaps.len(), 5);
+        assert!(comparison.gaps.iter().all(|gap| *gap > -1e-9));
+        assert_eq!(comparison.success_rate(), 1.0);
+        assert!(comparison.mean_time_to_target().is_some());
+        // The runs are seeded, so they are reproducible.
+        assert_eq!(
+            benchmark_against_optimum(&distance_mat, optimum, &config, 5, 0.0).gaps,
+            comparison.gaps
+        );
+        // Without any generation, a random route rarely is optimal, but always within 1000%.
+        let config = EvolutionConfig::new()
+            .with_n_generations(0)
+            .with_size_generation(1);
+        let comparison = benchmark_against_optimum(&distance_mat, optimum, &config, 5, 10.0);
+        assert_eq!(comparison.success_rate(), 1.0);
+        assert_eq!(comparison.mean_time_to_target(), Some(Duration::ZERO));
+    }
+    #[test]
+    fn comparison_runs_the_seeded_evolutions() {
+        let distance_mat = DistanceMat::from_coordinates(&[
+            (0.0, 0.0),
+            (3.0, 1.0),
+            (6.0, 0.0),
+            (7.0, 4.0),
+            (5.0, 7.0),
+            (1.0, 6.0),
+            (2.0, 3.0),
+            (4.0, 3.0),
+        ]);
+        let optimum = distance_mat.get_cost(&held_karp(&distance_mat).unwrap().indexes);
+        let config = EvolutionConfig::new()
+            .with_n_generations(3)
+            .with_size_generation(4)
+            .with_mutation_rate(0.2)
+            .with_mutation_operator(MutationOperator::Swap)
+            .with_crossover_operator(CrossoverOperator::Cycle)
+            .with_selection(Selection::Tournament { k: 2 });
+        let comparison = benchmark_against_optimum(&distance_mat, optimum, &config, 3, 0.0);
+        // The best route is never lost, so the last population holds it.
+        let gaps = (0..3)
+            .map(|seed| {
+                let initial_population =
+                    Routes::random_with_rng(4, 8, &mut StdRng::seed_from_u64(seed));
+                let mut evolution =
+                    Evolution::new(&distance_mat, config).start(initial_population, seed);
+                let best_cost = population_best_cost(evolution.evolve(3), &distance_mat);
+                (best_cost - optimum) / optimum
+            })
+            .collect::<Vec<f64>>();
+        assert_eq!(comparison.gaps, gaps);
+    }
+}
@@ -0,0 +1,2142 @@
+#[cfg(not(any(feature = "std", test)))]
+use crate::compat::Float;
+use crate::distance_mat::DistanceMat;
+use crate::local_search::LocalSearch;
+use crate::route::{
+    CrossoverOperator, LocalSearchPolicy, MutationOperator, Route, CANDIDATES_MISSING,
+    SPATIAL_WITHOUT_COORDINATES,
+};
+#[cfg(feature = "std")]
+use crate::routes::evolve_population_generations;
+use crate::routes::{evolve_generation_with_rng, GenerationOptions, OperatorCounts, Routes};
+use crate::selection::{DuplicatePolicy, GenerationalScheme, Selection};
+#[cfg(feature = "snapshots")]
+use crate::snapshot::SnapshotError;
+#[cfg(feature = "std")]
+use crate::tabu::TabuMemory;
+#[cfg(feature = "trace")]
+use crate::trace::{TraceError, TraceRecord};
+use crate::utils::n_permutations;
+use alloc::sync::Arc;
+#[cfg(feature = "snapshots")]
+use bincode::Options;
+use core::fmt;
+use core::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "snapshots")]
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+#[cfg(feature = "snapshots")]
+use std::fs;
+#[cfg(feature = "snapshots")]
+use std::io::Read;
+#[cfg(feature = "trace")]
+use std::io::Write;
+#[cfg(feature = "snapshots")]
+use std::path::Path;
+#[cfg(feature = "trace")]
+use std::time::Instant;
+
+/// Why an evolution ended, as returned alongside its result, so that callers can tell e.g. a
+/// converged run from one that ran out of time. More reasons may be added, so a match needs a
+/// wildcard arm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum StopReason {
+    /// All generations that were allowed were evolved.
+    GenerationsExhausted,
+    /// The evolution stopped improving, e.g. the best fitness stalled for `patience`
+    /// generations or the edges of the best route became stable.
+    Converged,
+    /// The time budget was used up before all generations were evolved.
+    TimeBudgetExhausted,
+    /// A route reached the target cost.
+    TargetReached,
+    /// The route is known to be optimal, e.g. because it was found by an exact solver.
+    Optimal,
+    /// The caller cancelled the evolution with a `CancelToken` before it finished.
+    Cancelled,
+}
+impl fmt::Display for StopReason {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let reason = match self {
+            StopReason::GenerationsExhausted => "generations exhausted",
+            StopReason::Converged => "converged",
+            StopReason::TimeBudgetExhausted => "time budget exhausted",
+            StopReason::TargetReached => "target reached",
+            StopReason::Optimal => "optimal",
+            StopReason::Cancelled => "cancelled",
+        };
+        write!(formatter, "{}", reason)
+    }
+}
+
+/// A token to cancel a running `Evolution` from another thread, e.g. when a user aborts a long
+/// run. Clones share the same state, so a clone is given to the evolution with
+/// `Evolution::with_cancel_token` and the original is cancelled. The evolution finishes the
+/// generation it is evolving and stops with `StopReason::Cancelled`.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    /// Has the evolution been cancelled?
+    cancelled: Arc<AtomicBool>,
+}
+impl CancelToken {
+    /// Create a token that is not cancelled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evolution::CancelToken;
+    ///
+    /// assert!(!CancelToken::new().is_cancelled());
+    /// ```
+    pub fn new() -> Self {
+        CancelToken::default()
+    }
+    /// Cancel the evolutions that were given this token or one of its clones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evolution::CancelToken;
+    ///
+    /// let token = CancelToken::new();
+    /// let evolutions_token = token.clone();
+    /// token.cancel();
+    /// assert!(evolutions_token.is_cancelled());
+    /// ```
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+    /// Has the token or one of its clones been cancelled?
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evolution::CancelToken;
+    ///
+    /// let token = CancelToken::new();
+    /// token.cancel();
+    /// assert!(token.is_cancelled());
+    /// ```
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// A controller that adapts the number of routes kept per generation to the progress of an
+/// evolution: while the best cost improves quickly, a small population is cheap and good
+/// enough, and when the improvements stall, a larger population explores more of the search
+/// space. The size always stays between `min_size` and `max_size`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AdaptiveSizing {
+    /// The smallest number of routes kept per generation.
+    pub min_size: usize,
+    /// The largest number of routes kept per generation.
+    pub max_size: usize,
+    /// The relative improvement of the best cost in a generation at or below which the
+    /// evolution stalls and the population grows.
+    pub stall_improvement: f64,
+    /// The relative improvement of the best cost in a generation at or above which the
+    /// progress is fast and the population shrinks.
+    pub fast_improvement: f64,
+    /// By which factor the population grows or shrinks, it changes by at least one route.
+    pub factor: f64,
+}
+impl AdaptiveSizing {
+    /// Create a controller that grows the population by half when the best cost improved by
+    /// less than 0.1% and shrinks it when it improved by at least 1%.
+    ///
+    /// # Arguments
+    ///
+    /// * `min_size` - The smallest number of routes kept per generation, at least one.
+    /// * `max_size` - The largest number of routes kept per generation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evolution::AdaptiveSizing;
+    ///
+    /// let sizing = AdaptiveSizing::new(10, 200);
+    /// assert_eq!(sizing.next_size(20, 100.0, 100.0), 30);
+    /// ```
+    pub fn new(min_size: usize, max_size: usize) -> Self {
+        let min_size = min_size.max(1);
+        AdaptiveSizing {
+            min_size,
+            max_size: max_size.max(min_size),
+            stall_improvement: 0.001,
+            fast_improvement: 0.01,
+            factor: 1.5,
+        }
+    }
+    /// Get the size of the next generation from the size of the current one and the best cost
+    /// before and after it.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - How many routes the current generation kept.
+    /// * `previous_best_cost` - The best cost before the current generation.
+    /// * `best_cost` - The best cost after the current generation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evolution::AdaptiveSizing;
+    ///
+    /// let sizing = AdaptiveSizing::new(10, 40);
+    /// // Stalled, fast and moderate progress.
+    /// assert_eq!(sizing.next_size(30, 100.0, 100.0), 40);
+    /// assert_eq!(sizing.next_size(30, 100.0, 90.0), 20);
+    /// assert_eq!(sizing.next_size(30, 100.0, 99.5), 30);
+    /// ```
+    pub fn next_size(&self, size: usize, previous_best_cost: f64, best_cost: f64) -> usize {
+        let improvement = (previous_best_cost - best_cost) / previous_best_cost.abs().max(1e-12);
+        let next_size = if improvement <= self.stall_improvement {
+            ((size as f64 * self.factor).ceil() as usize).max(size + 1)
+        } else if improvement >= self.fast_improvement {
+            ((size as f64 / self.factor).floor() as usize).min(size.saturating_sub(1))
+        } else {
+            size
+        };
+        next_size.clamp(self.min_size, self.max_size.max(self.min_size))
+    }
+}
+
+/// How the routes that replace a population at a restart are created.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Reinitialization {
+    /// Random routes, as drawn by `Routes::random`.
+    #[default]
+    Random,
+    /// Nearest-neighbor tours from random start nodes as built by `Routes::nearest_neighbor`,
+    /// and random routes once there is one tour per node.
+    NearestNeighbor,
+}
+
+/// When and how an evolution restarts after it converged prematurely: if the best cost has not
+/// improved for `patience` generations, only the `n_elites` fittest routes are kept and the
+/// rest of the population is replaced by new routes. The elites then recombine with routes
+/// that don't share the edges the population converged to, which lets the evolution escape
+/// from a local optimum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RestartPolicy {
+    /// After how many generations without improvement the population is restarted.
+    pub patience: usize,
+    /// How many of the fittest routes survive a restart.
+    pub n_elites: usize,
+    /// How the routes that replace the rest of the population are created.
+    pub reinitialization: Reinitialization,
+}
+impl RestartPolicy {
+    /// Restart with random routes after `patience` generations without improvement, keeping
+    /// the fittest route.
+    ///
+    /// # Arguments
+    ///
+    /// * `patience` - After how many generations without improvement the population is
+    ///   restarted, at least one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evolution::RestartPolicy;
+    ///
+    /// let restart = RestartPolicy::new(50);
+    /// assert_eq!(restart.n_elites, 1);
+    /// ```
+    pub fn new(patience: usize) -> Self {
+        RestartPolicy {
+            patience: patience.max(1),
+            n_elites: 1,
+            reinitialization: Reinitialization::default(),
+        }
+    }
+    /// Set how many of the fittest routes survive a restart.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_elites` - The number of elites, 0 to start over completely.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evolution::RestartPolicy;
+    ///
+    /// let restart = RestartPolicy::new(50).with_n_elites(3);
+    /// ```
+    pub fn with_n_elites(mut self, n_elites: usize) -> Self {
+        self.n_elites = n_elites;
+        self
+    }
+    /// Set how the routes that replace the rest of the population are created.
+    ///
+    /// # Arguments
+    ///
+    /// * `reinitialization` - Random routes or nearest-neighbor tours.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evolution::{Reinitialization, RestartPolicy};
+    ///
+    /// let restart = RestartPolicy::new(50).with_reinitialization(Reinitialization::NearestNeighbor);
+    /// ```
+    pub fn with_reinitialization(mut self, reinitialization: Reinitialization) -> Self {
+        self.reinitialization = reinitialization;
+        self
+    }
+    /// Restart a population: keep its `n_elites` fittest routes, but at most `size_generation`,
+    /// and fill it up to `size_generation` routes with new ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `population` - The population that converged.
+    /// * `size_generation` - How many routes the restarted population contains.
+    /// * `distance_mat` - The distance matrix the fitness is computed on.
+    /// * `rng` - The random number generator the new routes are drawn from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::evolution::RestartPolicy;
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 9.0, 1.0],
+    ///     vec![1.0, 0.0, 1.0, 9.0],
+    ///     vec![9.0, 1.0, 0.0, 1.0],
+    ///     vec![1.0, 9.0, 1.0, 0.0],
+    /// ]);
+    /// let converged = Routes::from(vec![Route::new(vec![0, 1, 2, 3]), Route::new(vec![1, 2, 3, 0])]);
+    /// let restarted = RestartPolicy::new(10).restart(&converged, 5, &distance_matrix, &mut rand::thread_rng());
+    /// assert_eq!(restarted.len(), 5);
+    /// ```
+    pub fn restart<R: Rng + ?Sized>(
+        &self,
+        population: &Routes,
+        size_generation: usize,
+        distance_mat: &DistanceMat,
+        rng: &mut R,
+    ) -> Routes {
+        if population.is_empty() {
+            return population.clone();
+        }
+        let n_nodes = population.get_n_nodes();
+        let elites = population.get_n_fittest(self.n_elites.min(size_generation), distance_mat);
+        let n_fresh = size_generation.saturating_sub(elites.len());
+        let fresh = match self.reinitialization {
+            Reinitialization::Random => Routes::random_with_rng(n_fresh, n_nodes, rng),
+            Reinitialization::NearestNeighbor => {
+                let tours = Routes::nearest_neighbor_with_rng(n_fresh, distance_mat, rng);
+                let n_random = n_fresh - tours.len();
+                tours.combine_routes(Routes::random_with_rng(n_random, n_nodes, rng))
+            }
+        };
+        let mut restarted = fresh.add_vec_route(elites);
+        // A new route may repeat an elite, it is replaced by another random route.
+        let n_routes = size_generation.min(n_permutations(n_nodes));
+        while restarted.len() < n_routes {
+            let n_missing = n_routes - restarted.len();
+            restarted = restarted.combine_routes(Routes::random_with_rng(n_missing, n_nodes, rng));
+        }
+        restarted
+    }
+}
+
+/// How long the best cost of an evolution has stagnated, to decide when a `RestartPolicy`
+/// restarts it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "snapshots", derive(serde::Serialize, serde::Deserialize))]
+struct Stagnation {
+    /// The best cost found so far.
+    best_cost: f64,
+    /// For how many generations the best cost has not improved.
+    n_stagnant: usize,
+    /// How often the population was restarted.
+    n_restarts: usize,
+}
+impl Stagnation {
+    /// Start tracking an evolution that has not evolved any generation.
+    fn new() -> Self {
+        Stagnation {
+            best_cost: f64::INFINITY,
+            n_stagnant: 0,
+            n_restarts: 0,
+        }
+    }
+    /// Record the best cost after a generation and decide whether the population is restarted,
+    /// which is the case if the best cost has not improved for `patience` generations.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - When the population is restarted.
+    /// * `cost` - The best cost of the population after the generation.
+    ///
+    fn should_restart(&mut self, policy: &RestartPolicy, cost: f64) -> bool {
+        // Ignore improvements that are only rounding errors.
+        if self.best_cost - cost > 1e-9 * cost.abs().max(1.0) {
+            self.best_cost = cost;
+            self.n_stagnant = 0;
+            return false;
+        }
+        self.n_stagnant += 1;
+        if self.n_stagnant < policy.patience {
+            return false;
+        }
+        self.n_stagnant = 0;
+        self.n_restarts += 1;
+        true
+    }
+}
+impl Default for Stagnation {
+    fn default() -> Self {
+        Stagnation::new()
+    }
+}
+
+/// All parameters of an evolution. Create it with `EvolutionConfig::new` and change the
+/// parameters that differ from the defaults with the `with_`-methods.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EvolutionConfig {
+    /// How many generations should the algorithm evolve?
+    pub n_generations: usize,
+    /// How many individuals should be selected at the end of each evolution step.
+    pub size_generation: usize,
+    /// The probabilty of an offspring beeing mutated.
+    pub mutation_rate: f32,
+    /// The operator with which offspring are mutated.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub mutation_operator: MutationOperator,
+    /// The probability that a pair of routes is crossed, otherwise the offspring is a copy of
+    /// its first parent.
+    pub crossover_rate: f64,
+    /// The operator with which pairs of routes are crossed.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub crossover_operator: CrossoverOperator,
+    /// How the routes that survive a generation are selected.
+    pub selection: Selection,
+    /// How many of the fittest routes survive every generation regardless of the selection.
+    pub elitism: usize,
+    /// Whether the parents compete with their offspring, (μ+λ), or only the offspring survive,
+    /// (μ,λ).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub scheme: GenerationalScheme,
+    /// Whether routes of the same round-trip are kept, removed or replaced with random routes
+    /// after every selection.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub duplicates: DuplicatePolicy,
+    /// How many threads should be used, 0 means single-threaded. Without the `std` feature
+    /// there are no threads and `n_jobs` is ignored.
+    pub n_jobs: usize,
+    /// The most offspring a generation may create, `None` for no limit. Every pair of parents
+    /// is crossed, so `n` parents create about `n * n` offspring. Above the limit only a random
+    /// sample of the parents, which always contains the fittest one, is crossed.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub max_offspring: Option<usize>,
+    /// The most memory in bytes that the offspring of a generation may take, `None` for no
+    /// limit. It is enforced like `max_offspring`, see `EvolutionConfig::offspring_limit`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub max_memory: Option<usize>,
+    /// Adapt the number of routes kept per generation to the progress, `None` to always keep
+    /// `size_generation` routes, which is then only the size of the first generation.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub adaptive_sizing: Option<AdaptiveSizing>,
+    /// Restart the population when its best cost stagnates, `None` to never restart.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub restart: Option<RestartPolicy>,
+    /// How many of the fittest routes are improved with 2-opt after every selection, 0 for
+    /// none.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub n_refined: usize,
+    /// How the fittest routes are improved with 2-opt.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub local_search: LocalSearchPolicy,
+}
+impl Default for EvolutionConfig {
+    fn default() -> Self {
+        EvolutionConfig {
+            n_generations: 1000,
+            size_generation: 20,
+            mutation_rate: 0.5,
+            mutation_operator: MutationOperator::Insertion,
+            crossover_rate: 1.0,
+            crossover_operator: CrossoverOperator::Ordered,
+            selection: Selection::Truncation,
+            elitism: 0,
+            scheme: GenerationalScheme::MuPlusLambda,
+            duplicates: DuplicatePolicy::KeepEquivalent,
+            n_jobs: 0,
+            max_offspring: None,
+            max_memory: None,
+            adaptive_sizing: None,
+            restart: None,
+            n_refined: 0,
+            local_search: LocalSearchPolicy::default(),
+        }
+    }
+}
+impl EvolutionConfig {
+    /// Create the default configuration: 1000 single-threaded generations of 20 routes, a
+    /// mutation rate of 0.5, every pair of routes is crossed and the fittest routes survive,
+    /// e.g. the same evolution as `evolve_population`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evolution::EvolutionConfig;
+    ///
+    /// let config = EvolutionConfig::new();
+    /// assert_eq!(config.mutation_rate, 0.5);
+    /// ```
+    pub fn new() -> Self {
+        EvolutionConfig::default()
+    }
+    /// Set how many generations should be evolved.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_generations` - How many generations should the algorithm evolve?
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evolution::EvolutionConfig;
+    ///
+    /// let config = EvolutionConfig::new().with_n_generations(50);
+    /// ```
+    pub fn with_n_generations(mut self, n_generations: usize) -> Self {
+        self.n_generations = n_generations;
+        self
+    }
+    /// Set how many routes are kept after every generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `size_generation` - How many individuals should be selected at the end of each
+    ///   evolution step.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evolution::EvolutionConfig;
+    ///
+    /// let config = EvolutionConfig::new().with_size_generation(10);
+    /// ```
+    pub fn with_size_generation(mut self, size_generation: usize) -> Self {
+        self.size_generation = size_generation;
+        self
+    }
+    /// Set the probability that an offspring is mutated.
+    ///
+    /// # Arguments
+    ///
+    /// * `mutation_rate` - The probabilty of an offspring beeing mutated, between 0 and 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evolution::EvolutionConfig;
+    ///
+    /// let config = EvolutionConfig::new().with_mutation_rate(0.2);
+    /// ```
+    pub fn with_mutation_rate(mut self, mutation_rate: f32) -> Self {
+        self.mutation_rate = mutation_rate;
+        self
+    }
+    /// Set the operator with which offspring are mutated.
+    ///
+    /// # Arguments
+    ///
+    /// * `mutation_operator` - The mutation operator, e.g. the inversion mutation that is the
+    ///   standard for the TSP.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evolution::EvolutionConfig;
+    /// use genetic_algorithm_tsp::route::MutationOperator;
+    ///
+    /// let config = EvolutionConfig::new().with_mutation_operator(MutationOperator::Inversion);
+    /// ```
+    pub fn with_mutation_operator(mut self, mutation_operator: MutationOperator) -> Self {
+        self.mutation_operator = mutation_operator;
+        self
+    }
+    /// Set the probability that a pair of routes is crossed.
+    ///
+    /// # Arguments
+    ///
+    /// * `crossover_rate` - The probability that a pair of routes is crossed, between 0 and 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evolution::EvolutionConfig;
+    ///
+    /// let config = EvolutionConfig::new().with_crossover_rate(0.8);
+    /// ```
+    pub fn with_crossover_rate(mut self, crossover_rate: f64) -> Self {
+        self.crossover_rate = crossover_rate;
+        self
+    }
+    /// Set the operator with which pairs of routes are crossed.
+    ///
+    /// # Arguments
+    ///
+    /// * `crossover_operator` - The crossover operator, e.g. the edge recombination crossover
+    ///   that preserves adjacencies.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evolution::EvolutionConfig;
+    /// use genetic_algorithm_tsp::route::CrossoverOperator;
+    ///
+    /// let config = EvolutionConfig::new().with_crossover_operator(CrossoverOperator::EdgeRecombination);
+    /// ```
+    pub fn with_crossover_operator(mut self, crossover_operator: CrossoverOperator) -> Self {
+        self.crossover_operator = crossover_operator;
+        self
+    }
+    /// Set how the routes that survive a generation are selected.
+    ///
+    /// # Arguments
+    ///
+    /// * `selection` - The selection strategy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evolution::EvolutionConfig;
+    /// use genetic_algorithm_tsp::selection::Selection;
+    ///
+    /// let config = EvolutionConfig::new().with_selection(Selection::Tournament { k: 3 });
+    /// ```
+    pub fn with_selection(mut self, selection: Selection) -> Self {
+        self.selection = selection;
+        self
+    }
+    /// Set how many of the fittest routes survive every generation regardless of the selection.
+    ///
+    /// # Arguments
+    ///
+    /// * `elitism` - The number of elites.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evolution::EvolutionConfig;
+    ///
+    /// let config = EvolutionConfig::new().with_elitism(2);
+    /// ```
+    pub fn with_elitism(mut self, elitism: usize) -> Self {
+        self.elitism = elitism;
+        self
+    }
+    /// Set which individuals compete for survival in every generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `scheme` - (μ+λ) to let the parents compete with their offspring or (μ,λ) to keep only
+    ///   the offspring.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evolution::EvolutionConfig;
+    /// use genetic_algorithm_tsp::selection::GenerationalScheme;
+    ///
+    /// let config = EvolutionConfig::new().with_scheme(GenerationalScheme::MuCommaLambda);
+    /// ```
+    pub fn with_scheme(mut self, scheme: GenerationalScheme) -> Self {
+        self.scheme = scheme;
+        self
+    }
+    /// Set what happens to routes of the same round-trip after every selection.
+    ///
+    /// # Arguments
+    ///
+    /// * `duplicates` - Whether duplicates are kept, removed or replaced with random routes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evolution::EvolutionConfig;
+    /// use genetic_algorithm_tsp::selection::DuplicatePolicy;
+    ///
+    /// let config = EvolutionConfig::new().with_duplicates(DuplicatePolicy::DedupeWithRandomReplacement);
+    /// ```
+    pub fn with_duplicates(mut self, duplicates: DuplicatePolicy) -> Self {
+        self.duplicates = duplicates;
+        self
+    }
+    /// Set how many threads are used.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_jobs` - How many threads should be used, 0 means single-threaded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evolution::EvolutionConfig;
+    ///
+    /// let config = EvolutionConfig::new().with_n_jobs(4);
+    /// ```
+    pub fn with_n_jobs(mut self, n_jobs: usize) -> Self {
+        self.n_jobs = n_jobs;
+        self
+    }
+    /// Limit how many offspring a generation may create.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_offspring` - The most offspring of a generation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evolution::EvolutionConfig;
+    ///
+    /// let config = EvolutionConfig::new().with_size_generation(1000).with_max_offspring(10_000);
+    /// ```
+    pub fn with_max_offspring(mut self, max_offspring: usize) -> Self {
+        self.max_offspring = Some(max_offspring);
+        self
+    }
+    /// Limit how much memory the offspring of a generation may take.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_memory` - The most memory of the offspring of a generation in bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evolution::EvolutionConfig;
+    ///
+    /// let config = EvolutionConfig::new().with_max_memory(512 * 1024 * 1024);
+    /// ```
+    pub fn with_max_memory(mut self, max_memory: usize) -> Self {
+        self.max_memory = Some(max_memory);
+        self
+    }
+    /// Adapt the number of routes kept per generation to the progress of the evolution,
+    /// starting from `size_generation`.
+    ///
+    /// # Arguments
+    ///
+    /// * `adaptive_sizing` - How the size of the generations is adapted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evolution::{AdaptiveSizing, EvolutionConfig};
+    ///
+    /// let config = EvolutionConfig::new()
+    ///     .with_size_generation(20)
+    ///     .with_adaptive_sizing(AdaptiveSizing::new(10, 100));
+    /// ```
+    pub fn with_adaptive_sizing(mut self, adaptive_sizing: AdaptiveSizing) -> Self {
+        self.adaptive_sizing = Some(adaptive_sizing);
+        self
+    }
+    /// Restart the population whenever its best cost stagnates.
+    ///
+    /// # Arguments
+    ///
+    /// * `restart` - When and how the population is restarted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evolution::{EvolutionConfig, RestartPolicy};
+    ///
+    /// let config = EvolutionConfig::new().with_restart(RestartPolicy::new(50).with_n_elites(2));
+    /// ```
+    pub fn with_restart(mut self, restart: RestartPolicy) -> Self {
+        self.restart = Some(restart);
+        self
+    }
+    /// Improve the fittest routes of every generation with 2-opt, which makes the evolution a
+    /// memetic algorithm.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_refined` - How many of the fittest routes are improved per generation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evolution::EvolutionConfig;
+    ///
+    /// let config = EvolutionConfig::new().with_n_refined(2);
+    /// ```
+    pub fn with_n_refined(mut self, n_refined: usize) -> Self {
+        self.n_refined = n_refined;
+        self
+    }
+    /// Set how the fittest routes are improved with 2-opt, see `with_n_refined`.
+    ///
+    /// # Arguments
+    ///
+    /// * `local_search` - How the local search moves through the 2-opt neighborhood.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evolution::EvolutionConfig;
+    /// use genetic_algorithm_tsp::route::{LocalSearchPolicy, NeighborhoodOrder, PivotingRule};
+    ///
+    /// let config = EvolutionConfig::new()
+    ///     .with_n_refined(2)
+    ///     .with_local_search_policy(LocalSearchPolicy::new(
+    ///         PivotingRule::BestImprovement,
+    ///         NeighborhoodOrder::Random,
+    ///     ));
+    /// ```
+    pub fn with_local_search_policy(mut self, local_search: LocalSearchPolicy) -> Self {
+        self.local_search = local_search;
+        self
+    }
+    /// The most offspring a generation on an instance with `n_nodes` nodes may create, the
+    /// smaller of `max_offspring` and how many routes fit into `max_memory`. A route is
+    /// estimated to take the size of a `Route` plus one `usize` per node.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_nodes` - The number of nodes of the instance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evolution::EvolutionConfig;
+    ///
+    /// assert_eq!(EvolutionConfig::new().offspring_limit(100), None);
+    /// let config = EvolutionConfig::new().with_max_offspring(500).with_max_memory(1024 * 1024);
+    /// assert_eq!(config.offspring_limit(10), Some(500));
+    /// assert!(config.offspring_limit(10_000).unwrap() < 500);
+    /// ```
+    pub fn offspring_limit(&self, n_nodes: usize) -> Option<usize> {
+        let route_size = core::mem::size_of::<Route>() + n_nodes * core::mem::size_of::<usize>();
+        let memory_limit = self.max_memory.map(|max_memory| max_memory / route_size);
+        match (self.max_offspring, memory_limit) {
+            (Some(max_offspring), Some(memory_limit)) => Some(max_offspring.min(memory_limit)),
+            (max_offspring, memory_limit) => max_offspring.or(memory_limit),
+        }
+    }
+}
+
+/// An evolution of routes on an instance with an `EvolutionConfig`. It either evolves a
+/// population in one go with `run`, or step by step from a seed with `start` and `evolve`,
+/// which can be saved with `checkpoint` and continued with `resume`.
+#[derive(Debug, Clone)]
+pub struct Evolution<'a> {
+    /// The parameters of the evolution.
+    config: EvolutionConfig,
+    /// The distance matrix on which the fitness will be computed on.
+    distance_mat: &'a DistanceMat,
+    /// The current population of a stepwise evolution.
+    population: Routes,
+    /// How many generations the stepwise evolution has evolved.
+    generation: usize,
+    /// How many routes the next generation keeps.
+    size_generation: usize,
+    /// The seed of the random number generator that evolves the next generation.
+    rng_seed: u64,
+    /// How long the best cost has stagnated, for the restarts.
+    stagnation: Stagnation,
+    /// The best cost of the current population, if it is known already.
+    best_cost: Option<f64>,
+    /// The neighborhoods the fittest routes are improved in instead of 2-opt, if any.
+    neighborhoods: Option<&'a dyn LocalSearch>,
+    /// The memory of removed edges the offspring are mutated with, if any.
+    #[cfg(feature = "std")]
+    tabu: Option<TabuMemory>,
+    /// The token the evolution can be cancelled with, if any.
+    cancel: Option<CancelToken>,
+}
+
+/// The state of a stepwise evolution as it is stored in a checkpoint. The fields that were
+/// added later default to the state of an evolution without adaptive sizing and restarts.
+#[cfg(feature = "snapshots")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Checkpoint {
+    /// The parameters of the evolution.
+    config: EvolutionConfig,
+    /// How many generations have been evolved.
+    generation: usize,
+    /// How many routes the next generation keeps, `None` for the configured `size_generation`.
+    #[serde(default)]
+    size_generation: Option<usize>,
+    /// The seed of the random number generator that evolves the next generation.
+    rng_seed: u64,
+    /// The current population.
+    population: Routes,
+    /// How long the best cost has stagnated, for the restarts.
+    #[serde(default)]
+    stagnation: Stagnation,
+}
+
+/// A checkpoint as it was written before the size of the next generation and the stagnation
+/// were stored. Bincode can't skip missing fields, so these checkpoints are read separately.
+#[cfg(feature = "snapshots")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct LegacyCheckpoint {
+    /// The parameters of the evolution.
+    config: EvolutionConfig,
+    /// How many generations have been evolved.
+    generation: usize,
+    /// The seed of the random number generator that evolves the next generation.
+    rng_seed: u64,
+    /// The current population.
+    population: Routes,
+}
+#[cfg(feature = "snapshots")]
+impl From<LegacyCheckpoint> for Checkpoint {
+    fn from(checkpoint: LegacyCheckpoint) -> Self {
+        Checkpoint {
+            config: checkpoint.config,
+            generation: checkpoint.generation,
+            size_generation: None,
+            rng_seed: checkpoint.rng_seed,
+            population: checkpoint.population,
+            stagnation: Stagnation::default(),
+        }
+    }
+}
+impl<'a> Evolution<'a> {
+    /// Create an evolution.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix on which the fitness will be computed on.
+    /// * `config` - The parameters of the evolution.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::evolution::{Evolution, EvolutionConfig};
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let evolution = Evolution::new(&distance_matrix, EvolutionConfig::new());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the configuration uses `CrossoverOperator::Spatial`, but the distance matrix
+    /// has no coordinates, or a candidate operator, but the distance matrix has no candidate
+    /// list.
+    ///
+    pub fn new(distance_mat: &'a DistanceMat, config: EvolutionConfig) -> Self {
+        assert!(
+            config.crossover_operator != CrossoverOperator::Spatial
+                || distance_mat.coordinates().is_some(),
+            "{}",
+            SPATIAL_WITHOUT_COORDINATES
+        );
+        assert!(
+            (config.crossover_operator != CrossoverOperator::Candidate
+                && config.mutation_operator != MutationOperator::Candidate)
+                || distance_mat.candidate_list().is_some(),
+            "{}",
+            CANDIDATES_MISSING
+        );
+        Evolution {
+            config,
+            distance_mat,
+            population: Routes::from(vec![]),
+            generation: 0,
+            size_generation: config.size_generation,
+            rng_seed: 0,
+            stagnation: Stagnation::new(),
+            best_cost: None,
+            neighborhoods: None,
+            #[cfg(feature = "std")]
+            tabu: None,
+            cancel: None,
+        }
+    }
+    /// Get the parameters of the evolution.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::evolution::{Evolution, EvolutionConfig};
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let evolution = Evolution::new(&distance_matrix, EvolutionConfig::new().with_elitism(1));
+    /// assert_eq!(evolution.config().elitism, 1);
+    /// ```
+    pub fn config(&self) -> &EvolutionConfig {
+        &self.config
+    }
+    /// Improve the `n_refined` fittest routes of every generation in the neighborhoods of
+    /// `local_search` instead of with 2-opt alone, e.g. a `LocalSearchChain` of 2-opt, Or-opt
+    /// and 3-opt. The local search is not stored in checkpoints, set it again after `resume`.
+    ///
+    /// # Arguments
+    ///
+    /// * `local_search` - The neighborhoods the fittest routes are improved in.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::evolution::{Evolution, EvolutionConfig};
+    /// use genetic_algorithm_tsp::local_search::{LocalSearchChain, OrOpt, TwoOpt};
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let chain = LocalSearchChain::new().then(TwoOpt::default()).then(OrOpt);
+    /// let evolution = Evolution::new(&distance_matrix, EvolutionConfig::new().with_n_refined(2))
+    ///     .with_local_search(&chain);
+    /// ```
+    pub fn with_local_search(mut self, local_search: &'a dyn LocalSearch) -> Self {
+        self.neighborhoods = Some(local_search);
+        self
+    }
+    /// Stop the evolution after the current generation once `cancel_token` is cancelled. The
+    /// token is not stored in checkpoints, set it again after `resume`.
+    ///
+    /// # Arguments
+    ///
+    /// * `cancel_token` - A clone of the token that cancels the evolution.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::evolution::{CancelToken, Evolution, EvolutionConfig, StopReason};
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let token = CancelToken::new();
+    /// let evolution = Evolution::new(&distance_matrix, EvolutionConfig::new())
+    ///     .with_cancel_token(token.clone());
+    /// token.cancel();
+    /// let (_, stop_reason) = evolution.run(Routes::random(2, 3));
+    /// assert_eq!(stop_reason, StopReason::Cancelled);
+    /// ```
+    pub fn with_cancel_token(mut self, cancel_token: CancelToken) -> Self {
+        self.cancel = Some(cancel_token);
+        self
+    }
+    /// Has the token of the evolution been cancelled?
+    fn is_cancelled(&self) -> bool {
+        self.cancel.as_ref().is_some_and(CancelToken::is_cancelled)
+    }
+    /// Get the distance matrix the evolution computes the fitness on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::evolution::{Evolution, EvolutionConfig};
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let evolution = Evolution::new(&distance_matrix, EvolutionConfig::new());
+    /// assert_eq!(evolution.distance_mat().n_units(), 3);
+    /// ```
+    pub fn distance_mat(&self) -> &'a DistanceMat {
+        self.distance_mat
+    }
+    /// Evolve an initial population and return the final population together with the
+    /// `StopReason`, which is `Cancelled` if the evolution was cancelled with its `CancelToken`
+    /// before all generations were evolved. With adaptive sizing or restarts every generation
+    /// depends on the progress of the previous one, so the evolution is a single-threaded
+    /// stepwise evolution from a random seed and `n_jobs` is ignored. Use `start` with a seed
+    /// to make such a run reproducible.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial_population` - Your initial population that should be evolved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::evolution::{Evolution, EvolutionConfig, StopReason};
+    /// use genetic_algorithm_tsp::routes::Routes;
+    /// use genetic_algorithm_tsp::selection::Selection;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let config = EvolutionConfig::new()
+    ///     .with_n_generations(10)
+    ///     .with_size_generation(5)
+    ///     .with_mutation_rate(0.3)
+    ///     .with_selection(Selection::Tournament { k: 2 })
+    ///     .with_elitism(1);
+    /// let (evolved_population, stop_reason) =
+    ///     Evolution::new(&distance_matrix, config).run(Routes::random(2, 3));
+    /// assert_eq!(stop_reason, StopReason::GenerationsExhausted);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn run(&self, initial_population: Routes) -> (Routes, StopReason) {
+        if self.config.adaptive_sizing.is_some() || self.config.restart.is_some() {
+            let mut evolution = self.clone().start(initial_population, rand::random());
+            evolution.evolve(self.config.n_generations);
+            let stop_reason = evolution.stop_reason();
+            return (evolution.population, stop_reason);
+        }
+        let population = evolve_population_generations(
+            initial_population,
+            self.config.n_generations,
+            self.config.size_generation,
+            self.distance_mat,
+            self.config.n_jobs,
+            self.generation_options(),
+        );
+        // The generations aren't counted across the threads, a cancelled run may have
+        // evolved all of them just before it was cancelled.
+        let stop_reason = if self.is_cancelled() {
+            StopReason::Cancelled
+        } else {
+            StopReason::GenerationsExhausted
+        };
+        (population, stop_reason)
+    }
+    /// Why a stepwise evolution stopped: `Cancelled` if it was cancelled before all
+    /// generations were evolved, otherwise `GenerationsExhausted`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::evolution::{CancelToken, Evolution, EvolutionConfig, StopReason};
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let token = CancelToken::new();
+    /// let mut evolution = Evolution::new(&distance_matrix, EvolutionConfig::new().with_n_generations(10))
+    ///     .with_cancel_token(token.clone())
+    ///     .start(Routes::random(2, 3), 42);
+    /// evolution.evolve(4);
+    /// token.cancel();
+    /// evolution.evolve(100);
+    /// assert_eq!(evolution.generation(), 4);
+    /// assert_eq!(evolution.stop_reason(), StopReason::Cancelled);
+    /// ```
+    pub fn stop_reason(&self) -> StopReason {
+        if self.is_cancelled() && !self.is_finished() {
+            StopReason::Cancelled
+        } else {
+            StopReason::GenerationsExhausted
+        }
+    }
+    /// The options every generation of the evolution is evolved with.
+    fn generation_options(&self) -> GenerationOptions<'_> {
+        GenerationOptions {
+            mutate_prob: self.config.mutation_rate,
+            crossover_rate: self.config.crossover_rate,
+            crossover_operator: self.config.crossover_operator,
+            mutation_operator: self.config.mutation_operator,
+            selection: self.config.selection,
+            n_elites: self.config.elitism,
+            scheme: self.config.scheme,
+            duplicates: self.config.duplicates,
+            max_offspring: self.config.offspring_limit(self.distance_mat.n_units()),
+            n_refined: self.config.n_refined,
+            local_search: self.config.local_search,
+            neighborhoods: self.neighborhoods,
+            #[cfg(feature = "std")]
+            tabu: self.tabu.as_ref(),
+            #[cfg(feature = "std")]
+            cancel: self.cancel.as_ref(),
+            ..GenerationOptions::default()
+        }
+    }
+    /// Start a stepwise evolution of an initial population. Every generation is evolved with a
+    /// `StdRng` seeded from the seed that the previous generation drew, so the same seed always
+    /// gives the same run, and a run that is resumed from a checkpoint continues exactly as if
+    /// it had not been interrupted. A stepwise evolution is single-threaded, `n_jobs` is ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial_population` - Your initial population that should be evolved.
+    /// * `seed` - The seed of the first generation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::evolution::{Evolution, EvolutionConfig};
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let mut evolution = Evolution::new(&distance_matrix, EvolutionConfig::new().with_n_generations(10))
+    ///     .start(Routes::random(2, 3), 42);
+    /// assert_eq!(evolution.generation(), 0);
+    /// ```
+    pub fn start(mut self, initial_population: Routes, seed: u64) -> Self {
+        self.population = initial_population;
+        self.generation = 0;
+        self.size_generation = self.config.size_generation;
+        self.rng_seed = seed;
+        self.stagnation = Stagnation::new();
+        self.best_cost = None;
+        self
+    }
+    /// Evolve up to `n_generations` further generations of a stepwise evolution, but not more
+    /// than the `n_generations` of its configuration and none once it is cancelled, and return
+    /// the current population.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_generations` - How many generations should be evolved at most.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::evolution::{Evolution, EvolutionConfig};
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let mut evolution = Evolution::new(&distance_matrix, EvolutionConfig::new().with_n_generations(10))
+    ///     .start(Routes::random(2, 3), 42);
+    /// evolution.evolve(4);
+    /// evolution.evolve(100);
+    /// assert_eq!(evolution.generation(), 10);
+    /// assert!(evolution.is_finished());
+    /// ```
+    pub fn evolve(&mut self, n_generations: usize) -> &Routes {
+        for _ in 0..self.remaining_generations(n_generations) {
+            if self.is_cancelled() {
+                break;
+            }
+            self.step(None);
+        }
+        &self.population
+    }
+    /// Evolve a stepwise evolution like `evolve` and write one JSON line per generation to
+    /// `writer`: its number, the statistics of its fitnesses, a hash of its best route and how
+    /// often the operators were applied, see `TraceRecord`. The trace can be ingested by
+    /// experiment-tracking tools without further code, and counting the operators doesn't
+    /// change the random choices, so the run is the same as without the trace.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_generations` - How many generations should be evolved at most.
+    /// * `writer` - Where the trace is written to, e.g. a file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::evolution::{Evolution, EvolutionConfig};
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let mut evolution = Evolution::new(&distance_matrix, EvolutionConfig::new().with_n_generations(10))
+    ///     .start(Routes::random(2, 3), 42);
+    /// let mut trace = Vec::new();
+    /// evolution.evolve_with_trace(4, &mut trace).unwrap();
+    /// assert_eq!(String::from_utf8(trace).unwrap().lines().count(), 4);
+    /// ```
+    #[cfg(feature = "trace")]
+    pub fn evolve_with_trace<W: Write>(
+        &mut self,
+        n_generations: usize,
+        writer: &mut W,
+    ) -> Result<&Routes, TraceError> {
+        for _ in 0..self.remaining_generations(n_generations) {
+            if self.is_cancelled() {
+                break;
+            }
+            let start = Instant::now();
+            let mut operators = OperatorCounts::default();
+            self.step(Some(&mut operators));
+            if let Some(record) = TraceRecord::new(
+                self.generation,
+                &self.population,
+                self.distance_mat,
+                operators,
+                start.elapsed(),
+            ) {
+                record.write(writer)?;
+            }
+        }
+        writer.flush()?;
+        Ok(&self.population)
+    }
+    /// How many of `n_generations` further generations can be evolved before the configured
+    /// number of generations is reached, none if the evolution is cancelled.
+    fn remaining_generations(&self, n_generations: usize) -> usize {
+        if self.is_cancelled() {
+            return 0;
+        }
+        n_generations.min(self.config.n_generations.saturating_sub(self.generation))
+    }
+    /// Evolve the next generation of a stepwise evolution.
+    ///
+    /// # Arguments
+    ///
+    /// * `operators` - Where to count the applied operators, `None` to not count them.
+    ///
+    fn step(&mut self, operators: Option<&mut OperatorCounts>) {
+        let mut rng = StdRng::seed_from_u64(self.rng_seed);
+        let population = core::mem::replace(&mut self.population, Routes::from(vec![]));
+        let tracks_cost = self.config.restart.is_some() || self.config.adaptive_sizing.is_some();
+        let previous_best_cost = match self.best_cost {
+            Some(cost) => cost,
+            None if tracks_cost => best_cost(&population, self.distance_mat),
+            None => f64::INFINITY,
+        };
+        #[cfg(feature = "std")]
+        let best_before = self
+            .tabu
+            .is_some()
+            .then(|| population.get_n_fittest(1, self.distance_mat));
+        let mut population = evolve_generation_with_rng(
+            population,
+            self.size_generation,
+            self.distance_mat,
+            self.generation_options(),
+            &mut rng,
+            operators,
+        );
+        #[cfg(feature = "std")]
+        if let Some(memory) = self.tabu.as_mut() {
+            if let (Some(before), Some(after)) = (
+                best_before.as_ref().and_then(|best| best.first()),
+                population.get_n_fittest(1, self.distance_mat).first(),
+            ) {
+                memory.record(&before.indexes, &after.indexes);
+            }
+            memory.advance();
+        }
+        // The best cost is computed once per generation and reused by the next one.
+        self.best_cost = None;
+        if tracks_cost {
+            let cost = best_cost(&population, self.distance_mat);
+            self.best_cost = Some(cost);
+            if let Some(restart) = self.config.restart {
+                if self.stagnation.should_restart(&restart, cost) {
+                    population = restart.restart(
+                        &population,
+                        self.size_generation,
+                        self.distance_mat,
+                        &mut rng,
+                    );
+                    // Without elites the restarted population has a new best cost.
+                    self.best_cost = None;
+                }
+            }
+            if let Some(adaptive_sizing) = self.config.adaptive_sizing {
+                self.size_generation =
+                    adaptive_sizing.next_size(self.size_generation, previous_best_cost, cost);
+            }
+        }
+        self.population = population;
+        self.rng_seed = rng.gen();
+        self.generation += 1;
+    }
+    /// Get how many routes the next generation of a stepwise evolution keeps, which only
+    /// differs from the configured `size_generation` with adaptive sizing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::evolution::{AdaptiveSizing, Evolution, EvolutionConfig};
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let config = EvolutionConfig::new()
+    ///     .with_size_generation(4)
+    ///     .with_adaptive_sizing(AdaptiveSizing::new(2, 8));
+    /// let mut evolution = Evolution::new(&distance_matrix, config).start(Routes::random(4, 3), 42);
+    /// evolution.evolve(1);
+    /// // Every route of the instance has the same cost, so the evolution stalls.
+    /// assert_eq!(evolution.size_generation(), 6);
+    /// ```
+    pub fn size_generation(&self) -> usize {
+        self.size_generation
+    }
+    /// Get how often a stepwise evolution was restarted by its `RestartPolicy`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::evolution::{Evolution, EvolutionConfig, RestartPolicy};
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let config = EvolutionConfig::new()
+    ///     .with_n_generations(10)
+    ///     .with_restart(RestartPolicy::new(3));
+    /// let mut evolution = Evolution::new(&distance_matrix, config).start(Routes::random(2, 3), 42);
+    /// evolution.evolve(10);
+    /// // Every route of the instance has the same cost, so the evolution restarts every third
+    /// // generation after the first.
+    /// assert_eq!(evolution.n_restarts(), 3);
+    /// ```
+    pub fn n_restarts(&self) -> usize {
+        self.stagnation.n_restarts
+    }
+    /// Get the seed of the random number generator that evolves the next generation.
+    #[cfg(feature = "std")]
+    pub(crate) fn rng_seed(&self) -> u64 {
+        self.rng_seed
+    }
+    /// Continue a started stepwise evolution as if `generation` generations had been evolved.
+    #[cfg(feature = "std")]
+    pub(crate) fn at_generation(mut self, generation: usize) -> Self {
+        self.generation = generation;
+        self
+    }
+    /// Replace the memory of removed edges the offspring are mutated with and return the
+    /// previous one.
+    #[cfg(feature = "std")]
+    pub(crate) fn replace_tabu(&mut self, tabu: Option<TabuMemory>) -> Option<TabuMemory> {
+        core::mem::replace(&mut self.tabu, tabu)
+    }
+    /// Add the migrants from another island to the population of a stepwise evolution and keep
+    /// its fittest routes.
+    #[cfg(feature = "std")]
+    pub(crate) fn receive_migrants(&mut self, migrants: Vec<Route>) {
+        let population = core::mem::replace(&mut self.population, Routes::from(vec![]));
+        self.population = population
+            .add_vec_route(migrants)
+            .get_fittest_population(self.size_generation, self.distance_mat);
+        self.best_cost = None;
+    }
+    /// Get the current population of a stepwise evolution.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::evolution::{Evolution, EvolutionConfig};
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let evolution = Evolution::new(&distance_matrix, EvolutionConfig::new())
+    ///     .start(Routes::random(2, 3), 42);
+    /// assert_eq!(evolution.population().len(), 2);
+    /// ```
+    pub fn population(&self) -> &Routes {
+        &self.population
+    }
+    /// Get how many generations a stepwise evolution has evolved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::evolution::{Evolution, EvolutionConfig};
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert_eq!(Evolution::new(&distance_matrix, EvolutionConfig::new()).generation(), 0);
+    /// ```
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+    /// Has a stepwise evolution evolved all generations of its configuration?
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::evolution::{Evolution, EvolutionConfig};
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert!(!Evolution::new(&distance_matrix, EvolutionConfig::new()).is_finished());
+    /// ```
+    pub fn is_finished(&self) -> bool {
+        self.generation >= self.config.n_generations
+    }
+    /// Save the state of a stepwise evolution, e.g. its configuration, population, generation
+    /// counter and random number generator, to a compressed checkpoint file. The file is written
+    /// next to `path` first and then renamed, so that a crash while writing doesn't destroy the
+    /// previous checkpoint.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the checkpoint file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::evolution::{Evolution, EvolutionConfig};
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let mut evolution = Evolution::new(&distance_matrix, EvolutionConfig::new().with_n_generations(10))
+    ///     .start(Routes::random(2, 3), 42);
+    /// evolution.evolve(5);
+    /// let path = std::env::temp_dir().join("genetic_algorithm_tsp_doc_checkpoint.bin.gz");
+    /// evolution.checkpoint(&path).unwrap();
+    /// ```
+    #[cfg(feature = "snapshots")]
+    pub fn checkpoint(&self, path: &Path) -> Result<(), SnapshotError> {
+        let checkpoint = Checkpoint {
+            config: self.config,
+            generation: self.generation,
+            size_generation: Some(self.size_generation),
+            rng_seed: self.rng_seed,
+            population: self.population.clone(),
+            stagnation: self.stagnation,
+        };
+        let partial_path = path.with_extension("partial");
+        let mut encoder = GzEncoder::new(fs::File::create(&partial_path)?, Compression::default());
+        bincode::serialize_into(&mut encoder, &checkpoint)?;
+        encoder.finish()?;
+        fs::rename(partial_path, path)?;
+        Ok(())
+    }
+    /// Continue a stepwise evolution from a checkpoint that was written by `checkpoint`. The
+    /// configuration is read from the checkpoint as well. Checkpoints of older versions, which
+    /// don't store the adaptive size of the next generation and the stagnation of restarts,
+    /// continue with the configured size and without stagnation.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix of the evolution, it is not part of the checkpoint.
+    /// * `path` - The path of the checkpoint file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::evolution::{Evolution, EvolutionConfig};
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let mut evolution = Evolution::new(&distance_matrix, EvolutionConfig::new().with_n_generations(10))
+    ///     .start(Routes::random(2, 3), 42);
+    /// evolution.evolve(5);
+    /// let path = std::env::temp_dir().join("genetic_algorithm_tsp_doc_resume.bin.gz");
+    /// evolution.checkpoint(&path).unwrap();
+    ///
+    /// let mut resumed = Evolution::resume(&distance_matrix, &path).unwrap();
+    /// assert_eq!(resumed.generation(), 5);
+    /// assert_eq!(resumed.evolve(5), evolution.evolve(5));
+    /// ```
+    #[cfg(feature = "snapshots")]
+    pub fn resume(distance_mat: &'a DistanceMat, path: &Path) -> Result<Self, SnapshotError> {
+        let mut bytes = Vec::new();
+        GzDecoder::new(fs::File::open(path)?).read_to_end(&mut bytes)?;
+        // The encoding of `bincode::serialize`, but a checkpoint has to fill the whole file.
+        let options = bincode::DefaultOptions::new()
+            .with_fixint_encoding()
+            .reject_trailing_bytes();
+        let checkpoint = options.deserialize::<Checkpoint>(&bytes).or_else(|error| {
+            options
+                .deserialize::<LegacyCheckpoint>(&bytes)
+                .map(Checkpoint::from)
+                .map_err(|_| error)
+        })?;
+        Ok(Evolution {
+            config: checkpoint.config,
+            distance_mat,
+            population: checkpoint.population,
+            generation: checkpoint.generation,
+            size_generation: checkpoint
+                .size_generation
+                .unwrap_or(checkpoint.config.size_generation),
+            rng_seed: checkpoint.rng_seed,
+            stagnation: checkpoint.stagnation,
+            best_cost: None,
+            neighborhoods: None,
+            tabu: None,
+            cancel: None,
+        })
+    }
+    /// Evolve a stepwise evolution until it is finished and write a checkpoint every
+    /// `interval` generations and at the end. Pass the evolution from `resume` to continue
+    /// after a crash.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the checkpoint file.
+    /// * `interval` - After how many generations a checkpoint is written, at least 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::evolution::{Evolution, EvolutionConfig};
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let path = std::env::temp_dir().join("genetic_algorithm_tsp_doc_run_with_checkpoints.bin.gz");
+    /// let mut evolution = Evolution::new(&distance_matrix, EvolutionConfig::new().with_n_generations(20))
+    ///     .start(Routes::random(2, 3), 42);
+    /// evolution.run_with_checkpoints(&path, 5).unwrap();
+    /// assert!(Evolution::resume(&distance_matrix, &path).unwrap().is_finished());
+    /// ```
+    #[cfg(feature = "snapshots")]
+    pub fn run_with_checkpoints(
+        &mut self,
+        path: &Path,
+        interval: usize,
+    ) -> Result<&Routes, SnapshotError> {
+        while !self.is_finished() {
+            self.evolve(interval.max(1));
+            self.checkpoint(path)?;
+        }
+        Ok(&self.population)
+    }
+}
+
+/// The cost of the best route of a population, infinite if it is empty.
+///
+/// # Arguments
+///
+/// * `population` - The population whose best route is evaluated.
+/// * `distance_mat` - The distance matrix the cost is computed on.
+///
+fn best_cost(population: &Routes, distance_mat: &DistanceMat) -> f64 {
+    population
+        .get_n_fittest(1, distance_mat)
+        .first()
+        .map_or(f64::INFINITY, |route| -route.fitness(distance_mat))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::local_search::OrOpt;
+    #[cfg(feature = "std")]
+    use crate::route::Route;
+    use crate::route::{NeighborhoodOrder, PivotingRule};
+    #[cfg(feature = "std")]
+    use crate::test_utils::valid_permutation;
+    use crate::test_utils::{clustered_dist_mat, test_dist_mat};
+
+    #[test]
+    fn format_stop_reason() {
+        assert_eq!(
+            format!("{}", StopReason::GenerationsExhausted),
+            "generations exhausted"
+        );
+        assert_eq!(
+            format!("{}", StopReason::TimeBudgetExhausted),
+            "time budget exhausted"
+        );
+    }
+    #[cfg(feature = "std")]
+    #[test]
+    fn cancel_token_stops_a_running_evolution() {
+        let distance_mat = test_dist_mat();
+        for n_jobs in [0, 2] {
+            let token = CancelToken::new();
+            let evolution = Evolution::new(
+                &distance_mat,
+                EvolutionConfig::new()
+                    .with_n_generations(usize::MAX)
+                    .with_size_generation(4)
+                    .with_n_jobs(n_jobs),
+            )
+            .with_cancel_token(token.clone());
+            let canceller = std::thread::spawn(move || {
+                std::thread::sleep(std::time::Duration::from_millis(20));
+                token.cancel();
+            });
+            let (routes, stop_reason) = evolution.run(Routes::random(4, 3));
+            canceller.join().unwrap();
+            assert_eq!(stop_reason, StopReason::Cancelled);
+            assert!(!routes.is_empty());
+        }
+    }
+    #[test]
+    fn builder_sets_all_parameters() {
+        let config = EvolutionConfig::new()
+            .with_n_generations(3)
+            .with_size_generation(4)
+            .with_mutation_rate(0.1)
+            .with_mutation_operator(MutationOperator::Scramble)
+            .with_crossover_rate(0.7)
+            .with_crossover_operator(CrossoverOperator::Cycle)
+            .with_selection(Selection::Rank)
+            .with_elitism(2)
+            .with_scheme(GenerationalScheme::MuCommaLambda)
+            .with_duplicates(DuplicatePolicy::Dedupe)
+            .with_n_jobs(1)
+            .with_max_offspring(100)
+            .with_max_memory(4096)
+            .with_adaptive_sizing(AdaptiveSizing::new(5, 50))
+            .with_restart(RestartPolicy::new(7))
+            .with_n_refined(2)
+            .with_local_search_policy(LocalSearchPolicy::new(
+                PivotingRule::BestImprovement,
+                NeighborhoodOrder::Random,
+            ));
+        assert_eq!(
+            config,
+            EvolutionConfig {
+                n_generations: 3,
+                size_generation: 4,
+                mutation_rate: 0.1,
+                mutation_operator: MutationOperator::Scramble,
+                crossover_rate: 0.7,
+                crossover_operator: CrossoverOperator::Cycle,
+                selection: Selection::Rank,
+                elitism: 2,
+                scheme: GenerationalScheme::MuCommaLambda,
+                duplicates: DuplicatePolicy::Dedupe,
+                n_jobs: 1,
+                max_offspring: Some(100),
+                max_memory: Some(4096),
+                adaptive_sizing: Some(AdaptiveSizing::new(5, 50)),
+                restart: Some(RestartPolicy::new(7)),
+                n_refined: 2,
+                local_search: LocalSearchPolicy::new(
+                    PivotingRule::BestImprovement,
+                    NeighborhoodOrder::Random,
+                ),
+            }
+        );
+    }
+    #[test]
+    fn duplicate_policy_controls_round_trips() {
+        let distance_mat = DistanceMat::new(vec![
+            vec![0.0, 1.0, 9.0, 1.0, 2.0],
+            vec![1.0, 0.0, 1.0, 9.0, 3.0],
+            vec![9.0, 1.0, 0.0, 1.0, 4.0],
+            vec![1.0, 9.0, 1.0, 0.0, 5.0],
+            vec![2.0, 3.0, 4.0, 5.0, 0.0],
+        ]);
+        let config = EvolutionConfig::new()
+            .with_n_generations(5)
+            .with_size_generation(10);
+        // Only 12 round-trips exist, so random replacements often repeat one. The evolutions are
+        // seeded, as the number of replacements that are tried is bounded.
+        let evolve = |duplicates| {
+            let initial_population = Routes::random_with_rng(10, 5, &mut StdRng::seed_from_u64(0));
+            Evolution::new(&distance_mat, config.with_duplicates(duplicates))
+                .start(initial_population, 0)
+                .evolve(usize::MAX)
+                .clone()
+        };
+        let deduped = evolve(DuplicatePolicy::Dedupe);
+        let tour_keys = deduped
+            .iter()
+            .map(|route| distance_mat.tour_key(&route.indexes))
+            .collect::<std::collections::HashSet<_>>();
+        assert_eq!(tour_keys.len(), deduped.len());
+        let replaced = evolve(DuplicatePolicy::DedupeWithRandomReplacement);
+        assert_eq!(replaced.len(), 10);
+    }
+    #[test]
+    fn restarts_escape_premature_convergence() {
+        let distance_mat = clustered_dist_mat(4, 6);
+        let config = EvolutionConfig::new()
+            .with_n_generations(300)
+            .with_size_generation(4)
+            .with_mutation_rate(0.1);
+        for reinitialization in [Reinitialization::Random, Reinitialization::NearestNeighbor] {
+            let restart = RestartPolicy::new(20).with_reinitialization(reinitialization);
+            let (mut wins, mut losses, mut n_restarts) = (0, 0, 0);
+            for seed in 0..10 {
+                let initial = Routes::random_with_rng(4, 24, &mut StdRng::seed_from_u64(seed));
+                let mut plain = Evolution::new(&distance_mat, config).start(initial.clone(), seed);
+                let plain_cost = best_cost(plain.evolve(300), &distance_mat);
+                let mut restarted = Evolution::new(&distance_mat, config.with_restart(restart))
+                    .start(initial, seed);
+                let restarted_cost = best_cost(restarted.evolve(300), &distance_mat);
+                n_restarts += restarted.n_restarts();
+                wins += usize::from(restarted_cost < plain_cost - 1e-9);
+                losses += usize::from(plain_cost < restarted_cost - 1e-9);
+            }
+            assert!(n_restarts > 0);
+            assert!(wins > losses);
+        }
+    }
+    #[cfg(feature = "std")]
+    #[test]
+    fn run_with_restarts_ignores_threads() {
+        let distance_mat = clustered_dist_mat(3, 4);
+        let config = EvolutionConfig::new()
+            .with_n_generations(30)
+            .with_size_generation(6)
+            .with_n_jobs(4)
+            .with_restart(RestartPolicy::new(3));
+        let initial = Routes::random(6, 12);
+        let routes = Evolution::new(&distance_mat, config).run(initial.clone()).0;
+        assert_eq!(routes.len(), 6);
+        assert!(best_cost(&routes, &distance_mat) <= best_cost(&initial, &distance_mat));
+    }
+    #[test]
+    fn restart_keeps_the_elites() {
+        let distance_mat = clustered_dist_mat(3, 4);
+        let population = Routes::random_with_rng(6, 12, &mut StdRng::seed_from_u64(2));
+        let elites = population.get_n_fittest(2, &distance_mat);
+        let restarted = RestartPolicy::new(1).with_n_elites(2).restart(
+            &population,
+            6,
+            &distance_mat,
+            &mut StdRng::seed_from_u64(3),
+        );
+        assert_eq!(restarted.len(), 6);
+        assert!(elites
+            .iter()
+            .all(|elite| restarted.iter().any(|route| route == elite)));
+    }
+    #[test]
+    fn restart_replaces_new_routes_that_repeat_an_elite() {
+        // Every route of the instance is drawn, so the new routes always repeat the elites.
+        let distance_mat = clustered_dist_mat(2, 2);
+        let population = Routes::random_with_rng(4, 4, &mut StdRng::seed_from_u64(0));
+        for seed in 0..5 {
+            let restarted = RestartPolicy::new(1).with_n_elites(2).restart(
+                &population,
+                24,
+                &distance_mat,
+                &mut StdRng::seed_from_u64(seed),
+            );
+            assert_eq!(restarted.len(), 24);
+        }
+    }
+    #[test]
+    fn restart_keeps_at_most_size_generation_elites() {
+        let distance_mat = clustered_dist_mat(3, 4);
+        let population = Routes::random_with_rng(6, 12, &mut StdRng::seed_from_u64(1));
+        let restarted = RestartPolicy::new(1).with_n_elites(5).restart(
+            &population,
+            3,
+            &distance_mat,
+            &mut StdRng::seed_from_u64(1),
+        );
+        assert_eq!(restarted.len(), 3);
+        assert!(population
+            .get_n_fittest(3, &distance_mat)
+            .iter()
+            .all(|elite| restarted.iter().any(|route| route == elite)));
+    }
+    #[cfg(feature = "std")]
+    #[test]
+    fn elites_survive_any_selection() {
+        let distance_mat = DistanceMat::new(vec![
+            vec![0.0, 1.0, 9.0, 1.0],
+            vec![1.0, 0.0, 1.0, 9.0],
+            vec![9.0, 1.0, 0.0, 1.0],
+            vec![1.0, 9.0, 1.0, 0.0],
+        ]);
+        let config = EvolutionConfig::new()
+            .with_n_generations(5)
+            .with_size_generation(3)
+            .with_selection(Selection::RouletteWheel)
+            .with_elitism(1);
+        let best = Routes::from(vec![Route::new(vec![0, 1, 2, 3])]);
+        let routes = Evolution::new(&distance_mat, config).run(best).0;
+        assert_eq!(
+            routes.get_n_fittest(1, &distance_mat)[0].fitness(&distance_mat),
+            -4.0
+        );
+    }
+    #[cfg(feature = "std")]
+    #[test]
+    fn every_crossover_operator_keeps_permutations() {
+        let distance_mat = DistanceMat::new(vec![vec![1.0; 6]; 6]).with_candidate_list(2);
+        for operator in [
+            CrossoverOperator::Ordered,
+            CrossoverOperator::PartiallyMapped,
+            CrossoverOperator::Cycle,
+            CrossoverOperator::EdgeRecombination,
+            CrossoverOperator::Candidate,
+        ] {
+            let config = EvolutionConfig::new()
+                .with_n_generations(5)
+                .with_size_generation(4)
+                .with_crossover_operator(operator);
+            let routes = Evolution::new(&distance_mat, config)
+                .run(Routes::random(4, 6))
+                .0;
+            for route in routes.iter() {
+                valid_permutation(&[0, 1, 2, 3, 4, 5], &route.indexes);
+            }
+        }
+    }
+    #[test]
+    #[should_panic(expected = "CrossoverOperator::Spatial needs the coordinates")]
+    fn spatial_crossover_without_coordinates_is_rejected() {
+        Evolution::new(
+            &test_dist_mat(),
+            EvolutionConfig::new().with_crossover_operator(CrossoverOperator::Spatial),
+        );
+    }
+    #[test]
+    #[should_panic(expected = "The candidate operators need the candidate list")]
+    fn candidate_operators_without_candidate_list_are_rejected() {
+        Evolution::new(
+            &test_dist_mat(),
+            EvolutionConfig::new().with_mutation_operator(MutationOperator::Candidate),
+        );
+    }
+    #[cfg(feature = "std")]
+    #[test]
+    fn every_mutation_operator_keeps_permutations() {
+        let distance_mat = DistanceMat::new(vec![vec![1.0; 6]; 6]).with_candidate_list(2);
+        for operator in [
+            MutationOperator::Insertion,
+            MutationOperator::Swap,
+            MutationOperator::Inversion,
+            MutationOperator::Scramble,
+            MutationOperator::Candidate,
+        ] {
+            let config = EvolutionConfig::new()
+                .with_n_generations(5)
+                .with_size_generation(4)
+                .with_mutation_operator(operator);
+            let routes = Evolution::new(&distance_mat, config)
+                .run(Routes::random(4, 6))
+                .0;
+            for route in routes.iter() {
+                valid_permutation(&[0, 1, 2, 3, 4, 5], &route.indexes);
+            }
+        }
+    }
+    #[cfg(feature = "std")]
+    #[test]
+    fn comma_scheme_discards_parents() {
+        let parents = Routes::from(vec![
+            Route::new(vec![0, 1, 2, 3, 4]),
+            Route::new(vec![4, 3, 2, 1, 0]),
+            Route::new(vec![2, 0, 4, 1, 3]),
+            Route::new(vec![3, 1, 4, 0, 2]),
+        ]);
+        let config = EvolutionConfig::new()
+            .with_n_generations(1)
+            .with_size_generation(10)
+            .with_mutation_rate(1.0)
+            .with_scheme(GenerationalScheme::MuCommaLambda);
+        let routes = Evolution::new(&DistanceMat::new(vec![vec![1.0; 5]; 5]), config)
+            .run(parents.clone())
+            .0;
+        assert!(routes
+            .iter()
+            .all(|route| parents.iter().all(|parent| parent != route)));
+    }
+    #[cfg(feature = "std")]
+    #[test]
+    fn comma_scheme_keeps_parents_without_new_offspring() {
+        let config = EvolutionConfig::new()
+            .with_n_generations(3)
+            .with_mutation_rate(0.0)
+            .with_crossover_rate(0.0)
+            .with_scheme(GenerationalScheme::MuCommaLambda);
+        let initial_population =
+            Routes::from(vec![Route::new(vec![0, 1, 2]), Route::new(vec![1, 0, 2])]);
+        assert_eq!(
+            Evolution::new(&test_dist_mat(), config)
+                .run(initial_population.clone())
+                .0,
+            initial_population
+        );
+    }
+    #[cfg(feature = "std")]
+    #[test]
+    fn no_crossover_no_mutation_keeps_population() {
+        let config = EvolutionConfig::new()
+            .with_n_generations(3)
+            .with_size_generation(4)
+            .with_mutation_rate(0.0)
+            .with_crossover_rate(0.0);
+        let initial_population =
+            Routes::from(vec![Route::new(vec![0, 1, 2]), Route::new(vec![1, 0, 2])]);
+        assert_eq!(
+            Evolution::new(&test_dist_mat(), config)
+                .run(initial_population.clone())
+                .0,
+            initial_population
+        );
+    }
+    #[test]
+    fn adaptive_sizing_stays_within_bounds() {
+        let sizing = AdaptiveSizing::new(3, 12);
+        let mut size = 3;
+        for _ in 0..10 {
+            size = sizing.next_size(size, 10.0, 10.0);
+            assert!((3..=12).contains(&size));
+        }
+        assert_eq!(size, 12);
+        for _ in 0..10 {
+            size = sizing.next_size(size, 10.0, 5.0);
+            assert!((3..=12).contains(&size));
+        }
+        assert_eq!(size, 3);
+    }
+    #[cfg(feature = "std")]
+    #[test]
+    fn adaptive_run_keeps_at_most_max_size() {
+        let distance_mat = DistanceMat::from_coordinates(
+            &(0..9)
+                .map(|node| ((node * 4 % 9) as f64, (node * 7 % 9) as f64))
+                .collect::<Vec<(f64, f64)>>(),
+        );
+        let config = EvolutionConfig::new()
+            .with_n_generations(15)
+            .with_size_generation(4)
+            .with_adaptive_sizing(AdaptiveSizing::new(2, 8));
+        let evolution = Evolution::new(&distance_mat, config);
+        assert!(evolution.run(Routes::random(4, 9)).0.len() <= 8);
+        let mut stepwise = evolution.start(Routes::random(4, 9), 3);
+        for _ in 0..15 {
+            stepwise.evolve(1);
+            assert!((2..=8).contains(&stepwise.size_generation()));
+            assert!(stepwise.population().len() <= 8);
+        }
+    }
+    #[cfg(feature = "std")]
+    #[test]
+    fn refined_config_improves_the_fittest_route() {
+        let distance_mat = DistanceMat::from_coordinates(&[
+            (0.0, 0.0),
+            (1.0, 5.0),
+            (3.0, 2.0),
+            (6.0, 6.0),
+            (7.0, 1.0),
+            (2.0, 8.0),
+            (5.0, 4.0),
+        ]);
+        let policy =
+            LocalSearchPolicy::new(PivotingRule::BestImprovement, NeighborhoodOrder::Sequential);
+        // Without crossover and mutation the routes only change by the local search.
+        let config = EvolutionConfig::new()
+            .with_n_generations(1)
+            .with_size_generation(4)
+            .with_mutation_rate(0.0)
+            .with_crossover_rate(0.0)
+            .with_n_refined(1)
+            .with_local_search_policy(policy);
+        let initial_population = Routes::random(4, 7);
+        let refined = initial_population.get_n_fittest(1, &distance_mat)[0].two_opt_with_policy(
+            &distance_mat,
+            policy,
+            &mut StdRng::seed_from_u64(0),
+        );
+        let mut evolution = Evolution::new(&distance_mat, config).start(initial_population, 1);
+        let population = evolution.evolve(1);
+        assert_eq!(
+            population.get_n_fittest(1, &distance_mat)[0].fitness(&distance_mat),
+            refined.fitness(&distance_mat)
+        );
+    }
+    #[cfg(feature = "std")]
+    #[test]
+    fn local_search_improves_the_fittest_route() {
+        let distance_mat = DistanceMat::from_coordinates(&[
+            (0.0, 0.0),
+            (1.0, 5.0),
+            (3.0, 2.0),
+            (6.0, 6.0),
+            (7.0, 1.0),
+            (2.0, 8.0),
+            (5.0, 4.0),
+        ]);
+        let config = EvolutionConfig::new()
+            .with_n_generations(1)
+            .with_size_generation(4)
+            .with_mutation_rate(0.0)
+            .with_crossover_rate(0.0)
+            .with_n_refined(1);
+        let initial_population = Routes::random(4, 7);
+        let refined = OrOpt.improve(
+            &initial_population.get_n_fittest(1, &distance_mat)[0],
+            &distance_mat,
+        );
+        let mut evolution = Evolution::new(&distance_mat, config)
+            .with_local_search(&OrOpt)
+            .start(initial_population, 1);
+        let population = evolution.evolve(1);
+        assert_eq!(
+            population.get_n_fittest(1, &distance_mat)[0].fitness(&distance_mat),
+            refined.fitness(&distance_mat)
+        );
+    }
+    #[cfg(feature = "std")]
+    fn stepwise_config() -> EvolutionConfig {
+        EvolutionConfig::new()
+            .with_n_generations(12)
+            .with_size_generation(6)
+            .with_selection(Selection::Tournament { k: 2 })
+            .with_mutation_operator(MutationOperator::Swap)
+            .with_crossover_operator(CrossoverOperator::PartiallyMapped)
+    }
+    #[cfg(feature = "std")]
+    #[test]
+    fn same_seed_same_stepwise_evolution() {
+        let distance_mat = DistanceMat::from_coordinates(&[
+            (0.0, 0.0),
+            (1.0, 5.0),
+            (3.0, 2.0),
+            (6.0, 6.0),
+            (7.0, 1.0),
+            (2.0, 8.0),
+            (5.0, 4.0),
+        ]);
+        let initial_population = Routes::random(6, 7);
+        let run = |seed| {
+            let mut evolution = Evolution::new(&distance_mat, stepwise_config())
+                .start(initial_population.clone(), seed);
+            evolution.evolve(usize::MAX).clone()
+        };
+        assert_eq!(run(3), run(3));
+    }
+    #[cfg(feature = "snapshots")]
+    #[test]
+    fn resumed_evolution_continues_exactly() {
+        let distance_mat = DistanceMat::from_coordinates(&[
+            (0.0, 0.0),
+            (1.0, 5.0),
+            (3.0, 2.0),
+            (6.0, 6.0),
+            (7.0, 1.0),
+            (2.0, 8.0),
+            (5.0, 4.0),
+        ]);
+        let path = std::env::temp_dir().join("genetic_algorithm_tsp_resumed_evolution.bin.gz");
+        let mut evolution =
+            Evolution::new(&distance_mat, stepwise_config()).start(Routes::random(6, 7), 11);
+        evolution.evolve(5);
+        evolution.checkpoint(&path).unwrap();
+        let mut resumed = Evolution::resume(&distance_mat, &path).unwrap();
+        assert_eq!(resumed.config(), evolution.config());
+        assert_eq!(resumed.generation(), 5);
+        assert_eq!(resumed.population(), evolution.population());
+        resumed.run_with_checkpoints(&path, 3).unwrap();
+        assert_eq!(resumed.generation(), 12);
+        assert_eq!(resumed.population(), evolution.evolve(usize::MAX));
+    }
+    #[cfg(feature = "snapshots")]
+    #[test]
+    fn resume_checkpoint_without_stagnation() {
+        let distance_mat = clustered_dist_mat(3, 4);
+        let config = stepwise_config().with_restart(RestartPolicy::new(2));
+        let mut evolution = Evolution::new(&distance_mat, config)
+            .start(Routes::random(6, distance_mat.n_units()), 5);
+        evolution.evolve(3);
+        // A checkpoint as it was written before adaptive sizing and restarts were stored.
+        let path = std::env::temp_dir().join("genetic_algorithm_tsp_legacy_checkpoint.bin.gz");
+        let mut encoder = GzEncoder::new(fs::File::create(&path).unwrap(), Compression::default());
+        let legacy = LegacyCheckpoint {
+            config,
+            generation: evolution.generation(),
+            rng_seed: evolution.rng_seed,
+            population: evolution.population().clone(),
+        };
+        bincode::serialize_into(&mut encoder, &legacy).unwrap();
+        encoder.finish().unwrap();
+        let mut resumed = Evolution::resume(&distance_mat, &path).unwrap();
+        assert_eq!(resumed.generation(), 3);
+        assert_eq!(resumed.size_generation(), config.size_generation);
+        assert_eq!(resumed.population(), evolution.population());
+        assert_eq!(resumed.stagnation, Stagnation::new());
+        assert!(!resumed.evolve(usize::MAX).is_empty());
+    }
+}
@@ -0,0 +1,137 @@
+use crate::distance_mat::DistanceMat;
+use crate::objective::Objective;
+use crate::route::Route;
+use alloc::vec::Vec;
+
+/// The most nodes of an instance that `held_karp` solves.
+pub const MAX_NODES: usize = 20;
+
+/// Solve an instance exactly with the Held-Karp dynamic program: the cheapest path through every
+/// subset of the nodes that ends at a given node is built from the cheapest paths through the
+/// smaller subsets. It takes `O(2^n n^2)` time and `O(2^n n)` memory, about a second and 200 MB
+/// for `MAX_NODES` nodes, so it is meant for small instances, e.g. to check in tests how far the
+/// genetic algorithm is from the optimum. Returns `None` for instances with more than
+/// `MAX_NODES` nodes or without any node, and for objectives the program doesn't cover, which
+/// are all but the length of a round-trip or an open path with a single scenario and no time
+/// windows.
+///
+/// # Arguments
+///
+/// * `distance_mat` - The instance that should be solved.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+/// use genetic_algorithm_tsp::exact::held_karp;
+///
+/// let distance_matrix = DistanceMat::new(vec![
+///     vec![0.0, 1.0, 9.0, 1.0],
+///     vec![1.0, 0.0, 1.0, 9.0],
+///     vec![9.0, 1.0, 0.0, 1.0],
+///     vec![1.0, 9.0, 1.0, 0.0],
+/// ]);
+/// let route = held_karp(&distance_matrix).unwrap();
+/// assert_eq!(distance_matrix.get_cost(&route.indexes), 4.0);
+/// ```
+pub fn held_karp(distance_mat: &DistanceMat) -> Option<Route> {
+    let is_open_path = match distance_mat.objective() {
+        Objective::TourLength | Objective::Makespan => false,
+        Objective::PathLength => true,
+        Objective::Latency | Objective::Bottleneck => return None,
+    };
+    let n_nodes = distance_mat.n_units();
+    if distance_mat.n_scenarios() > 1
+        || distance_mat.time_windows().is_some()
+        || n_nodes == 0
+        || n_nodes > MAX_NODES
+    {
+        return None;
+    }
+    let n_subsets = 1 << n_nodes;
+    // `costs[subset * n_nodes + last]` is the cost of the cheapest path through `subset` that
+    // ends at `last`, `previous` the node before `last` on this path.
+    let mut costs = vec![f64::INFINITY; n_subsets * n_nodes];
+    let mut previous = vec![0u8; n_subsets * n_nodes];
+    if is_open_path {
+        for node in 0..n_nodes {
+            costs[(1 << node) * n_nodes + node] = 0.0;
+        }
+    } else {
+        // A round-trip can start anywhere, so it starts at node 0.
+        costs[n_nodes] = 0.0;
+    }
+    for subset in 1..n_subsets {
+        for last in (0..n_nodes).filter(|last| subset & (1 << last) != 0) {
+            let cost = costs[subset * n_nodes + last];
+            if cost == f64::INFINITY {
+                continue;
+            }
+            for next in (0..n_nodes).filter(|next| subset & (1 << next) == 0) {
+                let extended = (subset | (1 << next)) * n_nodes + next;
+                let extended_cost = cost + distance_mat.distance(last, next);
+                if extended_cost < costs[extended] {
+                    costs[extended] = extended_cost;
+                    previous[extended] = last as u8;
+                }
+            }
+        }
+    }
+    let all_nodes = n_subsets - 1;
+    let closing = |last: usize| {
+        if is_open_path {
+            0.0
+        } else {
+            distance_mat.distance(last, 0)
+        }
+    };
+    let mut last = (0..n_nodes).min_by(|a, b| {
+        (costs[all_nodes * n_nodes + a] + closing(*a))
+            .total_cmp(&(costs[all_nodes * n_nodes + b] + closing(*b)))
+    })?;
+    let mut subset = all_nodes;
+    let mut indexes = Vec::with_capacity(n_nodes);
+    for _ in 0..n_nodes {
+        indexes.push(last);
+        let before = previous[subset * n_nodes + last] as usize;
+        subset &= !(1 << last);
+        last = before;
+    }
+    indexes.reverse();
+    Some(Route::new(indexes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{random_asymmetric_dist_mat, test_dist_mat, valid_permutation};
+    use crate::utils::all_permutations;
+
+    #[test]
+    fn held_karp_is_optimal() {
+        for objective in [Objective::TourLength, Objective::PathLength] {
+            let distance_mat = random_asymmetric_dist_mat(7, 0).with_objective(objective);
+            let optimum = all_permutations(7)
+                .iter()
+                .map(|route| distance_mat.get_cost(route))
+                .fold(f64::INFINITY, f64::min);
+            let route = held_karp(&distance_mat).unwrap();
+            valid_permutation(&(0..7).collect::<Vec<usize>>(), &route.indexes);
+            assert!((distance_mat.get_cost(&route.indexes) - optimum).abs() < 1e-9);
+        }
+        let distance_mat = test_dist_mat().with_objective(Objective::Latency);
+        assert_eq!(held_karp(&distance_mat), None);
+    }
+    #[test]
+    fn held_karp_rejects_large_instances() {
+        assert_eq!(
+            held_karp(&random_asymmetric_dist_mat(MAX_NODES + 1, 0)),
+            None
+        );
+        assert_eq!(held_karp(&DistanceMat::new(Vec::<Vec<f64>>::new())), None);
+        assert_eq!(
+            held_karp(&DistanceMat::new(vec![vec![0.0]])),
+            Some(Route::new(vec![0]))
+        );
+    }
+}
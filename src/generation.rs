@@ -0,0 +1,467 @@
+use crate::convergence::{tour_edges, History};
+use crate::distance_mat::DistanceMat;
+use crate::evolution::{Evolution, EvolutionConfig};
+use crate::landscape::FitnessStatistics;
+use crate::routes::Routes;
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+/// One generation of an evolution: the population after the selection together with where it
+/// stands in the run, the statistics of its fitnesses, how long it took to evolve and the seed
+/// of the next generation, so that the run can be resumed from it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Generation {
+    /// The number of the generation, the initial population is generation 0.
+    pub index: usize,
+    /// The population of the generation.
+    pub population: Routes,
+    /// The statistics of the fitnesses of the population, `None` if it is empty.
+    pub statistics: Option<FitnessStatistics>,
+    /// How long it took to evolve this generation from the previous one.
+    pub elapsed: Duration,
+    /// The seed of the random number generator that evolves the next generation. Resuming from
+    /// this generation with `Generations::resume` continues the run exactly.
+    pub rng_seed: u64,
+}
+impl Generation {
+    /// Create a generation, e.g. the initial population of a run.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The number of the generation.
+    /// * `population` - The population of the generation.
+    /// * `distance_mat` - The distance matrix the statistics are computed on.
+    /// * `rng_seed` - The seed of the random number generator that evolves the next generation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::generation::Generation;
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let generation = Generation::new(0, Routes::random(2, 3), &distance_matrix, 42);
+    /// assert_eq!(generation.best_fitness(), Some(-6.0));
+    /// ```
+    pub fn new(
+        index: usize,
+        population: Routes,
+        distance_mat: &DistanceMat,
+        rng_seed: u64,
+    ) -> Self {
+        let fitnesses = population
+            .iter()
+            .map(|route| route.fitness(distance_mat))
+            .collect::<Vec<f64>>();
+        Generation {
+            index,
+            statistics: FitnessStatistics::from_fitnesses(&fitnesses),
+            population,
+            elapsed: Duration::ZERO,
+            rng_seed,
+        }
+    }
+    /// The fitness of the best route of the generation, `None` if the population is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::generation::Generation;
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let generation = Generation::new(0, Routes::from(vec![]), &distance_matrix, 42);
+    /// assert_eq!(generation.best_fitness(), None);
+    /// ```
+    pub fn best_fitness(&self) -> Option<f64> {
+        self.statistics.map(|statistics| statistics.max)
+    }
+}
+
+/// An iterator over the generations of a stepwise `Evolution`, which ends when all generations
+/// of its configuration are evolved. Every generation is evolved with the configuration and a
+/// `StdRng` seeded from the seed recorded in the previous generation, so the same initial seed
+/// always yields the same generations.
+#[derive(Debug)]
+pub struct Generations<'a> {
+    /// The stepwise evolution that evolves the generations.
+    evolution: Evolution<'a>,
+}
+impl<'a> Generations<'a> {
+    /// Iterate over the generations of a stepwise evolution.
+    ///
+    /// # Arguments
+    ///
+    /// * `evolution` - A stepwise evolution, see `Evolution::start`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::evolution::{Evolution, EvolutionConfig};
+    /// use genetic_algorithm_tsp::generation::Generations;
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 2.0, 3.0],
+    ///     vec![1.0, 0.0, 3.0, 1.0],
+    ///     vec![2.0, 3.0, 0.0, 2.0],
+    ///     vec![3.0, 1.0, 2.0, 0.0],
+    /// ]);
+    /// let config = EvolutionConfig::new().with_n_generations(10).with_size_generation(5);
+    /// let evolution = Evolution::new(&distance_matrix, config).start(Routes::random(5, 4), 42);
+    /// for generation in Generations::new(evolution) {
+    ///     println!("{}: {:?} in {:?}", generation.index, generation.best_fitness(), generation.elapsed);
+    /// }
+    /// ```
+    pub fn new(evolution: Evolution<'a>) -> Self {
+        Generations { evolution }
+    }
+    /// Continue an evolution after a generation, e.g. one that was stored in a checkpoint. The
+    /// following generations are the same as in the original run, only adaptive sizing and
+    /// restarts start to track the progress anew.
+    ///
+    /// # Arguments
+    ///
+    /// * `generation` - The last generation of the run that should be continued.
+    /// * `distance_mat` - The distance matrix on which the fitness will be computed on.
+    /// * `config` - The configuration of the original run.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::evolution::{Evolution, EvolutionConfig};
+    /// use genetic_algorithm_tsp::generation::Generations;
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 2.0, 3.0],
+    ///     vec![1.0, 0.0, 3.0, 1.0],
+    ///     vec![2.0, 3.0, 0.0, 2.0],
+    ///     vec![3.0, 1.0, 2.0, 0.0],
+    /// ]);
+    /// let config = EvolutionConfig::new().with_n_generations(10).with_size_generation(5);
+    /// let evolution = Evolution::new(&distance_matrix, config).start(Routes::random(5, 4), 42);
+    /// let mut full_run = Generations::new(evolution);
+    /// let checkpoint = full_run.nth(4).unwrap();
+    /// let mut resumed_run = Generations::resume(checkpoint, &distance_matrix, config);
+    /// assert_eq!(full_run.next().unwrap().population, resumed_run.next().unwrap().population);
+    /// ```
+    pub fn resume(
+        generation: Generation,
+        distance_mat: &'a DistanceMat,
+        config: EvolutionConfig,
+    ) -> Self {
+        Generations::new(
+            Evolution::new(distance_mat, config)
+                .start(generation.population, generation.rng_seed)
+                .at_generation(generation.index),
+        )
+    }
+}
+impl Iterator for Generations<'_> {
+    type Item = Generation;
+    fn next(&mut self) -> Option<Generation> {
+        if self.evolution.is_finished() {
+            return None;
+        }
+        let before = Instant::now();
+        self.evolution.evolve(1);
+        let elapsed = before.elapsed();
+        let mut generation = Generation::new(
+            self.evolution.generation(),
+            self.evolution.population().clone(),
+            self.evolution.distance_mat(),
+            self.evolution.rng_seed(),
+        );
+        generation.elapsed = elapsed;
+        Some(generation)
+    }
+}
+
+/// The statistics of a generation that are reported to the observer of
+/// `evolve_population_with_observer` and recorded in a `History`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GenerationStats {
+    /// The fitness of the best route.
+    pub best_fitness: f64,
+    /// The mean fitness of all routes.
+    pub mean_fitness: f64,
+    /// The median fitness of all routes, the mean of the two middle fitnesses for an even
+    /// number of routes.
+    pub median_fitness: f64,
+    /// The standard deviation of the fitnesses of all routes.
+    pub std_dev_fitness: f64,
+    /// The fitness of the worst route.
+    pub worst_fitness: f64,
+    /// The number of distinct edges in the population per node: 1.0 if all routes use the same
+    /// edges and up to the number of routes if they share no edge.
+    pub diversity: f64,
+    /// How many milliseconds it took to evolve the generation, 0.0 if it wasn't measured.
+    pub elapsed_ms: f64,
+}
+impl GenerationStats {
+    /// Compute the statistics of a population, `None` if it is empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `population` - The population of the generation.
+    /// * `distance_mat` - The distance matrix the fitness is computed on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::generation::GenerationStats;
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let population = Routes::from(vec![Route::new(vec![0, 1, 2]), Route::new(vec![2, 1, 0])]);
+    /// let stats = GenerationStats::new(&population, &distance_matrix).unwrap();
+    /// assert_eq!(stats.best_fitness, -6.0);
+    /// // Both routes are the same round-trip.
+    /// assert_eq!(stats.diversity, 1.0);
+    /// ```
+    pub fn new(population: &Routes, distance_mat: &DistanceMat) -> Option<Self> {
+        let fitnesses = population
+            .iter()
+            .map(|route| route.fitness(distance_mat))
+            .collect::<Vec<f64>>();
+        let statistics = FitnessStatistics::from_fitnesses(&fitnesses)?;
+        let mut sorted_fitnesses = fitnesses;
+        sorted_fitnesses.sort_unstable_by(f64::total_cmp);
+        let middle = sorted_fitnesses.len() / 2;
+        let median_fitness = if sorted_fitnesses.len() % 2 == 0 {
+            (sorted_fitnesses[middle - 1] + sorted_fitnesses[middle]) / 2.0
+        } else {
+            sorted_fitnesses[middle]
+        };
+        let n_nodes = population.get_n_nodes();
+        let n_edges = population
+            .iter()
+            .flat_map(|route| tour_edges(&route.indexes))
+            .collect::<HashSet<(usize, usize)>>()
+            .len();
+        Some(GenerationStats {
+            best_fitness: statistics.max,
+            mean_fitness: statistics.mean,
+            median_fitness,
+            std_dev_fitness: statistics.std_dev,
+            worst_fitness: statistics.min,
+            diversity: if n_nodes == 0 {
+                0.0
+            } else {
+                n_edges as f64 / n_nodes as f64
+            },
+            elapsed_ms: 0.0,
+        })
+    }
+}
+
+/// Evolve a stepwise evolution generation by generation with its configuration until all of
+/// its generations are evolved, and report the statistics of every generation to `observer`,
+/// e.g. to log the progress or plot the convergence while the evolution runs. The observer gets
+/// the number of the generation, starting at 1, and its statistics.
+///
+/// # Arguments
+///
+/// * `evolution` - A stepwise evolution, see `Evolution::start`.
+/// * `observer` - Is called after every generation.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+/// use genetic_algorithm_tsp::evolution::{Evolution, EvolutionConfig};
+/// use genetic_algorithm_tsp::generation::{evolve_population_with_observer, GenerationStats};
+/// use genetic_algorithm_tsp::routes::Routes;
+///
+/// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+/// let config = EvolutionConfig::new().with_n_generations(10).with_size_generation(5);
+/// let mut evolution = Evolution::new(&distance_matrix, config).start(Routes::random(2, 3), 42);
+/// evolve_population_with_observer(&mut evolution, |generation: usize, stats: &GenerationStats| {
+///     println!("{}: best {}, diversity {}", generation, stats.best_fitness, stats.diversity)
+/// });
+/// ```
+pub fn evolve_population_with_observer<F: FnMut(usize, &GenerationStats)>(
+    evolution: &mut Evolution<'_>,
+    mut observer: F,
+) {
+    let distance_matrix = evolution.distance_mat();
+    while !evolution.is_finished() {
+        let start = Instant::now();
+        let population = evolution.evolve(1);
+        let elapsed = start.elapsed();
+        if let Some(mut stats) = GenerationStats::new(population, distance_matrix) {
+            stats.elapsed_ms = elapsed.as_secs_f64() * 1000.0;
+            observer(evolution.generation(), &stats);
+        }
+    }
+}
+
+/// Evolve a stepwise evolution generation by generation with its configuration until all of
+/// its generations are evolved, and record the `GenerationStats` of every generation as
+/// `History`, e.g. to analyze the convergence of a configuration. The statistics are the ones
+/// `evolve_population_with_observer` reports.
+///
+/// # Arguments
+///
+/// * `evolution` - A stepwise evolution, see `Evolution::start`.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+/// use genetic_algorithm_tsp::evolution::{Evolution, EvolutionConfig};
+/// use genetic_algorithm_tsp::generation::evolve_population_with_stats;
+/// use genetic_algorithm_tsp::routes::Routes;
+///
+/// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+/// let config = EvolutionConfig::new().with_n_generations(10).with_size_generation(5);
+/// let mut evolution = Evolution::new(&distance_matrix, config).start(Routes::random(2, 3), 42);
+/// let history = evolve_population_with_stats(&mut evolution);
+/// assert_eq!(history.n_generations(), 10);
+/// assert_eq!(history.generations[9].best_fitness, -6.0);
+/// ```
+pub fn evolve_population_with_stats(evolution: &mut Evolution<'_>) -> History {
+    let mut history = History::default();
+    evolve_population_with_observer(evolution, |_, stats| history.generations.push(*stats));
+    history
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_dist_mat;
+    #[test]
+    fn same_seed_same_generations() {
+        let initial_population = Routes::random(3, 3);
+        let distance_mat = test_dist_mat();
+        let config = EvolutionConfig::new()
+            .with_n_generations(5)
+            .with_size_generation(4);
+        let run = |seed| {
+            Generations::new(
+                Evolution::new(&distance_mat, config).start(initial_population.clone(), seed),
+            )
+            .map(|generation| (generation.index, generation.population, generation.rng_seed))
+            .collect::<Vec<(usize, Routes, u64)>>()
+        };
+        let generations = run(7);
+        assert_eq!(
+            generations
+                .iter()
+                .map(|(index, _, _)| *index)
+                .collect::<Vec<usize>>(),
+            vec![1, 2, 3, 4, 5]
+        );
+        assert_eq!(generations, run(7));
+    }
+    #[test]
+    fn generations_follow_the_configured_evolution() {
+        let distance_mat = DistanceMat::from_coordinates(&[
+            (0.0, 0.0),
+            (3.0, 1.0),
+            (1.0, 4.0),
+            (5.0, 2.0),
+            (2.0, 2.0),
+            (4.0, 5.0),
+        ]);
+        let config = EvolutionConfig::new()
+            .with_n_generations(8)
+            .with_size_generation(5)
+            .with_mutation_rate(0.1)
+            .with_selection(crate::selection::Selection::Tournament { k: 2 })
+            .with_elitism(1);
+        let evolution = Evolution::new(&distance_mat, config).start(Routes::random(5, 6), 3);
+        let generations = Generations::new(evolution.clone()).collect::<Vec<Generation>>();
+        assert_eq!(generations.len(), 8);
+        let mut stepwise = evolution;
+        assert_eq!(stepwise.evolve(8), &generations[7].population);
+        let resumed = Generations::resume(generations[2].clone(), &distance_mat, config)
+            .collect::<Vec<Generation>>();
+        assert_eq!(resumed.len(), 5);
+        assert_eq!(resumed[4].population, generations[7].population);
+    }
+    #[test]
+    fn observer_sees_every_generation() {
+        let mut generations = Vec::new();
+        let distance_mat = test_dist_mat();
+        let config = EvolutionConfig::new()
+            .with_n_generations(4)
+            .with_size_generation(3);
+        let mut evolution = Evolution::new(&distance_mat, config).start(Routes::random(3, 3), 0);
+        evolve_population_with_observer(&mut evolution, |generation, stats| {
+            assert!(stats.worst_fitness <= stats.mean_fitness);
+            assert!(stats.mean_fitness <= stats.best_fitness);
+            generations.push(generation);
+        });
+        assert_eq!(generations, vec![1, 2, 3, 4]);
+        assert_eq!(evolution.population().len(), 3);
+    }
+    #[test]
+    fn stats_of_every_generation() {
+        let distance_mat = test_dist_mat();
+        let config = EvolutionConfig::new()
+            .with_n_generations(6)
+            .with_size_generation(4);
+        let mut evolution = Evolution::new(&distance_mat, config).start(Routes::random(4, 3), 0);
+        let history = evolve_population_with_stats(&mut evolution);
+        assert_eq!(history.n_generations(), 6);
+        assert!(history.stable_edge_fractions.is_empty());
+        assert!(evolution.population().len() <= 4);
+        for stats in &history.generations {
+            assert!(stats.worst_fitness <= stats.median_fitness);
+            assert!(stats.median_fitness <= stats.best_fitness);
+            assert!(stats.std_dev_fitness >= 0.0);
+            assert!(stats.elapsed_ms >= 0.0);
+        }
+        assert!(history
+            .generations
+            .windows(2)
+            .all(|stats| stats[0].best_fitness <= stats[1].best_fitness));
+    }
+    #[test]
+    fn median_of_even_population() {
+        let population = Routes::from(vec![
+            crate::route::Route::new(vec![0, 1, 2, 3]),
+            crate::route::Route::new(vec![0, 2, 1, 3]),
+        ]);
+        let distance_mat = DistanceMat::new(vec![
+            vec![0.0, 1.0, 3.0, 1.0],
+            vec![1.0, 0.0, 1.0, 3.0],
+            vec![3.0, 1.0, 0.0, 1.0],
+            vec![1.0, 3.0, 1.0, 0.0],
+        ]);
+        let stats = GenerationStats::new(&population, &distance_mat).unwrap();
+        assert_eq!(stats.median_fitness, -6.0);
+        assert_eq!(stats.std_dev_fitness, 2.0);
+    }
+    #[test]
+    fn diversity_counts_distinct_edges() {
+        let population = Routes::from(vec![
+            crate::route::Route::new(vec![0, 1, 2, 3]),
+            crate::route::Route::new(vec![0, 2, 1, 3]),
+        ]);
+        let distance_mat = DistanceMat::new(vec![vec![1.0; 4]; 4]);
+        let stats = GenerationStats::new(&population, &distance_mat).unwrap();
+        // (0,1), (1,2), (2,3), (0,3) and (0,2), (1,3)
+        assert_eq!(stats.diversity, 1.5);
+        assert_eq!(
+            GenerationStats::new(&Routes::from(vec![]), &distance_mat),
+            None
+        );
+    }
+    #[test]
+    fn statistics_of_population() {
+        let generation = Generation::new(3, Routes::random(6, 3), &test_dist_mat(), 0);
+        let statistics = generation.statistics.unwrap();
+        assert_eq!(statistics.n_samples, 6);
+        assert_eq!(statistics.min, -6.0);
+    }
+}
@@ -0,0 +1,267 @@
+use crate::compat::HashMap;
+use crate::distance_mat::DistanceMat;
+use crate::route::Route;
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt;
+use core::hash::Hash;
+
+/// The errors that can occur while mapping external ids to the indexes of a distance matrix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdMapError {
+    /// An id appears more than once in the ids of the nodes.
+    DuplicateId {
+        /// The index at which the id appears again.
+        index: usize,
+    },
+    /// An id is not the id of any node.
+    UnknownId {
+        /// The position of the unknown id in the sequence that was mapped.
+        position: usize,
+    },
+}
+impl fmt::Display for IdMapError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IdMapError::DuplicateId { index } => {
+                write!(formatter, "The id of node {} is already used", index)
+            }
+            IdMapError::UnknownId { position } => {
+                write!(
+                    formatter,
+                    "The id at position {} is not a known node",
+                    position
+                )
+            }
+        }
+    }
+}
+impl Error for IdMapError {}
+
+/// A bidirectional mapping between the external ids of the nodes, e.g. customer numbers or
+/// names, and their indexes in a `DistanceMat`. The node with the `i`-th id has index `i`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdMap<T: Eq + Hash> {
+    /// The id of every node, the index of a node is its position.
+    ids: Vec<T>,
+    /// The index of every id.
+    indexes: HashMap<T, usize>,
+}
+impl<T: Eq + Hash + Clone> IdMap<T> {
+    /// Create the mapping from the ids of the nodes in the order of their indexes.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - The id of every node, the ids have to be distinct.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::id_map::IdMap;
+    ///
+    /// let id_map = IdMap::new(vec!["depot", "bakery", "school"]).unwrap();
+    /// assert_eq!(id_map.index(&"school"), Some(2));
+    /// assert!(IdMap::new(vec!["depot", "depot"]).is_err());
+    /// ```
+    pub fn new(ids: Vec<T>) -> Result<Self, IdMapError> {
+        let mut indexes = HashMap::with_capacity_and_hasher(ids.len(), Default::default());
+        for (index, id) in ids.iter().enumerate() {
+            if indexes.insert(id.clone(), index).is_some() {
+                return Err(IdMapError::DuplicateId { index });
+            }
+        }
+        Ok(IdMap { ids, indexes })
+    }
+    /// Get the index of the node with an id.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::id_map::IdMap;
+    ///
+    /// let id_map = IdMap::new(vec![17, 4, 9]).unwrap();
+    /// assert_eq!(id_map.index(&4), Some(1));
+    /// assert_eq!(id_map.index(&5), None);
+    /// ```
+    pub fn index(&self, id: &T) -> Option<usize> {
+        self.indexes.get(id).copied()
+    }
+    /// Get the id of the node with an index.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the node in the distance matrix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::id_map::IdMap;
+    ///
+    /// let id_map = IdMap::new(vec![17, 4, 9]).unwrap();
+    /// assert_eq!(id_map.id(0), Some(&17));
+    /// assert_eq!(id_map.id(3), None);
+    /// ```
+    pub fn id(&self, index: usize) -> Option<&T> {
+        self.ids.get(index)
+    }
+    /// The ids of all nodes in the order of their indexes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::id_map::IdMap;
+    ///
+    /// let id_map = IdMap::new(vec![17, 4, 9]).unwrap();
+    /// assert_eq!(id_map.ids(), &[17, 4, 9]);
+    /// ```
+    pub fn ids(&self) -> &[T] {
+        &self.ids
+    }
+    /// The number of nodes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::id_map::IdMap;
+    ///
+    /// assert_eq!(IdMap::new(vec![17, 4, 9]).unwrap().len(), 3);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+    /// Are there no nodes?
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::id_map::IdMap;
+    ///
+    /// assert!(IdMap::<usize>::new(vec![]).unwrap().is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+    /// Create the distance matrix of the nodes, the distance from one node to another is
+    /// computed from their ids.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance` - The distance from the node with the first id to the node with the second.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::id_map::IdMap;
+    ///
+    /// let id_map = IdMap::new(vec![10, 13, 17]).unwrap();
+    /// let distance_matrix = id_map.distance_mat(|from, to| (*from as f64 - *to as f64).abs());
+    /// assert_eq!(distance_matrix.get_cost(&[0, 1, 2]), 14.0);
+    /// ```
+    pub fn distance_mat<F: Fn(&T, &T) -> f64>(&self, distance: F) -> DistanceMat {
+        DistanceMat::new(
+            self.ids
+                .iter()
+                .map(|from| self.ids.iter().map(|to| distance(from, to)).collect())
+                .collect(),
+        )
+    }
+    /// Create the route that visits the nodes with `ids` in this order.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - The ids of the nodes in the order in which they are visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::id_map::IdMap;
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let id_map = IdMap::new(vec!["depot", "bakery", "school"]).unwrap();
+    /// assert_eq!(id_map.route(&["school", "depot", "bakery"]), Ok(Route::new(vec![2, 0, 1])));
+    /// assert!(id_map.route(&["school", "park"]).is_err());
+    /// ```
+    pub fn route(&self, ids: &[T]) -> Result<Route, IdMapError> {
+        ids.iter()
+            .enumerate()
+            .map(|(position, id)| self.index(id).ok_or(IdMapError::UnknownId { position }))
+            .collect::<Result<Vec<usize>, IdMapError>>()
+            .map(Route::new)
+    }
+    /// Get the ids of the nodes of a route in the order in which they are visited.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The route whose nodes should be mapped to their ids.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::id_map::IdMap;
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let id_map = IdMap::new(vec!["depot", "bakery", "school"]).unwrap();
+    /// assert_eq!(id_map.route_ids(&Route::new(vec![2, 0, 1])), Ok(vec![&"school", &"depot", &"bakery"]));
+    /// assert!(id_map.route_ids(&Route::new(vec![3])).is_err());
+    /// ```
+    pub fn route_ids(&self, route: &Route) -> Result<Vec<&T>, IdMapError> {
+        route
+            .indexes
+            .iter()
+            .enumerate()
+            .map(|(position, index)| self.id(*index).ok_or(IdMapError::UnknownId { position }))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duplicate_id() {
+        assert_eq!(
+            IdMap::new(vec!["a", "b", "c", "b"]),
+            Err(IdMapError::DuplicateId { index: 3 })
+        );
+    }
+    #[test]
+    fn round_trip() {
+        let id_map = IdMap::new(vec![
+            String::from("a"),
+            String::from("b"),
+            String::from("c"),
+        ])
+        .unwrap();
+        let ids = [String::from("c"), String::from("a"), String::from("b")];
+        let route = id_map.route(&ids).unwrap();
+        assert_eq!(route.indexes, vec![2, 0, 1]);
+        assert_eq!(
+            id_map.route_ids(&route).unwrap(),
+            ids.iter().collect::<Vec<&String>>()
+        );
+    }
+    #[test]
+    fn unknown_ids() {
+        let id_map = IdMap::new(vec![5, 6, 7]).unwrap();
+        assert_eq!(
+            id_map.route(&[5, 8]),
+            Err(IdMapError::UnknownId { position: 1 })
+        );
+        assert_eq!(
+            id_map.route_ids(&Route::new(vec![0, 1, 3])),
+            Err(IdMapError::UnknownId { position: 2 })
+        );
+    }
+    #[test]
+    fn distance_mat_in_index_order() {
+        let id_map = IdMap::new(vec![30, 10, 20]).unwrap();
+        let distance_mat = id_map.distance_mat(|from, to| (*from * 100 + *to) as f64);
+        assert_eq!(distance_mat.get_distance(&[0, 1]), Ok(3010.0 + 1030.0));
+        assert_eq!(distance_mat.n_units(), 3);
+    }
+}
@@ -0,0 +1,243 @@
+#[cfg(feature = "std")]
+use crate::compat::hash_set;
+use crate::compat::{FixedState, HashSet};
+#[cfg(feature = "std")]
+use crate::routes::MAX_FRUITLESS_REPLACEMENTS;
+#[cfg(feature = "std")]
+use crate::selection::DuplicatePolicy;
+use alloc::vec::Vec;
+use core::hash::Hash;
+#[cfg(feature = "std")]
+use genetic_algorithm_traits::{Individual, Population};
+
+/// A population of any individual, e.g. of the individuals of routing variants like
+/// `SelectiveRoute` and `CapacitatedRoute`. Like `Routes` it never contains the same individual
+/// twice. `MultiRoutes` is the population of `MultiRoute`s.
+#[derive(Debug, Clone)]
+pub struct Individuals<I> {
+    /// The individuals of the population, hashed with the fixed hasher like `Routes`, so that the
+    /// order in which they are bred only depends on the individuals.
+    pub(crate) individuals: HashSet<I, FixedState>,
+}
+impl<I: Eq + Hash> PartialEq for Individuals<I> {
+    fn eq(&self, other: &Self) -> bool {
+        self.individuals == other.individuals
+    }
+}
+impl<I: Eq + Hash> From<Vec<I>> for Individuals<I> {
+    /// Create a population from a vector of individuals, duplicates are only kept once.
+    ///
+    /// # Arguments
+    ///
+    /// * `individuals` - The individuals of the population.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::individuals::Individuals;
+    /// use genetic_algorithm_tsp::selective::SelectiveRoute;
+    ///
+    /// let individuals = Individuals::from(vec![
+    ///     SelectiveRoute::new(4, 0, vec![1, 2]),
+    ///     SelectiveRoute::new(4, 0, vec![1, 2]),
+    /// ]);
+    /// assert_eq!(individuals.len(), 1);
+    /// ```
+    fn from(individuals: Vec<I>) -> Self {
+        Individuals {
+            individuals: individuals.into_iter().collect(),
+        }
+    }
+}
+impl<I> Individuals<I> {
+    /// Get the number of individuals in the population.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::individuals::Individuals;
+    /// use genetic_algorithm_tsp::selective::SelectiveRoute;
+    ///
+    /// assert_eq!(Individuals::from(vec![SelectiveRoute::new(4, 0, vec![1])]).len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.individuals.len()
+    }
+    /// Check whether the population is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::individuals::Individuals;
+    /// use genetic_algorithm_tsp::selective::SelectiveRoute;
+    ///
+    /// assert!(Individuals::<SelectiveRoute>::from(vec![]).is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.individuals.is_empty()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, I: Individual<'a> + Eq + Hash + 'a> Individuals<I> {
+    /// Cross every individual with every other individual and mutate the offspring, the
+    /// parents are appended at the end.
+    ///
+    /// # Arguments
+    ///
+    /// * `mutate_prob` - The probabilty of an inviduals beeing mutated.
+    ///
+    fn breed(&self, mutate_prob: f32) -> Vec<I> {
+        let parents = self.individuals.iter().collect::<Vec<&I>>();
+        let mut offspring = Vec::with_capacity(parents.len() * parents.len());
+        for (idx, parent_a) in parents.iter().enumerate() {
+            for (other_idx, parent_b) in parents.iter().enumerate() {
+                if idx != other_idx {
+                    offspring.push(parent_a.crossover(parent_b).mutate(mutate_prob));
+                }
+            }
+        }
+        offspring.extend(parents.into_iter().cloned());
+        offspring
+    }
+    /// Evolve the population like `Population::evolve`, but apply a `DuplicatePolicy` to the
+    /// offspring. Offspring are equivalent if `tour_key` maps them to the same key: `Dedupe`
+    /// keeps one of them, and `DedupeWithRandomReplacement` additionally draws random
+    /// individuals, whose keys are distinct from all others, until there are as many individuals
+    /// as offspring or too many draws in a row were equivalent to an existing one.
+    ///
+    /// # Arguments
+    ///
+    /// * `mutate_prob` - The probabilty of an inviduals beeing mutated.
+    /// * `policy` - What happens to equivalent offspring.
+    /// * `tour_key` - Maps an individual to the key equivalent individuals share, e.g.
+    ///   `MultiRoute::tour_key`.
+    /// * `random_individual` - Creates the random individuals that replace duplicates, e.g.
+    ///   `MultiRoute::random`. Capture a seeded rng to make the replacements reproducible.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::multi_route::{MultiRoute, MultiRoutes};
+    /// use genetic_algorithm_tsp::selection::DuplicatePolicy;
+    /// use genetic_algorithm_traits::Population;
+    ///
+    /// let multi_routes = MultiRoutes::from(vec![
+    ///     MultiRoute::new(0, vec![vec![1], vec![2]]),
+    ///     MultiRoute::new(0, vec![vec![2], vec![1]]),
+    /// ]);
+    /// let evolved = multi_routes.evolve_with_duplicate_policy(
+    ///     0.5,
+    ///     DuplicatePolicy::Dedupe,
+    ///     MultiRoute::tour_key,
+    ///     || MultiRoute::random(3, 0, 2),
+    /// );
+    /// let mut keys: Vec<_> = evolved.iter().map(MultiRoute::tour_key).collect();
+    /// keys.sort();
+    /// keys.dedup();
+    /// assert_eq!(keys.len(), evolved.len());
+    /// ```
+    pub fn evolve_with_duplicate_policy<K: Eq + Hash>(
+        &self,
+        mutate_prob: f32,
+        policy: DuplicatePolicy,
+        tour_key: impl Fn(&I) -> K,
+        mut random_individual: impl FnMut() -> I,
+    ) -> Self {
+        let offspring = self.breed(mutate_prob);
+        if policy == DuplicatePolicy::KeepEquivalent {
+            return Individuals::from(offspring);
+        }
+        let n_offspring = offspring.len();
+        let mut keys: HashSet<K> = HashSet::default();
+        let mut evolved: Vec<I> = offspring
+            .into_iter()
+            .filter(|individual| keys.insert(tour_key(individual)))
+            .collect();
+        if policy == DuplicatePolicy::DedupeWithRandomReplacement {
+            let mut n_fruitless = 0;
+            while evolved.len() < n_offspring && n_fruitless < MAX_FRUITLESS_REPLACEMENTS {
+                let individual = random_individual();
+                if keys.insert(tour_key(&individual)) {
+                    evolved.push(individual);
+                    n_fruitless = 0;
+                } else {
+                    n_fruitless += 1;
+                }
+            }
+        }
+        Individuals::from(evolved)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, I: Individual<'a> + Eq + Hash + 'a> Population<'a> for Individuals<I> {
+    type Individual = I;
+    type IndividualCollection = hash_set::Iter<'a, I>;
+
+    /// Get the n fittest individuals as a new population.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The number of individuals you would like to keep.
+    /// * `cost_data` - The cost data the fitness should be evaluated on, e.g. the distance matrix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::individuals::Individuals;
+    /// use genetic_algorithm_tsp::selective::{PrizeCost, SelectiveRoute};
+    /// use genetic_algorithm_traits::Population;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let prize_cost = PrizeCost::new(distance_matrix, vec![0.0, 5.0, 1.0]);
+    /// let individuals = Individuals::from(vec![
+    ///     SelectiveRoute::new(3, 0, vec![1]),
+    ///     SelectiveRoute::new(3, 0, vec![2]),
+    /// ]);
+    /// let fittest = individuals.get_fittest_population(1, &prize_cost);
+    /// assert_eq!(fittest, Individuals::from(vec![SelectiveRoute::new(3, 0, vec![1])]));
+    /// ```
+    fn get_fittest_population(&'a self, n: usize, cost_data: &'a I::IndividualCost) -> Self {
+        Individuals::from(self.get_n_fittest(n, cost_data))
+    }
+    /// Evolve the population: every individual is crossed with every other individual, the
+    /// offspring are mutated and the parents are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `mutate_prob` - The probabilty of an inviduals beeing mutated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::individuals::Individuals;
+    /// use genetic_algorithm_tsp::selective::SelectiveRoute;
+    /// use genetic_algorithm_traits::Population;
+    ///
+    /// let individuals = Individuals::from(
+    ///     (0..4).map(|_| SelectiveRoute::random(8, 0)).collect::<Vec<SelectiveRoute>>(),
+    /// );
+    /// let evolved = individuals.evolve(0.5);
+    /// ```
+    fn evolve(&self, mutate_prob: f32) -> Self {
+        Individuals::from(self.breed(mutate_prob))
+    }
+    /// Iterate over the individuals of the population.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::individuals::Individuals;
+    /// use genetic_algorithm_tsp::selective::SelectiveRoute;
+    /// use genetic_algorithm_traits::Population;
+    ///
+    /// for selective_route in Individuals::from(vec![SelectiveRoute::random(8, 0)]).iter() {
+    ///     println!("{}", selective_route);
+    /// }
+    /// ```
+    fn iter(&'a self) -> hash_set::Iter<'a, I> {
+        self.individuals.iter()
+    }
+}
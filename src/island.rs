@@ -0,0 +1,336 @@
+use crate::distance_mat::DistanceMat;
+use crate::evolution::{Evolution, EvolutionConfig};
+use crate::route::Route;
+use crate::routes::Routes;
+use crossbeam_utils::thread;
+use std::sync::mpsc;
+
+/// How the islands of `evolve_islands` exchange routes. The islands form a ring: every
+/// `interval` generations each island sends copies of its `n_migrants` fittest routes to the
+/// next island and receives the fittest routes of the previous one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Migration {
+    /// After how many generations the islands exchange routes.
+    pub interval: usize,
+    /// How many of its fittest routes an island sends to its neighbor.
+    pub n_migrants: usize,
+}
+impl Migration {
+    /// Create a migration scheme.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - After how many generations the islands exchange routes, 0 means never.
+    /// * `n_migrants` - How many of its fittest routes an island sends to its neighbor.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::island::Migration;
+    ///
+    /// let migration = Migration::new(10, 2);
+    /// ```
+    pub fn new(interval: usize, n_migrants: usize) -> Self {
+        Migration {
+            interval,
+            n_migrants,
+        }
+    }
+}
+
+/// Derive the seed of a random number stream from a master seed with SplitMix64, e.g. the seed
+/// of every island of a run. Different streams give unrelated seeds, so the islands don't share
+/// a random number generator and the whole run is reproducible from the master seed.
+///
+/// # Arguments
+///
+/// * `master_seed` - The seed of the whole run.
+/// * `stream` - The id of the stream, e.g. the index of the island.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::island::stream_seed;
+///
+/// assert_eq!(stream_seed(42, 3), stream_seed(42, 3));
+/// assert_ne!(stream_seed(42, 3), stream_seed(42, 4));
+/// ```
+pub fn stream_seed(master_seed: u64, stream: u64) -> u64 {
+    let mut z =
+        master_seed.wrapping_add(stream.wrapping_add(1).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Evolve one copy of the initial population per island in parallel, one thread per island, and
+/// let them exchange their fittest routes as set by `migration`. The islands explore different
+/// regions of the search space, while the migrants spread good building blocks between them,
+/// which usually gives better routes than isolated copies. The fittest routes of all islands
+/// are combined at the end.
+///
+/// # Arguments
+///
+/// * `initial_population` - Your initial population that should be evolved.
+/// * `distance_matrix` - The distance matrix on which the fitness will be computed on.
+/// * `config` - The parameters of the evolution of every island, `n_jobs` is the number of
+///   islands, at least one island is evolved.
+/// * `migration` - How the islands exchange routes.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+/// use genetic_algorithm_tsp::evolution::EvolutionConfig;
+/// use genetic_algorithm_tsp::island::{evolve_islands, Migration};
+/// use genetic_algorithm_tsp::routes::Routes;
+///
+/// let evolved_population = evolve_islands(
+///     Routes::random(4, 3),
+///     &DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]),
+///     EvolutionConfig::new()
+///         .with_n_generations(20)
+///         .with_size_generation(5)
+///         .with_n_jobs(3),
+///     Migration::new(5, 1),
+/// );
+/// ```
+pub fn evolve_islands(
+    initial_population: Routes,
+    distance_matrix: &DistanceMat,
+    config: EvolutionConfig,
+    migration: Migration,
+) -> Routes {
+    evolve_islands_seeded(
+        initial_population,
+        distance_matrix,
+        config,
+        migration,
+        rand::random(),
+    )
+}
+
+/// Evolve islands like `evolve_islands`, but reproducibly: every island is a stepwise
+/// `Evolution` with the configuration, seeded with `stream_seed(master_seed, island)`, so the
+/// same master seed always gives the same final population, no matter how the threads are
+/// scheduled.
+///
+/// # Arguments
+///
+/// * `initial_population` - Your initial population that should be evolved.
+/// * `distance_matrix` - The distance matrix on which the fitness will be computed on.
+/// * `config` - The parameters of the evolution of every island, `n_jobs` is the number of
+///   islands, at least one island is evolved.
+/// * `migration` - How the islands exchange routes.
+/// * `master_seed` - The seed all seeds of the islands are derived from.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+/// use genetic_algorithm_tsp::evolution::EvolutionConfig;
+/// use genetic_algorithm_tsp::island::{evolve_islands_seeded, Migration};
+/// use genetic_algorithm_tsp::routes::Routes;
+///
+/// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+/// let config = EvolutionConfig::new()
+///     .with_n_generations(20)
+///     .with_size_generation(5)
+///     .with_mutation_rate(0.2)
+///     .with_n_jobs(3);
+/// let run = |seed| {
+///     evolve_islands_seeded(Routes::random(4, 3), &distance_matrix, config, Migration::new(5, 1), seed)
+/// };
+/// assert_eq!(run(7), run(7));
+/// ```
+pub fn evolve_islands_seeded(
+    initial_population: Routes,
+    distance_matrix: &DistanceMat,
+    config: EvolutionConfig,
+    migration: Migration,
+    master_seed: u64,
+) -> Routes {
+    let n_islands = config.n_jobs.max(1);
+    Routes::from(
+        evolve_island_populations(
+            vec![initial_population; n_islands],
+            distance_matrix,
+            config,
+            migration,
+            master_seed,
+        )
+        .into_iter()
+        .flatten()
+        .collect::<Vec<Route>>(),
+    )
+}
+
+/// Evolve every initial population on its own island and return the fittest routes of every
+/// island, in the order of the islands.
+fn evolve_island_populations(
+    initial_populations: Vec<Routes>,
+    distance_matrix: &DistanceMat,
+    config: EvolutionConfig,
+    migration: Migration,
+    master_seed: u64,
+) -> Vec<Vec<Route>> {
+    let n_islands = initial_populations.len();
+    // Island `i` sends to island `i + 1` and the last island to the first one.
+    let (senders, receivers): (Vec<_>, Vec<_>) = (0..n_islands)
+        .map(|_| mpsc::channel::<Vec<Route>>())
+        .unzip();
+    let mut senders = senders;
+    senders.rotate_left(1);
+    thread::scope(|s| {
+        let islands = senders
+            .into_iter()
+            .zip(receivers)
+            .zip(initial_populations)
+            .enumerate()
+            .map(|(island, ((sender, receiver), initial_population))| {
+                let mut evolution = Evolution::new(distance_matrix, config)
+                    .start(initial_population, stream_seed(master_seed, island as u64));
+                s.spawn(move |_| -> Vec<Route> {
+                    while !evolution.is_finished() {
+                        evolution.evolve(1);
+                        // An interval of 0 never migrates.
+                        if n_islands > 1
+                            && evolution.generation().checked_rem(migration.interval) == Some(0)
+                        {
+                            // The channels are unbounded, so every island sends before it
+                            // waits for the migrants of its neighbor. If the neighbor is gone,
+                            // the island continues without migrants.
+                            let _ = sender.send(
+                                evolution
+                                    .population()
+                                    .get_n_fittest(migration.n_migrants, distance_matrix),
+                            );
+                            if let Ok(migrants) = receiver.recv() {
+                                evolution.receive_migrants(migrants);
+                            }
+                        }
+                    }
+                    evolution
+                        .population()
+                        .get_n_fittest(evolution.size_generation(), distance_matrix)
+                })
+            })
+            .collect::<Vec<_>>();
+        islands
+            .into_iter()
+            .map(|island| island.join().unwrap())
+            .collect::<Vec<Vec<Route>>>()
+    })
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ring_distance_mat(n_nodes: usize) -> DistanceMat {
+        // The nodes lie on a circle, so the optimal route visits them in order.
+        let points = (0..n_nodes)
+            .map(|node| {
+                let angle = node as f64 / n_nodes as f64 * std::f64::consts::TAU;
+                (angle.cos(), angle.sin())
+            })
+            .collect::<Vec<(f64, f64)>>();
+        DistanceMat::from_coordinates(&points)
+    }
+    #[test]
+    fn islands_keep_their_fittest_routes() {
+        let distance_mat = ring_distance_mat(8);
+        let routes = evolve_islands(
+            Routes::random(5, 8),
+            &distance_mat,
+            EvolutionConfig::new()
+                .with_n_generations(20)
+                .with_size_generation(5)
+                .with_n_jobs(4),
+            Migration::new(3, 2),
+        );
+        assert!(!routes.is_empty());
+        assert!(routes.len() <= 4 * 5);
+    }
+    #[test]
+    fn same_master_seed_same_population() {
+        let distance_mat = ring_distance_mat(9);
+        let initial_population = Routes::random(6, 9);
+        let config = EvolutionConfig::new()
+            .with_n_generations(15)
+            .with_size_generation(6)
+            .with_mutation_rate(0.2)
+            .with_elitism(1)
+            .with_n_jobs(4);
+        let run = |seed| {
+            evolve_islands_seeded(
+                initial_population.clone(),
+                &distance_mat,
+                config,
+                Migration::new(4, 2),
+                seed,
+            )
+        };
+        assert_eq!(run(7), run(7));
+    }
+    #[test]
+    fn streams_are_distinct() {
+        let seeds = (0..100)
+            .map(|stream| stream_seed(0, stream))
+            .collect::<std::collections::HashSet<u64>>();
+        assert_eq!(seeds.len(), 100);
+    }
+    #[test]
+    fn single_island_does_not_wait_for_migrants() {
+        let distance_mat = ring_distance_mat(6);
+        let best = Route::new(vec![0, 1, 2, 3, 4, 5]);
+        let routes = evolve_islands(
+            Routes::from(vec![best.clone()]),
+            &distance_mat,
+            EvolutionConfig::new()
+                .with_n_generations(5)
+                .with_size_generation(3)
+                .with_n_jobs(1),
+            Migration::new(1, 1),
+        );
+        assert_eq!(
+            routes.get_n_fittest(1, &distance_mat)[0].fitness(&distance_mat),
+            best.fitness(&distance_mat)
+        );
+    }
+    #[test]
+    fn marked_route_migrates_to_the_next_island() {
+        let distance_mat = ring_distance_mat(6);
+        // The optimal route only starts on the first island. Without crossover and mutation the
+        // islands never change their routes, so it can only reach another island as a migrant.
+        let marked = Route::new(vec![0, 1, 2, 3, 4, 5]);
+        let initial_populations = vec![
+            Routes::from(vec![marked.clone(), Route::new(vec![0, 2, 4, 1, 3, 5])]),
+            Routes::from(vec![
+                Route::new(vec![0, 3, 1, 4, 2, 5]),
+                Route::new(vec![0, 2, 1, 3, 5, 4]),
+            ]),
+            Routes::from(vec![
+                Route::new(vec![0, 4, 2, 5, 1, 3]),
+                Route::new(vec![0, 3, 5, 2, 4, 1]),
+            ]),
+        ];
+        let islands = evolve_island_populations(
+            initial_populations,
+            &distance_mat,
+            EvolutionConfig::new()
+                .with_n_generations(1)
+                .with_size_generation(2)
+                .with_mutation_rate(0.0)
+                .with_crossover_rate(0.0),
+            Migration::new(1, 1),
+            7,
+        );
+        assert!(islands[0].contains(&marked));
+        assert!(islands[1].contains(&marked));
+        // The last island only received a route of the second island.
+        assert!(!islands[2].contains(&marked));
+    }
+}
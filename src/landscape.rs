@@ -0,0 +1,332 @@
+use crate::distance_mat::DistanceMat;
+use crate::route::Route;
+use crate::utils::{move_elem, random_permutation};
+use std::fmt;
+
+/// Statistics of the fitnesses of a sample of routes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FitnessStatistics {
+    /// How many routes were sampled.
+    pub n_samples: usize,
+    /// The fitness of the worst route.
+    pub min: f64,
+    /// The fitness of the best route.
+    pub max: f64,
+    /// The mean fitness.
+    pub mean: f64,
+    /// The standard deviation of the fitnesses.
+    pub std_dev: f64,
+}
+impl FitnessStatistics {
+    /// Compute the statistics of a non-empty slice of fitnesses, `None` if it is empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `fitnesses` - The fitnesses of the sampled routes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::landscape::FitnessStatistics;
+    ///
+    /// let statistics = FitnessStatistics::from_fitnesses(&[-1.0, -3.0]).unwrap();
+    /// assert_eq!(statistics.mean, -2.0);
+    /// assert_eq!(statistics.std_dev, 1.0);
+    /// ```
+    pub fn from_fitnesses(fitnesses: &[f64]) -> Option<Self> {
+        if fitnesses.is_empty() {
+            return None;
+        }
+        let n_samples = fitnesses.len();
+        let mean = fitnesses.iter().sum::<f64>() / n_samples as f64;
+        let variance = fitnesses
+            .iter()
+            .map(|fitness| (fitness - mean).powi(2))
+            .sum::<f64>()
+            / n_samples as f64;
+        Some(FitnessStatistics {
+            n_samples,
+            min: fitnesses.iter().cloned().fold(f64::INFINITY, f64::min),
+            max: fitnesses.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+            mean,
+            std_dev: variance.sqrt(),
+        })
+    }
+}
+impl fmt::Display for FitnessStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "n: {}, min: {:.2}, max: {:.2}, mean: {:.2}, std: {:.2}",
+            self.n_samples, self.min, self.max, self.mean, self.std_dev
+        )
+    }
+}
+
+/// A summary of the fitness landscape of an instance.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LandscapeSummary {
+    /// The fitnesses of uniformly random routes.
+    pub random_routes: FitnessStatistics,
+    /// The fitnesses of local optima reached from random routes.
+    pub local_optima: FitnessStatistics,
+    /// The autocorrelation of the fitness between consecutive steps of a random walk.
+    pub autocorrelation: f64,
+    /// The correlation length `-1 / ln(|autocorrelation|)`. The larger it is, the smoother is
+    /// the landscape.
+    pub correlation_length: f64,
+}
+impl fmt::Display for LandscapeSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "random routes: {}", self.random_routes)?;
+        writeln!(f, "local optima: {}", self.local_optima)?;
+        write!(
+            f,
+            "autocorrelation: {:.4}, correlation length: {:.2}",
+            self.autocorrelation, self.correlation_length
+        )
+    }
+}
+
+/// Create a uniformly random route over all nodes of the distance matrix.
+fn random_route(distance_mat: &DistanceMat) -> Route {
+    Route::new(random_permutation(
+        &(0..distance_mat.n_units()).collect::<Vec<usize>>(),
+    ))
+}
+
+/// Sample the fitnesses of uniformly random routes.
+///
+/// # Arguments
+///
+/// * `distance_mat` - The instance whose landscape is sampled.
+/// * `n_samples` - How many random routes should be sampled.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+/// use genetic_algorithm_tsp::landscape::sample_random_routes;
+///
+/// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+/// let fitnesses = sample_random_routes(&distance_matrix, 10);
+/// assert_eq!(fitnesses.len(), 10);
+/// ```
+pub fn sample_random_routes(distance_mat: &DistanceMat, n_samples: usize) -> Vec<f64> {
+    (0..n_samples)
+        .map(|_| random_route(distance_mat).fitness(distance_mat))
+        .collect()
+}
+
+/// Improve a route by moving single nodes to other positions until no such move increases
+/// the fitness anymore. The first improving move is taken. If the objective of the distance
+/// matrix allows it, only the legs changed by a move are evaluated.
+///
+/// # Arguments
+///
+/// * `route` - The route to start the local search from.
+/// * `distance_mat` - The distance matrix the fitness is computed on.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+/// use genetic_algorithm_tsp::landscape::local_search;
+/// use genetic_algorithm_tsp::route::Route;
+///
+/// let distance_matrix = DistanceMat::new(vec![
+///     vec![0.0, 1.0, 9.0, 1.0],
+///     vec![1.0, 0.0, 1.0, 9.0],
+///     vec![9.0, 1.0, 0.0, 1.0],
+///     vec![1.0, 9.0, 1.0, 0.0],
+/// ]);
+/// let local_optimum = local_search(Route::new(vec![0, 2, 1, 3]), &distance_matrix);
+/// assert_eq!(distance_matrix.get_cost(&local_optimum.indexes), 4.0);
+/// ```
+pub fn local_search(mut route: Route, distance_mat: &DistanceMat) -> Route {
+    let n_nodes = route.get_n_nodes();
+    let mut fitness = route.fitness(distance_mat);
+    let mut candidate = route.indexes.clone();
+    let mut improved = true;
+    while improved {
+        improved = false;
+        'moves: for move_idx in 0..n_nodes {
+            for put_before_idx in 0..n_nodes {
+                let candidate_fitness = match distance_mat.get_relocation_delta(
+                    &route.indexes,
+                    put_before_idx,
+                    move_idx,
+                ) {
+                    Some(delta) => fitness - delta,
+                    None => {
+                        candidate.copy_from_slice(&route.indexes);
+                        move_elem(&mut candidate, put_before_idx, move_idx);
+                        -distance_mat.get_cost(&candidate)
+                    }
+                };
+                // Ignore improvements that are only rounding errors of the delta, otherwise the
+                // search could cycle between equally good routes.
+                if candidate_fitness - fitness > 1e-9 * fitness.abs().max(1.0) {
+                    move_elem(&mut route.indexes, put_before_idx, move_idx);
+                    fitness = candidate_fitness;
+                    improved = true;
+                    break 'moves;
+                }
+            }
+        }
+    }
+    route
+}
+
+/// Sample the fitnesses of local optima, each reached by `local_search` from a random route.
+///
+/// # Arguments
+///
+/// * `distance_mat` - The instance whose landscape is sampled.
+/// * `n_samples` - How many local optima should be sampled.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+/// use genetic_algorithm_tsp::landscape::sample_local_optima;
+///
+/// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+/// let fitnesses = sample_local_optima(&distance_matrix, 5);
+/// assert!(fitnesses.iter().all(|fitness| *fitness == -6.0));
+/// ```
+pub fn sample_local_optima(distance_mat: &DistanceMat, n_samples: usize) -> Vec<f64> {
+    (0..n_samples)
+        .map(|_| local_search(random_route(distance_mat), distance_mat).fitness(distance_mat))
+        .collect()
+}
+
+/// Estimate the ruggedness of the landscape by the autocorrelation of the fitness along a
+/// random walk, in which every step is a mutation of the previous route. Values close to one
+/// describe a smooth landscape, values close to zero a rugged one. Returns `None` if the walk
+/// has less than two steps or the fitness never changes.
+///
+/// # Arguments
+///
+/// * `distance_mat` - The instance whose landscape is sampled.
+/// * `n_steps` - How many steps the random walk takes.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+/// use genetic_algorithm_tsp::landscape::random_walk_autocorrelation;
+///
+/// let distance_matrix = DistanceMat::new(vec![
+///     vec![0.0, 1.0, 9.0, 1.0],
+///     vec![1.0, 0.0, 1.0, 9.0],
+///     vec![9.0, 1.0, 0.0, 1.0],
+///     vec![1.0, 9.0, 1.0, 0.0],
+/// ]);
+/// let autocorrelation = random_walk_autocorrelation(&distance_matrix, 100).unwrap();
+/// assert!(autocorrelation <= 1.0);
+/// ```
+pub fn random_walk_autocorrelation(distance_mat: &DistanceMat, n_steps: usize) -> Option<f64> {
+    let mut route = random_route(distance_mat);
+    let mut cost = distance_mat.get_cost(&route.indexes);
+    let mut fitnesses = Vec::with_capacity(n_steps);
+    for _ in 0..n_steps {
+        fitnesses.push(-cost);
+        (route, cost) = route.mutate_with_cost(1.0, distance_mat, cost);
+    }
+    let statistics = FitnessStatistics::from_fitnesses(&fitnesses)?;
+    if n_steps < 2 || statistics.std_dev == 0.0 {
+        return None;
+    }
+    let covariance = fitnesses
+        .windows(2)
+        .map(|pair| (pair[0] - statistics.mean) * (pair[1] - statistics.mean))
+        .sum::<f64>()
+        / (n_steps - 1) as f64;
+    Some(covariance / statistics.std_dev.powi(2))
+}
+
+/// Sample random routes, local optima and a random walk of an instance and summarize its
+/// fitness landscape, e.g. to choose the parameters of the genetic algorithm. Returns `None`
+/// if there are no samples or the random walk is too short to estimate the autocorrelation.
+///
+/// # Arguments
+///
+/// * `distance_mat` - The instance whose landscape is sampled.
+/// * `n_samples` - How many random routes and local optima should be sampled.
+/// * `n_walk_steps` - How many steps the random walk takes.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+/// use genetic_algorithm_tsp::landscape::analyze_landscape;
+///
+/// let distance_matrix = DistanceMat::new(vec![
+///     vec![0.0, 1.0, 9.0, 1.0],
+///     vec![1.0, 0.0, 1.0, 9.0],
+///     vec![9.0, 1.0, 0.0, 1.0],
+///     vec![1.0, 9.0, 1.0, 0.0],
+/// ]);
+/// let summary = analyze_landscape(&distance_matrix, 10, 100).unwrap();
+/// assert_eq!(summary.local_optima.max, -4.0);
+/// println!("{}", summary);
+/// ```
+pub fn analyze_landscape(
+    distance_mat: &DistanceMat,
+    n_samples: usize,
+    n_walk_steps: usize,
+) -> Option<LandscapeSummary> {
+    let autocorrelation = random_walk_autocorrelation(distance_mat, n_walk_steps)?;
+    Some(LandscapeSummary {
+        random_routes: FitnessStatistics::from_fitnesses(&sample_random_routes(
+            distance_mat,
+            n_samples,
+        ))?,
+        local_optima: FitnessStatistics::from_fitnesses(&sample_local_optima(
+            distance_mat,
+            n_samples,
+        ))?,
+        autocorrelation,
+        correlation_length: -1.0 / autocorrelation.abs().ln(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{test_dist_mat, valid_permutation};
+    #[test]
+    fn statistics_of_empty_sample() {
+        assert_eq!(FitnessStatistics::from_fitnesses(&[]), None);
+    }
+    #[test]
+    fn statistics() {
+        let statistics = FitnessStatistics::from_fitnesses(&[-2.0, -4.0, -6.0]).unwrap();
+        assert_eq!(statistics.n_samples, 3);
+        assert_eq!(statistics.min, -6.0);
+        assert_eq!(statistics.max, -2.0);
+        assert_eq!(statistics.mean, -4.0);
+    }
+    #[test]
+    fn local_search_keeps_permutation() {
+        let distance_mat = test_dist_mat();
+        let route = random_route(&distance_mat);
+        let local_optimum = local_search(route.clone(), &distance_mat);
+        valid_permutation(&route.indexes, &local_optimum.indexes);
+        assert!(local_optimum.fitness(&distance_mat) >= route.fitness(&distance_mat));
+    }
+    #[test]
+    fn local_optima_are_not_worse_than_random_routes() {
+        let distance_mat = test_dist_mat();
+        let random_routes =
+            FitnessStatistics::from_fitnesses(&sample_random_routes(&distance_mat, 20)).unwrap();
+        let local_optima =
+            FitnessStatistics::from_fitnesses(&sample_local_optima(&distance_mat, 20)).unwrap();
+        assert!(local_optima.mean >= random_routes.mean);
+    }
+    #[test]
+    fn short_random_walk() {
+        assert_eq!(random_walk_autocorrelation(&test_dist_mat(), 1), None);
+    }
+}
@@ -1,23 +1,197 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![deny(rustdoc::missing_doc_code_examples)]
 #![deny(missing_docs)]
 //! # Genetic algorithms for solving TSPs.
 //!
 //! This crates contains utitlities to run genetic algorithms and solve Traveling Salesman Problems.
+//!
+//! The default `std` feature enables everything that needs an operating system: multi-threaded
+//! evolution, timing in `benchmark` and the file based `evaluation`. Without it, the crate is
+//! `no_std` and only needs `alloc`: the core types and single-threaded evolution are still
+//! available. As there is no `thread_rng` then, everything random takes a seeded rng, e.g. the
+//! `_with_rng` functions and `Evolution::start`, and the `Individual` and `Population` traits
+//! of `genetic-algorithm-traits` are only implemented with `std`.
+
+#[macro_use]
+extern crate alloc;
 
-/// Represent a distance Matrix as a Vec<Vec<f64>>.
+/// The `aco`-module contains `AntColony`, an Ant System and MAX-MIN Ant System solver over the
+/// same `DistanceMat` whose tours are ordinary `Route`s, and `Routes::ant_colony`, which seeds
+/// a population of the genetic algorithm from a colony.
+#[cfg(feature = "aco")]
+pub mod aco;
+/// The `array_route`-module contains `ArrayRoute`, a route of a fixed number of nodes that is
+/// stored inline, so that its operators don't allocate on the heap.
+#[cfg(feature = "array-routes")]
+pub mod array_route;
+/// The `batch`-module stores a population as one contiguous buffer of nodes with offsets,
+/// e.g. to evaluate all routes in one pass.
+pub mod batch;
+/// The `candidates`-module contains `CandidateList`, the nearest neighbors of every node, to
+/// which the candidate crossover and mutation restrict the edges they introduce.
+pub mod candidates;
+/// The `certificate`-module checks whether a route is a local optimum for 2-opt and Or-opt, or
+/// improves it until it is, so that a result carries a `LocalOptimality`-certificate.
+pub mod certificate;
+/// The `columnar`-module reads distance matrices and coordinates from Parquet files and Arrow
+/// record batches, e.g. as handed over by data pipelines.
+#[cfg(feature = "parquet")]
+pub mod columnar;
+/// The `compat`-module re-exports the hash maps and sets of `std`, or of `hashbrown` without the
+/// `std` feature.
+mod compat;
+/// The `constraint`-module contains side `Constraint`s of constrained variants, e.g. a
+/// `Precedence` of pickups before deliveries, `TourHalves` for morning and afternoon
+/// customers or `FixedEndpoints` for a depot, which are handled by comparing routes by their
+/// feasibility first and their cost second instead of by a penalty.
+pub mod constraint;
+/// The `convergence`-module detects convergence by the stability of the edges of the best
+/// route, stops evolutions early with `EarlyStopping` and records the course of an evolution as
+/// `History`.
+#[cfg(feature = "std")]
+pub mod convergence;
+/// The `datasets`-module contains small, classic TSP instances with known optima that are
+/// embedded in the crate, e.g. for examples and smoke tests.
+#[cfg(feature = "datasets")]
+pub mod datasets;
+/// Represent a distance Matrix as a Vec<Vec<f64>>. Besides creating random populations, the
+/// `DistanceMat` computes the cost of a route, e.g. with the bounds-checked `get_distance` or its
+/// fast variant `get_distance_unchecked` that can be used in custom operators.
 pub mod distance_mat;
+/// The `evaluation`-module contains a harness that runs several configurations of the genetic
+/// algorithm on a directory of instances and summarizes the results in a table.
+#[cfg(feature = "std")]
+pub mod evaluation;
+/// The `evolution`-module contains `Evolution`, which runs the genetic algorithm with all its
+/// parameters collected in an `EvolutionConfig`, either in one go or step by step with
+/// checkpoints that a long run can be resumed from, and the `StopReason` of a run.
+pub mod evolution;
+/// The `exact`-module contains `held_karp`, which solves instances of up to 20 nodes optimally,
+/// e.g. to validate the routes of the genetic algorithm.
+pub mod exact;
+/// The `generation`-module contains `Generation`, a population together with its index,
+/// statistics, timing and the seed to resume from, `Generations`, which streams them, and an
+/// observer hook and a history of the `GenerationStats` of every generation.
+#[cfg(feature = "std")]
+pub mod generation;
+/// The `id_map`-module maps the external ids of the nodes, e.g. customer numbers or names, to
+/// the indexes of a `DistanceMat` and back.
+pub mod id_map;
+/// The `individuals`-module contains `Individuals`, a population of any individual, e.g. of the
+/// individuals of the routing variants.
+pub mod individuals;
+/// The `island`-module contains an island model, in which parallel sub-populations exchange
+/// their fittest routes every few generations.
+#[cfg(feature = "std")]
+pub mod island;
+/// The `landscape`-module samples random routes, local optima and random walks of an instance
+/// to describe its fitness landscape before the genetic algorithm is tuned.
+#[cfg(feature = "std")]
+pub mod landscape;
+/// The `lk`-module contains `Route::lin_kernighan`, a Lin-Kernighan local search of variable
+/// depth, e.g. to polish the fittest routes of the final population.
+#[cfg(feature = "lk")]
+pub mod lk;
+/// The `local_search`-module contains the `LocalSearch` trait of the neighborhoods a route is
+/// improved in, e.g. `TwoOpt`, `OrOpt` and `ThreeOpt`, and `LocalSearchChain`, which searches
+/// several of them in turn, e.g. in the memetic step of an evolution.
+pub mod local_search;
+/// The `lower_bound`-module contains `OneTree`, the Held-Karp lower bound of an instance, with
+/// which the gap of a route to the optimum can be reported.
+pub mod lower_bound;
+/// The `memory`-module contains an allocator that counts allocations, so that benchmarks can
+/// report the memory usage alongside the run time.
+#[cfg(feature = "memory-stats")]
+pub mod memory;
+/// The `multi_route`-module contains `MultiRoute`, the individual of the multiple Traveling
+/// Salesman Problem whose vehicles start at a common depot, and its population `MultiRoutes`.
+pub mod multi_route;
+/// The `objective`-module contains the `Objective` that decides which cost of a route is minimized,
+/// e.g. the round-trip length or the sum of arrival times.
+pub mod objective;
+/// The `prelude`-module re-exports the traits `Individual` and `Population`, the main types
+/// and the entry points of the solver, so that a single `use` is enough to get started.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::prelude::*;
+///
+/// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+/// let routes = evolve_population(Routes::random(2, 3), 10, 5, &distance_matrix, 0);
+/// let best_route = &routes.get_n_fittest(1, &distance_matrix)[0];
+/// println!("{} with fitness {}", best_route, best_route.fitness(&distance_matrix));
+/// ```
+pub mod prelude;
+/// The `projection`-module projects `(latitude, longitude)`-coordinates onto a plane in
+/// kilometers, e.g. for spatial operators and plots of geographic instances.
+pub mod projection;
+/// The `published`-module shares the latest population of a running evolution with other
+/// threads, which read it without a lock, e.g. to monitor the evolution or take its best route.
+#[cfg(feature = "published")]
+pub mod published;
+/// The `registry`-module interns routes and gives them stable, cheap ids that histories and
+/// caches can refer to.
+#[cfg(feature = "std")]
+pub mod registry;
+/// The `regression`-module writes benchmark results to a canonical JSON file and compares a new
+/// run against such a baseline, so that regressions of the run time or the quality are detected.
+#[cfg(feature = "regression")]
+pub mod regression;
 /// The `route`-module contains the `Route`-class, the individual element of the TSP that implements
-/// important methods like `crossover` or `mutate`.
+/// important methods like `crossover` or `mutate`, and `breed`, which breeds the offspring of a
+/// single pair of routes.
 pub mod route;
 /// The `routes`-module contains the main class of this crate which is the `Routes`-class that contains
 /// your current subset of routes and with which you can evolve them.
 pub mod routes;
+/// The `sa`-module contains `SimulatedAnnealing`, a single-route baseline that anneals a `Route` with
+/// the same mutation operators as the genetic algorithm, e.g. to benchmark it against.
+pub mod sa;
+/// The `selection`-module contains the `Selection` strategies with which the routes that survive a
+/// generation are chosen, e.g. truncation or tournament selection.
+pub mod selection;
+/// The `selective`-module contains `PrizeCost`, the cost data of the prize-collecting
+/// Traveling Salesman Problem with a prize for every node, and `SelectiveRoute`, a round-trip
+/// that visits only the subset of the nodes that is worth its detour.
+pub mod selective;
+/// The `self_check`-module validates the offspring and fitnesses created by operators. The
+/// `self-check` feature, which enables it, makes `Route` and `ArrayRoute` run these checks after
+/// every operator, so that a broken operator panics where it went wrong.
+#[cfg(feature = "self-check")]
+pub mod self_check;
+/// The `snapshot`-module writes the population of every generation to disk, so that the
+/// dynamics of a long run can be analyzed afterwards and parallel runs can be resumed after a crash.
+#[cfg(feature = "snapshots")]
+pub mod snapshot;
+/// The `solver`-module contains `solve_tsp`, which solves an instance with sensible defaults for
+/// its size, `solve_tsp_detailed`, whose `SolverResult` can be reported, and `PreparedSolver`,
+/// which does the one-time work up front for predictable latency.
+#[cfg(feature = "std")]
+pub mod solver;
 /// The `subsequence`-module contains a helper function, `Subsequence` that gives you functionality to select elements
 /// before, in and after a subsequence of a Vector. It is used extensively in the `ordered_crossover`-function.
 mod subsequence;
+/// The `tabu`-module contains `TabuMemory`, a run-level memory of the edges recently removed
+/// from the best route, which penalizes mutations that add them back.
+#[cfg(feature = "std")]
+pub mod tabu;
 /// the `test-utils`-module contains utitlities for testing and include for example the construction of test-data
 /// or the comparison of specializied objects (like permutations).
+#[cfg(test)]
 mod test_utils;
+/// The `trace`-module writes one JSON line per generation of an evolution, e.g. its fitness
+/// statistics and operator usage, so that experiment-tracking tools can ingest the runs.
+#[cfg(feature = "trace")]
+pub mod trace;
+/// The `tsplib`-module reads instances in the TSPLIB format, e.g. `.tsp` files with coordinates
+/// or explicit edge weights.
+#[cfg(feature = "std")]
+pub mod tsplib;
 /// The `utils`-module contains utility that are used throughout the rest of the code base. The underlying `ordered_crossover`-
 /// function is implemented here.
 mod utils;
+/// The `vrp`-module contains `VrpCost`, the cost data of the Capacitated Vehicle Routing Problem
+/// with the demands of the nodes and the capacity of the vehicles, and `CapacitatedRoute`, the
+/// `MultiRoute` that is evaluated on it.
+pub mod vrp;
@@ -0,0 +1,298 @@
+use crate::distance_mat::DistanceMat;
+use crate::local_search::LocalSearch;
+use crate::route::Route;
+use alloc::vec::Vec;
+
+/// Check whether a leg between two nodes is one of `legs`, in either direction.
+fn contains_leg(legs: &[(usize, usize)], from: usize, to: usize) -> bool {
+    legs.iter()
+        .any(|&(start, end)| (start, end) == (from, to) || (start, end) == (to, from))
+}
+
+/// Compute the cost of a tour that was rotated to start at another node, after rotating it
+/// back so that it starts at `depot` again.
+fn cost_from_depot(tour: &[usize], depot: usize, distance_mat: &DistanceMat) -> f64 {
+    let depot_idx = tour.iter().position(|node| *node == depot).unwrap_or(0);
+    distance_mat.get_cost(
+        &tour[depot_idx..]
+            .iter()
+            .chain(&tour[..depot_idx])
+            .copied()
+            .collect::<Vec<usize>>(),
+    )
+}
+
+/// Search one sequential Lin-Kernighan move that starts by removing the leg from `tour[0]` to
+/// `tour[1]`. The move is a chain of at most `max_depth` reversals of `tour[1..=last_idx]`,
+/// every one of which removes a leg at the free end `tour[1]` and adds a new one. The chain is
+/// only extended while the legs removed so far are longer than the legs added (the gain
+/// criterion), and the next leg is chosen with a lookahead of one reversal. In the end, `tour`
+/// is rolled back to the prefix of the chain with the lowest cost. Returns the change of cost
+/// if that prefix improves the tour.
+///
+/// # Arguments
+///
+/// * `tour` - The route rotated so that it starts at the first node of the move.
+/// * `distance_mat` - The distance matrix the cost is computed on.
+/// * `max_depth` - How many reversals the move consists of at most.
+/// * `cost` - The cost of the route before the move.
+/// * `depot` - The first node of the route, at which the cost is computed if it can't be
+///   updated locally.
+///
+fn lin_kernighan_move(
+    tour: &mut [usize],
+    distance_mat: &DistanceMat,
+    max_depth: usize,
+    cost: f64,
+    depot: usize,
+) -> Option<f64> {
+    let first = tour[0];
+    let all_nodes = tour.to_vec();
+    // The position of every node in `tour`, `usize::MAX` for nodes that are not visited.
+    let mut positions = vec![usize::MAX; distance_mat.n_units()];
+    for (idx, node) in tour.iter().enumerate() {
+        positions[*node] = idx;
+    }
+    // The lengths of the removed legs minus the lengths of the added legs, without the leg that
+    // closes the round-trip.
+    let mut gain = distance_mat.distance(first, tour[1]);
+    let mut removed_legs = vec![(first, tour[1])];
+    let mut added_legs = Vec::with_capacity(max_depth);
+    let mut reversals = Vec::with_capacity(max_depth);
+    let mut delta = 0.0;
+    // Ignore improvements that are only rounding errors, they could cycle.
+    let mut best_delta = -1e-9 * cost.abs().max(1.0);
+    let mut best_n_reversals = 0;
+    for _ in 0..max_depth {
+        let last = tour[1];
+        let neighbors = match distance_mat.candidate_list() {
+            Some(candidates) => candidates.neighbors(last),
+            None => &all_nodes,
+        };
+        // The best next node as `(lookahead, position)`.
+        let mut best_next: Option<(f64, usize)> = None;
+        for &next in neighbors {
+            let next_idx = positions[next];
+            // The first node, the free end and its successor can't start the next leg.
+            if next_idx == usize::MAX || next_idx < 3 {
+                continue;
+            }
+            if gain - distance_mat.distance(last, next) <= 0.0 {
+                continue;
+            }
+            let before_next = tour[next_idx - 1];
+            if contains_leg(&added_legs, before_next, next)
+                || contains_leg(&removed_legs, last, next)
+            {
+                continue;
+            }
+            let lookahead =
+                distance_mat.distance(before_next, next) - distance_mat.distance(last, next);
+            if best_next.is_none_or(|(best_lookahead, _)| lookahead > best_lookahead) {
+                best_next = Some((lookahead, next_idx));
+            }
+        }
+        let Some((_, next_idx)) = best_next else {
+            break;
+        };
+        let (next, before_next) = (tour[next_idx], tour[next_idx - 1]);
+        let local_delta = distance_mat.get_two_opt_delta(tour, 0, next_idx - 1);
+        tour[1..next_idx].reverse();
+        for (idx, node) in tour.iter().enumerate().take(next_idx).skip(1) {
+            positions[*node] = idx;
+        }
+        delta += local_delta
+            .unwrap_or_else(|| cost_from_depot(tour, depot, distance_mat) - cost - delta);
+        gain += distance_mat.distance(before_next, next) - distance_mat.distance(last, next);
+        added_legs.push((last, next));
+        removed_legs.push((before_next, next));
+        reversals.push(next_idx - 1);
+        if delta < best_delta {
+            best_delta = delta;
+            best_n_reversals = reversals.len();
+        }
+    }
+    for &last_idx in reversals[best_n_reversals..].iter().rev() {
+        tour[1..=last_idx].reverse();
+    }
+    (best_n_reversals > 0).then_some(best_delta)
+}
+
+impl Route {
+    /// Improve the route with Lin-Kernighan moves until it is a local optimum. Starting from
+    /// every node, a move removes the leg to its successor and then repeatedly reconnects the
+    /// free end of the path to a close node, which is one 2-opt reversal per step, as long as
+    /// the removed legs are longer than the added ones. The best prefix of up to `max_depth`
+    /// reversals is applied if it improves the route, so a move can reach routes that no single
+    /// 2-opt or 3-opt move does. The next nodes are taken from the candidate list of the
+    /// distance matrix if it has one, otherwise all nodes are considered. Every reversal is
+    /// evaluated in O(1) with `DistanceMat::get_two_opt_delta` if the objective allows it,
+    /// otherwise the moved route is evaluated as a whole. The first node of the route stays in
+    /// place.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the cost is computed on.
+    /// * `max_depth` - How many reversals a move consists of at most, e.g. 5.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 9.0, 1.0],
+    ///     vec![1.0, 0.0, 1.0, 9.0],
+    ///     vec![9.0, 1.0, 0.0, 1.0],
+    ///     vec![1.0, 9.0, 1.0, 0.0],
+    /// ]);
+    /// let route = Route::new(vec![0, 2, 1, 3]).lin_kernighan(&distance_matrix, 5);
+    /// assert_eq!(distance_matrix.get_cost(&route.indexes), 4.0);
+    /// ```
+    pub fn lin_kernighan(&self, distance_mat: &DistanceMat, max_depth: usize) -> Route {
+        let mut indexes = self.indexes.clone();
+        let n_nodes = indexes.len();
+        if n_nodes < 4 || max_depth == 0 {
+            return Route::new(indexes);
+        }
+        let depot = indexes[0];
+        let mut cost = distance_mat.get_cost(&indexes);
+        let mut tour = Vec::with_capacity(n_nodes);
+        let mut improved = true;
+        while improved {
+            improved = false;
+            for start in 0..n_nodes {
+                tour.clear();
+                tour.extend(indexes[start..].iter().chain(&indexes[..start]));
+                if let Some(delta) =
+                    lin_kernighan_move(&mut tour, distance_mat, max_depth, cost, depot)
+                {
+                    let depot_idx = tour.iter().position(|node| *node == depot).unwrap_or(0);
+                    indexes.clear();
+                    indexes.extend(tour[depot_idx..].iter().chain(&tour[..depot_idx]));
+                    cost += delta;
+                    improved = true;
+                }
+            }
+        }
+        Route::new(indexes)
+    }
+}
+
+/// The Lin-Kernighan neighborhood of `Route::lin_kernighan`, e.g. to polish the fittest routes
+/// of the final population with `Routes::refine_fittest_with_local_search`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LinKernighan {
+    /// How many reversals a move consists of at most.
+    pub max_depth: usize,
+}
+impl LinKernighan {
+    /// Create the Lin-Kernighan neighborhood with a maximal depth of its moves.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_depth` - How many reversals a move consists of at most.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::lk::LinKernighan;
+    ///
+    /// let lin_kernighan = LinKernighan::new(5);
+    /// ```
+    pub fn new(max_depth: usize) -> Self {
+        LinKernighan { max_depth }
+    }
+}
+impl Default for LinKernighan {
+    fn default() -> Self {
+        LinKernighan::new(5)
+    }
+}
+impl LocalSearch for LinKernighan {
+    /// Improve the route with `Route::lin_kernighan`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::lk::LinKernighan;
+    /// use genetic_algorithm_tsp::local_search::LocalSearch;
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_tsp::routes::Routes;
+    /// use genetic_algorithm_traits::{Individual, Population};
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 9.0, 1.0],
+    ///     vec![1.0, 0.0, 1.0, 9.0],
+    ///     vec![9.0, 1.0, 0.0, 1.0],
+    ///     vec![1.0, 9.0, 1.0, 0.0],
+    /// ]);
+    /// let route = LinKernighan::default().improve(&Route::new(vec![0, 2, 1, 3]), &distance_matrix);
+    /// assert_eq!(distance_matrix.get_cost(&route.indexes), 4.0);
+    /// // Polish the fittest route of a final population.
+    /// let routes = Routes::from(vec![Route::new(vec![0, 2, 1, 3])])
+    ///     .refine_fittest_with_local_search(1, &distance_matrix, 1, &LinKernighan::default());
+    /// assert_eq!(routes.get_n_fittest(1, &distance_matrix)[0].fitness(&distance_matrix), -4.0);
+    /// ```
+    fn improve(&self, route: &Route, distance_mat: &DistanceMat) -> Route {
+        route.lin_kernighan(distance_mat, self.max_depth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::valid_permutation;
+
+    /// Points on a circle in a scrambled order, the optimal route visits them around the circle.
+    fn scrambled_circle(n_nodes: usize) -> (DistanceMat, Route) {
+        let points = (0..n_nodes)
+            .map(|idx| {
+                let angle = 2.0 * std::f64::consts::PI * idx as f64 / n_nodes as f64;
+                (angle.cos(), angle.sin())
+            })
+            .collect::<Vec<(f64, f64)>>();
+        let route = Route::new((0..n_nodes).map(|idx| (idx * 7) % n_nodes).collect());
+        (DistanceMat::from_coordinates(&points), route)
+    }
+
+    #[test]
+    fn lin_kernighan_finds_the_circle() {
+        let (distance_mat, route) = scrambled_circle(16);
+        let optimal_cost = distance_mat.get_cost(&(0..16).collect::<Vec<usize>>());
+        let (with_candidates, _) = scrambled_circle(16);
+        for distance_mat in [distance_mat, with_candidates.with_candidate_list(5)] {
+            let improved_route = route.lin_kernighan(&distance_mat, 5);
+            valid_permutation(&route.indexes, &improved_route.indexes);
+            assert_eq!(improved_route.indexes[0], route.indexes[0]);
+            assert!((distance_mat.get_cost(&improved_route.indexes) - optimal_cost).abs() < 1e-9);
+        }
+    }
+    #[test]
+    fn lin_kernighan_is_a_local_optimum() {
+        let (distance_mat, route) = scrambled_circle(12);
+        let improved_route = route.lin_kernighan(&distance_mat, 3);
+        assert_eq!(
+            improved_route.lin_kernighan(&distance_mat, 3),
+            improved_route
+        );
+        // Without any reversals the route is left unchanged.
+        assert_eq!(route.lin_kernighan(&distance_mat, 0), route);
+    }
+    #[test]
+    fn lin_kernighan_on_asymmetric_matrix() {
+        let distance_mat = DistanceMat::new(
+            (0..7)
+                .map(|from| (0..7).map(|to| ((3 * from + 7 * to) % 11) as f64).collect())
+                .collect(),
+        );
+        let route = Route::new(vec![3, 0, 6, 4, 1, 5, 2]);
+        let improved_route = route.lin_kernighan(&distance_mat, 4);
+        valid_permutation(&route.indexes, &improved_route.indexes);
+        assert!(
+            distance_mat.get_cost(&improved_route.indexes) <= distance_mat.get_cost(&route.indexes)
+        );
+    }
+}
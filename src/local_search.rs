@@ -0,0 +1,279 @@
+use crate::distance_mat::DistanceMat;
+use crate::route::{LocalSearchPolicy, Route};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// A neighborhood in which a route is improved until it is a local optimum, e.g. `TwoOpt`,
+/// `OrOpt` or `ThreeOpt`. Several neighborhoods are chained with `LocalSearchChain`, and the
+/// memetic algorithm of `evolve_population_memetic_with_local_search` improves the fittest
+/// routes of every generation with any of them.
+pub trait LocalSearch: fmt::Debug + Sync {
+    /// Improve a route until no move of the neighborhood lowers its cost any more.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The route that is improved.
+    /// * `distance_mat` - The distance matrix the cost is computed on.
+    ///
+    fn improve(&self, route: &Route, distance_mat: &DistanceMat) -> Route;
+}
+
+/// The 2-opt neighborhood of `Route::two_opt_with_policy`, in which a stretch of the route is
+/// reversed. It is a `LocalSearch` with the `std` feature, which the random order of the policy
+/// is drawn with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TwoOpt {
+    /// How the local search moves through the neighborhood.
+    pub policy: LocalSearchPolicy,
+}
+impl TwoOpt {
+    /// Create the 2-opt neighborhood with a policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - How the local search moves through the neighborhood.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::local_search::TwoOpt;
+    /// use genetic_algorithm_tsp::route::{LocalSearchPolicy, NeighborhoodOrder, PivotingRule};
+    ///
+    /// let two_opt = TwoOpt::new(LocalSearchPolicy::new(PivotingRule::BestImprovement, NeighborhoodOrder::Random));
+    /// ```
+    pub fn new(policy: LocalSearchPolicy) -> Self {
+        TwoOpt { policy }
+    }
+}
+#[cfg(feature = "std")]
+impl LocalSearch for TwoOpt {
+    /// Improve the route with `Route::two_opt_with_policy`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::local_search::{LocalSearch, TwoOpt};
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 9.0, 1.0],
+    ///     vec![1.0, 0.0, 1.0, 9.0],
+    ///     vec![9.0, 1.0, 0.0, 1.0],
+    ///     vec![1.0, 9.0, 1.0, 0.0],
+    /// ]);
+    /// let route = TwoOpt::default().improve(&Route::new(vec![0, 2, 1, 3]), &distance_matrix);
+    /// assert_eq!(distance_matrix.get_cost(&route.indexes), 4.0);
+    /// ```
+    fn improve(&self, route: &Route, distance_mat: &DistanceMat) -> Route {
+        route.two_opt_with_policy(distance_mat, self.policy, &mut rand::thread_rng())
+    }
+}
+
+/// The Or-opt neighborhood of `Route::or_opt`, in which a chain of one to three consecutive
+/// nodes is moved to another position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct OrOpt;
+impl LocalSearch for OrOpt {
+    /// Improve the route with `Route::or_opt`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::local_search::{LocalSearch, OrOpt};
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 9.0, 1.0],
+    ///     vec![1.0, 0.0, 1.0, 9.0],
+    ///     vec![9.0, 1.0, 0.0, 1.0],
+    ///     vec![1.0, 9.0, 1.0, 0.0],
+    /// ]);
+    /// let route = OrOpt.improve(&Route::new(vec![0, 2, 1, 3]), &distance_matrix);
+    /// assert_eq!(distance_matrix.get_cost(&route.indexes), 4.0);
+    /// ```
+    fn improve(&self, route: &Route, distance_mat: &DistanceMat) -> Route {
+        route.or_opt(distance_mat)
+    }
+}
+
+/// The 3-opt neighborhood of `Route::three_opt`, in which three legs are removed and the
+/// segments between them are reconnected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ThreeOpt;
+impl LocalSearch for ThreeOpt {
+    /// Improve the route with `Route::three_opt`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::local_search::{LocalSearch, ThreeOpt};
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 9.0, 1.0],
+    ///     vec![1.0, 0.0, 1.0, 9.0],
+    ///     vec![9.0, 1.0, 0.0, 1.0],
+    ///     vec![1.0, 9.0, 1.0, 0.0],
+    /// ]);
+    /// let route = ThreeOpt.improve(&Route::new(vec![0, 2, 1, 3]), &distance_matrix);
+    /// assert_eq!(distance_matrix.get_cost(&route.indexes), 4.0);
+    /// ```
+    fn improve(&self, route: &Route, distance_mat: &DistanceMat) -> Route {
+        route.three_opt(distance_mat)
+    }
+}
+
+/// Several neighborhoods that are searched one after the other (variable neighborhood
+/// descent): the route is improved in every neighborhood in turn, and the rounds are repeated
+/// until no neighborhood changes the route any more, so that the result is a local optimum of
+/// all of them. Cheap neighborhoods should come first, e.g. 2-opt before 3-opt.
+#[derive(Debug, Default)]
+pub struct LocalSearchChain {
+    /// The neighborhoods in the order in which they are searched.
+    neighborhoods: Vec<Box<dyn LocalSearch>>,
+}
+impl LocalSearchChain {
+    /// Create an empty chain, which leaves every route unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::local_search::LocalSearchChain;
+    ///
+    /// assert!(LocalSearchChain::new().is_empty());
+    /// ```
+    pub fn new() -> Self {
+        LocalSearchChain {
+            neighborhoods: Vec::new(),
+        }
+    }
+    /// Append a neighborhood to the chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `neighborhood` - The neighborhood that is searched after the others.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::local_search::{LocalSearchChain, OrOpt, TwoOpt};
+    ///
+    /// let chain = LocalSearchChain::new().then(TwoOpt::default()).then(OrOpt);
+    /// assert_eq!(chain.len(), 2);
+    /// ```
+    pub fn then(mut self, neighborhood: impl LocalSearch + 'static) -> Self {
+        self.neighborhoods.push(Box::new(neighborhood));
+        self
+    }
+    /// Get the number of neighborhoods in the chain.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::local_search::{LocalSearchChain, ThreeOpt};
+    ///
+    /// assert_eq!(LocalSearchChain::new().then(ThreeOpt).len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.neighborhoods.len()
+    }
+    /// Check whether the chain has no neighborhoods.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::local_search::{LocalSearchChain, OrOpt};
+    ///
+    /// assert!(!LocalSearchChain::new().then(OrOpt).is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.neighborhoods.is_empty()
+    }
+}
+impl LocalSearch for LocalSearchChain {
+    /// Improve the route in every neighborhood in turn until none of them changes it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::local_search::{LocalSearch, LocalSearchChain, OrOpt, ThreeOpt, TwoOpt};
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 9.0, 1.0],
+    ///     vec![1.0, 0.0, 1.0, 9.0],
+    ///     vec![9.0, 1.0, 0.0, 1.0],
+    ///     vec![1.0, 9.0, 1.0, 0.0],
+    /// ]);
+    /// let chain = LocalSearchChain::new().then(TwoOpt::default()).then(OrOpt).then(ThreeOpt);
+    /// let route = chain.improve(&Route::new(vec![0, 2, 1, 3]), &distance_matrix);
+    /// assert_eq!(distance_matrix.get_cost(&route.indexes), 4.0);
+    /// ```
+    fn improve(&self, route: &Route, distance_mat: &DistanceMat) -> Route {
+        let mut route = route.clone();
+        loop {
+            let improved = self
+                .neighborhoods
+                .iter()
+                .fold(route.clone(), |route, neighborhood| {
+                    neighborhood.improve(&route, distance_mat)
+                });
+            if improved == route {
+                return route;
+            }
+            route = improved;
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::test_utils::valid_permutation;
+    use crate::utils::get_random_elem_from_range;
+
+    fn random_instance(n_nodes: usize) -> DistanceMat {
+        DistanceMat::from_coordinates(
+            &(0..n_nodes)
+                .map(|_| {
+                    (
+                        get_random_elem_from_range(0.0..100.0),
+                        get_random_elem_from_range(0.0..100.0),
+                    )
+                })
+                .collect::<Vec<(f64, f64)>>(),
+        )
+    }
+
+    #[test]
+    fn every_neighborhood_improves_the_route() {
+        let distance_mat = random_instance(20);
+        let route = Route::new((0..20).collect());
+        let cost = distance_mat.get_cost(&route.indexes);
+        let neighborhoods: [&dyn LocalSearch; 3] = [&TwoOpt::default(), &OrOpt, &ThreeOpt];
+        for neighborhood in neighborhoods {
+            let improved = neighborhood.improve(&route, &distance_mat);
+            valid_permutation(&route.indexes, &improved.indexes);
+            assert!(distance_mat.get_cost(&improved.indexes) <= cost);
+            // A local optimum is not improved any further.
+            assert_eq!(neighborhood.improve(&improved, &distance_mat), improved);
+        }
+    }
+    #[test]
+    fn chain_is_a_local_optimum_of_every_neighborhood() {
+        let distance_mat = random_instance(15);
+        let chain = LocalSearchChain::new()
+            .then(TwoOpt::default())
+            .then(OrOpt)
+            .then(ThreeOpt);
+        let route = chain.improve(&Route::new((0..15).collect()), &distance_mat);
+        assert_eq!(route.two_opt(&distance_mat), route);
+        assert_eq!(route.or_opt(&distance_mat), route);
+        assert_eq!(route.three_opt(&distance_mat), route);
+    }
+}
@@ -0,0 +1,379 @@
+use crate::distance_mat::DistanceMat;
+use crate::objective::Objective;
+use alloc::vec::Vec;
+
+/// How many subgradient steps the Held-Karp bound takes at most.
+const N_ITERATIONS: usize = 200;
+/// After how many steps without a better bound the step size is halved.
+const N_STALLED: usize = 10;
+
+/// The Held-Karp lower bound of an instance together with the 1-tree it was found with. A
+/// 1-tree is a spanning tree of all nodes but node 0 plus the two cheapest edges of node 0, so
+/// every round-trip is a 1-tree and the cheapest 1-tree is no more expensive than the optimal
+/// round-trip. Every node gets a penalty that is added to the cost of its edges, which doesn't
+/// change which round-trip is optimal, and the penalties are raised on nodes with too many
+/// edges and lowered on leaves (subgradient ascent), which pushes the cheapest 1-tree towards
+/// a round-trip and its cost towards the optimum. On open paths a spanning tree with penalties
+/// on nodes of more than two edges is used instead. Asymmetric matrices are bounded with the
+/// cheaper direction of every edge.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OneTree {
+    /// The lower bound: no route of the instance is cheaper.
+    pub bound: f64,
+    /// The edges of the 1-tree with the highest bound, or of the spanning tree on open paths.
+    pub edges: Vec<(usize, usize)>,
+    /// The penalty of every node with which the bound was found.
+    pub penalties: Vec<f64>,
+}
+impl OneTree {
+    /// Compute the Held-Karp lower bound of an instance. Returns `None` for the objectives the
+    /// bound doesn't cover, which are all but the length of a round-trip or an open path with a
+    /// single scenario. Every iteration of the subgradient ascent builds a spanning tree in
+    /// O(n²) and there are at most 200 of them, so the bound costs O(200·n²) time, which is
+    /// worth caching on large instances.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The instance that is bounded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::lower_bound::OneTree;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 9.0, 1.0],
+    ///     vec![1.0, 0.0, 1.0, 9.0],
+    ///     vec![9.0, 1.0, 0.0, 1.0],
+    ///     vec![1.0, 9.0, 1.0, 0.0],
+    /// ]);
+    /// let one_tree = OneTree::new(&distance_matrix).unwrap();
+    /// assert_eq!(one_tree.bound, 4.0);
+    /// ```
+    pub fn new(distance_mat: &DistanceMat) -> Option<Self> {
+        let is_open_path = match distance_mat.objective() {
+            Objective::TourLength | Objective::Makespan => false,
+            Objective::PathLength => true,
+            Objective::Latency | Objective::Bottleneck => return None,
+        };
+        if distance_mat.n_scenarios() > 1 {
+            return None;
+        }
+        let n_nodes = distance_mat.n_units();
+        let distance = |from: usize, to: usize| {
+            distance_mat
+                .distance(from, to)
+                .min(distance_mat.distance(to, from))
+        };
+        if n_nodes < 3 {
+            // There is only one route, up to its direction.
+            let route = (0..n_nodes).collect::<Vec<usize>>();
+            let reversed = route.iter().rev().copied().collect::<Vec<usize>>();
+            let edges = match (n_nodes, is_open_path) {
+                (2, true) => vec![(0, 1)],
+                (2, false) => vec![(0, 1), (0, 1)],
+                _ => Vec::new(),
+            };
+            return Some(OneTree {
+                // An empty instance has no route, and no cost.
+                bound: if n_nodes == 0 {
+                    0.0
+                } else {
+                    distance_mat
+                        .get_cost(&route)
+                        .min(distance_mat.get_cost(&reversed))
+                },
+                edges,
+                penalties: vec![0.0; n_nodes],
+            });
+        }
+        let upper_bound = nearest_neighbor_cost(n_nodes, is_open_path, &distance);
+        let mut penalties = vec![0.0; n_nodes];
+        let mut best: Option<OneTree> = None;
+        let mut step_scale = 2.0;
+        let mut n_stalled = 0;
+        for _ in 0..N_ITERATIONS {
+            let weight =
+                |from: usize, to: usize| distance(from, to) + penalties[from] + penalties[to];
+            let (cost, edges) = if is_open_path {
+                spanning_tree(0, n_nodes, &weight)
+            } else {
+                one_tree(n_nodes, &weight)
+            };
+            let bound = cost - 2.0 * penalties.iter().sum::<f64>();
+            let mut degrees = vec![0_i64; n_nodes];
+            for (from, to) in &edges {
+                degrees[*from] += 1;
+                degrees[*to] += 1;
+            }
+            if best.as_ref().is_none_or(|best| bound > best.bound) {
+                best = Some(OneTree {
+                    bound,
+                    edges,
+                    penalties: penalties.clone(),
+                });
+                n_stalled = 0;
+            } else {
+                n_stalled += 1;
+                if n_stalled == N_STALLED {
+                    step_scale /= 2.0;
+                    n_stalled = 0;
+                }
+            }
+            // On open paths the penalties must not become negative, as leaves are allowed.
+            let subgradient = degrees
+                .iter()
+                .zip(&penalties)
+                .map(|(degree, penalty)| {
+                    let violation = (degree - 2) as f64;
+                    if is_open_path && *penalty <= 0.0 && violation < 0.0 {
+                        0.0
+                    } else {
+                        violation
+                    }
+                })
+                .collect::<Vec<f64>>();
+            let norm = subgradient.iter().map(|value| value * value).sum::<f64>();
+            // Without any violation the tree is a route, and the bound is optimal.
+            if norm == 0.0 || step_scale < 1e-4 {
+                break;
+            }
+            let step =
+                step_scale * (upper_bound - bound).max(1e-9 * upper_bound.abs().max(1.0)) / norm;
+            for (penalty, value) in penalties.iter_mut().zip(&subgradient) {
+                *penalty += step * value;
+                if is_open_path {
+                    *penalty = penalty.max(0.0);
+                }
+            }
+        }
+        best
+    }
+    /// Get how many edges of the tree every node has.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::lower_bound::OneTree;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert_eq!(OneTree::new(&distance_matrix).unwrap().degrees(), vec![2, 2, 2]);
+    /// ```
+    pub fn degrees(&self) -> Vec<usize> {
+        let mut degrees = vec![0; self.penalties.len()];
+        for (from, to) in &self.edges {
+            degrees[*from] += 1;
+            degrees[*to] += 1;
+        }
+        degrees
+    }
+    /// Check whether the tree is a route itself, every node has two edges or, on open paths,
+    /// at most two edges. Then the bound is the cost of an optimal route.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::lower_bound::OneTree;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert!(OneTree::new(&distance_matrix).unwrap().is_route());
+    /// ```
+    pub fn is_route(&self) -> bool {
+        let is_open_path = self.edges.len() + 1 == self.penalties.len();
+        self.penalties.len() <= 1
+            || self
+                .degrees()
+                .iter()
+                .all(|degree| *degree == 2 || (is_open_path && *degree == 1))
+    }
+}
+
+/// Compute the cheapest spanning tree of the nodes `first..n_nodes` with Prim's algorithm.
+/// Returns its cost and its edges.
+///
+/// # Arguments
+///
+/// * `first` - The first node of the tree, the nodes before it are left out.
+/// * `n_nodes` - The number of nodes of the instance.
+/// * `weight` - The cost of the edge between two nodes.
+///
+fn spanning_tree(
+    first: usize,
+    n_nodes: usize,
+    weight: &dyn Fn(usize, usize) -> f64,
+) -> (f64, Vec<(usize, usize)>) {
+    let mut in_tree = vec![false; n_nodes];
+    // The cheapest edge from every node outside the tree into the tree.
+    let mut cheapest = vec![(f64::INFINITY, first); n_nodes];
+    let mut edges = Vec::with_capacity(n_nodes.saturating_sub(first + 1));
+    let mut cost = 0.0;
+    let mut current = first;
+    in_tree[first] = true;
+    for _ in (first + 1)..n_nodes {
+        let mut next = None;
+        for node in (first + 1)..n_nodes {
+            if in_tree[node] {
+                continue;
+            }
+            let edge = weight(current, node);
+            if edge < cheapest[node].0 {
+                cheapest[node] = (edge, current);
+            }
+            if next.is_none_or(|next: usize| cheapest[node].0 < cheapest[next].0) {
+                next = Some(node);
+            }
+        }
+        let next = next.unwrap();
+        in_tree[next] = true;
+        cost += cheapest[next].0;
+        edges.push((cheapest[next].1, next));
+        current = next;
+    }
+    (cost, edges)
+}
+
+/// Compute the cheapest 1-tree: the cheapest spanning tree of all nodes but node 0 plus the
+/// two cheapest edges of node 0. Returns its cost and its edges.
+///
+/// # Arguments
+///
+/// * `n_nodes` - The number of nodes of the instance, at least three.
+/// * `weight` - The cost of the edge between two nodes.
+///
+fn one_tree(n_nodes: usize, weight: &dyn Fn(usize, usize) -> f64) -> (f64, Vec<(usize, usize)>) {
+    let (mut cost, mut edges) = spanning_tree(1, n_nodes, weight);
+    let mut closest = (1..n_nodes).collect::<Vec<usize>>();
+    closest.select_nth_unstable_by(1, |a, b| weight(0, *a).total_cmp(&weight(0, *b)));
+    for node in &closest[..2] {
+        cost += weight(0, *node);
+        edges.push((0, *node));
+    }
+    (cost, edges)
+}
+
+/// The cost of a nearest-neighbor route from node 0, an upper bound that scales the steps of
+/// the subgradient ascent.
+///
+/// # Arguments
+///
+/// * `n_nodes` - The number of nodes of the instance.
+/// * `is_open_path` - Whether the route doesn't return to its start.
+/// * `distance` - The cost of the edge between two nodes.
+///
+fn nearest_neighbor_cost(
+    n_nodes: usize,
+    is_open_path: bool,
+    distance: &dyn Fn(usize, usize) -> f64,
+) -> f64 {
+    let mut unvisited = (1..n_nodes).collect::<Vec<usize>>();
+    let mut current = 0;
+    let mut cost = 0.0;
+    while !unvisited.is_empty() {
+        let position = (0..unvisited.len())
+            .min_by(|a, b| {
+                distance(current, unvisited[*a]).total_cmp(&distance(current, unvisited[*b]))
+            })
+            .unwrap();
+        let next = unvisited.swap_remove(position);
+        cost += distance(current, next);
+        current = next;
+    }
+    if is_open_path {
+        cost
+    } else {
+        cost + distance(current, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exact::held_karp;
+    use crate::utils::get_random_elem_from_range_with_rng;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::f64::consts::PI;
+
+    fn random_points(n_nodes: usize, rng: &mut StdRng) -> DistanceMat {
+        DistanceMat::from_coordinates(
+            &(0..n_nodes)
+                .map(|_| {
+                    (
+                        get_random_elem_from_range_with_rng(0.0..100.0, rng),
+                        get_random_elem_from_range_with_rng(0.0..100.0, rng),
+                    )
+                })
+                .collect::<Vec<(f64, f64)>>(),
+        )
+    }
+
+    #[test]
+    fn bound_is_below_the_optimum() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..10 {
+            let distance_mat = random_points(10, &mut rng);
+            let optimum = distance_mat.get_cost(&held_karp(&distance_mat).unwrap().indexes);
+            let one_tree = OneTree::new(&distance_mat).unwrap();
+            assert!(one_tree.bound <= optimum + 1e-9);
+            // The Held-Karp bound is usually within a few percent of the optimum.
+            assert!(one_tree.bound >= 0.9 * optimum);
+            assert_eq!(one_tree.edges.len(), 10);
+        }
+    }
+    #[test]
+    fn bound_of_open_paths_and_asymmetric_matrices() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let distance_mat = random_points(9, &mut rng).with_objective(Objective::PathLength);
+        let optimum = distance_mat.get_cost(&held_karp(&distance_mat).unwrap().indexes);
+        let one_tree = OneTree::new(&distance_mat).unwrap();
+        assert!(one_tree.bound <= optimum + 1e-9);
+        assert_eq!(one_tree.edges.len(), 8);
+        let distance_mat = DistanceMat::new(
+            (0..8)
+                .map(|from| {
+                    (0..8)
+                        .map(|to| {
+                            if from == to {
+                                0.0
+                            } else {
+                                get_random_elem_from_range_with_rng(1.0..10.0, &mut rng)
+                            }
+                        })
+                        .collect()
+                })
+                .collect(),
+        );
+        let optimum = distance_mat.get_cost(&held_karp(&distance_mat).unwrap().indexes);
+        assert!(OneTree::new(&distance_mat).unwrap().bound <= optimum + 1e-9);
+    }
+    #[test]
+    fn bound_of_a_circle_is_tight() {
+        let distance_mat = DistanceMat::from_coordinates(
+            &(0..12)
+                .map(|node| {
+                    let angle = 2.0 * PI * (node * 5 % 12) as f64 / 12.0;
+                    (angle.cos(), angle.sin())
+                })
+                .collect::<Vec<(f64, f64)>>(),
+        );
+        let one_tree = OneTree::new(&distance_mat).unwrap();
+        assert!(one_tree.is_route());
+        assert!((one_tree.bound - 24.0 * (PI / 12.0).sin()).abs() < 1e-9);
+    }
+    #[test]
+    fn bound_of_tiny_instances() {
+        assert_eq!(DistanceMat::new(Vec::<Vec<f64>>::new()).lower_bound(), 0.0);
+        assert_eq!(DistanceMat::new(vec![vec![0.0]]).lower_bound(), 0.0);
+        let distance_mat = DistanceMat::new(vec![vec![0.0, 1.0], vec![2.0, 0.0]]);
+        assert_eq!(distance_mat.lower_bound(), 3.0);
+    }
+    #[test]
+    fn bound_of_uncovered_objectives() {
+        let distance_mat =
+            random_points(5, &mut StdRng::seed_from_u64(2)).with_objective(Objective::Latency);
+        assert_eq!(OneTree::new(&distance_mat), None);
+    }
+}
@@ -1,42 +1,239 @@
+//! The `genetic-tsp` command line tool: solve an instance, benchmark configurations of the
+//! genetic algorithm or validate an instance and a route, without writing Rust.
+//!
+//! Usage: `genetic-tsp solve --input cities.tsp --generations 500 --population 100 --jobs 8
+//! --seed 42 --output route.json`
+use clap::{Parser, Subcommand};
 use genetic_algorithm_tsp::distance_mat::DistanceMat;
-use genetic_algorithm_tsp::routes::benchmark_population;
+use genetic_algorithm_tsp::evaluation::{benchmark_sweep, read_instance, OperatorConfig};
+use genetic_algorithm_tsp::evolution::{Evolution, EvolutionConfig};
+use genetic_algorithm_tsp::route::Route;
+use genetic_algorithm_tsp::routes::Routes;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 use std::fs;
+use std::path::{Path, PathBuf};
 
-fn main() {
-    // Read-in test distance matrix from `tests/test-data` folder.
-    let distances = DistanceMat::new(
-        fs::read_to_string("tests/test-data/distances.txt")
-            .unwrap()
-            .lines()
-            .collect::<Vec<&str>>()
-            .iter()
-            .map(|line| {
-                line.split(';')
-                    .map(|float_string| float_string.parse::<f64>().unwrap())
-                    .collect::<Vec<f64>>()
-            })
-            .collect(),
-    );
-    for n_generations in (10..=510).step_by(100) {
-        for size_generation in (10..=40).step_by(10) {
-            let (run_time, minimal_loss) =
-                benchmark_population(n_generations, size_generation, &distances, 0);
-            println!(
-                "n_generations: {}, size_generation: {}, time: {} ms, minimal loss: {}",
-                n_generations, size_generation, run_time, minimal_loss
+// Count allocations, so that the memory usage of a configuration can be reported.
+#[cfg(feature = "memory-stats")]
+#[global_allocator]
+static ALLOCATOR: genetic_algorithm_tsp::memory::CountingAllocator =
+    genetic_algorithm_tsp::memory::CountingAllocator;
+
+/// Solve traveling salesman problems with a genetic algorithm.
+#[derive(Debug, Parser)]
+#[command(name = "genetic-tsp", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Solve an instance and write the best route as JSON.
+    Solve {
+        /// The instance, a TSPLIB `.tsp` file or a `;`-separated distance matrix.
+        #[arg(long)]
+        input: PathBuf,
+        /// How many generations are evolved, by default chosen by the size of the instance.
+        #[arg(long)]
+        generations: Option<usize>,
+        /// How many routes survive every generation, by default chosen by the size of the
+        /// instance.
+        #[arg(long)]
+        population: Option<usize>,
+        /// How many threads are used, 0 means single-threaded.
+        #[arg(long, default_value_t = 0)]
+        jobs: usize,
+        /// Make the run reproducible. A seeded run is single-threaded.
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Where the route is written to, stdout by default.
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Benchmark every combination of numbers of generations and population sizes.
+    Benchmark {
+        /// The instance, a TSPLIB `.tsp` file or a `;`-separated distance matrix.
+        #[arg(long, default_value = "tests/test-data/distances.txt")]
+        input: PathBuf,
+        /// The numbers of generations, separated by commas.
+        #[arg(long, value_delimiter = ',', default_value = "10,110,210,310,410,510")]
+        generations: Vec<usize>,
+        /// The population sizes, separated by commas.
+        #[arg(long, value_delimiter = ',', default_value = "10,20,30,40")]
+        population: Vec<usize>,
+        /// How many threads every configuration uses, 0 means single-threaded.
+        #[arg(long, default_value_t = 0)]
+        jobs: usize,
+    },
+    /// Check that an instance is valid, report its properties and optionally check a route.
+    Validate {
+        /// The instance, a TSPLIB `.tsp` file or a `;`-separated distance matrix.
+        #[arg(long)]
+        input: PathBuf,
+        /// A route as written by `solve` that should visit every node of the instance once.
+        #[arg(long)]
+        route: Option<PathBuf>,
+    },
+}
+
+/// Read an instance, TSPLIB files are recognized by their `.tsp` extension.
+///
+/// # Arguments
+///
+/// * `path` - The path of the instance.
+///
+fn read_input(path: &Path) -> Result<DistanceMat, String> {
+    let distance_mat = if path.extension().is_some_and(|extension| extension == "tsp") {
+        DistanceMat::from_tsplib(path).map_err(|error| error.to_string())
+    } else {
+        read_instance(path).map_err(|error| error.to_string())
+    }
+    .map_err(|error| format!("could not read {}: {}", path.display(), error))?;
+    if distance_mat.n_units() == 0 {
+        return Err(format!("{} contains no nodes", path.display()));
+    }
+    Ok(distance_mat)
+}
+
+/// Read the nodes of a route from the JSON written by `solve`.
+///
+/// # Arguments
+///
+/// * `path` - The path of the route.
+///
+fn read_route(path: &Path) -> Result<Route, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|error| format!("could not read {}: {}", path.display(), error))?;
+    let json: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|error| format!("could not parse {}: {}", path.display(), error))?;
+    let indexes = json["route"]
+        .as_array()
+        .and_then(|nodes| {
+            nodes
+                .iter()
+                .map(|node| node.as_u64().map(|node| node as usize))
+                .collect::<Option<Vec<usize>>>()
+        })
+        .ok_or_else(|| format!("{} has no `route` of node indexes", path.display()))?;
+    Ok(Route::new(indexes))
+}
+
+fn solve(
+    input: &Path,
+    generations: Option<usize>,
+    population: Option<usize>,
+    jobs: usize,
+    seed: Option<u64>,
+    output: Option<&Path>,
+) -> Result<(), String> {
+    let distance_mat = read_input(input)?;
+    let n_nodes = distance_mat.n_units();
+    let defaults = OperatorConfig::for_instance_size(n_nodes);
+    let config = EvolutionConfig::new()
+        .with_n_generations(generations.unwrap_or(defaults.n_generations))
+        .with_size_generation(population.unwrap_or(defaults.size_generation))
+        .with_n_jobs(jobs);
+    let evolution = Evolution::new(&distance_mat, config);
+    let final_population = match seed {
+        Some(seed) => {
+            let initial_population = Routes::random_with_rng(
+                config.size_generation,
+                n_nodes,
+                &mut StdRng::seed_from_u64(seed),
             );
+            evolution
+                .start(initial_population, seed)
+                .evolve(usize::MAX)
+                .clone()
         }
+        None => {
+            evolution
+                .run(Routes::random(config.size_generation, n_nodes))
+                .0
+        }
+    };
+    let best_route = &final_population.get_n_fittest(1, &distance_mat)[0];
+    let cost = distance_mat.get_cost(&best_route.indexes);
+    let result = serde_json::json!({
+        "input": input.display().to_string(),
+        "n_nodes": n_nodes,
+        "cost": cost,
+        "route": best_route.indexes,
+    });
+    let json = serde_json::to_string_pretty(&result).expect("A route is always valid JSON.");
+    match output {
+        Some(output) => fs::write(output, json + "\n")
+            .map_err(|error| format!("could not write {}: {}", output.display(), error))?,
+        None => println!("{}", json),
     }
-    println!("Running multi-threaded computation!");
-    let n_jobs = 8;
-    for n_generations in (10..=1100).step_by(100) {
-        for size_generation in (10..=80).step_by(10) {
-            let (run_time, minimal_loss) =
-                benchmark_population(n_generations, size_generation, &distances, n_jobs);
-            println!(
-                "n_generations: {}, size_generation: {}, time: {} ms, minimal loss: {}, n_jobs: {}",
-                n_generations, size_generation, run_time, minimal_loss, n_jobs
-            );
+    eprintln!("Best route has cost {}", cost);
+    Ok(())
+}
+
+fn benchmark(
+    input: &Path,
+    generations: &[usize],
+    population: &[usize],
+    jobs: usize,
+) -> Result<(), String> {
+    let distance_mat = read_input(input)?;
+    for result in benchmark_sweep(
+        &OperatorConfig::grid(generations, population, jobs),
+        &distance_mat,
+    ) {
+        println!("{}", result);
+    }
+    Ok(())
+}
+
+fn validate(input: &Path, route: Option<&Path>) -> Result<(), String> {
+    let distance_mat = read_input(input)?;
+    let summary = distance_mat.summary();
+    println!("{}", summary);
+    if let Some(path) = route {
+        let route = read_route(path)?;
+        let mut nodes = route.indexes.clone();
+        nodes.sort_unstable();
+        if nodes != (0..distance_mat.n_units()).collect::<Vec<usize>>() {
+            return Err(format!(
+                "{} does not visit every node of {} exactly once",
+                path.display(),
+                input.display()
+            ));
         }
+        let cost = distance_mat
+            .compute_cost_checked(&route)
+            .map_err(|error| format!("{}: {}", path.display(), error))?;
+        println!("Route {} is valid with cost {}", path.display(), cost);
+    }
+    Ok(())
+}
+
+fn main() -> Result<(), String> {
+    match Cli::parse().command {
+        Command::Solve {
+            input,
+            generations,
+            population,
+            jobs,
+            seed,
+            output,
+        } => solve(
+            &input,
+            generations,
+            population,
+            jobs,
+            seed,
+            output.as_deref(),
+        ),
+        Command::Benchmark {
+            input,
+            generations,
+            population,
+            jobs,
+        } => benchmark(&input, &generations, &population, jobs),
+        Command::Validate { input, route } => validate(&input, route.as_deref()),
     }
 }
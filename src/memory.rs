@@ -0,0 +1,103 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The number of allocations since the start of the program.
+static N_ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+/// The number of bytes that are currently allocated.
+static CURRENT_BYTES: AtomicUsize = AtomicUsize::new(0);
+/// The maximal number of bytes that were allocated at the same time since the last reset.
+static PEAK_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+/// An allocator that counts allocations and tracks the peak heap usage, before it forwards
+/// to the system allocator. It has to be installed as global allocator in your binary, so
+/// that `benchmark` can report memory statistics.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::memory::CountingAllocator;
+///
+/// #[global_allocator]
+/// static ALLOCATOR: CountingAllocator = CountingAllocator;
+///
+/// fn main() {}
+/// ```
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let pointer = System.alloc(layout);
+        if !pointer.is_null() {
+            record_allocation(layout.size());
+        }
+        pointer
+    }
+    unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+        System.dealloc(pointer, layout);
+        CURRENT_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+    unsafe fn realloc(&self, pointer: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_pointer = System.realloc(pointer, layout, new_size);
+        if !new_pointer.is_null() {
+            CURRENT_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+            record_allocation(new_size);
+        }
+        new_pointer
+    }
+}
+
+/// Count an allocation of `size` bytes and update the peak heap usage.
+///
+/// # Arguments
+///
+/// * `size` - The number of bytes that were allocated.
+///
+fn record_allocation(size: usize) {
+    N_ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    let current_bytes = CURRENT_BYTES.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK_BYTES.fetch_max(current_bytes, Ordering::Relaxed);
+}
+
+/// The allocations that happened during a measurement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AllocationStats {
+    /// The number of allocations.
+    pub n_allocations: usize,
+    /// The maximal number of bytes that were allocated on the heap at the same time.
+    pub peak_bytes: usize,
+}
+
+/// Measure the allocations of a function. Returns `None` for the statistics if the
+/// `CountingAllocator` is not installed as global allocator.
+///
+/// # Arguments
+///
+/// * `function` - The function whose allocations should be measured.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::memory::measure_allocations;
+///
+/// let (vector, stats) = measure_allocations(|| vec![1, 2, 3]);
+/// // Without the `CountingAllocator` as global allocator, there are no statistics.
+/// assert_eq!(stats, None);
+/// ```
+pub fn measure_allocations<T, F: FnOnce() -> T>(function: F) -> (T, Option<AllocationStats>) {
+    let n_allocations_before = N_ALLOCATIONS.load(Ordering::Relaxed);
+    let bytes_before = CURRENT_BYTES.load(Ordering::Relaxed);
+    PEAK_BYTES.store(bytes_before, Ordering::Relaxed);
+    let result = function();
+    let n_allocations = N_ALLOCATIONS.load(Ordering::Relaxed) - n_allocations_before;
+    let stats = if n_allocations > 0 {
+        Some(AllocationStats {
+            n_allocations,
+            peak_bytes: PEAK_BYTES
+                .load(Ordering::Relaxed)
+                .saturating_sub(bytes_before),
+        })
+    } else {
+        None
+    };
+    (result, stats)
+}
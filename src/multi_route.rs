@@ -0,0 +1,582 @@
+#[cfg(feature = "std")]
+use crate::distance_mat::DistanceMat;
+use crate::individuals::Individuals;
+use crate::route::{sample_relocation_with_rng, Route};
+use crate::utils::{get_random_elem_from_range_with_rng, move_elem, random_permutation_with_rng};
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
+use genetic_algorithm_traits::Individual;
+use rand::Rng;
+
+/// The routes of several vehicles (salesmen) that all start and end at a common depot, the
+/// individual of the multiple Traveling Salesman Problem (mTSP). The nodes other than the depot
+/// are stored as one permutation that is split into the sub-tours of the vehicles, so that the
+/// operators of `Route` can be used on it, and crossover and mutation keep one sub-tour per
+/// vehicle.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MultiRoute {
+    /// The node every sub-tour starts and ends at.
+    pub depot: usize,
+    /// The order in which the nodes other than the depot are visited, one sub-tour after the
+    /// other.
+    pub indexes: Vec<usize>,
+    /// The positions in `indexes` at which the next sub-tour starts, sorted and at most the
+    /// number of nodes. There is one break less than there are vehicles, and a vehicle whose
+    /// sub-tour is empty stays at the depot.
+    pub breaks: Vec<usize>,
+}
+impl fmt::Display for MultiRoute {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "MultiRoute({}: {:?})",
+            self.depot,
+            self.sub_tours()
+        )
+    }
+}
+impl MultiRoute {
+    /// Create a multi-vehicle route from the sub-tours of the vehicles, each without the depot.
+    ///
+    /// # Arguments
+    ///
+    /// * `depot` - The node every sub-tour starts and ends at.
+    /// * `sub_tours` - The nodes every vehicle visits after leaving the depot, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::multi_route::MultiRoute;
+    ///
+    /// let multi_route = MultiRoute::new(0, vec![vec![1, 2], vec![3]]);
+    /// assert_eq!(multi_route.indexes, vec![1, 2, 3]);
+    /// assert_eq!(multi_route.breaks, vec![2]);
+    /// ```
+    pub fn new(depot: usize, sub_tours: Vec<Vec<usize>>) -> Self {
+        let mut indexes = Vec::new();
+        let mut breaks = Vec::with_capacity(sub_tours.len().saturating_sub(1));
+        for (idx, sub_tour) in sub_tours.into_iter().enumerate() {
+            if idx > 0 {
+                breaks.push(indexes.len());
+            }
+            indexes.extend(sub_tour);
+        }
+        MultiRoute {
+            depot,
+            indexes,
+            breaks,
+        }
+    }
+    /// Create a random multi-vehicle route through `n_nodes` nodes with `n_vehicles` vehicles.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_nodes` - The number of nodes of the instance, including the depot.
+    /// * `depot` - The node every sub-tour starts and ends at.
+    /// * `n_vehicles` - The number of vehicles, at least 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::multi_route::MultiRoute;
+    ///
+    /// let multi_route = MultiRoute::random(6, 0, 2);
+    /// assert_eq!(multi_route.n_vehicles(), 2);
+    /// assert_eq!(multi_route.indexes.len(), 5);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn random(n_nodes: usize, depot: usize, n_vehicles: usize) -> Self {
+        MultiRoute::random_with_rng(n_nodes, depot, n_vehicles, &mut rand::thread_rng())
+    }
+    /// Create a random multi-vehicle route like `random`, but draw it from `rng`.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_nodes` - The number of nodes of the instance, including the depot.
+    /// * `depot` - The node every sub-tour starts and ends at.
+    /// * `n_vehicles` - The number of vehicles, at least 1.
+    /// * `rng` - The random number generator the route is drawn from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::multi_route::MultiRoute;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// assert_eq!(
+    ///     MultiRoute::random_with_rng(6, 0, 2, &mut StdRng::seed_from_u64(7)),
+    ///     MultiRoute::random_with_rng(6, 0, 2, &mut StdRng::seed_from_u64(7))
+    /// );
+    /// ```
+    pub fn random_with_rng<R: Rng + ?Sized>(
+        n_nodes: usize,
+        depot: usize,
+        n_vehicles: usize,
+        rng: &mut R,
+    ) -> Self {
+        let nodes = (0..n_nodes)
+            .filter(|node| *node != depot)
+            .collect::<Vec<usize>>();
+        let indexes = random_permutation_with_rng(&nodes, rng);
+        let mut breaks = (1..n_vehicles.max(1))
+            .map(|_| get_random_elem_from_range_with_rng(0..(indexes.len() + 1), rng))
+            .collect::<Vec<usize>>();
+        breaks.sort_unstable();
+        MultiRoute {
+            depot,
+            indexes,
+            breaks,
+        }
+    }
+    /// Get the number of vehicles.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::multi_route::MultiRoute;
+    ///
+    /// assert_eq!(MultiRoute::new(0, vec![vec![1, 2], vec![], vec![3]]).n_vehicles(), 3);
+    /// ```
+    pub fn n_vehicles(&self) -> usize {
+        self.breaks.len() + 1
+    }
+    /// Get the nodes every vehicle visits after leaving the depot, without the depot.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::multi_route::MultiRoute;
+    ///
+    /// let multi_route = MultiRoute::new(0, vec![vec![1, 2], vec![3]]);
+    /// assert_eq!(multi_route.sub_tours(), vec![vec![1, 2], vec![3]]);
+    /// ```
+    pub fn sub_tours(&self) -> Vec<Vec<usize>> {
+        let mut starts = vec![0];
+        starts.extend(&self.breaks);
+        let mut ends = self.breaks.clone();
+        ends.push(self.indexes.len());
+        starts
+            .into_iter()
+            .zip(ends)
+            .map(|(start, end)| self.indexes[start..end].to_vec())
+            .collect()
+    }
+    /// Get the round-trips of the vehicles, each starting at the depot. A vehicle with an
+    /// empty sub-tour has an empty round-trip.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::multi_route::MultiRoute;
+    ///
+    /// let multi_route = MultiRoute::new(0, vec![vec![1, 2], vec![]]);
+    /// assert_eq!(multi_route.tours(), vec![vec![0, 1, 2], vec![]]);
+    /// ```
+    pub fn tours(&self) -> Vec<Vec<usize>> {
+        self.sub_tours()
+            .into_iter()
+            .map(|sub_tour| {
+                if sub_tour.is_empty() {
+                    sub_tour
+                } else {
+                    core::iter::once(self.depot).chain(sub_tour).collect()
+                }
+            })
+            .collect()
+    }
+    /// Get the sub-tours in sorted order. Vehicles are interchangeable, so two multi-routes
+    /// with the same key serve the same nodes in the same order and only differ in which
+    /// vehicle drives which sub-tour.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::multi_route::MultiRoute;
+    ///
+    /// assert_eq!(
+    ///     MultiRoute::new(0, vec![vec![1], vec![2]]).tour_key(),
+    ///     MultiRoute::new(0, vec![vec![2], vec![1]]).tour_key()
+    /// );
+    /// ```
+    pub fn tour_key(&self) -> Vec<Vec<usize>> {
+        let mut sub_tours = self.sub_tours();
+        sub_tours.sort_unstable();
+        sub_tours
+    }
+    /// Mutate the route like `mutate`, but draw the mutation from `rng`.
+    ///
+    /// # Arguments
+    ///
+    /// * `prob` - The probability with which the route will be changed.
+    /// * `rng` - The random number generator the mutation is drawn from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::multi_route::MultiRoute;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// let mutated = MultiRoute::new(0, vec![vec![1, 2], vec![3, 4]])
+    ///     .mutate_with_rng(1.0, &mut StdRng::seed_from_u64(7));
+    /// assert_eq!(mutated.n_vehicles(), 2);
+    /// ```
+    pub fn mutate_with_rng<R: Rng + ?Sized>(mut self, prob: f32, rng: &mut R) -> Self {
+        if get_random_elem_from_range_with_rng(0.0..1.0, rng) > prob {
+            return self;
+        }
+        if !self.breaks.is_empty() && rng.gen_bool(0.5) {
+            self.shift_break(rng);
+        } else if let Some((put_before_idx, move_idx)) =
+            sample_relocation_with_rng(self.indexes.len(), 1.0, rng)
+        {
+            move_elem(&mut self.indexes, put_before_idx, move_idx);
+        }
+        self
+    }
+    /// Cross the route with another one like `crossover`, but draw the crossover from `rng`.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The other route you would like to crossover with this route.
+    /// * `rng` - The random number generator the crossover is drawn from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::multi_route::MultiRoute;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// let parent_a = MultiRoute::new(0, vec![vec![1, 2], vec![3, 4]]);
+    /// let parent_b = MultiRoute::new(0, vec![vec![4], vec![2, 1, 3]]);
+    /// let child = parent_a.crossover_with_rng(&parent_b, &mut StdRng::seed_from_u64(7));
+    /// assert_eq!(child.breaks, vec![2]);
+    /// ```
+    pub fn crossover_with_rng<R: Rng + ?Sized>(&self, other: &MultiRoute, rng: &mut R) -> Self {
+        let child = Route::new(self.indexes.clone())
+            .crossover_with_rng(&Route::new(other.indexes.clone()), rng);
+        MultiRoute {
+            depot: self.depot,
+            indexes: child.indexes,
+            breaks: self.breaks.clone(),
+        }
+    }
+    /// Move a random break between the breaks before and after it, so that nodes change from
+    /// one vehicle to its neighbor.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The random number generator the new position is drawn from.
+    ///
+    fn shift_break<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        let idx = get_random_elem_from_range_with_rng(0..self.breaks.len(), rng);
+        let lower = if idx == 0 { 0 } else { self.breaks[idx - 1] };
+        let upper = self
+            .breaks
+            .get(idx + 1)
+            .copied()
+            .unwrap_or(self.indexes.len());
+        self.breaks[idx] = get_random_elem_from_range_with_rng(lower..(upper + 1), rng);
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> Individual<'a> for MultiRoute {
+    // The Distance matrix is needed by the individuals to compute their fitness on.
+    type IndividualCost = DistanceMat;
+    /// With probability `prob` either move a node in front of another node, like
+    /// `Route::mutate`, which may move it to another vehicle, or shift the border between two
+    /// neighboring sub-tours. The number of vehicles doesn't change.
+    ///
+    /// # Arguments
+    ///
+    /// * `prob` - The probability with which the route will be changed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::multi_route::MultiRoute;
+    /// use genetic_algorithm_traits::Individual;
+    ///
+    /// let mutated = MultiRoute::new(0, vec![vec![1, 2], vec![3, 4]]).mutate(1.0);
+    /// assert_eq!(mutated.n_vehicles(), 2);
+    /// ```
+    fn mutate(self, prob: f32) -> Self {
+        self.mutate_with_rng(prob, &mut rand::thread_rng())
+    }
+    /// Cross the node orders of both routes with the Order Crossover of `Route::crossover`.
+    /// The child keeps the depot and the breaks of this route, so that it has as many vehicles.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The other individual you would like to crossover with this individual.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::multi_route::MultiRoute;
+    /// use genetic_algorithm_traits::Individual;
+    ///
+    /// let parent_a = MultiRoute::new(0, vec![vec![1, 2], vec![3, 4]]);
+    /// let parent_b = MultiRoute::new(0, vec![vec![4], vec![2, 1, 3]]);
+    /// assert_eq!(parent_a.crossover(&parent_b).breaks, vec![2]);
+    /// ```
+    fn crossover(&self, other: &MultiRoute) -> Self {
+        self.crossover_with_rng(other, &mut rand::thread_rng())
+    }
+    /// Compute the fitness of the routes with `DistanceMat::get_tours_cost`: the negative sum
+    /// of the lengths of all round-trips, or with `Objective::Makespan` the negative length of
+    /// the longest round-trip.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the fitness is computed on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::multi_route::MultiRoute;
+    /// use genetic_algorithm_tsp::objective::Objective;
+    /// use genetic_algorithm_traits::Individual;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let multi_route = MultiRoute::new(0, vec![vec![1], vec![2]]);
+    /// assert_eq!(multi_route.fitness(&distance_matrix), -6.0);
+    /// assert_eq!(multi_route.fitness(&distance_matrix.with_objective(Objective::Makespan)), -4.0);
+    /// ```
+    fn fitness(&self, distance_mat: &DistanceMat) -> f64 {
+        -distance_mat.get_tours_cost(&self.tours())
+    }
+}
+
+/// A population of `MultiRoute`s, the counterpart of `Routes` for the multiple Traveling
+/// Salesman Problem. Like `Routes` it never contains the same route twice.
+pub type MultiRoutes = Individuals<MultiRoute>;
+impl MultiRoutes {
+    /// Create a population of up to `n_routes` random routes, see `MultiRoute::random`.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_routes` - The number of routes that are drawn, duplicates are only kept once.
+    /// * `n_nodes` - The number of nodes of the instance, including the depot.
+    /// * `depot` - The node every sub-tour starts and ends at.
+    /// * `n_vehicles` - The number of vehicles, at least 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::multi_route::MultiRoutes;
+    ///
+    /// let multi_routes = MultiRoutes::random(10, 8, 0, 3);
+    /// assert!(multi_routes.len() <= 10);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn random(n_routes: usize, n_nodes: usize, depot: usize, n_vehicles: usize) -> Self {
+        MultiRoutes::random_with_rng(
+            n_routes,
+            n_nodes,
+            depot,
+            n_vehicles,
+            &mut rand::thread_rng(),
+        )
+    }
+    /// Create a population of random routes like `random`, but draw them from `rng`.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_routes` - The number of routes that are drawn, duplicates are only kept once.
+    /// * `n_nodes` - The number of nodes of the instance, including the depot.
+    /// * `depot` - The node every sub-tour starts and ends at.
+    /// * `n_vehicles` - The number of vehicles, at least 1.
+    /// * `rng` - The random number generator the routes are drawn from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::multi_route::MultiRoutes;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// assert_eq!(
+    ///     MultiRoutes::random_with_rng(10, 8, 0, 3, &mut StdRng::seed_from_u64(7)),
+    ///     MultiRoutes::random_with_rng(10, 8, 0, 3, &mut StdRng::seed_from_u64(7))
+    /// );
+    /// ```
+    pub fn random_with_rng<R: Rng + ?Sized>(
+        n_routes: usize,
+        n_nodes: usize,
+        depot: usize,
+        n_vehicles: usize,
+        rng: &mut R,
+    ) -> Self {
+        MultiRoutes::from(
+            (0..n_routes)
+                .map(|_| MultiRoute::random_with_rng(n_nodes, depot, n_vehicles, rng))
+                .collect::<Vec<MultiRoute>>(),
+        )
+    }
+    /// Evolve the population like `evolve`, but draw all random choices from `rng`, so that
+    /// the next generation only depends on the population and the state of `rng`.
+    ///
+    /// # Arguments
+    ///
+    /// * `mutate_prob` - The probabilty of an inviduals beeing mutated.
+    /// * `rng` - The random number generator all random choices are drawn from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::multi_route::MultiRoutes;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// let multi_routes = MultiRoutes::random(4, 8, 0, 2);
+    /// assert_eq!(
+    ///     multi_routes.evolve_with_rng(0.5, &mut StdRng::seed_from_u64(7)),
+    ///     multi_routes.evolve_with_rng(0.5, &mut StdRng::seed_from_u64(7))
+    /// );
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn evolve_with_rng<R: Rng + ?Sized>(&self, mutate_prob: f32, rng: &mut R) -> Self {
+        let mut parents = self.individuals.iter().collect::<Vec<&MultiRoute>>();
+        parents.sort_unstable_by(|route_a, route_b| {
+            (&route_a.indexes, &route_a.breaks).cmp(&(&route_b.indexes, &route_b.breaks))
+        });
+        let mut offspring = Vec::with_capacity(parents.len() * parents.len());
+        for (idx, parent_a) in parents.iter().enumerate() {
+            for (other_idx, parent_b) in parents.iter().enumerate() {
+                if idx != other_idx {
+                    offspring.push(
+                        parent_a
+                            .crossover_with_rng(parent_b, rng)
+                            .mutate_with_rng(mutate_prob, rng),
+                    );
+                }
+            }
+        }
+        offspring.extend(parents.into_iter().cloned());
+        MultiRoutes::from(offspring)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::objective::Objective;
+    use crate::routes::MAX_FRUITLESS_REPLACEMENTS;
+    use crate::selection::DuplicatePolicy;
+    use crate::test_utils::valid_permutation;
+    use genetic_algorithm_traits::Population;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::collections::HashSet;
+
+    fn square() -> DistanceMat {
+        // The depot in the middle of a square of four nodes.
+        DistanceMat::from_coordinates(&[
+            (0.0, 0.0),
+            (-1.0, -1.0),
+            (1.0, -1.0),
+            (1.0, 1.0),
+            (-1.0, 1.0),
+        ])
+    }
+
+    #[test]
+    fn operators_keep_the_partition() {
+        let parent_a = MultiRoute::random(9, 4, 3);
+        let parent_b = MultiRoute::random(9, 4, 3);
+        for _ in 0..20 {
+            let child = parent_a.crossover(&parent_b).mutate(1.0);
+            assert_eq!(child.depot, 4);
+            assert_eq!(child.n_vehicles(), 3);
+            assert!(child.breaks.windows(2).all(|pair| pair[0] <= pair[1]));
+            assert!(child.breaks.iter().all(|brk| *brk <= child.indexes.len()));
+            valid_permutation(&parent_a.indexes, &child.indexes);
+        }
+    }
+    #[test]
+    fn evolution_balances_makespan() {
+        let distance_mat = square().with_objective(Objective::Makespan);
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut population = MultiRoutes::random_with_rng(10, 5, 0, 2, &mut rng);
+        for _ in 0..30 {
+            population = population
+                .evolve_with_rng(0.5, &mut rng)
+                .get_fittest_population(10, &distance_mat);
+        }
+        let best = population.get_n_fittest(1, &distance_mat).remove(0);
+        // Every vehicle serves two neighboring corners: 2 * sqrt(2) + 2.
+        let optimum = 2.0 * 2f64.sqrt() + 2.0;
+        assert!((best.fitness(&distance_mat) + optimum).abs() < 1e-9);
+    }
+    #[test]
+    fn duplicates_are_replaced_by_random_routes() {
+        let multi_routes = MultiRoutes::from(vec![
+            MultiRoute::new(0, vec![vec![1], vec![2]]),
+            MultiRoute::new(0, vec![vec![2], vec![1]]),
+        ]);
+        let mut n_random = 0;
+        let deduped = multi_routes.evolve_with_duplicate_policy(
+            0.5,
+            DuplicatePolicy::Dedupe,
+            MultiRoute::tour_key,
+            || {
+                n_random += 1;
+                MultiRoute::random(3, 0, 2)
+            },
+        );
+        assert_eq!(n_random, 0);
+        // Both parents serve node 1 and node 2 with one vehicle each, so only one is kept.
+        assert!(deduped.len() < 4);
+        let keys: HashSet<_> = deduped.iter().map(MultiRoute::tour_key).collect();
+        assert_eq!(keys.len(), deduped.len());
+        // Every random route is distinct from the offspring and from each other, so the
+        // equivalent offspring are replaced one to one.
+        let replaced = multi_routes.evolve_with_duplicate_policy(
+            0.5,
+            DuplicatePolicy::DedupeWithRandomReplacement,
+            MultiRoute::tour_key,
+            || {
+                n_random += 1;
+                MultiRoute::new(3, vec![vec![n_random], vec![]])
+            },
+        );
+        assert_eq!(replaced.len(), 4);
+        assert_eq!(
+            replaced.iter().filter(|route| route.depot == 3).count(),
+            n_random
+        );
+    }
+    #[test]
+    fn replacements_are_deduped_by_tour_key() {
+        let multi_routes = MultiRoutes::from(vec![
+            MultiRoute::new(0, vec![vec![1], vec![2]]),
+            MultiRoute::new(0, vec![vec![2], vec![1]]),
+        ]);
+        // The random individuals are all equivalent to the parents, which are kept as offspring,
+        // so replacing is given up after a bounded number of draws instead of looping forever.
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut n_random = 0;
+        let replaced = multi_routes.evolve_with_duplicate_policy(
+            0.0,
+            DuplicatePolicy::DedupeWithRandomReplacement,
+            MultiRoute::tour_key,
+            || {
+                n_random += 1;
+                if rng.gen_bool(0.5) {
+                    MultiRoute::new(0, vec![vec![1], vec![2]])
+                } else {
+                    MultiRoute::new(0, vec![vec![2], vec![1]])
+                }
+            },
+        );
+        // Two nodes on two vehicles can only be served in three distinct ways.
+        assert!(replaced.len() <= 3);
+        assert_eq!(n_random, MAX_FRUITLESS_REPLACEMENTS);
+    }
+}
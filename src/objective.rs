@@ -0,0 +1,111 @@
+/// How the costs of a route under several scenario matrices are combined into one cost, see
+/// `DistanceMat::with_scenarios`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ScenarioAggregation {
+    /// The cost of the route in the scenario in which it is most expensive, so that the
+    /// evolution finds routes that are robust in all scenarios.
+    #[default]
+    WorstCase,
+    /// The mean cost of the route over all scenarios.
+    Average,
+}
+
+/// The `Objective` decides how the cost of a route is computed from a `DistanceMat`. The
+/// fitness of a `Route` is always the negative of this cost, so all objectives are minimized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Objective {
+    /// The classic TSP objective: the length of the round-trip through all nodes.
+    #[default]
+    TourLength,
+    /// The traveling repairman (or minimum-latency) objective: the sum of the arrival
+    /// times at all nodes. The route starts at its first node at time 0, and the return
+    /// to the first node is not counted.
+    Latency,
+    /// The bottleneck objective: the length of the longest single leg of the round-trip.
+    Bottleneck,
+    /// The balanced multi-salesman objective: the length of the longest sub-tour (the
+    /// makespan). For a single route this is the same as `TourLength`.
+    Makespan,
+    /// The open-path objective: the length of the Hamiltonian path from the first to the last
+    /// node of the route, which does not return to its start, e.g. a delivery route that ends
+    /// at a different depot.
+    PathLength,
+}
+
+/// How routes with the same fitness are ordered when the fittest routes are selected, see
+/// `DistanceMat::with_tie_breaking`. With integer-like costs many routes tie, and which of them
+/// survive a selection decides how the evolution continues.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TieBreaking {
+    /// Equal routes are ordered by their canonical tour (see `Route::canonical_tour`) and then
+    /// by their nodes, so that the selection doesn't depend on the order in which the routes
+    /// are stored and runs with the same seed stay the same.
+    #[default]
+    CanonicalTour,
+    /// Equal routes keep the order in which they are stored in the population. This skips
+    /// computing the canonical tours.
+    Unordered,
+}
+
+/// Which routes a selection treats as the same individual, see
+/// `DistanceMat::with_tour_equivalence`. Every route has as many rotations as nodes, and in a
+/// symmetric matrix its reverse has the same cost as well, so without deduplication a
+/// population can fill up with copies of the same round-trip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TourEquivalence {
+    /// Every sequence of nodes is a distinct route.
+    #[default]
+    Sequence,
+    /// Routes that describe the same round-trip are the same individual and only the first of
+    /// them survives a selection, see `DistanceMat::tour_key`: rotations of a route are always
+    /// the same round-trip, its reverse only if the matrix is symmetric.
+    RoundTrip,
+}
+
+/// When a node can be served, see `DistanceMat::with_time_windows`. The times are measured in
+/// the units of the distances, e.g. travel times, from the start of the route at time 0.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimeWindow {
+    /// The earliest time the service can start, a vehicle that arrives earlier waits.
+    pub earliest: f64,
+    /// The latest time the service should start, every unit of time after it is penalized.
+    pub latest: f64,
+    /// How long the service takes before the vehicle can leave.
+    pub service_time: f64,
+}
+impl TimeWindow {
+    /// Create the time window of a node.
+    ///
+    /// # Arguments
+    ///
+    /// * `earliest` - The earliest time the service can start.
+    /// * `latest` - The latest time the service should start.
+    /// * `service_time` - How long the service takes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::objective::TimeWindow;
+    ///
+    /// let time_window = TimeWindow::new(8.0, 12.0, 0.5);
+    /// assert_eq!(time_window.latest, 12.0);
+    /// ```
+    pub fn new(earliest: f64, latest: f64, service_time: f64) -> Self {
+        TimeWindow {
+            earliest,
+            latest,
+            service_time,
+        }
+    }
+}
+impl Default for TimeWindow {
+    /// A node without a time window: it can be served at any time and immediately.
+    fn default() -> Self {
+        TimeWindow::new(0.0, f64::INFINITY, 0.0)
+    }
+}
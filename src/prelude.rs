@@ -0,0 +1,10 @@
+pub use crate::distance_mat::DistanceMat;
+pub use crate::evolution::{Evolution, EvolutionConfig};
+pub use crate::route::Route;
+#[cfg(feature = "std")]
+pub use crate::routes::evolve_population;
+pub use crate::routes::Routes;
+#[cfg(feature = "std")]
+pub use crate::solver::{solve_tsp, PreparedSolver};
+#[cfg(feature = "std")]
+pub use genetic_algorithm_traits::{Individual, Population};
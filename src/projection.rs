@@ -0,0 +1,300 @@
+#[cfg(not(any(feature = "std", test)))]
+use crate::compat::Float;
+use alloc::vec::Vec;
+/// The mean radius of the earth in kilometers.
+pub const EARTH_RADIUS: f64 = 6371.0;
+
+/// A map projection of `(latitude, longitude)`-coordinates in decimal degrees onto a plane with
+/// coordinates in kilometers, so that spatial operators and plots work with distances instead of
+/// degrees, in which a degree of longitude shrinks towards the poles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Projection {
+    /// The equirectangular projection around the center of the points: longitudes are scaled
+    /// by the cosine of the mean latitude. It is fast and accurate for instances that span a
+    /// few hundred kilometers, e.g. a city.
+    #[default]
+    Equirectangular,
+    /// The Universal Transverse Mercator projection on the WGS84 ellipsoid into the zone of the
+    /// mean longitude. It is accurate to a fraction of a meter within the zone and still to
+    /// about a percent for instances that span a country.
+    Utm,
+}
+impl Projection {
+    /// Project `(latitude, longitude)`-coordinates onto the plane. The result is given as
+    /// `(x, y)` in kilometers, with `x` growing towards the east and `y` towards the north.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - The `(latitude, longitude)`-coordinates in decimal degrees.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::projection::Projection;
+    ///
+    /// // Berlin, Hamburg and Munich.
+    /// let points = [(52.52, 13.405), (53.551, 9.994), (48.137, 11.575)];
+    /// let projected = Projection::Utm.project(&points);
+    /// let (x, y) = (projected[0].0 - projected[1].0, projected[0].1 - projected[1].1);
+    /// assert!((x.hypot(y) - 255.0).abs() < 5.0);
+    /// ```
+    pub fn project(&self, points: &[(f64, f64)]) -> Vec<(f64, f64)> {
+        match self {
+            Projection::Equirectangular => equirectangular(points),
+            Projection::Utm => utm(points),
+        }
+    }
+}
+
+/// The great-circle distance in kilometers between two locations on a sphere with the mean
+/// radius of the earth (haversine formula).
+///
+/// # Arguments
+///
+/// * `from` - The `(latitude, longitude)`-coordinates of the first location in decimal degrees.
+/// * `to` - The `(latitude, longitude)`-coordinates of the second location in decimal degrees.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::projection::great_circle_distance;
+///
+/// // A degree of latitude is about 111 kilometers.
+/// assert!((great_circle_distance((0.0, 0.0), (1.0, 0.0)) - 111.19).abs() < 0.01);
+/// ```
+pub fn great_circle_distance(from: (f64, f64), to: (f64, f64)) -> f64 {
+    let ((latitude_from, longitude_from), (latitude_to, longitude_to)) = (from, to);
+    let delta_latitude = (latitude_to - latitude_from).to_radians();
+    let delta_longitude = (longitude_to - longitude_from).to_radians();
+    let a = (delta_latitude / 2.0).sin().powi(2)
+        + latitude_from.to_radians().cos()
+            * latitude_to.to_radians().cos()
+            * (delta_longitude / 2.0).sin().powi(2);
+    // Rounding can push `a` slightly above 1 for antipodal points, which would make `asin` NaN.
+    2.0 * EARTH_RADIUS * a.clamp(0.0, 1.0).sqrt().asin()
+}
+
+/// The mean latitude and the circular mean longitude of the points, `(0.0, 0.0)` if there are
+/// none. Longitudes are averaged as directions, so that points on both sides of the
+/// antimeridian are centered around ±180° instead of 0°.
+///
+/// # Arguments
+///
+/// * `points` - The `(latitude, longitude)`-coordinates in decimal degrees.
+///
+fn center(points: &[(f64, f64)]) -> (f64, f64) {
+    if points.is_empty() {
+        return (0.0, 0.0);
+    }
+    let (latitude_sum, sin_sum, cos_sum) = points.iter().fold(
+        (0.0, 0.0, 0.0),
+        |(latitude_sum, sin_sum, cos_sum), (latitude, longitude)| {
+            let longitude = longitude.to_radians();
+            (
+                latitude_sum + latitude,
+                sin_sum + longitude.sin(),
+                cos_sum + longitude.cos(),
+            )
+        },
+    );
+    (
+        latitude_sum / points.len() as f64,
+        sin_sum.atan2(cos_sum).to_degrees(),
+    )
+}
+
+/// The longitude relative to a reference meridian, wrapped into `[-180, 180)` degrees.
+///
+/// # Arguments
+///
+/// * `longitude` - The longitude in decimal degrees.
+/// * `reference` - The longitude of the reference meridian in decimal degrees.
+///
+fn longitude_offset(longitude: f64, reference: f64) -> f64 {
+    (longitude - reference + 180.0).rem_euclid(360.0) - 180.0
+}
+
+/// Project points with the equirectangular projection around their center, the center is
+/// projected to `(0.0, 0.0)`.
+///
+/// # Arguments
+///
+/// * `points` - The `(latitude, longitude)`-coordinates in decimal degrees.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::projection::equirectangular;
+///
+/// let projected = equirectangular(&[(60.0, 10.0), (60.0, 12.0)]);
+/// // At 60° north a degree of longitude is half as long as at the equator.
+/// assert!((projected[1].0 - projected[0].0 - 111.19).abs() < 0.01);
+/// assert_eq!(projected[0].1, 0.0);
+/// ```
+pub fn equirectangular(points: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    let (center_latitude, center_longitude) = center(points);
+    let scale = center_latitude.to_radians().cos();
+    points
+        .iter()
+        .map(|(latitude, longitude)| {
+            (
+                EARTH_RADIUS * longitude_offset(*longitude, center_longitude).to_radians() * scale,
+                EARTH_RADIUS * (latitude - center_latitude).to_radians(),
+            )
+        })
+        .collect()
+}
+
+/// The UTM zone of a longitude, from 1 to 60.
+///
+/// # Arguments
+///
+/// * `longitude` - The longitude in decimal degrees.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::projection::utm_zone;
+///
+/// assert_eq!(utm_zone(13.405), 33);
+/// assert_eq!(utm_zone(-180.0), 1);
+/// assert_eq!(utm_zone(180.0), 60);
+/// ```
+pub fn utm_zone(longitude: f64) -> u8 {
+    (((longitude + 180.0) / 6.0).floor() as i64 + 1).clamp(1, 60) as u8
+}
+
+/// Project points with the Universal Transverse Mercator projection on the WGS84 ellipsoid.
+/// All points are projected into the zone of their mean longitude, so that they share one
+/// plane even if they cross a zone border. As usual, `x` is 500 kilometers on the central
+/// meridian of the zone and `y` is offset by 10000 kilometers if the mean latitude is south of
+/// the equator.
+///
+/// # Arguments
+///
+/// * `points` - The `(latitude, longitude)`-coordinates in decimal degrees.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::projection::utm;
+///
+/// // The central meridian of zone 31 is at 3° east.
+/// let projected = utm(&[(0.0, 3.0)]);
+/// assert!((projected[0].0 - 500.0).abs() < 1e-9);
+/// assert!(projected[0].1.abs() < 1e-9);
+/// ```
+pub fn utm(points: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    let (center_latitude, center_longitude) = center(points);
+    let central_meridian = (utm_zone(center_longitude) as f64 - 1.0) * 6.0 - 180.0 + 3.0;
+    let false_northing = if center_latitude < 0.0 { 10_000.0 } else { 0.0 };
+    // The WGS84 ellipsoid in kilometers.
+    let semi_major_axis = 6378.137;
+    let flattening = 1.0 / 298.257_223_563;
+    let scale = 0.9996;
+    let e2 = flattening * (2.0 - flattening);
+    let (e4, e6) = (e2 * e2, e2 * e2 * e2);
+    let ep2 = e2 / (1.0 - e2);
+    points
+        .iter()
+        .map(|(latitude, longitude)| {
+            let phi = latitude.to_radians();
+            let (sin_phi, cos_phi, tan_phi) = (phi.sin(), phi.cos(), phi.tan());
+            let n = semi_major_axis / (1.0 - e2 * sin_phi * sin_phi).sqrt();
+            let t = tan_phi * tan_phi;
+            let c = ep2 * cos_phi * cos_phi;
+            let a = cos_phi * longitude_offset(*longitude, central_meridian).to_radians();
+            // The length of the meridian arc from the equator to the latitude.
+            let m = semi_major_axis
+                * ((1.0 - e2 / 4.0 - 3.0 * e4 / 64.0 - 5.0 * e6 / 256.0) * phi
+                    - (3.0 * e2 / 8.0 + 3.0 * e4 / 32.0 + 45.0 * e6 / 1024.0) * (2.0 * phi).sin()
+                    + (15.0 * e4 / 256.0 + 45.0 * e6 / 1024.0) * (4.0 * phi).sin()
+                    - (35.0 * e6 / 3072.0) * (6.0 * phi).sin());
+            let x = scale
+                * n
+                * (a + (1.0 - t + c) * a.powi(3) / 6.0
+                    + (5.0 - 18.0 * t + t * t + 72.0 * c - 58.0 * ep2) * a.powi(5) / 120.0)
+                + 500.0;
+            let y = scale
+                * (m + n
+                    * tan_phi
+                    * (a * a / 2.0
+                        + (5.0 - t + 9.0 * c + 4.0 * c * c) * a.powi(4) / 24.0
+                        + (61.0 - 58.0 * t + t * t + 600.0 * c - 330.0 * ep2) * a.powi(6) / 720.0))
+                + false_northing;
+            (x, y)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn planar_distance(from: (f64, f64), to: (f64, f64)) -> f64 {
+        (from.0 - to.0).hypot(from.1 - to.1)
+    }
+    #[test]
+    fn utm_of_known_location() {
+        // The Eiffel Tower is at 448252 m east and 5411935 m north in zone 31.
+        let projected = utm(&[(48.85826, 2.2945)]);
+        assert!((projected[0].0 - 448.252).abs() < 0.01);
+        assert!((projected[0].1 - 5411.935).abs() < 0.01);
+    }
+    #[test]
+    fn utm_south_of_equator() {
+        let projected = utm(&[(-33.8688, 151.2093)]);
+        // Sydney is in zone 56, its northing is offset by 10000 kilometers.
+        assert_eq!(utm_zone(151.2093), 56);
+        assert!(projected[0].1 > 6000.0 && projected[0].1 < 6300.0);
+    }
+    #[test]
+    fn projections_keep_local_distances() {
+        // Points within a city.
+        let points = [(48.137, 11.575), (48.2, 11.6), (48.1, 11.5), (48.15, 11.45)];
+        for projection in [Projection::Equirectangular, Projection::Utm] {
+            let projected = projection.project(&points);
+            for i in 0..points.len() {
+                for j in 0..points.len() {
+                    let great_circle = great_circle_distance(points[i], points[j]);
+                    let planar = planar_distance(projected[i], projected[j]);
+                    assert!((planar - great_circle).abs() <= 0.005 * great_circle.max(1.0));
+                }
+            }
+        }
+    }
+    #[test]
+    fn great_circle_distance_of_antipodes() {
+        let half_circumference = EARTH_RADIUS * std::f64::consts::PI;
+        for (from, to) in [((0.0, 0.0), (0.0, 180.0)), ((45.0, 30.0), (-45.0, -150.0))] {
+            let distance = great_circle_distance(from, to);
+            assert!((distance - half_circumference).abs() < 1e-6);
+        }
+    }
+    #[test]
+    fn projections_across_the_antimeridian() {
+        // Fiji spans the antimeridian, its center is near 180° and not near 0°.
+        let points = [
+            (-17.8, 177.4),
+            (-16.6, 179.9),
+            (-16.8, -179.9),
+            (-18.1, -178.4),
+        ];
+        assert!(longitude_offset(center(&points).1, 180.0).abs() < 1.0);
+        for projection in [Projection::Equirectangular, Projection::Utm] {
+            let projected = projection.project(&points);
+            for i in 0..points.len() {
+                for j in 0..points.len() {
+                    let great_circle = great_circle_distance(points[i], points[j]);
+                    let planar = planar_distance(projected[i], projected[j]);
+                    assert!((planar - great_circle).abs() <= 0.01 * great_circle.max(1.0));
+                }
+            }
+        }
+    }
+    #[test]
+    fn empty_points() {
+        assert!(equirectangular(&[]).is_empty());
+        assert!(utm(&[]).is_empty());
+    }
+}
@@ -0,0 +1,282 @@
+use crate::distance_mat::DistanceMat;
+use crate::evolution::Evolution;
+use crate::route::Route;
+use crate::routes::Routes;
+use arc_swap::ArcSwap;
+use std::sync::Arc;
+
+/// The population of an evolution after one of its generations, as it is published by
+/// `PublishedPopulation`. A snapshot never changes, the evolution publishes a new one instead.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PopulationSnapshot {
+    /// The number of the generation, the initial population is generation 0.
+    pub generation: usize,
+    /// The population after the generation.
+    pub population: Routes,
+    /// The fittest route of the population, `None` if it is empty.
+    pub best_route: Option<Route>,
+    /// The cost of the fittest route, infinite if the population is empty.
+    pub best_cost: f64,
+}
+impl PopulationSnapshot {
+    /// Take the snapshot of a population and find its fittest route.
+    ///
+    /// # Arguments
+    ///
+    /// * `generation` - The number of the generation.
+    /// * `population` - The population after the generation.
+    /// * `distance_mat` - The distance matrix the fitness is computed on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::published::PopulationSnapshot;
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let snapshot = PopulationSnapshot::new(0, Routes::from(vec![Route::new(vec![0, 1, 2])]), &distance_matrix);
+    /// assert_eq!(snapshot.best_cost, 6.0);
+    /// ```
+    pub fn new(generation: usize, population: Routes, distance_mat: &DistanceMat) -> Self {
+        let best_route = population.get_n_fittest(1, distance_mat).pop();
+        let best_cost = best_route
+            .as_ref()
+            .map_or(f64::INFINITY, |route| -route.fitness(distance_mat));
+        PopulationSnapshot {
+            generation,
+            population,
+            best_route,
+            best_cost,
+        }
+    }
+}
+
+/// The latest population of a running evolution, shared with other threads. The evolution
+/// publishes a snapshot after every generation by swapping a pointer, and readers load the
+/// current snapshot without a lock, so monitoring threads never stop the evolution and a query
+/// for the best route doesn't copy the population.
+#[derive(Debug)]
+pub struct PublishedPopulation {
+    /// The snapshot of the latest generation.
+    current: ArcSwap<PopulationSnapshot>,
+}
+impl PublishedPopulation {
+    /// Create a published population that holds an empty population of generation 0 until the
+    /// first snapshot is published.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::published::PublishedPopulation;
+    ///
+    /// let published = PublishedPopulation::new();
+    /// assert_eq!(published.best_route(), None);
+    /// ```
+    pub fn new() -> Self {
+        PublishedPopulation {
+            current: ArcSwap::from_pointee(PopulationSnapshot {
+                generation: 0,
+                population: Routes::from(vec![]),
+                best_route: None,
+                best_cost: f64::INFINITY,
+            }),
+        }
+    }
+    /// Publish the population of a generation, it replaces the previous snapshot.
+    ///
+    /// # Arguments
+    ///
+    /// * `snapshot` - The snapshot of the generation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::published::{PopulationSnapshot, PublishedPopulation};
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let published = PublishedPopulation::new();
+    /// published.publish(PopulationSnapshot::new(1, Routes::from(vec![Route::new(vec![0, 1, 2])]), &distance_matrix));
+    /// assert_eq!(published.generation(), 1);
+    /// ```
+    pub fn publish(&self, snapshot: PopulationSnapshot) {
+        self.current.store(Arc::new(snapshot));
+    }
+    /// Get the latest snapshot. It stays valid while the evolution publishes newer ones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::published::PublishedPopulation;
+    ///
+    /// let snapshot = PublishedPopulation::new().snapshot();
+    /// assert_eq!(snapshot.generation, 0);
+    /// assert_eq!(snapshot.population.len(), 0);
+    /// ```
+    pub fn snapshot(&self) -> Arc<PopulationSnapshot> {
+        self.current.load_full()
+    }
+    /// Get the number of the latest published generation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::published::PublishedPopulation;
+    ///
+    /// assert_eq!(PublishedPopulation::new().generation(), 0);
+    /// ```
+    pub fn generation(&self) -> usize {
+        self.current.load().generation
+    }
+    /// Get the fittest route of the latest published generation, `None` before a non-empty
+    /// population was published. Only this route is copied.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::published::PublishedPopulation;
+    ///
+    /// assert_eq!(PublishedPopulation::new().best_route(), None);
+    /// ```
+    pub fn best_route(&self) -> Option<Route> {
+        self.current.load().best_route.clone()
+    }
+    /// Get the cost of the fittest route of the latest published generation, infinite before
+    /// a non-empty population was published.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::published::PublishedPopulation;
+    ///
+    /// assert_eq!(PublishedPopulation::new().best_cost(), f64::INFINITY);
+    /// ```
+    pub fn best_cost(&self) -> f64 {
+        self.current.load().best_cost
+    }
+}
+impl Default for PublishedPopulation {
+    fn default() -> Self {
+        PublishedPopulation::new()
+    }
+}
+
+/// Evolve a started stepwise `Evolution` until it is finished and publish its current
+/// population and the population after every generation to `published`, so that other threads
+/// can monitor the evolution or take its best route at any time while it runs.
+///
+/// # Arguments
+///
+/// * `evolution` - The started evolution, see `Evolution::start`.
+/// * `published` - Where the populations are published.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+/// use genetic_algorithm_tsp::evolution::{Evolution, EvolutionConfig};
+/// use genetic_algorithm_tsp::published::{evolve_population_published, PublishedPopulation};
+/// use genetic_algorithm_tsp::routes::Routes;
+///
+/// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+/// let config = EvolutionConfig::new().with_n_generations(10).with_size_generation(5);
+/// let mut evolution = Evolution::new(&distance_matrix, config).start(Routes::random(2, 3), 42);
+/// let published = PublishedPopulation::new();
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| println!("Best cost so far: {}", published.best_cost()));
+///     evolve_population_published(&mut evolution, &published);
+/// });
+/// assert_eq!(published.generation(), 10);
+/// ```
+pub fn evolve_population_published(evolution: &mut Evolution<'_>, published: &PublishedPopulation) {
+    let distance_mat = evolution.distance_mat();
+    loop {
+        published.publish(PopulationSnapshot::new(
+            evolution.generation(),
+            evolution.population().clone(),
+            distance_mat,
+        ));
+        if evolution.is_finished() {
+            break;
+        }
+        evolution.evolve(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evolution::EvolutionConfig;
+    use crate::route::MutationOperator;
+    use crossbeam_utils::thread;
+    use std::sync::Barrier;
+
+    fn distance_matrix() -> DistanceMat {
+        DistanceMat::from_coordinates(
+            &(0..12)
+                .map(|node| (node as f64, (node * node % 7) as f64))
+                .collect::<Vec<(f64, f64)>>(),
+        )
+    }
+    #[test]
+    fn readers_see_improving_generations() {
+        let distance_matrix = distance_matrix();
+        let config = EvolutionConfig::new()
+            .with_n_generations(50)
+            .with_size_generation(10);
+        let mut evolution =
+            Evolution::new(&distance_matrix, config).start(Routes::random(10, 12), 5);
+        let published = PublishedPopulation::new();
+        let barrier = Barrier::new(2);
+        thread::scope(|scope| {
+            let reader = scope.spawn(|_| {
+                barrier.wait();
+                (0..1000)
+                    .map(|_| {
+                        let snapshot = published.snapshot();
+                        (snapshot.generation, snapshot.best_cost)
+                    })
+                    .collect::<Vec<(usize, f64)>>()
+            });
+            // The reader starts together with the evolution, so it reads while it runs.
+            barrier.wait();
+            evolve_population_published(&mut evolution, &published);
+            // The best route always survives, so no reader sees the best cost get worse.
+            for pair in reader.join().unwrap().windows(2) {
+                assert!(pair[0].0 <= pair[1].0);
+                assert!(pair[0].0 == pair[1].0 || pair[1].1 <= pair[0].1);
+            }
+        })
+        .unwrap();
+        let population = evolution.population();
+        let snapshot = published.snapshot();
+        assert_eq!(snapshot.generation, 50);
+        assert_eq!(&snapshot.population, population);
+        assert_eq!(
+            snapshot.best_route,
+            Some(population.get_n_fittest(1, &distance_matrix).remove(0))
+        );
+    }
+    #[test]
+    fn publishes_the_configured_evolution() {
+        let distance_matrix = distance_matrix();
+        let config = EvolutionConfig::new()
+            .with_n_generations(8)
+            .with_size_generation(6)
+            .with_mutation_rate(0.2)
+            .with_mutation_operator(MutationOperator::Swap)
+            .with_elitism(1);
+        let initial_population = Routes::random(6, 12);
+        let mut plain =
+            Evolution::new(&distance_matrix, config).start(initial_population.clone(), 9);
+        plain.evolve(usize::MAX);
+        let mut evolution = Evolution::new(&distance_matrix, config).start(initial_population, 9);
+        let published = PublishedPopulation::new();
+        evolve_population_published(&mut evolution, &published);
+        assert_eq!(&published.snapshot().population, plain.population());
+    }
+}
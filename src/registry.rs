@@ -0,0 +1,202 @@
+use crate::route::Route;
+use crate::routes::Routes;
+use core::fmt;
+use fasthash_fork::xx;
+use std::collections::HashMap;
+
+/// A stable id of a route that was interned in a `RouteRegistry`. It is cheap to copy, hash and
+/// compare, so histories, lineages and caches can refer to routes by their id instead of
+/// cloning their indexes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RouteId(usize);
+impl RouteId {
+    /// The position of the route in its registry, ids are handed out as 0, 1, 2, ...
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::registry::RouteRegistry;
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let mut registry = RouteRegistry::new();
+    /// assert_eq!(registry.intern(Route::new(vec![0, 1, 2])).index(), 0);
+    /// ```
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+impl fmt::Display for RouteId {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "RouteId({})", self.0)
+    }
+}
+
+/// Interns routes: every distinct route is stored once and gets a stable `RouteId`. Interning
+/// the same route again returns the id it got the first time.
+#[derive(Debug, Clone, Default)]
+pub struct RouteRegistry {
+    /// The interned routes, the id of a route is its position.
+    routes: Vec<Route>,
+    /// The id of every interned route.
+    ids: HashMap<Route, RouteId, xx::Hash64>,
+}
+impl RouteRegistry {
+    /// Create an empty registry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::registry::RouteRegistry;
+    ///
+    /// let registry = RouteRegistry::new();
+    /// assert!(registry.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        RouteRegistry::default()
+    }
+    /// Get the id of a route, adding it to the registry if it wasn't interned before.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The route that should be interned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::registry::RouteRegistry;
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let mut registry = RouteRegistry::new();
+    /// let id = registry.intern(Route::new(vec![0, 1, 2]));
+    /// assert_eq!(registry.intern(Route::new(vec![0, 1, 2])), id);
+    /// assert_ne!(registry.intern(Route::new(vec![1, 0, 2])), id);
+    /// ```
+    pub fn intern(&mut self, route: Route) -> RouteId {
+        if let Some(id) = self.ids.get(&route) {
+            return *id;
+        }
+        let id = RouteId(self.routes.len());
+        self.routes.push(route.clone());
+        self.ids.insert(route, id);
+        id
+    }
+    /// Intern all routes of a population and return their ids.
+    ///
+    /// # Arguments
+    ///
+    /// * `routes` - The population whose routes should be interned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::registry::RouteRegistry;
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let mut registry = RouteRegistry::new();
+    /// let ids = registry.intern_population(&Routes::from(vec![Route::new(vec![0, 1, 2]), Route::new(vec![1, 0, 2])]));
+    /// assert_eq!(ids.len(), 2);
+    /// ```
+    pub fn intern_population(&mut self, routes: &Routes) -> Vec<RouteId> {
+        routes
+            .iter()
+            .map(|route| self.intern(route.clone()))
+            .collect()
+    }
+    /// Get the id of a route without interning it, `None` if it was never interned.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The route whose id should be looked up.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::registry::RouteRegistry;
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let mut registry = RouteRegistry::new();
+    /// let id = registry.intern(Route::new(vec![0, 1, 2]));
+    /// assert_eq!(registry.id(&Route::new(vec![0, 1, 2])), Some(id));
+    /// assert_eq!(registry.id(&Route::new(vec![1, 0, 2])), None);
+    /// ```
+    pub fn id(&self, route: &Route) -> Option<RouteId> {
+        self.ids.get(route).copied()
+    }
+    /// Get the route with the given id, `None` if the id is from another registry.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the route.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::registry::RouteRegistry;
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let mut registry = RouteRegistry::new();
+    /// let id = registry.intern(Route::new(vec![0, 1, 2]));
+    /// assert_eq!(registry.get(id), Some(&Route::new(vec![0, 1, 2])));
+    /// ```
+    pub fn get(&self, id: RouteId) -> Option<&Route> {
+        self.routes.get(id.0)
+    }
+    /// The number of interned routes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::registry::RouteRegistry;
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let mut registry = RouteRegistry::new();
+    /// registry.intern(Route::new(vec![0, 1, 2]));
+    /// assert_eq!(registry.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.routes.len()
+    }
+    /// Does the registry contain no routes?
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::registry::RouteRegistry;
+    ///
+    /// assert!(RouteRegistry::new().is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn ids_are_stable() {
+        let mut registry = RouteRegistry::new();
+        let ids = (0..3)
+            .map(|offset| registry.intern(Route::new(vec![offset, offset + 1])))
+            .collect::<Vec<RouteId>>();
+        assert_eq!(
+            ids.iter().map(RouteId::index).collect::<Vec<usize>>(),
+            vec![0, 1, 2]
+        );
+        // Interning the routes again doesn't change their ids.
+        for (offset, id) in ids.iter().enumerate() {
+            assert_eq!(registry.intern(Route::new(vec![offset, offset + 1])), *id);
+            assert_eq!(
+                registry.get(*id),
+                Some(&Route::new(vec![offset, offset + 1]))
+            );
+        }
+        assert_eq!(registry.len(), 3);
+    }
+    #[test]
+    fn unknown_id() {
+        assert_eq!(RouteRegistry::new().get(RouteId(0)), None);
+    }
+}
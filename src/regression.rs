@@ -0,0 +1,509 @@
+use crate::evaluation::{EvaluationTable, SweepResult};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The version of the file format written by `BenchmarkReport::write`. Reading a report with
+/// another version fails, so that an outdated baseline is not compared silently.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// The errors that can occur while writing or reading benchmark reports.
+#[derive(Debug)]
+pub enum ReportError {
+    /// The report file could not be accessed.
+    Io(io::Error),
+    /// The report is not valid JSON or misses a field.
+    Format(serde_json::Error),
+    /// The report was written in another version of the file format.
+    UnsupportedVersion(u32),
+}
+impl fmt::Display for ReportError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReportError::Io(error) => write!(formatter, "Benchmark report IO failed: {}", error),
+            ReportError::Format(error) => {
+                write!(formatter, "Benchmark report is malformed: {}", error)
+            }
+            ReportError::UnsupportedVersion(version) => write!(
+                formatter,
+                "Benchmark report has format version {}, but only version {} is supported",
+                version, FORMAT_VERSION
+            ),
+        }
+    }
+}
+impl Error for ReportError {}
+impl From<io::Error> for ReportError {
+    fn from(error: io::Error) -> Self {
+        ReportError::Io(error)
+    }
+}
+impl From<serde_json::Error> for ReportError {
+    fn from(error: serde_json::Error) -> Self {
+        ReportError::Format(error)
+    }
+}
+
+/// The result of one configuration on one instance, the unit that is compared against the
+/// baseline.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkRecord {
+    /// The name of the instance.
+    pub instance: String,
+    /// The name of the configuration.
+    pub config: String,
+    /// The run time in milliseconds.
+    #[serde(with = "crate::distance_mat::non_finite")]
+    pub run_time_ms: f64,
+    /// The fitness of the best route, e.g. the negative length of the round-trip. It is
+    /// infinite if the route has an infinite distance, which is written as `"-inf"`.
+    #[serde(with = "crate::distance_mat::non_finite")]
+    pub fitness: f64,
+}
+
+/// The results of a benchmark run in a canonical form: the records are sorted by instance and
+/// configuration and always written with the same fields in the same order, so that reports of
+/// the same results are identical files that can be versioned and diffed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkReport {
+    /// The version of the file format, see `FORMAT_VERSION`.
+    pub format_version: u32,
+    /// The records, sorted by instance and configuration.
+    pub records: Vec<BenchmarkRecord>,
+}
+impl BenchmarkReport {
+    /// Create a report from records in any order.
+    ///
+    /// # Arguments
+    ///
+    /// * `records` - The results of the benchmark run.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::regression::{BenchmarkRecord, BenchmarkReport};
+    ///
+    /// let report = BenchmarkReport::new(vec![BenchmarkRecord {
+    ///     instance: "berlin52".to_string(),
+    ///     config: "small".to_string(),
+    ///     run_time_ms: 120.0,
+    ///     fitness: -7542.0,
+    /// }]);
+    /// ```
+    pub fn new(mut records: Vec<BenchmarkRecord>) -> Self {
+        records.sort_by(|a, b| (&a.instance, &a.config).cmp(&(&b.instance, &b.config)));
+        BenchmarkReport {
+            format_version: FORMAT_VERSION,
+            records,
+        }
+    }
+    /// Create a report from the results of `benchmark_sweep` on one instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `instance` - The name of the instance the sweep ran on.
+    /// * `results` - The results of the sweep.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::evaluation::{benchmark_sweep, OperatorConfig};
+    /// use genetic_algorithm_tsp::regression::BenchmarkReport;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let results = benchmark_sweep(&OperatorConfig::grid(&[5, 10], &[3], 0), &distance_matrix);
+    /// let report = BenchmarkReport::from_sweep("three", &results);
+    /// assert_eq!(report.records.len(), 2);
+    /// ```
+    pub fn from_sweep(instance: &str, results: &[SweepResult]) -> Self {
+        BenchmarkReport::new(
+            results
+                .iter()
+                .map(|result| BenchmarkRecord {
+                    instance: instance.to_string(),
+                    config: result.config.name.clone(),
+                    run_time_ms: result.result.run_time as f64,
+                    fitness: result.result.minimal_loss,
+                })
+                .collect(),
+        )
+    }
+    /// Create a report from an evaluation, with the mean run time and the mean fitness of every
+    /// row, as they are less noisy than a single run.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The summary table of `evaluate_instances`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::evaluation::{EvaluationSummary, EvaluationTable};
+    /// use genetic_algorithm_tsp::regression::BenchmarkReport;
+    ///
+    /// let table = EvaluationTable { rows: vec![EvaluationSummary {
+    ///     instance: "three".to_string(),
+    ///     config: "small".to_string(),
+    ///     n_runs: 3,
+    ///     best_fitness: -6.0,
+    ///     mean_fitness: -6.5,
+    ///     worst_fitness: -7.0,
+    ///     mean_run_time: 2.0,
+    /// }]};
+    /// assert_eq!(BenchmarkReport::from_table(&table).records[0].fitness, -6.5);
+    /// ```
+    pub fn from_table(table: &EvaluationTable) -> Self {
+        BenchmarkReport::new(
+            table
+                .rows
+                .iter()
+                .map(|row| BenchmarkRecord {
+                    instance: row.instance.clone(),
+                    config: row.config.clone(),
+                    run_time_ms: row.mean_run_time,
+                    fitness: row.mean_fitness,
+                })
+                .collect(),
+        )
+    }
+    /// Encode the report as pretty-printed JSON.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::regression::BenchmarkReport;
+    ///
+    /// println!("{}", BenchmarkReport::new(vec![]).to_json());
+    /// ```
+    pub fn to_json(&self) -> String {
+        // Only strings and numbers are encoded, which cannot fail.
+        serde_json::to_string_pretty(self).unwrap()
+    }
+    /// Decode a report from JSON, e.g. as written by `to_json`.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - The encoded report.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::regression::BenchmarkReport;
+    ///
+    /// let report = BenchmarkReport::new(vec![]);
+    /// assert_eq!(BenchmarkReport::from_json(&report.to_json()).unwrap(), report);
+    /// ```
+    pub fn from_json(json: &str) -> Result<Self, ReportError> {
+        let report: BenchmarkReport = serde_json::from_str(json)?;
+        if report.format_version != FORMAT_VERSION {
+            return Err(ReportError::UnsupportedVersion(report.format_version));
+        }
+        Ok(BenchmarkReport::new(report.records))
+    }
+    /// Write the report as JSON file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file, e.g. `baseline.json`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::regression::BenchmarkReport;
+    ///
+    /// let path = std::env::temp_dir().join("genetic_algorithm_tsp_doc_write_report.json");
+    /// BenchmarkReport::new(vec![]).write(&path).unwrap();
+    /// ```
+    pub fn write(&self, path: &Path) -> Result<(), ReportError> {
+        Ok(fs::write(path, self.to_json() + "\n")?)
+    }
+    /// Read a report that was written by `write`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::regression::BenchmarkReport;
+    ///
+    /// let path = std::env::temp_dir().join("genetic_algorithm_tsp_doc_read_report.json");
+    /// BenchmarkReport::new(vec![]).write(&path).unwrap();
+    /// assert!(BenchmarkReport::read(&path).unwrap().records.is_empty());
+    /// ```
+    pub fn read(path: &Path) -> Result<Self, ReportError> {
+        BenchmarkReport::from_json(&fs::read_to_string(path)?)
+    }
+    /// Compare the report against a baseline and return every regression: records of the
+    /// baseline that are missing, slower or of worse quality than allowed by the tolerances.
+    /// Records that are not part of the baseline are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `baseline` - The stored report of an earlier run.
+    /// * `tolerances` - How much slower and worse a record may be.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::regression::{BenchmarkRecord, BenchmarkReport, Tolerances};
+    ///
+    /// let record = |run_time_ms, fitness| BenchmarkRecord {
+    ///     instance: "three".to_string(),
+    ///     config: "small".to_string(),
+    ///     run_time_ms,
+    ///     fitness,
+    /// };
+    /// let baseline = BenchmarkReport::new(vec![record(100.0, -6.0)]);
+    /// let tolerances = Tolerances::new(0.1, 0.0);
+    /// assert!(BenchmarkReport::new(vec![record(105.0, -6.0)]).compare(&baseline, tolerances).is_empty());
+    /// assert_eq!(BenchmarkReport::new(vec![record(150.0, -7.0)]).compare(&baseline, tolerances).len(), 2);
+    /// ```
+    pub fn compare(&self, baseline: &BenchmarkReport, tolerances: Tolerances) -> Vec<Regression> {
+        let mut regressions = Vec::new();
+        for expected in &baseline.records {
+            let current = self.records.iter().find(|record| {
+                record.instance == expected.instance && record.config == expected.config
+            });
+            let current = match current {
+                Some(current) => current,
+                None => {
+                    regressions.push(Regression::Missing {
+                        instance: expected.instance.clone(),
+                        config: expected.config.clone(),
+                    });
+                    continue;
+                }
+            };
+            if current.run_time_ms > expected.run_time_ms * (1.0 + tolerances.run_time) {
+                regressions.push(Regression::Slower {
+                    instance: expected.instance.clone(),
+                    config: expected.config.clone(),
+                    baseline: expected.run_time_ms,
+                    current: current.run_time_ms,
+                });
+            }
+            if current.fitness < expected.fitness - tolerances.fitness * expected.fitness.abs() {
+                regressions.push(Regression::Worse {
+                    instance: expected.instance.clone(),
+                    config: expected.config.clone(),
+                    baseline: expected.fitness,
+                    current: current.fitness,
+                });
+            }
+        }
+        regressions
+    }
+}
+
+/// How much a record may deviate from the baseline before it counts as regression, both
+/// relative to the value of the baseline. Run times are noisy, so their tolerance is usually
+/// much larger than the one of the fitness.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerances {
+    /// How much longer a run may take, e.g. 0.2 allows 20% more time.
+    pub run_time: f64,
+    /// How much worse the fitness may be, e.g. 0.01 allows a 1% longer round-trip.
+    pub fitness: f64,
+}
+impl Tolerances {
+    /// Create the tolerances.
+    ///
+    /// # Arguments
+    ///
+    /// * `run_time` - How much longer a run may take, relative to the baseline.
+    /// * `fitness` - How much worse the fitness may be, relative to the baseline.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::regression::Tolerances;
+    ///
+    /// let tolerances = Tolerances::new(0.2, 0.01);
+    /// ```
+    pub fn new(run_time: f64, fitness: f64) -> Self {
+        Tolerances { run_time, fitness }
+    }
+}
+impl Default for Tolerances {
+    /// 25% more run time and a 1% worse fitness.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::regression::Tolerances;
+    ///
+    /// assert_eq!(Tolerances::default(), Tolerances::new(0.25, 0.01));
+    /// ```
+    fn default() -> Self {
+        Tolerances::new(0.25, 0.01)
+    }
+}
+
+/// A record that regressed compared to the baseline.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Regression {
+    /// The record of the baseline is not part of the new report.
+    Missing {
+        /// The name of the instance.
+        instance: String,
+        /// The name of the configuration.
+        config: String,
+    },
+    /// The run took longer than allowed.
+    Slower {
+        /// The name of the instance.
+        instance: String,
+        /// The name of the configuration.
+        config: String,
+        /// The run time of the baseline in milliseconds.
+        baseline: f64,
+        /// The run time of the new report in milliseconds.
+        current: f64,
+    },
+    /// The best route is worse than allowed.
+    Worse {
+        /// The name of the instance.
+        instance: String,
+        /// The name of the configuration.
+        config: String,
+        /// The fitness of the baseline.
+        baseline: f64,
+        /// The fitness of the new report.
+        current: f64,
+    },
+}
+impl fmt::Display for Regression {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Regression::Missing { instance, config } => {
+                write!(formatter, "{}/{}: missing", instance, config)
+            }
+            Regression::Slower {
+                instance,
+                config,
+                baseline,
+                current,
+            } => write!(
+                formatter,
+                "{}/{}: run time {} ms, baseline {} ms",
+                instance, config, current, baseline
+            ),
+            Regression::Worse {
+                instance,
+                config,
+                baseline,
+                current,
+            } => write!(
+                formatter,
+                "{}/{}: fitness {}, baseline {}",
+                instance, config, current, baseline
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(instance: &str, config: &str, run_time_ms: f64, fitness: f64) -> BenchmarkRecord {
+        BenchmarkRecord {
+            instance: instance.to_string(),
+            config: config.to_string(),
+            run_time_ms,
+            fitness,
+        }
+    }
+    #[test]
+    fn canonical_order() {
+        let a = BenchmarkReport::new(vec![
+            record("b", "small", 1.0, -2.0),
+            record("a", "small", 1.0, -2.0),
+            record("a", "large", 1.0, -2.0),
+        ]);
+        let b = BenchmarkReport::new(vec![
+            record("a", "large", 1.0, -2.0),
+            record("b", "small", 1.0, -2.0),
+            record("a", "small", 1.0, -2.0),
+        ]);
+        assert_eq!(a.to_json(), b.to_json());
+        assert_eq!(a.records[0], record("a", "large", 1.0, -2.0));
+    }
+    #[test]
+    fn json_round_trip() {
+        let report = BenchmarkReport::new(vec![record("a", "small", 12.5, -0.1)]);
+        assert_eq!(
+            BenchmarkReport::from_json(&report.to_json()).unwrap(),
+            report
+        );
+    }
+    #[test]
+    fn json_round_trip_of_non_finite_values() {
+        let report = BenchmarkReport::new(vec![
+            record("a", "small", f64::INFINITY, f64::NEG_INFINITY),
+            record("b", "small", f64::NAN, -1.5),
+        ]);
+        let json = report.to_json();
+        assert!(json.contains(r#""fitness": "-inf""#), "{}", json);
+        let read = BenchmarkReport::from_json(&json).unwrap();
+        assert_eq!(read.records[0], report.records[0]);
+        assert!(read.records[1].run_time_ms.is_nan());
+        assert_eq!(read.records[1].fitness, -1.5);
+    }
+    #[test]
+    fn reject_other_version() {
+        let json = r#"{"format_version": 2, "records": []}"#;
+        assert!(matches!(
+            BenchmarkReport::from_json(json),
+            Err(ReportError::UnsupportedVersion(2))
+        ));
+        assert!(matches!(
+            BenchmarkReport::from_json("{"),
+            Err(ReportError::Format(_))
+        ));
+    }
+    #[test]
+    fn detect_regressions() {
+        let baseline = BenchmarkReport::new(vec![
+            record("a", "small", 100.0, -100.0),
+            record("a", "large", 100.0, -100.0),
+            record("b", "small", 100.0, -100.0),
+        ]);
+        let current = BenchmarkReport::new(vec![
+            // Within the tolerances.
+            record("a", "small", 119.0, -100.9),
+            // Slower and worse.
+            record("a", "large", 121.0, -101.1),
+            // Not part of the baseline.
+            record("c", "small", 1000.0, -1000.0),
+        ]);
+        let regressions = current.compare(&baseline, Tolerances::new(0.2, 0.01));
+        assert_eq!(
+            regressions,
+            vec![
+                Regression::Slower {
+                    instance: "a".to_string(),
+                    config: "large".to_string(),
+                    baseline: 100.0,
+                    current: 121.0
+                },
+                Regression::Worse {
+                    instance: "a".to_string(),
+                    config: "large".to_string(),
+                    baseline: -100.0,
+                    current: -101.1
+                },
+                Regression::Missing {
+                    instance: "b".to_string(),
+                    config: "small".to_string()
+                },
+            ]
+        );
+    }
+}
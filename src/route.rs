@@ -1,13 +1,207 @@
+use crate::candidates::CandidateList;
 use crate::distance_mat::DistanceMat;
+#[cfg(feature = "self-check")]
+use crate::self_check::{check_fitness, check_permutation};
 use crate::subsequence::Subsequence;
-use crate::utils::{change_order, get_random_elem_from_range, ordered_crossover, remove_elem};
+use crate::utils::{
+    cycle_crossover, edge_recombination_crossover, get_random_elem_from_range_with_rng, move_elem,
+    ordered_crossover, partially_mapped_crossover, spatial_segment_crossover,
+};
+use alloc::vec::Vec;
+use core::cmp::max;
+use core::fmt;
+#[cfg(feature = "std")]
 use genetic_algorithm_traits::Individual;
+use rand::rngs::mock::StepRng;
 use rand::seq::SliceRandom;
-use std::cmp::max;
-use std::fmt;
+use rand::Rng;
+
+/// The crossover operator with which two routes are combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CrossoverOperator {
+    /// The Order Crossover (OX) of `Route::crossover`: a subsequence of the first parent is
+    /// inserted into the second parent, whose other nodes keep their relative order.
+    #[default]
+    Ordered,
+    /// The Partially Mapped Crossover (PMX): a subsequence of the first parent is copied to the
+    /// same positions, the other nodes keep their positions in the second parent where possible.
+    PartiallyMapped,
+    /// The Cycle Crossover (CX): every node keeps its position in one of the parents.
+    Cycle,
+    /// The Edge Recombination Crossover (ERX): the child is built from the edges of its
+    /// parents, which preserves adjacencies and usually gives the shortest children.
+    EdgeRecombination,
+    /// The spatial segment crossover of `Route::spatial_crossover_with_rng`: the nodes in a random
+    /// rectangle are exchanged as one block, which respects the locality of the instance. It
+    /// needs the coordinates of the `DistanceMat`, an evolution without them is rejected.
+    Spatial,
+    /// The candidate crossover of `Route::candidate_crossover_with_rng`: the child follows the
+    /// edges of its parents and only introduces edges to near neighbors. It needs the
+    /// candidate list of the `DistanceMat`, an evolution without it is rejected.
+    Candidate,
+}
+
+/// Why the spatial crossover can't be applied without coordinates.
+pub(crate) const SPATIAL_WITHOUT_COORDINATES: &str =
+    "CrossoverOperator::Spatial needs the coordinates of the nodes, create the distance matrix \
+     with `DistanceMat::from_coordinates` or add them with `DistanceMat::with_coordinates`";
+
+/// Why the candidate operators can't be applied without a candidate list.
+pub(crate) const CANDIDATES_MISSING: &str =
+    "The candidate operators need the candidate list of the nodes, add it with \
+     `DistanceMat::with_candidate_list`";
+
+/// The mutation operator with which a route is changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MutationOperator {
+    /// The mutation of `Route::mutate`: one node is moved before another node.
+    #[default]
+    Insertion,
+    /// Two nodes swap their positions.
+    Swap,
+    /// A random subsequence is reversed. This is a 2-opt move, the standard mutation for the
+    /// TSP, as it only replaces two edges of a symmetric tour.
+    Inversion,
+    /// The nodes of a random subsequence are shuffled.
+    Scramble,
+    /// The 2-opt move of `Route::candidate_mutate_with_rng`, which connects a node to one of
+    /// its near neighbors. It needs the candidate list of the `DistanceMat`, an evolution
+    /// without it is rejected.
+    Candidate,
+}
+
+/// Which of the improving moves of a neighborhood a local search applies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PivotingRule {
+    /// Apply the first improving move that is found and continue the scan from there. A pass
+    /// is cheap, which usually pays off on uniform instances.
+    #[default]
+    FirstImprovement,
+    /// Scan the whole neighborhood and apply the move that improves the route the most. A pass
+    /// is expensive, but takes fewer and larger steps, e.g. on clustered instances.
+    BestImprovement,
+}
+
+/// In which order a local search scans the moves of a neighborhood.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NeighborhoodOrder {
+    /// Scan the moves by the position at which they change the route.
+    #[default]
+    Sequential,
+    /// Scan the positions in a new random order in every pass, so that a first-improvement
+    /// search doesn't always improve the start of the route first.
+    Random,
+}
+
+/// How a 3-opt move of `Route::three_opt` reconnects the two segments `B` and `C` of a route
+/// `A B C D` after three of its legs were removed. Only the reconnections that replace all
+/// three legs are listed, the others are 2-opt moves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ThreeOptMove {
+    /// `A C B D`: the segments swap their places, none is reversed. This is the only
+    /// reconnection that keeps the direction of every leg, e.g. for asymmetric matrices.
+    SegmentExchange,
+    /// `A B' C' D`: both segments are reversed in place.
+    DoubleReversal,
+    /// `A C B' D`: the segments swap their places and the first one is reversed.
+    ExchangeReversedFirst,
+    /// `A C' B D`: the segments swap their places and the second one is reversed.
+    ExchangeReversedSecond,
+}
+impl ThreeOptMove {
+    /// All reconnections of a 3-opt move.
+    pub const ALL: [ThreeOptMove; 4] = [
+        ThreeOptMove::SegmentExchange,
+        ThreeOptMove::DoubleReversal,
+        ThreeOptMove::ExchangeReversedFirst,
+        ThreeOptMove::ExchangeReversedSecond,
+    ];
+    /// Apply the move to a route: the legs after `first_idx`, `second_idx` and `third_idx`
+    /// are removed and the segments between them are reconnected.
+    ///
+    /// # Arguments
+    ///
+    /// * `indexes` - The nodes of the route.
+    /// * `first_idx` - The position of the last node before the first segment.
+    /// * `second_idx` - The position of the last node of the first segment.
+    /// * `third_idx` - The position of the last node of the second segment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::route::ThreeOptMove;
+    ///
+    /// let mut indexes = vec![0, 1, 2, 3, 4, 5];
+    /// ThreeOptMove::ExchangeReversedFirst.apply(&mut indexes, 0, 2, 4);
+    /// assert_eq!(indexes, vec![0, 3, 4, 2, 1, 5]);
+    /// ```
+    pub fn apply(
+        self,
+        indexes: &mut [usize],
+        first_idx: usize,
+        second_idx: usize,
+        third_idx: usize,
+    ) {
+        let first_len = second_idx - first_idx;
+        let segments = &mut indexes[(first_idx + 1)..=third_idx];
+        match self {
+            ThreeOptMove::SegmentExchange => segments.rotate_left(first_len),
+            ThreeOptMove::DoubleReversal => {
+                let (first, second) = segments.split_at_mut(first_len);
+                first.reverse();
+                second.reverse();
+            }
+            ThreeOptMove::ExchangeReversedFirst => {
+                segments.rotate_left(first_len);
+                let second_len = segments.len() - first_len;
+                segments[second_len..].reverse();
+            }
+            ThreeOptMove::ExchangeReversedSecond => {
+                segments.rotate_left(first_len);
+                let second_len = segments.len() - first_len;
+                segments[..second_len].reverse();
+            }
+        }
+    }
+}
+
+/// How a local search such as `Route::two_opt_with_policy` moves through its neighborhood.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LocalSearchPolicy {
+    /// Which improving move is applied.
+    pub pivoting: PivotingRule,
+    /// In which order the moves are scanned.
+    pub ordering: NeighborhoodOrder,
+}
+impl LocalSearchPolicy {
+    /// Create a local search policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `pivoting` - Which improving move is applied.
+    /// * `ordering` - In which order the moves are scanned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::route::{LocalSearchPolicy, NeighborhoodOrder, PivotingRule};
+    ///
+    /// let policy = LocalSearchPolicy::new(PivotingRule::BestImprovement, NeighborhoodOrder::Sequential);
+    /// ```
+    pub fn new(pivoting: PivotingRule, ordering: NeighborhoodOrder) -> Self {
+        LocalSearchPolicy { pivoting, ordering }
+    }
+}
 
 /// The `Route` is an invidiual in the traveling salemens problem that is a valid route.
 #[derive(Debug, PartialEq, Clone, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Route {
     /// The order in which the nodes should be visited.
     pub indexes: Vec<usize>,
@@ -19,42 +213,1374 @@ impl fmt::Display for Route {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         write!(formatter, "Route({:?})", self.indexes)
     }
-}
-impl Route {
-    /// Create a new route based on a vector of indexes.
+}
+impl Route {
+    /// Create a new route based on a vector of indexes.
+    ///
+    /// # Arguments
+    ///
+    /// * `indexes` - The order in which the nodes are visited in the Traveling Salesman Problem.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let my_individual = Route::from(Route::new(vec![0,1,2]));
+    /// ```
+    pub fn new(indexes: Vec<usize>) -> Self {
+        Self { indexes }
+    }
+    /// Get the number of nodes for this route.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let three_node_route = Route::from(Route::new(vec![0,1,2]));
+    /// println!("This route has {} nodes", three_node_route.get_n_nodes());
+    /// ```
+    pub fn get_n_nodes(&self) -> usize {
+        self.indexes.len()
+    }
+    /// The canonical form of the round-trip: the route is rotated to start at its smallest node
+    /// and of its two directions the lexicographically smaller one is taken. All routes that
+    /// describe the same round-trip have the same canonical tour.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// assert_eq!(Route::new(vec![2, 3, 0, 1]).canonical_tour(), vec![0, 1, 2, 3]);
+    /// assert_eq!(Route::new(vec![1, 0, 3, 2]).canonical_tour(), vec![0, 1, 2, 3]);
+    /// ```
+    pub fn canonical_tour(&self) -> Vec<usize> {
+        CanonicalOrder::new(&self.indexes).iter().collect()
+    }
+    /// Get the nodes of the first `n_legs` legs of the route, e.g. the prefix a dispatcher
+    /// accepted and that should be pinned with `pin_prefix` while the rest is evolved further.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_legs` - The number of legs from the start of the route.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let route = Route::new(vec![3, 0, 2, 1]);
+    /// assert_eq!(route.first_legs(2), &[3, 0, 2]);
+    /// assert_eq!(route.first_legs(0), &[] as &[usize]);
+    /// ```
+    pub fn first_legs(&self, n_legs: usize) -> &[usize] {
+        if n_legs == 0 {
+            return &[];
+        }
+        &self.indexes[..(n_legs + 1).min(self.indexes.len())]
+    }
+    /// Move the nodes of `prefix` to the start of the route in the order of `prefix`. The other
+    /// nodes keep their order, so a route that already starts with `prefix` is not changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The nodes the route has to start with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let route = Route::new(vec![2, 0, 3, 1]).pin_prefix(&[1, 2]);
+    /// assert_eq!(route, Route::new(vec![1, 2, 0, 3]));
+    /// ```
+    pub fn pin_prefix(mut self, prefix: &[usize]) -> Self {
+        if self.indexes.starts_with(prefix) {
+            return self;
+        }
+        self.indexes.retain(|node| !prefix.contains(node));
+        self.indexes.splice(0..0, prefix.iter().copied());
+        self
+    }
+    /// Split the route into parts, where a new part starts at every node of `nodes`. Nodes
+    /// that are not visited by the route are ignored, so joining the parts with `join` gives
+    /// back the route.
+    ///
+    /// # Arguments
+    ///
+    /// * `nodes` - The nodes at which a new part should start.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let parts = Route::new(vec![0, 1, 2, 3, 4]).split_at_nodes(&[2, 4]);
+    /// assert_eq!(parts, vec![Route::new(vec![0, 1]), Route::new(vec![2, 3]), Route::new(vec![4])]);
+    /// ```
+    pub fn split_at_nodes(&self, nodes: &[usize]) -> Vec<Route> {
+        let mut parts: Vec<Route> = Vec::new();
+        for node in &self.indexes {
+            match parts.last_mut() {
+                Some(part) if !nodes.contains(node) => part.indexes.push(*node),
+                _ => parts.push(Route::new(vec![*node])),
+            }
+        }
+        parts
+    }
+    /// Join parts of a route, e.g. created by `split_at_nodes`, in the given order and
+    /// orientation.
+    ///
+    /// # Arguments
+    ///
+    /// * `parts` - The parts that should be visited one after another.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let route = Route::join(&[Route::new(vec![2, 3]), Route::new(vec![0, 1])]);
+    /// assert_eq!(route, Route::new(vec![2, 3, 0, 1]));
+    /// ```
+    pub fn join(parts: &[Route]) -> Route {
+        Route::new(
+            parts
+                .iter()
+                .flat_map(|part| part.indexes.iter().copied())
+                .collect(),
+        )
+    }
+    /// Join parts of a route in the order and orientation that keeps the cost low. Starting with
+    /// the first part that has nodes as it is, the part that increases the cost of the joined
+    /// route the least is appended next, either as it is or reversed, until all parts are joined.
+    /// The order of the nodes within a part is kept, so parts can e.g. be solved or edited
+    /// independently. Where the cost can be updated locally, a part is compared by the legs at
+    /// its ends and the precomputed cost of its inner legs, so joining `k` parts of `n` nodes
+    /// takes O(k² + n) steps instead of evaluating O(k²) joined routes.
+    ///
+    /// # Arguments
+    ///
+    /// * `parts` - The parts that should be joined.
+    /// * `distance_mat` - The distance matrix the cost is computed on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 5.0, 5.0],
+    ///     vec![1.0, 0.0, 1.0, 5.0],
+    ///     vec![5.0, 1.0, 0.0, 1.0],
+    ///     vec![5.0, 5.0, 1.0, 0.0],
+    /// ]);
+    /// let route = Route::join_best(&[Route::new(vec![0, 1]), Route::new(vec![3, 2])], &distance_matrix);
+    /// assert_eq!(route, Route::new(vec![0, 1, 2, 3]));
+    /// ```
+    pub fn join_best(parts: &[Route], distance_mat: &DistanceMat) -> Route {
+        // Empty parts don't change the joined route.
+        let mut remaining = parts
+            .iter()
+            .filter(|part| !part.indexes.is_empty())
+            .collect::<Vec<&Route>>();
+        if remaining.is_empty() {
+            return Route::new(Vec::new());
+        }
+        let mut joined = remaining.remove(0).indexes.clone();
+        // The cost of the legs within every remaining part, forwards and reversed.
+        let inner_cost = |nodes: &[usize], reversed: bool| -> f64 {
+            nodes
+                .windows(2)
+                .map(|leg| {
+                    if reversed {
+                        distance_mat.distance(leg[1], leg[0])
+                    } else {
+                        distance_mat.distance(leg[0], leg[1])
+                    }
+                })
+                .sum()
+        };
+        let mut inner_costs = remaining
+            .iter()
+            .map(|part| {
+                [
+                    inner_cost(&part.indexes, false),
+                    inner_cost(&part.indexes, true),
+                ]
+            })
+            .collect::<Vec<[f64; 2]>>();
+        while !remaining.is_empty() {
+            let mut best: Option<(f64, usize, bool)> = None;
+            for (position, part) in remaining.iter().enumerate() {
+                for reversed in [false, true] {
+                    let (first, last) = (part.indexes[0], part.indexes[part.indexes.len() - 1]);
+                    let (first, last) = if reversed {
+                        (last, first)
+                    } else {
+                        (first, last)
+                    };
+                    // The cost of the joined route is the same for every part, so only the change
+                    // of the cost is compared. Without a local delta the whole route is evaluated.
+                    let cost = match distance_mat.get_append_delta(&joined, first, last) {
+                        Some(delta) => delta + inner_costs[position][usize::from(reversed)],
+                        None => {
+                            let mut candidate = joined.clone();
+                            if reversed {
+                                candidate.extend(part.indexes.iter().rev());
+                            } else {
+                                candidate.extend(&part.indexes);
+                            }
+                            distance_mat.get_cost(&candidate)
+                        }
+                    };
+                    if best.is_none_or(|(best_cost, _, _)| cost < best_cost) {
+                        best = Some((cost, position, reversed));
+                    }
+                }
+            }
+            // `remaining` is not empty, so a best part was found.
+            let (_, position, reversed) = best.unwrap();
+            let part = remaining.remove(position);
+            inner_costs.remove(position);
+            if reversed {
+                joined.extend(part.indexes.iter().rev());
+            } else {
+                joined.extend(&part.indexes);
+            }
+        }
+        Route::new(joined)
+    }
+    /// Mutate the route like `mutate`, but draw the mutation from `rng`, e.g. a seeded
+    /// `StdRng` for reproducible runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `prob` - The probability with which the indexes will be changed.
+    /// * `rng` - The random number generator the mutation is drawn from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// let route = Route::new(vec![0, 1, 2, 3]);
+    /// assert_eq!(
+    ///     route.clone().mutate_with_rng(1.0, &mut StdRng::seed_from_u64(7)),
+    ///     route.mutate_with_rng(1.0, &mut StdRng::seed_from_u64(7))
+    /// );
+    /// ```
+    pub fn mutate_with_rng<R: Rng + ?Sized>(mut self, prob: f32, rng: &mut R) -> Self {
+        if let Some((put_before_idx, move_idx)) =
+            sample_relocation_with_rng(self.indexes.len(), prob, rng)
+        {
+            #[cfg(feature = "self-check")]
+            let parent = self.indexes.clone();
+            // The mutation is applied in place, so that no buffer has to be allocated.
+            move_elem(&mut self.indexes, put_before_idx, move_idx);
+            #[cfg(feature = "self-check")]
+            check_permutation("Route::mutate", &parent, &self.indexes);
+        }
+        self
+    }
+    /// Crossover this route with another route like `crossover`, but draw the subsequence
+    /// from `rng`, e.g. a seeded `StdRng` for reproducible runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The other individual you would like to crossover with this individual.
+    /// * `rng` - The random number generator the subsequence is drawn from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// let parent_a = Route::new(vec![0, 1, 2, 3]);
+    /// let parent_b = Route::new(vec![3, 1, 0, 2]);
+    /// assert_eq!(
+    ///     parent_a.crossover_with_rng(&parent_b, &mut StdRng::seed_from_u64(7)),
+    ///     parent_a.crossover_with_rng(&parent_b, &mut StdRng::seed_from_u64(7))
+    /// );
+    /// ```
+    pub fn crossover_with_rng<R: Rng + ?Sized>(&self, other: &Route, rng: &mut R) -> Self {
+        let child = ordered_crossover(
+            self,
+            other,
+            Subsequence::random_subsequence_with_rng(self.indexes.len(), rng),
+        );
+        #[cfg(feature = "self-check")]
+        check_permutation("Route::crossover", &self.indexes, &child.indexes);
+        child
+    }
+    /// Crossover this route with another route with a `CrossoverOperator` and draw all random
+    /// choices from `rng`. `CrossoverOperator::Ordered` is the same as `crossover_with_rng`.
+    /// The spatial and candidate crossovers need coordinates and a candidate list, use
+    /// `spatial_crossover_with_rng` and `candidate_crossover_with_rng` for them.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The other individual you would like to crossover with this individual.
+    /// * `operator` - The crossover operator.
+    /// * `rng` - The random number generator the random choices are drawn from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::route::{CrossoverOperator, Route};
+    ///
+    /// let parent_a = Route::new(vec![0, 1, 2, 3, 4, 5, 6, 7, 8]);
+    /// let parent_b = Route::new(vec![8, 2, 6, 7, 1, 5, 4, 0, 3]);
+    /// assert_eq!(
+    ///     parent_a.crossover_with_operator(&parent_b, CrossoverOperator::Cycle, &mut rand::thread_rng()),
+    ///     Route::new(vec![0, 2, 6, 3, 1, 5, 4, 7, 8])
+    /// );
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics for `CrossoverOperator::Spatial` and `CrossoverOperator::Candidate`, as there
+    /// are no coordinates or candidate list to apply them with.
+    ///
+    pub fn crossover_with_operator<R: Rng + ?Sized>(
+        &self,
+        other: &Route,
+        operator: CrossoverOperator,
+        rng: &mut R,
+    ) -> Self {
+        let child = match operator {
+            CrossoverOperator::Ordered => return self.crossover_with_rng(other, rng),
+            CrossoverOperator::Spatial => panic!("{}", SPATIAL_WITHOUT_COORDINATES),
+            CrossoverOperator::Candidate => panic!("{}", CANDIDATES_MISSING),
+            CrossoverOperator::PartiallyMapped => partially_mapped_crossover(
+                self,
+                other,
+                Subsequence::random_subsequence_with_rng(self.indexes.len(), rng),
+            ),
+            CrossoverOperator::Cycle => cycle_crossover(self, other),
+            CrossoverOperator::EdgeRecombination => edge_recombination_crossover(self, other, rng),
+        };
+        #[cfg(feature = "self-check")]
+        check_permutation("Route::crossover", &self.indexes, &child.indexes);
+        child
+    }
+    /// Crossover this route with another route by exchanging a geographically contiguous
+    /// segment, see `CrossoverOperator::Spatial`. A rectangle is drawn around a random node of
+    /// this route, with random side lengths of up to the extent of all points. The nodes in the
+    /// rectangle are visited in the order of this route and as one block, the other nodes keep
+    /// the order of `other`.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The other individual you would like to crossover with this individual.
+    /// * `coordinates` - The coordinates of the nodes, e.g. from `DistanceMat::coordinates`.
+    /// * `rng` - The random number generator the rectangle is drawn from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let distance_matrix = DistanceMat::from_coordinates(&[(0.0, 0.0), (4.0, 0.0), (4.0, 3.0), (0.0, 3.0)]);
+    /// let parent_a = Route::new(vec![0, 1, 2, 3]);
+    /// let parent_b = Route::new(vec![2, 0, 3, 1]);
+    /// let child = parent_a.spatial_crossover_with_rng(
+    ///     &parent_b, distance_matrix.coordinates().unwrap(), &mut rand::thread_rng()
+    /// );
+    /// assert_eq!(child.get_n_nodes(), 4);
+    /// ```
+    pub fn spatial_crossover_with_rng<R: Rng + ?Sized>(
+        &self,
+        other: &Route,
+        coordinates: &[(f64, f64)],
+        rng: &mut R,
+    ) -> Self {
+        let center = match self
+            .indexes
+            .choose(rng)
+            .and_then(|node| coordinates.get(*node))
+        {
+            Some(center) => *center,
+            None => return self.clone(),
+        };
+        let (lower, upper) = coordinates.iter().fold(
+            (
+                (f64::INFINITY, f64::INFINITY),
+                (f64::NEG_INFINITY, f64::NEG_INFINITY),
+            ),
+            |(lower, upper), (x, y)| {
+                (
+                    (lower.0.min(*x), lower.1.min(*y)),
+                    (upper.0.max(*x), upper.1.max(*y)),
+                )
+            },
+        );
+        let half_width = rng.gen::<f64>() * (upper.0 - lower.0) / 2.0;
+        let half_height = rng.gen::<f64>() * (upper.1 - lower.1) / 2.0;
+        let child = spatial_segment_crossover(
+            self,
+            other,
+            coordinates,
+            (center.0 - half_width, center.1 - half_height),
+            (center.0 + half_width, center.1 + half_height),
+        );
+        #[cfg(feature = "self-check")]
+        check_permutation("Route::crossover", &self.indexes, &child.indexes);
+        child
+    }
+    /// Crossover this route with another route without introducing long edges, see
+    /// `CrossoverOperator::Candidate`. Starting at a random node, the child moves on to the
+    /// successor of its current node in one of the parents, preferring successors that are
+    /// near neighbors. If both successors are visited already, it moves on to the nearest
+    /// unvisited neighbor, and only if all neighbors are visited as well to the next unvisited
+    /// node in the order of `other`. So all edges of the child are edges of a parent or
+    /// candidate edges, except for these rare jumps.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The other individual you would like to crossover with this individual.
+    /// * `candidates` - The near neighbors of every node, e.g. from `DistanceMat::candidate_list`.
+    /// * `rng` - The random number generator the random choices are drawn from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let distance_matrix = DistanceMat::from_coordinates(&[(0.0, 0.0), (4.0, 0.0), (4.0, 3.0), (0.0, 3.0)])
+    ///     .with_candidate_list(2);
+    /// let parent_a = Route::new(vec![0, 1, 2, 3]);
+    /// let parent_b = Route::new(vec![2, 0, 3, 1]);
+    /// let child = parent_a.candidate_crossover_with_rng(
+    ///     &parent_b, distance_matrix.candidate_list().unwrap(), &mut rand::thread_rng()
+    /// );
+    /// assert_eq!(child.get_n_nodes(), 4);
+    /// ```
+    pub fn candidate_crossover_with_rng<R: Rng + ?Sized>(
+        &self,
+        other: &Route,
+        candidates: &CandidateList,
+        rng: &mut R,
+    ) -> Self {
+        let n_nodes = self.indexes.len();
+        if n_nodes < 3 || other.indexes.len() != n_nodes {
+            return self.clone();
+        }
+        let successors = |route: &Route| {
+            let mut successors = vec![0; n_nodes];
+            for (idx, node) in route.indexes.iter().enumerate() {
+                successors[*node] = route.indexes[(idx + 1) % n_nodes];
+            }
+            successors
+        };
+        let (successors_a, successors_b) = (successors(self), successors(other));
+        let mut visited = vec![false; n_nodes];
+        let mut indexes = Vec::with_capacity(n_nodes);
+        let mut current = self.indexes[get_random_elem_from_range_with_rng(0..n_nodes, rng)];
+        // Where to continue the scan of `other` for an unvisited node.
+        let mut fallback_idx = 0;
+        loop {
+            visited[current] = true;
+            indexes.push(current);
+            if indexes.len() == n_nodes {
+                break;
+            }
+            let mut inherited = [successors_a[current], successors_b[current]];
+            if rng.gen_bool(0.5) {
+                inherited.swap(0, 1);
+            }
+            let mut unvisited = inherited.into_iter().filter(|node| !visited[*node]);
+            current = match unvisited
+                .clone()
+                .find(|node| candidates.is_candidate(current, *node))
+                .or_else(|| unvisited.next())
+            {
+                Some(node) => node,
+                None => match candidates
+                    .neighbors(current)
+                    .iter()
+                    .find(|node| !visited[**node])
+                {
+                    Some(node) => *node,
+                    None => {
+                        while visited[other.indexes[fallback_idx]] {
+                            fallback_idx += 1;
+                        }
+                        other.indexes[fallback_idx]
+                    }
+                },
+            };
+        }
+        let child = Route::new(indexes);
+        #[cfg(feature = "self-check")]
+        check_permutation("Route::crossover", &self.indexes, &child.indexes);
+        child
+    }
+    /// Mutate the route with a 2-opt move that connects a random node to one of its near
+    /// neighbors, see `MutationOperator::Candidate`: the stretch between the successor of the
+    /// node and the neighbor is reversed, so that the neighbor follows the node.
+    ///
+    /// # Arguments
+    ///
+    /// * `prob` - The probability with which the indexes will be changed.
+    /// * `candidates` - The near neighbors of every node, e.g. from `DistanceMat::candidate_list`.
+    /// * `rng` - The random number generator the mutation is drawn from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let distance_matrix = DistanceMat::from_coordinates(&[(0.0, 0.0), (4.0, 0.0), (4.0, 3.0), (0.0, 3.0)])
+    ///     .with_candidate_list(1);
+    /// let route = Route::new(vec![0, 2, 1, 3]);
+    /// let mutated = route.candidate_mutate_with_rng(
+    ///     1.0, distance_matrix.candidate_list().unwrap(), &mut rand::thread_rng()
+    /// );
+    /// assert_eq!(mutated.get_n_nodes(), 4);
+    /// ```
+    pub fn candidate_mutate_with_rng<R: Rng + ?Sized>(
+        mut self,
+        prob: f32,
+        candidates: &CandidateList,
+        rng: &mut R,
+    ) -> Self {
+        let n_nodes = self.indexes.len();
+        // With probabilty (1-prop) don't do any mutation.
+        if get_random_elem_from_range_with_rng(0.0..1.0, rng) > prob || n_nodes < 4 {
+            return self;
+        }
+        let node_idx = get_random_elem_from_range_with_rng(0..n_nodes, rng);
+        let node = self.indexes[node_idx];
+        let successor = self.indexes[(node_idx + 1) % n_nodes];
+        let neighbor = match candidates
+            .neighbors(node)
+            .iter()
+            .filter(|neighbor| **neighbor != successor)
+            .collect::<Vec<&usize>>()
+            .choose(rng)
+        {
+            Some(neighbor) => **neighbor,
+            None => return self,
+        };
+        let neighbor_idx = self
+            .indexes
+            .iter()
+            .position(|other| *other == neighbor)
+            .unwrap();
+        #[cfg(feature = "self-check")]
+        let parent = self.indexes.clone();
+        if node_idx < neighbor_idx {
+            self.indexes[(node_idx + 1)..=neighbor_idx].reverse();
+        } else {
+            self.indexes[(neighbor_idx + 1)..=node_idx].reverse();
+        }
+        #[cfg(feature = "self-check")]
+        check_permutation("Route::mutate", &parent, &self.indexes);
+        self
+    }
+    /// Mutate the route with a `MutationOperator` and draw the mutation from `rng`.
+    /// `MutationOperator::Insertion` is the same as `mutate_with_rng`.
+    ///
+    /// # Arguments
+    ///
+    /// * `prob` - The probability with which the indexes will be changed.
+    /// * `operator` - The mutation operator.
+    /// * `rng` - The random number generator the mutation is drawn from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::route::{MutationOperator, Route};
+    ///
+    /// let route = Route::new(vec![0, 1, 2, 3, 4]);
+    /// let mutated = route.clone().mutate_with_operator(1.0, MutationOperator::Swap, &mut rand::thread_rng());
+    /// // Exactly two nodes changed their positions.
+    /// assert_eq!(route.indexes.iter().zip(&mutated.indexes).filter(|(a, b)| a != b).count(), 2);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics for `MutationOperator::Candidate`, as there is no candidate list to apply it with,
+    /// use `candidate_mutate_with_rng` for it.
+    ///
+    pub fn mutate_with_operator<R: Rng + ?Sized>(
+        mut self,
+        prob: f32,
+        operator: MutationOperator,
+        rng: &mut R,
+    ) -> Self {
+        assert!(
+            operator != MutationOperator::Candidate,
+            "{}",
+            CANDIDATES_MISSING
+        );
+        let n_nodes = self.indexes.len();
+        if operator == MutationOperator::Insertion {
+            return self.mutate_with_rng(prob, rng);
+        }
+        // With probabilty (1-prop) don't do any mutation.
+        if get_random_elem_from_range_with_rng(0.0..1.0, rng) > prob || n_nodes < 2 {
+            return self;
+        }
+        // Two distinct positions, `start` before `end`.
+        let first = get_random_elem_from_range_with_rng(0..n_nodes, rng);
+        let second = get_random_elem_from_range_with_rng(0..(n_nodes - 1), rng);
+        let second = if second >= first { second + 1 } else { second };
+        let (start, end) = (first.min(second), first.max(second));
+        #[cfg(feature = "self-check")]
+        let parent = self.indexes.clone();
+        match operator {
+            MutationOperator::Insertion | MutationOperator::Candidate => unreachable!(),
+            MutationOperator::Swap => self.indexes.swap(start, end),
+            MutationOperator::Inversion => self.indexes[start..=end].reverse(),
+            MutationOperator::Scramble => self.indexes[start..=end].shuffle(rng),
+        }
+        #[cfg(feature = "self-check")]
+        check_permutation("Route::mutate", &parent, &self.indexes);
+        self
+    }
+    /// Mutate the route like `mutate`, but update its known cost under `distance_mat` as well.
+    /// Only the legs that are changed by the mutation are evaluated if the objective of the
+    /// distance matrix allows it, otherwise the whole route is evaluated again.
+    ///
+    /// # Arguments
+    ///
+    /// * `prob` - The probability with which the indexes will be changed.
+    /// * `distance_mat` - The distance matrix the cost is computed on.
+    /// * `cost` - The cost of the route before the mutation, e.g. `distance_mat.get_cost`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let route = Route::new(vec![0, 1, 2]);
+    /// let cost = distance_matrix.get_cost(&route.indexes);
+    /// let (mutated_route, mutated_cost) = route.mutate_with_cost(1.0, &distance_matrix, cost);
+    /// assert_eq!(mutated_cost, distance_matrix.get_cost(&mutated_route.indexes));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn mutate_with_cost(
+        mut self,
+        prob: f32,
+        distance_mat: &DistanceMat,
+        cost: f64,
+    ) -> (Self, f64) {
+        match sample_relocation(self.indexes.len(), prob) {
+            Some((put_before_idx, move_idx)) => {
+                let delta =
+                    distance_mat.get_relocation_delta(&self.indexes, put_before_idx, move_idx);
+                move_elem(&mut self.indexes, put_before_idx, move_idx);
+                let cost = match delta {
+                    Some(delta) => cost + delta,
+                    None => distance_mat.get_cost(&self.indexes),
+                };
+                (self, cost)
+            }
+            None => (self, cost),
+        }
+    }
+    /// Improve the route with 2-opt until it is a local optimum: a stretch of the route is
+    /// reversed whenever that lowers its cost (first improvement), and the passes over all
+    /// stretches are repeated until none of them improves the route any more. On symmetric
+    /// matrices every move is evaluated in O(1) with `DistanceMat::get_two_opt_delta`, otherwise
+    /// the reversed route is evaluated as a whole.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the cost is computed on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 9.0, 1.0],
+    ///     vec![1.0, 0.0, 1.0, 9.0],
+    ///     vec![9.0, 1.0, 0.0, 1.0],
+    ///     vec![1.0, 9.0, 1.0, 0.0],
+    /// ]);
+    /// let route = Route::new(vec![0, 2, 1, 3]).two_opt(&distance_matrix);
+    /// assert_eq!(distance_matrix.get_cost(&route.indexes), 4.0);
+    /// ```
+    pub fn two_opt(&self, distance_mat: &DistanceMat) -> Route {
+        // The sequential order of the default policy never draws from the rng.
+        self.two_opt_with_policy(
+            distance_mat,
+            LocalSearchPolicy::default(),
+            &mut StepRng::new(0, 0),
+        )
+    }
+    /// Improve the route with 2-opt until it is a local optimum like `Route::two_opt`, but
+    /// choose the pivoting rule and the order in which the stretches are scanned. With a random
+    /// order, the first nodes of the stretches are shuffled with `rng` in every pass.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the cost is computed on.
+    /// * `policy` - How the neighborhood is scanned and which improving move is applied.
+    /// * `rng` - The random number generator the order of the scan is drawn from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::route::{LocalSearchPolicy, NeighborhoodOrder, PivotingRule, Route};
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 9.0, 1.0],
+    ///     vec![1.0, 0.0, 1.0, 9.0],
+    ///     vec![9.0, 1.0, 0.0, 1.0],
+    ///     vec![1.0, 9.0, 1.0, 0.0],
+    /// ]);
+    /// let policy = LocalSearchPolicy::new(PivotingRule::BestImprovement, NeighborhoodOrder::Random);
+    /// let route = Route::new(vec![0, 2, 1, 3]).two_opt_with_policy(
+    ///     &distance_matrix,
+    ///     policy,
+    ///     &mut rand::thread_rng(),
+    /// );
+    /// assert_eq!(distance_matrix.get_cost(&route.indexes), 4.0);
+    /// ```
+    pub fn two_opt_with_policy<R: Rng + ?Sized>(
+        &self,
+        distance_mat: &DistanceMat,
+        policy: LocalSearchPolicy,
+        rng: &mut R,
+    ) -> Route {
+        let mut indexes = self.indexes.clone();
+        let n_nodes = indexes.len();
+        if n_nodes < 4 {
+            return Route::new(indexes);
+        }
+        let mut cost = distance_mat.get_cost(&indexes);
+        let mut first_idxs = (0..(n_nodes - 2)).collect::<Vec<usize>>();
+        let mut improved = true;
+        while improved {
+            improved = false;
+            if policy.ordering == NeighborhoodOrder::Random {
+                first_idxs.shuffle(rng);
+            }
+            // The best move of the pass as `(delta, first_idx, last_idx)`.
+            let mut best_move: Option<(f64, usize, usize)> = None;
+            for &first_idx in &first_idxs {
+                for last_idx in (first_idx + 2)..n_nodes {
+                    // Reversing everything but the first node gives the same round-trip.
+                    if first_idx == 0 && last_idx == n_nodes - 1 {
+                        continue;
+                    }
+                    let delta = match distance_mat.get_two_opt_delta(&indexes, first_idx, last_idx)
+                    {
+                        Some(delta) => delta,
+                        None => {
+                            indexes[(first_idx + 1)..=last_idx].reverse();
+                            let delta = distance_mat.get_cost(&indexes) - cost;
+                            indexes[(first_idx + 1)..=last_idx].reverse();
+                            delta
+                        }
+                    };
+                    // Ignore improvements that are only rounding errors, they could cycle.
+                    if delta < -1e-9 * cost.abs().max(1.0) {
+                        match policy.pivoting {
+                            PivotingRule::FirstImprovement => {
+                                indexes[(first_idx + 1)..=last_idx].reverse();
+                                cost += delta;
+                                improved = true;
+                            }
+                            PivotingRule::BestImprovement => {
+                                if best_move.is_none_or(|(best_delta, _, _)| delta < best_delta) {
+                                    best_move = Some((delta, first_idx, last_idx));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            if let Some((delta, first_idx, last_idx)) = best_move {
+                indexes[(first_idx + 1)..=last_idx].reverse();
+                cost += delta;
+                improved = true;
+            }
+        }
+        Route::new(indexes)
+    }
+    /// Improve the route with Or-opt until it is a local optimum: a chain of one to three
+    /// consecutive nodes is moved to another position of the route whenever that lowers its
+    /// cost (first improvement), until no move improves the route any more. If the route is a
+    /// round-trip, chains that wrap around its end, e.g. its last and its first node, are moved
+    /// as well, and the improved route starts at the same node. Every move is evaluated in O(1)
+    /// with `DistanceMat::get_or_opt_delta` if the objective allows it, otherwise the moved
+    /// route is evaluated as a whole. If `DistanceMat::is_metric` verified that the matrix is a
+    /// metric, single nodes whose removal doesn't lower the cost aren't moved at all, as no
+    /// insertion can lower it.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the cost is computed on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 9.0, 1.0],
+    ///     vec![1.0, 0.0, 1.0, 9.0],
+    ///     vec![9.0, 1.0, 0.0, 1.0],
+    ///     vec![1.0, 9.0, 1.0, 0.0],
+    /// ]);
+    /// let route = Route::new(vec![0, 2, 1, 3]).or_opt(&distance_matrix);
+    /// assert_eq!(distance_matrix.get_cost(&route.indexes), 4.0);
+    /// ```
+    pub fn or_opt(&self, distance_mat: &DistanceMat) -> Route {
+        let mut indexes = self.indexes.clone();
+        let n_nodes = indexes.len();
+        if n_nodes < 4 {
+            return Route::new(indexes);
+        }
+        let mut cost = distance_mat.get_cost(&indexes);
+        let is_round_trip = distance_mat.is_round_trip();
+        let mut candidate = Vec::with_capacity(n_nodes);
+        let mut improved = true;
+        while improved {
+            improved = false;
+            for chain_length in 1..=3.min(n_nodes - 2) {
+                let last_chain_start = if is_round_trip {
+                    n_nodes - 1
+                } else {
+                    n_nodes - chain_length
+                };
+                for chain_start in 0..=last_chain_start {
+                    if chain_length == 1 && distance_mat.can_skip_relocation(&indexes, chain_start)
+                    {
+                        continue;
+                    }
+                    // A chain that wraps around the end is moved to the front of the route,
+                    // which doesn't change the cost of a round-trip.
+                    let rotation = if chain_start + chain_length > n_nodes {
+                        indexes.rotate_left(chain_start);
+                        chain_start
+                    } else {
+                        0
+                    };
+                    let chain_start = chain_start - rotation;
+                    let chain_end = chain_start + chain_length;
+                    for insert_idx in 0..=(n_nodes - chain_length) {
+                        if insert_idx == chain_start {
+                            continue;
+                        }
+                        // Insert the chain at `insert_idx` of the route without the chain.
+                        let rest = || indexes[..chain_start].iter().chain(&indexes[chain_end..]);
+                        let moved = |candidate: &mut Vec<usize>| {
+                            candidate.clear();
+                            candidate.extend(
+                                rest()
+                                    .take(insert_idx)
+                                    .chain(&indexes[chain_start..chain_end])
+                                    .chain(rest().skip(insert_idx))
+                                    .copied(),
+                            );
+                        };
+                        let delta = match distance_mat.get_or_opt_delta(
+                            &indexes,
+                            chain_start,
+                            chain_length,
+                            insert_idx,
+                        ) {
+                            Some(delta) => delta,
+                            None => {
+                                moved(&mut candidate);
+                                distance_mat.get_cost(&candidate) - cost
+                            }
+                        };
+                        // Ignore improvements that are only rounding errors, they could cycle.
+                        if delta < -1e-9 * cost.abs().max(1.0) {
+                            moved(&mut candidate);
+                            indexes.clone_from(&candidate);
+                            cost += delta;
+                            improved = true;
+                        }
+                    }
+                    indexes.rotate_right(rotation);
+                }
+            }
+        }
+        if is_round_trip {
+            if let Some(start) = indexes.iter().position(|node| *node == self.indexes[0]) {
+                indexes.rotate_left(start);
+            }
+        }
+        Route::new(indexes)
+    }
+    /// Improve the route with 3-opt until it is a local optimum: three legs of the route are
+    /// removed and the two segments between them are reconnected by one of the `ThreeOptMove`s
+    /// whenever that lowers its cost (first improvement), until no move improves the route any
+    /// more. A pass takes O(n^3) moves, so 3-opt is best used on few routes of small and medium
+    /// instances, e.g. after 2-opt. Every move is evaluated in O(1) with
+    /// `DistanceMat::get_three_opt_delta` if the objective allows it, otherwise the moved route
+    /// is evaluated as a whole.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the cost is computed on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 9.0, 1.0],
+    ///     vec![1.0, 0.0, 1.0, 9.0],
+    ///     vec![9.0, 1.0, 0.0, 1.0],
+    ///     vec![1.0, 9.0, 1.0, 0.0],
+    /// ]);
+    /// let route = Route::new(vec![0, 2, 1, 3]).three_opt(&distance_matrix);
+    /// assert_eq!(distance_matrix.get_cost(&route.indexes), 4.0);
+    /// ```
+    pub fn three_opt(&self, distance_mat: &DistanceMat) -> Route {
+        let mut indexes = self.indexes.clone();
+        let n_nodes = indexes.len();
+        if n_nodes < 4 {
+            return Route::new(indexes);
+        }
+        let mut cost = distance_mat.get_cost(&indexes);
+        let mut candidate = Vec::with_capacity(n_nodes);
+        let mut improved = true;
+        while improved {
+            improved = false;
+            for first_idx in 0..(n_nodes - 2) {
+                for second_idx in (first_idx + 1)..(n_nodes - 1) {
+                    for third_idx in (second_idx + 1)..n_nodes {
+                        for reconnection in ThreeOptMove::ALL {
+                            let delta = match distance_mat.get_three_opt_delta(
+                                &indexes,
+                                first_idx,
+                                second_idx,
+                                third_idx,
+                                reconnection,
+                            ) {
+                                Some(delta) => delta,
+                                None => {
+                                    candidate.clone_from(&indexes);
+                                    reconnection.apply(
+                                        &mut candidate,
+                                        first_idx,
+                                        second_idx,
+                                        third_idx,
+                                    );
+                                    distance_mat.get_cost(&candidate) - cost
+                                }
+                            };
+                            // Ignore improvements that are only rounding errors, they could cycle.
+                            if delta < -1e-9 * cost.abs().max(1.0) {
+                                reconnection.apply(&mut indexes, first_idx, second_idx, third_idx);
+                                cost += delta;
+                                improved = true;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Route::new(indexes)
+    }
+    /// Compute how much distance the individual implies with its order of nodes
+    /// and the distance matrix. The cost is computed with the objective of the
+    /// distance matrix, which is the round-trip length by default.
     ///
     /// # Arguments
     ///
-    /// * `indexes` - The order in which the nodes are visited in the Traveling Salesman Problem.
+    /// * `distance_matrix` - Distance Matrix that determines the length of the proposed
+    ///   route
     ///
     /// # Examples
     ///
     /// ```
     /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
     ///
     /// let my_individual = Route::from(Route::new(vec![0,1,2]));
+    /// println!("Fitness of your individual: {}", my_individual.fitness(
+    ///     &DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]))
+    /// )
     /// ```
-    pub fn new(indexes: Vec<usize>) -> Self {
-        Self { indexes }
+    ///
+    pub fn fitness(&self, distance_mat: &DistanceMat) -> f64 {
+        let fitness = -distance_mat.get_cached_cost(&self.indexes[..]);
+        #[cfg(feature = "self-check")]
+        check_fitness("Route::fitness", &self.indexes, fitness);
+        fitness
     }
-    /// Get the number of nodes for this route.
+}
+/// How `breed` creates the offspring of a pair of parents.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BreedConfig {
+    /// How many offspring are created.
+    pub n_offspring: usize,
+    /// The probability that an offspring is a crossover of the parents rather than a copy.
+    pub crossover_rate: f64,
+    /// The crossover operator.
+    pub crossover_operator: CrossoverOperator,
+    /// The probability that an offspring is mutated.
+    pub mutation_rate: f32,
+    /// The mutation operator.
+    pub mutation_operator: MutationOperator,
+}
+impl Default for BreedConfig {
+    fn default() -> Self {
+        BreedConfig {
+            n_offspring: 2,
+            crossover_rate: 1.0,
+            crossover_operator: CrossoverOperator::Ordered,
+            mutation_rate: 0.5,
+            mutation_operator: MutationOperator::Insertion,
+        }
+    }
+}
+impl BreedConfig {
+    /// Create a configuration with the operators of `Routes::evolve` and two offspring.
     ///
     /// # Examples
     ///
     /// ```
-    /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_tsp::route::BreedConfig;
     ///
-    /// let three_node_route = Route::from(Route::new(vec![0,1,2]));
-    /// println!("This route has {} nodes", three_node_route.get_n_nodes());
+    /// let config = BreedConfig::new();
+    /// assert_eq!(config.n_offspring, 2);
     /// ```
-    pub fn get_n_nodes(&self) -> usize {
-        self.indexes.len()
+    pub fn new() -> Self {
+        BreedConfig::default()
+    }
+    /// Set how many offspring are created.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_offspring` - How many offspring are created.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::route::BreedConfig;
+    ///
+    /// let config = BreedConfig::new().with_n_offspring(5);
+    /// ```
+    pub fn with_n_offspring(mut self, n_offspring: usize) -> Self {
+        self.n_offspring = n_offspring;
+        self
+    }
+    /// Set the probability that an offspring is a crossover of the parents.
+    ///
+    /// # Arguments
+    ///
+    /// * `crossover_rate` - The probability that an offspring is a crossover of the parents.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::route::BreedConfig;
+    ///
+    /// let config = BreedConfig::new().with_crossover_rate(0.8);
+    /// ```
+    pub fn with_crossover_rate(mut self, crossover_rate: f64) -> Self {
+        self.crossover_rate = crossover_rate;
+        self
+    }
+    /// Set the crossover operator.
+    ///
+    /// # Arguments
+    ///
+    /// * `crossover_operator` - The crossover operator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::route::{BreedConfig, CrossoverOperator};
+    ///
+    /// let config = BreedConfig::new().with_crossover_operator(CrossoverOperator::Cycle);
+    /// ```
+    pub fn with_crossover_operator(mut self, crossover_operator: CrossoverOperator) -> Self {
+        self.crossover_operator = crossover_operator;
+        self
+    }
+    /// Set the probability that an offspring is mutated.
+    ///
+    /// # Arguments
+    ///
+    /// * `mutation_rate` - The probability that an offspring is mutated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::route::BreedConfig;
+    ///
+    /// let config = BreedConfig::new().with_mutation_rate(0.1);
+    /// ```
+    pub fn with_mutation_rate(mut self, mutation_rate: f32) -> Self {
+        self.mutation_rate = mutation_rate;
+        self
+    }
+    /// Set the mutation operator.
+    ///
+    /// # Arguments
+    ///
+    /// * `mutation_operator` - The mutation operator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::route::{BreedConfig, MutationOperator};
+    ///
+    /// let config = BreedConfig::new().with_mutation_operator(MutationOperator::Inversion);
+    /// ```
+    pub fn with_mutation_operator(mut self, mutation_operator: MutationOperator) -> Self {
+        self.mutation_operator = mutation_operator;
+        self
+    }
+}
+
+/// Breed the offspring of a single pair of parents with the operators of `config`, e.g. for a
+/// steady-state loop that keeps its own population. The parents take turns: every other
+/// offspring is a crossover of `parent_b` into `parent_a` and the others of `parent_a` into
+/// `parent_b`. An offspring that isn't crossed is a copy of its first parent, and every offspring
+/// may be mutated.
+///
+/// # Arguments
+///
+/// * `parent_a` - The first parent.
+/// * `parent_b` - The second parent.
+/// * `config` - How many offspring are created and with which operators.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::route::{breed, BreedConfig, CrossoverOperator, Route};
+///
+/// let offspring = breed(
+///     &Route::new(vec![0, 1, 2, 3, 4]),
+///     &Route::new(vec![4, 2, 0, 3, 1]),
+///     &BreedConfig::new()
+///         .with_n_offspring(4)
+///         .with_crossover_operator(CrossoverOperator::PartiallyMapped),
+/// );
+/// assert_eq!(offspring.len(), 4);
+/// ```
+///
+/// # Panics
+///
+/// Panics if an operator needs coordinates or a candidate list, e.g. `CrossoverOperator::Spatial`.
+///
+#[cfg(feature = "std")]
+pub fn breed(parent_a: &Route, parent_b: &Route, config: &BreedConfig) -> Vec<Route> {
+    breed_with_rng(parent_a, parent_b, config, &mut rand::thread_rng())
+}
+
+/// Breed the offspring of a pair of parents like `breed`, but draw all random choices from
+/// `rng`.
+///
+/// # Arguments
+///
+/// * `parent_a` - The first parent.
+/// * `parent_b` - The second parent.
+/// * `config` - How many offspring are created and with which operators.
+/// * `rng` - The random number generator the crossovers and mutations are drawn from.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::route::{breed_with_rng, BreedConfig, Route};
+/// use rand::rngs::StdRng;
+/// use rand::SeedableRng;
+///
+/// let parent_a = Route::new(vec![0, 1, 2, 3, 4]);
+/// let parent_b = Route::new(vec![4, 2, 0, 3, 1]);
+/// let run = |seed| {
+///     breed_with_rng(&parent_a, &parent_b, &BreedConfig::new(), &mut StdRng::seed_from_u64(seed))
+/// };
+/// assert_eq!(run(3), run(3));
+/// ```
+///
+/// # Panics
+///
+/// Panics if an operator needs coordinates or a candidate list, e.g. `CrossoverOperator::Spatial`.
+///
+pub fn breed_with_rng<R: Rng + ?Sized>(
+    parent_a: &Route,
+    parent_b: &Route,
+    config: &BreedConfig,
+    rng: &mut R,
+) -> Vec<Route> {
+    assert!(
+        config.crossover_operator != CrossoverOperator::Spatial,
+        "{}",
+        SPATIAL_WITHOUT_COORDINATES
+    );
+    assert!(
+        config.crossover_operator != CrossoverOperator::Candidate
+            && config.mutation_operator != MutationOperator::Candidate,
+        "{}",
+        CANDIDATES_MISSING
+    );
+    (0..config.n_offspring)
+        .map(|offspring_idx| {
+            let (first, second) = if offspring_idx % 2 == 0 {
+                (parent_a, parent_b)
+            } else {
+                (parent_b, parent_a)
+            };
+            let child = if rng.gen::<f64>() < config.crossover_rate {
+                first.crossover_with_operator(second, config.crossover_operator, rng)
+            } else {
+                first.clone()
+            };
+            child.mutate_with_operator(config.mutation_rate, config.mutation_operator, rng)
+        })
+        .collect()
+}
+
+/// The canonical tour of a route (see `Route::canonical_tour`) as a view of its nodes, so that
+/// routes can be ordered by their canonical tours without allocating them, e.g. to break the
+/// ties of a selection. Only the start and the direction of the canonical tour are stored.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CanonicalOrder<'a> {
+    /// The nodes of the route.
+    nodes: &'a [usize],
+    /// The position of the smallest node, at which the canonical tour starts.
+    start: usize,
+    /// Whether the canonical tour runs through the route backwards.
+    backward: bool,
+}
+impl<'a> CanonicalOrder<'a> {
+    /// Find the start and the direction of the canonical tour of a route.
+    ///
+    /// # Arguments
+    ///
+    /// * `nodes` - The nodes of the route.
+    ///
+    pub(crate) fn new(nodes: &'a [usize]) -> Self {
+        let start = nodes
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, node)| **node)
+            .map_or(0, |(start, _)| start);
+        let forward = CanonicalOrder {
+            nodes,
+            start,
+            backward: false,
+        };
+        let backward = CanonicalOrder {
+            backward: true,
+            ..forward
+        };
+        // Of the two directions the lexicographically smaller one is canonical.
+        if backward.iter().lt(forward.iter()) {
+            backward
+        } else {
+            forward
+        }
+    }
+    /// Iterate over the nodes of the canonical tour.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = usize> + 'a {
+        let (nodes, start, backward) = (self.nodes, self.start, self.backward);
+        let n_nodes = nodes.len();
+        (0..n_nodes).map(move |offset| {
+            if backward {
+                nodes[(start + n_nodes - offset) % n_nodes]
+            } else {
+                nodes[(start + offset) % n_nodes]
+            }
+        })
     }
 }
+impl PartialEq for CanonicalOrder<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+impl Eq for CanonicalOrder<'_> {}
+impl PartialOrd for CanonicalOrder<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for CanonicalOrder<'_> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+/// Sample the mutation of a route with `n_nodes` nodes as the pair `(put_before_idx, move_idx)`,
+/// e.g. the node at `move_idx` is put in front of the node at `put_before_idx`. Returns `None`
+/// if no mutation should be applied, which happens with probability (1-prob) and for routes with
+/// less than three nodes.
+///
+/// # Arguments
+///
+/// * `n_nodes` - The number of nodes of the route that is mutated.
+/// * `prob` - The probability with which the route will be changed.
+///
+#[cfg(feature = "std")]
+pub(crate) fn sample_relocation(n_nodes: usize, prob: f32) -> Option<(usize, usize)> {
+    sample_relocation_with_rng(n_nodes, prob, &mut rand::thread_rng())
+}
+/// Sample the mutation of a route like `sample_relocation`, but draw it from `rng`.
+///
+/// # Arguments
+///
+/// * `n_nodes` - The number of nodes of the route that is mutated.
+/// * `prob` - The probability with which the route will be changed.
+/// * `rng` - The random number generator the mutation is drawn from.
+///
+pub(crate) fn sample_relocation_with_rng<R: Rng + ?Sized>(
+    n_nodes: usize,
+    prob: f32,
+    rng: &mut R,
+) -> Option<(usize, usize)> {
+    // With probabilty (1-prop) don't do any mutation.
+    if get_random_elem_from_range_with_rng(0.0..1.0, rng) > prob || n_nodes < 3 {
+        return None;
+    }
+    // To do so first sample an element to put another element in front of.
+    let put_before_idx: usize = get_random_elem_from_range_with_rng(0..(n_nodes - 1), rng);
+    // Sample the element that should be put before `put_before_idx`. Should neither be
+    // `put_before_idx` itself nor its predecessor, as moving those doesn't change the route.
+    let first_excluded = max(put_before_idx, 1) - 1;
+    let move_idx = if n_nodes > 3 {
+        let sample = get_random_elem_from_range_with_rng(0..(n_nodes - 3), rng);
+        if sample < first_excluded {
+            sample
+        } else {
+            sample + 2
+        }
+    } else {
+        (put_before_idx + 1) % n_nodes
+    };
+    Some((put_before_idx, move_idx))
+}
+
+#[cfg(feature = "std")]
 impl<'a> Individual<'a> for Route {
     // The Distance matrix is needed by the individuals to compute their fitness on.
     type IndividualCost = DistanceMat;
-    /// Randomly changes the order of two nodes in the route
+    /// Randomly changes the order of two nodes in the route. Routes with less than three nodes
+    /// are returned unchanged, because all of their orders describe the same round-trip.
     ///
     /// # Arguments
     ///
@@ -70,31 +1596,7 @@ impl<'a> Individual<'a> for Route {
     /// let my_mutated_indiviual =  my_individual.mutate(1.0);
     /// ```
     fn mutate(self, prob: f32) -> Self {
-        Route {
-            indexes: if get_random_elem_from_range(0.0..1.0) > prob {
-                // With probabilty (1-prop) don't do any mutation.
-                self.indexes
-            } else {
-                // else mutation is applied.
-                // To do so first sample an element to put another element in front of.
-                let put_before_idx: usize = get_random_elem_from_range(0..(self.indexes.len() - 1));
-                change_order(
-                    &self.indexes,
-                    put_before_idx,
-                    // Sample the element that should be put before `put_before_idx`. Should not be
-                    // the `put_before_idx` itself.
-                    *remove_elem(
-                        remove_elem(
-                            (0..(self.indexes.len() - 1)).collect::<Vec<usize>>(),
-                            put_before_idx,
-                        ),
-                        max(put_before_idx, 1) - 1,
-                    )
-                    .choose(&mut rand::thread_rng())
-                    .unwrap_or(&((put_before_idx + 1) % self.indexes.len())),
-                )
-            },
-        }
+        self.mutate_with_rng(prob, &mut rand::thread_rng())
     }
     /// Crossover this invidual with another individual to create a new individual. Currently
     /// uses the `ordered_crossover` algorithm.
@@ -115,44 +1617,25 @@ impl<'a> Individual<'a> for Route {
     /// );
     /// ```
     fn crossover(&self, other: &Route) -> Self {
-        ordered_crossover(
-            self,
-            other,
-            Subsequence::random_subsequence(self.indexes.len()),
-        )
+        self.crossover_with_rng(other, &mut rand::thread_rng())
     }
-    /// Compute how much distance the individual implies with its order of nodes
-    /// and the distance matrix.
-    ///
-    /// # Arguments
-    ///
-    /// * `distance_matrix` - Distance Matrix that determines the length of the proposed
-    /// route
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// use genetic_algorithm_tsp::route::Route;
-    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
-    /// use  genetic_algorithm_traits::Individual;
-    ///
-    /// let my_individual = Route::from(Route::new(vec![0,1,2]));
-    /// println!("Fitness of your individual: {}", my_individual.fitness(
-    ///     &DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]))
-    /// )
-    /// ```
-    ///
+    /// Compute the fitness of the route, see `Route::fitness`.
     fn fitness(&self, distance_mat: &DistanceMat) -> f64 {
-        -distance_mat.get_distance(&self.indexes[..])
+        Route::fitness(self, distance_mat)
     }
 }
 
 #[cfg(test)]
+#[allow(duplicate_macro_attributes, clippy::useless_conversion)]
 mod tests {
     use super::*;
     mod test_route {
         use super::*;
+        #[cfg(feature = "std")]
         use crate::test_utils::valid_permutation;
+        use crate::utils::random_permutation_with_rng;
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
         #[test]
         fn test_format() {
             let route_to_print = Route::new(vec![1, 2, 3, 4]);
@@ -170,6 +1653,39 @@ mod tests {
             assert_eq!(three_node_route.get_n_nodes(), 3);
         }
         #[test]
+        fn test_canonical_tour() {
+            assert_eq!(Route::new(vec![]).canonical_tour(), Vec::<usize>::new());
+            assert_eq!(Route::new(vec![4]).canonical_tour(), vec![4]);
+            let canonical = Route::new(vec![3, 1, 4, 0, 2]).canonical_tour();
+            assert_eq!(canonical, vec![0, 2, 3, 1, 4]);
+            for route in [
+                vec![4, 1, 3, 2, 0],
+                vec![0, 4, 1, 3, 2],
+                vec![2, 0, 4, 1, 3],
+            ] {
+                assert_eq!(Route::new(route).canonical_tour(), canonical);
+            }
+        }
+        #[test]
+        fn canonical_order_compares_the_canonical_tours() {
+            let routes = (0..24)
+                .map(|seed| {
+                    random_permutation_with_rng(&[0, 1, 2, 3], &mut StdRng::seed_from_u64(seed))
+                })
+                .collect::<Vec<Vec<usize>>>();
+            for route_a in &routes {
+                for route_b in &routes {
+                    assert_eq!(
+                        CanonicalOrder::new(route_a).cmp(&CanonicalOrder::new(route_b)),
+                        Route::new(route_a.clone())
+                            .canonical_tour()
+                            .cmp(&Route::new(route_b.clone()).canonical_tour())
+                    );
+                }
+            }
+        }
+        #[cfg(feature = "std")]
+        #[test]
         fn test_mutuate_no_prob() {
             assert_eq!(
                 Route::new(vec![1, 2, 3, 4]).mutate(0.0).indexes,
@@ -177,6 +1693,7 @@ mod tests {
             )
         }
         // Run the following test five times.
+        #[cfg(feature = "std")]
         #[test]
         #[test]
         #[test]
@@ -188,20 +1705,354 @@ mod tests {
                 vec![1, 2, 3, 4]
             )
         }
+        #[cfg(feature = "std")]
         #[test]
         fn test_mutuate_100_prob_3_elems() {
             assert_ne!(Route::new(vec![1, 2, 3]).mutate(1.0).indexes, vec![1, 2, 3])
         }
+        #[cfg(feature = "std")]
+        #[test]
+        fn test_mutate_short_routes() {
+            assert_eq!(Route::new(vec![]).mutate(1.0).indexes, Vec::<usize>::new());
+            assert_eq!(Route::new(vec![0]).mutate(1.0).indexes, vec![0]);
+            assert_eq!(Route::new(vec![1, 0]).mutate(1.0).indexes, vec![1, 0]);
+        }
+        #[cfg(feature = "std")]
         #[test]
         fn test_mutate_simple_run() {
             let test_route = Route::new(vec![1, 2, 0]);
             valid_permutation(&test_route.indexes, &test_route.clone().mutate(0.5).indexes);
         }
     }
+    #[cfg(feature = "std")]
+    mod test_two_opt {
+        use super::*;
+        use crate::test_utils::valid_permutation;
+        /// Is there a reversal that improves the route?
+        fn has_improving_reversal(route: &[usize], distance_mat: &DistanceMat) -> bool {
+            let cost = distance_mat.get_cost(route);
+            (0..route.len()).any(|first_idx| {
+                ((first_idx + 1)..route.len()).any(|last_idx| {
+                    let mut reversed = route.to_vec();
+                    reversed[first_idx..=last_idx].reverse();
+                    distance_mat.get_cost(&reversed) < cost - 1e-9
+                })
+            })
+        }
+        #[test]
+        fn two_opt_gives_local_optimum() {
+            let points = (0..12)
+                .map(|_| {
+                    (
+                        get_random_elem_from_range_with_rng(0.0..100.0, &mut rand::thread_rng()),
+                        get_random_elem_from_range_with_rng(0.0..100.0, &mut rand::thread_rng()),
+                    )
+                })
+                .collect::<Vec<(f64, f64)>>();
+            let distance_mat = DistanceMat::from_coordinates(&points);
+            let route = Route::new((0..12).collect());
+            let improved_route = route.two_opt(&distance_mat);
+            valid_permutation(&route.indexes, &improved_route.indexes);
+            assert!(!has_improving_reversal(
+                &improved_route.indexes,
+                &distance_mat
+            ));
+        }
+        #[test]
+        fn two_opt_policies_give_local_optima() {
+            let points = (0..12)
+                .map(|_| {
+                    (
+                        get_random_elem_from_range_with_rng(0.0..100.0, &mut rand::thread_rng()),
+                        get_random_elem_from_range_with_rng(0.0..100.0, &mut rand::thread_rng()),
+                    )
+                })
+                .collect::<Vec<(f64, f64)>>();
+            let distance_mat = DistanceMat::from_coordinates(&points);
+            let route = Route::new((0..12).collect());
+            for pivoting in [
+                PivotingRule::FirstImprovement,
+                PivotingRule::BestImprovement,
+            ] {
+                for ordering in [NeighborhoodOrder::Sequential, NeighborhoodOrder::Random] {
+                    let improved_route = route.two_opt_with_policy(
+                        &distance_mat,
+                        LocalSearchPolicy::new(pivoting, ordering),
+                        &mut rand::thread_rng(),
+                    );
+                    valid_permutation(&route.indexes, &improved_route.indexes);
+                    assert!(!has_improving_reversal(
+                        &improved_route.indexes,
+                        &distance_mat
+                    ));
+                }
+            }
+        }
+        #[test]
+        fn best_improvement_applies_best_move() {
+            // Reversing the two middle nodes makes this route optimal.
+            let distance_mat = DistanceMat::new(vec![
+                vec![0.0, 1.0, 9.0, 1.0],
+                vec![1.0, 0.0, 1.0, 9.0],
+                vec![9.0, 1.0, 0.0, 1.0],
+                vec![1.0, 9.0, 1.0, 0.0],
+            ]);
+            let route = Route::new(vec![0, 2, 1, 3]).two_opt_with_policy(
+                &distance_mat,
+                LocalSearchPolicy::new(
+                    PivotingRule::BestImprovement,
+                    NeighborhoodOrder::Sequential,
+                ),
+                &mut rand::thread_rng(),
+            );
+            assert_eq!(distance_mat.get_cost(&route.indexes), 4.0);
+        }
+        #[test]
+        fn two_opt_on_asymmetric_matrix() {
+            let distance_mat = DistanceMat::new(vec![
+                vec![0.0, 1.0, 5.0, 5.0, 5.0],
+                vec![5.0, 0.0, 1.0, 5.0, 5.0],
+                vec![5.0, 5.0, 0.0, 1.0, 5.0],
+                vec![5.0, 5.0, 5.0, 0.0, 1.0],
+                vec![1.0, 5.0, 5.0, 5.0, 0.0],
+            ]);
+            let route = Route::new(vec![0, 3, 2, 1, 4]);
+            let improved_route = route.two_opt(&distance_mat);
+            assert!(
+                distance_mat.get_cost(&improved_route.indexes)
+                    <= distance_mat.get_cost(&route.indexes)
+            );
+            assert!(!has_improving_reversal(
+                &improved_route.indexes,
+                &distance_mat
+            ));
+        }
+    }
+    mod test_or_opt {
+        use super::*;
+        use crate::test_utils::valid_permutation;
+        use crate::utils::random_permutation_with_rng;
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        #[test]
+        fn or_opt_moves_chain() {
+            // The chain 1, 2 is misplaced, the optimal round-trip is 0, 1, 2, 3, 4, 5.
+            let points = [
+                (0.0, 0.0),
+                (1.0, 0.0),
+                (2.0, 0.0),
+                (3.0, 0.0),
+                (3.0, 1.0),
+                (0.0, 1.0),
+            ];
+            let distance_mat = DistanceMat::from_coordinates(&points);
+            let route = Route::new(vec![0, 3, 4, 1, 2, 5]);
+            let improved_route = route.or_opt(&distance_mat);
+            valid_permutation(&route.indexes, &improved_route.indexes);
+            assert!(
+                distance_mat.get_cost(&improved_route.indexes)
+                    < distance_mat.get_cost(&route.indexes)
+            );
+            // A local optimum is not changed by another pass.
+            assert_eq!(improved_route.or_opt(&distance_mat), improved_route);
+        }
+        #[test]
+        fn or_opt_keeps_local_optimum() {
+            let distance_mat = DistanceMat::new(vec![
+                vec![0.0, 1.0, 9.0, 1.0],
+                vec![1.0, 0.0, 1.0, 9.0],
+                vec![9.0, 1.0, 0.0, 1.0],
+                vec![1.0, 9.0, 1.0, 0.0],
+            ]);
+            let route = Route::new(vec![1, 2, 3, 0]);
+            assert_eq!(route.or_opt(&distance_mat), route);
+        }
+        #[test]
+        fn or_opt_is_not_changed_by_metric_pruning() {
+            let mut rng = StdRng::seed_from_u64(0);
+            let mut n_metric = 0;
+            for _ in 0..20 {
+                let points = (0..12)
+                    .map(|_| (rng.gen_range(0..10) as f64, rng.gen_range(0..10) as f64))
+                    .collect::<Vec<(f64, f64)>>();
+                let distance_mat = DistanceMat::from_coordinates(&points);
+                let route = Route::new(random_permutation_with_rng(
+                    &(0..12).collect::<Vec<usize>>(),
+                    &mut rng,
+                ));
+                let unpruned = route.or_opt(&distance_mat);
+                // The pruned moves can't lower the cost, so the search takes the same moves.
+                if distance_mat.is_metric() {
+                    n_metric += 1;
+                    assert_eq!(route.or_opt(&distance_mat), unpruned);
+                }
+            }
+            assert!(n_metric > 0);
+        }
+        #[test]
+        fn or_opt_moves_chains_around_the_end() {
+            let points = [
+                (23.0, 32.0),
+                (47.0, 2.0),
+                (42.0, 47.0),
+                (64.0, 97.0),
+                (52.0, 65.0),
+                (3.0, 52.0),
+                (30.0, 1.0),
+                (47.0, 97.0),
+                (2.0, 4.0),
+                (46.0, 1.0),
+                (23.0, 23.0),
+                (96.0, 9.0),
+            ];
+            let distance_mat = DistanceMat::from_coordinates(&points);
+            // No chain that lies within the route improves it, only moving a chain that wraps
+            // around its end does.
+            let route = Route::new(vec![10, 11, 1, 9, 6, 8, 5, 7, 3, 4, 2, 0]);
+            let improved_route = route.or_opt(&distance_mat);
+            valid_permutation(&route.indexes, &improved_route.indexes);
+            assert_eq!(improved_route.indexes[0], 10);
+            assert!(
+                distance_mat.get_cost(&improved_route.indexes)
+                    < distance_mat.get_cost(&route.indexes) - 1.0
+            );
+        }
+    }
+    mod test_three_opt {
+        use super::*;
+        use crate::test_utils::valid_permutation;
+        #[test]
+        fn three_opt_exchanges_segments() {
+            // Exchanging the segments [3, 4] and [1, 2] gives the optimal round-trip, which
+            // no 2-opt move reaches from this route.
+            let points = [
+                (0.0, 0.0),
+                (1.0, 0.0),
+                (2.0, 0.0),
+                (3.0, 0.0),
+                (4.0, 0.0),
+                (4.0, 1.0),
+                (0.0, 1.0),
+            ];
+            let distance_mat = DistanceMat::from_coordinates(&points);
+            let route = Route::new(vec![0, 3, 4, 1, 2, 5, 6]);
+            let improved_route = route.three_opt(&distance_mat);
+            valid_permutation(&route.indexes, &improved_route.indexes);
+            assert_eq!(
+                distance_mat.get_cost(&improved_route.indexes),
+                distance_mat.get_cost(&[0, 1, 2, 3, 4, 5, 6])
+            );
+            assert_eq!(improved_route.three_opt(&distance_mat), improved_route);
+        }
+        #[test]
+        fn three_opt_on_asymmetric_matrix() {
+            let distance_mat = DistanceMat::new(
+                (0..6)
+                    .map(|from| (0..6).map(|to| ((3 * from + 7 * to) % 11) as f64).collect())
+                    .collect(),
+            );
+            let route = Route::new(vec![3, 0, 4, 1, 5, 2]);
+            let improved_route = route.three_opt(&distance_mat);
+            valid_permutation(&route.indexes, &improved_route.indexes);
+            assert!(
+                distance_mat.get_cost(&improved_route.indexes)
+                    <= distance_mat.get_cost(&route.indexes)
+            );
+        }
+    }
+    mod test_pinning {
+        use super::*;
+        #[test]
+        fn first_legs_longer_than_route() {
+            assert_eq!(Route::new(vec![1, 0]).first_legs(5), &[1, 0]);
+        }
+        #[test]
+        fn pin_prefix_keeps_order_of_rest() {
+            assert_eq!(
+                Route::new(vec![4, 3, 2, 1, 0]).pin_prefix(&[1, 3]),
+                Route::new(vec![1, 3, 4, 2, 0])
+            );
+            assert_eq!(
+                Route::new(vec![1, 3, 0, 2]).pin_prefix(&[1, 3]),
+                Route::new(vec![1, 3, 0, 2])
+            );
+        }
+    }
+    mod test_split_join {
+        use super::*;
+        use crate::objective::ScenarioAggregation;
+        use crate::test_utils::valid_permutation;
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        #[test]
+        fn split_and_join_roundtrip() {
+            let route = Route::new(vec![3, 0, 4, 1, 2]);
+            for nodes in [vec![], vec![3], vec![4, 2], vec![7]] {
+                assert_eq!(Route::join(&route.split_at_nodes(&nodes)), route);
+            }
+        }
+        #[test]
+        fn split_empty_route() {
+            assert!(Route::new(vec![]).split_at_nodes(&[0]).is_empty());
+        }
+        #[test]
+        fn join_best_keeps_nodes() {
+            let distance_mat = DistanceMat::new(vec![
+                vec![0.0, 1.0, 9.0, 9.0, 1.0],
+                vec![1.0, 0.0, 1.0, 9.0, 9.0],
+                vec![9.0, 1.0, 0.0, 1.0, 9.0],
+                vec![9.0, 9.0, 1.0, 0.0, 1.0],
+                vec![1.0, 9.0, 9.0, 1.0, 0.0],
+            ]);
+            let parts = vec![
+                Route::new(vec![0]),
+                Route::new(vec![4, 3]),
+                Route::new(vec![1, 2]),
+            ];
+            let route = Route::join_best(&parts, &distance_mat);
+            valid_permutation(&[0, 1, 2, 3, 4], &route.indexes);
+            assert_eq!(distance_mat.get_cost(&route.indexes), 5.0);
+        }
+        #[test]
+        fn join_best_compares_parts_by_their_ends() {
+            let mut rng = StdRng::seed_from_u64(3);
+            let distances = (0..12)
+                .map(|_| (0..12).map(|_| rng.gen_range(1.0..10.0)).collect())
+                .collect::<Vec<Vec<f64>>>();
+            let distance_mat = DistanceMat::new(distances.clone());
+            // A single identical scenario keeps the costs, but they are no longer updated
+            // locally, so every joined route is evaluated as a whole.
+            let evaluated = DistanceMat::new(distances.clone()).with_scenarios(
+                vec![DistanceMat::new(distances)],
+                ScenarioAggregation::Average,
+            );
+            let parts = vec![
+                Route::new(vec![5, 0, 7]),
+                Route::new(vec![]),
+                Route::new(vec![3, 11]),
+                Route::new(vec![1, 9, 4, 2]),
+                Route::new(vec![10]),
+                Route::new(vec![8, 6]),
+            ];
+            let route = Route::join_best(&parts, &distance_mat);
+            valid_permutation(&(0..12).collect::<Vec<usize>>(), &route.indexes);
+            assert_eq!(route, Route::join_best(&parts, &evaluated));
+        }
+        #[test]
+        fn join_best_without_parts() {
+            assert_eq!(
+                Route::join_best(&[], &crate::test_utils::test_dist_mat()),
+                Route::new(vec![])
+            );
+        }
+    }
     mod test_crossover {
         use super::*;
         use crate::test_utils::valid_permutation;
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
 
+        #[cfg(feature = "std")]
         #[test]
         fn random_test_10() {
             let n_tests = 1000;
@@ -222,9 +2073,175 @@ mod tests {
             }
             assert!(n_no_crossover <= n_tests / 5);
         }
+        #[cfg(feature = "std")]
+        #[test]
+        fn spatial_crossover_keeps_permutation() {
+            let coordinates = (0..16)
+                .map(|node| ((node % 4) as f64, (node / 4) as f64))
+                .collect::<Vec<(f64, f64)>>();
+            let route_a = Route::new(vec![0, 12, 7, 3, 9, 8, 11, 5, 13, 1, 4, 6, 10, 15, 2, 14]);
+            let route_b = Route::new(vec![7, 10, 15, 12, 2, 9, 5, 3, 1, 6, 4, 13, 14, 11, 8, 0]);
+            let mut rng = rand::thread_rng();
+            for _ in 0..100 {
+                let child = route_a.spatial_crossover_with_rng(&route_b, &coordinates, &mut rng);
+                valid_permutation(&child.indexes, &route_a.indexes);
+            }
+        }
+        #[test]
+        fn candidate_crossover_only_adds_candidate_edges() {
+            // Nodes on a circle, so that every node has a unique pair of nearest neighbors.
+            let distance_mat = DistanceMat::from_coordinates(
+                &(0..16)
+                    .map(|node| {
+                        let angle = node as f64 * std::f64::consts::PI / 8.0;
+                        (angle.cos(), angle.sin())
+                    })
+                    .collect::<Vec<(f64, f64)>>(),
+            )
+            .with_candidate_list(2);
+            let candidates = distance_mat.candidate_list().unwrap();
+            let route_a = Route::new(vec![0, 12, 7, 3, 9, 8, 11, 5, 13, 1, 4, 6, 10, 15, 2, 14]);
+            let route_b = Route::new(vec![7, 10, 15, 12, 2, 9, 5, 3, 1, 6, 4, 13, 14, 11, 8, 0]);
+            let edges = |route: &Route| {
+                (0..16)
+                    .map(|idx| {
+                        let (from, to) = (route.indexes[idx], route.indexes[(idx + 1) % 16]);
+                        (from.min(to), from.max(to))
+                    })
+                    .collect::<Vec<(usize, usize)>>()
+            };
+            let (edges_a, edges_b) = (edges(&route_a), edges(&route_b));
+            let mut rng = StdRng::seed_from_u64(0);
+            for _ in 0..100 {
+                let child = route_a.candidate_crossover_with_rng(&route_b, candidates, &mut rng);
+                valid_permutation(&child.indexes, &route_a.indexes);
+                // Only the jumps when all neighbors are visited introduce other edges.
+                let n_other = edges(&child)
+                    .into_iter()
+                    .filter(|(from, to)| {
+                        !edges_a.contains(&(*from, *to))
+                            && !edges_b.contains(&(*from, *to))
+                            && !candidates.is_candidate(*from, *to)
+                    })
+                    .count();
+                assert!(n_other <= 4);
+            }
+            let route = Route::new((0..16).collect());
+            // A child of two equal parents is the same tour.
+            assert_eq!(
+                route
+                    .candidate_crossover_with_rng(&route, candidates, &mut rng)
+                    .canonical_tour(),
+                route.canonical_tour()
+            );
+        }
+        #[cfg(feature = "std")]
+        #[test]
+        fn spatial_crossover_without_coordinates() {
+            let route_a = Route::new(vec![0, 1, 2]);
+            let route_b = Route::new(vec![2, 1, 0]);
+            assert_eq!(
+                route_a.spatial_crossover_with_rng(&route_b, &[], &mut rand::thread_rng()),
+                route_a
+            );
+        }
+    }
+    #[cfg(feature = "std")]
+    mod test_mutation_operators {
+        use super::*;
+        use crate::test_utils::valid_permutation;
+
+        fn route() -> Route {
+            Route::new(vec![0, 1, 2, 3, 4, 5, 6, 7])
+        }
+        #[test]
+        fn inversion_reverses_a_subsequence() {
+            for _ in 0..100 {
+                let mutated = route().mutate_with_operator(
+                    1.0,
+                    MutationOperator::Inversion,
+                    &mut rand::thread_rng(),
+                );
+                // The changed positions form one reversed, contiguous block.
+                let changed = (0..8)
+                    .filter(|position| mutated.indexes[*position] != *position)
+                    .collect::<Vec<usize>>();
+                if let (Some(start), Some(end)) = (changed.first(), changed.last()) {
+                    for position in *start..=*end {
+                        assert_eq!(mutated.indexes[position], start + end - position);
+                    }
+                }
+            }
+        }
+        #[test]
+        fn swap_changes_two_positions() {
+            for _ in 0..100 {
+                let mutated = route().mutate_with_operator(
+                    1.0,
+                    MutationOperator::Swap,
+                    &mut rand::thread_rng(),
+                );
+                assert_eq!(
+                    (0..8)
+                        .filter(|position| mutated.indexes[*position] != *position)
+                        .count(),
+                    2
+                );
+            }
+        }
+        #[test]
+        fn operators_keep_permutation() {
+            for operator in [
+                MutationOperator::Insertion,
+                MutationOperator::Swap,
+                MutationOperator::Inversion,
+                MutationOperator::Scramble,
+            ] {
+                for _ in 0..100 {
+                    let mutated =
+                        route().mutate_with_operator(1.0, operator, &mut rand::thread_rng());
+                    valid_permutation(&route().indexes, &mutated.indexes);
+                }
+            }
+        }
+        #[test]
+        fn candidate_mutation_connects_a_neighbor() {
+            let distance_mat = DistanceMat::from_coordinates(
+                &(0..8)
+                    .map(|node| (node as f64, 0.0))
+                    .collect::<Vec<(f64, f64)>>(),
+            )
+            .with_candidate_list(1);
+            let candidates = distance_mat.candidate_list().unwrap();
+            let parent = Route::new(vec![0, 5, 2, 7, 4, 1, 6, 3]);
+            for _ in 0..100 {
+                let mutated = parent.clone().candidate_mutate_with_rng(
+                    1.0,
+                    candidates,
+                    &mut rand::thread_rng(),
+                );
+                valid_permutation(&parent.indexes, &mutated.indexes);
+                // No node of the parent is next to its nearest neighbor, the mutation moves one
+                // next to it.
+                assert!((0..8).any(|idx| candidates
+                    .is_candidate(mutated.indexes[idx], mutated.indexes[(idx + 1) % 8])));
+            }
+        }
+        #[test]
+        fn no_mutation_without_probability() {
+            assert_eq!(
+                route().mutate_with_operator(
+                    0.0,
+                    MutationOperator::Scramble,
+                    &mut rand::thread_rng()
+                ),
+                route()
+            );
+        }
     }
     mod test_fitness {
         use super::*;
+        use crate::objective::Objective;
         use crate::test_utils::test_dist_mat;
         #[test]
         fn simple_functionality_test() {
@@ -232,5 +2249,68 @@ mod tests {
             let route = Route::new(vec![1, 2, 0]);
             assert_eq!(route.fitness(&distance_mat), -6.0);
         }
+        #[test]
+        fn latency_objective() {
+            let distance_mat = test_dist_mat().with_objective(Objective::Latency);
+            let route = Route::new(vec![1, 2, 0]);
+            assert_eq!(route.fitness(&distance_mat), -8.0);
+        }
+    }
+    #[cfg(feature = "std")]
+    mod test_breed {
+        use super::*;
+        use crate::test_utils::valid_permutation;
+        #[test]
+        fn breeds_configured_number_of_offspring() {
+            let parent_a = Route::new(vec![0, 1, 2, 3, 4, 5]);
+            let parent_b = Route::new(vec![5, 3, 1, 0, 2, 4]);
+            for crossover_operator in [
+                CrossoverOperator::Ordered,
+                CrossoverOperator::PartiallyMapped,
+                CrossoverOperator::Cycle,
+                CrossoverOperator::EdgeRecombination,
+            ] {
+                let config = BreedConfig::new()
+                    .with_n_offspring(7)
+                    .with_crossover_operator(crossover_operator)
+                    .with_mutation_operator(MutationOperator::Scramble);
+                let offspring = breed(&parent_a, &parent_b, &config);
+                assert_eq!(offspring.len(), 7);
+                for child in offspring {
+                    valid_permutation(&parent_a.indexes, &child.indexes);
+                }
+            }
+        }
+        #[test]
+        #[should_panic(expected = "CrossoverOperator::Spatial needs the coordinates")]
+        fn spatial_crossover_is_rejected() {
+            breed(
+                &Route::new(vec![0, 1, 2, 3]),
+                &Route::new(vec![3, 1, 0, 2]),
+                &BreedConfig::new().with_crossover_operator(CrossoverOperator::Spatial),
+            );
+        }
+        #[test]
+        #[should_panic(expected = "The candidate operators need the candidate list")]
+        fn candidate_mutation_is_rejected() {
+            breed(
+                &Route::new(vec![0, 1, 2, 3]),
+                &Route::new(vec![3, 1, 0, 2]),
+                &BreedConfig::new().with_mutation_operator(MutationOperator::Candidate),
+            );
+        }
+        #[test]
+        fn without_crossover_and_mutation_offspring_are_copies() {
+            let parent_a = Route::new(vec![0, 1, 2, 3]);
+            let parent_b = Route::new(vec![3, 1, 0, 2]);
+            let config = BreedConfig::new()
+                .with_n_offspring(3)
+                .with_crossover_rate(0.0)
+                .with_mutation_rate(0.0);
+            assert_eq!(
+                breed(&parent_a, &parent_b, &config),
+                vec![parent_a.clone(), parent_b, parent_a]
+            );
+        }
     }
 }
@@ -1,35 +1,329 @@
+use crate::candidates::CandidateList;
+#[cfg(not(any(feature = "std", test)))]
+use crate::compat::Float;
+use crate::compat::{hash_set, FixedState, HashSet};
+use crate::constraint::{compare_lexicographic, Constraint};
+#[cfg(feature = "std")]
+use crate::convergence::tour_edges;
 use crate::distance_mat::DistanceMat;
-use crate::route::Route;
-use crate::utils::random_permutation;
+#[cfg(feature = "std")]
+use crate::distance_mat::DistanceMatMetadata;
+#[cfg(feature = "std")]
+use crate::evolution::{CancelToken, Evolution, EvolutionConfig, StopReason};
+use crate::local_search::LocalSearch;
+#[cfg(feature = "memory-stats")]
+use crate::memory::{measure_allocations, AllocationStats};
+use crate::objective::{TieBreaking, TourEquivalence};
+use crate::route::{CanonicalOrder, CrossoverOperator, LocalSearchPolicy, MutationOperator, Route};
+#[cfg(feature = "std")]
+use crate::route::{CANDIDATES_MISSING, SPATIAL_WITHOUT_COORDINATES};
+use crate::selection::{sample_fitnesses, DuplicatePolicy, GenerationalScheme, Selection};
+#[cfg(feature = "std")]
+use crate::tabu::TabuMemory;
+#[cfg(feature = "std")]
+use crate::utils::get_random_elem_from_range;
+use crate::utils::{
+    all_permutations, argsort, argsort_with_tie_breaker, n_permutations,
+    random_permutation_with_rng,
+};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt;
+use core::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "std")]
 use crossbeam_utils::thread;
-use fasthash_fork::xx;
+#[cfg(feature = "std")]
 use genetic_algorithm_traits::{Individual, Population};
-use std::collections::HashSet;
-use std::convert::From;
-use std::fmt;
+#[cfg(feature = "std")]
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::seq::SliceRandom;
+use rand::Rng;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+#[cfg(feature = "std")]
 use std::time::Instant;
 
-/// From a vector of routes create a Hashet with capacity length and hash function `xx-hash`.
+/// From a vector of routes create a Hashet with capacity length and the fixed hash function,
+/// `xx-hash` with the `std` feature.
 ///
 /// # Arguments
 ///
 /// * `routes` - The routes that should be added to the hashset.
 ///
-fn route_vec_to_xx_hashset(routes: Vec<Route>) -> HashSet<Route, xx::Hash64> {
+fn route_vec_to_xx_hashset(routes: Vec<Route>) -> HashSet<Route, FixedState> {
     let n_routes = routes.len();
-    let mut routes_as_hashset = HashSet::with_capacity_and_hasher(n_routes, xx::Hash64);
+    let mut routes_as_hashset = HashSet::with_capacity_and_hasher(n_routes, FixedState::default());
     for route in routes {
         routes_as_hashset.insert(route);
     }
     routes_as_hashset
 }
 
+/// How often in a row random routes may repeat known round-trips before
+/// `DuplicatePolicy::DedupeWithRandomReplacement` gives up filling the population.
+pub(crate) const MAX_FRUITLESS_REPLACEMENTS: usize = 10;
+
+/// From how many routes on `Routes::random` draws the routes in parallel.
+#[cfg(feature = "rayon")]
+const MIN_PARALLEL_ROUTES: usize = 1024;
+
+/// Are at least half of all permutations of `route_length` nodes requested? Then drawing random
+/// routes until enough distinct ones were found would mostly draw duplicates.
+///
+/// # Arguments
+///
+/// * `n_routes` - The number of distinct routes that are requested.
+/// * `route_length` - The length of an individual route.
+///
+fn is_dense(n_routes: usize, route_length: usize) -> bool {
+    n_routes.saturating_mul(2) >= n_permutations(route_length)
+}
+
+/// Build a tour with the nearest-neighbor heuristic: start at `start` and always go to the
+/// closest node that was not visited yet. If the distance matrix has a candidate list, it is
+/// used like in `nearest_neighbor_tour_with_candidates`.
+///
+/// # Arguments
+///
+/// * `start` - The node the tour starts at.
+/// * `distance_mat` - The distances between the nodes.
+///
+pub(crate) fn nearest_neighbor_tour(start: usize, distance_mat: &DistanceMat) -> Vec<usize> {
+    nearest_neighbor_tour_with_candidates(start, distance_mat, distance_mat.candidate_list())
+}
+
+/// Build a tour with the nearest-neighbor heuristic like `nearest_neighbor_tour`. The closest
+/// unvisited node is looked up among the neighbors of `candidates` first, as they are sorted by
+/// their distance, and only if all of them were visited among all nodes. Both lookups break ties
+/// by the index, so the tour is the same as without candidates, but it is built in about
+/// `O(n * k)` instead of `O(n^2)` steps.
+///
+/// # Arguments
+///
+/// * `start` - The node the tour starts at.
+/// * `distance_mat` - The distances between the nodes.
+/// * `candidates` - The near neighbors of every node, if any.
+///
+pub(crate) fn nearest_neighbor_tour_with_candidates(
+    start: usize,
+    distance_mat: &DistanceMat,
+    candidates: Option<&CandidateList>,
+) -> Vec<usize> {
+    let n_units = distance_mat.n_units();
+    let mut visited = vec![false; n_units];
+    let mut tour = Vec::with_capacity(n_units);
+    let mut current = start;
+    visited[current] = true;
+    tour.push(current);
+    while tour.len() < n_units {
+        let candidate = candidates.and_then(|candidates| {
+            candidates
+                .neighbors(current)
+                .iter()
+                .copied()
+                .find(|node| !visited[*node])
+        });
+        current = candidate.unwrap_or_else(|| {
+            (0..n_units)
+                .filter(|node| !visited[*node])
+                .min_by(|a, b| {
+                    distance_mat
+                        .distance(current, *a)
+                        .total_cmp(&distance_mat.distance(current, *b))
+                })
+                .unwrap()
+        });
+        visited[current] = true;
+        tour.push(current);
+    }
+    tour
+}
+
+/// Find the representative of the component of `node`, compressing the path on the way.
+///
+/// # Arguments
+///
+/// * `components` - The parent of every node, a node is the representative if it is its own parent.
+/// * `node` - The node whose component is looked up.
+///
+#[cfg(feature = "std")]
+fn find_component(components: &mut [usize], mut node: usize) -> usize {
+    while components[node] != node {
+        components[node] = components[components[node]];
+        node = components[node];
+    }
+    node
+}
+
+/// Build a tour with the greedy-edge heuristic: add the edges from cheapest to most expensive
+/// as long as no node gets more than two edges and no subtour is closed. The cheapest edge of
+/// `start` is added first, so that different start nodes can lead to different tours. The edges
+/// are weighted with the costs of both directions and the tour is returned starting at `start`
+/// in its cheaper direction.
+///
+/// # Arguments
+///
+/// * `start` - The node the tour starts at.
+/// * `distance_mat` - The distances between the nodes.
+///
+#[cfg(feature = "std")]
+fn greedy_edge_tour(start: usize, distance_mat: &DistanceMat) -> Vec<usize> {
+    let n_units = distance_mat.n_units();
+    if n_units <= 2 {
+        return (0..n_units).map(|node| (start + node) % n_units).collect();
+    }
+    let weight = |a: usize, b: usize| distance_mat.distance(a, b) + distance_mat.distance(b, a);
+    let mut edges = (0..n_units)
+        .flat_map(|a| (a + 1..n_units).map(move |b| (a, b)))
+        .collect::<Vec<(usize, usize)>>();
+    edges.sort_by(|(a, b), (c, d)| weight(*a, *b).total_cmp(&weight(*c, *d)));
+    let first_edge = *edges
+        .iter()
+        .find(|(a, b)| *a == start || *b == start)
+        .unwrap();
+    let mut neighbors = vec![Vec::with_capacity(2); n_units];
+    let mut components = (0..n_units).collect::<Vec<usize>>();
+    let mut n_edges = 0;
+    for (a, b) in core::iter::once(first_edge).chain(edges) {
+        if n_edges == n_units - 1 {
+            break;
+        }
+        if neighbors[a].len() == 2 || neighbors[b].len() == 2 {
+            continue;
+        }
+        let (component_a, component_b) = (
+            find_component(&mut components, a),
+            find_component(&mut components, b),
+        );
+        if component_a == component_b {
+            continue;
+        }
+        components[component_a] = component_b;
+        neighbors[a].push(b);
+        neighbors[b].push(a);
+        n_edges += 1;
+    }
+    // The edges form one path through all nodes, closing it gives the tour.
+    let ends = (0..n_units)
+        .filter(|node| neighbors[*node].len() == 1)
+        .collect::<Vec<usize>>();
+    neighbors[ends[0]].push(ends[1]);
+    neighbors[ends[1]].push(ends[0]);
+    let mut tour = Vec::with_capacity(n_units);
+    let (mut previous, mut current) = (start, neighbors[start][0]);
+    tour.push(start);
+    while current != start {
+        tour.push(current);
+        let following = if neighbors[current][0] == previous {
+            neighbors[current][1]
+        } else {
+            neighbors[current][0]
+        };
+        previous = current;
+        current = following;
+    }
+    let reversed = core::iter::once(start)
+        .chain(tour[1..].iter().rev().cloned())
+        .collect::<Vec<usize>>();
+    if distance_mat.get_cost(&reversed) < distance_mat.get_cost(&tour) {
+        reversed
+    } else {
+        tour
+    }
+}
+
+/// The errors that can occur when creating a `Routes`-object or selecting individuals from it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PopulationError {
+    /// More individuals were requested than the population contains.
+    NotEnoughIndividuals {
+        /// The number of individuals that were requested.
+        requested: usize,
+        /// The number of individuals in the population.
+        available: usize,
+    },
+    /// More distinct routes were requested than there are permutations of the nodes.
+    NotEnoughPermutations {
+        /// The number of routes that were requested.
+        requested: usize,
+        /// The number of permutations of the nodes.
+        available: usize,
+    },
+}
+impl fmt::Display for PopulationError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PopulationError::NotEnoughIndividuals {
+                requested,
+                available,
+            } => write!(
+                formatter,
+                "Requested {} individuals, but the population only contains {}",
+                requested, available
+            ),
+            PopulationError::NotEnoughPermutations {
+                requested,
+                available,
+            } => write!(
+                formatter,
+                "Requested {} distinct routes, but there are only {} permutations",
+                requested, available
+            ),
+        }
+    }
+}
+impl Error for PopulationError {}
+
+/// The distribution of the fitnesses of a population as created by `Routes::fitness_histogram`.
+/// The range from `min` to `max` is split into bins of equal width, the last bin includes `max`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FitnessHistogram {
+    /// The lowest fitness in the population, e.g. the lower bound of the first bin.
+    pub min: f64,
+    /// The highest fitness in the population, e.g. the upper bound of the last bin.
+    pub max: f64,
+    /// The number of routes whose fitness falls into each bin.
+    pub counts: Vec<usize>,
+}
+impl FitnessHistogram {
+    /// The width of every bin.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::routes::FitnessHistogram;
+    ///
+    /// let histogram = FitnessHistogram { min: -10.0, max: -6.0, counts: vec![1, 0, 0, 3] };
+    /// assert_eq!(histogram.bin_width(), 1.0);
+    /// ```
+    pub fn bin_width(&self) -> f64 {
+        (self.max - self.min) / self.counts.len() as f64
+    }
+}
+impl fmt::Display for FitnessHistogram {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for (bin, count) in self.counts.iter().enumerate() {
+            let lower = self.min + bin as f64 * self.bin_width();
+            writeln!(
+                formatter,
+                "[{}, {}): {}",
+                lower,
+                lower + self.bin_width(),
+                count
+            )?;
+        }
+        Ok(())
+    }
+}
+
 /// The `Population` is your current pools of routes that you would to improve by evolving them.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Routes {
     /// An individual routes is made from `routes`, e.g. individuals that might your given problem
     /// better of worse.
-    routes: HashSet<Route, xx::Hash64>,
+    routes: HashSet<Route, FixedState>,
 }
 impl fmt::Display for Routes {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
@@ -51,7 +345,7 @@ impl From<Vec<Route>> for Routes {
     /// # Arguments
     ///
     /// * `routes` - The routes you collected so far and would like to put into your
-    /// routes.
+    ///   routes.
     ///
     /// # Examples
     ///
@@ -71,7 +365,12 @@ impl From<Vec<Route>> for Routes {
 }
 
 impl Routes {
-    /// Create a new Population of routes by creating random invidiual routes.
+    /// Create a new Population of routes by creating random invidiual routes. As all routes
+    /// are unique, at most `route_length!` routes are created, use `try_random` to get an
+    /// error instead. If at least half of all permutations are requested, e.g. for tiny
+    /// instances, all permutations are enumerated and a random subset is taken, instead of
+    /// drawing routes until enough distinct ones were found. With the `rayon` feature, large
+    /// populations are drawn in parallel.
     ///
     /// # Arguments
     ///
@@ -86,16 +385,242 @@ impl Routes {
     ///
     /// let routes = Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]);
     /// ```
+    #[cfg(feature = "std")]
     pub fn random(n_routes: usize, route_length: usize) -> Self {
+        #[cfg(feature = "rayon")]
+        if n_routes >= MIN_PARALLEL_ROUTES && !is_dense(n_routes, route_length) {
+            let all_objects = (0..route_length).collect::<Vec<usize>>();
+            let mut routes = route_vec_to_xx_hashset(
+                (0..n_routes)
+                    .into_par_iter()
+                    .map_init(rand::thread_rng, |rng, _| {
+                        Route::new(random_permutation_with_rng(&all_objects, rng))
+                    })
+                    .collect(),
+            );
+            // Duplicates are replaced one by one.
+            let mut rng = rand::thread_rng();
+            while routes.len() < n_routes {
+                routes.insert(Route::new(random_permutation_with_rng(
+                    &all_objects,
+                    &mut rng,
+                )));
+            }
+            return Routes { routes };
+        }
+        Routes::random_with_rng(n_routes, route_length, &mut rand::thread_rng())
+    }
+    /// Create a random population like `random`, but return an error if more routes are
+    /// requested than there are distinct permutations of the nodes.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_routes` - The number of routes your population of routes should contain.
+    /// * `route_length` - The length of an individual route.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::routes::{PopulationError, Routes};
+    ///
+    /// assert_eq!(Routes::try_random(6, 3).unwrap().len(), 6);
+    /// assert_eq!(
+    ///     Routes::try_random(7, 3),
+    ///     Err(PopulationError::NotEnoughPermutations { requested: 7, available: 6 })
+    /// );
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn try_random(n_routes: usize, route_length: usize) -> Result<Self, PopulationError> {
+        let available = n_permutations(route_length);
+        if n_routes > available {
+            return Err(PopulationError::NotEnoughPermutations {
+                requested: n_routes,
+                available,
+            });
+        }
+        Ok(Routes::random(n_routes, route_length))
+    }
+    /// Create a random population like `random`, but draw the routes from `rng`, e.g. a seeded
+    /// `StdRng` for reproducible runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_routes` - The number of routes your population of routes should contain.
+    /// * `route_length` - The length of an individual route.
+    /// * `rng` - The random number generator the routes are drawn from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::routes::Routes;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// assert_eq!(
+    ///     Routes::random_with_rng(5, 10, &mut StdRng::seed_from_u64(7)),
+    ///     Routes::random_with_rng(5, 10, &mut StdRng::seed_from_u64(7))
+    /// );
+    /// ```
+    pub fn random_with_rng<R: Rng + ?Sized>(
+        n_routes: usize,
+        route_length: usize,
+        rng: &mut R,
+    ) -> Self {
+        let n_routes = n_routes.min(n_permutations(route_length));
+        if is_dense(n_routes, route_length) {
+            let mut permutations = all_permutations(route_length);
+            let (chosen, _) = permutations.partial_shuffle(rng, n_routes);
+            return Routes::from(
+                chosen
+                    .iter()
+                    .cloned()
+                    .map(Route::new)
+                    .collect::<Vec<Route>>(),
+            );
+        }
         let all_objects = (0..route_length).collect::<Vec<usize>>();
-        let mut routes = HashSet::with_capacity_and_hasher(n_routes, xx::Hash64);
+        let mut routes = HashSet::with_capacity_and_hasher(n_routes, FixedState::default());
 
         while routes.len() < n_routes {
-            routes.insert(Route::new(random_permutation(&all_objects)));
+            routes.insert(Route::new(random_permutation_with_rng(&all_objects, rng)));
         }
 
         Routes { routes }
     }
+    /// Create a population of nearest-neighbor tours, each starting at a different random node
+    /// and always moving on to the closest node that was not visited yet. Such tours are
+    /// usually within 25% of the optimum and a much better start than random routes on large
+    /// instances. As there is one tour per start node, at most `distance_mat.n_units()` routes
+    /// are created, `top_up` can fill the population with random routes.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_routes` - The number of routes your population of routes should contain.
+    /// * `distance_mat` - The distances the tours are built on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let routes = Routes::nearest_neighbor(2, &distance_matrix);
+    /// assert_eq!(routes.len(), 2);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn nearest_neighbor(n_routes: usize, distance_mat: &DistanceMat) -> Self {
+        Routes::nearest_neighbor_with_rng(n_routes, distance_mat, &mut rand::thread_rng())
+    }
+    /// Create a population of nearest-neighbor tours like `Routes::nearest_neighbor`, but draw
+    /// the start nodes from `rng`, so that the same seed gives the same population.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_routes` - The number of routes your population of routes should contain.
+    /// * `distance_mat` - The distances the tours are built on.
+    /// * `rng` - The random number generator the start nodes are drawn from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::routes::Routes;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let routes = Routes::nearest_neighbor_with_rng(2, &distance_matrix, &mut StdRng::seed_from_u64(7));
+    /// assert_eq!(routes.len(), 2);
+    /// ```
+    pub fn nearest_neighbor_with_rng<R: Rng + ?Sized>(
+        n_routes: usize,
+        distance_mat: &DistanceMat,
+        rng: &mut R,
+    ) -> Self {
+        Routes::from_start_nodes(n_routes, distance_mat, nearest_neighbor_tour, rng)
+    }
+    /// Create a population of nearest-neighbor tours like `Routes::nearest_neighbor_with_rng`,
+    /// but look the closest nodes up in `candidates`, see `nearest_neighbor_tour_with_candidates`.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_routes` - The number of routes your population of routes should contain.
+    /// * `distance_mat` - The distances the tours are built on.
+    /// * `candidates` - The near neighbors of every node.
+    /// * `rng` - The random number generator the start nodes are drawn from.
+    ///
+    #[cfg(feature = "std")]
+    pub(crate) fn nearest_neighbor_with_candidates<R: Rng + ?Sized>(
+        n_routes: usize,
+        distance_mat: &DistanceMat,
+        candidates: &CandidateList,
+        rng: &mut R,
+    ) -> Self {
+        Routes::from_start_nodes(
+            n_routes,
+            distance_mat,
+            |start, distance_mat| {
+                nearest_neighbor_tour_with_candidates(start, distance_mat, Some(candidates))
+            },
+            rng,
+        )
+    }
+    /// Create a population of greedy-edge tours: the edges are added from cheapest to most
+    /// expensive as long as they neither give a node a third edge nor close a subtour. Every
+    /// tour starts at a different random node, whose cheapest edge is added first, so the tours
+    /// differ a bit more than rotations of the same tour. At most `distance_mat.n_units()`
+    /// routes are created.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_routes` - The number of routes your population of routes should contain.
+    /// * `distance_mat` - The distances the tours are built on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let routes = Routes::greedy_edge(5, &distance_matrix);
+    /// assert_eq!(routes.len(), 3);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn greedy_edge(n_routes: usize, distance_mat: &DistanceMat) -> Self {
+        Routes::from_start_nodes(
+            n_routes,
+            distance_mat,
+            greedy_edge_tour,
+            &mut rand::thread_rng(),
+        )
+    }
+    /// Build one tour from each of `n_routes` different random start nodes.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_routes` - The number of routes, at most one per node is built.
+    /// * `distance_mat` - The distances the tours are built on.
+    /// * `build_tour` - Builds the tour from a start node.
+    /// * `rng` - The random number generator the start nodes are drawn from.
+    ///
+    fn from_start_nodes<R: Rng + ?Sized>(
+        n_routes: usize,
+        distance_mat: &DistanceMat,
+        build_tour: impl Fn(usize, &DistanceMat) -> Vec<usize>,
+        rng: &mut R,
+    ) -> Self {
+        let mut start_nodes = (0..distance_mat.n_units()).collect::<Vec<usize>>();
+        let n_routes = n_routes.min(start_nodes.len());
+        let (chosen, _) = start_nodes.partial_shuffle(rng, n_routes);
+        Routes::from(
+            chosen
+                .iter()
+                .map(|start| Route::new(build_tour(*start, distance_mat)))
+                .collect::<Vec<Route>>(),
+        )
+    }
     /// Add new routes to a `Routes`-object and create a new `Routes`-object
     ///
     /// # Arguments
@@ -160,37 +685,41 @@ impl Routes {
             .first()
             .unwrap()
     }
-    /// Add n random nodes to your current pool.
+    /// Get the number of routes in this `Routes`-object.
     ///
-    /// # Arguments:
+    /// # Examples
     ///
-    /// `n_random_nodes`: The number of random nodes that should be added.
+    /// ```
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let routes = Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]);
+    /// println!("There are {} routes", routes.len());
+    /// ```
+    pub fn len(&self) -> usize {
+        self.routes.len()
+    }
+    /// Does this `Routes`-object contain no routes at all?
     ///
     /// # Examples
+    ///
     /// ```
     /// use genetic_algorithm_tsp::route::Route;
     /// use genetic_algorithm_tsp::routes::Routes;
     ///
-    /// let a_single_route = Routes::from(vec![Route::new(vec![0,1,2])]);
-    /// println!("{}", a_single_route.add_n_random_nodes(1));
+    /// let no_routes = Routes::from(vec![]);
+    /// assert!(no_routes.is_empty());
     /// ```
-    pub fn add_n_random_nodes(self, n_random_nodes: usize) -> Self {
-        let number_of_nodes = self.get_n_nodes();
-        self.combine_routes(Routes::random(n_random_nodes, number_of_nodes))
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
     }
-}
-
-impl<'a> Population<'a> for Routes {
-    type Individual = Route;
-    type IndividualCollection = std::collections::hash_set::Iter<'a, Route>;
-
-    /// Given your pool of current routes, compute the fitness of your individuals to solve the
-    /// problem at hand.
+    /// Get the n fittest individuals like `get_n_fittest`, but fail if the population contains
+    /// less than `n` individuals instead of silently returning fewer.
     ///
     /// # Arguments
     ///
-    /// * `distance_mat` - The distances between nodes that is neccessary to computes how well the route
-    /// work in terms of the TSP
+    /// * `n` - The number of individuals you would like to have.
+    /// * `distance_mat` - The distance matrix the fitness should be evaluated on.
     ///
     /// # Examples
     ///
@@ -198,19 +727,27 @@ impl<'a> Population<'a> for Routes {
     /// use genetic_algorithm_tsp::routes::Routes;
     /// use genetic_algorithm_tsp::route::Route;
     /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
-    /// use genetic_algorithm_traits::Population;
     ///
     /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
     /// let routes = Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]);
-    /// println!("Your routes's fitnesses: {:?}", routes.fitnesses(&distance_matrix));
+    /// assert!(routes.try_get_n_fittest(2, &distance_matrix).is_ok());
+    /// assert!(routes.try_get_n_fittest(3, &distance_matrix).is_err());
     /// ```
-    // fn fitnesses(&self, distance_mat: &DistanceMat) -> Vec<(f64, &Route)> {
-    //     self.iter()
-    //         .map(|route| (route.fitness(distance_mat), route))
-    //         .collect()
-    // }
-    /// Get the n fittest individuals in your routes as new routes object. This is typically used
-    /// to select the top n inidividuals, before continuing to evolve the routes further.
+    pub fn try_get_n_fittest(
+        &self,
+        n: usize,
+        distance_mat: &DistanceMat,
+    ) -> Result<Vec<Route>, PopulationError> {
+        if n > self.len() {
+            return Err(PopulationError::NotEnoughIndividuals {
+                requested: n,
+                available: self.len(),
+            });
+        }
+        Ok(self.get_n_fittest(n, distance_mat))
+    }
+    /// Get the n fittest individuals as new routes object like `get_fittest_population`, but
+    /// fail if the population contains less than `n` individuals.
     ///
     /// # Arguments
     ///
@@ -223,125 +760,2870 @@ impl<'a> Population<'a> for Routes {
     /// use genetic_algorithm_tsp::routes::Routes;
     /// use genetic_algorithm_tsp::route::Route;
     /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
-    /// use genetic_algorithm_traits::Population;
     ///
     /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
     /// let routes = Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]);
-    /// let my_fittest_routes = routes.get_fittest_population(2, &distance_matrix);
+    /// let my_fittest_routes = routes.try_get_fittest_population(1, &distance_matrix).unwrap();
     /// ```
-    fn get_fittest_population(&self, n: usize, distance_mat: &DistanceMat) -> Routes {
-        Routes {
-            routes: route_vec_to_xx_hashset(self.get_n_fittest(n, distance_mat)),
-        }
+    pub fn try_get_fittest_population(
+        &self,
+        n: usize,
+        distance_mat: &DistanceMat,
+    ) -> Result<Routes, PopulationError> {
+        Ok(Routes::from(self.try_get_n_fittest(n, distance_mat)?))
     }
-    /// Evolve your population.
-    ///
-    /// The evolution consists of the following stages:
-    /// 1) `crossover` between all 1,...,n routes excluding the route itself.
-    /// 2) `mutate` is applied to all individuals.
+    /// Get the fitnesses of the population at the given percentiles, e.g. `50.0` for the median.
+    /// Between two routes the fitness is interpolated linearly and percentiles outside of
+    /// `0.0..=100.0` are clamped. Returns `None` for an empty population.
     ///
     /// # Arguments
     ///
-    /// * `mutate_prob` - The probabilty of an inviduals beeing mutated. Is applied via `individuals.mutate`.
+    /// * `distance_mat` - The distance matrix the fitness should be evaluated on.
+    /// * `percentiles` - The percentiles that should be computed.
     ///
     /// # Examples
     ///
     /// ```
     /// use genetic_algorithm_tsp::routes::Routes;
     /// use genetic_algorithm_tsp::route::Route;
-    /// use genetic_algorithm_traits::Population;
     /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
     ///
-    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
-    /// let routes = Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]);
-    /// let evolved_routes = routes.evolve(0.5);
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 2.0, 3.0],
+    ///     vec![1.0, 0.0, 3.0, 1.0],
+    ///     vec![2.0, 3.0, 0.0, 2.0],
+    ///     vec![3.0, 1.0, 2.0, 0.0],
+    /// ]);
+    /// let routes = Routes::from(vec![Route::new(vec![0, 1, 2, 3]), Route::new(vec![0, 1, 3, 2])]);
+    /// assert_eq!(routes.fitness_percentiles(&distance_matrix, &[0.0, 50.0, 100.0]), Some(vec![-9.0, -7.5, -6.0]));
     /// ```
-    fn evolve(&self, mutate_prob: f32) -> Routes {
-        let mutated_individuals = self.evolve_individuals(mutate_prob);
-        Routes {
-            routes: route_vec_to_xx_hashset(mutated_individuals),
+    pub fn fitness_percentiles(
+        &self,
+        distance_mat: &DistanceMat,
+        percentiles: &[f64],
+    ) -> Option<Vec<f64>> {
+        if self.is_empty() {
+            return None;
         }
+        let mut fitnesses = self
+            .iter()
+            .map(|route| route.fitness(distance_mat))
+            .collect::<Vec<f64>>();
+        fitnesses.sort_unstable_by(f64::total_cmp);
+        let last_index = (fitnesses.len() - 1) as f64;
+        Some(
+            percentiles
+                .iter()
+                .map(|percentile| {
+                    let position = percentile.clamp(0.0, 100.0) / 100.0 * last_index;
+                    let lower = fitnesses[position.floor() as usize];
+                    let upper = fitnesses[position.ceil() as usize];
+                    lower + (upper - lower) * position.fract()
+                })
+                .collect(),
+        )
     }
-    /// Iterate over the individuals of your population.
+    /// Count the fitnesses of the population in `n_bins` bins of equal width between the lowest
+    /// and the highest fitness. A population that converged prematurely shows up as a single
+    /// high bin long before the best fitness stops improving. Returns `None` for an empty
+    /// population or zero bins.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix the fitness should be evaluated on.
+    /// * `n_bins` - The number of bins.
     ///
     /// # Examples
     ///
     /// ```
     /// use genetic_algorithm_tsp::routes::Routes;
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 2.0, 3.0],
+    ///     vec![1.0, 0.0, 3.0, 1.0],
+    ///     vec![2.0, 3.0, 0.0, 2.0],
+    ///     vec![3.0, 1.0, 2.0, 0.0],
+    /// ]);
+    /// let histogram = Routes::random(10, 4).fitness_histogram(&distance_matrix, 3).unwrap();
+    /// assert_eq!(histogram.counts.iter().sum::<usize>(), 10);
+    /// println!("{}", histogram);
+    /// ```
+    pub fn fitness_histogram(
+        &self,
+        distance_mat: &DistanceMat,
+        n_bins: usize,
+    ) -> Option<FitnessHistogram> {
+        if self.is_empty() || n_bins == 0 {
+            return None;
+        }
+        let fitnesses = self
+            .iter()
+            .map(|route| route.fitness(distance_mat))
+            .collect::<Vec<f64>>();
+        let min = fitnesses.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = fitnesses.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let mut counts = vec![0; n_bins];
+        for fitness in fitnesses {
+            let bin = if max > min {
+                ((fitness - min) / (max - min) * n_bins as f64) as usize
+            } else {
+                0
+            };
+            counts[bin.min(n_bins - 1)] += 1;
+        }
+        Some(FitnessHistogram { min, max, counts })
+    }
+    /// Add random routes until this `Routes`-object contains `n_routes` routes. Duplicates from
+    /// `crossover` make a population shrink, so this keeps its size constant. Like in `random`
+    /// at most `n_nodes!` routes can be contained and an empty population stays empty, as the
+    /// number of nodes is unknown.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_routes` - The number of routes the population should contain afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```
     /// use genetic_algorithm_tsp::route::Route;
-    /// use genetic_algorithm_traits::Population;
+    /// use genetic_algorithm_tsp::routes::Routes;
     ///
-    /// let routes = Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]);
-    /// for route in routes.iter(){
-    ///     println!("{:?}", route);
-    /// }
+    /// let a_single_route = Routes::from(vec![Route::new(vec![0,1,2,3])]);
+    /// assert_eq!(a_single_route.top_up(5).len(), 5);
     /// ```
-    fn iter(&'a self) -> std::collections::hash_set::Iter<Route> {
-        self.routes.iter()
+    #[cfg(feature = "std")]
+    pub fn top_up(self, n_routes: usize) -> Self {
+        self.top_up_with_rng(n_routes, &mut rand::thread_rng())
     }
-}
-
-/// Given an initial population evolve it for `n_generations` while keeping `size_generation`
-/// individuals. The final population will be returned.
-///
-/// # Arguments
-///
-/// * `initial_population` - Your initial population that should be evolved.
-/// * `n_generations` - How many times should your population be evolved?
-/// * `size_generation` - How many individuals should be kept after evolving it.
-/// * `distance_matrix` - The distance matrix on which the fitness will be computed on.
-///
-/// # Examples
-///
-/// ```
-/// use genetic_algorithm_tsp::routes::{Routes, evolve_population};
-/// use genetic_algorithm_tsp::route::Route;
-/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
-///
-/// let evolved_population = evolve_population(
-///     Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]),
-///     10,
-///     10,
-///     &DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]),
+    /// Add random routes like `top_up`, but draw them from the given random number generator,
+    /// e.g. a seeded one for reproducible runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_routes` - The number of routes the population should contain afterwards.
+    /// * `rng` - The random number generator the routes are drawn from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_tsp::routes::Routes;
+    /// use rand::SeedableRng;
+    ///
+    /// let a_single_route = Routes::from(vec![Route::new(vec![0,1,2,3])]);
+    /// let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+    /// assert_eq!(a_single_route.top_up_with_rng(5, &mut rng).len(), 5);
+    /// ```
+    pub fn top_up_with_rng<R: Rng + ?Sized>(mut self, n_routes: usize, rng: &mut R) -> Self {
+        if self.is_empty() {
+            return self;
+        }
+        let all_objects = (0..self.get_n_nodes()).collect::<Vec<usize>>();
+        let n_routes = n_routes.min(n_permutations(all_objects.len()));
+        while self.routes.len() < n_routes {
+            self.routes
+                .insert(Route::new(random_permutation_with_rng(&all_objects, rng)));
+        }
+        self
+    }
+    /// Measure how diverse the population is, e.g. to diagnose premature convergence. Routes
+    /// are compared by their undirected edges, so rotated or reversed copies of a round-trip
+    /// count as the same individual.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let routes = Routes::from(vec![Route::new(vec![0, 1, 2, 3]), Route::new(vec![1, 2, 3, 0])]);
+    /// let diversity = routes.diversity();
+    /// assert_eq!(diversity.n_unique, 1);
+    /// assert_eq!(diversity.mean_edge_overlap, 1.0);
+    /// assert_eq!(diversity.edge_entropy, 2.0);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn diversity(&self) -> Diversity {
+        let edges = self
+            .routes
+            .iter()
+            .map(|route| {
+                let mut edges = tour_edges(&route.indexes);
+                edges.sort_unstable();
+                edges
+            })
+            .collect::<Vec<Vec<(usize, usize)>>>();
+        let mut edge_counts = std::collections::HashMap::new();
+        for edge in edges.iter().flatten() {
+            *edge_counts.entry(*edge).or_insert(0usize) += 1;
+        }
+        let n_routes = edges.len();
+        let n_edges = edges.iter().map(Vec::len).sum::<usize>();
+        let n_route_edges = edges.first().map_or(0, Vec::len);
+        // Two routes share an edge for every pair of routes that use it.
+        let n_shared = edge_counts
+            .values()
+            .map(|count| count * (count - 1) / 2)
+            .sum::<usize>();
+        let n_pairs = n_routes * n_routes.saturating_sub(1) / 2;
+        Diversity {
+            mean_edge_overlap: if n_pairs == 0 || n_route_edges == 0 {
+                1.0
+            } else {
+                n_shared as f64 / (n_pairs * n_route_edges) as f64
+            },
+            n_unique: edges
+                .iter()
+                .collect::<HashSet<&Vec<(usize, usize)>>>()
+                .len(),
+            edge_entropy: edge_counts
+                .values()
+                .map(|count| {
+                    let share = *count as f64 / n_edges as f64;
+                    -share * share.log2()
+                })
+                .sum(),
+        }
+    }
+    /// Improve the `n` fittest routes with `Route::two_opt` and keep the `size_generation`
+    /// fittest routes of the population and the improved routes.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - How many of the fittest routes should be improved.
+    /// * `distance_mat` - The distance matrix the fitness is computed on.
+    /// * `size_generation` - How many routes should be kept afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_tsp::routes::Routes;
+    /// use genetic_algorithm_traits::{Individual, Population};
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 9.0, 1.0],
+    ///     vec![1.0, 0.0, 1.0, 9.0],
+    ///     vec![9.0, 1.0, 0.0, 1.0],
+    ///     vec![1.0, 9.0, 1.0, 0.0],
+    /// ]);
+    /// let routes = Routes::from(vec![Route::new(vec![0, 2, 1, 3])]).refine_fittest(1, &distance_matrix, 1);
+    /// assert_eq!(routes.get_n_fittest(1, &distance_matrix)[0].fitness(&distance_matrix), -4.0);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn refine_fittest(
+        self,
+        n: usize,
+        distance_mat: &DistanceMat,
+        size_generation: usize,
+    ) -> Self {
+        self.refine_fittest_with_policy(
+            n,
+            distance_mat,
+            size_generation,
+            LocalSearchPolicy::default(),
+        )
+    }
+    /// Improve the `n` fittest routes like `Routes::refine_fittest`, but with 2-opt following
+    /// `policy`, see `Route::two_opt_with_policy`.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - How many of the fittest routes should be improved.
+    /// * `distance_mat` - The distance matrix the fitness is computed on.
+    /// * `size_generation` - How many routes should be kept afterwards.
+    /// * `policy` - How the local search moves through the 2-opt neighborhood.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::route::{LocalSearchPolicy, NeighborhoodOrder, PivotingRule, Route};
+    /// use genetic_algorithm_tsp::routes::Routes;
+    /// use genetic_algorithm_traits::{Individual, Population};
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 9.0, 1.0],
+    ///     vec![1.0, 0.0, 1.0, 9.0],
+    ///     vec![9.0, 1.0, 0.0, 1.0],
+    ///     vec![1.0, 9.0, 1.0, 0.0],
+    /// ]);
+    /// let policy = LocalSearchPolicy::new(PivotingRule::BestImprovement, NeighborhoodOrder::Sequential);
+    /// let routes = Routes::from(vec![Route::new(vec![0, 2, 1, 3])])
+    ///     .refine_fittest_with_policy(1, &distance_matrix, 1, policy);
+    /// assert_eq!(routes.get_n_fittest(1, &distance_matrix)[0].fitness(&distance_matrix), -4.0);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn refine_fittest_with_policy(
+        self,
+        n: usize,
+        distance_mat: &DistanceMat,
+        size_generation: usize,
+        policy: LocalSearchPolicy,
+    ) -> Self {
+        self.refine_fittest_with_rng(
+            n,
+            distance_mat,
+            size_generation,
+            policy,
+            &mut rand::thread_rng(),
+        )
+    }
+    /// Improve the `n` fittest routes like `Routes::refine_fittest_with_policy`, but shuffle
+    /// the random order of the policy with `rng`.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - How many of the fittest routes should be improved.
+    /// * `distance_mat` - The distance matrix the fitness is computed on.
+    /// * `size_generation` - How many routes should be kept afterwards.
+    /// * `policy` - How the local search moves through the 2-opt neighborhood.
+    /// * `rng` - The random number generator the order of the stretches is shuffled with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::route::{LocalSearchPolicy, NeighborhoodOrder, PivotingRule, Route};
+    /// use genetic_algorithm_tsp::routes::Routes;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 9.0, 1.0],
+    ///     vec![1.0, 0.0, 1.0, 9.0],
+    ///     vec![9.0, 1.0, 0.0, 1.0],
+    ///     vec![1.0, 9.0, 1.0, 0.0],
+    /// ]);
+    /// let policy = LocalSearchPolicy::new(PivotingRule::FirstImprovement, NeighborhoodOrder::Random);
+    /// let routes = Routes::from(vec![Route::new(vec![0, 2, 1, 3])])
+    ///     .refine_fittest_with_rng(1, &distance_matrix, 1, policy, &mut StdRng::seed_from_u64(3));
+    /// assert_eq!(routes.get_n_fittest(1, &distance_matrix)[0].fitness(&distance_matrix), -4.0);
+    /// ```
+    pub fn refine_fittest_with_rng<R: Rng + ?Sized>(
+        self,
+        n: usize,
+        distance_mat: &DistanceMat,
+        size_generation: usize,
+        policy: LocalSearchPolicy,
+        rng: &mut R,
+    ) -> Self {
+        let refined = self
+            .get_n_fittest(n, distance_mat)
+            .iter()
+            .map(|route| route.two_opt_with_policy(distance_mat, policy, rng))
+            .collect();
+        self.add_vec_route(refined)
+            .get_fittest_population(size_generation, distance_mat)
+    }
+    /// Improve the `n` fittest routes like `Routes::refine_fittest`, but in the neighborhoods
+    /// of `local_search`, e.g. a `LocalSearchChain` of 2-opt, Or-opt and 3-opt.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - How many of the fittest routes should be improved.
+    /// * `distance_mat` - The distance matrix the fitness is computed on.
+    /// * `size_generation` - How many routes should be kept afterwards.
+    /// * `local_search` - The neighborhoods the routes are improved in.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::local_search::{LocalSearchChain, OrOpt, TwoOpt};
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_tsp::routes::Routes;
+    /// use genetic_algorithm_traits::{Individual, Population};
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 9.0, 1.0],
+    ///     vec![1.0, 0.0, 1.0, 9.0],
+    ///     vec![9.0, 1.0, 0.0, 1.0],
+    ///     vec![1.0, 9.0, 1.0, 0.0],
+    /// ]);
+    /// let chain = LocalSearchChain::new().then(TwoOpt::default()).then(OrOpt);
+    /// let routes = Routes::from(vec![Route::new(vec![0, 2, 1, 3])])
+    ///     .refine_fittest_with_local_search(1, &distance_matrix, 1, &chain);
+    /// assert_eq!(routes.get_n_fittest(1, &distance_matrix)[0].fitness(&distance_matrix), -4.0);
+    /// ```
+    pub fn refine_fittest_with_local_search(
+        self,
+        n: usize,
+        distance_mat: &DistanceMat,
+        size_generation: usize,
+        local_search: &dyn LocalSearch,
+    ) -> Self {
+        let refined = self
+            .get_n_fittest(n, distance_mat)
+            .iter()
+            .map(|route| local_search.improve(route, distance_mat))
+            .collect();
+        self.add_vec_route(refined)
+            .get_fittest_population(size_generation, distance_mat)
+    }
+    /// Get the n best routes of a constrained variant: routes are compared lexicographically
+    /// as in `compare_lexicographic`, so a feasible route is always preferred over an
+    /// infeasible one, no matter how short the infeasible route is. Routes with the same
+    /// violation and fitness are ordered by their canonical tour.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The number of routes you would like to get.
+    /// * `distance_mat` - The distance matrix the fitness should be evaluated on.
+    /// * `constraint` - The constraint whose violation is compared first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::constraint::Precedence;
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 9.0, 1.0],
+    ///     vec![1.0, 0.0, 1.0, 9.0],
+    ///     vec![9.0, 1.0, 0.0, 1.0],
+    ///     vec![1.0, 9.0, 1.0, 0.0],
+    /// ]);
+    /// let routes = Routes::from(vec![Route::new(vec![0, 1, 2, 3]), Route::new(vec![0, 2, 1, 3])]);
+    /// // Node 2 has to be visited before node 1, which only the longer route does.
+    /// let constraint = Precedence::new(vec![(2, 1)]);
+    /// assert_eq!(
+    ///     routes.get_n_fittest_feasible_first(1, &distance_matrix, &constraint),
+    ///     vec![Route::new(vec![0, 2, 1, 3])]
+    /// );
+    /// ```
+    pub fn get_n_fittest_feasible_first(
+        &self,
+        n: usize,
+        distance_mat: &DistanceMat,
+        constraint: &dyn Constraint,
+    ) -> Vec<Route> {
+        let mut routes = self
+            .routes
+            .iter()
+            .map(|route| {
+                (
+                    (
+                        constraint.violation(route, distance_mat),
+                        route.fitness(distance_mat),
+                    ),
+                    CanonicalOrder::new(&route.indexes),
+                    route,
+                )
+            })
+            .collect::<Vec<((f64, f64), CanonicalOrder<'_>, &Route)>>();
+        routes.sort_by(|(a, a_tour, a_route), (b, b_tour, b_route)| {
+            compare_lexicographic(*a, *b)
+                .then_with(|| a_tour.cmp(b_tour))
+                .then_with(|| a_route.indexes.cmp(&b_route.indexes))
+        });
+        routes
+            .into_iter()
+            .take(n)
+            .map(|(_, _, route)| route.clone())
+            .collect()
+    }
+    /// Make every route of the population start with `prefix` as in `Route::pin_prefix`. Routes
+    /// that only differed in the order of the pinned nodes become equal and are kept once.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The nodes every route has to start with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let routes = Routes::from(vec![Route::new(vec![0, 1, 2]), Route::new(vec![1, 0, 2])]);
+    /// assert_eq!(routes.pin_prefix(&[1, 0]), Routes::from(vec![Route::new(vec![1, 0, 2])]));
+    /// ```
+    pub fn pin_prefix(self, prefix: &[usize]) -> Self {
+        Routes {
+            routes: self
+                .routes
+                .into_iter()
+                .map(|route| route.pin_prefix(prefix))
+                .collect(),
+        }
+    }
+    /// Breed `k` offspring whose parents are drawn from two different populations: the first
+    /// parent is a random route of this population, the second a random route of `other`. Like in
+    /// any `Routes`-object, duplicate offspring are only kept once, so fewer than `k` routes can
+    /// be returned. If one of the populations is empty, no offspring are bred.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The population the second parents are drawn from.
+    /// * `k` - How many offspring should be bred.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let island_a = Routes::from(vec![Route::new(vec![0,1,2,3]), Route::new(vec![3,2,1,0])]);
+    /// let island_b = Routes::from(vec![Route::new(vec![1,3,0,2])]);
+    /// let offspring = island_a.crossbreed(&island_b, 5);
+    /// assert!(offspring.len() <= 5);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn crossbreed(&self, other: &Routes, k: usize) -> Routes {
+        if self.is_empty() || other.is_empty() {
+            return Routes::from(vec![]);
+        }
+        let parents_a = self.iter().collect::<Vec<&Route>>();
+        let parents_b = other.iter().collect::<Vec<&Route>>();
+        Routes::from(
+            (0..k)
+                .map(|_| {
+                    parents_a[get_random_elem_from_range(0..parents_a.len())]
+                        .crossover(parents_b[get_random_elem_from_range(0..parents_b.len())])
+                })
+                .collect::<Vec<Route>>(),
+        )
+    }
+    /// Evolve the population like `evolve`, but draw all crossovers and mutations from `rng`,
+    /// e.g. a seeded `StdRng` for reproducible runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `mutate_prob` - The probabilty of an inviduals beeing mutated.
+    /// * `rng` - The random number generator the offspring are drawn from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_tsp::routes::Routes;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// let routes = Routes::from(vec![Route::new(vec![0,1,2,3]), Route::new(vec![1,0,3,2])]);
+    /// assert_eq!(
+    ///     routes.evolve_with_rng(0.5, &mut StdRng::seed_from_u64(7)),
+    ///     routes.evolve_with_rng(0.5, &mut StdRng::seed_from_u64(7))
+    /// );
+    /// ```
+    pub fn evolve_with_rng<R: Rng + ?Sized>(&self, mutate_prob: f32, rng: &mut R) -> Routes {
+        // The routes are sorted, so that the result only depends on `rng` and not on the
+        // order in which they are stored.
+        let mut parents = self.iter().collect::<Vec<&Route>>();
+        parents.sort_unstable_by(|route_a, route_b| route_a.indexes.cmp(&route_b.indexes));
+        if parents.len() < 2 {
+            return self.clone().add_vec_route(
+                parents
+                    .iter()
+                    .map(|route| (*route).clone().mutate_with_rng(1.0, rng))
+                    .collect(),
+            );
+        }
+        let mut offspring = Vec::with_capacity(parents.len() * parents.len());
+        for (idx, parent_a) in parents.iter().enumerate() {
+            for (other_idx, parent_b) in parents.iter().enumerate() {
+                if idx != other_idx {
+                    offspring.push(
+                        parent_a
+                            .crossover_with_rng(parent_b, rng)
+                            .mutate_with_rng(mutate_prob, rng),
+                    );
+                }
+            }
+        }
+        offspring.extend(parents.into_iter().cloned());
+        Routes::from(offspring)
+    }
+    /// Evolve the population like `evolve`, but cross every pair of routes only with probability
+    /// `crossover_rate`. A pair that is not crossed gives an offspring that is a mutated copy of
+    /// its first route, so the number of offspring stays the same.
+    ///
+    /// # Arguments
+    ///
+    /// * `mutate_prob` - The probabilty of an inviduals beeing mutated.
+    /// * `crossover_rate` - The probability that a pair of routes is crossed, 1.0 is `evolve`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let routes = Routes::from(vec![Route::new(vec![0,1,2,3]), Route::new(vec![1,0,3,2])]);
+    /// assert_eq!(routes.evolve_with_crossover_rate(0.0, 0.0), routes);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn evolve_with_crossover_rate(&self, mutate_prob: f32, crossover_rate: f64) -> Routes {
+        if crossover_rate >= 1.0 || self.len() < 2 {
+            return self.evolve(mutate_prob);
+        }
+        let mut rng = rand::thread_rng();
+        let mut offspring = Vec::with_capacity(self.len() * self.len());
+        for (idx, parent_a) in self.iter().enumerate() {
+            for (other_idx, parent_b) in self.iter().enumerate() {
+                if idx != other_idx {
+                    let child = if rng.gen::<f64>() < crossover_rate {
+                        parent_a.crossover(parent_b)
+                    } else {
+                        parent_a.clone()
+                    };
+                    offspring.push(child.mutate(mutate_prob));
+                }
+            }
+        }
+        offspring.extend(self.iter().cloned());
+        Routes::from(offspring)
+    }
+    /// Evolve the population like `evolve_with_crossover_rate`, but cross the pairs of routes
+    /// with `crossover_operator` instead of the ordered crossover and mutate the offspring with
+    /// `mutation_operator`.
+    ///
+    /// # Arguments
+    ///
+    /// * `mutate_prob` - The probabilty of an inviduals beeing mutated.
+    /// * `crossover_rate` - The probability that a pair of routes is crossed.
+    /// * `crossover_operator` - The crossover operator.
+    /// * `mutation_operator` - The mutation operator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::route::{CrossoverOperator, MutationOperator, Route};
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let routes = Routes::from(vec![Route::new(vec![0,1,2,3]), Route::new(vec![1,0,3,2])]);
+    /// let evolved_routes = routes.evolve_with_operators(
+    ///     0.5,
+    ///     1.0,
+    ///     CrossoverOperator::EdgeRecombination,
+    ///     MutationOperator::Inversion,
+    /// );
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn evolve_with_operators(
+        &self,
+        mutate_prob: f32,
+        crossover_rate: f64,
+        crossover_operator: CrossoverOperator,
+        mutation_operator: MutationOperator,
+    ) -> Routes {
+        assert!(
+            crossover_operator != CrossoverOperator::Spatial,
+            "{}",
+            SPATIAL_WITHOUT_COORDINATES
+        );
+        assert!(
+            crossover_operator != CrossoverOperator::Candidate
+                && mutation_operator != MutationOperator::Candidate,
+            "{}",
+            CANDIDATES_MISSING
+        );
+        if (crossover_operator == CrossoverOperator::Ordered
+            && mutation_operator == MutationOperator::Insertion)
+            || self.is_empty()
+        {
+            return self.evolve_with_crossover_rate(mutate_prob, crossover_rate);
+        }
+        self.evolve_with_crossover(
+            mutate_prob,
+            crossover_rate,
+            &mut rand::thread_rng(),
+            None,
+            |parent_a, parent_b, rng| {
+                parent_a.crossover_with_operator(parent_b, crossover_operator, rng)
+            },
+            |child, prob, rng| child.mutate_with_operator(prob, mutation_operator, rng),
+        )
+    }
+    /// Evolve the population like `evolve_with_operators` with `CrossoverOperator::Spatial`:
+    /// pairs of routes exchange geographically contiguous segments, see
+    /// `Route::spatial_crossover_with_rng`.
+    ///
+    /// # Arguments
+    ///
+    /// * `mutate_prob` - The probabilty of an inviduals beeing mutated.
+    /// * `crossover_rate` - The probability that a pair of routes is crossed.
+    /// * `mutation_operator` - The mutation operator.
+    /// * `coordinates` - The coordinates of the nodes, e.g. from `DistanceMat::coordinates`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::route::{MutationOperator, Route};
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let distance_matrix = DistanceMat::from_coordinates(&[(0.0, 0.0), (4.0, 0.0), (4.0, 3.0), (0.0, 3.0)]);
+    /// let routes = Routes::from(vec![Route::new(vec![0,1,2,3]), Route::new(vec![1,0,3,2])]);
+    /// let evolved_routes = routes.evolve_spatial(
+    ///     0.5,
+    ///     1.0,
+    ///     MutationOperator::Insertion,
+    ///     distance_matrix.coordinates().unwrap(),
+    /// );
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn evolve_spatial(
+        &self,
+        mutate_prob: f32,
+        crossover_rate: f64,
+        mutation_operator: MutationOperator,
+        coordinates: &[(f64, f64)],
+    ) -> Routes {
+        self.evolve_with_crossover(
+            mutate_prob,
+            crossover_rate,
+            &mut rand::thread_rng(),
+            None,
+            |parent_a, parent_b, rng| {
+                parent_a.spatial_crossover_with_rng(parent_b, coordinates, rng)
+            },
+            |child, prob, rng| child.mutate_with_operator(prob, mutation_operator, rng),
+        )
+    }
+    /// Evolve the population like `evolve_with_operators`, but restrict
+    /// `CrossoverOperator::Candidate` and `MutationOperator::Candidate` to the near neighbors
+    /// of `candidates`, see `Route::candidate_crossover_with_rng` and
+    /// `Route::candidate_mutate_with_rng`. The other operators are applied as usual.
+    ///
+    /// # Arguments
+    ///
+    /// * `mutate_prob` - The probabilty of an inviduals beeing mutated.
+    /// * `crossover_rate` - The probability that a pair of routes is crossed.
+    /// * `crossover_operator` - The crossover operator.
+    /// * `mutation_operator` - The mutation operator.
+    /// * `candidates` - The near neighbors of every node, e.g. from `DistanceMat::candidate_list`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::route::{CrossoverOperator, MutationOperator, Route};
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let distance_matrix = DistanceMat::from_coordinates(&[(0.0, 0.0), (4.0, 0.0), (4.0, 3.0), (0.0, 3.0)])
+    ///     .with_candidate_list(2);
+    /// let routes = Routes::from(vec![Route::new(vec![0,1,2,3]), Route::new(vec![1,0,3,2])]);
+    /// let evolved_routes = routes.evolve_with_candidates(
+    ///     0.5,
+    ///     1.0,
+    ///     CrossoverOperator::Candidate,
+    ///     MutationOperator::Candidate,
+    ///     distance_matrix.candidate_list().unwrap(),
+    /// );
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn evolve_with_candidates(
+        &self,
+        mutate_prob: f32,
+        crossover_rate: f64,
+        crossover_operator: CrossoverOperator,
+        mutation_operator: MutationOperator,
+        candidates: &CandidateList,
+    ) -> Routes {
+        assert!(
+            crossover_operator != CrossoverOperator::Spatial,
+            "{}",
+            SPATIAL_WITHOUT_COORDINATES
+        );
+        self.evolve_with_crossover(
+            mutate_prob,
+            crossover_rate,
+            &mut rand::thread_rng(),
+            None,
+            |parent_a, parent_b, rng| match crossover_operator {
+                CrossoverOperator::Candidate => {
+                    parent_a.candidate_crossover_with_rng(parent_b, candidates, rng)
+                }
+                _ => parent_a.crossover_with_operator(parent_b, crossover_operator, rng),
+            },
+            |child, prob, rng| {
+                mutate_with_candidates(child, prob, mutation_operator, Some(candidates), rng)
+            },
+        )
+    }
+    /// Cross every ordered pair of routes with `crossover` at the crossover rate, mutate the
+    /// offspring with `mutate_child` and keep the parents. The parents are sorted first, so
+    /// that the offspring only depend on `rng` and not on the order in which they are stored.
+    ///
+    /// # Arguments
+    ///
+    /// * `mutate_prob` - The probabilty of an inviduals beeing mutated.
+    /// * `crossover_rate` - The probability that a pair of routes is crossed.
+    /// * `rng` - The random number generator all random choices are drawn from.
+    /// * `counts` - Where to count the applied operators, `None` to not count them.
+    /// * `crossover` - Creates the child of two parents.
+    /// * `mutate_child` - Mutates a child with the given probability.
+    ///
+    fn evolve_with_crossover<R: Rng + ?Sized>(
+        &self,
+        mutate_prob: f32,
+        crossover_rate: f64,
+        rng: &mut R,
+        mut counts: Option<&mut OperatorCounts>,
+        mut crossover: impl FnMut(&Route, &Route, &mut R) -> Route,
+        mutate_child: impl Fn(Route, f32, &mut R) -> Route,
+    ) -> Routes {
+        let mut parents = self.iter().collect::<Vec<&Route>>();
+        parents.sort_unstable_by(|route_a, route_b| route_a.indexes.cmp(&route_b.indexes));
+        // A mutation may leave the route unchanged, so it only counts if the route differs.
+        let mut mutate = |child: Route, prob: f32, rng: &mut R| match counts.as_deref_mut() {
+            Some(counts) => {
+                let mutated = mutate_child(child.clone(), prob, rng);
+                counts.mutations += usize::from(mutated != child);
+                mutated
+            }
+            None => mutate_child(child, prob, rng),
+        };
+        if parents.len() < 2 {
+            // Without a partner the route reproduces by mutation alone, as in `evolve`.
+            let offspring = parents
+                .iter()
+                .map(|route| mutate((*route).clone(), 1.0, rng))
+                .collect();
+            return self.clone().add_vec_route(offspring);
+        }
+        let mut n_crossovers = 0;
+        let mut offspring = Vec::with_capacity(parents.len() * parents.len());
+        for (idx, parent_a) in parents.iter().enumerate() {
+            for (other_idx, parent_b) in parents.iter().enumerate() {
+                if idx != other_idx {
+                    let child = if rng.gen::<f64>() < crossover_rate {
+                        n_crossovers += 1;
+                        crossover(parent_a, parent_b, rng)
+                    } else {
+                        (*parent_a).clone()
+                    };
+                    offspring.push(mutate(child, mutate_prob, rng));
+                }
+            }
+        }
+        if let Some(counts) = counts {
+            counts.crossovers += n_crossovers;
+        }
+        offspring.extend(parents.into_iter().cloned());
+        Routes::from(offspring)
+    }
+    /// Evolve the population like `evolve`, but only keep the `elitism` fittest parents: they
+    /// are copied into the offspring unchanged, while all other parents are replaced by their
+    /// offspring. The best route of the population is never lost as long as `elitism` is at
+    /// least 1, even if only the offspring would survive otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `mutate_prob` - The probabilty of an inviduals beeing mutated.
+    /// * `elitism` - How many of the fittest parents are kept unchanged.
+    /// * `distance_mat` - The distance matrix the fitness is computed on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let routes = Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]);
+    /// let evolved_routes = routes.evolve_with_elitism(0.5, 1, &distance_matrix);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn evolve_with_elitism(
+        &self,
+        mutate_prob: f32,
+        elitism: usize,
+        distance_mat: &DistanceMat,
+    ) -> Routes {
+        discard_parents(self.evolve(mutate_prob), self)
+            .add_vec_route(self.get_n_fittest(elitism, distance_mat))
+    }
+    /// Evolve the population like `evolve`, but instead of crossing every pair of routes, sample
+    /// the parents of every offspring with a probability proportional to their fitness rank: the
+    /// best of `n` routes has weight `n`, the worst weight 1. As many offspring as in `evolve`
+    /// are bred, e.g. `n * (n - 1)`, and the parents are kept, so better routes reproduce more
+    /// often while the offspring count stays fixed.
+    ///
+    /// # Arguments
+    ///
+    /// * `mutate_prob` - The probabilty of an inviduals beeing mutated.
+    /// * `distance_mat` - The distance matrix the fitness rank is computed on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::routes::Routes;
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let routes = Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]);
+    /// let evolved_routes = routes.evolve_rank_weighted(0.5, &distance_matrix);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn evolve_rank_weighted(&self, mutate_prob: f32, distance_mat: &DistanceMat) -> Routes {
+        if self.len() < 2 {
+            return self.evolve(mutate_prob);
+        }
+        // The fittest route comes first and gets the highest weight.
+        let parents = self.get_n_fittest(self.len(), distance_mat);
+        let n_parents = parents.len();
+        let ranks = WeightedIndex::new((1..=n_parents).rev()).unwrap();
+        let mut rng = rand::thread_rng();
+        let offspring = (0..(n_parents * (n_parents - 1)))
+            .map(|_| {
+                let parent_a = ranks.sample(&mut rng);
+                // Don't crossover a route with itself.
+                let parent_b = loop {
+                    let parent_b = ranks.sample(&mut rng);
+                    if parent_b != parent_a {
+                        break parent_b;
+                    }
+                };
+                parents[parent_a]
+                    .crossover(&parents[parent_b])
+                    .mutate(mutate_prob)
+            })
+            .collect::<Vec<Route>>();
+        Routes::from(parents).add_vec_route(offspring)
+    }
+    /// Keep only one route of every round-trip, e.g. of all rotations of a route and, in a
+    /// symmetric matrix, of its reverse as well, see `DistanceMat::tour_key`. Of the routes
+    /// with the same key the one with the smallest nodes is kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distance matrix that decides which routes are the same round-trip.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 2.0, 3.0],
+    ///     vec![1.0, 0.0, 4.0, 5.0],
+    ///     vec![2.0, 4.0, 0.0, 6.0],
+    ///     vec![3.0, 5.0, 6.0, 0.0],
+    /// ]);
+    /// let routes = Routes::from(vec![
+    ///     Route::new(vec![0, 1, 2, 3]),
+    ///     Route::new(vec![2, 3, 0, 1]),
+    ///     Route::new(vec![3, 2, 1, 0]),
+    ///     Route::new(vec![0, 2, 1, 3]),
+    /// ]);
+    /// assert_eq!(routes.dedup_tours(&distance_matrix).len(), 2);
+    /// ```
+    pub fn dedup_tours(self, distance_mat: &DistanceMat) -> Self {
+        let mut routes = self.routes.into_iter().collect::<Vec<Route>>();
+        routes.sort_unstable_by(|route_a, route_b| route_a.indexes.cmp(&route_b.indexes));
+        let mut seen: HashSet<_> =
+            HashSet::with_capacity_and_hasher(routes.len(), Default::default());
+        Routes::from(
+            routes
+                .into_iter()
+                .filter(|route| seen.insert(distance_mat.tour_key(&route.indexes)))
+                .collect::<Vec<Route>>(),
+        )
+    }
+    /// Apply a `DuplicatePolicy` to the population: routes that describe the same round-trip as
+    /// another route are either kept, removed like in `Routes::dedup_tours`, or replaced with
+    /// random routes of further round-trips like in `Routes::top_up_with_rng`. If random routes
+    /// keep repeating known round-trips, e.g. because the instance has only a few of them, the
+    /// population stays smaller.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - What happens to routes of the same round-trip.
+    /// * `distance_mat` - The distance matrix whose `tour_key` decides which routes describe
+    ///   the same round-trip.
+    /// * `rng` - The random number generator the replacements are drawn from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_tsp::routes::Routes;
+    /// use genetic_algorithm_tsp::selection::DuplicatePolicy;
+    /// use rand::SeedableRng;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 2.0, 3.0],
+    ///     vec![1.0, 0.0, 4.0, 5.0],
+    ///     vec![2.0, 4.0, 0.0, 6.0],
+    ///     vec![3.0, 5.0, 6.0, 0.0],
+    /// ]);
+    /// let routes = Routes::from(vec![
+    ///     Route::new(vec![0, 1, 2, 3]),
+    ///     Route::new(vec![2, 3, 0, 1]),
+    ///     Route::new(vec![0, 2, 1, 3]),
+    /// ]);
+    /// let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+    /// let deduped =
+    ///     routes.clone().apply_duplicate_policy(DuplicatePolicy::Dedupe, &distance_matrix, &mut rng);
+    /// assert_eq!(deduped.len(), 2);
+    /// // The four nodes only have three round-trips.
+    /// let replaced = routes.apply_duplicate_policy(
+    ///     DuplicatePolicy::DedupeWithRandomReplacement,
+    ///     &distance_matrix,
+    ///     &mut rng,
+    /// );
+    /// assert_eq!(replaced.len(), 3);
+    /// assert_eq!(replaced.clone().dedup_tours(&distance_matrix), replaced);
+    /// ```
+    pub fn apply_duplicate_policy<R: Rng + ?Sized>(
+        self,
+        policy: DuplicatePolicy,
+        distance_mat: &DistanceMat,
+        rng: &mut R,
+    ) -> Self {
+        match policy {
+            DuplicatePolicy::KeepEquivalent => self,
+            DuplicatePolicy::Dedupe => self.dedup_tours(distance_mat),
+            DuplicatePolicy::DedupeWithRandomReplacement => {
+                let n_routes = self.len();
+                let mut population = self.dedup_tours(distance_mat);
+                let mut n_fruitless = 0;
+                while population.len() < n_routes && n_fruitless < MAX_FRUITLESS_REPLACEMENTS {
+                    let n_before = population.len();
+                    population = population
+                        .top_up_with_rng(n_routes, rng)
+                        .dedup_tours(distance_mat);
+                    n_fruitless = if population.len() > n_before {
+                        0
+                    } else {
+                        n_fruitless + 1
+                    };
+                }
+                population
+            }
+        }
+    }
+    /// Add n random nodes to your current pool.
+    ///
+    /// # Arguments:
+    ///
+    /// `n_random_nodes`: The number of random nodes that should be added.
+    ///
+    /// # Examples
+    /// ```
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_tsp::routes::Routes;
+    ///
+    /// let a_single_route = Routes::from(vec![Route::new(vec![0,1,2])]);
+    /// println!("{}", a_single_route.add_n_random_nodes(1));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn add_n_random_nodes(self, n_random_nodes: usize) -> Self {
+        let number_of_nodes = self.get_n_nodes();
+        self.combine_routes(Routes::random(n_random_nodes, number_of_nodes))
+    }
+    /// Given your pool of current routes, compute the fitness of your individuals to solve the
+    /// problem at hand.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distances between nodes that is neccessary to computes how well the route
+    ///   work in terms of the TSP
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::routes::Routes;
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let routes = Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]);
+    /// println!("Your routes's fitnesses: {:?}", routes.fitnesses(&distance_matrix));
+    /// ```
+    pub fn fitnesses(&self, distance_mat: &DistanceMat) -> Vec<(f64, &Route)> {
+        let routes = self.iter().collect::<Vec<&Route>>();
+        route_fitnesses(&routes, distance_mat)
+            .into_iter()
+            .zip(routes)
+            .collect()
+    }
+    /// Get the n fittest individuals in your routes. Routes with the same fitness are ordered as
+    /// set by `DistanceMat::with_tie_breaking`, by default by their canonical tour, so that the
+    /// selection doesn't depend on the order in which the routes are stored.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The number of individuals you would like to get.
+    /// * `distance_mat` - The distance matrix the fitness should be evaluated on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::routes::Routes;
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let routes = Routes::from(vec![Route::new(vec![2,1,0]), Route::new(vec![0,1,2])]);
+    /// assert_eq!(routes.get_n_fittest(1, &distance_matrix), vec![Route::new(vec![0,1,2])]);
+    /// ```
+    pub fn get_n_fittest(&self, n: usize, distance_mat: &DistanceMat) -> Vec<Route> {
+        let routes = self.iter().collect::<Vec<&Route>>();
+        let fitnesses = route_fitnesses(&routes, distance_mat);
+        let order = match distance_mat.tie_breaking() {
+            TieBreaking::CanonicalTour => argsort_with_tie_breaker(
+                &fitnesses,
+                &routes
+                    .iter()
+                    .map(|route| (CanonicalOrder::new(&route.indexes), &route.indexes))
+                    .collect::<Vec<(CanonicalOrder<'_>, &Vec<usize>)>>(),
+            ),
+            TieBreaking::Unordered => argsort(&fitnesses),
+        };
+        if distance_mat.tour_equivalence() == TourEquivalence::RoundTrip {
+            // Only the fittest route of every round-trip survives.
+            let mut seen: HashSet<_> = HashSet::with_capacity_and_hasher(n, Default::default());
+            return order
+                .into_iter()
+                .filter(|idx| seen.insert(distance_mat.tour_key(&routes[*idx].indexes)))
+                .take(n)
+                .map(|idx| routes[idx].clone())
+                .collect();
+        }
+        order
+            .into_iter()
+            .take(n)
+            .map(|idx| routes[idx].clone())
+            .collect()
+    }
+    /// Get the n fittest individuals in your routes as new routes object. This is typically used
+    /// to select the top n inidividuals, before continuing to evolve the routes further.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The number of individuals you would like to have.
+    /// * `distance_mat` - The distance matrix the fitness should be evaluated on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::routes::Routes;
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let routes = Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]);
+    /// let my_fittest_routes = routes.get_fittest_population(2, &distance_matrix);
+    /// ```
+    pub fn get_fittest_population(&self, n: usize, distance_mat: &DistanceMat) -> Routes {
+        Routes {
+            routes: route_vec_to_xx_hashset(self.get_n_fittest(n, distance_mat)),
+        }
+    }
+    /// Iterate over the individuals of your population.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::routes::Routes;
+    /// use genetic_algorithm_tsp::route::Route;
+    ///
+    /// let routes = Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]);
+    /// for route in routes.iter(){
+    ///     println!("{:?}", route);
+    /// }
+    /// ```
+    pub fn iter(&self) -> hash_set::Iter<'_, Route> {
+        self.routes.iter()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> Population<'a> for Routes {
+    type Individual = Route;
+    type IndividualCollection = hash_set::Iter<'a, Route>;
+
+    /// Compute the fitness of every route, see `Routes::fitnesses`.
+    fn fitnesses(&'a self, distance_mat: &'a DistanceMat) -> Vec<(f64, &'a Route)> {
+        Routes::fitnesses(self, distance_mat)
+    }
+    /// Get the n fittest routes, see `Routes::get_n_fittest`.
+    fn get_n_fittest(&self, n: usize, distance_mat: &DistanceMat) -> Vec<Route> {
+        Routes::get_n_fittest(self, n, distance_mat)
+    }
+    /// Get the n fittest routes as new routes object, see `Routes::get_fittest_population`.
+    fn get_fittest_population(&self, n: usize, distance_mat: &DistanceMat) -> Routes {
+        Routes::get_fittest_population(self, n, distance_mat)
+    }
+    /// Evolve your population.
+    ///
+    /// The evolution consists of the following stages:
+    /// 1) `crossover` between all 1,...,n routes excluding the route itself.
+    /// 2) `mutate` is applied to all individuals.
+    ///
+    /// A population with a single route has no partner for `crossover`. In that case the
+    /// route reproduces by mutation alone and the mutation is always applied, as the
+    /// offspring would otherwise only be a copy of its parent.
+    ///
+    /// # Arguments
+    ///
+    /// * `mutate_prob` - The probabilty of an inviduals beeing mutated. Is applied via `individuals.mutate`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::routes::Routes;
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_traits::Population;
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let routes = Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]);
+    /// let evolved_routes = routes.evolve(0.5);
+    /// ```
+    fn evolve(&self, mutate_prob: f32) -> Routes {
+        if self.len() < 2 {
+            return self
+                .clone()
+                .add_vec_route(self.iter().map(|route| route.clone().mutate(1.0)).collect());
+        }
+        let mutated_individuals = self.evolve_individuals(mutate_prob);
+        Routes {
+            routes: route_vec_to_xx_hashset(mutated_individuals),
+        }
+    }
+    /// Cross every route with every other route and mutate the offspring, the parents are kept
+    /// as well. With the `rayon` feature the offspring are bred in parallel.
+    ///
+    /// # Arguments
+    ///
+    /// * `mutate_prob` - The probabilty of an inviduals beeing mutated. Is applied via `individuals.mutate`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::routes::Routes;
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_traits::Population;
+    ///
+    /// let routes = Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]);
+    /// assert_eq!(routes.evolve_individuals(0.5).len(), 4);
+    /// ```
+    fn evolve_individuals(&'a self, mutate_prob: f32) -> Vec<Route> {
+        let parents = self.iter().collect::<Vec<&Route>>();
+        let parents_ref = &parents;
+        // The offspring of the route with index `idx` and all other routes.
+        let breed = move |idx: usize| {
+            parents_ref
+                .iter()
+                .enumerate()
+                .filter(move |(other_idx, _)| *other_idx != idx)
+                .map(move |(_, parent_b)| parents_ref[idx].crossover(parent_b).mutate(mutate_prob))
+        };
+        #[cfg(feature = "rayon")]
+        let offspring = (0..parents.len())
+            .into_par_iter()
+            .flat_map_iter(breed)
+            .collect::<Vec<Route>>();
+        #[cfg(not(feature = "rayon"))]
+        let offspring = (0..parents.len()).flat_map(breed).collect::<Vec<Route>>();
+        offspring
+            .into_iter()
+            .chain(parents.iter().map(|route| (*route).clone()))
+            .collect()
+    }
+    /// Iterate over the routes, see `Routes::iter`.
+    fn iter(&'a self) -> hash_set::Iter<'a, Route> {
+        Routes::iter(self)
+    }
+}
+
+/// Compute the fitness of every route, in parallel with the `rayon` feature.
+///
+/// # Arguments
+///
+/// * `routes` - The routes whose fitness should be computed.
+/// * `distance_mat` - The distance matrix the fitness is computed on.
+///
+fn route_fitnesses(routes: &[&Route], distance_mat: &DistanceMat) -> Vec<f64> {
+    #[cfg(feature = "rayon")]
+    let routes = routes.par_iter();
+    #[cfg(not(feature = "rayon"))]
+    let routes = routes.iter();
+    routes.map(|route| route.fitness(distance_mat)).collect()
+}
+
+/// Given an initial population evolve it for `n_generations` while keeping `size_generation`
+/// individuals. The final population will be returned.
+///
+/// # Arguments
+///
+/// * `initial_population` - Your initial population that should be evolved.
+/// * `n_generations` - How many times should your population be evolved?
+/// * `size_generation` - How many individuals should be kept after evolving it.
+/// * `distance_matrix` - The distance matrix on which the fitness will be computed on.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::routes::{Routes, evolve_population};
+/// use genetic_algorithm_tsp::route::Route;
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+///
+/// let evolved_population = evolve_population(
+///     Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]),
+///     10,
+///     10,
+///     &DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]),
+///     0
+/// );
+/// ```
+#[cfg(feature = "std")]
+pub fn evolve_population(
+    initial_population: Routes,
+    n_generations: usize,
+    size_generation: usize,
+    distance_matrix: &DistanceMat,
+    n_jobs: usize,
+) -> Routes {
+    evolve_population_generations(
+        initial_population,
+        n_generations,
+        size_generation,
+        distance_matrix,
+        n_jobs,
+        GenerationOptions::default(),
+    )
+}
+/// Evolve a population like `evolve_population`, but draw all randomness from `rng`, so that a
+/// seeded `StdRng` gives the same final population in every run, e.g. for tests and benchmarks.
+/// As the threads of `evolve_population` can't share one random number generator, the evolution
+/// is single-threaded.
+///
+/// # Arguments
+///
+/// * `initial_population` - Your initial population that should be evolved.
+/// * `n_generations` - How many times should your population be evolved?
+/// * `size_generation` - How many individuals should be kept after evolving it.
+/// * `distance_matrix` - The distance matrix on which the fitness will be computed on.
+/// * `rng` - The random number generator all crossovers and mutations are drawn from.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::routes::{Routes, evolve_population_with_rng};
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+/// use rand::rngs::StdRng;
+/// use rand::SeedableRng;
+///
+/// let distance_matrix = DistanceMat::new(vec![
+///     vec![0.0, 1.0, 2.0, 3.0],
+///     vec![1.0, 0.0, 3.0, 1.0],
+///     vec![2.0, 3.0, 0.0, 2.0],
+///     vec![3.0, 1.0, 2.0, 0.0],
+/// ]);
+/// let run = |seed| {
+///     let mut rng = StdRng::seed_from_u64(seed);
+///     let initial_population = Routes::random_with_rng(5, 4, &mut rng);
+///     evolve_population_with_rng(initial_population, 10, 5, &distance_matrix, &mut rng)
+/// };
+/// assert_eq!(run(42), run(42));
+/// ```
+pub fn evolve_population_with_rng<R: Rng + ?Sized>(
+    initial_population: Routes,
+    n_generations: usize,
+    size_generation: usize,
+    distance_matrix: &DistanceMat,
+    rng: &mut R,
+) -> Routes {
+    (0..n_generations).fold(initial_population, |population, _| {
+        population
+            .evolve_with_rng(0.5, rng)
+            .get_fittest_population(size_generation, distance_matrix)
+    })
+}
+/// Evolve a population like `evolve_population`, but after each selection top the population
+/// up with random routes, so that it always contains `size_generation` individuals.
+///
+/// # Arguments
+///
+/// * `initial_population` - Your initial population that should be evolved.
+/// * `n_generations` - How many times should your population be evolved?
+/// * `size_generation` - How many individuals should be kept after evolving it.
+/// * `distance_matrix` - The distance matrix on which the fitness will be computed on.
+/// * `n_jobs` - How many threads should be used, 0 means single-threaded. Without the `std`
+///   feature there are no threads and `n_jobs` is ignored.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::routes::{Routes, evolve_population_with_top_up};
+/// use genetic_algorithm_tsp::route::Route;
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+///
+/// let evolved_population = evolve_population_with_top_up(
+///     Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]),
+///     10,
+///     5,
+///     &DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]),
+///     0
+/// );
+/// assert_eq!(evolved_population.len(), 5);
+/// ```
+#[cfg(feature = "std")]
+pub fn evolve_population_with_top_up(
+    initial_population: Routes,
+    n_generations: usize,
+    size_generation: usize,
+    distance_matrix: &DistanceMat,
+    n_jobs: usize,
+) -> Routes {
+    evolve_population_generations(
+        initial_population,
+        n_generations,
+        size_generation,
+        distance_matrix,
+        n_jobs,
+        GenerationOptions {
+            top_up: true,
+            ..GenerationOptions::default()
+        },
+    )
+}
+/// Evolve a population like `evolve_population`, but improve the `n_refined` fittest routes of
+/// every generation with 2-opt as in `Routes::refine_fittest`. This hybrid of the genetic
+/// algorithm and local search (a memetic algorithm) finds much better routes on larger instances,
+/// on which the genetic algorithm alone converges slowly.
+///
+/// # Arguments
+///
+/// * `initial_population` - Your initial population that should be evolved.
+/// * `n_generations` - How many times should your population be evolved?
+/// * `size_generation` - How many individuals should be kept after evolving it.
+/// * `distance_matrix` - The distance matrix on which the fitness will be computed on.
+/// * `n_jobs` - How many threads should be used, 0 means single-threaded. Without the `std`
+///   feature there are no threads and `n_jobs` is ignored.
+/// * `n_refined` - How many of the fittest routes are improved with 2-opt per generation.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::routes::{Routes, evolve_population_memetic};
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+///
+/// let evolved_population = evolve_population_memetic(
+///     Routes::random(5, 3),
+///     10,
+///     5,
+///     &DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]),
+///     0,
+///     2,
+/// );
+/// ```
+#[cfg(feature = "std")]
+pub fn evolve_population_memetic(
+    initial_population: Routes,
+    n_generations: usize,
+    size_generation: usize,
+    distance_matrix: &DistanceMat,
+    n_jobs: usize,
+    n_refined: usize,
+) -> Routes {
+    evolve_population_generations(
+        initial_population,
+        n_generations,
+        size_generation,
+        distance_matrix,
+        n_jobs,
+        GenerationOptions {
+            n_refined,
+            ..GenerationOptions::default()
+        },
+    )
+}
+/// Evolve a population with the parameters of `config`, but refine the `n_refined` fittest
+/// routes of every generation with 2-opt following `policy`, see
+/// `EvolutionConfig::with_local_search_policy`. Which pivoting rule and neighborhood order work
+/// best differs between clustered and uniform instances, so both are worth benchmarking.
+///
+/// # Arguments
+///
+/// * `initial_population` - Your initial population that should be evolved.
+/// * `distance_matrix` - The distance matrix on which the fitness will be computed on.
+/// * `config` - The parameters of the evolution.
+/// * `n_refined` - How many of the fittest routes are improved with 2-opt per generation.
+/// * `policy` - How the local search moves through the 2-opt neighborhood.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+/// use genetic_algorithm_tsp::evolution::EvolutionConfig;
+/// use genetic_algorithm_tsp::route::{LocalSearchPolicy, NeighborhoodOrder, PivotingRule};
+/// use genetic_algorithm_tsp::routes::{Routes, evolve_population_memetic_with_policy};
+///
+/// let evolved_population = evolve_population_memetic_with_policy(
+///     Routes::random(5, 3),
+///     &DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]),
+///     EvolutionConfig::new().with_n_generations(10).with_size_generation(5),
+///     2,
+///     LocalSearchPolicy::new(PivotingRule::BestImprovement, NeighborhoodOrder::Random),
+/// );
+/// ```
+#[cfg(feature = "std")]
+pub fn evolve_population_memetic_with_policy(
+    initial_population: Routes,
+    distance_matrix: &DistanceMat,
+    config: EvolutionConfig,
+    n_refined: usize,
+    policy: LocalSearchPolicy,
+) -> Routes {
+    Evolution::new(
+        distance_matrix,
+        config
+            .with_n_refined(n_refined)
+            .with_local_search_policy(policy),
+    )
+    .run(initial_population)
+    .0
+}
+/// Evolve a population with the parameters of `config`, but refine the `n_refined` fittest
+/// routes of every generation in the neighborhoods of `local_search` instead of with 2-opt
+/// alone, e.g. a `LocalSearchChain` of 2-opt, Or-opt and 3-opt, see
+/// `Evolution::with_local_search`.
+///
+/// # Arguments
+///
+/// * `initial_population` - Your initial population that should be evolved.
+/// * `distance_matrix` - The distance matrix on which the fitness will be computed on.
+/// * `config` - The parameters of the evolution.
+/// * `n_refined` - How many of the fittest routes are improved per generation.
+/// * `local_search` - The neighborhoods the fittest routes are improved in.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+/// use genetic_algorithm_tsp::evolution::EvolutionConfig;
+/// use genetic_algorithm_tsp::local_search::{LocalSearchChain, OrOpt, ThreeOpt, TwoOpt};
+/// use genetic_algorithm_tsp::routes::{Routes, evolve_population_memetic_with_local_search};
+///
+/// let evolved_population = evolve_population_memetic_with_local_search(
+///     Routes::random(5, 3),
+///     &DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]),
+///     EvolutionConfig::new().with_n_generations(10).with_size_generation(5),
+///     2,
+///     &LocalSearchChain::new().then(TwoOpt::default()).then(OrOpt).then(ThreeOpt),
+/// );
+/// ```
+#[cfg(feature = "std")]
+pub fn evolve_population_memetic_with_local_search(
+    initial_population: Routes,
+    distance_matrix: &DistanceMat,
+    config: EvolutionConfig,
+    n_refined: usize,
+    local_search: &dyn LocalSearch,
+) -> Routes {
+    Evolution::new(distance_matrix, config.with_n_refined(n_refined))
+        .with_local_search(local_search)
+        .run(initial_population)
+        .0
+}
+/// Evolve a population like `evolve_population`, but keep the start of every route fixed to
+/// `pinned_prefix`, e.g. the legs of the best route that a dispatcher already accepted. The
+/// initial population and all offspring are pinned with `Routes::pin_prefix`, so only the order
+/// of the remaining nodes is evolved. Accepting more legs and evolving again gives the
+/// interactive "accept and pin" loop.
+///
+/// # Arguments
+///
+/// * `initial_population` - Your initial population that should be evolved.
+/// * `pinned_prefix` - The nodes every route has to start with.
+/// * `n_generations` - How many times should your population be evolved?
+/// * `size_generation` - How many individuals should be kept after evolving it.
+/// * `distance_matrix` - The distance matrix on which the fitness will be computed on.
+/// * `n_jobs` - How many threads should be used, 0 means single-threaded. Without the `std`
+///   feature there are no threads and `n_jobs` is ignored.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::routes::{Routes, evolve_population, evolve_population_pinned};
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+/// use genetic_algorithm_traits::Population;
+///
+/// let distance_matrix = DistanceMat::new(vec![
+///     vec![0.0, 1.0, 2.0, 3.0],
+///     vec![1.0, 0.0, 3.0, 1.0],
+///     vec![2.0, 3.0, 0.0, 2.0],
+///     vec![3.0, 1.0, 2.0, 0.0],
+/// ]);
+/// let population = evolve_population(Routes::random(5, 4), 10, 5, &distance_matrix, 0);
+/// // Accept the first leg of the best route and evolve the rest.
+/// let best_route = population.get_n_fittest(1, &distance_matrix).remove(0);
+/// let pinned = best_route.first_legs(1).to_vec();
+/// let population = evolve_population_pinned(population, &pinned, 10, 5, &distance_matrix, 0);
+/// assert!(population.iter().all(|route| route.indexes.starts_with(&pinned)));
+/// ```
+#[cfg(feature = "std")]
+pub fn evolve_population_pinned(
+    initial_population: Routes,
+    pinned_prefix: &[usize],
+    n_generations: usize,
+    size_generation: usize,
+    distance_matrix: &DistanceMat,
+    n_jobs: usize,
+) -> Routes {
+    evolve_population_generations(
+        initial_population.pin_prefix(pinned_prefix),
+        n_generations,
+        size_generation,
+        distance_matrix,
+        n_jobs,
+        GenerationOptions {
+            pinned_prefix,
+            ..GenerationOptions::default()
+        },
+    )
+}
+/// Evolve a population like `evolve_population`, but sample the parents of every generation by
+/// their fitness rank as in `Routes::evolve_rank_weighted` instead of crossing every pair.
+///
+/// # Arguments
+///
+/// * `initial_population` - Your initial population that should be evolved.
+/// * `n_generations` - How many times should your population be evolved?
+/// * `size_generation` - How many individuals should be kept after evolving it.
+/// * `distance_matrix` - The distance matrix on which the fitness will be computed on.
+/// * `n_jobs` - How many threads should be used, 0 means single-threaded. Without the `std`
+///   feature there are no threads and `n_jobs` is ignored.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::routes::{Routes, evolve_population_rank_weighted};
+/// use genetic_algorithm_tsp::route::Route;
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+///
+/// let evolved_population = evolve_population_rank_weighted(
+///     Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]),
+///     10,
+///     5,
+///     &DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]),
 ///     0
 /// );
 /// ```
-pub fn evolve_population(
+#[cfg(feature = "std")]
+pub fn evolve_population_rank_weighted(
+    initial_population: Routes,
+    n_generations: usize,
+    size_generation: usize,
+    distance_matrix: &DistanceMat,
+    n_jobs: usize,
+) -> Routes {
+    evolve_population_generations(
+        initial_population,
+        n_generations,
+        size_generation,
+        distance_matrix,
+        n_jobs,
+        GenerationOptions {
+            rank_weighted: true,
+            ..GenerationOptions::default()
+        },
+    )
+}
+/// Evolve a population like `evolve_population`, but select the routes that survive every
+/// generation with `selection` instead of keeping the fittest, e.g. to keep the population
+/// diverse for longer with a tournament selection.
+///
+/// # Arguments
+///
+/// * `initial_population` - Your initial population that should be evolved.
+/// * `n_generations` - How many times should your population be evolved?
+/// * `size_generation` - How many individuals should be kept after evolving it.
+/// * `distance_matrix` - The distance matrix on which the fitness will be computed on.
+/// * `n_jobs` - How many threads should be used, 0 means single-threaded. Without the `std`
+///   feature there are no threads and `n_jobs` is ignored.
+/// * `selection` - How the routes that survive a generation are selected.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::routes::{Routes, evolve_population_with_selection};
+/// use genetic_algorithm_tsp::route::Route;
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+/// use genetic_algorithm_tsp::selection::Selection;
+///
+/// let evolved_population = evolve_population_with_selection(
+///     Routes::from(vec![Route::new(vec![0,1,2]), Route::new(vec![1,0,2])]),
+///     10,
+///     5,
+///     &DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]),
+///     0,
+///     Selection::Tournament { k: 3 },
+/// );
+/// ```
+#[cfg(feature = "std")]
+pub fn evolve_population_with_selection(
+    initial_population: Routes,
+    n_generations: usize,
+    size_generation: usize,
+    distance_matrix: &DistanceMat,
+    n_jobs: usize,
+    selection: Selection,
+) -> Routes {
+    evolve_population_generations(
+        initial_population,
+        n_generations,
+        size_generation,
+        distance_matrix,
+        n_jobs,
+        GenerationOptions {
+            selection,
+            ..GenerationOptions::default()
+        },
+    )
+}
+/// Evolve a population of a constrained variant, e.g. with pickups that have to be visited
+/// before their deliveries. Instead of penalizing infeasible routes, the survivors of every
+/// generation are the best routes by `Routes::get_n_fittest_feasible_first`: feasible routes
+/// first, then by their cost. There is no penalty weight that has to be tuned.
+///
+/// # Arguments
+///
+/// * `initial_population` - Your initial population that should be evolved.
+/// * `n_generations` - How many times should your population be evolved?
+/// * `size_generation` - How many individuals should be kept after evolving it.
+/// * `distance_matrix` - The distance matrix on which the fitness will be computed on.
+/// * `n_jobs` - How many threads should be used, 0 means single-threaded. Without the `std`
+///   feature there are no threads and `n_jobs` is ignored.
+/// * `constraint` - The constraint the routes should satisfy.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::constraint::Precedence;
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+/// use genetic_algorithm_tsp::routes::{Routes, evolve_population_constrained};
+///
+/// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+/// let constraint = Precedence::new(vec![(2, 1)]);
+/// let evolved_population = evolve_population_constrained(
+///     Routes::random(5, 3),
+///     10,
+///     5,
+///     &distance_matrix,
+///     0,
+///     &constraint,
+/// );
+/// ```
+#[cfg(feature = "std")]
+pub fn evolve_population_constrained(
+    initial_population: Routes,
+    n_generations: usize,
+    size_generation: usize,
+    distance_matrix: &DistanceMat,
+    n_jobs: usize,
+    constraint: &dyn Constraint,
+) -> Routes {
+    evolve_population_generations(
+        initial_population,
+        n_generations,
+        size_generation,
+        distance_matrix,
+        n_jobs,
+        GenerationOptions {
+            constraint: Some(constraint),
+            ..GenerationOptions::default()
+        },
+    )
+}
+/// The lowest cost any island (thread) of an evolution has reached so far. It is shared between
+/// the threads of `evolve_population_with_target`, so that all of them can stop as soon as one
+/// reaches the target, and it can be read from other threads to monitor the progress.
+#[derive(Debug)]
+pub struct GlobalBest {
+    /// The bits of the lowest cost as `f64`.
+    cost: AtomicU64,
+}
+impl GlobalBest {
+    /// Create a global best without any known cost, e.g. with an infinite cost.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::routes::GlobalBest;
+    ///
+    /// assert_eq!(GlobalBest::new().cost(), f64::INFINITY);
+    /// ```
+    pub fn new() -> Self {
+        GlobalBest {
+            cost: AtomicU64::new(f64::INFINITY.to_bits()),
+        }
+    }
+    /// The lowest cost that was reported so far.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::routes::GlobalBest;
+    ///
+    /// let global_best = GlobalBest::new();
+    /// global_best.update(3.0);
+    /// assert_eq!(global_best.cost(), 3.0);
+    /// ```
+    pub fn cost(&self) -> f64 {
+        f64::from_bits(self.cost.load(Ordering::Acquire))
+    }
+    /// Report the cost of a route, it becomes the global best if it is lower than all costs
+    /// that were reported before. Returns the global best afterwards.
+    ///
+    /// # Arguments
+    ///
+    /// * `cost` - The cost of the best route of an island.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::routes::GlobalBest;
+    ///
+    /// let global_best = GlobalBest::new();
+    /// assert_eq!(global_best.update(3.0), 3.0);
+    /// assert_eq!(global_best.update(5.0), 3.0);
+    /// ```
+    pub fn update(&self, cost: f64) -> f64 {
+        let previous = self
+            .cost
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |best| {
+                (cost < f64::from_bits(best)).then(|| cost.to_bits())
+            });
+        match previous {
+            Ok(_) => cost,
+            Err(best) => f64::from_bits(best),
+        }
+    }
+}
+impl Default for GlobalBest {
+    fn default() -> Self {
+        GlobalBest::new()
+    }
+}
+/// Evolve a population like `evolve_population`, but stop as soon as a route with a cost of at
+/// most `target_cost` is found. With several threads, every thread reports the cost of its best
+/// route to `global_best` after each generation, and all threads stop once any of them reached
+/// the target instead of running until the end. The final population is returned together with
+/// the `StopReason`.
+///
+/// # Arguments
+///
+/// * `initial_population` - Your initial population that should be evolved.
+/// * `n_generations` - How many times should your population be evolved at most?
+/// * `size_generation` - How many individuals should be kept after evolving it.
+/// * `distance_matrix` - The distance matrix on which the fitness will be computed on.
+/// * `n_jobs` - How many threads should be used, 0 means single-threaded. Without the `std`
+///   feature there are no threads and `n_jobs` is ignored.
+/// * `target_cost` - The cost at which the evolution can stop.
+/// * `global_best` - Where the lowest cost of all threads is shared.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::routes::{Routes, evolve_population_with_target, GlobalBest};
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+/// use genetic_algorithm_tsp::evolution::StopReason;
+///
+/// let global_best = GlobalBest::new();
+/// let (evolved_population, stop_reason) = evolve_population_with_target(
+///     Routes::random(2, 3),
+///     1000,
+///     5,
+///     &DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]),
+///     2,
+///     6.0,
+///     &global_best,
+/// );
+/// assert_eq!(global_best.cost(), 6.0);
+/// assert_eq!(stop_reason, StopReason::TargetReached);
+/// ```
+#[cfg(feature = "std")]
+pub fn evolve_population_with_target(
     initial_population: Routes,
     n_generations: usize,
     size_generation: usize,
     distance_matrix: &DistanceMat,
     n_jobs: usize,
+    target_cost: f64,
+    global_best: &GlobalBest,
+) -> (Routes, StopReason) {
+    let population = evolve_population_generations(
+        initial_population,
+        n_generations,
+        size_generation,
+        distance_matrix,
+        n_jobs,
+        GenerationOptions {
+            target: Some((global_best, target_cost)),
+            ..GenerationOptions::default()
+        },
+    );
+    let stop_reason = if global_best.cost() <= target_cost {
+        StopReason::TargetReached
+    } else {
+        StopReason::GenerationsExhausted
+    };
+    (population, stop_reason)
+}
+/// How often the operators were applied while a generation was bred, e.g. to trace which
+/// operators an evolution relies on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OperatorCounts {
+    /// How many offspring were created by crossing two parents.
+    pub crossovers: usize,
+    /// How many offspring were changed by the mutation.
+    pub mutations: usize,
+}
+/// How a single generation is evolved by `evolve_generation`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct GenerationOptions<'a> {
+    /// The probabilty of an offspring beeing mutated.
+    pub(crate) mutate_prob: f32,
+    /// The probability that a pair of routes is crossed.
+    pub(crate) crossover_rate: f64,
+    /// The crossover operator, the rank-weighted evolution always uses the ordered crossover.
+    pub(crate) crossover_operator: CrossoverOperator,
+    /// The mutation operator, the rank-weighted evolution always uses the insertion mutation.
+    pub(crate) mutation_operator: MutationOperator,
+    /// Should the population be topped up with random routes after the selection?
+    pub(crate) top_up: bool,
+    #[cfg(feature = "std")]
+    /// Should the parents be sampled by their fitness rank instead of crossing every pair?
+    pub(crate) rank_weighted: bool,
+    #[cfg(feature = "std")]
+    /// Where to report the best cost after every generation and at which cost to stop.
+    pub(crate) target: Option<(&'a GlobalBest, f64)>,
+    /// The nodes every route has to start with, empty if no nodes are pinned.
+    pub(crate) pinned_prefix: &'a [usize],
+    /// How many of the fittest routes are improved with 2-opt after every selection.
+    pub(crate) n_refined: usize,
+    /// How the fittest routes are improved with 2-opt.
+    pub(crate) local_search: LocalSearchPolicy,
+    /// The neighborhoods the fittest routes are improved in instead of 2-opt, if any.
+    pub(crate) neighborhoods: Option<&'a dyn LocalSearch>,
+    /// How the routes that survive a generation are selected from the offspring.
+    pub(crate) selection: Selection,
+    /// How many of the fittest routes survive a generation regardless of the selection.
+    pub(crate) n_elites: usize,
+    /// The constraint whose violation is compared before the fitness, if any. A constraint
+    /// repairs the offspring and replaces the selection by a lexicographic truncation selection,
+    /// the elites are then the best routes of that ranking.
+    pub(crate) constraint: Option<&'a dyn Constraint>,
+    /// Whether the parents compete with their offspring.
+    pub(crate) scheme: GenerationalScheme,
+    /// The most offspring a generation may create, above it only a sample of the parents is
+    /// crossed.
+    pub(crate) max_offspring: Option<usize>,
+    /// What happens to routes of the same round-trip after the selection.
+    pub(crate) duplicates: DuplicatePolicy,
+    /// The memory of removed edges the offspring are mutated with, if any.
+    #[cfg(feature = "std")]
+    pub(crate) tabu: Option<&'a TabuMemory>,
+    #[cfg(feature = "std")]
+    /// The token that stops the evolution after the current generation, if any.
+    pub(crate) cancel: Option<&'a CancelToken>,
+}
+impl Default for GenerationOptions<'_> {
+    fn default() -> Self {
+        GenerationOptions {
+            mutate_prob: 0.5,
+            crossover_rate: 1.0,
+            crossover_operator: CrossoverOperator::default(),
+            mutation_operator: MutationOperator::default(),
+            top_up: false,
+            #[cfg(feature = "std")]
+            rank_weighted: false,
+            #[cfg(feature = "std")]
+            target: None,
+            pinned_prefix: &[],
+            n_refined: 0,
+            local_search: LocalSearchPolicy::default(),
+            neighborhoods: None,
+            selection: Selection::default(),
+            n_elites: 0,
+            constraint: None,
+            scheme: GenerationalScheme::default(),
+            max_offspring: None,
+            duplicates: DuplicatePolicy::default(),
+            #[cfg(feature = "std")]
+            tabu: None,
+            #[cfg(feature = "std")]
+            cancel: None,
+        }
+    }
+}
+#[cfg(feature = "std")]
+impl GenerationOptions<'_> {
+    /// Report the best route of `population` and check whether the evolution can stop.
+    ///
+    /// # Arguments
+    ///
+    /// * `population` - The population after the last generation.
+    /// * `distance_matrix` - The distance matrix on which the fitness will be computed on.
+    ///
+    fn target_reached(&self, population: &Routes, distance_matrix: &DistanceMat) -> bool {
+        match self.target {
+            Some((global_best, target_cost)) => {
+                let best_cost = population
+                    .get_n_fittest(1, distance_matrix)
+                    .first()
+                    .map_or(f64::INFINITY, |route| -route.fitness(distance_matrix));
+                global_best.update(best_cost) <= target_cost
+            }
+            None => false,
+        }
+    }
+    /// Has the evolution been cancelled?
+    fn is_cancelled(&self) -> bool {
+        self.cancel.is_some_and(CancelToken::is_cancelled)
+    }
+}
+/// Evolve a population for a single generation and select the fittest individuals.
+///
+/// # Arguments
+///
+/// * `population` - The population that should be evolved.
+/// * `size_generation` - How many individuals should be kept after evolving it.
+/// * `distance_matrix` - The distance matrix on which the fitness will be computed on.
+/// * `options` - How the generation is evolved.
+///
+#[cfg(feature = "std")]
+#[cfg(any(feature = "memory-stats", test))]
+pub(crate) fn evolve_generation(
+    population: Routes,
+    size_generation: usize,
+    distance_matrix: &DistanceMat,
+    options: GenerationOptions<'_>,
 ) -> Routes {
-    if n_jobs == 0 {
-        // single-thread
-        (0..n_generations).fold(initial_population, |pop, _| {
-            pop.evolve(0.5)
-                .get_fittest_population(size_generation, distance_matrix)
-        })
+    evolve_generation_counted(population, size_generation, distance_matrix, options).0
+}
+/// Evolve a population for a single generation like `evolve_generation` and also return how
+/// many routes the selection chose from, e.g. to count the fitness evaluations of a run.
+///
+/// # Arguments
+///
+/// * `population` - The population that should be evolved.
+/// * `size_generation` - How many individuals should be kept after evolving it.
+/// * `distance_matrix` - The distance matrix on which the fitness will be computed on.
+/// * `options` - How the generation is evolved.
+///
+#[cfg(feature = "std")]
+pub(crate) fn evolve_generation_counted(
+    population: Routes,
+    size_generation: usize,
+    distance_matrix: &DistanceMat,
+    options: GenerationOptions<'_>,
+) -> (Routes, usize) {
+    let breeders = sample_breeders(
+        &population,
+        options.max_offspring,
+        distance_matrix,
+        &mut rand::thread_rng(),
+    );
+    let parents = breeders.as_ref().unwrap_or(&population);
+    let offspring = if options.rank_weighted {
+        parents.evolve_rank_weighted(options.mutate_prob, distance_matrix)
     } else {
-        // Multi-threaded execution
-        thread::scope(|s| {
-            let mut result = Vec::new();
-            for _ in 0..n_jobs {
-                let this_population = initial_population.clone();
-                result.push(s.spawn(move |_| -> Vec<Route> {
-                    (0..((n_generations / n_jobs) + 1))
-                        .fold(this_population, |pop, _| {
-                            pop.evolve(0.5)
-                                .get_fittest_population(size_generation, distance_matrix)
-                        })
-                        .get_n_fittest(size_generation, distance_matrix)
-                }))
+        let spatial = options.crossover_operator == CrossoverOperator::Spatial
+            && distance_matrix.coordinates().is_some();
+        let candidates = (options.crossover_operator == CrossoverOperator::Candidate
+            || options.mutation_operator == MutationOperator::Candidate)
+            && distance_matrix.candidate_list().is_some();
+        // Fixed ends are only kept in place by the operators of `breed_offspring`.
+        let fixed_ends = options
+            .constraint
+            .is_some_and(|constraint| constraint.fixed_ends() != (0, 0));
+        if spatial || candidates || fixed_ends {
+            breed_offspring(
+                parents,
+                distance_matrix,
+                options,
+                &mut rand::thread_rng(),
+                None,
+            )
+        } else {
+            parents.evolve_with_operators(
+                options.mutate_prob,
+                options.crossover_rate,
+                options.crossover_operator,
+                options.mutation_operator,
+            )
+        }
+    };
+    let offspring = match breeders {
+        Some(_) => offspring.add_vec_route(population.iter().cloned().collect()),
+        None => offspring,
+    };
+    let n_offspring = offspring.len();
+    let selected = select_generation(
+        population,
+        offspring,
+        size_generation,
+        distance_matrix,
+        options,
+        &mut rand::thread_rng(),
+    );
+    (selected, n_offspring)
+}
+/// Evolve a population for a single generation like `evolve_generation`, but draw all random
+/// choices from `rng`, so that the next generation only depends on the population and the
+/// state of `rng`, e.g. to resume a seeded run exactly. The offspring are always bred with the
+/// crossover and mutation operators of the options, `rank_weighted` is ignored, and the routes
+/// added by `top_up` are still drawn from the thread's random number generator. The
+/// crossover and mutation operators are counted into `counts` if it is given, counting the
+/// mutations clones every child, so it is only done for traces.
+///
+/// # Arguments
+///
+/// * `population` - The population that should be evolved.
+/// * `size_generation` - How many individuals should be kept after evolving it.
+/// * `distance_matrix` - The distance matrix on which the fitness will be computed on.
+/// * `options` - How the generation is evolved.
+/// * `rng` - The random number generator all random choices are drawn from.
+/// * `counts` - Where to count the applied operators, `None` to not count them.
+///
+pub(crate) fn evolve_generation_with_rng<R: Rng + ?Sized>(
+    population: Routes,
+    size_generation: usize,
+    distance_matrix: &DistanceMat,
+    options: GenerationOptions<'_>,
+    rng: &mut R,
+    counts: Option<&mut OperatorCounts>,
+) -> Routes {
+    // The order in which the routes are stored depends on how the set was built, e.g. read from
+    // a checkpoint, so it is rebuilt from the sorted routes.
+    let mut routes = population.routes.into_iter().collect::<Vec<Route>>();
+    routes.sort_unstable_by(|route_a, route_b| route_a.indexes.cmp(&route_b.indexes));
+    let population = Routes::from(routes);
+    let breeders = sample_breeders(&population, options.max_offspring, distance_matrix, rng);
+    let parents = breeders.as_ref().unwrap_or(&population);
+    let offspring = breed_offspring(parents, distance_matrix, options, rng, counts);
+    let offspring = match breeders {
+        Some(_) => offspring.add_vec_route(population.iter().cloned().collect()),
+        None => offspring,
+    };
+    select_generation(
+        population,
+        offspring,
+        size_generation,
+        distance_matrix,
+        options,
+        rng,
+    )
+}
+/// Mutate a route with the mutation operator, `MutationOperator::Candidate` connects the near
+/// neighbors of `candidates`, the evolution checks that they are given.
+///
+/// # Arguments
+///
+/// * `route` - The route that is mutated.
+/// * `prob` - The probability with which the route will be changed.
+/// * `mutation_operator` - The mutation operator.
+/// * `candidates` - The near neighbors of every node, if any.
+/// * `rng` - The random number generator the mutation is drawn from.
+///
+fn mutate_with_candidates<R: Rng + ?Sized>(
+    route: Route,
+    prob: f32,
+    mutation_operator: MutationOperator,
+    candidates: Option<&CandidateList>,
+    rng: &mut R,
+) -> Route {
+    match (mutation_operator, candidates) {
+        (MutationOperator::Candidate, Some(candidates)) => {
+            route.candidate_mutate_with_rng(prob, candidates, rng)
+        }
+        _ => route.mutate_with_operator(prob, mutation_operator, rng),
+    }
+}
+/// Cross two routes with the crossover operator. The spatial and candidate crossovers use the
+/// coordinates and the candidate list, the evolution checks that they are given.
+///
+/// # Arguments
+///
+/// * `parent_a` - The first parent.
+/// * `parent_b` - The second parent.
+/// * `crossover_operator` - The crossover operator.
+/// * `coordinates` - The coordinates of the nodes, if any.
+/// * `candidates` - The near neighbors of every node, if any.
+/// * `rng` - The random number generator the crossover is drawn from.
+///
+fn cross_with_operator<R: Rng + ?Sized>(
+    parent_a: &Route,
+    parent_b: &Route,
+    crossover_operator: CrossoverOperator,
+    coordinates: Option<&[(f64, f64)]>,
+    candidates: Option<&CandidateList>,
+    rng: &mut R,
+) -> Route {
+    match (crossover_operator, coordinates, candidates) {
+        (CrossoverOperator::Spatial, Some(coordinates), _) => {
+            parent_a.spatial_crossover_with_rng(parent_b, coordinates, rng)
+        }
+        (CrossoverOperator::Candidate, _, Some(candidates)) => {
+            parent_a.candidate_crossover_with_rng(parent_b, candidates, rng)
+        }
+        (crossover_operator, _, _) => {
+            parent_a.crossover_with_operator(parent_b, crossover_operator, rng)
+        }
+    }
+}
+/// The nodes between the fixed ends of the routes of a constrained generation, relabeled to
+/// `0..n`, so that the crossover and mutation operators can be applied to them alone and the
+/// ends stay in place. The coordinates and the candidate list are relabeled the same way.
+struct FixedEnds {
+    /// How many positions at the start of every route are fixed.
+    n_start: usize,
+    /// How many positions at the end of every route are fixed.
+    n_end: usize,
+    /// The nodes between the ends, the position of a node is its label.
+    nodes: Vec<usize>,
+    /// The label of every node, `usize::MAX` for the nodes at the ends.
+    labels: Vec<usize>,
+    /// The coordinates of the nodes between the ends by their label, if any.
+    coordinates: Option<Vec<(f64, f64)>>,
+    /// The near neighbors among the nodes between the ends by their label, if any.
+    candidates: Option<CandidateList>,
+}
+impl FixedEnds {
+    /// Take the nodes between the fixed ends of a route that has the right ends, `None` if no
+    /// node is left between them.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - A route whose ends satisfy the constraint.
+    /// * `fixed_ends` - How many positions at the start and at the end are fixed.
+    /// * `distance_matrix` - The distance matrix that holds the coordinates and candidate list.
+    ///
+    fn new(
+        route: &Route,
+        (n_start, n_end): (usize, usize),
+        distance_matrix: &DistanceMat,
+    ) -> Option<Self> {
+        let n_nodes = route.indexes.len();
+        if n_start + n_end >= n_nodes {
+            return None;
+        }
+        let mut nodes = route.indexes[n_start..(n_nodes - n_end)].to_vec();
+        nodes.sort_unstable();
+        let mut labels = vec![usize::MAX; distance_matrix.n_units().max(n_nodes)];
+        for (label, node) in nodes.iter().enumerate() {
+            labels[*node] = label;
+        }
+        let coordinates = distance_matrix
+            .coordinates()
+            .map(|coordinates| nodes.iter().map(|node| coordinates[*node]).collect());
+        let candidates = distance_matrix
+            .candidate_list()
+            .map(|candidates| candidates.restricted_to(&nodes, &labels));
+        Some(FixedEnds {
+            n_start,
+            n_end,
+            nodes,
+            labels,
+            coordinates,
+            candidates,
+        })
+    }
+    /// The nodes of a route between its ends under their labels, `None` if they are not the
+    /// nodes between the fixed ends, e.g. because the route has other ends.
+    fn interior(&self, route: &Route) -> Option<Route> {
+        let n_nodes = route.indexes.len();
+        if n_nodes != self.n_start + self.nodes.len() + self.n_end {
+            return None;
+        }
+        route.indexes[self.n_start..(n_nodes - self.n_end)]
+            .iter()
+            .map(|node| {
+                self.labels
+                    .get(*node)
+                    .copied()
+                    .filter(|label| *label != usize::MAX)
+            })
+            .collect::<Option<Vec<usize>>>()
+            .map(Route::new)
+    }
+    /// Put the ends of `ends` around the nodes of `interior`.
+    fn restore(&self, ends: &Route, interior: Route) -> Route {
+        let n_nodes = ends.indexes.len();
+        let mut indexes = Vec::with_capacity(n_nodes);
+        indexes.extend_from_slice(&ends.indexes[..self.n_start]);
+        indexes.extend(interior.indexes.iter().map(|label| self.nodes[*label]));
+        indexes.extend_from_slice(&ends.indexes[(n_nodes - self.n_end)..]);
+        Route::new(indexes)
+    }
+}
+/// Breed the offspring of a population with the crossover and mutation operators of the
+/// options, see `Routes::evolve_with_operators`. The spatial and candidate operators use the
+/// coordinates and the candidate list of the distance matrix if it has them. With a tabu
+/// memory the offspring are mutated with `TabuMemory::mutate` instead. If the constraint fixes
+/// the ends of the routes, the operators only reorder the nodes between them and keep the ends
+/// of the first parent; parents with other ends are bred as a whole and repaired by the
+/// selection.
+///
+/// # Arguments
+///
+/// * `parents` - The routes that are crossed.
+/// * `distance_matrix` - The distance matrix that holds the coordinates and candidate list.
+/// * `options` - How the generation is evolved.
+/// * `rng` - The random number generator all random choices are drawn from.
+/// * `counts` - Where to count the applied operators, `None` to not count them.
+///
+fn breed_offspring<R: Rng + ?Sized>(
+    parents: &Routes,
+    distance_matrix: &DistanceMat,
+    options: GenerationOptions<'_>,
+    rng: &mut R,
+    counts: Option<&mut OperatorCounts>,
+) -> Routes {
+    let coordinates = distance_matrix.coordinates();
+    let candidates = distance_matrix.candidate_list();
+    let fixed_ends = options.constraint.and_then(|constraint| {
+        let first = parents.iter().next()?;
+        FixedEnds::new(
+            &constraint.repair(first.clone(), distance_matrix),
+            constraint.fixed_ends(),
+            distance_matrix,
+        )
+    });
+    let fixed_ends = fixed_ends.as_ref();
+    parents.evolve_with_crossover(
+        options.mutate_prob,
+        options.crossover_rate,
+        rng,
+        counts,
+        |parent_a, parent_b, rng| {
+            if let Some(fixed_ends) = fixed_ends {
+                if let (Some(interior_a), Some(interior_b)) =
+                    (fixed_ends.interior(parent_a), fixed_ends.interior(parent_b))
+                {
+                    let child = cross_with_operator(
+                        &interior_a,
+                        &interior_b,
+                        options.crossover_operator,
+                        fixed_ends.coordinates.as_deref(),
+                        fixed_ends.candidates.as_ref(),
+                        rng,
+                    );
+                    return fixed_ends.restore(parent_a, child);
+                }
+            }
+            cross_with_operator(
+                parent_a,
+                parent_b,
+                options.crossover_operator,
+                coordinates,
+                candidates,
+                rng,
+            )
+        },
+        |child, prob, rng| {
+            if let Some(fixed_ends) = fixed_ends {
+                if let Some(interior) = fixed_ends.interior(&child) {
+                    let mutated = mutate_with_candidates(
+                        interior,
+                        prob,
+                        options.mutation_operator,
+                        fixed_ends.candidates.as_ref(),
+                        rng,
+                    );
+                    return fixed_ends.restore(&child, mutated);
+                }
+            }
+            #[cfg(feature = "std")]
+            if let Some(memory) = options.tabu {
+                return memory.mutate_with(&child, |route| {
+                    mutate_with_candidates(route, prob, options.mutation_operator, candidates, rng)
+                });
             }
+            mutate_with_candidates(child, prob, options.mutation_operator, candidates, rng)
+        },
+    )
+}
+/// Sample the parents that are crossed when crossing every pair of `population` would create
+/// more than `max_offspring` offspring. `k` parents create about `k * k` offspring, so the
+/// fittest route and `k - 1` random other routes are kept, with `k` the square root of the
+/// limit. The sample is sorted, so that a seeded generation stays reproducible.
+///
+/// # Arguments
+///
+/// * `population` - The parents of the generation.
+/// * `max_offspring` - The most offspring of the generation, `None` for no limit.
+/// * `distance_matrix` - The distance matrix on which the fitness will be computed on.
+/// * `rng` - The random number generator the parents are sampled with.
+///
+fn sample_breeders<R: Rng + ?Sized>(
+    population: &Routes,
+    max_offspring: Option<usize>,
+    distance_matrix: &DistanceMat,
+    rng: &mut R,
+) -> Option<Routes> {
+    let n_breeders = ((max_offspring? as f64).sqrt() as usize).max(1);
+    if population.len() <= n_breeders {
+        return None;
+    }
+    let mut routes = population.iter().collect::<Vec<&Route>>();
+    routes.sort_unstable_by(|route_a, route_b| route_a.indexes.cmp(&route_b.indexes));
+    let fittest = population.get_n_fittest(1, distance_matrix).remove(0);
+    let mut breeders = routes
+        .into_iter()
+        .filter(|route| **route != fittest)
+        .collect::<Vec<&Route>>()
+        .choose_multiple(rng, n_breeders - 1)
+        .map(|route| (*route).clone())
+        .collect::<Vec<Route>>();
+    breeders.push(fittest);
+    breeders.sort_unstable_by(|route_a, route_b| route_a.indexes.cmp(&route_b.indexes));
+    Some(Routes::from(breeders))
+}
+/// Select the next generation from the offspring of a population: discard the parents in a
+/// (μ,λ)-scheme, pin the prefix, repair and select the survivors, refine them and top them up.
+/// With a constraint, the elites are the best routes by the lexicographic ranking of
+/// `Routes::get_n_fittest_feasible_first`, and they survive the refinement, the duplicate
+/// policy and the top-up, which would otherwise compare the routes by their fitness alone.
+///
+/// # Arguments
+///
+/// * `population` - The parents of the offspring.
+/// * `offspring` - The offspring together with their parents.
+/// * `size_generation` - How many individuals should be kept.
+/// * `distance_matrix` - The distance matrix on which the fitness will be computed on.
+/// * `options` - How the generation is evolved.
+/// * `rng` - The random number generator the survivors are selected with.
+///
+fn select_generation<R: Rng + ?Sized>(
+    population: Routes,
+    offspring: Routes,
+    size_generation: usize,
+    distance_matrix: &DistanceMat,
+    options: GenerationOptions<'_>,
+    rng: &mut R,
+) -> Routes {
+    // The best routes, by the lexicographic ranking if there is a constraint.
+    let fittest = |routes: &Routes, n: usize| match options.constraint {
+        Some(constraint) => routes.get_n_fittest_feasible_first(n, distance_matrix, constraint),
+        None => routes.get_n_fittest(n, distance_matrix),
+    };
+    let offspring = match options.scheme {
+        GenerationalScheme::MuPlusLambda => offspring,
+        // The elites survive unchanged, even though the other parents are discarded.
+        GenerationalScheme::MuCommaLambda => discard_parents(offspring, &population)
+            .add_vec_route(fittest(&population, options.n_elites)),
+    };
+    let offspring = if options.pinned_prefix.is_empty() {
+        offspring
+    } else {
+        offspring.pin_prefix(options.pinned_prefix)
+    };
+    let (population, elites) = match options.constraint {
+        Some(constraint) => {
+            let population = Routes::from(
+                Routes::from(
+                    offspring
+                        .iter()
+                        .map(|route| constraint.repair(route.clone(), distance_matrix))
+                        .collect::<Vec<Route>>(),
+                )
+                .get_n_fittest_feasible_first(
+                    size_generation,
+                    distance_matrix,
+                    constraint,
+                ),
+            );
+            let elites = fittest(&population, options.n_elites.min(size_generation));
+            (population, elites)
+        }
+        None => (
+            select_survivors(
+                offspring,
+                size_generation,
+                distance_matrix,
+                options.selection,
+                options.n_elites,
+                rng,
+            ),
+            Vec::new(),
+        ),
+    };
+    let population = match (options.n_refined, options.neighborhoods, options.constraint) {
+        (0, _, _) => population,
+        // The refined routes are repaired and compete by the lexicographic ranking.
+        (n_refined, neighborhoods, Some(constraint)) => {
+            let refined = fittest(&population, n_refined)
+                .iter()
+                .map(|route| {
+                    let refined = match neighborhoods {
+                        Some(neighborhoods) => neighborhoods.improve(route, distance_matrix),
+                        None => {
+                            route.two_opt_with_policy(distance_matrix, options.local_search, rng)
+                        }
+                    };
+                    constraint.repair(refined, distance_matrix)
+                })
+                .collect();
+            Routes::from(
+                population
+                    .add_vec_route(refined)
+                    .get_n_fittest_feasible_first(size_generation, distance_matrix, constraint),
+            )
+        }
+        (n_refined, Some(neighborhoods), None) => population.refine_fittest_with_local_search(
+            n_refined,
+            distance_matrix,
+            size_generation,
+            neighborhoods,
+        ),
+        (n_refined, None, None) => population.refine_fittest_with_rng(
+            n_refined,
+            distance_matrix,
+            size_generation,
+            options.local_search,
+            rng,
+        ),
+    };
+    let population = match options.duplicates {
+        DuplicatePolicy::KeepEquivalent => population,
+        policy => population
+            .apply_duplicate_policy(policy, distance_matrix, rng)
+            .pin_prefix(options.pinned_prefix),
+    };
+    let population = if options.top_up {
+        population
+            .top_up_with_rng(size_generation, rng)
+            .pin_prefix(options.pinned_prefix)
+    } else {
+        population
+    };
+    match options.constraint {
+        Some(constraint) if !elites.is_empty() => Routes::from(
             Routes::from(
-                result
-                    .into_iter()
-                    .flat_map(|thread| thread.join().unwrap())
+                population
+                    .iter()
+                    .map(|route| constraint.repair(route.clone(), distance_matrix))
                     .collect::<Vec<Route>>(),
             )
-        })
-        .unwrap()
+            .add_vec_route(elites)
+            .get_n_fittest_feasible_first(size_generation, distance_matrix, constraint),
+        ),
+        _ => population,
+    }
+}
+/// Remove the parents from their offspring for a (μ,λ)-scheme. If nothing else is left, the
+/// parents are kept, so that the population doesn't die out.
+///
+/// # Arguments
+///
+/// * `offspring` - The offspring together with their parents.
+/// * `parents` - The parents that should be removed.
+///
+fn discard_parents(mut offspring: Routes, parents: &Routes) -> Routes {
+    offspring
+        .routes
+        .retain(|route| !parents.routes.contains(route));
+    if offspring.is_empty() {
+        parents.clone()
+    } else {
+        offspring
+    }
+}
+/// Select the routes that survive a generation: the `n_elites` fittest routes and as many routes
+/// selected with `selection` from the others as are needed to keep `size_generation` routes.
+///
+/// # Arguments
+///
+/// * `offspring` - The routes the survivors are selected from.
+/// * `size_generation` - How many individuals should be kept.
+/// * `distance_matrix` - The distance matrix on which the fitness will be computed on.
+/// * `selection` - How the routes that are not elites are selected.
+/// * `n_elites` - How many of the fittest routes survive regardless of the selection.
+/// * `rng` - The random number generator the routes are selected with.
+///
+fn select_survivors<R: Rng + ?Sized>(
+    offspring: Routes,
+    size_generation: usize,
+    distance_matrix: &DistanceMat,
+    selection: Selection,
+    n_elites: usize,
+    rng: &mut R,
+) -> Routes {
+    // The truncation selection keeps the elites anyway.
+    if selection == Selection::Truncation {
+        return offspring.get_fittest_population(size_generation, distance_matrix);
+    }
+    let elites = offspring.get_n_fittest(n_elites.min(size_generation), distance_matrix);
+    let mut others = offspring;
+    for elite in &elites {
+        others.routes.remove(elite);
+    }
+    let selected = sample_fitnesses(
+        others.fitnesses(distance_matrix),
+        size_generation - elites.len(),
+        selection,
+        rng,
+    );
+    Routes::from(elites).add_vec_route(selected)
+}
+/// The implementation of `evolve_population` and its variants.
+#[cfg(feature = "std")]
+pub(crate) fn evolve_population_generations(
+    initial_population: Routes,
+    n_generations: usize,
+    size_generation: usize,
+    distance_matrix: &DistanceMat,
+    n_jobs: usize,
+    options: GenerationOptions<'_>,
+) -> Routes {
+    evolve_population_counted(
+        initial_population,
+        n_generations,
+        size_generation,
+        distance_matrix,
+        n_jobs,
+        options,
+    )
+    .0
+}
+/// Evolve a population like `evolve_population_generations` and also return how many routes
+/// the selections of all generations and threads chose from.
+#[cfg(feature = "std")]
+pub(crate) fn evolve_population_counted(
+    initial_population: Routes,
+    n_generations: usize,
+    size_generation: usize,
+    distance_matrix: &DistanceMat,
+    n_jobs: usize,
+    options: GenerationOptions<'_>,
+) -> (Routes, usize) {
+    #[cfg(feature = "std")]
+    if n_jobs > 0 {
+        return evolve_population_threaded(
+            initial_population,
+            n_generations,
+            size_generation,
+            distance_matrix,
+            n_jobs,
+            options,
+        );
+    }
+    // Without threads, `n_jobs` has no effect.
+    #[cfg(not(feature = "std"))]
+    let _ = n_jobs;
+    // single-thread
+    let mut population = initial_population;
+    let mut n_offspring = 0;
+    for _ in 0..n_generations {
+        let (evolved_population, n_generation_offspring) =
+            evolve_generation_counted(population, size_generation, distance_matrix, options);
+        population = evolved_population;
+        n_offspring += n_generation_offspring;
+        if options.target_reached(&population, distance_matrix) || options.is_cancelled() {
+            break;
+        }
+    }
+    (population, n_offspring)
+}
+/// Evolve `n_jobs` copies of the initial population in parallel and combine the fittest
+/// routes of every thread.
+#[cfg(feature = "std")]
+fn evolve_population_threaded(
+    initial_population: Routes,
+    n_generations: usize,
+    size_generation: usize,
+    distance_matrix: &DistanceMat,
+    n_jobs: usize,
+    options: GenerationOptions<'_>,
+) -> (Routes, usize) {
+    thread::scope(|s| {
+        let mut result = Vec::new();
+        for _ in 0..n_jobs {
+            let this_population = initial_population.clone();
+            result.push(s.spawn(move |_| -> (Vec<Route>, usize) {
+                let mut population = this_population;
+                let mut n_offspring = 0;
+                for _ in 0..((n_generations / n_jobs) + 1) {
+                    let (evolved_population, n_generation_offspring) = evolve_generation_counted(
+                        population,
+                        size_generation,
+                        distance_matrix,
+                        options,
+                    );
+                    population = evolved_population;
+                    n_offspring += n_generation_offspring;
+                    if options.target_reached(&population, distance_matrix)
+                        || options.is_cancelled()
+                    {
+                        break;
+                    }
+                }
+                (
+                    population.get_n_fittest(size_generation, distance_matrix),
+                    n_offspring,
+                )
+            }))
+        }
+        let mut routes = Vec::new();
+        let mut n_offspring = 0;
+        for thread in result {
+            let (thread_routes, thread_offspring) = thread.join().unwrap();
+            routes.extend(thread_routes);
+            n_offspring += thread_offspring;
+        }
+        (Routes::from(routes), n_offspring)
+    })
+    .unwrap()
+}
+/// Evolve a population like `evolve_population` with a single thread and measure the
+/// allocations of every generation, e.g. to verify that the solver meets an allocation budget.
+/// The statistics are `None` if the `CountingAllocator` is not installed as global allocator.
+///
+/// # Arguments
+///
+/// * `initial_population` - Your initial population that should be evolved.
+/// * `n_generations` - How many times should your population be evolved?
+/// * `size_generation` - How many individuals should be kept after evolving it.
+/// * `distance_matrix` - The distance matrix on which the fitness will be computed on.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::routes::{Routes, evolve_population_with_allocation_stats};
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+///
+/// let (evolved_population, allocations) = evolve_population_with_allocation_stats(
+///     Routes::random(2, 3),
+///     10,
+///     5,
+///     &DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]),
+/// );
+/// assert_eq!(allocations.len(), 10);
+/// ```
+#[cfg(feature = "memory-stats")]
+pub fn evolve_population_with_allocation_stats(
+    initial_population: Routes,
+    n_generations: usize,
+    size_generation: usize,
+    distance_matrix: &DistanceMat,
+) -> (Routes, Vec<Option<AllocationStats>>) {
+    let mut allocations = Vec::with_capacity(n_generations);
+    let mut population = initial_population;
+    for _ in 0..n_generations {
+        let (evolved_population, generation_allocations) = measure_allocations(|| {
+            evolve_generation(
+                population,
+                size_generation,
+                distance_matrix,
+                GenerationOptions::default(),
+            )
+        });
+        population = evolved_population;
+        allocations.push(generation_allocations);
+    }
+    (population, allocations)
+}
+/// How diverse a population is, as measured by `Routes::diversity`.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Diversity {
+    /// The mean fraction of edges that two routes share: 1.0 if all routes are the same
+    /// round-trip and 0.0 if no two routes share an edge. A single route overlaps itself.
+    pub mean_edge_overlap: f64,
+    /// The number of distinct round-trips in the population.
+    pub n_unique: usize,
+    /// The Shannon entropy in bits of how often every edge is used: the logarithm of the number
+    /// of nodes if all routes are the same round-trip, growing as the routes use more edges.
+    pub edge_entropy: f64,
+}
+/// The result of benchmarking a configuration of the genetic algorithm.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchmarkResult {
+    /// The run time in milliseconds.
+    pub run_time: u64,
+    /// The fitness of the best route in the final population.
+    pub minimal_loss: f64,
+    /// The metadata of the distance matrix the benchmark ran on, e.g. the units of
+    /// `minimal_loss`.
+    pub metadata: DistanceMatMetadata,
+    /// The allocations during the run. Only available with the `memory-stats` feature and
+    /// the `CountingAllocator` installed as global allocator.
+    #[cfg(feature = "memory-stats")]
+    pub allocations: Option<AllocationStats>,
+}
+/// Benchmark a run of the genetic algorithm on a random initial population.
+///
+/// # Arguments
+///
+/// * `n_generations` - How many generations should the algorithm evolve?
+/// * `size_generation` - How many individuals should be selected at the end of each
+///   evolution step.
+/// * `dist_mat` - What is the distance matrix for your TSP.
+/// * `n_jobs` - How many threads should be used, 0 means single-threaded.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::routes::benchmark;
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+///
+/// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+/// let result = benchmark(10, 5, &distance_matrix, 0);
+/// println!("time: {} ms, minimal loss: {}", result.run_time, result.minimal_loss);
+/// ```
+#[cfg(feature = "std")]
+pub fn benchmark(
+    n_generations: usize,
+    size_generation: usize,
+    dist_mat: &DistanceMat,
+    n_jobs: usize,
+) -> BenchmarkResult {
+    let run = || {
+        // End-to-end test: does the error of the route get down?
+        let before = Instant::now();
+        let final_population = evolve_population(
+            Routes::random(size_generation, dist_mat.n_units()),
+            n_generations,
+            size_generation,
+            dist_mat,
+            n_jobs,
+        );
+        let duration = before.elapsed();
+        let nanos = duration.subsec_nanos() as u64;
+        (
+            (1000 * 1000 * 1000 * duration.as_secs() + nanos) / (1000 * 1000),
+            final_population.get_n_fittest(1, dist_mat)[0].fitness(dist_mat),
+        )
+    };
+    #[cfg(feature = "memory-stats")]
+    {
+        let ((run_time, minimal_loss), allocations) = measure_allocations(run);
+        BenchmarkResult {
+            run_time,
+            minimal_loss,
+            metadata: dist_mat.metadata(),
+            allocations,
+        }
+    }
+    #[cfg(not(feature = "memory-stats"))]
+    {
+        let (run_time, minimal_loss) = run();
+        BenchmarkResult {
+            run_time,
+            minimal_loss,
+            metadata: dist_mat.metadata(),
+        }
     }
 }
 /// Compute the time in milliseconds that it takes for a genetic algorithm to run.
@@ -350,34 +3632,32 @@ pub fn evolve_population(
 ///
 /// * `n_generations` - How many generations should the algorithm evolve?
 /// * `size_generation` - How many individuals should be selected at the end of each
-/// evolution step.
+///   evolution step.
 /// * `dist_mat` - What is the distance matrix for your TSP.
+/// * `n_jobs` - How many threads should be used, 0 means single-threaded.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::routes::benchmark_population;
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
 ///
+/// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+/// let (run_time, minimal_loss) = benchmark_population(10, 5, &distance_matrix, 0);
 /// ```
+#[cfg(feature = "std")]
 pub fn benchmark_population(
     n_generations: usize,
     size_generation: usize,
     dist_mat: &DistanceMat,
     n_jobs: usize,
 ) -> (u64, f64) {
-    // End-to-end test: does the error of the route get down?
-    let before = Instant::now();
-    let final_population = evolve_population(
-        Routes::random(size_generation, dist_mat.n_units()),
-        n_generations,
-        size_generation,
-        dist_mat,
-        n_jobs,
-    );
-    let duration = before.elapsed();
-    let nanos = duration.subsec_nanos() as u64;
-    (
-        (1000 * 1000 * 1000 * duration.as_secs() + nanos) / (1000 * 1000),
-        final_population.get_n_fittest(1, dist_mat)[0].fitness(dist_mat),
-    )
+    let result = benchmark(n_generations, size_generation, dist_mat, n_jobs);
+    (result.run_time, result.minimal_loss)
 }
 
 #[cfg(test)]
+#[allow(clippy::useless_vec, clippy::map_clone)]
 mod tests {
     use super::*;
     use crate::test_utils::{test_dist_mat, valid_permutation};
@@ -388,7 +3668,7 @@ mod tests {
             Route::new(vec![0, 1, 2]),
             Route::new(vec![1, 0, 2]),
         ];
-        let routes_as_hashet: HashSet<Route, xx::Hash64> =
+        let routes_as_hashet: HashSet<Route, FixedState> =
             route_vec_to_xx_hashset(routes_vec.clone());
         // Routes in the hashset are unique, so the duplicate in `routes_vec`
         // should only be in there once.
@@ -426,6 +3706,7 @@ mod tests {
         )
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn random_constructor() {
         let n_objects = 3;
@@ -478,6 +3759,7 @@ mod tests {
             Routes::from(vec![Route::new(vec![1, 2, 3]), Route::new(vec![4, 5, 6])]);
         assert_eq!(routes_with_three_nodes.get_n_nodes(), 3);
     }
+    #[cfg(feature = "std")]
     #[test]
     fn add_n_random_nodes() {
         // Because there are only 6 possible routes with three nodes,
@@ -556,6 +3838,59 @@ mod tests {
             )
         }
     }
+    mod test_tie_breaking {
+        use super::*;
+        use crate::objective::TieBreaking;
+        /// Every round-trip through all nodes costs the same.
+        fn all_equal_dist_mat() -> DistanceMat {
+            DistanceMat::new(vec![
+                vec![0.0, 1.0, 1.0, 1.0],
+                vec![1.0, 0.0, 1.0, 1.0],
+                vec![1.0, 1.0, 0.0, 1.0],
+                vec![1.0, 1.0, 1.0, 0.0],
+            ])
+        }
+        #[test]
+        fn ties_ordered_by_canonical_tour() {
+            let routes = Routes::from(vec![
+                Route::new(vec![3, 2, 1, 0]),
+                Route::new(vec![1, 3, 0, 2]),
+                Route::new(vec![2, 0, 1, 3]),
+                Route::new(vec![0, 1, 2, 3]),
+            ]);
+            assert_eq!(
+                routes.get_n_fittest(4, &all_equal_dist_mat()),
+                vec![
+                    Route::new(vec![0, 1, 2, 3]),
+                    Route::new(vec![3, 2, 1, 0]),
+                    Route::new(vec![2, 0, 1, 3]),
+                    Route::new(vec![1, 3, 0, 2]),
+                ]
+            );
+        }
+        #[cfg(feature = "std")]
+        #[test]
+        fn selection_independent_of_insertion_order() {
+            let mut routes = Routes::random(20, 4)
+                .iter()
+                .cloned()
+                .collect::<Vec<Route>>();
+            let forward =
+                Routes::from(routes.clone()).get_fittest_population(5, &all_equal_dist_mat());
+            routes.reverse();
+            let backward = Routes::from(routes).get_fittest_population(5, &all_equal_dist_mat());
+            assert_eq!(forward, backward);
+        }
+        #[test]
+        fn unordered_keeps_fitness_order() {
+            let distance_mat = test_dist_mat().with_tie_breaking(TieBreaking::Unordered);
+            let routes = Routes::from(vec![Route::new(vec![1, 2, 0]), Route::new(vec![1, 0])]);
+            assert_eq!(
+                routes.get_n_fittest(1, &distance_mat),
+                vec![Route::new(vec![1, 0])]
+            );
+        }
+    }
     mod test_fittest_routes {
         use super::*;
         #[test]
@@ -569,7 +3904,7 @@ mod tests {
             assert_eq!(
                 routes.get_fittest_population(0, &distance_mat),
                 Routes {
-                    routes: HashSet::with_hasher(xx::Hash64),
+                    routes: HashSet::with_hasher(FixedState::default()),
                 },
             )
         }
@@ -626,6 +3961,7 @@ mod tests {
             )
         }
     }
+    #[cfg(feature = "std")]
     mod test_evolve {
         use super::*;
         use crate::test_utils::{test_dist_mat, valid_permutation};
@@ -653,9 +3989,959 @@ mod tests {
             }
         }
     }
+    mod test_small_populations {
+        use super::*;
+        #[cfg(feature = "std")]
+        #[test]
+        fn evolve_single_route() {
+            let routes = Routes::from(vec![Route::new(vec![0, 1, 2, 3])]);
+            let evolved_routes = routes.evolve(0.0);
+            assert_eq!(evolved_routes.len(), 2);
+            assert!(evolved_routes
+                .iter()
+                .any(|route| route == &Route::new(vec![0, 1, 2, 3])));
+            for route in evolved_routes.iter() {
+                valid_permutation(&[0, 1, 2, 3], &route.indexes);
+            }
+        }
+        #[cfg(feature = "std")]
+        #[test]
+        fn evolve_empty_population() {
+            assert!(Routes::from(vec![]).evolve(0.5).is_empty());
+        }
+        #[test]
+        fn try_get_too_many_fittest() {
+            let routes = Routes::from(vec![Route::new(vec![1, 2, 0]), Route::new(vec![1, 0])]);
+            assert_eq!(
+                routes.try_get_n_fittest(3, &test_dist_mat()),
+                Err(PopulationError::NotEnoughIndividuals {
+                    requested: 3,
+                    available: 2
+                })
+            );
+            assert_eq!(
+                routes.try_get_fittest_population(3, &test_dist_mat()),
+                Err(PopulationError::NotEnoughIndividuals {
+                    requested: 3,
+                    available: 2
+                })
+            );
+        }
+        #[test]
+        fn try_get_fittest() {
+            let routes = Routes::from(vec![Route::new(vec![1, 2, 0]), Route::new(vec![1, 0])]);
+            assert_eq!(
+                routes.try_get_n_fittest(1, &test_dist_mat()),
+                Ok(vec![Route::new(vec![1, 0])])
+            );
+            assert_eq!(
+                routes
+                    .try_get_fittest_population(2, &test_dist_mat())
+                    .unwrap()
+                    .len(),
+                2
+            );
+        }
+    }
+    #[cfg(feature = "std")]
+    mod test_top_up {
+        use super::*;
+        #[test]
+        fn top_up_single_route() {
+            let routes = Routes::from(vec![Route::new(vec![0, 1, 2, 3])]).top_up(10);
+            assert_eq!(routes.len(), 10);
+            assert!(routes
+                .iter()
+                .any(|route| route == &Route::new(vec![0, 1, 2, 3])));
+        }
+        #[test]
+        fn top_up_limited_by_permutations() {
+            let routes = Routes::from(vec![Route::new(vec![0, 1, 2])]).top_up(10);
+            assert_eq!(routes.len(), 6);
+        }
+        #[test]
+        fn evolve_rank_weighted_offspring() {
+            let distance_mat = test_dist_mat();
+            let routes = Routes::from(vec![Route::new(vec![0, 1, 2]), Route::new(vec![1, 0, 2])]);
+            let evolved_routes = routes.evolve_rank_weighted(0.5, &distance_mat);
+            // The parents are kept.
+            for route in routes.iter() {
+                assert!(evolved_routes.routes.contains(route));
+            }
+            for route in evolved_routes.iter() {
+                valid_permutation(&[0, 1, 2], &route.indexes);
+            }
+        }
+        #[test]
+        fn evolve_population_rank_weighted_improves() {
+            let distance_mat = DistanceMat::new(vec![
+                vec![0.0, 1.0, 9.0, 9.0, 1.0],
+                vec![1.0, 0.0, 1.0, 9.0, 9.0],
+                vec![9.0, 1.0, 0.0, 1.0, 9.0],
+                vec![9.0, 9.0, 1.0, 0.0, 1.0],
+                vec![1.0, 9.0, 9.0, 1.0, 0.0],
+            ]);
+            let routes =
+                evolve_population_rank_weighted(Routes::random(5, 5), 20, 5, &distance_mat, 0);
+            assert_eq!(
+                routes.get_n_fittest(1, &distance_mat)[0].fitness(&distance_mat),
+                -5.0
+            );
+        }
+        #[test]
+        fn crossbreed_keeps_permutations() {
+            let island_a = Routes::random(3, 5);
+            let island_b = Routes::random(3, 5);
+            let offspring = island_a.crossbreed(&island_b, 10);
+            assert!(!offspring.is_empty());
+            assert!(offspring.len() <= 10);
+            for route in offspring.iter() {
+                valid_permutation(&[0, 1, 2, 3, 4], &route.indexes);
+            }
+        }
+        #[test]
+        fn crossbreed_with_empty_population() {
+            let island = Routes::random(3, 5);
+            assert!(island.crossbreed(&Routes::from(vec![]), 10).is_empty());
+            assert!(Routes::from(vec![]).crossbreed(&island, 10).is_empty());
+        }
+        #[test]
+        fn top_up_empty() {
+            assert!(Routes::from(vec![]).top_up(10).is_empty());
+        }
+        #[test]
+        fn random_limited_by_permutations() {
+            assert_eq!(Routes::random(10, 3).len(), 6);
+        }
+        #[test]
+        fn random_all_permutations() {
+            // Drawing the last of 7! routes at random would take a long time.
+            let routes = Routes::random(5040, 7);
+            assert_eq!(routes.len(), 5040);
+            for route in routes.iter() {
+                valid_permutation(&[0, 1, 2, 3, 4, 5, 6], &route.indexes);
+            }
+        }
+        #[test]
+        fn random_dense_subset() {
+            assert_eq!(Routes::random(70, 5).len(), 70);
+        }
+        #[test]
+        fn try_random_too_many_routes() {
+            assert_eq!(
+                Routes::try_random(25, 4),
+                Err(PopulationError::NotEnoughPermutations {
+                    requested: 25,
+                    available: 24
+                })
+            );
+            assert_eq!(Routes::try_random(24, 4).unwrap().len(), 24);
+        }
+        #[test]
+        fn random_many_routes() {
+            let routes = Routes::random(2000, 10);
+            assert_eq!(routes.len(), 2000);
+        }
+        #[test]
+        fn evolve_keeps_size() {
+            let distance_mat = test_dist_mat();
+            for n_jobs in [0, 2] {
+                let routes = evolve_population_with_top_up(
+                    Routes::from(vec![Route::new(vec![0, 1, 2]), Route::new(vec![1, 0, 2])]),
+                    3,
+                    5,
+                    &distance_mat,
+                    n_jobs,
+                );
+                assert!(routes.len() >= 5);
+            }
+        }
+    }
+    mod test_distribution {
+        use super::*;
+        #[test]
+        fn percentiles_of_single_route() {
+            let routes = Routes::from(vec![Route::new(vec![0, 1, 2])]);
+            assert_eq!(
+                routes.fitness_percentiles(&test_dist_mat(), &[-10.0, 25.0, 150.0]),
+                Some(vec![-6.0, -6.0, -6.0])
+            );
+        }
+        #[test]
+        fn empty_population() {
+            let routes = Routes::from(vec![]);
+            assert_eq!(routes.fitness_percentiles(&test_dist_mat(), &[50.0]), None);
+            assert_eq!(routes.fitness_histogram(&test_dist_mat(), 3), None);
+        }
+        #[test]
+        fn histogram_of_converged_population() {
+            let routes = Routes::from(vec![Route::new(vec![0, 1, 2]), Route::new(vec![1, 0, 2])]);
+            let histogram = routes.fitness_histogram(&test_dist_mat(), 4).unwrap();
+            assert_eq!(histogram.counts, vec![2, 0, 0, 0]);
+            assert_eq!(histogram.bin_width(), 0.0);
+        }
+        #[test]
+        fn histogram_includes_max() {
+            let distance_mat = DistanceMat::new(vec![
+                vec![0.0, 1.0, 2.0, 3.0],
+                vec![1.0, 0.0, 3.0, 1.0],
+                vec![2.0, 3.0, 0.0, 2.0],
+                vec![3.0, 1.0, 2.0, 0.0],
+            ]);
+            let routes = Routes::from(vec![
+                Route::new(vec![0, 1, 2, 3]),
+                Route::new(vec![0, 2, 1, 3]),
+                Route::new(vec![0, 1, 3, 2]),
+            ]);
+            let histogram = routes.fitness_histogram(&distance_mat, 2).unwrap();
+            assert_eq!((histogram.min, histogram.max), (-9.0, -6.0));
+            assert_eq!(histogram.counts, vec![2, 1]);
+        }
+    }
+    mod test_seeded {
+        use super::*;
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        #[test]
+        fn same_seed_same_population() {
+            let distance_mat = DistanceMat::new(vec![
+                vec![0.0, 1.0, 9.0, 9.0, 1.0],
+                vec![1.0, 0.0, 1.0, 9.0, 9.0],
+                vec![9.0, 1.0, 0.0, 1.0, 9.0],
+                vec![9.0, 9.0, 1.0, 0.0, 1.0],
+                vec![1.0, 9.0, 9.0, 1.0, 0.0],
+            ]);
+            let run = |seed| {
+                let mut rng = StdRng::seed_from_u64(seed);
+                let routes = Routes::random_with_rng(6, 5, &mut rng);
+                evolve_population_with_rng(routes, 20, 6, &distance_mat, &mut rng)
+            };
+            let population = run(1);
+            assert_eq!(population, run(1));
+            assert_eq!(population.len(), 6);
+        }
+        #[test]
+        fn evolve_with_rng_single_route() {
+            let routes = Routes::from(vec![Route::new(vec![0, 1, 2, 3])]);
+            let evolved_routes = routes.evolve_with_rng(0.5, &mut StdRng::seed_from_u64(3));
+            assert_eq!(evolved_routes.len(), 2);
+        }
+    }
+    #[cfg(feature = "std")]
+    mod test_memetic {
+        use super::*;
+        #[test]
+        fn memetic_finds_ring() {
+            let distance_mat = DistanceMat::new(vec![
+                vec![0.0, 1.0, 9.0, 9.0, 1.0],
+                vec![1.0, 0.0, 1.0, 9.0, 9.0],
+                vec![9.0, 1.0, 0.0, 1.0, 9.0],
+                vec![9.0, 9.0, 1.0, 0.0, 1.0],
+                vec![1.0, 9.0, 9.0, 1.0, 0.0],
+            ]);
+            let routes = evolve_population_memetic(Routes::random(3, 5), 1, 3, &distance_mat, 0, 3);
+            assert_eq!(
+                routes.get_n_fittest(1, &distance_mat)[0].fitness(&distance_mat),
+                -5.0
+            );
+        }
+        #[test]
+        fn memetic_with_best_improvement_finds_ring() {
+            let distance_mat = DistanceMat::new(vec![
+                vec![0.0, 1.0, 9.0, 9.0, 1.0],
+                vec![1.0, 0.0, 1.0, 9.0, 9.0],
+                vec![9.0, 1.0, 0.0, 1.0, 9.0],
+                vec![9.0, 9.0, 1.0, 0.0, 1.0],
+                vec![1.0, 9.0, 9.0, 1.0, 0.0],
+            ]);
+            let routes = evolve_population_memetic_with_policy(
+                Routes::random(3, 5),
+                &distance_mat,
+                EvolutionConfig::new()
+                    .with_n_generations(1)
+                    .with_size_generation(3),
+                3,
+                LocalSearchPolicy::new(
+                    crate::route::PivotingRule::BestImprovement,
+                    crate::route::NeighborhoodOrder::Random,
+                ),
+            );
+            assert_eq!(
+                routes.get_n_fittest(1, &distance_mat)[0].fitness(&distance_mat),
+                -5.0
+            );
+        }
+    }
+    #[cfg(feature = "std")]
+    mod test_selection {
+        use super::*;
+        #[test]
+        fn every_selection_keeps_size_generation() {
+            for selection in [
+                Selection::Truncation,
+                Selection::Tournament { k: 2 },
+                Selection::RouletteWheel,
+                Selection::Rank,
+            ] {
+                let routes = evolve_population_with_selection(
+                    Routes::random(6, 5),
+                    3,
+                    3,
+                    &DistanceMat::new(vec![vec![1.0; 5]; 5]),
+                    0,
+                    selection,
+                );
+                assert_eq!(routes.len(), 3);
+            }
+        }
+    }
+    #[cfg(feature = "std")]
+    mod test_elitism {
+        use super::*;
+        use crate::selection::GenerationalScheme;
+        fn best_fitness(routes: &Routes, distance_mat: &DistanceMat) -> f64 {
+            routes.get_n_fittest(1, distance_mat)[0].fitness(distance_mat)
+        }
+        #[test]
+        fn evolve_with_elitism_keeps_best() {
+            let distance_mat = DistanceMat::from_coordinates(&[
+                (0.0, 0.0),
+                (1.0, 5.0),
+                (3.0, 2.0),
+                (6.0, 6.0),
+                (7.0, 1.0),
+                (2.0, 8.0),
+            ]);
+            let mut routes = Routes::random(5, 6);
+            for _ in 0..20 {
+                let best = best_fitness(&routes, &distance_mat);
+                routes = routes.evolve_with_elitism(1.0, 1, &distance_mat);
+                assert!(best_fitness(&routes, &distance_mat) >= best);
+                routes = routes.get_fittest_population(5, &distance_mat);
+            }
+        }
+        #[test]
+        fn best_fitness_never_decreases() {
+            let distance_mat = DistanceMat::from_coordinates(&[
+                (0.0, 0.0),
+                (1.0, 5.0),
+                (3.0, 2.0),
+                (6.0, 6.0),
+                (7.0, 1.0),
+                (2.0, 8.0),
+                (5.0, 4.0),
+            ]);
+            let options = GenerationOptions {
+                mutate_prob: 1.0,
+                selection: Selection::RouletteWheel,
+                scheme: GenerationalScheme::MuCommaLambda,
+                n_elites: 1,
+                ..GenerationOptions::default()
+            };
+            let mut routes = Routes::random(6, 7);
+            let mut best = best_fitness(&routes, &distance_mat);
+            for _ in 0..30 {
+                routes = evolve_generation(routes, 6, &distance_mat, options);
+                let new_best = best_fitness(&routes, &distance_mat);
+                assert!(new_best >= best);
+                best = new_best;
+            }
+        }
+    }
+    #[cfg(feature = "std")]
+    mod test_resource_limits {
+        use super::*;
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        fn distance_mat() -> DistanceMat {
+            DistanceMat::from_coordinates(
+                &(0..12)
+                    .map(|node| ((node * 7 % 12) as f64, (node * 5 % 12) as f64))
+                    .collect::<Vec<(f64, f64)>>(),
+            )
+        }
+        #[test]
+        fn breeders_keep_fittest_route() {
+            let distance_mat = distance_mat();
+            let population = Routes::random(50, 12);
+            let breeders = sample_breeders(
+                &population,
+                Some(100),
+                &distance_mat,
+                &mut StdRng::seed_from_u64(3),
+            )
+            .unwrap();
+            assert_eq!(breeders.len(), 10);
+            assert_eq!(
+                breeders.get_n_fittest(1, &distance_mat),
+                population.get_n_fittest(1, &distance_mat)
+            );
+        }
+        #[test]
+        fn small_population_is_not_sampled() {
+            let distance_mat = distance_mat();
+            let mut rng = StdRng::seed_from_u64(3);
+            assert!(
+                sample_breeders(&Routes::random(10, 12), Some(100), &distance_mat, &mut rng)
+                    .is_none()
+            );
+            assert!(
+                sample_breeders(&Routes::random(50, 12), None, &distance_mat, &mut rng).is_none()
+            );
+        }
+        #[test]
+        fn limited_generation_keeps_best() {
+            let distance_mat = distance_mat();
+            let options = GenerationOptions {
+                max_offspring: Some(25),
+                ..GenerationOptions::default()
+            };
+            let mut routes = Routes::random(40, 12);
+            let mut rng = StdRng::seed_from_u64(11);
+            for _ in 0..10 {
+                let best = routes.get_n_fittest(1, &distance_mat)[0].fitness(&distance_mat);
+                routes =
+                    evolve_generation_with_rng(routes, 40, &distance_mat, options, &mut rng, None);
+                assert!(routes.len() <= 40);
+                assert!(routes.get_n_fittest(1, &distance_mat)[0].fitness(&distance_mat) >= best);
+            }
+            routes = evolve_generation(routes, 40, &distance_mat, options);
+            assert!(routes.len() <= 40);
+        }
+    }
+    mod test_construction {
+        use super::*;
+        #[cfg(feature = "std")]
+        #[test]
+        fn nearest_neighbor_goes_to_closest_node() {
+            // Nodes on a line at 0, 1, 3, 7.
+            let positions = [0.0f64, 1.0, 3.0, 7.0];
+            let distance_mat = DistanceMat::new(
+                positions
+                    .iter()
+                    .map(|a| positions.iter().map(|b| (a - b).abs()).collect())
+                    .collect(),
+            );
+            assert_eq!(nearest_neighbor_tour(2, &distance_mat), vec![2, 1, 0, 3]);
+            let routes = Routes::nearest_neighbor(10, &distance_mat);
+            assert_eq!(routes.len(), 4);
+            let mut starts = routes
+                .iter()
+                .map(|route| route.indexes[0])
+                .collect::<Vec<usize>>();
+            starts.sort();
+            assert_eq!(starts, vec![0, 1, 2, 3]);
+        }
+        #[test]
+        fn nearest_neighbor_is_not_changed_by_candidates() {
+            // Ties between grid points check that both lookups prefer the lower index.
+            let distance_mat = DistanceMat::from_coordinates(
+                &(0..30)
+                    .map(|node| ((node % 6) as f64, (node / 6) as f64))
+                    .collect::<Vec<(f64, f64)>>(),
+            );
+            for k in [1, 3, 29] {
+                let candidates = CandidateList::new(&distance_mat, k);
+                for start in 0..30 {
+                    assert_eq!(
+                        nearest_neighbor_tour_with_candidates(
+                            start,
+                            &distance_mat,
+                            Some(&candidates)
+                        ),
+                        nearest_neighbor_tour_with_candidates(start, &distance_mat, None)
+                    );
+                }
+            }
+        }
+        #[cfg(feature = "std")]
+        #[test]
+        fn greedy_edge_builds_valid_tours() {
+            let distance_mat = DistanceMat::from_coordinates(&[
+                (0.0, 0.0),
+                (1.0, 0.0),
+                (2.0, 0.0),
+                (2.0, 1.0),
+                (1.0, 1.0),
+                (0.0, 1.0),
+            ]);
+            for start in 0..6 {
+                let mut tour = greedy_edge_tour(start, &distance_mat);
+                assert_eq!(tour[0], start);
+                tour.sort();
+                assert_eq!(tour, (0..6).collect::<Vec<usize>>());
+            }
+            // The greedy edges give the perimeter of the grid.
+            let tour = greedy_edge_tour(0, &distance_mat);
+            assert!((distance_mat.get_distance(&tour).unwrap() - 6.0).abs() < 1e-9);
+            assert_eq!(Routes::greedy_edge(4, &distance_mat).len(), 4);
+        }
+        #[cfg(feature = "std")]
+        #[test]
+        fn greedy_edge_on_tiny_instances() {
+            let distance_mat = DistanceMat::new(vec![vec![0.0, 1.0], vec![1.0, 0.0]]);
+            assert_eq!(greedy_edge_tour(1, &distance_mat), vec![1, 0]);
+            let distance_mat = DistanceMat::new(vec![vec![0.0]]);
+            assert_eq!(greedy_edge_tour(0, &distance_mat), vec![0]);
+        }
+    }
+    #[cfg(feature = "std")]
+    mod test_diversity {
+        use super::*;
+        #[test]
+        fn converged_population() {
+            let routes = Routes::from(vec![
+                Route::new(vec![0, 1, 2, 3, 4]),
+                Route::new(vec![4, 3, 2, 1, 0]),
+                Route::new(vec![2, 3, 4, 0, 1]),
+            ]);
+            let diversity = routes.diversity();
+            assert_eq!(diversity.n_unique, 1);
+            assert_eq!(diversity.mean_edge_overlap, 1.0);
+            assert!((diversity.edge_entropy - 5f64.log2()).abs() < 1e-12);
+        }
+        #[test]
+        fn routes_without_common_edges() {
+            // The two round-trips through five nodes share no edge.
+            let routes = Routes::from(vec![
+                Route::new(vec![0, 1, 2, 3, 4]),
+                Route::new(vec![0, 2, 4, 1, 3]),
+            ]);
+            let diversity = routes.diversity();
+            assert_eq!(diversity.n_unique, 2);
+            assert_eq!(diversity.mean_edge_overlap, 0.0);
+            assert!((diversity.edge_entropy - 10f64.log2()).abs() < 1e-12);
+        }
+        #[test]
+        fn partial_overlap() {
+            // (0,1), (1,2), (2,3), (0,3) and (0,2), (1,2), (1,3), (0,3)
+            let routes = Routes::from(vec![
+                Route::new(vec![0, 1, 2, 3]),
+                Route::new(vec![0, 2, 1, 3]),
+            ]);
+            assert_eq!(routes.diversity().mean_edge_overlap, 0.5);
+        }
+    }
+    #[cfg(feature = "std")]
+    mod test_fitnesses {
+        use super::*;
+        #[test]
+        fn fitnesses_match_routes() {
+            let distance_mat = test_dist_mat();
+            let routes = Routes::random(6, 3);
+            for (fitness, route) in routes.fitnesses(&distance_mat) {
+                assert_eq!(fitness, route.fitness(&distance_mat));
+            }
+            assert_eq!(routes.fitnesses(&distance_mat).len(), routes.len());
+        }
+        #[test]
+        fn evolve_individuals_breeds_every_pair() {
+            let routes = Routes::random(4, 5);
+            let individuals = routes.evolve_individuals(0.5);
+            assert_eq!(individuals.len(), 4 * 3 + 4);
+            assert!(routes.iter().all(|route| individuals.contains(route)));
+        }
+    }
+    mod test_tour_equivalence {
+        use super::*;
+        use crate::objective::TourEquivalence;
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        fn rotations_and_reverse() -> Routes {
+            Routes::from(vec![
+                Route::new(vec![0, 1, 2, 3]),
+                Route::new(vec![1, 2, 3, 0]),
+                Route::new(vec![3, 2, 1, 0]),
+            ])
+        }
+        #[test]
+        fn symmetric_mirrors_are_identical() {
+            let distance_mat = DistanceMat::new(vec![
+                vec![0.0, 1.0, 2.0, 3.0],
+                vec![1.0, 0.0, 4.0, 5.0],
+                vec![2.0, 4.0, 0.0, 6.0],
+                vec![3.0, 5.0, 6.0, 0.0],
+            ])
+            .with_tour_equivalence(TourEquivalence::RoundTrip);
+            assert_eq!(
+                rotations_and_reverse()
+                    .get_n_fittest(3, &distance_mat)
+                    .len(),
+                1
+            );
+            assert_eq!(rotations_and_reverse().dedup_tours(&distance_mat).len(), 1);
+        }
+        #[test]
+        fn asymmetric_mirrors_are_distinct() {
+            let distance_mat = DistanceMat::new(vec![
+                vec![0.0, 1.0, 2.0, 3.0],
+                vec![7.0, 0.0, 4.0, 5.0],
+                vec![2.0, 4.0, 0.0, 6.0],
+                vec![3.0, 5.0, 6.0, 0.0],
+            ])
+            .with_tour_equivalence(TourEquivalence::RoundTrip);
+            assert_eq!(
+                rotations_and_reverse()
+                    .get_n_fittest(3, &distance_mat)
+                    .len(),
+                2
+            );
+            assert_eq!(rotations_and_reverse().dedup_tours(&distance_mat).len(), 2);
+        }
+        #[test]
+        fn sequences_are_distinct_by_default() {
+            let distance_mat = DistanceMat::new(vec![vec![1.0; 4]; 4]);
+            assert_eq!(
+                rotations_and_reverse()
+                    .get_n_fittest(3, &distance_mat)
+                    .len(),
+                3
+            );
+        }
+        #[test]
+        fn replacements_are_seeded_and_distinct_tours() {
+            let distance_mat = DistanceMat::new(vec![vec![1.0; 5]; 5])
+                .with_tour_equivalence(TourEquivalence::RoundTrip);
+            let routes = Routes::from(vec![
+                Route::new(vec![0, 1, 2, 3, 4]),
+                Route::new(vec![1, 2, 3, 4, 0]),
+                Route::new(vec![4, 3, 2, 1, 0]),
+                Route::new(vec![0, 2, 1, 3, 4]),
+            ]);
+            let replace = |seed| {
+                routes.clone().apply_duplicate_policy(
+                    DuplicatePolicy::DedupeWithRandomReplacement,
+                    &distance_mat,
+                    &mut StdRng::seed_from_u64(seed),
+                )
+            };
+            let replaced = replace(7);
+            assert_eq!(replaced, replace(7));
+            assert_eq!(replaced.len(), 4);
+            let tour_keys = replaced
+                .iter()
+                .map(|route| distance_mat.tour_key(&route.indexes))
+                .collect::<HashSet<_>>();
+            assert_eq!(tour_keys.len(), 4);
+        }
+        #[test]
+        fn replacements_give_up_without_distinct_tours() {
+            // Four nodes only form three distinct round-trips.
+            let distance_mat = DistanceMat::new(vec![vec![1.0; 4]; 4])
+                .with_tour_equivalence(TourEquivalence::RoundTrip);
+            let routes = Routes::random_with_rng(10, 4, &mut StdRng::seed_from_u64(0));
+            assert!(routes.len() > 3);
+            let replaced = routes.apply_duplicate_policy(
+                DuplicatePolicy::DedupeWithRandomReplacement,
+                &distance_mat,
+                &mut StdRng::seed_from_u64(1),
+            );
+            assert_eq!(replaced.len(), 3);
+        }
+    }
+    mod test_constraint {
+        use super::*;
+        #[cfg(feature = "std")]
+        use crate::constraint::TourHalves;
+        use crate::constraint::{FixedEndpoints, Precedence};
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        #[test]
+        fn operators_keep_fixed_ends() {
+            let coordinates = (0..12)
+                .map(|node| (node as f64, (node * node % 7) as f64))
+                .collect::<Vec<(f64, f64)>>();
+            let distance_mat = DistanceMat::from_coordinates(&coordinates).with_candidate_list(4);
+            let constraint = FixedEndpoints::new(Some(3), Some(7));
+            let mut rng = StdRng::seed_from_u64(7);
+            let parents = Routes::from(
+                (0..6)
+                    .map(|_| {
+                        constraint.repair(
+                            Route::new(random_permutation_with_rng(
+                                &(0..12).collect::<Vec<usize>>(),
+                                &mut rng,
+                            )),
+                            &distance_mat,
+                        )
+                    })
+                    .collect::<Vec<Route>>(),
+            );
+            for crossover_operator in [
+                CrossoverOperator::Ordered,
+                CrossoverOperator::PartiallyMapped,
+                CrossoverOperator::Cycle,
+                CrossoverOperator::EdgeRecombination,
+                CrossoverOperator::Spatial,
+                CrossoverOperator::Candidate,
+            ] {
+                for mutation_operator in [
+                    MutationOperator::Insertion,
+                    MutationOperator::Swap,
+                    MutationOperator::Inversion,
+                    MutationOperator::Scramble,
+                    MutationOperator::Candidate,
+                ] {
+                    let options = GenerationOptions {
+                        mutate_prob: 1.0,
+                        crossover_operator,
+                        mutation_operator,
+                        constraint: Some(&constraint),
+                        ..GenerationOptions::default()
+                    };
+                    let offspring =
+                        breed_offspring(&parents, &distance_mat, options, &mut rng, None);
+                    for child in offspring.iter() {
+                        assert!(constraint.is_feasible(child, &distance_mat));
+                        let mut nodes = child.indexes.clone();
+                        nodes.sort_unstable();
+                        assert_eq!(nodes, (0..12).collect::<Vec<usize>>());
+                    }
+                }
+            }
+        }
+        #[test]
+        fn feasible_routes_come_first() {
+            let distance_mat = DistanceMat::new(vec![
+                vec![0.0, 1.0, 9.0, 1.0],
+                vec![1.0, 0.0, 1.0, 9.0],
+                vec![9.0, 1.0, 0.0, 1.0],
+                vec![1.0, 9.0, 1.0, 0.0],
+            ]);
+            let constraint = Precedence::new(vec![(2, 1), (3, 1)]);
+            let routes = Routes::from(vec![
+                Route::new(vec![0, 1, 2, 3]),
+                Route::new(vec![0, 2, 1, 3]),
+                Route::new(vec![0, 2, 3, 1]),
+            ]);
+            assert_eq!(
+                routes.get_n_fittest_feasible_first(3, &distance_mat, &constraint),
+                vec![
+                    Route::new(vec![0, 2, 3, 1]),
+                    Route::new(vec![0, 2, 1, 3]),
+                    Route::new(vec![0, 1, 2, 3]),
+                ]
+            );
+        }
+        /// The square of `feasible_routes_come_first` with the only feasible order [2, 1, 3, 0],
+        /// whose round-trip is four times as long as the shortest ones.
+        fn square_with_one_feasible_order() -> (DistanceMat, Precedence) {
+            (
+                DistanceMat::new(vec![
+                    vec![0.0, 1.0, 9.0, 1.0],
+                    vec![1.0, 0.0, 1.0, 9.0],
+                    vec![9.0, 1.0, 0.0, 1.0],
+                    vec![1.0, 9.0, 1.0, 0.0],
+                ]),
+                Precedence::new(vec![(2, 1), (1, 3), (3, 0)]),
+            )
+        }
+        #[test]
+        fn discarded_parents_keep_the_feasible_elite() {
+            let (distance_mat, constraint) = square_with_one_feasible_order();
+            let feasible = Route::new(vec![2, 1, 3, 0]);
+            let parents = Routes::from(vec![feasible.clone(), Route::new(vec![0, 1, 2, 3])]);
+            let offspring = parents
+                .clone()
+                .add_vec_route(vec![Route::new(vec![1, 2, 3, 0])]);
+            let options = GenerationOptions {
+                constraint: Some(&constraint),
+                scheme: GenerationalScheme::MuCommaLambda,
+                n_elites: 1,
+                ..GenerationOptions::default()
+            };
+            let population = select_generation(
+                parents,
+                offspring,
+                1,
+                &distance_mat,
+                options,
+                &mut StdRng::seed_from_u64(0),
+            );
+            assert_eq!(population, Routes::from(vec![feasible]));
+        }
+        #[test]
+        fn refined_routes_are_ranked_feasible_first() {
+            let (distance_mat, constraint) = square_with_one_feasible_order();
+            let feasible = Route::new(vec![2, 1, 3, 0]);
+            // 2-opt shortens the feasible route to an infeasible one.
+            assert!(!constraint.is_feasible(&feasible.two_opt(&distance_mat), &distance_mat));
+            for n_elites in [0, 1] {
+                let options = GenerationOptions {
+                    constraint: Some(&constraint),
+                    n_refined: 1,
+                    n_elites,
+                    ..GenerationOptions::default()
+                };
+                let population = select_generation(
+                    Routes::from(vec![feasible.clone()]),
+                    Routes::from(vec![feasible.clone()]),
+                    1,
+                    &distance_mat,
+                    options,
+                    &mut StdRng::seed_from_u64(0),
+                );
+                assert_eq!(population, Routes::from(vec![feasible.clone()]));
+            }
+        }
+        #[test]
+        fn elites_survive_the_deduplication() {
+            let (distance_mat, constraint) = square_with_one_feasible_order();
+            let feasible = Route::new(vec![2, 1, 3, 0]);
+            // The same round-trip, but it visits node 1 after node 3.
+            let rotated = Route::new(vec![1, 3, 0, 2]);
+            for (n_elites, survivor) in [(0, &rotated), (1, &feasible)] {
+                let options = GenerationOptions {
+                    constraint: Some(&constraint),
+                    n_elites,
+                    duplicates: DuplicatePolicy::Dedupe,
+                    ..GenerationOptions::default()
+                };
+                let population = select_generation(
+                    Routes::from(vec![feasible.clone()]),
+                    Routes::from(vec![feasible.clone(), rotated.clone()]),
+                    2,
+                    &distance_mat,
+                    options,
+                    &mut StdRng::seed_from_u64(0),
+                );
+                assert_eq!(
+                    population.get_n_fittest_feasible_first(1, &distance_mat, &constraint),
+                    vec![survivor.clone()]
+                );
+            }
+        }
+        #[cfg(feature = "std")]
+        #[test]
+        fn evolution_finds_feasible_route() {
+            let distance_mat = DistanceMat::new(vec![vec![1.0; 5]; 5]);
+            let constraint = Precedence::new(vec![(4, 0), (3, 1)]);
+            let routes = evolve_population_constrained(
+                Routes::random(6, 5),
+                20,
+                6,
+                &distance_mat,
+                0,
+                &constraint,
+            );
+            let best = &routes.get_n_fittest_feasible_first(1, &distance_mat, &constraint)[0];
+            assert!(constraint.is_feasible(best, &distance_mat));
+        }
+        #[cfg(feature = "std")]
+        #[test]
+        fn offspring_are_repaired() {
+            let distance_mat = DistanceMat::from_coordinates(&[
+                (0.0, 0.0),
+                (1.0, 5.0),
+                (3.0, 2.0),
+                (6.0, 6.0),
+                (7.0, 1.0),
+                (2.0, 8.0),
+            ]);
+            let constraint = TourHalves::new(vec![3, 4], vec![0, 1]);
+            let routes = evolve_population_constrained(
+                Routes::from(vec![Route::new(vec![0, 1, 2, 3, 4, 5])]),
+                1,
+                1,
+                &distance_mat,
+                0,
+                &constraint,
+            );
+            assert!(
+                constraint.is_feasible(&routes.get_n_fittest(1, &distance_mat)[0], &distance_mat)
+            );
+        }
+    }
+    #[cfg(feature = "std")]
+    mod test_pinning {
+        use super::*;
+        #[test]
+        fn pinned_prefix_survives_evolution() {
+            let distance_mat = DistanceMat::new(vec![
+                vec![0.0, 1.0, 9.0, 9.0, 1.0],
+                vec![1.0, 0.0, 1.0, 9.0, 9.0],
+                vec![9.0, 1.0, 0.0, 1.0, 9.0],
+                vec![9.0, 9.0, 1.0, 0.0, 1.0],
+                vec![1.0, 9.0, 9.0, 1.0, 0.0],
+            ]);
+            for n_jobs in [0, 2] {
+                let routes = evolve_population_pinned(
+                    Routes::random(5, 5),
+                    &[2, 0],
+                    10,
+                    5,
+                    &distance_mat,
+                    n_jobs,
+                );
+                assert!(!routes.is_empty());
+                for route in routes.iter() {
+                    assert_eq!(route.first_legs(1), &[2, 0]);
+                    valid_permutation(&[0, 1, 2, 3, 4], &route.indexes);
+                }
+            }
+        }
+    }
+    #[cfg(feature = "std")]
+    mod test_global_best {
+        use super::*;
+        #[test]
+        fn keeps_minimum_across_threads() {
+            let global_best = GlobalBest::new();
+            crossbeam_utils::thread::scope(|s| {
+                for thread in 0..4 {
+                    let global_best = &global_best;
+                    s.spawn(move |_| {
+                        for cost in (0..100).rev() {
+                            global_best.update((cost * 4 + thread) as f64);
+                        }
+                    });
+                }
+            })
+            .unwrap();
+            assert_eq!(global_best.cost(), 0.0);
+        }
+        #[test]
+        fn stops_once_target_is_reached() {
+            // Without the early stop a million generations would take far too long.
+            for n_jobs in [0, 2] {
+                let global_best = GlobalBest::new();
+                let (routes, stop_reason) = evolve_population_with_target(
+                    Routes::random(5, 3),
+                    1_000_000,
+                    5,
+                    &test_dist_mat(),
+                    n_jobs,
+                    f64::INFINITY,
+                    &global_best,
+                );
+                assert!(!routes.is_empty());
+                assert_eq!(stop_reason, StopReason::TargetReached);
+                assert!(global_best.cost().is_finite());
+            }
+        }
+        #[test]
+        fn unreachable_target_runs_all_generations() {
+            let distance_mat = test_dist_mat();
+            let global_best = GlobalBest::new();
+            let (routes, stop_reason) = evolve_population_with_target(
+                Routes::random(5, 3),
+                3,
+                5,
+                &distance_mat,
+                0,
+                -1.0,
+                &global_best,
+            );
+            assert_eq!(stop_reason, StopReason::GenerationsExhausted);
+            assert_eq!(
+                global_best.cost(),
+                -routes.get_n_fittest(1, &distance_mat)[0].fitness(&distance_mat)
+            );
+        }
+    }
     #[test]
     fn test() {
-        let mut set = HashSet::with_capacity_and_hasher(1000, xx::Hash64);
+        let mut set = HashSet::with_capacity_and_hasher(1000, FixedState::default());
         set.insert(Route::new(vec![1, 2, 3]));
     }
 }
@@ -0,0 +1,377 @@
+#[cfg(not(any(feature = "std", test)))]
+use crate::compat::Float;
+use crate::distance_mat::DistanceMat;
+use crate::route::{MutationOperator, Route, CANDIDATES_MISSING};
+use crate::utils::{get_random_elem_from_range_with_rng, move_elem};
+use alloc::vec::Vec;
+use rand::Rng;
+
+/// How many random moves are sampled from the start route to estimate the initial
+/// temperature.
+const N_TEMPERATURE_SAMPLES: usize = 100;
+
+/// Simulated annealing, a single-route baseline for the genetic algorithm on the same
+/// `Route` and `DistanceMat`: in every iteration one random move of a `MutationOperator` is
+/// proposed, an improvement is always accepted and a deterioration by `delta` with the
+/// probability `exp(-delta / temperature)` (the Metropolis criterion). The temperature starts
+/// high, so that the search walks freely, and is multiplied by `cooling_rate` after every
+/// iteration, so that it ends as a local search. Inversions and insertions are evaluated in
+/// O(1) with `DistanceMat::delta_two_opt` and `DistanceMat::delta_relocate`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SimulatedAnnealing {
+    /// How many moves are proposed.
+    pub n_iterations: usize,
+    /// The temperature of the first iteration. If it is `None`, it is estimated from the start
+    /// route, so that an average deterioration is accepted with a probability of one half.
+    pub initial_temperature: Option<f64>,
+    /// By how much the temperature is multiplied after every iteration, between 0 and 1.
+    pub cooling_rate: f64,
+    /// The moves that are proposed.
+    pub operator: MutationOperator,
+}
+impl SimulatedAnnealing {
+    /// Create a schedule that proposes inversions (2-opt moves), estimates its initial
+    /// temperature from the start route and cools it to a thousandth of it over the run.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_iterations` - How many moves are proposed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::sa::SimulatedAnnealing;
+    ///
+    /// let annealing = SimulatedAnnealing::new(10_000);
+    /// assert!(annealing.cooling_rate < 1.0);
+    /// ```
+    pub fn new(n_iterations: usize) -> Self {
+        SimulatedAnnealing {
+            n_iterations,
+            initial_temperature: None,
+            cooling_rate: 0.001_f64.powf(1.0 / n_iterations.max(1) as f64),
+            operator: MutationOperator::Inversion,
+        }
+    }
+    /// Start with a fixed temperature instead of estimating it from the start route.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial_temperature` - The temperature of the first iteration.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::sa::SimulatedAnnealing;
+    ///
+    /// let annealing = SimulatedAnnealing::new(10_000).with_initial_temperature(100.0);
+    /// assert_eq!(annealing.initial_temperature, Some(100.0));
+    /// ```
+    pub fn with_initial_temperature(mut self, initial_temperature: f64) -> Self {
+        self.initial_temperature = Some(initial_temperature);
+        self
+    }
+    /// Set by how much the temperature is multiplied after every iteration.
+    ///
+    /// # Arguments
+    ///
+    /// * `cooling_rate` - The factor of the geometric cooling, between 0 and 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::sa::SimulatedAnnealing;
+    ///
+    /// let annealing = SimulatedAnnealing::new(10_000).with_cooling_rate(0.999);
+    /// assert_eq!(annealing.cooling_rate, 0.999);
+    /// ```
+    pub fn with_cooling_rate(mut self, cooling_rate: f64) -> Self {
+        self.cooling_rate = cooling_rate;
+        self
+    }
+    /// Set which moves are proposed.
+    ///
+    /// # Arguments
+    ///
+    /// * `operator` - The mutation whose moves are proposed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::route::MutationOperator;
+    /// use genetic_algorithm_tsp::sa::SimulatedAnnealing;
+    ///
+    /// let annealing = SimulatedAnnealing::new(10_000).with_operator(MutationOperator::Insertion);
+    /// ```
+    pub fn with_operator(mut self, operator: MutationOperator) -> Self {
+        self.operator = operator;
+        self
+    }
+    /// Anneal a route and return the best route that was visited.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The route the search starts from.
+    /// * `distance_mat` - The distance matrix the cost is computed on.
+    /// * `rng` - The random number generator the moves and their acceptance are drawn from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_tsp::sa::SimulatedAnnealing;
+    /// use rand::SeedableRng;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 9.0, 1.0],
+    ///     vec![1.0, 0.0, 1.0, 9.0],
+    ///     vec![9.0, 1.0, 0.0, 1.0],
+    ///     vec![1.0, 9.0, 1.0, 0.0],
+    /// ]);
+    /// let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+    /// let result = SimulatedAnnealing::new(1_000).anneal(&Route::new(vec![0, 2, 1, 3]), &distance_matrix, &mut rng);
+    /// assert_eq!(result.cost, 4.0);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the operator is `MutationOperator::Candidate`, but the distance matrix has no
+    /// candidate list.
+    ///
+    pub fn anneal<R: Rng + ?Sized>(
+        &self,
+        route: &Route,
+        distance_mat: &DistanceMat,
+        rng: &mut R,
+    ) -> AnnealingResult {
+        assert!(
+            self.operator != MutationOperator::Candidate || distance_mat.candidate_list().is_some(),
+            "{}",
+            CANDIDATES_MISSING
+        );
+        let mut indexes = route.indexes.clone();
+        let mut cost = distance_mat.get_cost(&indexes);
+        let mut best = (indexes.clone(), cost);
+        let mut temperature = match self.initial_temperature {
+            Some(temperature) => temperature,
+            None => self.estimate_temperature(&indexes, distance_mat, cost, rng),
+        };
+        let mut n_accepted = 0;
+        for _ in 0..self.n_iterations {
+            let (proposal, delta) = self.propose(&indexes, distance_mat, cost, rng);
+            if delta <= 0.0
+                || (temperature > 0.0
+                    && get_random_elem_from_range_with_rng(0.0..1.0, rng)
+                        < (-delta / temperature).exp())
+            {
+                proposal.apply(&mut indexes);
+                cost += delta;
+                n_accepted += 1;
+                if cost < best.1 {
+                    best = (indexes.clone(), cost);
+                }
+            }
+            temperature *= self.cooling_rate;
+        }
+        // The deltas add up rounding errors over the run, the best route is evaluated anew.
+        let cost = distance_mat.get_cost(&best.0);
+        AnnealingResult {
+            route: Route::new(best.0),
+            cost,
+            n_accepted,
+            final_temperature: temperature,
+        }
+    }
+    /// Estimate a temperature at which an average deterioration around a route is accepted with
+    /// a probability of one half, 0 if no sampled move makes the route worse.
+    ///
+    /// # Arguments
+    ///
+    /// * `indexes` - The route the moves are sampled around.
+    /// * `distance_mat` - The distance matrix the cost is computed on.
+    /// * `cost` - The cost of the route.
+    /// * `rng` - The random number generator the moves are drawn from.
+    ///
+    fn estimate_temperature<R: Rng + ?Sized>(
+        &self,
+        indexes: &[usize],
+        distance_mat: &DistanceMat,
+        cost: f64,
+        rng: &mut R,
+    ) -> f64 {
+        let deteriorations = (0..N_TEMPERATURE_SAMPLES)
+            .map(|_| self.propose(indexes, distance_mat, cost, rng).1)
+            .filter(|delta| *delta > 0.0)
+            .collect::<Vec<f64>>();
+        if deteriorations.is_empty() {
+            return 0.0;
+        }
+        let mean = deteriorations.iter().sum::<f64>() / deteriorations.len() as f64;
+        mean / 2.0_f64.ln()
+    }
+    /// Draw a random move of the operator and compute how it changes the cost of a route.
+    ///
+    /// # Arguments
+    ///
+    /// * `indexes` - The route the move is drawn for.
+    /// * `distance_mat` - The distance matrix the cost is computed on.
+    /// * `cost` - The cost of the route.
+    /// * `rng` - The random number generator the move is drawn from.
+    ///
+    fn propose<R: Rng + ?Sized>(
+        &self,
+        indexes: &[usize],
+        distance_mat: &DistanceMat,
+        cost: f64,
+        rng: &mut R,
+    ) -> (Move, f64) {
+        let n_nodes = indexes.len();
+        if n_nodes < 3 {
+            return (Move::Keep, 0.0);
+        }
+        match (self.operator, distance_mat.candidate_list()) {
+            (MutationOperator::Insertion, _) => {
+                let move_idx = get_random_elem_from_range_with_rng(0..n_nodes, rng);
+                let put_before_idx = get_random_elem_from_range_with_rng(0..(n_nodes + 1), rng);
+                (
+                    Move::Relocation {
+                        put_before_idx,
+                        move_idx,
+                    },
+                    distance_mat.delta_relocate(indexes, put_before_idx, move_idx),
+                )
+            }
+            (MutationOperator::Inversion, _) => {
+                let first_idx = get_random_elem_from_range_with_rng(0..(n_nodes - 1), rng);
+                let last_idx = get_random_elem_from_range_with_rng((first_idx + 1)..n_nodes, rng);
+                (
+                    Move::Reversal {
+                        first_idx,
+                        last_idx,
+                    },
+                    distance_mat.delta_two_opt(indexes, first_idx, last_idx),
+                )
+            }
+            (MutationOperator::Candidate, Some(candidates)) => {
+                let route =
+                    Route::new(indexes.to_vec()).candidate_mutate_with_rng(1.0, candidates, rng);
+                let delta = distance_mat.get_cost(&route.indexes) - cost;
+                (Move::Replacement(route.indexes), delta)
+            }
+            (operator, _) => {
+                let route = Route::new(indexes.to_vec()).mutate_with_operator(1.0, operator, rng);
+                let delta = distance_mat.get_cost(&route.indexes) - cost;
+                (Move::Replacement(route.indexes), delta)
+            }
+        }
+    }
+}
+
+/// A move proposed by `SimulatedAnnealing`, which is only applied once it is accepted.
+#[derive(Debug, Clone, PartialEq)]
+enum Move {
+    /// Leave the route unchanged, e.g. because it is too short for any move.
+    Keep,
+    /// Reverse the nodes at the positions `first_idx + 1` up to `last_idx`.
+    Reversal { first_idx: usize, last_idx: usize },
+    /// Move the node at `move_idx` in front of the node at `put_before_idx`.
+    Relocation {
+        put_before_idx: usize,
+        move_idx: usize,
+    },
+    /// Replace the route by another one, for moves without a local cost update.
+    Replacement(Vec<usize>),
+}
+impl Move {
+    /// Apply the move to a route.
+    ///
+    /// # Arguments
+    ///
+    /// * `indexes` - The route that is changed.
+    ///
+    fn apply(self, indexes: &mut Vec<usize>) {
+        match self {
+            Move::Keep => {}
+            Move::Reversal {
+                first_idx,
+                last_idx,
+            } => indexes[(first_idx + 1)..=last_idx].reverse(),
+            Move::Relocation {
+                put_before_idx,
+                move_idx,
+            } => move_elem(indexes, put_before_idx, move_idx),
+            Move::Replacement(route) => *indexes = route,
+        }
+    }
+}
+
+/// The outcome of `SimulatedAnnealing::anneal`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnnealingResult {
+    /// The best route that was visited.
+    pub route: Route,
+    /// The cost of the best route.
+    pub cost: f64,
+    /// How many of the proposed moves were accepted.
+    pub n_accepted: usize,
+    /// The temperature after the last iteration.
+    pub final_temperature: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{clustered_dist_mat, valid_permutation};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn annealing_improves_a_random_route() {
+        let distance_mat = clustered_dist_mat(4, 5).with_candidate_list(5);
+        let route = Route::new(vec![
+            0, 5, 10, 15, 1, 6, 11, 16, 2, 7, 12, 17, 3, 8, 13, 18, 4, 9, 14, 19,
+        ]);
+        let cost = distance_mat.get_cost(&route.indexes);
+        for operator in [
+            MutationOperator::Insertion,
+            MutationOperator::Swap,
+            MutationOperator::Inversion,
+            MutationOperator::Scramble,
+            MutationOperator::Candidate,
+        ] {
+            let result = SimulatedAnnealing::new(20_000)
+                .with_operator(operator)
+                .anneal(&route, &distance_mat, &mut StdRng::seed_from_u64(0));
+            valid_permutation(&route.indexes, &result.route.indexes);
+            assert_eq!(result.cost, distance_mat.get_cost(&result.route.indexes));
+            assert!(
+                result.cost < cost,
+                "{:?} didn't improve the route",
+                operator
+            );
+        }
+    }
+    #[test]
+    fn annealing_is_deterministic_given_the_seed() {
+        let distance_mat = clustered_dist_mat(3, 4);
+        let route = Route::new((0..12).collect());
+        let annealing = SimulatedAnnealing::new(2_000);
+        assert_eq!(
+            annealing.anneal(&route, &distance_mat, &mut StdRng::seed_from_u64(7)),
+            annealing.anneal(&route, &distance_mat, &mut StdRng::seed_from_u64(7))
+        );
+    }
+    #[test]
+    fn cold_annealing_only_accepts_improvements() {
+        let distance_mat = clustered_dist_mat(3, 4);
+        let route = Route::new((0..12).collect());
+        let result = SimulatedAnnealing::new(5_000)
+            .with_initial_temperature(0.0)
+            .anneal(&route, &distance_mat, &mut StdRng::seed_from_u64(0));
+        // Without any uphill move, the result is a local optimum of the inversions.
+        assert_eq!(result.route.two_opt(&distance_mat), result.route);
+        assert_eq!(result.final_temperature, 0.0);
+    }
+}
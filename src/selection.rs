@@ -0,0 +1,302 @@
+use crate::utils::argsort;
+use alloc::vec::Vec;
+use core::cmp::{max, min, Ordering};
+#[cfg(feature = "std")]
+use genetic_algorithm_traits::{Individual, Population};
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::seq::index;
+use rand::Rng;
+
+/// How the individuals that survive a generation are selected from the offspring.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Selection {
+    /// Keep the fittest individuals, e.g. `get_n_fittest`. This is the fastest selection but
+    /// reduces the diversity of the population quickly.
+    #[default]
+    Truncation,
+    /// Draw `k` individuals at random and keep the fittest of them, until enough individuals
+    /// are kept. A larger `k` selects more greedily.
+    Tournament {
+        /// The number of individuals that compete in every tournament.
+        k: usize,
+    },
+    /// Draw individuals with a probability proportional to how much fitter they are than the
+    /// least fit individual of the population.
+    RouletteWheel,
+    /// Draw individuals with a probability proportional to their fitness rank: the fittest of
+    /// `n` individuals has weight `n`, the least fit weight 1.
+    Rank,
+}
+
+/// Which individuals compete for survival in every generation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GenerationalScheme {
+    /// (μ+λ): the parents compete with their offspring, so the best individual is only lost if
+    /// neither the truncation selection nor an elite keeps it. This is how `Routes::evolve`
+    /// works.
+    #[default]
+    MuPlusLambda,
+    /// (μ,λ): only the offspring survive and the parents are discarded, which escapes local
+    /// optima more easily but loses the best individual unless it is kept as an elite, see
+    /// `EvolutionConfig::with_elitism`. Offspring that are equal to a
+    /// parent are discarded as well. If no offspring differs from the parents, the parents
+    /// are kept, so that the population doesn't die out.
+    MuCommaLambda,
+}
+
+/// What happens to duplicates in the population that survives a generation. A population is a
+/// set, so identical individuals are always stored once and only equivalent individuals can be
+/// kept several times. For `Routes`, routes that describe the same round-trip, e.g. rotated or
+/// reversed copies that have the same `DistanceMat::tour_key`, are equivalent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DuplicatePolicy {
+    /// Keep all distinct individuals, even if they are equivalent, e.g. describe the same
+    /// round-trip. This is how `Routes::evolve` works.
+    #[default]
+    #[cfg_attr(feature = "serde", serde(alias = "AllowDuplicates"))]
+    KeepEquivalent,
+    /// Keep a single individual of every round-trip, so the population can shrink below the
+    /// size of a generation.
+    Dedupe,
+    /// Keep a single individual of every round-trip and replace the removed ones with fresh
+    /// random individuals of new round-trips, which keeps the size of the population and its
+    /// diversity.
+    DedupeWithRandomReplacement,
+}
+
+/// Select individuals from a population with a `Selection`. It is implemented for every
+/// `Population`.
+#[cfg(feature = "std")]
+pub trait Select<'a>: Population<'a> {
+    /// Select `n` distinct individuals of the population, or all of them if the population is
+    /// smaller. Apart from `Selection::Truncation` the individuals are drawn without replacement
+    /// in random order, so that fitter individuals are more likely but not sure to be selected.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The number of individuals you would like to get.
+    /// * `cost_data` - The cost data structure your individuals need to compute their fitness.
+    /// * `selection` - How the individuals are selected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::routes::Routes;
+    /// use genetic_algorithm_tsp::selection::{Select, Selection};
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 2.0, 3.0],
+    ///     vec![1.0, 0.0, 3.0, 1.0],
+    ///     vec![2.0, 3.0, 0.0, 2.0],
+    ///     vec![3.0, 1.0, 2.0, 0.0],
+    /// ]);
+    /// let routes = Routes::random(6, 4);
+    /// let selected = routes.select(3, &distance_matrix, Selection::Tournament { k: 2 });
+    /// assert_eq!(selected.len(), 3);
+    /// ```
+    fn select(
+        &'a self,
+        n: usize,
+        cost_data: &'a <<Self as Population<'a>>::Individual as Individual<'a>>::IndividualCost,
+        selection: Selection,
+    ) -> Vec<Self::Individual> {
+        self.select_with_rng(n, cost_data, selection, &mut rand::thread_rng())
+    }
+    /// Select individuals like `select`, but draw them from `rng`.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The number of individuals you would like to get.
+    /// * `cost_data` - The cost data structure your individuals need to compute their fitness.
+    /// * `selection` - How the individuals are selected.
+    /// * `rng` - The random number generator the individuals are drawn from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::routes::Routes;
+    /// use genetic_algorithm_tsp::selection::{Select, Selection};
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let routes = Routes::random(4, 3);
+    /// assert_eq!(
+    ///     routes.select_with_rng(2, &distance_matrix, Selection::Rank, &mut StdRng::seed_from_u64(7)),
+    ///     routes.select_with_rng(2, &distance_matrix, Selection::Rank, &mut StdRng::seed_from_u64(7))
+    /// );
+    /// ```
+    fn select_with_rng<R: Rng + ?Sized>(
+        &'a self,
+        n: usize,
+        cost_data: &'a <<Self as Population<'a>>::Individual as Individual<'a>>::IndividualCost,
+        selection: Selection,
+        rng: &mut R,
+    ) -> Vec<Self::Individual> {
+        if selection == Selection::Truncation {
+            return self.get_n_fittest(n, cost_data);
+        }
+        sample_fitnesses(self.fitnesses(cost_data), n, selection, rng)
+    }
+}
+#[cfg(feature = "std")]
+impl<'a, P: Population<'a>> Select<'a> for P {}
+
+/// Draw `n` individuals with a `Selection` other than `Selection::Truncation`, which is the
+/// random part of `Select::select_with_rng`.
+///
+/// # Arguments
+///
+/// * `remaining` - The individuals that can be drawn, together with their fitness.
+/// * `n` - The number of individuals you would like to get.
+/// * `selection` - How the individuals are selected.
+/// * `rng` - The random number generator the individuals are drawn from.
+///
+pub(crate) fn sample_fitnesses<I: Clone, R: Rng + ?Sized>(
+    mut remaining: Vec<(f64, &I)>,
+    n: usize,
+    selection: Selection,
+    rng: &mut R,
+) -> Vec<I> {
+    let n = min(n, remaining.len());
+    let mut selected = Vec::with_capacity(n);
+    if let Selection::Tournament { k } = selection {
+        for _ in 0..n {
+            let n_competitors = min(max(k, 1), remaining.len());
+            let winner = index::sample(rng, remaining.len(), n_competitors)
+                .into_iter()
+                .max_by(|a_idx, b_idx| {
+                    remaining[*a_idx]
+                        .0
+                        .partial_cmp(&remaining[*b_idx].0)
+                        .unwrap_or(Ordering::Equal)
+                })
+                .unwrap();
+            selected.push(remaining.swap_remove(winner).1.clone());
+        }
+        return selected;
+    }
+    let mut weights = selection_weights(
+        &remaining
+            .iter()
+            .map(|(fitness, _)| *fitness)
+            .collect::<Vec<f64>>(),
+        selection,
+    );
+    for _ in 0..n {
+        // If all remaining individuals have weight 0, they are drawn uniformly.
+        let drawn = match WeightedIndex::new(&weights) {
+            Ok(distribution) => distribution.sample(rng),
+            Err(_) => rng.gen_range(0..remaining.len()),
+        };
+        weights.swap_remove(drawn);
+        selected.push(remaining.swap_remove(drawn).1.clone());
+    }
+    selected
+}
+
+/// The weight with which every individual is drawn by `Selection::RouletteWheel` or
+/// `Selection::Rank`.
+///
+/// # Arguments
+///
+/// * `fitnesses` - The fitness of every individual.
+/// * `selection` - The selection the weights are computed for.
+///
+fn selection_weights(fitnesses: &[f64], selection: Selection) -> Vec<f64> {
+    match selection {
+        Selection::Rank => {
+            let mut weights = vec![0.0; fitnesses.len()];
+            for (rank, idx) in argsort(fitnesses).into_iter().enumerate() {
+                weights[idx] = (fitnesses.len() - rank) as f64;
+            }
+            weights
+        }
+        _ => {
+            let least_fit = fitnesses.iter().copied().fold(f64::INFINITY, f64::min);
+            fitnesses
+                .iter()
+                .map(|fitness| {
+                    let weight = fitness - least_fit;
+                    if weight.is_finite() {
+                        weight
+                    } else {
+                        0.0
+                    }
+                })
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::route::Route;
+    #[cfg(feature = "std")]
+    use crate::routes::Routes;
+    #[cfg(feature = "std")]
+    use crate::test_utils::test_dist_mat;
+    #[cfg(feature = "std")]
+    use std::collections::HashSet;
+
+    #[cfg(feature = "std")]
+    fn four_routes() -> Routes {
+        Routes::from(vec![
+            Route::new(vec![1, 2, 0]),
+            Route::new(vec![1, 0]),
+            Route::new(vec![2, 0]),
+            Route::new(vec![0]),
+        ])
+    }
+    #[cfg(feature = "std")]
+    #[test]
+    fn truncation_is_get_n_fittest() {
+        let distance_mat = test_dist_mat();
+        assert_eq!(
+            four_routes().select(2, &distance_mat, Selection::Truncation),
+            four_routes().get_n_fittest(2, &distance_mat)
+        );
+    }
+    #[cfg(feature = "std")]
+    #[test]
+    fn selected_routes_are_distinct() {
+        let distance_mat = test_dist_mat();
+        for selection in [
+            Selection::Tournament { k: 2 },
+            Selection::RouletteWheel,
+            Selection::Rank,
+        ] {
+            let selected = four_routes().select(3, &distance_mat, selection);
+            assert_eq!(selected.len(), 3);
+            assert_eq!(selected.iter().collect::<HashSet<&Route>>().len(), 3);
+            assert_eq!(four_routes().select(10, &distance_mat, selection).len(), 4);
+        }
+    }
+    #[cfg(feature = "std")]
+    #[test]
+    fn full_tournament_is_truncation() {
+        let distance_mat = test_dist_mat();
+        assert_eq!(
+            four_routes().select(2, &distance_mat, Selection::Tournament { k: 4 }),
+            vec![Route::new(vec![0]), Route::new(vec![1, 0])]
+        );
+    }
+    #[test]
+    fn weights() {
+        assert_eq!(
+            selection_weights(&[-3.0, -1.0, -2.0], Selection::Rank),
+            vec![1.0, 3.0, 2.0]
+        );
+        assert_eq!(
+            selection_weights(&[-3.0, -1.0, -2.0], Selection::RouletteWheel),
+            vec![0.0, 2.0, 1.0]
+        );
+    }
+}
@@ -0,0 +1,417 @@
+use crate::compat::HashSet;
+use crate::distance_mat::DistanceMat;
+#[cfg(feature = "std")]
+use crate::route::sample_relocation;
+#[cfg(feature = "std")]
+use crate::utils::{get_random_elem_from_range, move_elem, random_permutation};
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
+use genetic_algorithm_traits::Individual;
+
+/// The cost data of the prize-collecting (selective) Traveling Salesman Problem: the distances
+/// between the nodes and the prize that is collected when a node is visited. It is the
+/// `IndividualCost` of `SelectiveRoute`.
+#[derive(Debug)]
+pub struct PrizeCost {
+    /// The distances between the nodes, its objective decides how the length of a route is
+    /// computed.
+    distance_mat: DistanceMat,
+    /// The prize of every node, the prize of the depot is ignored.
+    prizes: Vec<f64>,
+}
+impl PrizeCost {
+    /// Create the cost data of a prize-collecting TSP.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distances between the nodes.
+    /// * `prizes` - The prize of every node, the prize of the depot is ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is not exactly one prize per node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::selective::PrizeCost;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let prize_cost = PrizeCost::new(distance_matrix, vec![0.0, 5.0, 1.0]);
+    /// assert_eq!(prize_cost.prizes(), &[0.0, 5.0, 1.0]);
+    /// ```
+    pub fn new(distance_mat: DistanceMat, prizes: Vec<f64>) -> Self {
+        assert_eq!(
+            prizes.len(),
+            distance_mat.n_units(),
+            "There are {} prizes but the distance matrix has {} nodes",
+            prizes.len(),
+            distance_mat.n_units()
+        );
+        PrizeCost {
+            distance_mat,
+            prizes,
+        }
+    }
+    /// Get the distances between the nodes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::selective::PrizeCost;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let prize_cost = PrizeCost::new(distance_matrix, vec![0.0, 5.0, 1.0]);
+    /// assert_eq!(prize_cost.distance_mat().n_units(), 3);
+    /// ```
+    pub fn distance_mat(&self) -> &DistanceMat {
+        &self.distance_mat
+    }
+    /// Get the prize of every node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::selective::PrizeCost;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert_eq!(PrizeCost::new(distance_matrix, vec![0.0, 5.0, 1.0]).prizes()[1], 5.0);
+    /// ```
+    pub fn prizes(&self) -> &[f64] {
+        &self.prizes
+    }
+    /// Get the sum of the prizes of the nodes a route visits besides its depot.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The route that collects the prizes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::selective::{PrizeCost, SelectiveRoute};
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let prize_cost = PrizeCost::new(distance_matrix, vec![0.0, 5.0, 1.0]);
+    /// assert_eq!(prize_cost.collected(&SelectiveRoute::new(3, 0, vec![1, 2])), 6.0);
+    /// ```
+    pub fn collected(&self, route: &SelectiveRoute) -> f64 {
+        route.indexes.iter().map(|node| self.prizes[*node]).sum()
+    }
+    /// Compute the profit of a route: the prizes it collects minus the cost of its round-trip
+    /// from the depot under the objective of the distance matrix.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The route whose profit is computed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::selective::{PrizeCost, SelectiveRoute};
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let prize_cost = PrizeCost::new(distance_matrix, vec![0.0, 5.0, 1.0]);
+    /// // Visiting node 2 as well costs 4.0 more than its prize.
+    /// assert_eq!(prize_cost.get_profit(&SelectiveRoute::new(3, 0, vec![1])), 3.0);
+    /// assert_eq!(prize_cost.get_profit(&SelectiveRoute::new(3, 0, vec![1, 2])), 0.0);
+    /// ```
+    pub fn get_profit(&self, route: &SelectiveRoute) -> f64 {
+        self.collected(route) - self.distance_mat.get_cost(&route.tour())
+    }
+}
+
+/// A round-trip from a depot that visits only a subset of the other nodes, the individual of
+/// the prize-collecting (selective) Traveling Salesman Problem. Unlike `Route` the individuals
+/// of a population differ in length: mutation adds and removes nodes, and crossover combines
+/// routes of different lengths. Use it with `Individuals` to evolve a population of them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SelectiveRoute {
+    /// The number of nodes of the instance, including the depot.
+    pub n_nodes: usize,
+    /// The node the round-trip starts and ends at.
+    pub depot: usize,
+    /// The nodes other than the depot that are visited, in order. Every node occurs at most
+    /// once.
+    pub indexes: Vec<usize>,
+}
+impl fmt::Display for SelectiveRoute {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "SelectiveRoute({}: {:?})",
+            self.depot, self.indexes
+        )
+    }
+}
+impl SelectiveRoute {
+    /// Create a selective route from the nodes it visits after leaving the depot.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_nodes` - The number of nodes of the instance, including the depot.
+    /// * `depot` - The node the round-trip starts and ends at.
+    /// * `indexes` - The nodes that are visited after leaving the depot, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::selective::SelectiveRoute;
+    ///
+    /// let selective_route = SelectiveRoute::new(5, 0, vec![3, 1]);
+    /// assert_eq!(selective_route.tour(), vec![0, 3, 1]);
+    /// ```
+    pub fn new(n_nodes: usize, depot: usize, indexes: Vec<usize>) -> Self {
+        SelectiveRoute {
+            n_nodes,
+            depot,
+            indexes,
+        }
+    }
+    /// Create a random selective route that visits a random number of random nodes.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_nodes` - The number of nodes of the instance, including the depot.
+    /// * `depot` - The node the round-trip starts and ends at.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::selective::SelectiveRoute;
+    ///
+    /// let selective_route = SelectiveRoute::random(6, 0);
+    /// assert!(selective_route.indexes.len() <= 5);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn random(n_nodes: usize, depot: usize) -> Self {
+        let nodes = (0..n_nodes)
+            .filter(|node| *node != depot)
+            .collect::<Vec<usize>>();
+        let mut indexes = random_permutation(&nodes);
+        indexes.truncate(get_random_elem_from_range(0..(nodes.len() + 1)));
+        SelectiveRoute::new(n_nodes, depot, indexes)
+    }
+    /// Get the round-trip of the route, the depot followed by the visited nodes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::selective::SelectiveRoute;
+    ///
+    /// assert_eq!(SelectiveRoute::new(4, 2, vec![]).tour(), vec![2]);
+    /// ```
+    pub fn tour(&self) -> Vec<usize> {
+        let mut tour = Vec::with_capacity(self.indexes.len() + 1);
+        tour.push(self.depot);
+        tour.extend(self.indexes.iter().copied());
+        tour
+    }
+    /// Get the nodes other than the depot that the route doesn't visit, in ascending order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::selective::SelectiveRoute;
+    ///
+    /// assert_eq!(SelectiveRoute::new(5, 0, vec![3, 1]).unvisited(), vec![2, 4]);
+    /// ```
+    pub fn unvisited(&self) -> Vec<usize> {
+        let visited = self.indexes.iter().collect::<HashSet<&usize>>();
+        (0..self.n_nodes)
+            .filter(|node| *node != self.depot && !visited.contains(node))
+            .collect()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> Individual<'a> for SelectiveRoute {
+    // The cost data holds the prizes next to the distances.
+    type IndividualCost = PrizeCost;
+    /// With probability `prob` either insert an unvisited node at a random position, remove a
+    /// random node, or move a node in front of another node like `Route::mutate`. Each change
+    /// is equally likely among those that are possible for the route.
+    ///
+    /// # Arguments
+    ///
+    /// * `prob` - The probability with which the route will be changed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::selective::SelectiveRoute;
+    /// use genetic_algorithm_traits::Individual;
+    ///
+    /// // The only possible change of an empty route is to visit a node.
+    /// let mutated = SelectiveRoute::new(3, 0, vec![]).mutate(1.0);
+    /// assert_eq!(mutated.indexes.len(), 1);
+    /// ```
+    fn mutate(mut self, prob: f32) -> Self {
+        if get_random_elem_from_range(0.0..1.0) > prob {
+            return self;
+        }
+        let unvisited = self.unvisited();
+        let n_changes = usize::from(!unvisited.is_empty())
+            + usize::from(!self.indexes.is_empty())
+            + usize::from(self.indexes.len() >= 3);
+        if n_changes == 0 {
+            return self;
+        }
+        let mut change = get_random_elem_from_range(0..n_changes);
+        if !unvisited.is_empty() {
+            if change == 0 {
+                let node = unvisited[get_random_elem_from_range(0..unvisited.len())];
+                let position = get_random_elem_from_range(0..(self.indexes.len() + 1));
+                self.indexes.insert(position, node);
+                return self;
+            }
+            change -= 1;
+        }
+        if change == 0 {
+            self.indexes
+                .remove(get_random_elem_from_range(0..self.indexes.len()));
+        } else if let Some((put_before_idx, move_idx)) = sample_relocation(self.indexes.len(), 1.0)
+        {
+            move_elem(&mut self.indexes, put_before_idx, move_idx);
+        }
+        self
+    }
+    /// Cross two routes of possibly different lengths: a random segment of this route is kept
+    /// in order and the nodes of `other` that are not in the segment are placed around it in
+    /// the order of `other`. The child takes its nodes from both parents: it visits the nodes
+    /// of the segment and the nodes both parents visit, but each node that only `other` visits
+    /// is dropped with probability one half, so crossover can shrink routes as well as grow them.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The other individual you would like to crossover with this individual.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::selective::SelectiveRoute;
+    /// use genetic_algorithm_traits::Individual;
+    ///
+    /// let parent_a = SelectiveRoute::new(6, 0, vec![1, 2, 3]);
+    /// let parent_b = SelectiveRoute::new(6, 0, vec![5, 2]);
+    /// let child = parent_a.crossover(&parent_b);
+    /// // Both parents visit node 2.
+    /// assert!(child.indexes.contains(&2) && child.indexes.len() <= 4);
+    /// ```
+    fn crossover(&self, other: &SelectiveRoute) -> Self {
+        let start = get_random_elem_from_range(0..(self.indexes.len() + 1));
+        let end = get_random_elem_from_range(start..(self.indexes.len() + 1));
+        let segment = &self.indexes[start..end];
+        let mut indexes = other
+            .indexes
+            .iter()
+            .filter(|node| !segment.contains(node))
+            .filter(|node| {
+                self.indexes.contains(node) || get_random_elem_from_range(0.0..1.0) < 0.5
+            })
+            .copied()
+            .collect::<Vec<usize>>();
+        let position = start.min(indexes.len());
+        indexes.splice(position..position, segment.iter().copied());
+        SelectiveRoute::new(self.n_nodes, self.depot, indexes)
+    }
+    /// Compute the fitness of the route, its profit with `PrizeCost::get_profit`.
+    ///
+    /// # Arguments
+    ///
+    /// * `prize_cost` - The cost data the fitness is computed on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::selective::{PrizeCost, SelectiveRoute};
+    /// use genetic_algorithm_traits::Individual;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let prize_cost = PrizeCost::new(distance_matrix, vec![0.0, 5.0, 1.0]);
+    /// assert_eq!(SelectiveRoute::new(3, 0, vec![1]).fitness(&prize_cost), 3.0);
+    /// ```
+    fn fitness(&self, prize_cost: &PrizeCost) -> f64 {
+        prize_cost.get_profit(self)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::individuals::Individuals;
+    use genetic_algorithm_traits::Population;
+
+    fn assert_valid(route: &SelectiveRoute) {
+        let visited = route.indexes.iter().collect::<HashSet<&usize>>();
+        assert_eq!(visited.len(), route.indexes.len());
+        assert!(route
+            .indexes
+            .iter()
+            .all(|node| *node != route.depot && *node < route.n_nodes));
+    }
+
+    #[test]
+    fn operators_keep_routes_valid() {
+        let parent_a = SelectiveRoute::random(9, 4);
+        let parent_b = SelectiveRoute::random(9, 4);
+        for _ in 0..50 {
+            let child = parent_a.crossover(&parent_b);
+            assert_valid(&child);
+            assert!(child
+                .indexes
+                .iter()
+                .all(|node| parent_a.indexes.contains(node) || parent_b.indexes.contains(node)));
+            assert_valid(&child.mutate(1.0));
+        }
+    }
+    #[test]
+    fn crossover_takes_nodes_from_both_parents() {
+        let parent_a = SelectiveRoute::new(7, 0, vec![1, 2, 3]);
+        let parent_b = SelectiveRoute::new(7, 0, vec![4, 2, 5, 6]);
+        let children = (0..100)
+            .map(|_| parent_a.crossover(&parent_b))
+            .collect::<Vec<SelectiveRoute>>();
+        // The node both parents visit is always kept.
+        assert!(children.iter().all(|child| child.indexes.contains(&2)));
+        // Nodes of either parent are dropped and kept.
+        for node in [1, 3, 4, 5, 6] {
+            assert!(children.iter().any(|child| child.indexes.contains(&node)));
+            assert!(children.iter().any(|child| !child.indexes.contains(&node)));
+        }
+        assert!(children.iter().any(|child| child.indexes.len() < 4));
+    }
+    #[test]
+    fn evolution_skips_unprofitable_nodes() {
+        // Two valuable nodes next to the depot and a cheap one far away.
+        let positions = [0.0f64, 1.0, 2.0, 10.0];
+        let distance_mat = DistanceMat::new(
+            positions
+                .iter()
+                .map(|from| positions.iter().map(|to| (from - to).abs()).collect())
+                .collect(),
+        );
+        let prize_cost = PrizeCost::new(distance_mat, vec![0.0, 10.0, 10.0, 1.0]);
+        let mut population = Individuals::from(
+            (0..10)
+                .map(|_| SelectiveRoute::random(4, 0))
+                .collect::<Vec<SelectiveRoute>>(),
+        );
+        for _ in 0..30 {
+            population = population
+                .evolve(0.5)
+                .get_fittest_population(10, &prize_cost);
+        }
+        let mut best = population.get_n_fittest(1, &prize_cost).remove(0);
+        assert_eq!(best.fitness(&prize_cost), 16.0);
+        best.indexes.sort_unstable();
+        assert_eq!(best.indexes, vec![1, 2]);
+    }
+}
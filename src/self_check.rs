@@ -0,0 +1,85 @@
+use core::fmt::Debug;
+
+/// Panic if `offspring` is not a permutation of `parent`, e.g. if an operator lost, duplicated or
+/// invented a node. The message names the operator and shows the parent and the offspring.
+///
+/// # Arguments
+///
+/// * `operator` - The name of the operator that created the offspring.
+/// * `parent` - The nodes of (one of) the parent(s) of the offspring.
+/// * `offspring` - The nodes of the offspring that should be checked.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::self_check::check_permutation;
+///
+/// check_permutation("reverse", &[0, 1, 2], &[2, 1, 0]);
+/// ```
+///
+/// ```should_panic
+/// use genetic_algorithm_tsp::self_check::check_permutation;
+///
+/// check_permutation("broken", &[0, 1, 2], &[0, 1, 1]);
+/// ```
+pub fn check_permutation<T: Ord + Clone + Debug>(operator: &str, parent: &[T], offspring: &[T]) {
+    let mut sorted_parent = parent.to_vec();
+    sorted_parent.sort_unstable();
+    let mut sorted_offspring = offspring.to_vec();
+    sorted_offspring.sort_unstable();
+    if sorted_parent != sorted_offspring {
+        panic!(
+            "Self-check failed: operator `{}` created {:?}, which is not a permutation of its parent {:?}.",
+            operator, offspring, parent
+        );
+    }
+}
+
+/// Panic if `fitness` is NaN. The message names the operator and shows the individual.
+///
+/// # Arguments
+///
+/// * `operator` - The name of the operator that computed the fitness.
+/// * `individual` - The individual whose fitness was computed.
+/// * `fitness` - The fitness that should be checked.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::self_check::check_fitness;
+///
+/// check_fitness("fitness", &[0, 1, 2], -6.0);
+/// ```
+///
+/// ```should_panic
+/// use genetic_algorithm_tsp::self_check::check_fitness;
+///
+/// check_fitness("fitness", &[0, 1, 2], f64::NAN);
+/// ```
+pub fn check_fitness<T: Debug + ?Sized>(operator: &str, individual: &T, fitness: f64) {
+    if fitness.is_nan() {
+        panic!(
+            "Self-check failed: operator `{}` computed a NaN fitness for {:?}.",
+            operator, individual
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    #[should_panic(expected = "operator `crossover` created [0, 3]")]
+    fn invented_node() {
+        check_permutation("crossover", &[0, 1], &[0, 3]);
+    }
+    #[test]
+    #[should_panic(expected = "is not a permutation")]
+    fn lost_node() {
+        check_permutation("mutate", &[0, 1, 2], &[0, 1]);
+    }
+    #[test]
+    fn infinite_fitness_is_allowed() {
+        check_fitness("fitness", &[0, 1], f64::NEG_INFINITY);
+    }
+}
@@ -0,0 +1,703 @@
+use crate::distance_mat::DistanceMat;
+use crate::evolution::{Evolution, EvolutionConfig};
+use crate::island::stream_seed;
+use crate::route::Route;
+use crate::routes::Routes;
+use crossbeam_utils::thread;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::cmp::max;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The file extension of a snapshot, e.g. a gzip-compressed, bincode-encoded `Routes`-object.
+const SNAPSHOT_EXTENSION: &str = "bin.gz";
+
+/// The errors that can occur while writing or reading snapshots.
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// The snapshot file or directory could not be accessed.
+    Io(io::Error),
+    /// The population could not be encoded or decoded.
+    Encoding(bincode::Error),
+    /// A replayed best-fitness trajectory differs from the recorded one. A missing value
+    /// means that one of the trajectories has no entry for this generation.
+    TrajectoryMismatch {
+        /// The first generation where the trajectories differ.
+        generation: usize,
+        /// The recorded best fitness of this generation.
+        recorded: Option<f64>,
+        /// The replayed best fitness of this generation.
+        replayed: Option<f64>,
+    },
+}
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SnapshotError::Io(error) => write!(formatter, "Snapshot IO failed: {}", error),
+            SnapshotError::Encoding(error) => {
+                write!(formatter, "Snapshot encoding failed: {}", error)
+            }
+            SnapshotError::TrajectoryMismatch {
+                generation,
+                recorded,
+                replayed,
+            } => write!(
+                formatter,
+                "Best fitness of generation {} was recorded as {:?} but replayed as {:?}",
+                generation, recorded, replayed
+            ),
+        }
+    }
+}
+impl Error for SnapshotError {}
+impl From<io::Error> for SnapshotError {
+    fn from(error: io::Error) -> Self {
+        SnapshotError::Io(error)
+    }
+}
+impl From<bincode::Error> for SnapshotError {
+    fn from(error: bincode::Error) -> Self {
+        SnapshotError::Encoding(error)
+    }
+}
+
+/// Get the path of the snapshot of a generation in a directory.
+///
+/// # Arguments
+///
+/// * `directory` - The directory the snapshots are stored in.
+/// * `generation` - The generation of the snapshot.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::snapshot::snapshot_path;
+/// use std::path::Path;
+///
+/// println!("{:?}", snapshot_path(Path::new("snapshots"), 3));
+/// ```
+pub fn snapshot_path(directory: &Path, generation: usize) -> PathBuf {
+    directory.join(format!(
+        "generation_{:08}.{}",
+        generation, SNAPSHOT_EXTENSION
+    ))
+}
+
+/// Write the population of a generation as compressed snapshot into a directory. The
+/// directory is created if it does not exist yet.
+///
+/// # Arguments
+///
+/// * `directory` - The directory the snapshot should be written to.
+/// * `generation` - The generation of the population.
+/// * `routes` - The population that should be stored.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::snapshot::write_snapshot;
+/// use genetic_algorithm_tsp::routes::Routes;
+///
+/// let directory = std::env::temp_dir().join("genetic_algorithm_tsp_doc_write_snapshot");
+/// let path = write_snapshot(&directory, 0, &Routes::random(3, 4)).unwrap();
+/// ```
+pub fn write_snapshot(
+    directory: &Path,
+    generation: usize,
+    routes: &Routes,
+) -> Result<PathBuf, SnapshotError> {
+    fs::create_dir_all(directory)?;
+    let path = snapshot_path(directory, generation);
+    let mut encoder = GzEncoder::new(fs::File::create(&path)?, Compression::default());
+    bincode::serialize_into(&mut encoder, routes)?;
+    encoder.finish()?;
+    Ok(path)
+}
+
+/// Read a single snapshot that was written by `write_snapshot`.
+///
+/// # Arguments
+///
+/// * `path` - The path of the snapshot file.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::snapshot::{read_snapshot, write_snapshot};
+/// use genetic_algorithm_tsp::routes::Routes;
+///
+/// let directory = std::env::temp_dir().join("genetic_algorithm_tsp_doc_read_snapshot");
+/// let path = write_snapshot(&directory, 0, &Routes::random(3, 4)).unwrap();
+/// println!("{}", read_snapshot(&path).unwrap());
+/// ```
+pub fn read_snapshot(path: &Path) -> Result<Routes, SnapshotError> {
+    Ok(bincode::deserialize_from(GzDecoder::new(fs::File::open(
+        path,
+    )?))?)
+}
+
+/// Read all snapshots from a directory, ordered by their generation. Files that are not
+/// snapshots are ignored.
+///
+/// # Arguments
+///
+/// * `directory` - The directory the snapshots were written to.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::snapshot::{read_snapshots, write_snapshot};
+/// use genetic_algorithm_tsp::routes::Routes;
+///
+/// let directory = std::env::temp_dir().join("genetic_algorithm_tsp_doc_read_snapshots");
+/// write_snapshot(&directory, 0, &Routes::random(3, 4)).unwrap();
+/// for (generation, routes) in read_snapshots(&directory).unwrap() {
+///     println!("{}: {}", generation, routes);
+/// }
+/// ```
+pub fn read_snapshots(directory: &Path) -> Result<Vec<(usize, Routes)>, SnapshotError> {
+    snapshot_generations(directory)?
+        .into_iter()
+        .map(|generation| {
+            read_snapshot(&snapshot_path(directory, generation)).map(|routes| (generation, routes))
+        })
+        .collect()
+}
+/// The generations of all snapshots in a directory in ascending order.
+///
+/// # Arguments
+///
+/// * `directory` - The directory the snapshots were written to.
+///
+fn snapshot_generations(directory: &Path) -> Result<Vec<usize>, SnapshotError> {
+    let mut generations = Vec::new();
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        let generation = path
+            .file_name()
+            .and_then(|file_name| file_name.to_str())
+            .and_then(|file_name| file_name.strip_prefix("generation_"))
+            .and_then(|file_name| file_name.strip_suffix(&format!(".{}", SNAPSHOT_EXTENSION)))
+            .and_then(|generation| generation.parse::<usize>().ok());
+        if let Some(generation) = generation {
+            generations.push(generation);
+        }
+    }
+    generations.sort_unstable();
+    Ok(generations)
+}
+
+/// Read the snapshot of the latest generation in a directory, e.g. to resume a run after a
+/// crash. A directory that doesn't exist or contains no snapshots gives `None`.
+///
+/// # Arguments
+///
+/// * `directory` - The directory the snapshots were written to.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::snapshot::{latest_snapshot, write_snapshot};
+/// use genetic_algorithm_tsp::routes::Routes;
+///
+/// let directory = std::env::temp_dir().join("genetic_algorithm_tsp_doc_latest_snapshot");
+/// write_snapshot(&directory, 0, &Routes::random(3, 4)).unwrap();
+/// write_snapshot(&directory, 5, &Routes::random(3, 4)).unwrap();
+/// assert_eq!(latest_snapshot(&directory).unwrap().unwrap().0, 5);
+/// ```
+pub fn latest_snapshot(directory: &Path) -> Result<Option<(usize, Routes)>, SnapshotError> {
+    if !directory.is_dir() {
+        return Ok(None);
+    }
+    match snapshot_generations(directory)?.pop() {
+        Some(generation) => Ok(Some((
+            generation,
+            read_snapshot(&snapshot_path(directory, generation))?,
+        ))),
+        None => Ok(None),
+    }
+}
+
+/// Get the directory the checkpoints of an island are stored in, see
+/// `evolve_islands_with_checkpoints`.
+///
+/// # Arguments
+///
+/// * `directory` - The directory of the whole run.
+/// * `island` - The number of the island.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::snapshot::island_directory;
+/// use std::path::Path;
+///
+/// println!("{:?}", island_directory(Path::new("checkpoints"), 2));
+/// ```
+pub fn island_directory(directory: &Path, island: usize) -> PathBuf {
+    directory.join(format!("island_{:03}", island))
+}
+
+/// Evolve a stepwise evolution generation by generation with its configuration until all of
+/// its generations are evolved, and write the population of every generation as snapshot into
+/// `directory`. The current population is written first, e.g. generation 0 is the initial
+/// population of a started evolution.
+///
+/// # Arguments
+///
+/// * `evolution` - A stepwise evolution, see `Evolution::start`.
+/// * `directory` - The directory the snapshots should be written to.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+/// use genetic_algorithm_tsp::evolution::{Evolution, EvolutionConfig};
+/// use genetic_algorithm_tsp::routes::Routes;
+/// use genetic_algorithm_tsp::snapshot::evolve_population_with_snapshots;
+///
+/// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+/// let config = EvolutionConfig::new().with_n_generations(10).with_size_generation(10);
+/// let mut evolution = Evolution::new(&distance_matrix, config).start(Routes::random(2, 3), 42);
+/// evolve_population_with_snapshots(
+///     &mut evolution,
+///     &std::env::temp_dir().join("genetic_algorithm_tsp_doc_evolve_snapshots"),
+/// ).unwrap();
+/// ```
+pub fn evolve_population_with_snapshots(
+    evolution: &mut Evolution<'_>,
+    directory: &Path,
+) -> Result<(), SnapshotError> {
+    write_snapshot(directory, evolution.generation(), evolution.population())?;
+    while !evolution.is_finished() {
+        evolution.evolve(1);
+        write_snapshot(directory, evolution.generation(), evolution.population())?;
+    }
+    Ok(())
+}
+
+/// Evolve one island per job of the configuration, e.g. copies of the initial population, in
+/// parallel with the configuration and combine the fittest routes of every island. Every
+/// island is a stepwise `Evolution` seeded with `stream_seed(master_seed, island)`, and writes
+/// a checkpoint into its own `island_directory` every `checkpoint_interval` generations and
+/// after its last generation. When the run is started again in the same directory, every
+/// island continues from its latest checkpoint and islands that completed are not evolved
+/// again, so a crash only loses the generations since the last checkpoints. An island is
+/// reseeded after every checkpoint from the master seed, so a resumed run gives the same
+/// routes as one that wasn't interrupted, only adaptive sizing and restarts start to track
+/// the progress anew.
+///
+/// # Arguments
+///
+/// * `initial_population` - Your initial population that should be evolved.
+/// * `distance_matrix` - The distance matrix on which the fitness will be computed on.
+/// * `config` - The parameters of the evolution of every island, `n_jobs` is the number of
+///   islands, at least one island is evolved.
+/// * `master_seed` - The seed all seeds of the islands are derived from.
+/// * `checkpoint_interval` - After how many generations an island writes a checkpoint.
+/// * `directory` - The directory the checkpoints should be written to.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+/// use genetic_algorithm_tsp::evolution::EvolutionConfig;
+/// use genetic_algorithm_tsp::routes::Routes;
+/// use genetic_algorithm_tsp::snapshot::evolve_islands_with_checkpoints;
+///
+/// let config = EvolutionConfig::new()
+///     .with_n_generations(10)
+///     .with_size_generation(10)
+///     .with_n_jobs(2);
+/// let evolved_population = evolve_islands_with_checkpoints(
+///     Routes::random(2, 3),
+///     &DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]),
+///     config,
+///     42,
+///     5,
+///     &std::env::temp_dir().join("genetic_algorithm_tsp_doc_evolve_islands"),
+/// ).unwrap();
+/// ```
+pub fn evolve_islands_with_checkpoints(
+    initial_population: Routes,
+    distance_matrix: &DistanceMat,
+    config: EvolutionConfig,
+    master_seed: u64,
+    checkpoint_interval: usize,
+    directory: &Path,
+) -> Result<Routes, SnapshotError> {
+    let checkpoint_interval = max(checkpoint_interval, 1);
+    thread::scope(|s| {
+        let islands = (0..max(config.n_jobs, 1))
+            .map(|island| {
+                let initial_population = &initial_population;
+                s.spawn(move |_| -> Result<Vec<Route>, SnapshotError> {
+                    let island_directory = island_directory(directory, island);
+                    let island_seed = stream_seed(master_seed, island as u64);
+                    let start = |population: Routes, generation: usize| {
+                        Evolution::new(distance_matrix, config)
+                            .start(population, stream_seed(island_seed, generation as u64))
+                            .at_generation(generation)
+                    };
+                    let mut evolution = match latest_snapshot(&island_directory)? {
+                        Some((generation, population)) => start(population, generation),
+                        None => {
+                            write_snapshot(&island_directory, 0, initial_population)?;
+                            start(initial_population.clone(), 0)
+                        }
+                    };
+                    while !evolution.is_finished() {
+                        evolution.evolve(1);
+                        let generation = evolution.generation();
+                        if generation % checkpoint_interval == 0 || evolution.is_finished() {
+                            write_snapshot(&island_directory, generation, evolution.population())?;
+                            evolution = start(evolution.population().clone(), generation);
+                        }
+                    }
+                    Ok(evolution
+                        .population()
+                        .get_n_fittest(config.size_generation, distance_matrix))
+                })
+            })
+            .collect::<Vec<_>>();
+        let mut routes = Vec::new();
+        for island in islands {
+            routes.extend(island.join().unwrap()?);
+        }
+        Ok(Routes::from(routes))
+    })
+    .unwrap()
+}
+
+/// Compute the best fitness of every generation that was recorded in `directory`. The
+/// snapshots have to cover all generations starting at 0, e.g. a missing snapshot is
+/// reported as `TrajectoryMismatch`.
+///
+/// # Arguments
+///
+/// * `directory` - The directory the snapshots were written to.
+/// * `distance_matrix` - The distance matrix on which the fitness will be computed on.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::snapshot::{best_fitness_trajectory, evolve_population_with_snapshots};
+/// use genetic_algorithm_tsp::routes::Routes;
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+/// use genetic_algorithm_tsp::evolution::{Evolution, EvolutionConfig};
+///
+/// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+/// let directory = std::env::temp_dir().join("genetic_algorithm_tsp_doc_best_fitness_trajectory");
+/// let config = EvolutionConfig::new().with_n_generations(5).with_size_generation(5);
+/// let mut evolution = Evolution::new(&distance_matrix, config).start(Routes::random(2, 3), 42);
+/// evolve_population_with_snapshots(&mut evolution, &directory).unwrap();
+/// println!("{:?}", best_fitness_trajectory(&directory, &distance_matrix).unwrap());
+/// ```
+pub fn best_fitness_trajectory(
+    directory: &Path,
+    distance_matrix: &DistanceMat,
+) -> Result<Vec<f64>, SnapshotError> {
+    read_snapshots(directory)?
+        .into_iter()
+        .enumerate()
+        .map(|(expected_generation, (generation, routes))| {
+            let best_fitness = routes
+                .get_n_fittest(1, distance_matrix)
+                .first()
+                .map(|route| route.fitness(distance_matrix));
+            match best_fitness {
+                Some(best_fitness) if generation == expected_generation => Ok(best_fitness),
+                _ => Err(SnapshotError::TrajectoryMismatch {
+                    generation: expected_generation,
+                    recorded: None,
+                    replayed: best_fitness,
+                }),
+            }
+        })
+        .collect()
+}
+
+/// Replay a recorded run: evolve the population of its first snapshot again with the same
+/// configuration and seed, and verify that every generation has the best fitness that was
+/// recorded in the snapshots, e.g. to guard against nondeterministic operators or fitness
+/// computations, or a changed distance matrix.
+///
+/// # Arguments
+///
+/// * `directory` - The directory the snapshots were written to, see
+///   `evolve_population_with_snapshots`.
+/// * `distance_matrix` - The distance matrix on which the fitness will be computed on.
+/// * `config` - The parameters the recorded run was evolved with.
+/// * `seed` - The seed the recorded run was started with, see `Evolution::start`.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::snapshot::{evolve_population_with_snapshots, replay};
+/// use genetic_algorithm_tsp::routes::Routes;
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+/// use genetic_algorithm_tsp::evolution::{Evolution, EvolutionConfig};
+///
+/// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+/// let directory = std::env::temp_dir().join("genetic_algorithm_tsp_doc_replay");
+/// let config = EvolutionConfig::new().with_n_generations(5).with_size_generation(5);
+/// let mut evolution = Evolution::new(&distance_matrix, config).start(Routes::random(2, 3), 42);
+/// evolve_population_with_snapshots(&mut evolution, &directory).unwrap();
+/// replay(&directory, &distance_matrix, config, 42).unwrap();
+/// ```
+pub fn replay(
+    directory: &Path,
+    distance_matrix: &DistanceMat,
+    config: EvolutionConfig,
+    seed: u64,
+) -> Result<(), SnapshotError> {
+    let recorded_trajectory = best_fitness_trajectory(directory, distance_matrix)?;
+    let initial_population = read_snapshot(&snapshot_path(directory, 0))?;
+    let best_fitness = |routes: &Routes| {
+        routes
+            .get_n_fittest(1, distance_matrix)
+            .first()
+            .map(|route| route.fitness(distance_matrix))
+    };
+    let mut evolution = Evolution::new(distance_matrix, config).start(initial_population, seed);
+    let mut replayed_trajectory = vec![best_fitness(evolution.population())];
+    while !evolution.is_finished() {
+        replayed_trajectory.push(best_fitness(evolution.evolve(1)));
+    }
+    for generation in 0..max(recorded_trajectory.len(), replayed_trajectory.len()) {
+        let recorded = recorded_trajectory.get(generation).copied();
+        let replayed = replayed_trajectory.get(generation).copied().flatten();
+        if recorded != replayed {
+            return Err(SnapshotError::TrajectoryMismatch {
+                generation,
+                recorded,
+                replayed,
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evolution::EvolutionConfig;
+    use crate::test_utils::test_dist_mat;
+
+    fn config(n_generations: usize, size_generation: usize) -> EvolutionConfig {
+        EvolutionConfig::new()
+            .with_n_generations(n_generations)
+            .with_size_generation(size_generation)
+    }
+    fn test_directory(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("genetic_algorithm_tsp_{}", name));
+        let _ = fs::remove_dir_all(&directory);
+        directory
+    }
+    #[test]
+    fn write_and_read_snapshot() {
+        let directory = test_directory("write_and_read_snapshot");
+        let routes = Routes::random(4, 5);
+        let path = write_snapshot(&directory, 7, &routes).unwrap();
+        assert_eq!(path, snapshot_path(&directory, 7));
+        assert_eq!(read_snapshot(&path).unwrap(), routes);
+    }
+    #[test]
+    fn read_missing_snapshot() {
+        let directory = test_directory("read_missing_snapshot");
+        assert!(matches!(
+            read_snapshot(&snapshot_path(&directory, 0)),
+            Err(SnapshotError::Io(_))
+        ));
+    }
+    #[test]
+    fn evolve_writes_all_generations() {
+        let directory = test_directory("evolve_writes_all_generations");
+        let distance_mat = test_dist_mat();
+        let mut evolution =
+            Evolution::new(&distance_mat, config(3, 4)).start(Routes::random(2, 3), 0);
+        evolve_population_with_snapshots(&mut evolution, &directory).unwrap();
+        fs::write(directory.join("notes.txt"), "not a snapshot").unwrap();
+        let snapshots = read_snapshots(&directory).unwrap();
+        assert_eq!(
+            snapshots
+                .iter()
+                .map(|(generation, _)| *generation)
+                .collect::<Vec<usize>>(),
+            vec![0, 1, 2, 3]
+        );
+        assert_eq!(&snapshots[3].1, evolution.population());
+    }
+    #[test]
+    fn latest_snapshot_of_missing_directory() {
+        let directory = test_directory("latest_snapshot_of_missing_directory");
+        assert!(latest_snapshot(&directory).unwrap().is_none());
+    }
+    #[test]
+    fn every_island_writes_final_checkpoint() {
+        let directory = test_directory("every_island_writes_final_checkpoint");
+        let final_population = evolve_islands_with_checkpoints(
+            Routes::random(2, 3),
+            &test_dist_mat(),
+            config(5, 4).with_n_jobs(3),
+            0,
+            2,
+            &directory,
+        )
+        .unwrap();
+        assert!(!final_population.is_empty());
+        for island in 0..3 {
+            assert_eq!(
+                snapshot_generations(&island_directory(&directory, island)).unwrap(),
+                vec![0, 2, 4, 5]
+            );
+        }
+    }
+    #[test]
+    fn resume_partially_completed_run() {
+        let directory = test_directory("resume_partially_completed_run");
+        let routes = Routes::from(vec![Route::new(vec![0, 1, 2])]);
+        // Island 0 completed, island 1 crashed after generation 2 and island 2 never started.
+        write_snapshot(&island_directory(&directory, 0), 4, &routes).unwrap();
+        write_snapshot(&island_directory(&directory, 1), 2, &routes).unwrap();
+        evolve_islands_with_checkpoints(
+            routes,
+            &test_dist_mat(),
+            config(4, 4).with_n_jobs(3),
+            0,
+            1,
+            &directory,
+        )
+        .unwrap();
+        assert_eq!(
+            snapshot_generations(&island_directory(&directory, 0)).unwrap(),
+            vec![4]
+        );
+        assert_eq!(
+            snapshot_generations(&island_directory(&directory, 1)).unwrap(),
+            vec![2, 3, 4]
+        );
+        assert_eq!(
+            snapshot_generations(&island_directory(&directory, 2)).unwrap(),
+            vec![0, 1, 2, 3, 4]
+        );
+    }
+    #[test]
+    fn resumed_islands_continue_like_the_uninterrupted_run() {
+        let distance_mat = DistanceMat::from_coordinates(&[
+            (0.0, 0.0),
+            (3.0, 1.0),
+            (1.0, 4.0),
+            (5.0, 2.0),
+            (2.0, 2.0),
+            (4.0, 5.0),
+            (0.0, 3.0),
+        ]);
+        let config = config(6, 5).with_n_jobs(2).with_mutation_rate(0.3);
+        let initial_population = Routes::random(5, 7);
+        let directory = test_directory("uninterrupted_islands");
+        let uninterrupted = evolve_islands_with_checkpoints(
+            initial_population.clone(),
+            &distance_mat,
+            config,
+            3,
+            2,
+            &directory,
+        )
+        .unwrap();
+        // The interrupted run crashed after the checkpoints of generation 2.
+        let interrupted_directory = test_directory("interrupted_islands");
+        for island in 0..2 {
+            for generation in [0, 2] {
+                let population = read_snapshot(&snapshot_path(
+                    &island_directory(&directory, island),
+                    generation,
+                ))
+                .unwrap();
+                write_snapshot(
+                    &island_directory(&interrupted_directory, island),
+                    generation,
+                    &population,
+                )
+                .unwrap();
+            }
+        }
+        let resumed = evolve_islands_with_checkpoints(
+            initial_population,
+            &distance_mat,
+            config,
+            3,
+            2,
+            &interrupted_directory,
+        )
+        .unwrap();
+        assert_eq!(resumed, uninterrupted);
+    }
+    #[test]
+    fn replay_reproduces_the_recorded_run() {
+        let directory = test_directory("replay_reproduces_the_recorded_run");
+        let distance_mat = DistanceMat::from_coordinates(&[
+            (0.0, 0.0),
+            (3.0, 1.0),
+            (1.0, 4.0),
+            (5.0, 2.0),
+            (2.0, 2.0),
+            (4.0, 5.0),
+            (0.0, 3.0),
+        ]);
+        let config = config(6, 5).with_mutation_rate(0.3);
+        let mut evolution = Evolution::new(&distance_mat, config).start(Routes::random(5, 7), 11);
+        evolve_population_with_snapshots(&mut evolution, &directory).unwrap();
+        assert!(replay(&directory, &distance_mat, config, 11).is_ok());
+        // A run of more generations than were recorded doesn't reproduce the recording.
+        assert!(matches!(
+            replay(&directory, &distance_mat, config.with_n_generations(7), 11),
+            Err(SnapshotError::TrajectoryMismatch {
+                generation: 7,
+                recorded: None,
+                replayed: Some(_),
+            })
+        ));
+    }
+    #[test]
+    fn replay_detects_changed_fitness() {
+        let directory = test_directory("replay_detects_changed_fitness");
+        let routes = Routes::from(vec![Route::new(vec![0, 1, 2])]);
+        write_snapshot(&directory, 0, &routes).unwrap();
+        write_snapshot(
+            &directory,
+            1,
+            &Routes::from(vec![Route::new(vec![0, 2, 1])]),
+        )
+        .unwrap();
+        // Both recorded routes have the same cost on the symmetric matrix. On the asymmetric
+        // one the recorded generation 1 is worse than generation 0, which a run that keeps
+        // its parents doesn't reproduce.
+        let other_distance_mat = DistanceMat::new(vec![
+            vec![0.0, 1.0, 2.0],
+            vec![5.0, 0.0, 3.0],
+            vec![2.0, 3.0, 0.0],
+        ]);
+        assert!(replay(&directory, &test_dist_mat(), config(1, 4), 0).is_ok());
+        assert!(matches!(
+            replay(&directory, &other_distance_mat, config(1, 4), 0),
+            Err(SnapshotError::TrajectoryMismatch { generation: 1, .. })
+        ));
+    }
+    #[test]
+    fn replay_detects_missing_generation() {
+        let directory = test_directory("replay_detects_missing_generation");
+        let routes = Routes::from(vec![Route::new(vec![0, 1, 2])]);
+        write_snapshot(&directory, 0, &routes).unwrap();
+        write_snapshot(&directory, 2, &routes).unwrap();
+        assert!(matches!(
+            replay(&directory, &test_dist_mat(), config(2, 4), 0),
+            Err(SnapshotError::TrajectoryMismatch { generation: 1, .. })
+        ));
+    }
+}
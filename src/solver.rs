@@ -0,0 +1,646 @@
+use crate::candidates::CandidateList;
+use crate::certificate::{improve_until_locally_optimal, LocalOptimality};
+use crate::distance_mat::DistanceMat;
+use crate::evaluation::OperatorConfig;
+use crate::evolution::StopReason;
+use crate::exact::held_karp;
+use crate::objective::Objective;
+use crate::route::Route;
+use crate::routes::{evolve_population, evolve_population_counted, GenerationOptions, Routes};
+use core::fmt::Write;
+
+/// Solve a TSP with the genetic algorithm and return the best route that was found. Without a
+/// configuration, small instances take a fast path: instances of up to 14 nodes are solved
+/// exactly with the Held-Karp dynamic program, and instances of up to 49 nodes with a short
+/// memetic evolution of nearest-neighbor tours improved with 2-opt. Larger instances use the
+/// default for their size, see `OperatorConfig::for_instance_size`.
+///
+/// # Arguments
+///
+/// * `distance_mat` - The instance that should be solved.
+/// * `config` - The settings of the genetic algorithm, `None` for the defaults.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+/// use genetic_algorithm_tsp::solver::solve_tsp;
+///
+/// let distance_matrix = DistanceMat::new(vec![
+///     vec![0.0, 1.0, 9.0, 1.0],
+///     vec![1.0, 0.0, 1.0, 9.0],
+///     vec![9.0, 1.0, 0.0, 1.0],
+///     vec![1.0, 9.0, 1.0, 0.0],
+/// ]);
+/// let best_route = solve_tsp(&distance_matrix, None);
+/// assert_eq!(distance_matrix.get_cost(&best_route.indexes), 4.0);
+/// ```
+pub fn solve_tsp(distance_mat: &DistanceMat, config: Option<&OperatorConfig>) -> Route {
+    solve_tsp_detailed(distance_mat, config).route
+}
+/// Solve a TSP like `solve_tsp`, but return the best route together with how it was found, e.g.
+/// to print a `SolverResult::report` of the run.
+///
+/// # Arguments
+///
+/// * `distance_mat` - The instance that should be solved.
+/// * `config` - The settings of the genetic algorithm, `None` for the defaults.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+/// use genetic_algorithm_tsp::evolution::StopReason;
+/// use genetic_algorithm_tsp::solver::solve_tsp_detailed;
+///
+/// let distance_matrix = DistanceMat::new(vec![
+///     vec![0.0, 1.0, 9.0, 1.0],
+///     vec![1.0, 0.0, 1.0, 9.0],
+///     vec![9.0, 1.0, 0.0, 1.0],
+///     vec![1.0, 9.0, 1.0, 0.0],
+/// ]);
+/// let result = solve_tsp_detailed(&distance_matrix, None);
+/// assert_eq!(result.cost, 4.0);
+/// assert_eq!(result.stop_reason, StopReason::Optimal);
+/// ```
+pub fn solve_tsp_detailed(
+    distance_mat: &DistanceMat,
+    config: Option<&OperatorConfig>,
+) -> SolverResult {
+    let n_nodes = distance_mat.n_units();
+    match config {
+        Some(config) => solve_with_config(distance_mat, config),
+        None if (3..=MAX_EXACT_NODES).contains(&n_nodes) => match held_karp(distance_mat) {
+            Some(route) => SolverResult::new(
+                route,
+                distance_mat,
+                &OperatorConfig::new("exact", 0, 0, 0),
+                0,
+                0,
+                StopReason::Optimal,
+            ),
+            None => solve_seeded(distance_mat),
+        },
+        None if (3..=MAX_SEEDED_NODES).contains(&n_nodes) => solve_seeded(distance_mat),
+        None => solve_with_config(distance_mat, &OperatorConfig::for_instance_size(n_nodes)),
+    }
+}
+/// Solve a TSP like `solve_tsp`, then improve the best route with 2-opt and Or-opt until it is
+/// a local optimum for both and return it with its `LocalOptimality`-certificate.
+///
+/// # Arguments
+///
+/// * `distance_mat` - The instance that should be solved.
+/// * `config` - The settings of the genetic algorithm, `None` for the defaults.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+/// use genetic_algorithm_tsp::solver::solve_tsp_certified;
+///
+/// let distance_matrix = DistanceMat::new(vec![
+///     vec![0.0, 1.0, 9.0, 1.0],
+///     vec![1.0, 0.0, 1.0, 9.0],
+///     vec![9.0, 1.0, 0.0, 1.0],
+///     vec![1.0, 9.0, 1.0, 0.0],
+/// ]);
+/// let (best_route, certificate) = solve_tsp_certified(&distance_matrix, None);
+/// assert!(certificate.is_certified());
+/// println!("{}: {}", best_route, certificate);
+/// ```
+pub fn solve_tsp_certified(
+    distance_mat: &DistanceMat,
+    config: Option<&OperatorConfig>,
+) -> (Route, LocalOptimality) {
+    improve_until_locally_optimal(&solve_tsp(distance_mat, config), distance_mat)
+}
+/// Solve a TSP with the genetic algorithm and a configuration.
+///
+/// # Arguments
+///
+/// * `distance_mat` - The instance that should be solved.
+/// * `config` - The settings of the genetic algorithm.
+///
+fn solve_with_config(distance_mat: &DistanceMat, config: &OperatorConfig) -> SolverResult {
+    solve_from_population(
+        distance_mat,
+        config,
+        Routes::random(config.size_generation, distance_mat.n_units()),
+    )
+}
+/// Solve a TSP with the genetic algorithm, a configuration and the population it starts from.
+///
+/// # Arguments
+///
+/// * `distance_mat` - The instance that should be solved.
+/// * `config` - The settings of the genetic algorithm.
+/// * `initial_population` - The routes the evolution starts from.
+///
+fn solve_from_population(
+    distance_mat: &DistanceMat,
+    config: &OperatorConfig,
+    initial_population: Routes,
+) -> SolverResult {
+    let n_nodes = distance_mat.n_units();
+    // All orders of less than three nodes describe the same round-trip.
+    if n_nodes < 3 {
+        return SolverResult::new(
+            Route::new((0..n_nodes).collect()),
+            distance_mat,
+            config,
+            0,
+            0,
+            // Every order of the nodes is the same round-trip.
+            StopReason::Optimal,
+        );
+    }
+    let n_initial = initial_population.len();
+    let (population, n_offspring) = evolve_population_counted(
+        initial_population,
+        config.n_generations,
+        config.size_generation,
+        distance_mat,
+        config.n_jobs,
+        GenerationOptions::default(),
+    );
+    SolverResult::new(
+        population.get_n_fittest(1, distance_mat).remove(0),
+        distance_mat,
+        config,
+        config.n_generations,
+        n_initial + n_offspring,
+        StopReason::GenerationsExhausted,
+    )
+}
+
+/// How many nearest neighbors of every node `PreparedSolver::prepare` looks the nearest-neighbor
+/// tours of the initial population up in.
+const PREPARED_N_CANDIDATES: usize = 10;
+
+/// The most nodes of an instance that `solve_tsp` solves exactly without a configuration.
+const MAX_EXACT_NODES: usize = 14;
+/// The most nodes of an instance that `solve_tsp` solves with `solve_seeded` without a
+/// configuration.
+const MAX_SEEDED_NODES: usize = 49;
+
+/// Solve a small instance with a short memetic evolution: the initial population are
+/// nearest-neighbor tours from different start nodes improved with 2-opt, topped up with random
+/// routes, and the fittest route of every generation is improved with 2-opt as well.
+///
+/// # Arguments
+///
+/// * `distance_mat` - The instance that should be solved.
+///
+fn solve_seeded(distance_mat: &DistanceMat) -> SolverResult {
+    let config = OperatorConfig::new("seeded", 100, 20, 0);
+    let initial_population = Routes::from(
+        Routes::nearest_neighbor(config.size_generation, distance_mat)
+            .iter()
+            .map(|route| route.two_opt(distance_mat))
+            .collect::<Vec<Route>>(),
+    )
+    .top_up(config.size_generation);
+    let n_initial = initial_population.len();
+    let (population, n_offspring) = evolve_population_counted(
+        initial_population,
+        config.n_generations,
+        config.size_generation,
+        distance_mat,
+        config.n_jobs,
+        GenerationOptions {
+            n_refined: 1,
+            ..GenerationOptions::default()
+        },
+    );
+    SolverResult::new(
+        population.get_n_fittest(1, distance_mat).remove(0),
+        distance_mat,
+        &config,
+        config.n_generations,
+        n_initial + n_offspring,
+        StopReason::GenerationsExhausted,
+    )
+}
+
+/// The best route of a run of the solver together with how it was found, as returned by
+/// `solve_tsp_detailed`. `report` turns it into a summary for humans.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolverResult {
+    /// The best route that was found.
+    pub route: Route,
+    /// The cost of the best route.
+    pub cost: f64,
+    /// The number of nodes of the instance.
+    pub n_nodes: usize,
+    /// The settings of the genetic algorithm.
+    pub config: OperatorConfig,
+    /// How many generations were evolved, 0 if the instance was too small to need any.
+    pub n_generations: usize,
+    /// How many routes were evaluated: the initial population and the offspring of every
+    /// generation.
+    pub n_evaluations: usize,
+    /// A lower bound of the cost of every round-trip of the instance, no route can be cheaper.
+    /// This is `DistanceMat::lower_bound`, 0 for the objectives it doesn't cover.
+    pub lower_bound: f64,
+    /// The legs of the best route as `(from, to, distance)`, from the longest to the shortest.
+    /// Under `Objective::PathLength` the leg back to the start is not part of the route.
+    pub legs: Vec<(usize, usize, f64)>,
+    /// Why the run ended.
+    pub stop_reason: StopReason,
+}
+impl SolverResult {
+    /// Collect the result of a run.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The best route that was found.
+    /// * `distance_mat` - The instance that was solved.
+    /// * `config` - The settings of the genetic algorithm.
+    /// * `n_generations` - How many generations were evolved.
+    /// * `n_evaluations` - How many routes were evaluated.
+    /// * `stop_reason` - Why the run ended.
+    ///
+    fn new(
+        route: Route,
+        distance_mat: &DistanceMat,
+        config: &OperatorConfig,
+        n_generations: usize,
+        n_evaluations: usize,
+        stop_reason: StopReason,
+    ) -> Self {
+        let n_nodes = route.indexes.len();
+        // An open path doesn't return to its start.
+        let n_legs = if distance_mat.objective() == Objective::PathLength {
+            n_nodes.saturating_sub(1)
+        } else {
+            n_nodes
+        };
+        let mut legs = (0..n_legs)
+            .map(|position| {
+                let (from, to) = (
+                    route.indexes[position],
+                    route.indexes[(position + 1) % n_nodes],
+                );
+                (from, to, distance_mat.distance(from, to))
+            })
+            .collect::<Vec<(usize, usize, f64)>>();
+        // A single node has no legs.
+        if n_nodes < 2 {
+            legs.clear();
+        }
+        legs.sort_by(|leg_a, leg_b| leg_b.2.total_cmp(&leg_a.2));
+        SolverResult {
+            // An empty route has no cost.
+            cost: if n_nodes == 0 {
+                0.0
+            } else {
+                distance_mat.get_cost(&route.indexes)
+            },
+            n_nodes: distance_mat.n_units(),
+            config: config.clone(),
+            n_generations,
+            n_evaluations,
+            lower_bound: distance_mat.lower_bound(),
+            legs,
+            stop_reason,
+            route,
+        }
+    }
+    /// The relative gap between the cost of the best route and the lower bound, e.g. 0.05 if
+    /// the route is at most 5% more expensive than the optimum. `None` if the bound is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::solver::solve_tsp_detailed;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 9.0, 1.0],
+    ///     vec![1.0, 0.0, 1.0, 9.0],
+    ///     vec![9.0, 1.0, 0.0, 1.0],
+    ///     vec![1.0, 9.0, 1.0, 0.0],
+    /// ]);
+    /// assert_eq!(solve_tsp_detailed(&distance_matrix, None).gap(), Some(0.0));
+    /// ```
+    pub fn gap(&self) -> Option<f64> {
+        if self.lower_bound > 0.0 {
+            Some((self.cost - self.lower_bound) / self.lower_bound)
+        } else {
+            None
+        }
+    }
+    /// A summary of the run for humans, e.g. to attach to a ticket or a planning document: the
+    /// size of the instance, the configuration, how much work was done, the best cost and its
+    /// gap to the lower bound, the longest legs of the best route and why the run ended.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_legs` - How many of the longest legs are listed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::solver::solve_tsp_detailed;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let report = solve_tsp_detailed(&distance_matrix, None).report(3);
+    /// assert!(report.contains("best cost: 6.00"));
+    /// println!("{}", report);
+    /// ```
+    pub fn report(&self, n_legs: usize) -> String {
+        let mut report = String::new();
+        // Writing to a `String` can't fail.
+        let _ = writeln!(report, "instance: {} nodes", self.n_nodes);
+        let _ = writeln!(
+            report,
+            "config: {} ({} generations of {} routes, {} threads)",
+            self.config.name,
+            self.config.n_generations,
+            self.config.size_generation,
+            self.config.n_jobs
+        );
+        let _ = writeln!(report, "generations run: {}", self.n_generations);
+        let _ = writeln!(report, "evaluations: {}", self.n_evaluations);
+        let _ = writeln!(report, "best cost: {:.2}", self.cost);
+        let _ = match self.gap() {
+            Some(gap) => writeln!(
+                report,
+                "lower bound: {:.2} (gap {:.1}%)",
+                self.lower_bound,
+                gap * 100.0
+            ),
+            None => writeln!(report, "lower bound: {:.2}", self.lower_bound),
+        };
+        let _ = writeln!(report, "longest legs:");
+        for (from, to, distance) in self.legs.iter().take(n_legs) {
+            let _ = writeln!(report, "  {} -> {}: {:.2}", from, to, distance);
+        }
+        let _ = writeln!(report, "stop reason: {}", self.stop_reason);
+        report
+    }
+}
+
+/// A solver for one instance that did all of its one-time work up front, so that every call to
+/// `solve` has a predictable latency, e.g. in a service that answers requests within a deadline.
+/// `prepare` chooses the configuration, computes the candidate list of the instance, builds the
+/// initial population from it and evolves a copy of it for a single warm-up generation, which
+/// fills the properties of the distance matrix that are cached on first use and warms the code
+/// and the buffers of the allocator. Every `solve` then only evolves a copy of the prepared
+/// population.
+#[derive(Debug, Clone)]
+pub struct PreparedSolver<'a> {
+    /// The instance that is solved.
+    distance_mat: &'a DistanceMat,
+    /// The settings of the genetic algorithm.
+    config: OperatorConfig,
+    /// The nearest neighbors of every node.
+    candidates: CandidateList,
+    /// The population every `solve` starts from.
+    initial_population: Routes,
+}
+impl<'a> PreparedSolver<'a> {
+    /// Prepare solving an instance. Without a configuration, the default for the size of the
+    /// instance is used, see `OperatorConfig::for_instance_size`. The initial population are
+    /// nearest-neighbor tours from different start nodes, topped up with random routes to the
+    /// size of a generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The instance that should be solved.
+    /// * `config` - The settings of the genetic algorithm, `None` for the defaults.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::solver::PreparedSolver;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let solver = PreparedSolver::prepare(&distance_matrix, None);
+    /// assert_eq!(solver.config().size_generation, 20);
+    /// ```
+    pub fn prepare(distance_mat: &'a DistanceMat, config: Option<&OperatorConfig>) -> Self {
+        let n_nodes = distance_mat.n_units();
+        let config = match config {
+            Some(config) => config.clone(),
+            None => OperatorConfig::for_instance_size(n_nodes),
+        };
+        let candidates = CandidateList::new(distance_mat, PREPARED_N_CANDIDATES);
+        let mut rng = rand::thread_rng();
+        let initial_population = if n_nodes >= 3 {
+            Routes::nearest_neighbor_with_candidates(
+                config.size_generation,
+                distance_mat,
+                &candidates,
+                &mut rng,
+            )
+            .top_up_with_rng(config.size_generation, &mut rng)
+        } else {
+            Routes::from(Vec::new())
+        };
+        if n_nodes >= 3 {
+            evolve_population(
+                initial_population.clone(),
+                1,
+                config.size_generation,
+                distance_mat,
+                config.n_jobs,
+            );
+        }
+        PreparedSolver {
+            distance_mat,
+            config,
+            candidates,
+            initial_population,
+        }
+    }
+    /// Get the settings of the genetic algorithm.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::evaluation::OperatorConfig;
+    /// use genetic_algorithm_tsp::solver::PreparedSolver;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let config = OperatorConfig::new("tiny", 5, 2, 0);
+    /// assert_eq!(PreparedSolver::prepare(&distance_matrix, Some(&config)).config(), &config);
+    /// ```
+    pub fn config(&self) -> &OperatorConfig {
+        &self.config
+    }
+    /// Get the nearest neighbors of every node that the initial population was built with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::solver::PreparedSolver;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let solver = PreparedSolver::prepare(&distance_matrix, None);
+    /// assert_eq!(solver.candidates().neighbors(2), &[0, 1]);
+    /// ```
+    pub fn candidates(&self) -> &CandidateList {
+        &self.candidates
+    }
+    /// Get the population every `solve` starts from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::evaluation::OperatorConfig;
+    /// use genetic_algorithm_tsp::solver::PreparedSolver;
+    ///
+    /// let distance_matrix = DistanceMat::from_coordinates(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]);
+    /// let config = OperatorConfig::new("tiny", 5, 6, 0);
+    /// let solver = PreparedSolver::prepare(&distance_matrix, Some(&config));
+    /// assert_eq!(solver.initial_population().len(), 6);
+    /// ```
+    pub fn initial_population(&self) -> &Routes {
+        &self.initial_population
+    }
+    /// Solve the instance like `solve_tsp`, but evolve a copy of the prepared population, and
+    /// return the best route that was found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::solver::PreparedSolver;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![
+    ///     vec![0.0, 1.0, 9.0, 1.0],
+    ///     vec![1.0, 0.0, 1.0, 9.0],
+    ///     vec![9.0, 1.0, 0.0, 1.0],
+    ///     vec![1.0, 9.0, 1.0, 0.0],
+    /// ]);
+    /// let solver = PreparedSolver::prepare(&distance_matrix, None);
+    /// for _ in 0..3 {
+    ///     assert_eq!(distance_matrix.get_cost(&solver.solve().indexes), 4.0);
+    /// }
+    /// ```
+    pub fn solve(&self) -> Route {
+        solve_from_population(
+            self.distance_mat,
+            &self.config,
+            self.initial_population.clone(),
+        )
+        .route
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routes::nearest_neighbor_tour;
+    use crate::test_utils::{random_asymmetric_dist_mat, test_dist_mat, valid_permutation};
+    #[test]
+    fn tiny_instances() {
+        assert_eq!(
+            solve_tsp(&DistanceMat::new(Vec::<Vec<f64>>::new()), None),
+            Route::new(vec![])
+        );
+        assert_eq!(
+            solve_tsp(
+                &DistanceMat::new(vec![vec![0.0, 1.0], vec![1.0, 0.0]]),
+                None
+            ),
+            Route::new(vec![0, 1])
+        );
+    }
+    #[test]
+    fn with_config() {
+        let route = solve_tsp(
+            &test_dist_mat(),
+            Some(&OperatorConfig::new("tiny", 5, 2, 0)),
+        );
+        valid_permutation(&[0, 1, 2], &route.indexes);
+    }
+    #[test]
+    fn detailed_result() {
+        let distance_mat = test_dist_mat();
+        let result = solve_tsp_detailed(&distance_mat, Some(&OperatorConfig::new("tiny", 5, 2, 0)));
+        valid_permutation(&[0, 1, 2], &result.route.indexes);
+        assert_eq!(result.n_generations, 5);
+        assert!(result.n_evaluations >= 2);
+        assert!(result.lower_bound <= result.cost);
+        assert_eq!(result.legs.len(), 3);
+        assert!(result.legs[0].2 >= result.legs[2].2);
+        let report = result.report(2);
+        assert!(report.contains("instance: 3 nodes"));
+        assert!(report.contains("config: tiny (5 generations of 2 routes, 0 threads)"));
+        assert!(report.contains("stop reason: generations exhausted"));
+        assert_eq!(report.matches(" -> ").count(), 2);
+    }
+    #[test]
+    fn detailed_result_of_tiny_instance() {
+        let result = solve_tsp_detailed(&DistanceMat::new(vec![vec![0.0]]), None);
+        assert_eq!(result.n_evaluations, 0);
+        assert_eq!(result.stop_reason, StopReason::Optimal);
+        assert!(result.legs.is_empty());
+        assert_eq!(result.gap(), None);
+    }
+    #[test]
+    fn detailed_result_of_open_path() {
+        let distance_mat = test_dist_mat().with_objective(Objective::PathLength);
+        let result =
+            solve_tsp_detailed(&distance_mat, Some(&OperatorConfig::new("tiny", 50, 6, 0)));
+        assert_eq!(result.legs.len(), 2);
+        // The cheapest spanning tree is the optimal path.
+        assert_eq!(result.lower_bound, 3.0);
+        assert_eq!(result.cost, 3.0);
+    }
+    #[test]
+    fn fast_paths_without_config() {
+        let result = solve_tsp_detailed(&random_asymmetric_dist_mat(MAX_EXACT_NODES, 0), None);
+        assert_eq!(result.stop_reason, StopReason::Optimal);
+        assert_eq!(result.config.name, "exact");
+        let distance_mat = random_asymmetric_dist_mat(30, 0);
+        let result = solve_tsp_detailed(&distance_mat, None);
+        assert_eq!(result.config.name, "seeded");
+        valid_permutation(&(0..30).collect::<Vec<usize>>(), &result.route.indexes);
+        // Latency can't be solved exactly, so small instances fall back to the evolution.
+        let result = solve_tsp_detailed(&test_dist_mat().with_objective(Objective::Latency), None);
+        assert_eq!(result.config.name, "seeded");
+    }
+    #[test]
+    fn prepared_solver() {
+        let distance_mat = test_dist_mat();
+        let solver =
+            PreparedSolver::prepare(&distance_mat, Some(&OperatorConfig::new("tiny", 5, 2, 0)));
+        valid_permutation(&[0, 1, 2], &solver.solve().indexes);
+        assert_eq!(
+            PreparedSolver::prepare(&DistanceMat::new(Vec::<Vec<f64>>::new()), None).solve(),
+            Route::new(vec![])
+        );
+    }
+    #[test]
+    fn prepared_solver_starts_from_nearest_neighbor_tours() {
+        let distance_mat = DistanceMat::from_coordinates(
+            &(0..20)
+                .map(|node| (((node * 7) % 20) as f64, ((node * 3) % 11) as f64))
+                .collect::<Vec<(f64, f64)>>(),
+        );
+        let solver =
+            PreparedSolver::prepare(&distance_mat, Some(&OperatorConfig::new("tiny", 5, 6, 0)));
+        let initial_population = solver.initial_population().clone();
+        assert_eq!(initial_population.len(), 6);
+        for route in initial_population.iter() {
+            assert_eq!(
+                route.indexes,
+                nearest_neighbor_tour(route.indexes[0], &distance_mat)
+            );
+        }
+        for _ in 0..2 {
+            valid_permutation(&(0..20).collect::<Vec<usize>>(), &solver.solve().indexes);
+        }
+        // Solving evolves copies, so every request starts from the same population.
+        assert_eq!(solver.initial_population(), &initial_population);
+    }
+}
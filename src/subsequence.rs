@@ -1,4 +1,5 @@
-use crate::utils::get_random_elem_from_range;
+use crate::utils::get_random_elem_from_range_with_rng;
+use rand::Rng;
 
 /// The `Subsequence`-object only stores the indexes of a potential subsequences. Then based on a sequence, operations
 /// on that subsequence can be applied.
@@ -23,17 +24,18 @@ impl Subsequence {
             length,
         }
     }
-    /// Create a new, random subsequence.
+    /// Create a new, random subsequence drawn from `rng`.
     ///
     /// # Arguments
     ///
     /// * `len_sequence` - What is the len of the actual sequence that should be subsequenced?
-    pub fn random_subsequence(len_sequence: usize) -> Self {
-        let start_index = get_random_elem_from_range(0..(len_sequence - 2));
-        Subsequence {
+    /// * `rng` - The random number generator the subsequence is drawn from.
+    pub fn random_subsequence_with_rng<R: Rng + ?Sized>(len_sequence: usize, rng: &mut R) -> Self {
+        let start_index = get_random_elem_from_range_with_rng(0..(len_sequence - 2), rng);
+        Subsequence::new(
             start_index,
-            length: get_random_elem_from_range(1..(len_sequence - start_index - 1)),
-        }
+            get_random_elem_from_range_with_rng(1..(len_sequence - start_index - 1), rng),
+        )
     }
     /// Based on an actual sequence, get all elements that are in the subsequence
     ///
@@ -77,8 +79,10 @@ impl Subsequence {
 }
 
 #[cfg(test)]
+#[allow(duplicate_macro_attributes)]
 mod tests {
     use super::*;
+    #[cfg(feature = "std")]
     mod test_random_subsequence {
         use super::*;
         #[test]
@@ -87,7 +91,8 @@ mod tests {
         #[test]
         fn test_max_10() {
             let max_value = 10;
-            let random_subsequence = Subsequence::random_subsequence(max_value);
+            let random_subsequence =
+                Subsequence::random_subsequence_with_rng(max_value, &mut rand::thread_rng());
             assert!(random_subsequence.start_index < max_value);
             assert!(random_subsequence.length < max_value - random_subsequence.start_index);
             assert!(random_subsequence.start_index + random_subsequence.length < max_value);
@@ -98,7 +103,8 @@ mod tests {
         #[test]
         fn test_max_2() {
             let max_value = 2;
-            let random_subsequence = Subsequence::random_subsequence(max_value);
+            let random_subsequence =
+                Subsequence::random_subsequence_with_rng(max_value, &mut rand::thread_rng());
             assert!(random_subsequence.start_index < max_value);
             assert!(random_subsequence.length < max_value - random_subsequence.start_index);
             assert!(random_subsequence.start_index + random_subsequence.length < max_value);
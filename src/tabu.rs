@@ -0,0 +1,380 @@
+use crate::convergence::tour_edges;
+use crate::evolution::Evolution;
+use crate::route::{MutationOperator, Route};
+use rand::Rng;
+use std::collections::{HashMap, HashSet};
+
+/// A run-level memory of the edges that were recently removed from the best route, adapted
+/// from tabu search to the genetic algorithm: a mutation that adds such an edge back is
+/// penalized, which pushes the population away from the routes it just left. An edge is tabu
+/// for `tenure` generations after it was removed (recency), and every removal of an edge adds
+/// to its penalty for the rest of the run (frequency). The memory is kept across generations
+/// and can be shared by several runs on the same instance.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TabuMemory {
+    /// For how many generations a removed edge is tabu.
+    tenure: usize,
+    /// How much every past removal of an edge adds to the penalty of adding it back.
+    frequency_weight: f64,
+    /// How many mutations are sampled to find one that adds no penalized edge.
+    n_candidates: usize,
+    /// The current generation.
+    generation: usize,
+    /// The generation in which every edge was last removed.
+    last_removed: HashMap<(usize, usize), usize>,
+    /// How often every edge was removed.
+    n_removals: HashMap<(usize, usize), usize>,
+}
+impl TabuMemory {
+    /// Create an empty memory that only penalizes edges removed in the last `tenure`
+    /// generations and samples up to ten mutations per offspring.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenure` - For how many generations a removed edge is tabu.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::tabu::TabuMemory;
+    ///
+    /// let memory = TabuMemory::new(5);
+    /// assert_eq!(memory.generation(), 0);
+    /// ```
+    pub fn new(tenure: usize) -> Self {
+        TabuMemory {
+            tenure,
+            frequency_weight: 0.0,
+            n_candidates: 10,
+            ..TabuMemory::default()
+        }
+    }
+    /// Also penalize edges that were removed often, no matter how long ago.
+    ///
+    /// # Arguments
+    ///
+    /// * `frequency_weight` - How much every past removal of an edge adds to its penalty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::tabu::TabuMemory;
+    ///
+    /// let memory = TabuMemory::new(5).with_frequency_weight(0.1);
+    /// ```
+    pub fn with_frequency_weight(mut self, frequency_weight: f64) -> Self {
+        self.frequency_weight = frequency_weight;
+        self
+    }
+    /// Set how many mutations are sampled to find one that adds no penalized edge.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_candidates` - How many mutations are sampled per offspring, at least one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::tabu::TabuMemory;
+    ///
+    /// let memory = TabuMemory::new(5).with_n_candidates(20);
+    /// ```
+    pub fn with_n_candidates(mut self, n_candidates: usize) -> Self {
+        self.n_candidates = n_candidates.max(1);
+        self
+    }
+    /// The number of generations the memory has seen.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::tabu::TabuMemory;
+    ///
+    /// let mut memory = TabuMemory::new(5);
+    /// memory.advance();
+    /// assert_eq!(memory.generation(), 1);
+    /// ```
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+    /// Move on to the next generation, edges removed `tenure` generations ago stop being tabu.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::tabu::TabuMemory;
+    ///
+    /// let mut memory = TabuMemory::new(1);
+    /// memory.record(&[0, 1, 2, 3], &[0, 2, 1, 3]);
+    /// memory.advance();
+    /// assert!(memory.is_tabu((0, 1)));
+    /// memory.advance();
+    /// assert!(!memory.is_tabu((0, 1)));
+    /// ```
+    pub fn advance(&mut self) {
+        self.generation += 1;
+    }
+    /// Remember the edges that were removed when the route `before` was replaced by `after`,
+    /// e.g. the best routes of two consecutive generations.
+    ///
+    /// # Arguments
+    ///
+    /// * `before` - The nodes of the route that was replaced.
+    /// * `after` - The nodes of the route that replaced it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::tabu::TabuMemory;
+    ///
+    /// let mut memory = TabuMemory::new(5);
+    /// // (0, 1) and (2, 3) are replaced by (0, 2) and (1, 3).
+    /// memory.record(&[0, 1, 2, 3], &[0, 2, 1, 3]);
+    /// assert!(memory.is_tabu((0, 1)));
+    /// assert!(!memory.is_tabu((0, 2)));
+    /// ```
+    pub fn record(&mut self, before: &[usize], after: &[usize]) {
+        let kept = tour_edges(after).into_iter().collect::<HashSet<_>>();
+        for edge in tour_edges(before) {
+            if !kept.contains(&edge) {
+                self.last_removed.insert(edge, self.generation);
+                *self.n_removals.entry(edge).or_insert(0) += 1;
+            }
+        }
+    }
+    /// Is the undirected edge tabu, i.e. was it removed in the last `tenure` generations?
+    ///
+    /// # Arguments
+    ///
+    /// * `edge` - The edge, in any direction.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::tabu::TabuMemory;
+    ///
+    /// let mut memory = TabuMemory::new(5);
+    /// memory.record(&[0, 1, 2, 3], &[0, 2, 1, 3]);
+    /// assert!(memory.is_tabu((1, 0)));
+    /// ```
+    pub fn is_tabu(&self, edge: (usize, usize)) -> bool {
+        let edge = (edge.0.min(edge.1), edge.0.max(edge.1));
+        self.last_removed
+            .get(&edge)
+            .is_some_and(|removed| self.generation - removed <= self.tenure)
+    }
+    /// The penalty of replacing the route `before` by `after`: every added edge that is tabu
+    /// costs 1.0, and every past removal of an added edge costs the frequency weight.
+    ///
+    /// # Arguments
+    ///
+    /// * `before` - The nodes of the route that is replaced.
+    /// * `after` - The nodes of the route that replaces it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::tabu::TabuMemory;
+    ///
+    /// let mut memory = TabuMemory::new(5);
+    /// memory.record(&[0, 1, 2, 3], &[0, 2, 1, 3]);
+    /// // Going back adds (0, 1) and (2, 3) again.
+    /// assert_eq!(memory.penalty(&[0, 2, 1, 3], &[0, 1, 2, 3]), 2.0);
+    /// ```
+    pub fn penalty(&self, before: &[usize], after: &[usize]) -> f64 {
+        let existing = tour_edges(before).into_iter().collect::<HashSet<_>>();
+        tour_edges(after)
+            .into_iter()
+            .filter(|edge| !existing.contains(edge))
+            .map(|edge| {
+                let tabu = if self.is_tabu(edge) { 1.0 } else { 0.0 };
+                let n_removals = self.n_removals.get(&edge).copied().unwrap_or(0);
+                tabu + self.frequency_weight * n_removals as f64
+            })
+            .sum()
+    }
+    /// Mutate a route like `Route::mutate_with_operator`, but sample up to `n_candidates`
+    /// mutations and keep the first one without penalty, or the one with the lowest penalty.
+    /// `MutationOperator::Candidate` needs a candidate list and panics, the evolutions that
+    /// are given a memory apply it with the candidate list of their distance matrix.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The route that is mutated.
+    /// * `prob` - The probability with which the route will be changed.
+    /// * `operator` - The mutation operator.
+    /// * `rng` - The random number generator the mutations are drawn from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::route::{MutationOperator, Route};
+    /// use genetic_algorithm_tsp::tabu::TabuMemory;
+    ///
+    /// let memory = TabuMemory::new(5);
+    /// let mutated = memory.mutate(
+    ///     &Route::new(vec![0, 1, 2, 3, 4]),
+    ///     1.0,
+    ///     MutationOperator::Swap,
+    ///     &mut rand::thread_rng(),
+    /// );
+    /// ```
+    pub fn mutate<R: Rng + ?Sized>(
+        &self,
+        route: &Route,
+        prob: f32,
+        operator: MutationOperator,
+        rng: &mut R,
+    ) -> Route {
+        self.mutate_with(route, |route| {
+            route.mutate_with_operator(prob, operator, rng)
+        })
+    }
+    /// Sample up to `n_candidates` mutations of a route with `mutate` and keep the first one
+    /// without penalty, or the one with the lowest penalty, see `mutate`.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The route that is mutated.
+    /// * `mutate` - Mutates a copy of the route.
+    ///
+    pub(crate) fn mutate_with(
+        &self,
+        route: &Route,
+        mut mutate: impl FnMut(Route) -> Route,
+    ) -> Route {
+        let mut best: Option<(f64, Route)> = None;
+        for _ in 0..self.n_candidates.max(1) {
+            let candidate = mutate(route.clone());
+            let penalty = self.penalty(&route.indexes, &candidate.indexes);
+            if penalty <= 0.0 {
+                return candidate;
+            }
+            if best
+                .as_ref()
+                .is_none_or(|(best_penalty, _)| penalty < *best_penalty)
+            {
+                best = Some((penalty, candidate));
+            }
+        }
+        best.map_or_else(|| route.clone(), |(_, candidate)| candidate)
+    }
+}
+
+/// Evolve a started stepwise `Evolution` until it is finished, but mutate the offspring with
+/// `TabuMemory::mutate` and record the edges removed from the best route of every generation
+/// in `memory`, so that the population keeps moving instead of returning to the routes it just
+/// left. All other parameters and the seed are the ones of the evolution. The memory is updated
+/// in place and can be passed to further runs, it is not stored in checkpoints.
+///
+/// # Arguments
+///
+/// * `evolution` - The started evolution, see `Evolution::start`.
+/// * `memory` - The memory of removed edges, which is kept across generations.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+/// use genetic_algorithm_tsp::evolution::{Evolution, EvolutionConfig};
+/// use genetic_algorithm_tsp::routes::Routes;
+/// use genetic_algorithm_tsp::tabu::{evolve_population_with_tabu, TabuMemory};
+///
+/// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+/// let config = EvolutionConfig::new().with_n_generations(10).with_size_generation(4);
+/// let mut evolution = Evolution::new(&distance_matrix, config).start(Routes::random(4, 3), 42);
+/// let mut memory = TabuMemory::new(3);
+/// evolve_population_with_tabu(&mut evolution, &mut memory);
+/// assert_eq!(memory.generation(), 10);
+/// ```
+pub fn evolve_population_with_tabu(evolution: &mut Evolution<'_>, memory: &mut TabuMemory) {
+    evolution.replace_tabu(Some(core::mem::take(memory)));
+    evolution.evolve(usize::MAX);
+    *memory = evolution.replace_tabu(None).unwrap_or_default();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance_mat::DistanceMat;
+    use crate::evolution::EvolutionConfig;
+    use crate::route::CrossoverOperator;
+    use crate::routes::Routes;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    #[test]
+    fn frequency_adds_to_penalty() {
+        let mut memory = TabuMemory::new(0).with_frequency_weight(0.5);
+        memory.record(&[0, 1, 2, 3], &[0, 2, 1, 3]);
+        memory.advance();
+        memory.record(&[0, 1, 2, 3], &[0, 2, 1, 3]);
+        memory.advance();
+        assert!(!memory.is_tabu((0, 1)));
+        // (0, 1) and (2, 3) were removed twice.
+        assert_eq!(memory.penalty(&[0, 2, 1, 3], &[0, 1, 2, 3]), 2.0);
+    }
+    #[test]
+    fn mutation_avoids_tabu_edges() {
+        let route = Route::new((0..10).collect());
+        let mut memory = TabuMemory::new(5).with_n_candidates(100);
+        // Replacing this route by `route` removes (0, 5), (1, 5), (4, 9), (6, 9) and (0, 8),
+        // the edges of `route` itself stay allowed.
+        memory.record(&[0, 5, 1, 2, 3, 4, 9, 6, 7, 8], &route.indexes);
+        for edge in [(0, 5), (1, 5), (4, 9), (6, 9), (0, 8)] {
+            assert!(memory.is_tabu(edge));
+        }
+        assert!(!memory.is_tabu((0, 1)));
+        let mut rng = StdRng::seed_from_u64(4);
+        for _ in 0..20 {
+            let mutated = memory.mutate(&route, 1.0, MutationOperator::Inversion, &mut rng);
+            assert_eq!(memory.penalty(&route.indexes, &mutated.indexes), 0.0);
+        }
+    }
+    #[test]
+    fn tabu_evolution_keeps_best() {
+        let distance_mat = DistanceMat::from_coordinates(
+            &(0..8)
+                .map(|node| ((node * 3 % 8) as f64, (node * 5 % 8) as f64))
+                .collect::<Vec<(f64, f64)>>(),
+        );
+        let config = EvolutionConfig::new()
+            .with_n_generations(2)
+            .with_size_generation(6);
+        let mut memory = TabuMemory::new(3).with_frequency_weight(0.1);
+        let mut population = Routes::random(6, 8);
+        for seed in 0..5 {
+            let best = population.get_n_fittest(1, &distance_mat)[0].fitness(&distance_mat);
+            let mut evolution = Evolution::new(&distance_mat, config).start(population, seed);
+            evolve_population_with_tabu(&mut evolution, &mut memory);
+            population = evolution.population().clone();
+            assert!(population.len() <= 6);
+            assert!(population.get_n_fittest(1, &distance_mat)[0].fitness(&distance_mat) >= best);
+        }
+        assert_eq!(memory.generation(), 10);
+    }
+    #[test]
+    fn memory_without_tabu_edges_follows_the_configured_evolution() {
+        let distance_mat = DistanceMat::from_coordinates(
+            &(0..8)
+                .map(|node| ((node * 3 % 8) as f64, (node * 5 % 8) as f64))
+                .collect::<Vec<(f64, f64)>>(),
+        );
+        let config = EvolutionConfig::new()
+            .with_n_generations(6)
+            .with_size_generation(5)
+            .with_mutation_rate(0.3)
+            .with_mutation_operator(MutationOperator::Swap)
+            .with_crossover_operator(CrossoverOperator::PartiallyMapped)
+            .with_elitism(1);
+        let initial_population = Routes::random(5, 8);
+        let mut plain = Evolution::new(&distance_mat, config).start(initial_population.clone(), 3);
+        plain.evolve(usize::MAX);
+        // Removed edges are only tabu in the generation that removed them, so the first sampled
+        // mutation is always kept, as in the evolution without the memory.
+        let mut memory = TabuMemory::new(0);
+        let mut tabu = Evolution::new(&distance_mat, config).start(initial_population, 3);
+        evolve_population_with_tabu(&mut tabu, &mut memory);
+        assert_eq!(tabu.population(), plain.population());
+        assert_eq!(memory.generation(), 6);
+    }
+}
@@ -1,5 +1,7 @@
 use crate::distance_mat::DistanceMat;
 use core::hash;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use std::collections::HashSet;
 
 /// Get the test matrix that should be use tests.
@@ -13,6 +15,58 @@ pub fn test_dist_mat() -> DistanceMat {
         vec![2.0, 3.0, 0.0],
     ])
 }
+/// Get a random asymmetric instance, whose distances between different nodes are drawn from
+/// 1 to 10 with a seeded rng, so that a failing test can be reproduced.
+///
+/// # Arguments
+///
+/// * `n_nodes` - The number of nodes.
+/// * `seed` - The seed the distances are drawn with.
+///
+pub fn random_asymmetric_dist_mat(n_nodes: usize, seed: u64) -> DistanceMat {
+    let mut rng = StdRng::seed_from_u64(seed);
+    DistanceMat::new(
+        (0..n_nodes)
+            .map(|from| {
+                (0..n_nodes)
+                    .map(|to| {
+                        if from == to {
+                            0.0
+                        } else {
+                            rng.gen_range(1.0..10.0)
+                        }
+                    })
+                    .collect()
+            })
+            .collect(),
+    )
+}
+/// Get a clustered instance: `n_clusters` clusters far apart on a circle, each with
+/// `n_per_cluster` nodes close together on a small circle. Good routes visit every cluster at
+/// once, and a converged population easily gets stuck with a bad order inside the clusters.
+///
+/// # Arguments
+///
+/// * `n_clusters` - The number of clusters.
+/// * `n_per_cluster` - The number of nodes in every cluster.
+///
+pub fn clustered_dist_mat(n_clusters: usize, n_per_cluster: usize) -> DistanceMat {
+    let angle = |idx: usize, n: usize| 2.0 * core::f64::consts::PI * idx as f64 / n as f64;
+    DistanceMat::from_coordinates(
+        &(0..n_clusters)
+            .flat_map(|cluster| {
+                let center = angle(cluster, n_clusters);
+                (0..n_per_cluster).map(move |node| {
+                    let offset = angle(node, n_per_cluster);
+                    (
+                        100.0 * center.cos() + offset.cos(),
+                        100.0 * center.sin() + offset.sin(),
+                    )
+                })
+            })
+            .collect::<Vec<(f64, f64)>>(),
+    )
+}
 /// Test if a sequence is a valid permutation for another sequence.
 ///
 /// Permuation means that it consists exactly all elements from the
@@ -47,6 +101,7 @@ where
 }
 
 #[cfg(test)]
+#[allow(clippy::useless_vec)]
 mod tests {
     use super::*;
     mod test_valid_permutation {
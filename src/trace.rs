@@ -0,0 +1,244 @@
+use crate::distance_mat::DistanceMat;
+use crate::generation::GenerationStats;
+use crate::routes::{OperatorCounts, Routes};
+use core::hash::Hasher;
+use fasthash_fork::{xx, FastHasher};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Write};
+use std::time::Duration;
+
+/// The errors that can occur while writing a trace.
+#[derive(Debug)]
+pub enum TraceError {
+    /// The trace could not be written.
+    Io(io::Error),
+    /// A record could not be serialized.
+    Format(serde_json::Error),
+}
+impl fmt::Display for TraceError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TraceError::Io(error) => write!(formatter, "Writing the trace failed: {}", error),
+            TraceError::Format(error) => {
+                write!(formatter, "Serializing a trace record failed: {}", error)
+            }
+        }
+    }
+}
+impl Error for TraceError {}
+impl From<io::Error> for TraceError {
+    fn from(error: io::Error) -> Self {
+        TraceError::Io(error)
+    }
+}
+impl From<serde_json::Error> for TraceError {
+    fn from(error: serde_json::Error) -> Self {
+        TraceError::Format(error)
+    }
+}
+
+/// One generation of a trace, written as a single flat JSON line, so that every field can be
+/// logged as a metric of the generation.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TraceRecord {
+    /// The number of the generation, starting at 1.
+    pub generation: usize,
+    /// The statistics of the fitnesses of the population.
+    #[serde(flatten)]
+    pub stats: GenerationStats,
+    /// The hash of the best route, see `route_hash`. It only changes when a better route is
+    /// found or the best route is lost.
+    pub best_route_hash: u64,
+    /// How often the operators were applied while the generation was bred.
+    #[serde(flatten)]
+    pub operators: OperatorCounts,
+}
+impl TraceRecord {
+    /// Create the record of a generation, `None` if its population is empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `generation` - The number of the generation.
+    /// * `population` - The population after the generation.
+    /// * `distance_mat` - The distance matrix the fitness is computed on.
+    /// * `operators` - How often the operators were applied in the generation.
+    /// * `elapsed` - How long it took to evolve the generation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_tsp::routes::{OperatorCounts, Routes};
+    /// use genetic_algorithm_tsp::trace::TraceRecord;
+    /// use std::time::Duration;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let population = Routes::from(vec![Route::new(vec![0, 1, 2])]);
+    /// let record = TraceRecord::new(1, &population, &distance_matrix, OperatorCounts::default(), Duration::ZERO)
+    ///     .unwrap();
+    /// assert_eq!(record.stats.best_fitness, -6.0);
+    /// ```
+    pub fn new(
+        generation: usize,
+        population: &Routes,
+        distance_mat: &DistanceMat,
+        operators: OperatorCounts,
+        elapsed: Duration,
+    ) -> Option<Self> {
+        let mut stats = GenerationStats::new(population, distance_mat)?;
+        stats.elapsed_ms = elapsed.as_secs_f64() * 1000.0;
+        let best_route = population.get_n_fittest(1, distance_mat).remove(0);
+        Some(TraceRecord {
+            generation,
+            stats,
+            best_route_hash: route_hash(&best_route.indexes, distance_mat),
+            operators,
+        })
+    }
+    /// Write the record as a single JSON line.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - Where the record is written to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::route::Route;
+    /// use genetic_algorithm_tsp::routes::{OperatorCounts, Routes};
+    /// use genetic_algorithm_tsp::trace::TraceRecord;
+    /// use std::time::Duration;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let population = Routes::from(vec![Route::new(vec![0, 1, 2])]);
+    /// let record = TraceRecord::new(1, &population, &distance_matrix, OperatorCounts::default(), Duration::ZERO)
+    ///     .unwrap();
+    /// let mut trace = Vec::new();
+    /// record.write(&mut trace).unwrap();
+    /// assert!(String::from_utf8(trace).unwrap().starts_with("{\"generation\":1,"));
+    /// ```
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<(), TraceError> {
+        serde_json::to_writer(&mut *writer, self)?;
+        writer.write_all(b"\n")?;
+        Ok(())
+    }
+}
+
+/// Hash a route so that equivalent tours, e.g. rotations of a round-trip, have the same hash
+/// and different tours almost surely differ. The nodes are hashed with the xx hash, like the
+/// fingerprint of `DistanceMat::metadata`, so the hash is the same across runs, builds and
+/// platforms, e.g. to track routes across experiments.
+///
+/// # Arguments
+///
+/// * `route` - The nodes of the route in the order they are visited.
+/// * `distance_mat` - The distance matrix that defines which tours are equivalent.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+/// use genetic_algorithm_tsp::trace::route_hash;
+///
+/// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+/// assert_eq!(route_hash(&[1, 2, 0], &distance_matrix), route_hash(&[0, 1, 2], &distance_matrix));
+/// ```
+pub fn route_hash(route: &[usize], distance_mat: &DistanceMat) -> u64 {
+    let bytes = distance_mat
+        .tour_key(route)
+        .into_iter()
+        .flat_map(|node| (node as u64).to_le_bytes())
+        .collect::<Vec<u8>>();
+    let mut hasher = xx::Hasher64::new();
+    hasher.write(&bytes);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evolution::{Evolution, EvolutionConfig};
+    use crate::route::{MutationOperator, Route};
+
+    fn hexagon() -> (DistanceMat, Routes) {
+        let distance_matrix = DistanceMat::from_coordinates(&[
+            (0.0, 0.0),
+            (2.0, 0.0),
+            (3.0, 1.0),
+            (2.0, 2.0),
+            (0.0, 2.0),
+            (-1.0, 1.0),
+        ]);
+        let population = Routes::from(vec![
+            Route::new(vec![0, 2, 4, 1, 3, 5]),
+            Route::new(vec![0, 3, 1, 4, 2, 5]),
+            Route::new(vec![5, 1, 0, 3, 4, 2]),
+            Route::new(vec![1, 5, 2, 0, 4, 3]),
+        ]);
+        (distance_matrix, population)
+    }
+
+    #[test]
+    fn trace_has_a_line_per_generation() {
+        let (distance_matrix, initial_population) = hexagon();
+        let config = EvolutionConfig::new()
+            .with_n_generations(5)
+            .with_size_generation(4)
+            .with_mutation_operator(MutationOperator::Swap);
+        let mut evolution = Evolution::new(&distance_matrix, config).start(initial_population, 7);
+        let mut trace = Vec::new();
+        let population = evolution.evolve_with_trace(10, &mut trace).unwrap().clone();
+        let records = String::from_utf8(trace)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<TraceRecord>(line).unwrap())
+            .collect::<Vec<TraceRecord>>();
+        assert_eq!(
+            records
+                .iter()
+                .map(|record| record.generation)
+                .collect::<Vec<usize>>(),
+            vec![1, 2, 3, 4, 5]
+        );
+        // Every ordered pair of the first four routes is crossed.
+        assert_eq!(records[0].operators.crossovers, 12);
+        assert!(records
+            .iter()
+            .all(|record| record.operators.mutations <= record.operators.crossovers));
+        let best_route = population.get_n_fittest(1, &distance_matrix).remove(0);
+        assert_eq!(
+            records[4].best_route_hash,
+            route_hash(&best_route.indexes, &distance_matrix)
+        );
+    }
+    #[test]
+    fn route_hash_is_the_xx_hash_of_the_tour() {
+        let distance_matrix = DistanceMat::new(vec![
+            vec![0.0, 1.0, 2.0],
+            vec![1.0, 0.0, 3.0],
+            vec![2.0, 3.0, 0.0],
+        ]);
+        let bytes = [0u64, 1, 2]
+            .iter()
+            .flat_map(|node| node.to_le_bytes())
+            .collect::<Vec<u8>>();
+        assert_eq!(route_hash(&[2, 0, 1], &distance_matrix), xx::hash64(bytes));
+    }
+    #[test]
+    fn trace_does_not_change_the_run() {
+        let (distance_matrix, initial_population) = hexagon();
+        let config = EvolutionConfig::new().with_n_generations(5);
+        let mut traced =
+            Evolution::new(&distance_matrix, config).start(initial_population.clone(), 3);
+        let mut untraced = Evolution::new(&distance_matrix, config).start(initial_population, 3);
+        let mut trace = Vec::new();
+        assert_eq!(
+            traced.evolve_with_trace(5, &mut trace).unwrap(),
+            untraced.evolve(5)
+        );
+    }
+}
@@ -0,0 +1,424 @@
+use crate::distance_mat::{CostUnit, DistanceMat};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The errors that can occur while reading a TSPLIB file.
+#[derive(Debug)]
+pub enum TsplibError {
+    /// The file could not be read.
+    Io(io::Error),
+    /// A line of the file could not be parsed.
+    Parse {
+        /// The number of the line, starting at 1.
+        line: usize,
+        /// What is wrong with the line.
+        message: String,
+    },
+    /// The `DIMENSION` of the instance is missing.
+    MissingDimension,
+    /// The `EDGE_WEIGHT_TYPE` or `EDGE_WEIGHT_FORMAT` is not supported.
+    Unsupported(String),
+    /// The file contains fewer or more coordinates or edge weights than its `DIMENSION` requires.
+    WrongNumberOfEntries {
+        /// The number of entries that were expected.
+        expected: usize,
+        /// The number of entries in the file.
+        found: usize,
+    },
+}
+impl fmt::Display for TsplibError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TsplibError::Io(error) => {
+                write!(formatter, "Reading the TSPLIB file failed: {}", error)
+            }
+            TsplibError::Parse { line, message } => {
+                write!(formatter, "Line {} of the TSPLIB file: {}", line, message)
+            }
+            TsplibError::MissingDimension => {
+                write!(formatter, "The TSPLIB file doesn't specify a DIMENSION")
+            }
+            TsplibError::Unsupported(specification) => {
+                write!(formatter, "{} is not supported", specification)
+            }
+            TsplibError::WrongNumberOfEntries { expected, found } => write!(
+                formatter,
+                "Expected {} entries in the TSPLIB file, but found {}",
+                expected, found
+            ),
+        }
+    }
+}
+impl Error for TsplibError {}
+impl From<io::Error> for TsplibError {
+    fn from(error: io::Error) -> Self {
+        TsplibError::Io(error)
+    }
+}
+
+/// Compute the distances between points in the plane as defined for `EUC_2D` in TSPLIB, e.g.
+/// rounded to the nearest integer.
+///
+/// # Arguments
+///
+/// * `coordinates` - The `(x, y)`-coordinates of the nodes.
+///
+pub(crate) fn euclidean_distances(coordinates: &[(f64, f64)]) -> Vec<Vec<f64>> {
+    coordinates
+        .iter()
+        .map(|(x_from, y_from)| {
+            coordinates
+                .iter()
+                .map(|(x_to, y_to)| {
+                    ((x_from - x_to).powi(2) + (y_from - y_to).powi(2))
+                        .sqrt()
+                        .round()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Compute the pseudo-euclidean distances as defined for `ATT` in TSPLIB.
+///
+/// # Arguments
+///
+/// * `coordinates` - The `(x, y)`-coordinates of the nodes.
+///
+pub(crate) fn att_distances(coordinates: &[(f64, f64)]) -> Vec<Vec<f64>> {
+    coordinates
+        .iter()
+        .map(|(x_from, y_from)| {
+            coordinates
+                .iter()
+                .map(|(x_to, y_to)| {
+                    let distance =
+                        (((x_from - x_to).powi(2) + (y_from - y_to).powi(2)) / 10.0).sqrt();
+                    let rounded = distance.round();
+                    if rounded < distance {
+                        rounded + 1.0
+                    } else {
+                        rounded
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Convert a `DDD.MM`-coordinate into radians as defined for `GEO` in TSPLIB.
+///
+/// # Arguments
+///
+/// * `coordinate` - The coordinate in degrees and minutes.
+///
+fn geo_radians(coordinate: f64) -> f64 {
+    // TSPLIB uses this approximation of pi, it has to be used to reproduce the optima.
+    #[allow(clippy::approx_constant)]
+    let pi = 3.141592;
+    let degrees = coordinate.trunc();
+    let minutes = coordinate - degrees;
+    pi * (degrees + 5.0 * minutes / 3.0) / 180.0
+}
+
+/// Compute the distances in kilometers between points on earth as defined for `GEO` in TSPLIB.
+///
+/// # Arguments
+///
+/// * `coordinates` - The `(latitude, longitude)`-coordinates of the nodes in `DDD.MM`-format.
+///
+pub(crate) fn geo_distances(coordinates: &[(f64, f64)]) -> Vec<Vec<f64>> {
+    let earth_radius = 6378.388;
+    coordinates
+        .iter()
+        .enumerate()
+        .map(|(from_idx, (latitude_from, longitude_from))| {
+            coordinates
+                .iter()
+                .enumerate()
+                .map(|(to_idx, (latitude_to, longitude_to))| {
+                    if from_idx == to_idx {
+                        return 0.0;
+                    }
+                    let (latitude_from, longitude_from) =
+                        (geo_radians(*latitude_from), geo_radians(*longitude_from));
+                    let (latitude_to, longitude_to) =
+                        (geo_radians(*latitude_to), geo_radians(*longitude_to));
+                    let q1 = (longitude_from - longitude_to).cos();
+                    let q2 = (latitude_from - latitude_to).cos();
+                    let q3 = (latitude_from + latitude_to).cos();
+                    (earth_radius * (0.5 * ((1.0 + q1) * q2 - (1.0 - q1) * q3)).acos() + 1.0)
+                        .trunc()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Fill a full distance matrix from the edge weights of an `EXPLICIT` instance.
+///
+/// # Arguments
+///
+/// * `weights` - The edge weights in the order of the file.
+/// * `dimension` - The number of nodes.
+/// * `format` - The `EDGE_WEIGHT_FORMAT` of the file.
+///
+fn explicit_distances(
+    weights: &[f64],
+    dimension: usize,
+    format: &str,
+) -> Result<Vec<Vec<f64>>, TsplibError> {
+    // Reading a column-wise triangle is the same as reading the opposite triangle row-wise,
+    // so every format is reduced to the cells of a row-wise traversal.
+    let cells: Vec<(usize, usize)> = match format {
+        "FULL_MATRIX" => (0..dimension)
+            .flat_map(|row| (0..dimension).map(move |column| (row, column)))
+            .collect(),
+        "UPPER_ROW" | "LOWER_COL" => (0..dimension)
+            .flat_map(|row| ((row + 1)..dimension).map(move |column| (row, column)))
+            .collect(),
+        "LOWER_ROW" | "UPPER_COL" => (0..dimension)
+            .flat_map(|row| (0..row).map(move |column| (row, column)))
+            .collect(),
+        "UPPER_DIAG_ROW" | "LOWER_DIAG_COL" => (0..dimension)
+            .flat_map(|row| (row..dimension).map(move |column| (row, column)))
+            .collect(),
+        "LOWER_DIAG_ROW" | "UPPER_DIAG_COL" => (0..dimension)
+            .flat_map(|row| (0..=row).map(move |column| (row, column)))
+            .collect(),
+        other => {
+            return Err(TsplibError::Unsupported(format!(
+                "EDGE_WEIGHT_FORMAT {}",
+                other
+            )))
+        }
+    };
+    if cells.len() != weights.len() {
+        return Err(TsplibError::WrongNumberOfEntries {
+            expected: cells.len(),
+            found: weights.len(),
+        });
+    }
+    let mut distances = vec![vec![0.0; dimension]; dimension];
+    for ((row, column), weight) in cells.into_iter().zip(weights) {
+        distances[row][column] = *weight;
+        if format != "FULL_MATRIX" {
+            distances[column][row] = *weight;
+        }
+    }
+    Ok(distances)
+}
+
+/// Parse the content of a TSPLIB `.tsp` file into a `DistanceMat`. The edge weight types
+/// `EUC_2D`, `GEO`, `ATT` and `EXPLICIT` with all matrix formats are supported. Distances of
+/// `GEO`-instances are given in kilometers, which is recorded as the units of the matrix.
+///
+/// # Arguments
+///
+/// * `content` - The content of the `.tsp` file.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::tsplib::parse_tsplib;
+///
+/// let distance_matrix = parse_tsplib(
+///     "NAME : square\nTYPE : TSP\nDIMENSION : 4\nEDGE_WEIGHT_TYPE : EUC_2D\n\
+///     NODE_COORD_SECTION\n1 0 0\n2 0 3\n3 4 3\n4 4 0\nEOF\n",
+/// ).unwrap();
+/// assert_eq!(distance_matrix.get_distance(&[0, 1, 2, 3]), Ok(14.0));
+/// ```
+pub fn parse_tsplib(content: &str) -> Result<DistanceMat, TsplibError> {
+    let mut dimension = None;
+    let mut edge_weight_type = None;
+    let mut edge_weight_format = String::from("FULL_MATRIX");
+    let mut coordinates = Vec::new();
+    let mut weights = Vec::new();
+    let mut section = "";
+    for (line_idx, line) in content.lines().enumerate() {
+        let line = line.trim();
+        let parse_error = |message: String| TsplibError::Parse {
+            line: line_idx + 1,
+            message,
+        };
+        if line.is_empty() {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            let value = value.trim();
+            match key.trim() {
+                "DIMENSION" => {
+                    dimension = Some(value.parse::<usize>().map_err(|_| {
+                        parse_error(format!("`{}` is not a valid dimension", value))
+                    })?)
+                }
+                "EDGE_WEIGHT_TYPE" => edge_weight_type = Some(value.to_string()),
+                "EDGE_WEIGHT_FORMAT" => edge_weight_format = value.to_string(),
+                "TYPE" if value != "TSP" && value != "ATSP" => {
+                    return Err(TsplibError::Unsupported(format!("TYPE {}", value)))
+                }
+                _ => (),
+            }
+            section = "";
+            continue;
+        }
+        match line {
+            "NODE_COORD_SECTION" | "EDGE_WEIGHT_SECTION" | "DISPLAY_DATA_SECTION" => {
+                section = line;
+                continue;
+            }
+            "EOF" => break,
+            _ => (),
+        }
+        let numbers = line
+            .split_whitespace()
+            .map(|token| {
+                token
+                    .parse::<f64>()
+                    .map_err(|_| parse_error(format!("`{}` is not a number", token)))
+            })
+            .collect::<Result<Vec<f64>, TsplibError>>()?;
+        match section {
+            "NODE_COORD_SECTION" => match numbers[..] {
+                [_, x, y] => coordinates.push((x, y)),
+                _ => {
+                    return Err(parse_error(String::from(
+                        "a node needs an index and two coordinates",
+                    )))
+                }
+            },
+            "EDGE_WEIGHT_SECTION" => weights.extend(numbers),
+            "DISPLAY_DATA_SECTION" => (),
+            _ => return Err(parse_error(format!("unexpected line `{}`", line))),
+        }
+    }
+    let dimension = dimension.ok_or(TsplibError::MissingDimension)?;
+    let edge_weight_type = edge_weight_type.unwrap_or_default();
+    if edge_weight_type == "EXPLICIT" {
+        return Ok(DistanceMat::new(explicit_distances(
+            &weights,
+            dimension,
+            &edge_weight_format,
+        )?));
+    }
+    if coordinates.len() != dimension {
+        return Err(TsplibError::WrongNumberOfEntries {
+            expected: dimension,
+            found: coordinates.len(),
+        });
+    }
+    match edge_weight_type.as_str() {
+        "EUC_2D" => Ok(DistanceMat::new(euclidean_distances(&coordinates))),
+        "ATT" => Ok(DistanceMat::new(att_distances(&coordinates))),
+        "GEO" => Ok(DistanceMat::new(geo_distances(&coordinates)).with_units(CostUnit::Kilometers)),
+        other => Err(TsplibError::Unsupported(format!(
+            "EDGE_WEIGHT_TYPE {}",
+            other
+        ))),
+    }
+}
+
+/// Read a TSPLIB `.tsp` file like `parse_tsplib`. The path is recorded as the source of the
+/// matrix.
+///
+/// # Arguments
+///
+/// * `path` - The path of the `.tsp` file.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_tsp::tsplib::read_tsplib;
+/// use std::fs;
+///
+/// let path = std::env::temp_dir().join("genetic_algorithm_tsp_doc_read_tsplib.tsp");
+/// fs::write(&path, "DIMENSION: 3\nEDGE_WEIGHT_TYPE: EXPLICIT\nEDGE_WEIGHT_FORMAT: UPPER_ROW\nEDGE_WEIGHT_SECTION\n1 2\n3\nEOF\n").unwrap();
+/// let distance_matrix = read_tsplib(&path).unwrap();
+/// assert_eq!(distance_matrix.get_distance(&[0, 1, 2]), Ok(6.0));
+/// ```
+pub fn read_tsplib(path: &Path) -> Result<DistanceMat, TsplibError> {
+    Ok(parse_tsplib(&fs::read_to_string(path)?)?.with_source(&path.to_string_lossy()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn explicit_formats_agree() {
+        let full = "0 1 2\n1 0 3\n2 3 0";
+        let formats = [
+            ("FULL_MATRIX", full),
+            ("UPPER_ROW", "1 2 3"),
+            ("LOWER_ROW", "1 2 3"),
+            ("UPPER_DIAG_ROW", "0 1 2 0 3 0"),
+            ("LOWER_DIAG_ROW", "0 1 0 2 3 0"),
+            ("LOWER_COL", "1 2 3"),
+        ];
+        for (format, weights) in formats {
+            let distance_mat = parse_tsplib(&format!(
+                "DIMENSION : 3\nEDGE_WEIGHT_TYPE : EXPLICIT\nEDGE_WEIGHT_FORMAT : {}\n\
+                EDGE_WEIGHT_SECTION\n{}\nEOF",
+                format, weights
+            ))
+            .unwrap();
+            assert_eq!(distance_mat.get_distance(&[0, 2, 1]), Ok(6.0), "{}", format);
+            assert_eq!(distance_mat.get_distance(&[0, 1]), Ok(2.0), "{}", format);
+            assert_eq!(distance_mat.get_distance(&[1, 2]), Ok(6.0), "{}", format);
+        }
+    }
+    #[test]
+    fn att_rounds_up() {
+        // sqrt(100 / 10) = 3.16..., which ATT rounds up to 4.
+        assert_eq!(
+            att_distances(&[(0.0, 0.0), (10.0, 0.0)]),
+            vec![vec![0.0, 4.0], vec![4.0, 0.0]]
+        );
+    }
+    #[test]
+    fn geo_in_kilometers() {
+        let distance_mat = parse_tsplib(
+            "DIMENSION: 2\nEDGE_WEIGHT_TYPE: GEO\nNODE_COORD_SECTION\n1 16.47 96.10\n2 16.47 94.44\nEOF",
+        )
+        .unwrap();
+        assert_eq!(distance_mat.units(), Some(CostUnit::Kilometers));
+        assert_eq!(distance_mat.get_distance(&[0, 1]), Ok(2.0 * 153.0));
+    }
+    #[test]
+    fn missing_dimension() {
+        assert!(matches!(
+            parse_tsplib("EDGE_WEIGHT_TYPE: EUC_2D\nNODE_COORD_SECTION\n1 0 0\nEOF"),
+            Err(TsplibError::MissingDimension)
+        ));
+    }
+    #[test]
+    fn wrong_number_of_nodes() {
+        assert!(matches!(
+            parse_tsplib("DIMENSION: 3\nEDGE_WEIGHT_TYPE: EUC_2D\nNODE_COORD_SECTION\n1 0 0\nEOF"),
+            Err(TsplibError::WrongNumberOfEntries {
+                expected: 3,
+                found: 1
+            })
+        ));
+    }
+    #[test]
+    fn unsupported_edge_weight_type() {
+        assert_eq!(
+            parse_tsplib("DIMENSION: 1\nEDGE_WEIGHT_TYPE: CEIL_2D\nNODE_COORD_SECTION\n1 0 0\nEOF")
+                .unwrap_err()
+                .to_string(),
+            "EDGE_WEIGHT_TYPE CEIL_2D is not supported"
+        );
+    }
+    #[test]
+    fn invalid_number() {
+        assert!(matches!(
+            parse_tsplib(
+                "DIMENSION: 1\nEDGE_WEIGHT_TYPE: EUC_2D\nNODE_COORD_SECTION\n1 zero 0\nEOF"
+            ),
+            Err(TsplibError::Parse { line: 4, .. })
+        ));
+    }
+}
@@ -1,11 +1,12 @@
 use crate::route::Route;
 use crate::subsequence::Subsequence;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::ops::Range;
 use rand::seq::SliceRandom;
+#[cfg(feature = "std")]
 use rand::thread_rng;
 use rand::Rng;
-use std::cmp::max;
-use std::cmp::Ordering;
-use std::ops::Range;
 
 /// Get a random alement from a range.
 ///
@@ -13,12 +14,27 @@ use std::ops::Range;
 ///
 /// * `range` - The range that should be sampled.
 ///
+#[cfg(feature = "std")]
 pub fn get_random_elem_from_range<T>(range: Range<T>) -> T
 where
-    T: std::cmp::PartialOrd + rand::distributions::uniform::SampleUniform,
+    T: core::cmp::PartialOrd + rand::distributions::uniform::SampleUniform,
+{
+    get_random_elem_from_range_with_rng(range, &mut thread_rng())
+}
+/// Get a random alement from a range like `get_random_elem_from_range`, but draw it from `rng`.
+///
+/// # Arguments
+///
+/// * `range` - The range that should be sampled.
+/// * `rng` - The random number generator the element is drawn from.
+///
+pub fn get_random_elem_from_range_with_rng<T, R>(range: Range<T>, rng: &mut R) -> T
+where
+    T: core::cmp::PartialOrd + rand::distributions::uniform::SampleUniform,
+    R: Rng + ?Sized,
 {
     if !range.is_empty() {
-        rand::thread_rng().gen_range::<T, Range<T>>(range)
+        rng.gen_range::<T, Range<T>>(range)
     } else {
         range.start
     }
@@ -29,32 +45,30 @@ where
 ///
 /// * `data` - The original slice that should be re-ordered.
 /// * `put_before_index` - The element as position `move_idx` should be positioned before
-/// the element at `put_before_index`.
+///   the element at `put_before_index`.
 /// * `move_idx` - The position of the element that should be moved.
 ///
+#[cfg(test)]
 pub fn change_order(data: &[usize], put_before_idx: usize, move_idx: usize) -> Vec<usize> {
     let mut new_data = data.to_owned();
-    if put_before_idx != move_idx {
-        let move_item = data[move_idx];
-        new_data.remove(move_idx);
-        let reset_index = (move_idx < put_before_idx) as usize;
-        new_data.insert(
-            max(put_before_idx, reset_index as usize) - reset_index as usize,
-            move_item,
-        );
-    }
+    move_elem(&mut new_data, put_before_idx, move_idx);
     new_data
 }
-/// Generate a new vector with by removing an element
+/// Re-order a slice in place like `change_order`, without allocating a new vector.
 ///
 /// # Arguments
 ///
-/// * `data` - The original vector from the element should be removed.
-/// * `elem_idx` - The index of the element that should be removed.
+/// * `data` - The slice that should be re-ordered.
+/// * `put_before_index` - The element as position `move_idx` should be positioned before
+///   the element at `put_before_index`.
+/// * `move_idx` - The position of the element that should be moved.
 ///
-pub fn remove_elem(mut data: Vec<usize>, elem_idx: usize) -> Vec<usize> {
-    data.remove(elem_idx);
-    data
+pub fn move_elem<T>(data: &mut [T], put_before_idx: usize, move_idx: usize) {
+    if move_idx < put_before_idx {
+        data[move_idx..put_before_idx].rotate_left(1);
+    } else if put_before_idx < move_idx {
+        data[put_before_idx..=move_idx].rotate_right(1);
+    }
 }
 /// The `ordered_crossover`-operator as defined in https://citeseerx.ist.psu.edu/viewdoc/download?doi=10.1.1.50.1898&rep=rep1&type=pdf
 ///
@@ -91,6 +105,195 @@ pub fn ordered_crossover(parent_a: &Route, parent_b: &Route, subsequence: Subseq
     }
     Route { indexes: child }
 }
+/// The position of every node in a route, indexed by the node. Nodes that are not part of the
+/// route have position `usize::MAX`.
+///
+/// # Arguments
+///
+/// * `route` - The nodes of the route.
+///
+fn node_positions(route: &[usize]) -> Vec<usize> {
+    let mut positions = vec![usize::MAX; route.iter().max().map_or(0, |max| max + 1)];
+    for (position, node) in route.iter().enumerate() {
+        positions[*node] = position;
+    }
+    positions
+}
+/// The Partially Mapped Crossover (PMX) as defined by Goldberg and Lingle: the child takes the
+/// subsequence of `parent_a` at the same positions, the other nodes are placed where they are
+/// in `parent_b`, following the mapping between the subsequences if their place is taken.
+///
+/// # Arguments
+///
+/// * `parent_a` - The first parent from which the subsequence is taken.
+/// * `parent_b` - The second parent that gives the positions of the other nodes.
+/// * `subsequence` - The actual subsequence that is taken.
+///
+pub fn partially_mapped_crossover(
+    parent_a: &Route,
+    parent_b: &Route,
+    subsequence: Subsequence,
+) -> Route {
+    let start = subsequence.start_index;
+    let end = start + subsequence.length;
+    let positions_b = node_positions(&parent_b.indexes);
+    let mut child = parent_b.indexes.clone();
+    child[start..end].copy_from_slice(&parent_a.indexes[start..end]);
+    let in_subsequence_a = &parent_a.indexes[start..end];
+    for position in start..end {
+        let node = parent_b.indexes[position];
+        if is_in(node, in_subsequence_a) {
+            continue;
+        }
+        // Follow the mapping until a position outside of the subsequence is found.
+        let mut target = position;
+        while (start..end).contains(&target) {
+            target = positions_b[parent_a.indexes[target]];
+        }
+        child[target] = node;
+    }
+    Route { indexes: child }
+}
+/// The Cycle Crossover (CX) as defined by Oliver, Smith and Holland: the positions of the parents
+/// are split into cycles, and the child takes the nodes of the cycles from `parent_a` and
+/// `parent_b` in turns, so every node keeps the position it has in one of the parents.
+///
+/// # Arguments
+///
+/// * `parent_a` - The parent the first cycle is taken from.
+/// * `parent_b` - The parent the second cycle is taken from.
+///
+pub fn cycle_crossover(parent_a: &Route, parent_b: &Route) -> Route {
+    let n_nodes = parent_a.get_n_nodes();
+    let positions_a = node_positions(&parent_a.indexes);
+    let mut child = vec![None; n_nodes];
+    let mut from_a = true;
+    for start in 0..n_nodes {
+        if child[start].is_some() {
+            continue;
+        }
+        let parent = if from_a { parent_a } else { parent_b };
+        let mut position = start;
+        while child[position].is_none() {
+            child[position] = Some(parent.indexes[position]);
+            position = positions_a[parent_b.indexes[position]];
+        }
+        from_a = !from_a;
+    }
+    Route {
+        indexes: child.into_iter().flatten().collect(),
+    }
+}
+/// The Edge Recombination Crossover (ERX) as defined by Whitley, Starkweather and Fuquay: the
+/// child starts with the first node of `parent_a` and always continues with a neighbor of the
+/// current node in one of the parents, preferring neighbors that have the fewest neighbors
+/// left. Most edges of the child are edges of a parent, which keeps the adjacencies that
+/// decide the length of a tour.
+///
+/// # Arguments
+///
+/// * `parent_a` - The first parent, the child starts with its first node.
+/// * `parent_b` - The second parent.
+/// * `rng` - The random number generator ties and dead ends are resolved with.
+///
+pub fn edge_recombination_crossover<R: Rng + ?Sized>(
+    parent_a: &Route,
+    parent_b: &Route,
+    rng: &mut R,
+) -> Route {
+    let n_nodes = parent_a.get_n_nodes();
+    if n_nodes == 0 {
+        return Route { indexes: vec![] };
+    }
+    // The nodes are identified by their position in `parent_a`.
+    let positions_a = node_positions(&parent_a.indexes);
+    let mut neighbors: Vec<Vec<usize>> = vec![Vec::with_capacity(4); n_nodes];
+    for parent in [parent_a, parent_b] {
+        for (position, node) in parent.indexes.iter().enumerate() {
+            let node = positions_a[*node];
+            for neighbor in [
+                parent.indexes[(position + n_nodes - 1) % n_nodes],
+                parent.indexes[(position + 1) % n_nodes],
+            ] {
+                let neighbor = positions_a[neighbor];
+                if neighbor != node && !is_in(neighbor, &neighbors[node]) {
+                    neighbors[node].push(neighbor);
+                }
+            }
+        }
+    }
+    let mut visited = vec![false; n_nodes];
+    let mut child = Vec::with_capacity(n_nodes);
+    let mut current = 0;
+    loop {
+        visited[current] = true;
+        child.push(parent_a.indexes[current]);
+        if child.len() == n_nodes {
+            break;
+        }
+        for neighbor in neighbors[current].clone() {
+            neighbors[neighbor].retain(|node| *node != current);
+        }
+        let candidates = &neighbors[current];
+        current = match candidates.iter().map(|node| neighbors[*node].len()).min() {
+            Some(fewest) => {
+                let fewest_candidates = candidates
+                    .iter()
+                    .copied()
+                    .filter(|node| neighbors[*node].len() == fewest)
+                    .collect::<Vec<usize>>();
+                *fewest_candidates.choose(rng).unwrap()
+            }
+            // A dead end, continue with a random node that was not visited yet.
+            None => *(0..n_nodes)
+                .filter(|node| !visited[*node])
+                .collect::<Vec<usize>>()
+                .choose(rng)
+                .unwrap(),
+        };
+    }
+    Route { indexes: child }
+}
+/// Cross two routes by exchanging a geographically contiguous segment instead of an index
+/// contiguous one: the nodes that lie in an axis-aligned rectangle are visited in the order of
+/// `parent_a`, as one block at the place where `parent_b` enters the rectangle first. All
+/// other nodes keep their order of `parent_b`. Nodes without coordinates lie outside.
+///
+/// # Arguments
+///
+/// * `parent_a` - The parent that decides the order inside the rectangle.
+/// * `parent_b` - The parent that decides the order outside the rectangle.
+/// * `coordinates` - The coordinates of the nodes.
+/// * `lower` - The corner of the rectangle with the smallest coordinates.
+/// * `upper` - The corner of the rectangle with the largest coordinates.
+///
+pub fn spatial_segment_crossover(
+    parent_a: &Route,
+    parent_b: &Route,
+    coordinates: &[(f64, f64)],
+    lower: (f64, f64),
+    upper: (f64, f64),
+) -> Route {
+    let is_inside = |node: &usize| {
+        coordinates
+            .get(*node)
+            .is_some_and(|(x, y)| lower.0 <= *x && *x <= upper.0 && lower.1 <= *y && *y <= upper.1)
+    };
+    // All nodes of `parent_b` before its first node inside lie outside, so this is also the
+    // position of the segment in the child.
+    let segment_start = parent_b.indexes.iter().position(is_inside).unwrap_or(0);
+    let mut child = parent_b
+        .indexes
+        .iter()
+        .copied()
+        .filter(|node| !is_inside(node))
+        .collect::<Vec<usize>>();
+    child.splice(
+        segment_start..segment_start,
+        parent_a.indexes.iter().copied().filter(is_inside),
+    );
+    Route { indexes: child }
+}
 /// Does a sequence contain a certain value?
 ///
 /// # Arguments
@@ -113,12 +316,61 @@ pub fn is_in(value: usize, elements: &[usize]) -> bool {
 ///
 /// * `vec` - The slice that should be permutated.
 ///
+#[cfg(feature = "std")]
 pub fn random_permutation(vec: &[usize]) -> Vec<usize> {
+    random_permutation_with_rng(vec, &mut thread_rng())
+}
+/// Give a random permutation of a slice like `random_permutation`, but shuffle it with `rng`.
+///
+/// # Arguments
+///
+/// * `vec` - The slice that should be permutated.
+/// * `rng` - The random number generator the permutation is drawn from.
+///
+pub fn random_permutation_with_rng<R: Rng + ?Sized>(vec: &[usize], rng: &mut R) -> Vec<usize> {
     let mut this_vec: Vec<usize> = vec.to_vec();
-    this_vec.shuffle(&mut thread_rng());
+    this_vec.shuffle(rng);
     this_vec
 }
 
+/// The number of distinct permutations of `n` elements, e.g. `n!`. Saturates at `usize::MAX`.
+///
+/// # Arguments
+///
+/// * `n` - The number of elements that are permutated.
+///
+pub fn n_permutations(n: usize) -> usize {
+    (1..=n).fold(1, |n_permutations: usize, factor| {
+        n_permutations.saturating_mul(factor)
+    })
+}
+
+/// All permutations of `0..n` in lexicographic order. There are `n!` of them, so this is only
+/// feasible for small `n`.
+///
+/// # Arguments
+///
+/// * `n` - The number of elements that are permutated.
+///
+pub fn all_permutations(n: usize) -> Vec<Vec<usize>> {
+    let mut permutation = (0..n).collect::<Vec<usize>>();
+    let mut permutations = Vec::with_capacity(n_permutations(n));
+    loop {
+        permutations.push(permutation.clone());
+        // Find the last ascent, everything after it is in descending order.
+        let pivot = match permutation.windows(2).rposition(|pair| pair[0] < pair[1]) {
+            Some(pivot) => pivot,
+            None => return permutations,
+        };
+        let successor = permutation
+            .iter()
+            .rposition(|elem| *elem > permutation[pivot])
+            .unwrap();
+        permutation.swap(pivot, successor);
+        permutation[(pivot + 1)..].reverse();
+    }
+}
+
 /// Return the index of a sorted slice
 ///
 /// # Arguments
@@ -136,6 +388,26 @@ pub fn argsort<T: PartialOrd>(data: &[T]) -> Vec<usize> {
     });
     indices
 }
+/// Return the index of a sorted slice like `argsort`, but order equal elements by their `keys`
+/// instead of their position.
+///
+/// # Arguments
+///
+/// * `data` - The slice that should be sorted by the index that is returned.
+/// * `keys` - For every element of `data` the key that decides the order of equal elements.
+///
+pub fn argsort_with_tie_breaker<T: PartialOrd, K: Ord>(data: &[T], keys: &[K]) -> Vec<usize> {
+    let mut indices = (0..data.len()).collect::<Vec<_>>();
+    indices.sort_by(|a_idx, b_idx| {
+        reverse_ordering(
+            data[*a_idx]
+                .partial_cmp(&data[*b_idx])
+                .unwrap_or(Ordering::Less),
+        )
+        .then_with(|| keys[*a_idx].cmp(&keys[*b_idx]))
+    });
+    indices
+}
 /// Reverse ordering
 ///
 /// # Arguments
@@ -151,8 +423,14 @@ fn reverse_ordering(ordering: Ordering) -> Ordering {
 }
 
 #[cfg(test)]
+#[allow(
+    duplicate_macro_attributes,
+    clippy::bool_assert_comparison,
+    clippy::useless_vec
+)]
 mod tests {
     use super::*;
+    #[cfg(feature = "std")]
     mod get_elem_from_range {
         use super::*;
         #[test]
@@ -168,33 +446,6 @@ mod tests {
             assert_eq!(get_random_elem_from_range(0..0), 0);
         }
     }
-    mod test_remove_elem {
-        use super::*;
-        #[test]
-        fn remove_first() {
-            assert_eq!(remove_elem(vec![1, 2, 3, 4], 0), vec![2, 3, 4]);
-        }
-        #[test]
-        fn remove_last() {
-            assert_eq!(remove_elem(vec![1, 2, 3, 4], 3), vec![1, 2, 3]);
-        }
-        #[test]
-        fn remove_middle() {
-            assert_eq!(remove_elem(vec![1, 2, 3, 4], 2), vec![1, 2, 4]);
-        }
-        #[test]
-        fn test_remove_elem_first() {
-            assert_eq!(remove_elem(vec![1, 2, 3], 0), vec![2, 3])
-        }
-        #[test]
-        fn test_remove_elem_middle() {
-            assert_eq!(remove_elem(vec![1, 2, 3], 1), vec![1, 3])
-        }
-        #[test]
-        fn test_remove_elem_last() {
-            assert_eq!(remove_elem(vec![1, 2, 3], 2), vec![1, 2])
-        }
-    }
     mod test_change_elem {
         use super::*;
         #[test]
@@ -371,6 +622,144 @@ mod tests {
             )
         }
     }
+    mod test_partially_mapped_crossover {
+        use super::*;
+        #[cfg(feature = "std")]
+        use crate::test_utils::valid_permutation;
+        #[test]
+        fn maps_displaced_nodes() {
+            let child = partially_mapped_crossover(
+                &Route {
+                    indexes: vec![1, 2, 3, 4, 5, 6, 7, 8, 9],
+                },
+                &Route {
+                    indexes: vec![4, 5, 2, 1, 8, 7, 6, 9, 3],
+                },
+                Subsequence::new(3, 4),
+            );
+            assert_eq!(child.indexes, vec![1, 8, 2, 4, 5, 6, 7, 9, 3]);
+        }
+        #[cfg(feature = "std")]
+        #[test]
+        fn random_parents_give_permutations() {
+            for _ in 0..50 {
+                let parent_a = Route {
+                    indexes: random_permutation(&(0..10).collect::<Vec<usize>>()),
+                };
+                let parent_b = Route {
+                    indexes: random_permutation(&parent_a.indexes),
+                };
+                let child = partially_mapped_crossover(
+                    &parent_a,
+                    &parent_b,
+                    Subsequence::random_subsequence_with_rng(10, &mut rand::thread_rng()),
+                );
+                valid_permutation(&parent_a.indexes, &child.indexes);
+            }
+        }
+    }
+    mod test_cycle_crossover {
+        use super::*;
+        #[cfg(feature = "std")]
+        use crate::test_utils::valid_permutation;
+        #[test]
+        fn alternates_cycles() {
+            let child = cycle_crossover(
+                &Route {
+                    indexes: vec![1, 2, 3, 4, 5, 6, 7, 8, 9],
+                },
+                &Route {
+                    indexes: vec![9, 3, 7, 8, 2, 6, 5, 1, 4],
+                },
+            );
+            assert_eq!(child.indexes, vec![1, 3, 7, 4, 2, 6, 5, 8, 9]);
+        }
+        #[cfg(feature = "std")]
+        #[test]
+        fn nodes_keep_a_parent_position() {
+            let parent_a = Route {
+                indexes: random_permutation(&(0..12).collect::<Vec<usize>>()),
+            };
+            let parent_b = Route {
+                indexes: random_permutation(&parent_a.indexes),
+            };
+            let child = cycle_crossover(&parent_a, &parent_b);
+            valid_permutation(&parent_a.indexes, &child.indexes);
+            for (position, node) in child.indexes.iter().enumerate() {
+                assert!(*node == parent_a.indexes[position] || *node == parent_b.indexes[position]);
+            }
+        }
+    }
+    mod test_spatial_segment_crossover {
+        use super::*;
+        use crate::test_utils::valid_permutation;
+        #[test]
+        fn segment_is_contiguous() {
+            // Nodes 1, 3 and 5 lie in the right half.
+            let coordinates = [
+                (0.0, 0.0),
+                (5.0, 0.0),
+                (1.0, 1.0),
+                (6.0, 1.0),
+                (2.0, 2.0),
+                (7.0, 2.0),
+            ];
+            let parent_a = Route::new(vec![5, 4, 3, 2, 1, 0]);
+            let parent_b = Route::new(vec![0, 1, 2, 3, 4, 5]);
+            let child = spatial_segment_crossover(
+                &parent_a,
+                &parent_b,
+                &coordinates,
+                (4.0, -1.0),
+                (8.0, 3.0),
+            );
+            valid_permutation(&parent_b.indexes, &child.indexes);
+            assert_eq!(child.indexes, vec![0, 5, 3, 1, 2, 4]);
+        }
+        #[test]
+        fn empty_rectangle() {
+            let parent_a = Route::new(vec![2, 1, 0]);
+            let parent_b = Route::new(vec![0, 1, 2]);
+            let child = spatial_segment_crossover(
+                &parent_a,
+                &parent_b,
+                &[(0.0, 0.0), (1.0, 0.0), (2.0, 0.0)],
+                (5.0, 5.0),
+                (6.0, 6.0),
+            );
+            assert_eq!(child, parent_b);
+        }
+    }
+    #[cfg(feature = "std")]
+    mod test_edge_recombination_crossover {
+        use super::*;
+        use crate::test_utils::valid_permutation;
+        #[test]
+        fn equal_parents_give_same_tour() {
+            let parent = Route {
+                indexes: vec![3, 0, 4, 1, 2],
+            };
+            let child = edge_recombination_crossover(&parent, &parent, &mut thread_rng());
+            // The child only uses the edges of the parent, possibly in the other direction.
+            let mut reversed = parent.indexes.clone();
+            reversed[1..].reverse();
+            assert!(child.indexes == parent.indexes || child.indexes == reversed);
+        }
+        #[test]
+        fn random_parents_give_permutations() {
+            for _ in 0..50 {
+                let parent_a = Route {
+                    indexes: random_permutation(&(0..10).collect::<Vec<usize>>()),
+                };
+                let parent_b = Route {
+                    indexes: random_permutation(&parent_a.indexes),
+                };
+                let child = edge_recombination_crossover(&parent_a, &parent_b, &mut thread_rng());
+                valid_permutation(&parent_a.indexes, &child.indexes);
+                assert_eq!(child.indexes[0], parent_a.indexes[0]);
+            }
+        }
+    }
     mod test_is_in {
         use super::*;
         #[test]
@@ -390,6 +779,7 @@ mod tests {
             assert_eq!(is_in(0, &[0, 1, 0, 3]), true)
         }
     }
+    #[cfg(feature = "std")]
     mod test_random_permutation {
         use super::*;
         use crate::test_utils::valid_permutation;
@@ -402,6 +792,48 @@ mod tests {
             valid_permutation(&main_vec, &random_permutation(&main_vec));
         }
     }
+    mod test_n_permutations {
+        use super::*;
+        #[test]
+        fn small_numbers() {
+            assert_eq!(n_permutations(0), 1);
+            assert_eq!(n_permutations(1), 1);
+            assert_eq!(n_permutations(3), 6);
+            assert_eq!(n_permutations(5), 120);
+        }
+        #[test]
+        fn saturates() {
+            assert_eq!(n_permutations(100), usize::MAX);
+        }
+    }
+    mod test_all_permutations {
+        use super::*;
+        use std::collections::HashSet;
+        #[test]
+        fn lexicographic_order() {
+            assert_eq!(
+                all_permutations(3),
+                vec![
+                    vec![0, 1, 2],
+                    vec![0, 2, 1],
+                    vec![1, 0, 2],
+                    vec![1, 2, 0],
+                    vec![2, 0, 1],
+                    vec![2, 1, 0]
+                ]
+            );
+        }
+        #[test]
+        fn all_distinct() {
+            let permutations = all_permutations(5);
+            assert_eq!(permutations.len(), 120);
+            assert_eq!(permutations.into_iter().collect::<HashSet<_>>().len(), 120);
+        }
+        #[test]
+        fn no_elements() {
+            assert_eq!(all_permutations(0), vec![Vec::<usize>::new()]);
+        }
+    }
     mod test_argsort {
         use super::*;
         #[test]
@@ -423,6 +855,23 @@ mod tests {
             assert_eq!(argsort(&vec![2, 5, 3, 4, 1, 6]), vec![5, 1, 3, 2, 0, 4]);
         }
     }
+    mod test_argsort_with_tie_breaker {
+        use super::*;
+        #[test]
+        fn ties_ordered_by_key() {
+            assert_eq!(
+                argsort_with_tie_breaker(&[1.0, 5.0, 1.0, 5.0], &[3, 2, 1, 0]),
+                vec![3, 1, 2, 0]
+            );
+        }
+        #[test]
+        fn distinct_values_ignore_key() {
+            assert_eq!(
+                argsort_with_tie_breaker(&[1.0, 5.0, 3.0, 6.0], &[0, 1, 2, 3]),
+                argsort(&[1.0, 5.0, 3.0, 6.0])
+            );
+        }
+    }
     mod test_reverse_ordering {
         use super::*;
         #[test]
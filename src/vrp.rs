@@ -0,0 +1,339 @@
+use crate::distance_mat::DistanceMat;
+use crate::multi_route::MultiRoute;
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
+use genetic_algorithm_traits::Individual;
+
+/// The cost data of the Capacitated Vehicle Routing Problem (CVRP): the distances between the
+/// nodes, the demand of every node and the capacity every vehicle has to deliver it. It is the
+/// `IndividualCost` of `CapacitatedRoute`.
+#[derive(Debug)]
+pub struct VrpCost {
+    /// The distances between the nodes, its objective decides whether the lengths of the
+    /// round-trips are summed up or their maximum is taken.
+    distance_mat: DistanceMat,
+    /// The demand of every node, the demand of the depot is ignored.
+    demands: Vec<f64>,
+    /// How much demand a single vehicle can serve.
+    capacity: f64,
+    /// The cost of every unit of demand above the capacity of a vehicle.
+    penalty: f64,
+}
+impl VrpCost {
+    /// Create the cost data of a CVRP. Every unit of demand above the capacity of a vehicle
+    /// costs twice the longest distance of the instance, so that every overloaded route is
+    /// more expensive than serving the excess demand with a detour; change it with
+    /// `with_penalty` if the demands are on a very different scale than the distances.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mat` - The distances between the nodes.
+    /// * `demands` - The demand of every node, the demand of the depot is ignored.
+    /// * `capacity` - How much demand a single vehicle can serve.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is not exactly one demand per node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::vrp::VrpCost;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let vrp_cost = VrpCost::new(distance_matrix, vec![0.0, 2.0, 3.0], 4.0);
+    /// assert_eq!(vrp_cost.capacity(), 4.0);
+    /// ```
+    pub fn new(distance_mat: DistanceMat, demands: Vec<f64>, capacity: f64) -> Self {
+        assert_eq!(
+            demands.len(),
+            distance_mat.n_units(),
+            "There are {} demands but the distance matrix has {} nodes",
+            demands.len(),
+            distance_mat.n_units()
+        );
+        let n_units = distance_mat.n_units();
+        let max_distance = (0..n_units)
+            .flat_map(|from| (0..n_units).map(move |to| (from, to)))
+            .map(|(from, to)| distance_mat.distance(from, to))
+            .fold(0.0, f64::max);
+        VrpCost {
+            distance_mat,
+            demands,
+            capacity,
+            penalty: 2.0 * max_distance,
+        }
+    }
+    /// Set the cost of every unit of demand above the capacity of a vehicle.
+    ///
+    /// # Arguments
+    ///
+    /// * `penalty` - The cost per unit of excess demand.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::vrp::VrpCost;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let vrp_cost = VrpCost::new(distance_matrix, vec![0.0, 2.0, 3.0], 4.0).with_penalty(100.0);
+    /// assert_eq!(vrp_cost.penalty(), 100.0);
+    /// ```
+    pub fn with_penalty(mut self, penalty: f64) -> Self {
+        self.penalty = penalty;
+        self
+    }
+    /// Get the distances between the nodes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::vrp::VrpCost;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let vrp_cost = VrpCost::new(distance_matrix, vec![0.0, 2.0, 3.0], 4.0);
+    /// assert_eq!(vrp_cost.distance_mat().n_units(), 3);
+    /// ```
+    pub fn distance_mat(&self) -> &DistanceMat {
+        &self.distance_mat
+    }
+    /// Get the capacity of a vehicle.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::vrp::VrpCost;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// assert_eq!(VrpCost::new(distance_matrix, vec![0.0, 2.0, 3.0], 4.0).capacity(), 4.0);
+    /// ```
+    pub fn capacity(&self) -> f64 {
+        self.capacity
+    }
+    /// Get the cost of every unit of demand above the capacity of a vehicle.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::vrp::VrpCost;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// // Twice the longest distance.
+    /// assert_eq!(VrpCost::new(distance_matrix, vec![0.0, 2.0, 3.0], 4.0).penalty(), 6.0);
+    /// ```
+    pub fn penalty(&self) -> f64 {
+        self.penalty
+    }
+    /// Get the demand that every vehicle of a route serves.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The routes of the vehicles.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::multi_route::MultiRoute;
+    /// use genetic_algorithm_tsp::vrp::VrpCost;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let vrp_cost = VrpCost::new(distance_matrix, vec![0.0, 2.0, 3.0], 4.0);
+    /// assert_eq!(vrp_cost.loads(&MultiRoute::new(0, vec![vec![1, 2], vec![]])), vec![5.0, 0.0]);
+    /// ```
+    pub fn loads(&self, route: &MultiRoute) -> Vec<f64> {
+        route
+            .sub_tours()
+            .iter()
+            .map(|sub_tour| sub_tour.iter().map(|node| self.demands[*node]).sum())
+            .collect()
+    }
+    /// Get the total demand above the capacity of the vehicles of a route, 0.0 if the route
+    /// is feasible.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The routes of the vehicles.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::multi_route::MultiRoute;
+    /// use genetic_algorithm_tsp::vrp::VrpCost;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let vrp_cost = VrpCost::new(distance_matrix, vec![0.0, 2.0, 3.0], 4.0);
+    /// assert_eq!(vrp_cost.overload(&MultiRoute::new(0, vec![vec![1, 2], vec![]])), 1.0);
+    /// assert_eq!(vrp_cost.overload(&MultiRoute::new(0, vec![vec![1], vec![2]])), 0.0);
+    /// ```
+    pub fn overload(&self, route: &MultiRoute) -> f64 {
+        self.loads(route)
+            .into_iter()
+            .map(|load| (load - self.capacity).max(0.0))
+            .sum()
+    }
+    /// Compute the cost of a route: the cost of its round-trips under the objective of the
+    /// distance matrix plus the penalty for every unit of demand above the capacities.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The routes of the vehicles.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::multi_route::MultiRoute;
+    /// use genetic_algorithm_tsp::vrp::VrpCost;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let vrp_cost = VrpCost::new(distance_matrix, vec![0.0, 2.0, 3.0], 4.0);
+    /// // The round-trip of length 6.0 is overloaded by 1.0.
+    /// assert_eq!(vrp_cost.get_cost(&MultiRoute::new(0, vec![vec![1, 2], vec![]])), 12.0);
+    /// ```
+    pub fn get_cost(&self, route: &MultiRoute) -> f64 {
+        self.distance_mat.get_tours_cost(&route.tours()) + self.penalty * self.overload(route)
+    }
+}
+
+/// A solution of the Capacitated Vehicle Routing Problem: a `MultiRoute` whose fitness is
+/// evaluated on a `VrpCost`, so that overloaded vehicles are penalized. Use it with
+/// `Individuals` to evolve a population of them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CapacitatedRoute {
+    /// The routes of the vehicles.
+    pub route: MultiRoute,
+}
+impl fmt::Display for CapacitatedRoute {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "Capacitated{}", self.route)
+    }
+}
+impl From<MultiRoute> for CapacitatedRoute {
+    /// Evaluate a multi-vehicle route on the cost data of a CVRP.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The routes of the vehicles.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::multi_route::MultiRoute;
+    /// use genetic_algorithm_tsp::vrp::CapacitatedRoute;
+    ///
+    /// let capacitated_route = CapacitatedRoute::from(MultiRoute::new(0, vec![vec![1], vec![2]]));
+    /// ```
+    fn from(route: MultiRoute) -> Self {
+        CapacitatedRoute { route }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> Individual<'a> for CapacitatedRoute {
+    // The cost data holds the demands and capacity next to the distances.
+    type IndividualCost = VrpCost;
+    /// Mutate the routes of the vehicles like `MultiRoute::mutate`.
+    ///
+    /// # Arguments
+    ///
+    /// * `prob` - The probability with which the route will be changed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::multi_route::MultiRoute;
+    /// use genetic_algorithm_tsp::vrp::CapacitatedRoute;
+    /// use genetic_algorithm_traits::Individual;
+    ///
+    /// let mutated = CapacitatedRoute::from(MultiRoute::new(0, vec![vec![1, 2], vec![3]])).mutate(1.0);
+    /// ```
+    fn mutate(self, prob: f32) -> Self {
+        CapacitatedRoute {
+            route: self.route.mutate(prob),
+        }
+    }
+    /// Cross the routes of the vehicles like `MultiRoute::crossover`.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The other individual you would like to crossover with this individual.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::multi_route::MultiRoute;
+    /// use genetic_algorithm_tsp::vrp::CapacitatedRoute;
+    /// use genetic_algorithm_traits::Individual;
+    ///
+    /// let parent_a = CapacitatedRoute::from(MultiRoute::new(0, vec![vec![1, 2], vec![3]]));
+    /// let parent_b = CapacitatedRoute::from(MultiRoute::new(0, vec![vec![3], vec![2, 1]]));
+    /// let child = parent_a.crossover(&parent_b);
+    /// ```
+    fn crossover(&self, other: &CapacitatedRoute) -> Self {
+        CapacitatedRoute {
+            route: self.route.crossover(&other.route),
+        }
+    }
+    /// Compute the fitness of the route, the negative of `VrpCost::get_cost`.
+    ///
+    /// # Arguments
+    ///
+    /// * `vrp_cost` - The cost data the fitness is computed on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_tsp::distance_mat::DistanceMat;
+    /// use genetic_algorithm_tsp::multi_route::MultiRoute;
+    /// use genetic_algorithm_tsp::vrp::{CapacitatedRoute, VrpCost};
+    /// use genetic_algorithm_traits::Individual;
+    ///
+    /// let distance_matrix = DistanceMat::new(vec![vec![0.0,1.0,2.0], vec![1.0,0.0,3.0], vec![2.0,3.0,0.0]]);
+    /// let vrp_cost = VrpCost::new(distance_matrix, vec![0.0, 2.0, 3.0], 4.0);
+    /// let capacitated_route = CapacitatedRoute::from(MultiRoute::new(0, vec![vec![1], vec![2]]));
+    /// assert_eq!(capacitated_route.fitness(&vrp_cost), -6.0);
+    /// ```
+    fn fitness(&self, vrp_cost: &VrpCost) -> f64 {
+        -vrp_cost.get_cost(&self.route)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::individuals::Individuals;
+    use genetic_algorithm_traits::Population;
+
+    #[test]
+    fn evolution_finds_feasible_routes() {
+        // Four customers on a line on both sides of the depot, every vehicle can serve two.
+        let positions = [0.0f64, 1.0, 2.0, -1.0, -2.0];
+        let distance_mat = DistanceMat::new(
+            positions
+                .iter()
+                .map(|from| positions.iter().map(|to| (from - to).abs()).collect())
+                .collect(),
+        );
+        let vrp_cost = VrpCost::new(distance_mat, vec![0.0, 1.0, 1.0, 1.0, 1.0], 2.0);
+        let mut population = Individuals::from(
+            (0..10)
+                .map(|_| CapacitatedRoute::from(MultiRoute::random(5, 0, 2)))
+                .collect::<Vec<CapacitatedRoute>>(),
+        );
+        for _ in 0..30 {
+            population = population.evolve(0.5).get_fittest_population(10, &vrp_cost);
+        }
+        let best = population.get_n_fittest(1, &vrp_cost).remove(0);
+        assert_eq!(vrp_cost.overload(&best.route), 0.0);
+        // Every vehicle serves one side of the depot.
+        assert_eq!(best.fitness(&vrp_cost), -8.0);
+    }
+}
@@ -0,0 +1,143 @@
+#![cfg(feature = "cli")]
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+const DISTANCES: &str = "tests/test-data/distances.txt";
+
+/// Run the `genetic-tsp` binary with the given arguments.
+fn genetic_tsp(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_genetic-tsp"))
+        .args(args)
+        .output()
+        .expect("the binary can be started")
+}
+
+/// A path in the temporary directory of the tests, unique per test.
+fn temp_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(format!("test_cli_{}", name))
+}
+
+/// Solve the test instance with a seed and return the JSON that was written.
+fn solve_seeded(output: &PathBuf) -> serde_json::Value {
+    let result = genetic_tsp(&[
+        "solve",
+        "--input",
+        DISTANCES,
+        "--generations",
+        "5",
+        "--population",
+        "5",
+        "--seed",
+        "42",
+        "--output",
+        output.to_str().unwrap(),
+    ]);
+    assert!(result.status.success(), "{:?}", result);
+    assert!(String::from_utf8_lossy(&result.stderr).contains("Best route has cost"));
+    serde_json::from_str(&fs::read_to_string(output).unwrap()).unwrap()
+}
+
+#[test]
+fn solve_writes_the_best_route() {
+    let json = solve_seeded(&temp_path("solve.json"));
+    assert_eq!(json["n_nodes"], 30);
+    assert_eq!(json["input"], DISTANCES);
+    let mut route = json["route"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|node| node.as_u64().unwrap())
+        .collect::<Vec<u64>>();
+    route.sort_unstable();
+    assert_eq!(route, (0..30).collect::<Vec<u64>>());
+    assert!(json["cost"].as_f64().unwrap() > 0.0);
+}
+
+#[test]
+fn seeded_solve_is_reproducible() {
+    assert_eq!(
+        solve_seeded(&temp_path("seeded_a.json")),
+        solve_seeded(&temp_path("seeded_b.json"))
+    );
+}
+
+#[test]
+fn solve_prints_to_stdout_without_output() {
+    let result = genetic_tsp(&[
+        "solve",
+        "--input",
+        DISTANCES,
+        "--generations",
+        "2",
+        "--population",
+        "4",
+    ]);
+    assert!(result.status.success(), "{:?}", result);
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(json["route"].as_array().unwrap().len(), 30);
+}
+
+#[test]
+fn validate_accepts_a_solved_route() {
+    let route = temp_path("validate.json");
+    let cost = solve_seeded(&route)["cost"].as_f64().unwrap();
+    let result = genetic_tsp(&[
+        "validate",
+        "--input",
+        DISTANCES,
+        "--route",
+        route.to_str().unwrap(),
+    ]);
+    assert!(result.status.success(), "{:?}", result);
+    let stdout = String::from_utf8_lossy(&result.stdout);
+    assert!(
+        stdout.contains(&format!("is valid with cost {}", cost)),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn validate_rejects_an_incomplete_route() {
+    let route = temp_path("incomplete.json");
+    fs::write(&route, r#"{"route": [0, 1, 2]}"#).unwrap();
+    let result = genetic_tsp(&[
+        "validate",
+        "--input",
+        DISTANCES,
+        "--route",
+        route.to_str().unwrap(),
+    ]);
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("does not visit every node"));
+}
+
+#[test]
+fn benchmark_prints_every_configuration() {
+    let result = genetic_tsp(&[
+        "benchmark",
+        "--input",
+        DISTANCES,
+        "--generations",
+        "2,3",
+        "--population",
+        "4",
+    ]);
+    assert!(result.status.success(), "{:?}", result);
+    assert_eq!(String::from_utf8_lossy(&result.stdout).lines().count(), 2);
+}
+
+#[test]
+fn missing_input_is_a_usage_error() {
+    let result = genetic_tsp(&["solve"]);
+    assert_eq!(result.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&result.stderr).contains("--input"));
+}
+
+#[test]
+fn unreadable_input_is_reported() {
+    let result = genetic_tsp(&["validate", "--input", "tests/test-data/missing.txt"]);
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("could not read"));
+}
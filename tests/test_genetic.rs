@@ -1,5 +1,3 @@
-use genetic_algorithm_traits::Individual;
-use genetic_algorithm_traits::Population;
 use genetic_algorithm_tsp::distance_mat::DistanceMat;
 use genetic_algorithm_tsp::routes::{evolve_population, Routes};
 use std::fs;
@@ -29,3 +27,28 @@ fn run_evolution() {
     // Assert after optimizing, the routes is fitter then before.
     assert!(max_fit <= max_fit_new);
 }
+
+#[cfg(feature = "datasets")]
+#[test]
+fn run_evolution_on_dataset() {
+    let dataset = genetic_algorithm_tsp::datasets::burma14();
+    let routes = Routes::random(20, dataset.distance_mat.n_units());
+    let routes = evolve_population(routes, 10, 20, &dataset.distance_mat, 0);
+    let best_length =
+        -routes.get_n_fittest(1, &dataset.distance_mat)[0].fitness(&dataset.distance_mat);
+    // No route can be shorter than the optimal one.
+    assert!(best_length >= dataset.optimum);
+}
+
+#[cfg(feature = "self-check")]
+#[test]
+#[should_panic(expected = "operator `Route::fitness` computed a NaN fitness")]
+fn self_check_detects_nan_fitness() {
+    let distances = DistanceMat::new(vec![
+        vec![0.0, 1.0, f64::NAN],
+        vec![1.0, 0.0, 3.0],
+        // Every round-trip through three nodes goes from 0 to 2 or from 2 to 0.
+        vec![f64::NAN, 3.0, 0.0],
+    ]);
+    evolve_population(Routes::random(2, 3), 2, 4, &distances, 0);
+}
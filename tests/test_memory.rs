@@ -0,0 +1,66 @@
+#![cfg(feature = "memory-stats")]
+use genetic_algorithm_tsp::distance_mat::DistanceMat;
+use genetic_algorithm_tsp::memory::{measure_allocations, CountingAllocator};
+use genetic_algorithm_tsp::routes::{benchmark, evolve_population_with_allocation_stats, Routes};
+use std::sync::Mutex;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+// The allocation counters are global, so the measurements must not run concurrently.
+static MEASUREMENT: Mutex<()> = Mutex::new(());
+
+#[test]
+fn measure_vector_allocation() {
+    let _measurement = MEASUREMENT.lock().unwrap();
+    let (vector, stats) = measure_allocations(|| vec![0_u64; 1000]);
+    let stats = stats.unwrap();
+    assert_eq!(vector.len(), 1000);
+    assert!(stats.n_allocations >= 1);
+    assert!(stats.peak_bytes >= 8000);
+}
+
+fn test_distances() -> DistanceMat {
+    DistanceMat::new(vec![
+        vec![0.0, 1.0, 2.0, 3.0],
+        vec![1.0, 0.0, 3.0, 1.0],
+        vec![2.0, 3.0, 0.0, 2.0],
+        vec![3.0, 1.0, 2.0, 0.0],
+    ])
+}
+
+#[test]
+fn benchmark_reports_allocations() {
+    let _measurement = MEASUREMENT.lock().unwrap();
+    let distances = test_distances();
+    let result = benchmark(5, 10, &distances, 0);
+    let allocations = result.allocations.unwrap();
+    assert!(allocations.n_allocations > 0);
+    assert!(allocations.peak_bytes > 0);
+}
+
+#[test]
+fn allocations_per_generation() {
+    let _measurement = MEASUREMENT.lock().unwrap();
+    let (population, allocations) =
+        evolve_population_with_allocation_stats(Routes::random(4, 4), 3, 4, &test_distances());
+    assert_eq!(population.len(), 4);
+    assert_eq!(allocations.len(), 3);
+    assert!(allocations.iter().all(|stats| stats.is_some()));
+}
+
+// The self-check copies the parent to validate the offspring.
+#[cfg(not(feature = "self-check"))]
+#[test]
+fn mutate_does_not_allocate() {
+    use genetic_algorithm_traits::Individual;
+    use genetic_algorithm_tsp::route::Route;
+
+    let _measurement = MEASUREMENT.lock().unwrap();
+    let route = Route::new((0..100).collect());
+    // The random number generator of the thread allocates once, when it is first used.
+    let route = route.mutate(1.0);
+    let (mutated_route, stats) = measure_allocations(|| route.mutate(1.0));
+    assert_eq!(mutated_route.get_n_nodes(), 100);
+    assert_eq!(stats, None);
+}